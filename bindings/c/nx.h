@@ -15,6 +15,23 @@
 
 #define NX_FFI_ABI_VERSION 10
 
+/**
+ * Bits of an `output_format` argument above this shift select the envelope version.
+ *
+ * The low bits hold the [`NxOutputFormat`]. Pass
+ * `format | (version << NX_OUTPUT_ENVELOPE_SHIFT)`, with a version agreed through
+ * [`nx_ffi_negotiate_envelope_version`], to receive results and diagnostics wrapped as
+ * `{"$nx": version, "value": ...}` or `{"$nx": version, "diagnostics": [...]}`. Version `0`, the
+ * default, writes bare payloads.
+ */
+#define NX_OUTPUT_ENVELOPE_SHIFT 8
+
+/**
+ * Size of the chunks passed to an [`NxStreamCallback`]. Only the last chunk of a payload may be
+ * shorter.
+ */
+#define NX_STREAM_CHUNK_SIZE (64 * 1024)
+
 enum NxEvalStatus
 #ifdef __cplusplus
   : uint32_t
@@ -41,6 +58,23 @@ enum NxOutputFormat
 typedef uint32_t NxOutputFormat;
 #endif // __cplusplus
 
+/**
+ * Severity of an NX `log(level, message, value?)` call, from least to most severe.
+ */
+enum NxLogLevel
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  NxLogLevel_Debug = 0,
+  NxLogLevel_Info = 1,
+  NxLogLevel_Warn = 2,
+  NxLogLevel_Error = 3,
+};
+#ifndef __cplusplus
+typedef uint32_t NxLogLevel;
+#endif // __cplusplus
+
 typedef struct NxLibraryRegistryHandle NxLibraryRegistryHandle;
 
 typedef struct NxProgramArtifactHandle NxProgramArtifactHandle;
@@ -60,12 +94,57 @@ typedef struct NxWorkspaceModule {
   size_t source_utf8_len;
 } NxWorkspaceModule;
 
+/**
+ * Receives the `log` calls of programs evaluated by `nx_eval_source`, `nx_eval_source_stream`, and
+ * `nx_eval_program_artifact`.
+ *
+ * `message_ptr` / `message_len` hold the UTF-8 message. `value_json_ptr` / `value_json_len` hold
+ * the optional third argument as JSON, or are null / `0` when the call had none. The buffers are
+ * only valid until the callback returns. The callback runs on the thread evaluating the program.
+ */
+typedef void (*NxLogCallback)(void *user_data,
+                              NxLogLevel level,
+                              const uint8_t *message_ptr,
+                              size_t message_len,
+                              const uint8_t *value_json_ptr,
+                              size_t value_json_len);
+
+/**
+ * Receives the output of `nx_eval_source_stream` one MessagePack chunk at a time.
+ *
+ * Chunks arrive in order on the calling thread, and together they hold the bytes `nx_eval_source`
+ * would write for `NxOutputFormat_MessagePack`. `chunk_ptr` is only valid until the callback
+ * returns.
+ */
+typedef void (*NxStreamCallback)(void *user_data, const uint8_t *chunk_ptr, size_t chunk_len);
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
 
 NX_FFI_EXPORT uint32_t nx_ffi_abi_version(void);
 
+/**
+ * Returns the envelope version to pass in `output_format` for a consumer that reads envelope
+ * versions up to `consumer_version`.
+ *
+ * The result is the newest version both sides understand, or `0` when the consumer reads no
+ * envelopes. See [`NX_OUTPUT_ENVELOPE_SHIFT`].
+ */
+NX_FFI_EXPORT uint32_t nx_ffi_negotiate_envelope_version(uint32_t consumer_version);
+
+/**
+ * Registers the callback that receives `log` calls, replacing any previous one.
+ *
+ * The registration is process-wide and applies to evaluations that start after this call.
+ * Calls below `min_level`, an [`NxLogLevel`] value, are dropped before reaching the callback.
+ * Pass a null callback to stop receiving them; without a callback, `log` does nothing.
+ * `user_data` is passed back unchanged and must stay valid until the callback is replaced.
+ * Returns `NxEvalStatus_InvalidArgument` when `min_level` is not a level.
+ */
+NX_FFI_EXPORT
+NxEvalStatus nx_set_log_callback(NxLogCallback callback, void *user_data, uint32_t min_level);
+
 NX_FFI_EXPORT void nx_free_buffer(struct NxBuffer buffer);
 
 NX_FFI_EXPORT
@@ -76,6 +155,37 @@ NxEvalStatus nx_eval_source(const uint8_t *source_ptr,
                             uint32_t output_format,
                             struct NxBuffer *out_buffer);
 
+/**
+ * Evaluates NX source like `nx_eval_source`, passing the MessagePack output to `callback` in
+ * chunks instead of one buffer.
+ *
+ * The result value is streamed with `NxEvalStatus_Ok` and diagnostics with `NxEvalStatus_Error`,
+ * so a host can decode or store a very large document piece by piece instead of allocating it
+ * whole. The chunks end early, with `NxEvalStatus_Error`, only if the value cannot be encoded
+ * after part of it was delivered. A null `callback` returns `NxEvalStatus_InvalidArgument`.
+ */
+NX_FFI_EXPORT
+NxEvalStatus nx_eval_source_stream(const uint8_t *source_ptr,
+                                   size_t source_len,
+                                   const uint8_t *file_name_ptr,
+                                   size_t file_name_len,
+                                   NxStreamCallback callback,
+                                   void *user_data);
+
+/**
+ * Reads the `meta { ... }` block of NX source text without type checking or evaluating it.
+ *
+ * Successful output is the metadata record in the selected format, or `null` when the module has
+ * no `meta` block. Syntax errors are returned as diagnostics with `NxEvalStatus::Error`.
+ */
+NX_FFI_EXPORT
+NxEvalStatus nx_read_module_metadata(const uint8_t *source_ptr,
+                                     size_t source_len,
+                                     const uint8_t *file_name_ptr,
+                                     size_t file_name_len,
+                                     uint32_t output_format,
+                                     struct NxBuffer *out_buffer);
+
 NX_FFI_EXPORT
 NxEvalStatus nx_build_program_artifact(const struct NxProgramBuildContextHandle *build_context_ptr,
                                        const uint8_t *source_ptr,
@@ -22,6 +22,15 @@ internal static class NxNativeMethods
         global::NxLang.Nx.NxOutputFormat outputFormat,
         out NxBuffer outBuffer);
 
+    [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    internal static extern NxEvalStatus nx_read_module_metadata(
+        byte[] sourcePtr,
+        nuint sourceLen,
+        byte[] fileNamePtr,
+        nuint fileNameLen,
+        global::NxLang.Nx.NxOutputFormat outputFormat,
+        out NxBuffer outBuffer);
+
     [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
     internal static extern NxEvalStatus nx_build_program_artifact(
         NxProgramBuildContextSafeHandle? buildContextPtr,
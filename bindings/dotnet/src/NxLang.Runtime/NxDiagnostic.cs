@@ -54,6 +54,63 @@ public sealed class NxDiagnostic
     [Key("note")]
     [JsonPropertyName("note")]
     public string? Note { get; set; }
+
+    /// <summary>
+    /// Gets or sets the machine-applicable fixes for the diagnostic, such as editor quick-fixes. Empty when the issue has
+    /// no automatic fix.
+    /// </summary>
+    [Key("fixes")]
+    [JsonPropertyName("fixes")]
+    public NxDiagnosticFix[] Fixes { get; set; } = Array.Empty<NxDiagnosticFix>();
+}
+
+/// <summary>
+/// Represents a machine-applicable fix attached to a diagnostic.
+/// </summary>
+[MessagePackObject]
+public sealed class NxDiagnosticFix
+{
+    /// <summary>
+    /// Gets or sets a short description of what applying the fix does.
+    /// </summary>
+    [Key("message")]
+    [JsonPropertyName("message")]
+    public string Message { get; set; } = string.Empty;
+
+    /// <summary>
+    /// Gets or sets the non-overlapping edits that make up the fix.
+    /// </summary>
+    [Key("edits")]
+    [JsonPropertyName("edits")]
+    public NxTextEdit[] Edits { get; set; } = Array.Empty<NxTextEdit>();
+}
+
+/// <summary>
+/// Represents a single text replacement that is part of a <see cref="NxDiagnosticFix"/>.
+/// </summary>
+[MessagePackObject]
+public sealed class NxTextEdit
+{
+    /// <summary>
+    /// Gets or sets the file to edit.
+    /// </summary>
+    [Key("file")]
+    [JsonPropertyName("file")]
+    public string File { get; set; } = string.Empty;
+
+    /// <summary>
+    /// Gets or sets the text span to replace. An empty span inserts <see cref="Replacement"/>.
+    /// </summary>
+    [Key("span")]
+    [JsonPropertyName("span")]
+    public NxTextSpan Span { get; set; } = new();
+
+    /// <summary>
+    /// Gets or sets the text to put in place of the span.
+    /// </summary>
+    [Key("replacement")]
+    [JsonPropertyName("replacement")]
+    public string Replacement { get; set; } = string.Empty;
 }
 
 /// <summary>
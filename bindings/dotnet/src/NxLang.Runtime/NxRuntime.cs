@@ -137,6 +137,28 @@ public static class NxRuntime
             "NX native runtime returned an invalid evaluation JSON payload.");
     }
 
+    /// <summary>
+    /// Reads the <c>meta { ... }</c> block of NX source code without evaluating it.
+    /// </summary>
+    /// <returns>The metadata record as JSON, or a JSON <c>null</c> when the module has no <c>meta</c> block.</returns>
+    public static JsonElement ReadModuleMetadataJson(string source, string? fileName = null)
+    {
+        byte[] payload = InvokeSourceNativeCall(
+            source,
+            fileName,
+            NxOutputFormat.Json,
+            NxNativeMethods.nx_read_module_metadata,
+            out NxEvalStatus status);
+        return status switch
+        {
+            NxEvalStatus.Ok => DeserializeJsonElement(
+                payload,
+                "NX native runtime returned an invalid metadata JSON payload."),
+            NxEvalStatus.Error => throw CreateEvaluationException(payload, NxOutputFormat.Json),
+            _ => throw CreateInteropStatusException(status),
+        };
+    }
+
     /// <summary>
     /// Evaluates NX source code against a caller-supplied build context and returns the JSON result.
     /// </summary>
@@ -0,0 +1,51 @@
+//! Startup benchmark for embedded builds.
+//!
+//! Reports how long the first `eval_source` call takes in a fresh process, which includes parser
+//! and type checker setup, and the mean of later calls on the same template. Build it with the
+//! `embedded` profile and the `minimal` feature set to measure what a mobile host ships:
+//!
+//! ```text
+//! cargo run -p nx-api --example embedded_startup --profile embedded \
+//!     --no-default-features --features minimal
+//! ```
+//!
+//! Pass an iteration count as the first argument to change how many warm runs are averaged.
+
+use nx_api::{eval_source, EvalResult, ProgramBuildContext};
+use std::time::{Duration, Instant};
+
+const TEMPLATE: &str = r#"
+type Item = { name:string price:float quantity:int }
+let lineTotal(item:Item): float = { item.price * 2.0 }
+let root() = <ul>{for item in [<Item name="Desk" price={199.0} quantity={1} />, <Item name="Lamp" price={24.5} quantity={2} />] { <li>{item.name}: {lineTotal(item)}</li> }}</ul>
+"#;
+
+fn eval_once(context: &ProgramBuildContext) -> Duration {
+    let started = Instant::now();
+    match eval_source(TEMPLATE, "startup.nx", context) {
+        EvalResult::Ok(_) => started.elapsed(),
+        EvalResult::Err(diagnostics) => panic!("template failed to evaluate: {:?}", diagnostics),
+    }
+}
+
+fn main() {
+    let iterations = std::env::args()
+        .nth(1)
+        .map(|arg| arg.parse().expect("iteration count must be a number"))
+        .unwrap_or(200u32);
+    let context = ProgramBuildContext::empty();
+
+    let first = eval_once(&context);
+    let warm = (0..iterations)
+        .map(|_| eval_once(&context))
+        .sum::<Duration>()
+        / iterations;
+
+    println!("first eval: {:?}", first);
+    println!("warm eval:  {:?} (mean of {})", warm, iterations);
+    if let Ok(path) = std::env::current_exe() {
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            println!("binary:     {} KiB", metadata.len() / 1024);
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use crate::diagnostics::{diagnostics_to_api, diagnostics_to_api_with_sources};
+use crate::module_resolver::{ModuleResolver, SharedModuleResolver};
 use crate::source_graph::{
     LogicalModuleGraph, LogicalSourceModule, SourceProvider, SourceProviderError,
     WorkspaceSourceProvider,
 };
 use crate::workspace::{normalize_workspace_identity, normalize_workspace_import_identity};
-use crate::{NxDiagnostic, NxWorkspace};
-use nx_diagnostics::{Diagnostic, Label, Severity, TextSize, TextSpan};
+use crate::{ImportPathResolver, NxDiagnostic, NxWorkspace};
+use nx_diagnostics::{normalize_path, suggest, Diagnostic, Label, Severity, TextSize, TextSpan};
 use nx_hir::{
     ast::TypeRef, binding_specs_for_item, local_definition_id, lower, Import, ImportKind,
     ImportedRawRef, InterfaceField, InterfaceItem, InterfaceItemKind, InterfaceParam, Item,
@@ -17,11 +18,12 @@ use nx_interpreter::{
     ModuleQualifiedItemRef, ResolvedItemKind, ResolvedModule, ResolvedModuleSource,
     ResolvedProgram, RuntimeModuleId,
 };
-use nx_syntax::parse_str as syntax_parse_str;
-use nx_types::{analyze_prepared_module, ModuleArtifact, Type, TypeEnvironment};
+use nx_stdlib::StdModule;
+use nx_syntax::{parse_str as syntax_parse_str, ParseResult, SyntaxTree};
+
+use nx_types::{analyze_prepared_module, time_stage, ModuleArtifact, Stage, Type, TypeEnvironment};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::hash_map::DefaultHasher;
-use std::fs;
 use std::hash::{Hash, Hasher};
 use std::io;
 use std::path::{Component, Path, PathBuf};
@@ -56,6 +58,9 @@ pub struct LibraryArtifact {
 }
 
 /// File-preserving artifact for one resolved NX program.
+///
+/// A program artifact is `Send + Sync`: build it once, then evaluate it from as many worker
+/// threads as needed, each with its own call to [`crate::eval_program_artifact`].
 #[derive(Debug, Clone)]
 pub struct ProgramArtifact {
     /// Analyzed root modules submitted by the source provider.
@@ -78,6 +83,13 @@ pub struct ProgramArtifact {
     pub(crate) source_map: FxHashMap<String, Arc<str>>,
 }
 
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ProgramArtifact>();
+    assert_send_sync::<LibraryArtifact>();
+    assert_send_sync::<ProgramBuildContext>();
+};
+
 #[derive(Debug, Default)]
 struct LibraryRegistryState {
     libraries: FxHashMap<PathBuf, Arc<LibraryArtifact>>,
@@ -86,9 +98,13 @@ struct LibraryRegistryState {
 }
 
 /// Public owner of analyzed library snapshots.
+///
+/// Libraries are read through the registry's [`ModuleResolver`], which is the file system unless
+/// the registry was created with [`LibraryRegistry::with_module_resolver`].
 #[derive(Debug, Clone, Default)]
 pub struct LibraryRegistry {
     inner: Arc<RwLock<LibraryRegistryState>>,
+    modules: SharedModuleResolver,
 }
 
 impl LibraryRegistry {
@@ -96,6 +112,19 @@ impl LibraryRegistry {
         Self::default()
     }
 
+    /// Creates a registry that reads libraries through `resolver` instead of the file system.
+    pub fn with_module_resolver(resolver: Arc<dyn ModuleResolver>) -> Self {
+        Self {
+            inner: Arc::default(),
+            modules: SharedModuleResolver(resolver),
+        }
+    }
+
+    /// Returns the resolver libraries are read through.
+    pub fn module_resolver(&self) -> &Arc<dyn ModuleResolver> {
+        &self.modules.0
+    }
+
     pub fn load_library_from_directory(
         &self,
         root_path: impl AsRef<Path>,
@@ -121,10 +150,137 @@ impl LibraryRegistry {
         Ok(artifact)
     }
 
+    /// Resolves the imports of a source file on disk, loads every library they reference, and
+    /// returns a build context for building that file.
+    ///
+    /// Each import binds to the library root chosen by `resolver` when the program is built with
+    /// `file_path` as its file name, even when the path is relative to a directory below the
+    /// imported libraries. Imports that cannot be found, or whose libraries fail to load,
+    /// are reported as diagnostics labeled in `file_path`. Git, HTTP and `std/` imports are left for
+    /// program analysis to resolve or report.
+    pub fn build_context_for_source(
+        &self,
+        source: &str,
+        file_path: &Path,
+        resolver: &ImportPathResolver,
+    ) -> Result<ProgramBuildContext, Vec<Diagnostic>> {
+        let file_name = file_path.display().to_string();
+        let parse_result = time_stage(Stage::Parse, || syntax_parse_str(source, &file_name));
+        let Some(tree) = parse_result.tree else {
+            return Ok(self.build_context());
+        };
+        self.build_context_for_tree(&tree, file_path, resolver)
+    }
+
+    /// Like [`build_context_for_source`](Self::build_context_for_source), for a file the caller
+    /// has already parsed, such as an editor buffer kept current with
+    /// [`SyntaxTree::apply_edit`].
+    pub fn build_context_for_tree(
+        &self,
+        tree: &SyntaxTree,
+        file_path: &Path,
+        resolver: &ImportPathResolver,
+    ) -> Result<ProgramBuildContext, Vec<Diagnostic>> {
+        let file_name = file_path.display().to_string();
+        let identity = logical_source_identity(&file_name);
+        let module = time_stage(Stage::Lower, || {
+            lower(tree.root(), SourceId::new(tree.source_id().as_u32()))
+        });
+
+        let mut diagnostics = Vec::new();
+        let mut import_roots = FxHashMap::default();
+        for import in &module.imports {
+            if is_http_library_path(&import.library_path)
+                || is_git_library_path(&import.library_path)
+                || nx_stdlib::is_std_import(&import.library_path)
+            {
+                continue;
+            }
+
+            let library_root = match resolver.resolve_with(
+                file_path,
+                &import.library_path,
+                self.module_resolver().as_ref(),
+            ) {
+                Ok(library_root) => library_root,
+                Err(error) => {
+                    diagnostics.push(
+                        Diagnostic::error("import-not-found")
+                            .with_message(error.to_string())
+                            .with_label(Label::primary(file_name.clone(), import.span))
+                            .build(),
+                    );
+                    continue;
+                }
+            };
+
+            match self.load_library_from_directory(&library_root) {
+                Ok(library) => {
+                    import_roots.insert(
+                        (identity.clone(), import.library_path.clone()),
+                        library.root_path.clone(),
+                    );
+                    continue;
+                }
+                Err(load_diagnostics) => {
+                    let messages = load_diagnostics
+                        .iter()
+                        .map(|diagnostic| diagnostic.message.as_str())
+                        .collect::<Vec<_>>();
+                    diagnostics.push(
+                        Diagnostic::error("library-load-error")
+                            .with_message(format!(
+                                "Imported library '{}' failed to load",
+                                library_root.display()
+                            ))
+                            .with_label(Label::primary(file_name.clone(), import.span))
+                            .with_note(messages.join("\n"))
+                            .build(),
+                    );
+                }
+            }
+        }
+
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
+        let mut build_context = self.build_context();
+        build_context.import_roots = import_roots;
+        Ok(build_context)
+    }
+
+    /// Returns the artifact for an embedded standard library module, analyzing it on first use.
+    ///
+    /// Standard library artifacts live alongside directory-backed libraries but are never part of
+    /// the visible roots of a build context; they are reached only through `std/` imports.
+    pub fn load_std_library(&self, module: &StdModule) -> Arc<LibraryArtifact> {
+        let root_path = std_library_root(module);
+        if let Some(existing) = self.get_loaded_library(&root_path) {
+            return existing;
+        }
+
+        let source_path = root_path.join(module.file_name());
+        let source_files = vec![parse_library_source_file(
+            source_path,
+            module.source.to_string(),
+        )];
+        let artifact = Arc::new(build_library_artifact_from_source_files(
+            root_path.clone(),
+            source_files,
+            self,
+        ));
+        let mut state = self.inner.write().expect("library registry lock poisoned");
+        state
+            .dependency_graph
+            .insert(root_path.clone(), artifact.dependency_roots.clone());
+        state.libraries.entry(root_path).or_insert(artifact).clone()
+    }
+
     pub fn build_context(&self) -> ProgramBuildContext {
         ProgramBuildContext {
             registry: self.clone(),
             visible_roots: self.loaded_roots().into_iter().collect(),
+            import_roots: FxHashMap::default(),
         }
     }
 
@@ -138,18 +294,67 @@ impl LibraryRegistry {
     {
         let mut visible_roots = FxHashSet::default();
         for root in roots {
-            visible_roots.insert(fs::canonicalize(root.as_ref())?);
+            visible_roots.insert(self.module_resolver().canonicalize(root.as_ref())?);
         }
 
         Ok(ProgramBuildContext {
             registry: self.clone(),
             visible_roots,
+            import_roots: FxHashMap::default(),
         })
     }
 
+    /// Drops the loaded libraries that contain the module at `path`, and every library that
+    /// depends on them, so that the next build reads them through the registry's resolver again.
+    ///
+    /// Hosts that change modules behind a long-lived registry, such as the buffers of an
+    /// [`OverlayModuleResolver`](crate::OverlayModuleResolver), call this for each changed path.
+    /// Returns true if any library was dropped.
+    pub fn invalidate(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        let path = self
+            .module_resolver()
+            .canonicalize(path)
+            .unwrap_or_else(|_| path.to_path_buf());
+        let mut state = self.inner.write().expect("library registry lock poisoned");
+        let mut stale = state
+            .libraries
+            .keys()
+            .filter(|root| path.starts_with(root))
+            .cloned()
+            .collect::<FxHashSet<_>>();
+        loop {
+            let dependents = state
+                .dependency_graph
+                .iter()
+                .filter(|(root, dependencies)| {
+                    !stale.contains(*root)
+                        && dependencies
+                            .iter()
+                            .any(|dependency| stale.contains(dependency))
+                })
+                .map(|(root, _)| root.clone())
+                .collect::<Vec<_>>();
+            if dependents.is_empty() {
+                break;
+            }
+            stale.extend(dependents);
+        }
+        for root in &stale {
+            state.libraries.remove(root);
+            state.dependency_graph.remove(root);
+        }
+        !stale.is_empty()
+    }
+
     fn loaded_roots(&self) -> Vec<PathBuf> {
         let state = self.inner.read().expect("library registry lock poisoned");
-        let mut roots = state.libraries.keys().cloned().collect::<Vec<_>>();
+        let mut roots = state
+            .libraries
+            .keys()
+            .filter(|root| std_module_for_root(root).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
         roots.sort();
         roots
     }
@@ -172,7 +377,7 @@ impl LibraryRegistry {
         root_path: &Path,
         loading_stack: &mut Vec<PathBuf>,
     ) -> io::Result<Arc<LibraryArtifact>> {
-        let root_path = fs::canonicalize(root_path)?;
+        let root_path = self.module_resolver().canonicalize(root_path)?;
         if let Some(existing) = self.get_loaded_library(&root_path) {
             return Ok(existing);
         }
@@ -201,8 +406,13 @@ impl LibraryRegistry {
 
         loading_stack.push(root_path.clone());
         let result = (|| {
-            let dependency_roots = discover_library_dependency_roots(&root_path)?;
+            let dependency_roots =
+                discover_library_dependency_roots(&root_path, self.module_resolver().as_ref())?;
             for dependency_root in &dependency_roots {
+                if let Some(module) = std_module_for_root(dependency_root) {
+                    self.load_std_library(module);
+                    continue;
+                }
                 let _ = self.load_library_from_directory_internal_with_stack(
                     dependency_root,
                     loading_stack,
@@ -265,6 +475,8 @@ impl LibraryRegistry {
 pub struct ProgramBuildContext {
     registry: LibraryRegistry,
     visible_roots: FxHashSet<PathBuf>,
+    /// Library roots chosen by the host, keyed by importing module identity and import path.
+    import_roots: FxHashMap<(String, String), PathBuf>,
 }
 
 impl Default for ProgramBuildContext {
@@ -278,6 +490,7 @@ impl ProgramBuildContext {
         Self {
             registry: LibraryRegistry::new(),
             visible_roots: FxHashSet::default(),
+            import_roots: FxHashMap::default(),
         }
     }
 
@@ -293,6 +506,26 @@ impl ProgramBuildContext {
         self.registry.get_loaded_library(root)
     }
 
+    fn import_root(
+        &self,
+        importer_identity: &str,
+        library_path: &str,
+    ) -> Option<(PathBuf, Arc<LibraryArtifact>)> {
+        let root = self
+            .import_roots
+            .get(&(importer_identity.to_string(), library_path.to_string()))?;
+        let library = self.registry.get_loaded_library(root)?;
+        Some((root.clone(), library))
+    }
+
+    fn std_library(&self, library_path: &str) -> Option<(PathBuf, Arc<LibraryArtifact>)> {
+        let module = nx_stdlib::module_for_import(library_path)?;
+        Some((
+            std_library_root(module),
+            self.registry.load_std_library(module),
+        ))
+    }
+
     fn visible_library_by_logical_identity(&self, identity: &str) -> LogicalLibraryResolution {
         let mut visible_roots = self.visible_roots.iter().collect::<Vec<_>>();
         visible_roots.sort();
@@ -496,8 +729,21 @@ fn build_library_artifact_with_registry(
     root_path: &Path,
     registry: &LibraryRegistry,
 ) -> io::Result<LibraryArtifact> {
-    let root_path = fs::canonicalize(root_path)?;
-    let source_files = read_library_source_files(&root_path)?;
+    let modules = registry.module_resolver().as_ref();
+    let root_path = modules.canonicalize(root_path)?;
+    let source_files = read_library_source_files(&root_path, modules)?;
+    Ok(build_library_artifact_from_source_files(
+        root_path,
+        source_files,
+        registry,
+    ))
+}
+
+fn build_library_artifact_from_source_files(
+    root_path: PathBuf,
+    source_files: Vec<LibrarySourceFile>,
+    registry: &LibraryRegistry,
+) -> LibraryArtifact {
     let mut hasher = DefaultHasher::new();
     root_path.hash(&mut hasher);
 
@@ -507,7 +753,12 @@ fn build_library_artifact_with_registry(
         source_file.source.hash(&mut hasher);
 
         if let Some(module) = source_file.preserved_module.as_ref() {
-            collect_library_dependencies(&module.imports, &source_file.path, &mut dependency_roots);
+            collect_library_dependencies(
+                &module.imports,
+                &source_file.path,
+                registry.module_resolver().as_ref(),
+                &mut dependency_roots,
+            );
         }
     }
 
@@ -561,7 +812,7 @@ fn build_library_artifact_with_registry(
         modules.push(artifact);
     }
 
-    Ok(LibraryArtifact {
+    LibraryArtifact {
         root_path,
         modules,
         exports,
@@ -571,16 +822,24 @@ fn build_library_artifact_with_registry(
         dependency_roots,
         diagnostics,
         fingerprint: hasher.finish(),
-    })
+    }
 }
 
-fn discover_library_dependency_roots(root_path: &Path) -> io::Result<Vec<PathBuf>> {
-    let source_files = read_library_source_files(root_path)?;
+fn discover_library_dependency_roots(
+    root_path: &Path,
+    modules: &dyn ModuleResolver,
+) -> io::Result<Vec<PathBuf>> {
+    let source_files = read_library_source_files(root_path, modules)?;
     let mut dependency_roots = FxHashSet::default();
 
     for source_file in &source_files {
         if let Some(module) = source_file.preserved_module.as_ref() {
-            collect_library_dependencies(&module.imports, &source_file.path, &mut dependency_roots);
+            collect_library_dependencies(
+                &module.imports,
+                &source_file.path,
+                modules,
+                &mut dependency_roots,
+            );
         }
     }
 
@@ -598,33 +857,46 @@ fn circular_library_dependency_message(cycle: &[PathBuf]) -> String {
     format!("Circular library dependency detected: {}", chain)
 }
 
-fn read_library_source_files(root_path: &Path) -> io::Result<Vec<LibrarySourceFile>> {
-    let mut source_paths = Vec::new();
-    collect_nx_files(root_path, &mut source_paths)?;
+fn read_library_source_files(
+    root_path: &Path,
+    modules: &dyn ModuleResolver,
+) -> io::Result<Vec<LibrarySourceFile>> {
+    let mut source_paths = if modules.is_directory(root_path) {
+        modules.list_modules(root_path)?
+    } else {
+        // A single `.nx` file imported by path is a library of one module.
+        vec![root_path.to_path_buf()]
+    };
     source_paths.sort();
 
     let mut source_files = Vec::with_capacity(source_paths.len());
     for source_path in source_paths {
-        let source = fs::read_to_string(&source_path)?;
-        let file_name = source_path.display().to_string();
-        let parse_result = syntax_parse_str(&source, &file_name);
-        let source_id = SourceId::new(parse_result.source_id.as_u32());
-        let diagnostics = normalize_diagnostics_file_name(parse_result.errors, &file_name);
-        let preserved_module = parse_result.tree.map(|tree| lower(tree.root(), source_id));
-
-        source_files.push(LibrarySourceFile {
-            file_name,
-            path: source_path,
-            source,
-            source_id,
-            diagnostics,
-            preserved_module,
-        });
+        let source = modules.resolve(&source_path)?;
+        source_files.push(parse_library_source_file(source_path, source.to_string()));
     }
 
     Ok(source_files)
 }
 
+fn parse_library_source_file(path: PathBuf, source: String) -> LibrarySourceFile {
+    let file_name = path.display().to_string();
+    let parse_result = time_stage(Stage::Parse, || syntax_parse_str(&source, &file_name));
+    let source_id = SourceId::new(parse_result.source_id.as_u32());
+    let diagnostics = normalize_diagnostics_file_name(parse_result.errors, &file_name);
+    let preserved_module = parse_result
+        .tree
+        .map(|tree| time_stage(Stage::Lower, || lower(tree.root(), source_id)));
+
+    LibrarySourceFile {
+        file_name,
+        path,
+        source,
+        source_id,
+        diagnostics,
+        preserved_module,
+    }
+}
+
 fn analyze_library_source_file(
     library_root: &Path,
     source_files: &[LibrarySourceFile],
@@ -721,6 +993,7 @@ fn apply_current_library_items(
                 .collect::<Vec<_>>()
                 .join(", ");
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "Library item '{}' is defined in multiple files ({}). Use unique names within one library.",
                     visible_name, sources
@@ -761,7 +1034,14 @@ fn analyze_logical_module_graph(
     graph: &LogicalModuleGraph,
     build_context: &ProgramBuildContext,
 ) -> LogicalProgramAnalysis {
-    let source_files = parse_logical_source_files(graph);
+    analyze_graph_source_files(graph, parse_logical_source_files(graph), build_context)
+}
+
+fn analyze_graph_source_files(
+    graph: &LogicalModuleGraph,
+    source_files: Vec<GraphSourceFile>,
+    build_context: &ProgramBuildContext,
+) -> LogicalProgramAnalysis {
     let source_map = graph.source_map();
     let mut modules = Vec::with_capacity(source_files.len());
     let mut libraries_by_root = FxHashMap::<PathBuf, Arc<LibraryArtifact>>::default();
@@ -794,23 +1074,33 @@ fn parse_logical_source_files(graph: &LogicalModuleGraph) -> Vec<GraphSourceFile
         .modules()
         .iter()
         .map(|module| {
-            let parse_result = syntax_parse_str(module.source.as_ref(), &module.identity);
-            let source_id = SourceId::new(parse_result.source_id.as_u32());
-            let diagnostics =
-                normalize_diagnostics_file_name(parse_result.errors, &module.identity);
-            let preserved_module = parse_result.tree.map(|tree| lower(tree.root(), source_id));
-
-            GraphSourceFile {
-                identity: module.identity.clone(),
-                source: module.source.clone(),
-                source_id,
-                diagnostics,
-                preserved_module,
-            }
+            let parse_result = time_stage(Stage::Parse, || {
+                syntax_parse_str(module.source.as_ref(), &module.identity)
+            });
+            lower_graph_source_file(module, parse_result)
         })
         .collect()
 }
 
+fn lower_graph_source_file(
+    module: &LogicalSourceModule,
+    parse_result: ParseResult,
+) -> GraphSourceFile {
+    let source_id = SourceId::new(parse_result.source_id.as_u32());
+    let diagnostics = normalize_diagnostics_file_name(parse_result.errors, &module.identity);
+    let preserved_module = parse_result
+        .tree
+        .map(|tree| time_stage(Stage::Lower, || lower(tree.root(), source_id)));
+
+    GraphSourceFile {
+        identity: module.identity.clone(),
+        source: module.source.clone(),
+        source_id,
+        diagnostics,
+        preserved_module,
+    }
+}
+
 fn analyze_logical_source_file(
     source_files: &[GraphSourceFile],
     build_context: &ProgramBuildContext,
@@ -883,6 +1173,7 @@ fn apply_graph_imports(
     for import in module.raw_module().imports.clone() {
         if is_git_library_path(&import.library_path) {
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "Git library imports are not yet supported: '{}'",
                     import.library_path
@@ -894,6 +1185,7 @@ fn apply_graph_imports(
 
         if is_http_library_path(&import.library_path) {
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "HTTP zip library imports are not yet supported: '{}'",
                     import.library_path
@@ -903,21 +1195,37 @@ fn apply_graph_imports(
             continue;
         }
 
-        let target_identity = match normalize_workspace_import_identity(
-            &source_file.identity,
-            &import.library_path,
-        ) {
-            Ok(identity) => identity,
-            Err(error) => {
-                module.add_diagnostic(LoweringDiagnostic {
-                    message: format!(
-                        "Workspace import '{}' is invalid: {}",
-                        import.library_path, error
-                    ),
-                    span: import.span,
-                });
-                continue;
-            }
+        if is_unknown_std_import(&import.library_path) {
+            module.add_diagnostic(LoweringDiagnostic {
+                code: None,
+                message: unknown_std_module_message(&import.library_path),
+                span: import.span,
+            });
+            continue;
+        }
+
+        let import_root = build_context
+            .import_root(&source_file.identity, &import.library_path)
+            .or_else(|| build_context.std_library(&import.library_path));
+        let target_identity = match &import_root {
+            Some((root, _)) => root.display().to_string(),
+            None => match normalize_workspace_import_identity(
+                &source_file.identity,
+                &import.library_path,
+            ) {
+                Ok(identity) => identity,
+                Err(error) => {
+                    module.add_diagnostic(LoweringDiagnostic {
+                        code: None,
+                        message: format!(
+                            "Workspace import '{}' is invalid: {}",
+                            import.library_path, error
+                        ),
+                        span: import.span,
+                    });
+                    continue;
+                }
+            },
         };
 
         if let Some(first_import_span) =
@@ -930,6 +1238,7 @@ fn apply_graph_imports(
                     })
                     .unwrap_or_default();
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "Module or library '{}' is imported more than once in this file{}",
                     target_identity, first_import_location
@@ -939,7 +1248,11 @@ fn apply_graph_imports(
             continue;
         }
 
-        if let Some(target_index) = identity_to_index.get(&target_identity).copied() {
+        if let Some(target_index) = identity_to_index
+            .get(&target_identity)
+            .copied()
+            .filter(|_| import_root.is_none())
+        {
             add_workspace_import_bindings(
                 module,
                 &source_files[target_index],
@@ -949,7 +1262,12 @@ fn apply_graph_imports(
             continue;
         }
 
-        match build_context.visible_library_by_logical_identity(&target_identity) {
+        let resolution = match import_root {
+            Some((root, library)) => LogicalLibraryResolution::Found(root, library),
+            None => build_context.visible_library_by_logical_identity(&target_identity),
+        };
+
+        match resolution {
             LogicalLibraryResolution::Found(normalized_root, library) => {
                 resolved_imports.push(ResolvedBuildContextImport {
                     normalized_root,
@@ -988,6 +1306,7 @@ fn apply_graph_imports(
                                 library.exported_items.get(entry.name.as_str())
                             else {
                                 module.add_diagnostic(LoweringDiagnostic {
+                                    code: None,
                                     message: format!(
                                         "Library '{}' does not export '{}'",
                                         library.root_path.display(),
@@ -999,6 +1318,14 @@ fn apply_graph_imports(
                             };
 
                             let visible_name = visible_name_for_selective(entry);
+                            if selective_import_conflicts_with_local(
+                                module,
+                                entry,
+                                &visible_name,
+                                &imported_visible_names,
+                            ) {
+                                continue;
+                            }
                             add_imported_interface_bindings(
                                 module,
                                 &visible_name,
@@ -1014,6 +1341,7 @@ fn apply_graph_imports(
             }
             LogicalLibraryResolution::Ambiguous(roots) => {
                 module.add_diagnostic(LoweringDiagnostic {
+                    code: None,
                     message: format!(
                         "Ambiguous loaded library import '{}' matches multiple visible library roots: {}",
                         target_identity,
@@ -1027,6 +1355,7 @@ fn apply_graph_imports(
         }
 
         module.add_diagnostic(LoweringDiagnostic {
+            code: None,
             message: format!(
                 "Missing workspace module or loaded library '{}' in the supplied build context",
                 target_identity
@@ -1046,6 +1375,7 @@ fn add_workspace_import_bindings(
 ) {
     let Some(target_module) = target_source_file.preserved_module.as_ref() else {
         module.add_diagnostic(LoweringDiagnostic {
+            code: None,
             message: format!(
                 "Workspace import '{}' targets a module that did not parse successfully",
                 target_source_file.identity
@@ -1086,6 +1416,7 @@ fn add_workspace_import_bindings(
                     })
                 else {
                     module.add_diagnostic(LoweringDiagnostic {
+                        code: None,
                         message: format!(
                             "Workspace module '{}' does not export '{}'",
                             target_source_file.identity,
@@ -1097,6 +1428,14 @@ fn add_workspace_import_bindings(
                 };
 
                 let visible_name = visible_name_for_selective(entry);
+                if selective_import_conflicts_with_local(
+                    module,
+                    entry,
+                    &visible_name,
+                    imported_visible_names,
+                ) {
+                    continue;
+                }
                 add_workspace_item_bindings(
                     module,
                     &target_source_file.identity,
@@ -1131,6 +1470,7 @@ fn add_workspace_item_bindings(
             imported_visible_names.get(&(namespace, visible_name.to_string()))
         {
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "Imported name '{}' is provided by both {} and {}. Use aliases to disambiguate.",
                     visible_name, previous_origin, target_module_identity
@@ -1178,12 +1518,56 @@ pub fn build_program_artifact_from_source(
     ))
 }
 
+/// Builds a file-preserving program artifact like [`build_program_artifact_from_source`] from a
+/// file the caller has already parsed, without parsing `source` again.
+///
+/// `parse_result` must be the result of parsing `source`, such as the one
+/// [`SyntaxTree::apply_edit`] returns for an editor buffer.
+pub fn build_program_artifact_from_parse(
+    source: &str,
+    parse_result: &ParseResult,
+    file_name: &str,
+    build_context: &ProgramBuildContext,
+) -> io::Result<ProgramArtifact> {
+    let module = LogicalSourceModule {
+        identity: logical_source_identity(file_name),
+        source: Arc::<str>::from(source),
+    };
+    let source_file = lower_graph_source_file(
+        &module,
+        ParseResult {
+            tree: parse_result.tree.clone(),
+            errors: parse_result.errors.clone(),
+            source_id: parse_result.source_id,
+        },
+    );
+    let identity = module.identity.clone();
+    let graph =
+        LogicalModuleGraph::from_modules(vec![module]).map_err(source_provider_error_to_io)?;
+    let analysis = analyze_graph_source_files(&graph, vec![source_file], build_context);
+    Ok(program_artifact_from_analysis(
+        &graph,
+        &identity,
+        build_context,
+        analysis,
+    ))
+}
+
 fn build_program_artifact_from_graph(
     graph: &LogicalModuleGraph,
     entry_identity: &str,
     build_context: &ProgramBuildContext,
 ) -> ProgramArtifact {
     let analysis = analyze_logical_module_graph(graph, build_context);
+    program_artifact_from_analysis(graph, entry_identity, build_context, analysis)
+}
+
+fn program_artifact_from_analysis(
+    graph: &LogicalModuleGraph,
+    entry_identity: &str,
+    build_context: &ProgramBuildContext,
+    analysis: LogicalProgramAnalysis,
+) -> ProgramArtifact {
     let mut hasher = DefaultHasher::new();
     entry_identity.hash(&mut hasher);
     for module in graph.modules() {
@@ -1200,7 +1584,13 @@ fn build_program_artifact_from_graph(
     let source_map = analysis.source_map;
 
     let fingerprint = hasher.finish();
-    let resolved_program = build_resolved_program(&root_modules, &libraries, fingerprint);
+    let resolved_program = build_resolved_program(
+        &root_modules,
+        &libraries,
+        &build_context.import_roots,
+        build_context.registry.module_resolver().as_ref(),
+        fingerprint,
+    );
     let entry_module_id = resolved_program.source_provider_module_id(entry_identity);
 
     ProgramArtifact {
@@ -1245,14 +1635,17 @@ fn apply_build_context_imports(
     build_context: &ProgramBuildContext,
     source: &str,
 ) -> Vec<ResolvedBuildContextImport> {
-    let root_path = match fs::canonicalize(root_path) {
+    let modules = build_context.registry.module_resolver().as_ref();
+    let root_path = match modules.canonicalize(root_path) {
         Ok(root_path) => root_path,
         Err(error) => {
             if module.raw_module().imports.iter().any(|import| {
                 !is_git_library_path(&import.library_path)
                     && !is_http_library_path(&import.library_path)
+                    && !nx_stdlib::is_std_import(&import.library_path)
             }) {
                 module.add_diagnostic(LoweringDiagnostic {
+                    code: None,
                     message: format!(
                         "Local library import resolution was skipped because source file path '{}' could not be resolved: {}",
                         root_path.display(),
@@ -1260,8 +1653,11 @@ fn apply_build_context_imports(
                     ),
                     span: full_source_span(source),
                 });
+                return Vec::new();
             }
-            return Vec::new();
+            // Embedded standard library sources have no file on disk; they can still import
+            // other standard library modules.
+            root_path.to_path_buf()
         }
     };
 
@@ -1272,6 +1668,7 @@ fn apply_build_context_imports(
     for import in module.raw_module().imports.clone() {
         if is_git_library_path(&import.library_path) {
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "Git library imports are not yet supported: '{}'",
                     import.library_path
@@ -1283,6 +1680,7 @@ fn apply_build_context_imports(
 
         if is_http_library_path(&import.library_path) {
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "HTTP zip library imports are not yet supported: '{}'",
                     import.library_path
@@ -1292,31 +1690,50 @@ fn apply_build_context_imports(
             continue;
         }
 
-        let normalized_root = match normalize_local_library_path(&root_path, &import.library_path) {
-            Ok(path) => path,
-            Err(_) => {
-                module.add_diagnostic(LoweringDiagnostic {
-                    message: format!(
-                        "Local library import '{}' could not be resolved to a directory",
-                        import.library_path
-                    ),
-                    span: import.span,
-                });
-                continue;
-            }
-        };
-
-        if !normalized_root.is_dir() {
+        if is_unknown_std_import(&import.library_path) {
             module.add_diagnostic(LoweringDiagnostic {
-                message: format!(
-                    "Local library import '{}' must resolve to a directory",
-                    import.library_path
-                ),
+                code: None,
+                message: unknown_std_module_message(&import.library_path),
                 span: import.span,
             });
             continue;
         }
 
+        let std_module = nx_stdlib::module_for_import(&import.library_path);
+        let normalized_root = match std_module {
+            Some(std_module) => std_library_root(std_module),
+            None => {
+                let normalized_root =
+                    match normalize_local_library_path(&root_path, &import.library_path, modules) {
+                        Ok(path) => path,
+                        Err(_) => {
+                            module.add_diagnostic(LoweringDiagnostic {
+                                code: None,
+                                message: format!(
+                                "Local library import '{}' could not be resolved to a directory",
+                                import.library_path
+                            ),
+                                span: import.span,
+                            });
+                            continue;
+                        }
+                    };
+
+                if !modules.is_directory(&normalized_root) {
+                    module.add_diagnostic(LoweringDiagnostic {
+                        code: None,
+                        message: format!(
+                            "Local library import '{}' must resolve to a directory",
+                            import.library_path
+                        ),
+                        span: import.span,
+                    });
+                    continue;
+                }
+                normalized_root
+            }
+        };
+
         let normalized_key = normalized_root.to_string_lossy().to_string();
         if let Some(first_import_span) = seen_import_roots.insert(normalized_key, import.span) {
             let first_import_location = line_col_for_span(source, first_import_span)
@@ -1325,6 +1742,7 @@ fn apply_build_context_imports(
                 })
                 .unwrap_or_default();
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "Library '{}' is imported more than once in this file{}",
                     normalized_root.display(),
@@ -1335,15 +1753,22 @@ fn apply_build_context_imports(
             continue;
         }
 
-        let Some(library) = build_context.visible_library(&normalized_root) else {
-            module.add_diagnostic(LoweringDiagnostic {
-                message: format!(
-                    "Missing loaded library '{}' in the supplied build context",
-                    normalized_root.display()
-                ),
-                span: import.span,
-            });
-            continue;
+        let library = match std_module {
+            Some(std_module) => build_context.registry.load_std_library(std_module),
+            None => {
+                let Some(library) = build_context.visible_library(&normalized_root) else {
+                    module.add_diagnostic(LoweringDiagnostic {
+                        code: None,
+                        message: format!(
+                            "Missing loaded library '{}' in the supplied build context",
+                            normalized_root.display()
+                        ),
+                        span: import.span,
+                    });
+                    continue;
+                };
+                library
+            }
         };
 
         resolved_imports.push(ResolvedBuildContextImport {
@@ -1379,6 +1804,7 @@ fn apply_build_context_imports(
                 for entry in entries {
                     let Some(item_indices) = library.exported_items.get(entry.name.as_str()) else {
                         module.add_diagnostic(LoweringDiagnostic {
+                            code: None,
                             message: format!(
                                 "Library '{}' does not export '{}'",
                                 normalized_root.display(),
@@ -1390,6 +1816,14 @@ fn apply_build_context_imports(
                     };
 
                     let visible_name = visible_name_for_selective(entry);
+                    if selective_import_conflicts_with_local(
+                        module,
+                        entry,
+                        &visible_name,
+                        &imported_visible_names,
+                    ) {
+                        continue;
+                    }
                     add_imported_interface_bindings(
                         module,
                         &visible_name,
@@ -1421,6 +1855,7 @@ fn add_ambiguous_interface_diagnostic(
         .join(" and ");
 
     module.add_diagnostic(LoweringDiagnostic {
+        code: None,
         message: format!(
             "Ambiguous imported name '{}' could refer to {}. Use a more specific import alias.",
             visible_name, sources
@@ -1474,6 +1909,7 @@ fn add_imported_interface_bindings(
             imported_visible_names.get(&(namespace, visible_name.to_string()))
         {
             module.add_diagnostic(LoweringDiagnostic {
+                code: None,
                 message: format!(
                     "Imported name '{}' is provided by both {} and {}. Use aliases to disambiguate.",
                     visible_name,
@@ -1580,6 +2016,8 @@ fn selected_program_libraries(
 fn build_resolved_program(
     root_modules: &[ModuleArtifact],
     libraries: &[Arc<LibraryArtifact>],
+    import_roots: &FxHashMap<(String, String), PathBuf>,
+    module_resolver: &dyn ModuleResolver,
     fingerprint: u64,
 ) -> ResolvedProgram {
     let mut modules = Vec::new();
@@ -1717,8 +2155,21 @@ fn build_resolved_program(
                 continue;
             }
 
-            let library = normalize_supported_library_path(module_file, &import.library_path)
-                .and_then(|normalized_root| library_by_root.get(&normalized_root))
+            let library = import_roots
+                .get(&(artifact.file_name.clone(), import.library_path.clone()))
+                .and_then(|root| library_by_root.get(root))
+                .or_else(|| {
+                    nx_stdlib::module_for_import(&import.library_path)
+                        .and_then(|module| library_by_root.get(&std_library_root(module)))
+                })
+                .or_else(|| {
+                    normalize_supported_library_path(
+                        module_file,
+                        &import.library_path,
+                        module_resolver,
+                    )
+                    .and_then(|normalized_root| library_by_root.get(&normalized_root))
+                })
                 .or_else(|| {
                     target_identity
                         .as_ref()
@@ -1939,6 +2390,7 @@ fn build_interface_item(
                         name: param.name.clone(),
                         ty: param.ty.clone(),
                         is_content: param.is_content,
+                        is_required: param.is_required(),
                         span: param.span,
                     })
                     .collect(),
@@ -1968,6 +2420,7 @@ fn build_interface_item(
                 .iter()
                 .map(record_field_to_interface_field)
                 .collect(),
+            rest: component.rest.clone(),
             emits: component.emits.clone(),
             state: component
                 .state
@@ -2005,6 +2458,7 @@ fn build_interface_item(
             kind: record_def.kind,
             is_abstract: record_def.is_abstract,
             base: record_def.base.clone(),
+            includes: record_def.includes.clone(),
             properties: record_def
                 .properties
                 .iter()
@@ -2029,6 +2483,7 @@ fn record_field_to_interface_field(field: &RecordField) -> LibraryInterfaceField
         ty: field.ty.clone(),
         is_content: field.is_content,
         is_required: field.default.is_none() && !matches!(field.ty, TypeRef::Nullable(_)),
+        constraints: field.constraints.clone(),
         span: field.span,
     }
 }
@@ -2039,6 +2494,7 @@ fn union_case_field_to_interface_field(field: &UnionCaseField) -> LibraryInterfa
         ty: field.ty.clone(),
         is_content: field.is_content,
         is_required: field.default.is_none() && !matches!(field.ty, TypeRef::Nullable(_)),
+        constraints: Vec::new(),
         span: field.span,
     }
 }
@@ -2063,6 +2519,7 @@ fn type_to_type_ref(ty: &Type) -> Option<TypeRef> {
             type_to_type_ref(ret)?,
         )),
         Type::Named(name) => Some(TypeRef::name(name.clone())),
+        Type::Element => Some(TypeRef::name("Element")),
         Type::Enum(enum_type) => Some(TypeRef::name(enum_type.name.clone())),
         Type::Union(union_type) => Some(TypeRef::name(union_type.name.clone())),
         Type::UnionCase(case_type) => {
@@ -2151,38 +2608,36 @@ fn format_library_roots(roots: &[PathBuf]) -> String {
 fn collect_library_dependencies(
     imports: &[Import],
     source_file: &Path,
+    modules: &dyn ModuleResolver,
     dependency_roots: &mut FxHashSet<PathBuf>,
 ) {
     for import in imports {
-        let Some(root) = normalize_supported_library_path(source_file, &import.library_path) else {
+        if let Some(module) = nx_stdlib::module_for_import(&import.library_path) {
+            dependency_roots.insert(std_library_root(module));
+            continue;
+        }
+        let Some(root) =
+            normalize_supported_library_path(source_file, &import.library_path, modules)
+        else {
             continue;
         };
         dependency_roots.insert(root);
     }
 }
 
-fn collect_nx_files(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        let file_type = entry.file_type()?;
-        if file_type.is_dir() {
-            collect_nx_files(&path, out)?;
-        } else if file_type.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("nx")
-        {
-            out.push(path);
-        }
-    }
-
-    Ok(())
-}
-
-fn normalize_supported_library_path(base_file: &Path, library_path: &str) -> Option<PathBuf> {
-    if is_http_library_path(library_path) || is_git_library_path(library_path) {
+fn normalize_supported_library_path(
+    base_file: &Path,
+    library_path: &str,
+    modules: &dyn ModuleResolver,
+) -> Option<PathBuf> {
+    if is_http_library_path(library_path)
+        || is_git_library_path(library_path)
+        || nx_stdlib::is_std_import(library_path)
+    {
         return None;
     }
 
-    normalize_local_library_path(base_file, library_path).ok()
+    normalize_local_library_path(base_file, library_path, modules).ok()
 }
 
 fn is_http_library_path(path: &str) -> bool {
@@ -2193,7 +2648,45 @@ fn is_git_library_path(path: &str) -> bool {
     path.starts_with("git://")
 }
 
-fn normalize_local_library_path(base_file: &Path, library_path: &str) -> io::Result<PathBuf> {
+/// Virtual root under which embedded standard library modules are registered.
+const STD_LIBRARY_ROOT: &str = "<std>";
+
+fn std_library_root(module: &StdModule) -> PathBuf {
+    Path::new(STD_LIBRARY_ROOT).join(module.name)
+}
+
+fn std_module_for_root(root: &Path) -> Option<&'static StdModule> {
+    let name = root.strip_prefix(STD_LIBRARY_ROOT).ok()?.to_str()?;
+    nx_stdlib::module(name)
+}
+
+fn is_unknown_std_import(library_path: &str) -> bool {
+    nx_stdlib::is_std_import(library_path) && nx_stdlib::module_for_import(library_path).is_none()
+}
+
+fn unknown_std_module_message(library_path: &str) -> String {
+    let requested = library_path.trim_start_matches(nx_stdlib::IMPORT_PREFIX);
+    let names = nx_stdlib::MODULES.iter().map(|module| module.name);
+    match suggest::closest_match(requested, names.clone()) {
+        Some(suggestion) => format!(
+            "Standard library module '{}' does not exist. Did you mean '{}{}'?",
+            library_path,
+            nx_stdlib::IMPORT_PREFIX,
+            suggestion
+        ),
+        None => format!(
+            "Standard library module '{}' does not exist. Available modules: {}",
+            library_path,
+            names.collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+fn normalize_local_library_path(
+    base_file: &Path,
+    library_path: &str,
+    modules: &dyn ModuleResolver,
+) -> io::Result<PathBuf> {
     let candidate = if Path::new(library_path).is_absolute() {
         PathBuf::from(library_path)
     } else {
@@ -2203,7 +2696,7 @@ fn normalize_local_library_path(base_file: &Path, library_path: &str) -> io::Res
             .join(library_path)
     };
 
-    fs::canonicalize(candidate)
+    modules.canonicalize(&candidate)
 }
 
 fn logical_source_identity(file_name: &str) -> String {
@@ -2241,6 +2734,40 @@ fn visible_name_for_selective(entry: &SelectiveImport) -> String {
     }
 }
 
+/// Reports a selectively imported name that collides with a declaration in the importing module.
+///
+/// Wildcard imports quietly yield to local declarations, but a name that was asked for explicitly
+/// would otherwise be dropped without notice.
+fn selective_import_conflicts_with_local(
+    module: &mut PreparedModule,
+    entry: &SelectiveImport,
+    visible_name: &str,
+    imported_visible_names: &FxHashMap<(PreparedNamespace, String), String>,
+) -> bool {
+    let visible_name_ref = Name::new(visible_name);
+    let conflicts = [
+        PreparedNamespace::Value,
+        PreparedNamespace::Type,
+        PreparedNamespace::Element,
+    ]
+    .into_iter()
+    .any(|namespace| {
+        module.has_binding(namespace, &visible_name_ref)
+            && !imported_visible_names.contains_key(&(namespace, visible_name.to_string()))
+    });
+    if conflicts {
+        module.add_diagnostic(LoweringDiagnostic {
+            code: None,
+            message: format!(
+                "Imported name '{}' conflicts with a declaration in this module. Use an alias to disambiguate.",
+                visible_name
+            ),
+            span: entry.span,
+        });
+    }
+    conflicts
+}
+
 fn line_col_for_span(source: &str, span: TextSpan) -> Option<(usize, usize)> {
     let start: usize = span.start().into();
     if start > source.len() {
@@ -2322,7 +2849,7 @@ fn normalize_diagnostics_file_name(
                 .cloned()
                 .map(|mut label| {
                     if label.file.is_empty() {
-                        label.file = file_name.to_string();
+                        label.file = normalize_path(file_name);
                     }
                     label
                 })
@@ -2346,7 +2873,16 @@ fn normalize_diagnostics_file_name(
                 builder = builder.with_note(note);
             }
 
-            builder.build()
+            let fixes = diagnostic.fixes().iter().cloned().map(|mut fix| {
+                for edit in &mut fix.edits {
+                    if edit.file.is_empty() {
+                        edit.file = normalize_path(file_name);
+                    }
+                }
+                fix
+            });
+
+            builder.with_fixes(fixes).build()
         })
         .collect()
 }
@@ -2364,6 +2900,7 @@ mod tests {
     use crate::source_graph::FilesystemSourceProvider;
     use crate::EvalResult;
     use crate::NxWorkspaceModule;
+    use std::fs;
     use tempfile::TempDir;
 
     fn workspace_module(identity: &str, source: impl Into<Vec<u8>>) -> NxWorkspaceModule {
@@ -2778,6 +3315,254 @@ let root() = { helper() }"#;
             .any(|diagnostic| { diagnostic.message().contains("does not export 'helper'") }));
     }
 
+    #[test]
+    fn build_context_for_source_resolves_relative_file_and_lib_root_imports() {
+        let temp = TempDir::new().expect("temp dir");
+        let app_dir = temp.path().join("app");
+        let lib_dir = temp.path().join("lib");
+        fs::create_dir_all(app_dir.join("widgets")).expect("widgets dir");
+        fs::create_dir_all(temp.path().join("math")).expect("math dir");
+        fs::create_dir_all(lib_dir.join("ui")).expect("ui dir");
+
+        fs::write(
+            temp.path().join("math").join("add.nx"),
+            r#"export let addOne(n:int) = { n + 1 }"#,
+        )
+        .expect("math library");
+        fs::write(
+            app_dir.join("widgets").join("button.nx"),
+            r#"export let double(n:int) = { n * 2 }"#,
+        )
+        .expect("button file");
+        fs::write(
+            lib_dir.join("ui").join("ten.nx"),
+            r#"export let ten() = { 10 }"#,
+        )
+        .expect("ui library");
+
+        let source = r#"import "../math" as Math
+import "./widgets/button.nx"
+import "ui"
+let root() = { Math.addOne(double(ten())) }"#;
+        let registry = LibraryRegistry::new();
+        let resolver = ImportPathResolver::new([&lib_dir]);
+        let build_context = registry
+            .build_context_for_source(source, &app_dir.join("main.nx"), &resolver)
+            .expect("Expected imports to resolve");
+
+        let artifact = build_program_artifact_from_source(
+            source,
+            &app_dir.join("main.nx").display().to_string(),
+            &build_context,
+        )
+        .expect("Expected program artifact");
+        assert!(
+            !has_error_diagnostics(&artifact.diagnostics),
+            "Expected resolved imports to bind, got {:?}",
+            artifact.diagnostics
+        );
+        let EvalResult::Ok(value) = eval_program_artifact(&artifact) else {
+            panic!("Expected program with resolved imports to evaluate");
+        };
+        assert_eq!(value, nx_value::NxValue::Int(21));
+    }
+
+    /// Modules kept in memory under absolute paths that exist on no disk.
+    struct MemoryModules(std::collections::BTreeMap<PathBuf, &'static str>);
+
+    impl MemoryModules {
+        fn normalize(path: &Path) -> PathBuf {
+            let mut normalized = PathBuf::new();
+            for component in path.components() {
+                match component {
+                    Component::CurDir => {}
+                    Component::ParentDir => {
+                        normalized.pop();
+                    }
+                    other => normalized.push(other),
+                }
+            }
+            normalized
+        }
+    }
+
+    impl ModuleResolver for MemoryModules {
+        fn resolve(&self, path: &Path) -> io::Result<Arc<str>> {
+            self.0
+                .get(&Self::normalize(path))
+                .map(|source| Arc::from(*source))
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+
+        fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+            let path = Self::normalize(path);
+            if self.0.contains_key(&path) || self.is_directory(&path) {
+                Ok(path)
+            } else {
+                Err(io::Error::from(io::ErrorKind::NotFound))
+            }
+        }
+
+        fn is_directory(&self, path: &Path) -> bool {
+            let path = Self::normalize(path);
+            self.0
+                .keys()
+                .any(|module| module != &path && module.starts_with(&path))
+        }
+
+        fn list_modules(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+            let dir = Self::normalize(dir);
+            Ok(self
+                .0
+                .keys()
+                .filter(|module| module.starts_with(&dir))
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[test]
+    fn module_resolver_supplies_imported_libraries_without_touching_disk() {
+        let modules = MemoryModules(
+            [
+                ("/templates/lib/ui/ten.nx", "export let ten() = { 10 }"),
+                (
+                    "/templates/shared/math.nx",
+                    "import \"../lib/ui\"\nexport let double(n:int) = { n * 2 + ten() }",
+                ),
+            ]
+            .into_iter()
+            .map(|(path, source)| (PathBuf::from(path), source))
+            .collect(),
+        );
+        let registry = LibraryRegistry::with_module_resolver(Arc::new(modules));
+        let main_path = Path::new("/templates/app/main.nx");
+        let source = r#"import "../shared"
+import "ui"
+let root() = { double(ten()) }"#;
+
+        let build_context = registry
+            .build_context_for_source(
+                source,
+                main_path,
+                &ImportPathResolver::new(["/templates/lib"]),
+            )
+            .expect("Expected imports to resolve in memory");
+        assert_eq!(
+            registry.loaded_roots(),
+            vec![
+                PathBuf::from("/templates/lib/ui"),
+                PathBuf::from("/templates/shared")
+            ]
+        );
+
+        let artifact = build_program_artifact_from_source(
+            source,
+            &main_path.display().to_string(),
+            &build_context,
+        )
+        .expect("Expected program artifact");
+        assert!(
+            !has_error_diagnostics(&artifact.diagnostics),
+            "Expected in-memory imports to bind, got {:?}",
+            artifact.diagnostics
+        );
+        let EvalResult::Ok(value) = eval_program_artifact(&artifact) else {
+            panic!("Expected program with in-memory imports to evaluate");
+        };
+        assert_eq!(value, nx_value::NxValue::Int(30));
+    }
+
+    #[test]
+    fn build_context_for_source_reports_missing_imports_with_searched_paths() {
+        let temp = TempDir::new().expect("temp dir");
+        let lib_dir = temp.path().join("lib");
+        let main_path = temp.path().join("main.nx");
+        let source = r#"import "charts"
+let root() = { 1 }"#;
+
+        let diagnostics = LibraryRegistry::new()
+            .build_context_for_source(source, &main_path, &ImportPathResolver::new([&lib_dir]))
+            .expect_err("Expected a missing import");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code(), Some("import-not-found"));
+        assert_eq!(
+            diagnostics[0].message(),
+            format!(
+                "Import 'charts' not found, searched: {}, {}",
+                temp.path().join("charts").display(),
+                lib_dir.join("charts").display()
+            )
+        );
+    }
+
+    #[test]
+    fn selective_import_conflicting_with_local_declaration_is_reported() {
+        let temp = TempDir::new().expect("temp dir");
+        let app_dir = temp.path().join("app");
+        let ui_dir = temp.path().join("ui");
+        fs::create_dir_all(&app_dir).expect("app dir");
+        fs::create_dir_all(&ui_dir).expect("ui dir");
+
+        fs::write(
+            ui_dir.join("public.nx"),
+            r#"export let answer(): int = { 42 }"#,
+        )
+        .expect("public file");
+
+        let registry = LibraryRegistry::new();
+        registry
+            .load_library_from_directory(&ui_dir)
+            .expect("Expected ui registry load");
+        let build_context = registry.build_context();
+
+        let main_path = app_dir.join("main.nx");
+        let source = r#"import { answer } from "../ui"
+let answer(): int = { 1 }
+let root() = { answer() }"#;
+        let artifact = build_program_artifact_from_source(
+            source,
+            &main_path.display().to_string(),
+            &build_context,
+        )
+        .expect("Expected program artifact with diagnostics");
+        assert!(artifact.diagnostics.iter().any(|diagnostic| {
+            diagnostic
+                .message()
+                .contains("Imported name 'answer' conflicts with a declaration in this module")
+        }));
+
+        let aliased_source = r#"import { answer as Ui.answer } from "../ui"
+let answer(): int = { 1 }
+let root() = { Ui.answer() + answer() }"#;
+        let aliased = build_program_artifact_from_source(
+            aliased_source,
+            &main_path.display().to_string(),
+            &build_context,
+        )
+        .expect("Expected aliased program artifact");
+        assert!(!has_error_diagnostics(&aliased.diagnostics));
+        let EvalResult::Ok(value) = eval_program_artifact(&aliased) else {
+            panic!("Expected aliased selective import to evaluate");
+        };
+        assert_eq!(value, nx_value::NxValue::Int(43));
+
+        let wildcard_source = r#"import "../ui"
+let answer(): int = { 1 }
+let root() = { answer() }"#;
+        let wildcard = build_program_artifact_from_source(
+            wildcard_source,
+            &main_path.display().to_string(),
+            &build_context,
+        )
+        .expect("Expected wildcard program artifact");
+        assert!(
+            !has_error_diagnostics(&wildcard.diagnostics),
+            "Wildcard imports should yield to local declarations"
+        );
+    }
+
     #[test]
     fn program_artifact_record_inheritance_resolves_imported_abstract_base() {
         let temp = TempDir::new().expect("temp dir");
@@ -2998,6 +3783,86 @@ let root() = { answer() }"#;
         assert_eq!(value, nx_value::NxValue::Int(42));
     }
 
+    #[test]
+    fn std_imports_resolve_without_loaded_libraries() {
+        let source = r#"import "std/math"
+import { repeat } from "std/strings"
+let root(): int = { gcd(12, 18) + length(repeat("ab", 3)) }"#;
+
+        let artifact =
+            build_program_artifact_from_source(source, "main.nx", &ProgramBuildContext::empty())
+                .expect("Expected program artifact");
+
+        assert!(
+            !has_error_diagnostics(&artifact.diagnostics),
+            "{:?}",
+            artifact.diagnostics
+        );
+        assert_eq!(
+            artifact
+                .libraries
+                .iter()
+                .map(|library| library.root_path.display().to_string())
+                .collect::<Vec<_>>(),
+            vec!["<std>/math", "<std>/strings"]
+        );
+        let EvalResult::Ok(value) = eval_program_artifact(&artifact) else {
+            panic!("Expected std import evaluation to succeed");
+        };
+        assert_eq!(value, nx_value::NxValue::Int(12));
+    }
+
+    #[test]
+    fn unknown_std_module_import_suggests_closest_module() {
+        let source = r#"import "std/maths"
+let root() = { 1 }"#;
+
+        let artifact =
+            build_program_artifact_from_source(source, "main.nx", &ProgramBuildContext::empty())
+                .expect("Expected program artifact");
+
+        assert!(artifact
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message()
+                == "Standard library module 'std/maths' does not exist. Did you mean 'std/math'?"));
+    }
+
+    #[test]
+    fn loaded_library_can_import_std_modules() {
+        let temp = TempDir::new().expect("temp dir");
+        let ui_dir = temp.path().join("ui");
+        fs::create_dir_all(&ui_dir).expect("ui dir");
+        fs::write(
+            ui_dir.join("calendar.nx"),
+            r#"import "std/dates"
+export let februaryDays(year:int): int = { daysInMonth(year, 2) }"#,
+        )
+        .expect("ui file");
+
+        let registry = LibraryRegistry::new();
+        let ui = registry
+            .load_library_from_directory(&ui_dir)
+            .expect("Expected registry load");
+        assert_eq!(ui.dependency_roots, vec![PathBuf::from("<std>/dates")]);
+        assert_eq!(registry.loaded_roots(), vec![ui.root_path.clone()]);
+
+        let main_path = temp.path().join("main.nx");
+        let source = r#"import "./ui"
+let root() = { februaryDays(2000) }"#;
+        let artifact = build_program_artifact_from_source(
+            source,
+            &main_path.display().to_string(),
+            &registry.build_context(),
+        )
+        .expect("Expected program artifact");
+
+        let EvalResult::Ok(value) = eval_program_artifact(&artifact) else {
+            panic!("Expected library std import evaluation to succeed");
+        };
+        assert_eq!(value, nx_value::NxValue::Int(29));
+    }
+
     #[test]
     fn imported_content_bindings_match_local_behavior() {
         let temp = TempDir::new().expect("temp dir");
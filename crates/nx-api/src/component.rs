@@ -289,6 +289,7 @@ pub(crate) fn invalid_input_diagnostics(message: impl ToString) -> Vec<NxDiagnos
         labels: Vec::new(),
         help: None,
         note: None,
+        fixes: Vec::new(),
     }]
 }
 
@@ -402,7 +403,7 @@ mod tests {
     fn assert_static_analysis_diagnostics(diagnostics: &[NxDiagnostic]) {
         assert!(diagnostics
             .iter()
-            .any(|diagnostic| diagnostic.code.as_deref() == Some("lowering-error")));
+            .any(|diagnostic| diagnostic.code.as_deref() == Some("record-base-not-abstract")));
         assert!(diagnostics
             .iter()
             .any(|diagnostic| diagnostic.code.as_deref() == Some("return-type-mismatch")));
@@ -447,6 +448,47 @@ mod tests {
         assert!(!result.state_snapshot.is_empty());
     }
 
+    #[test]
+    fn initialize_component_source_forwards_rest_props() {
+        let source = r#"
+            component <Button label:string ...rest:props /> = {
+              <button {...rest} class="btn">{label}</button>
+            }
+        "#;
+        let props = NxValue::Record {
+            type_name: None,
+            properties: BTreeMap::from([
+                ("label".to_string(), NxValue::String("Save".to_string())),
+                ("id".to_string(), NxValue::String("save".to_string())),
+                ("class".to_string(), NxValue::String("ignored".to_string())),
+            ]),
+        };
+
+        let result = initialize_component_source(
+            source,
+            "component-rest.nx",
+            &ProgramBuildContext::empty(),
+            "Button",
+            &props,
+        );
+        let ComponentInitEvalResult::Ok(result) = result else {
+            panic!("Expected rest props to be captured");
+        };
+
+        let NxValue::Record { properties, .. } = result.rendered else {
+            panic!("Expected rendered element record");
+        };
+        assert_eq!(
+            properties.get("id"),
+            Some(&NxValue::String("save".to_string()))
+        );
+        assert_eq!(
+            properties.get("class"),
+            Some(&NxValue::String("btn".to_string()))
+        );
+        assert!(!properties.contains_key("label"));
+    }
+
     #[test]
     fn evaluate_component_program_artifact_returns_rendered_output() {
         let source = r#"
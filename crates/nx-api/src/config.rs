@@ -0,0 +1,822 @@
+//! Project configuration loaded from `nx.toml`.
+//!
+//! Configuration files are discovered by walking up from a starting directory. Every `nx.toml`
+//! found on the way is merged, with files closer to the start taking precedence, until a file
+//! that sets `root = true` or the filesystem root is reached. Relative paths and patterns are
+//! resolved against the directory of the file that declares them.
+//!
+//! ```toml
+//! root = true
+//!
+//! [check]
+//! warnings-as-errors = false
+//! max-warnings = 10
+//! url-props = ["href", "src", "*Url"]
+//! event-props = ["on*"]
+//!
+//! [lints]
+//! unused-variable = "allow"
+//!
+//! [format]
+//! indent-width = 4
+//!
+//! [files]
+//! include = ["src/**/*.nx"]
+//! exclude = ["src/generated/**"]
+//!
+//! [imports]
+//! search-paths = ["lib", "../shared/lib"]
+//! ```
+
+use crate::ProgramArtifact;
+use nx_diagnostics::suggest::closest_match;
+use nx_diagnostics::{Diagnostic, Severity};
+use nx_types::{UnsafeInterpolationLint, UNSAFE_INTERPOLATION};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+
+/// File name of NX project configuration files.
+pub const CONFIG_FILE_NAME: &str = "nx.toml";
+
+const TABLES: &[&str] = &["check", "lints", "format", "files", "imports"];
+const ROOT_KEYS: &[&str] = &["root"];
+const CHECK_KEYS: &[&str] = &[
+    "warnings-as-errors",
+    "max-warnings",
+    "url-props",
+    "event-props",
+];
+const FORMAT_KEYS: &[&str] = &["indent-width"];
+const FILES_KEYS: &[&str] = &["include", "exclude"];
+const IMPORTS_KEYS: &[&str] = &["search-paths"];
+
+/// Project configuration shared by the CLI, embedding hosts, and editor tooling.
+///
+/// Every setting is optional so that configurations can be layered; unset values fall back to
+/// the tool's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NxConfig {
+    /// Configuration files that contributed to this configuration, outermost first.
+    pub sources: Vec<PathBuf>,
+    /// Settings from the `[check]` table.
+    pub check: NxCheckConfig,
+    /// Diagnostic levels from the `[lints]` table, keyed by diagnostic code.
+    pub lints: BTreeMap<String, NxLintLevel>,
+    /// Settings from the `[format]` table.
+    pub format: NxFormatConfig,
+    /// Settings from the `[files]` table.
+    pub files: NxFilesConfig,
+    /// Settings from the `[imports]` table.
+    pub imports: NxImportsConfig,
+}
+
+/// Strictness settings for static analysis.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NxCheckConfig {
+    /// Report every warning as an error.
+    pub warnings_as_errors: Option<bool>,
+    /// Fail a check when more than this many warnings are reported.
+    pub max_warnings: Option<usize>,
+    /// Property name patterns the `unsafe-interpolation` lint treats as URLs, replacing its
+    /// defaults.
+    pub url_props: Option<Vec<String>>,
+    /// Property name patterns the `unsafe-interpolation` lint treats as event handlers,
+    /// replacing its defaults.
+    pub event_props: Option<Vec<String>>,
+}
+
+/// Source formatting style.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NxFormatConfig {
+    /// Number of spaces per indentation level.
+    pub indent_width: Option<usize>,
+}
+
+/// Which source files belong to the project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NxFilesConfig {
+    /// Glob patterns of files to process, resolved against the declaring file's directory.
+    pub include: Vec<PathBuf>,
+    /// Glob patterns of files to skip, resolved against the declaring file's directory.
+    pub exclude: Vec<PathBuf>,
+}
+
+/// Where imports are resolved from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NxImportsConfig {
+    /// Lib roots searched for bare import paths, in resolution order (see
+    /// [`ImportPathResolver`](crate::ImportPathResolver)).
+    pub search_paths: Vec<PathBuf>,
+}
+
+/// The level at which diagnostics with a given code are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NxLintLevel {
+    /// Drop the diagnostic.
+    Allow,
+    /// Report the diagnostic as a warning.
+    Warn,
+    /// Report the diagnostic as an error.
+    Error,
+}
+
+/// An error reading or parsing a configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NxConfigError {
+    /// The configuration file.
+    pub path: PathBuf,
+    /// 1-based line of the error, if it is tied to one.
+    pub line: Option<usize>,
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for NxConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path.display(), line, self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+impl std::error::Error for NxConfigError {}
+
+impl NxConfig {
+    /// Discovers and merges the configuration files that apply to `start_dir`.
+    ///
+    /// Returns an empty configuration when no `nx.toml` is found.
+    pub fn discover(start_dir: impl AsRef<Path>) -> Result<Self, NxConfigError> {
+        let start_dir = start_dir.as_ref();
+        let start_dir = std::path::absolute(start_dir).map_err(|error| NxConfigError {
+            path: start_dir.to_path_buf(),
+            line: None,
+            message: format!("Failed to resolve directory: {}", error),
+        })?;
+
+        let mut found = Vec::new();
+        for dir in start_dir.ancestors() {
+            let path = dir.join(CONFIG_FILE_NAME);
+            if !path.is_file() {
+                continue;
+            }
+            let text = read_config(&path)?;
+            let (config, root) = parse_config(&text, &path)?;
+            found.push(config);
+            if root {
+                break;
+            }
+        }
+
+        let mut merged = NxConfig::default();
+        for config in found.into_iter().rev() {
+            merged.merge(config);
+        }
+        Ok(merged)
+    }
+
+    /// Loads a single configuration file without discovering its parents.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self, NxConfigError> {
+        let path = path.as_ref();
+        let text = read_config(path)?;
+        Self::parse_str(&text, path)
+    }
+
+    /// Parses configuration text. `path` is used in errors and to resolve relative paths.
+    pub fn parse_str(text: &str, path: impl AsRef<Path>) -> Result<Self, NxConfigError> {
+        parse_config(text, path.as_ref()).map(|(config, _)| config)
+    }
+
+    /// Layers `inner` over this configuration. Settings from `inner` win; lint levels are merged
+    /// per code, and `inner` search paths are tried before the existing ones.
+    pub fn merge(&mut self, inner: NxConfig) {
+        self.sources.extend(inner.sources);
+        self.check.warnings_as_errors = inner
+            .check
+            .warnings_as_errors
+            .or(self.check.warnings_as_errors);
+        self.check.max_warnings = inner.check.max_warnings.or(self.check.max_warnings);
+        self.check.url_props = inner.check.url_props.or(self.check.url_props.take());
+        self.check.event_props = inner.check.event_props.or(self.check.event_props.take());
+        self.lints.extend(inner.lints);
+        self.format.indent_width = inner.format.indent_width.or(self.format.indent_width);
+        if !inner.files.include.is_empty() {
+            self.files.include = inner.files.include;
+        }
+        if !inner.files.exclude.is_empty() {
+            self.files.exclude = inner.files.exclude;
+        }
+        let mut search_paths = inner.imports.search_paths;
+        search_paths.append(&mut self.imports.search_paths);
+        self.imports.search_paths = search_paths;
+    }
+
+    /// Returns the configured level for diagnostics with `code`, if any.
+    pub fn lint_level(&self, code: &str) -> Option<NxLintLevel> {
+        self.lints.get(code).copied()
+    }
+
+    /// Returns the `unsafe-interpolation` lint with the configured property patterns, or `None`
+    /// when `[check]` sets neither pattern list and analysis already ran the lint as configured.
+    pub fn unsafe_interpolation_lint(&self) -> Option<UnsafeInterpolationLint> {
+        if self.check.url_props.is_none() && self.check.event_props.is_none() {
+            return None;
+        }
+        let defaults = UnsafeInterpolationLint::default();
+        Some(UnsafeInterpolationLint {
+            url_props: self.check.url_props.clone().unwrap_or(defaults.url_props),
+            event_props: self
+                .check
+                .event_props
+                .clone()
+                .unwrap_or(defaults.event_props),
+        })
+    }
+
+    /// Returns the static diagnostics of `artifact`, with the `unsafe-interpolation` warnings of
+    /// its root modules recomputed for the configured property patterns.
+    pub fn program_diagnostics(&self, artifact: &ProgramArtifact) -> Vec<Diagnostic> {
+        let Some(lint) = self.unsafe_interpolation_lint() else {
+            return artifact.diagnostics.clone();
+        };
+        let is_root_file = |file: &str| {
+            artifact
+                .root_modules
+                .iter()
+                .any(|module| module.file_name == file)
+        };
+        let mut diagnostics = artifact
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| {
+                diagnostic.code() != Some(UNSAFE_INTERPOLATION)
+                    || !diagnostic
+                        .labels()
+                        .first()
+                        .is_some_and(|label| is_root_file(&label.file))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for module in &artifact.root_modules {
+            if let Some(lowered) = &module.lowered_module {
+                diagnostics.extend(lint.check(lowered, &module.type_env, &module.file_name));
+            }
+        }
+        diagnostics
+    }
+
+    /// Applies lint levels and `warnings-as-errors` to diagnostics.
+    pub fn apply_to_diagnostics(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let warnings_as_errors = self.check.warnings_as_errors.unwrap_or(false);
+        diagnostics
+            .into_iter()
+            .filter_map(|diagnostic| {
+                let diagnostic = match diagnostic.code().and_then(|code| self.lint_level(code)) {
+                    Some(NxLintLevel::Allow) => return None,
+                    Some(NxLintLevel::Warn) => diagnostic.with_severity(Severity::Warning),
+                    Some(NxLintLevel::Error) => diagnostic.with_severity(Severity::Error),
+                    None => diagnostic,
+                };
+                if warnings_as_errors && diagnostic.severity() == Severity::Warning {
+                    Some(diagnostic.with_severity(Severity::Error))
+                } else {
+                    Some(diagnostic)
+                }
+            })
+            .collect()
+    }
+}
+
+fn read_config(path: &Path) -> Result<String, NxConfigError> {
+    std::fs::read_to_string(path).map_err(|error| NxConfigError {
+        path: path.to_path_buf(),
+        line: None,
+        message: format!("Failed to read configuration: {}", error),
+    })
+}
+
+/// The entries of a TOML table in document order, each with the span of its key.
+struct Entries<V>(Vec<(Spanned<String>, V)>);
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for Entries<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor<V>(PhantomData<V>);
+
+        impl<'de, V: Deserialize<'de>> Visitor<'de> for EntriesVisitor<V> {
+            type Value = Entries<V>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a table")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                collect_entries(map).map(Entries)
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor(PhantomData))
+    }
+}
+
+fn collect_entries<'de, A: MapAccess<'de>, V: Deserialize<'de>>(
+    mut map: A,
+) -> Result<Vec<(Spanned<String>, V)>, A::Error> {
+    let mut entries = Vec::new();
+    while let Some(key) = map.next_key()? {
+        entries.push((key, map.next_value()?));
+    }
+    Ok(entries)
+}
+
+/// A top-level entry: either a table of settings or a setting such as `root`.
+enum RootItem {
+    Table(Vec<(Spanned<String>, toml::Value)>),
+    Value(toml::Value),
+}
+
+impl<'de> Deserialize<'de> for RootItem {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RootItemVisitor;
+
+        impl<'de> Visitor<'de> for RootItemVisitor {
+            type Value = RootItem;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a TOML value")
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(RootItem::Value(toml::Value::Boolean(v)))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(RootItem::Value(toml::Value::Integer(v)))
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(RootItem::Value(toml::Value::Float(v)))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(RootItem::Value(toml::Value::String(v.to_string())))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut items = Vec::new();
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(RootItem::Value(toml::Value::Array(items)))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                collect_entries(map).map(RootItem::Table)
+            }
+        }
+
+        deserializer.deserialize_any(RootItemVisitor)
+    }
+}
+
+fn type_name(value: &toml::Value) -> &'static str {
+    match value {
+        toml::Value::String(_) => "a string",
+        toml::Value::Integer(_) => "an integer",
+        toml::Value::Float(_) => "a float",
+        toml::Value::Boolean(_) => "a boolean",
+        toml::Value::Datetime(_) => "a date-time",
+        toml::Value::Array(_) => "an array",
+        toml::Value::Table(_) => "a table",
+    }
+}
+
+/// Parses a configuration file, returning the configuration and whether it sets `root = true`.
+fn parse_config(text: &str, path: &Path) -> Result<(NxConfig, bool), NxConfigError> {
+    let error = |offset: Option<usize>, message: String| NxConfigError {
+        path: path.to_path_buf(),
+        line: offset.map(|offset| text[..offset].matches('\n').count() + 1),
+        message,
+    };
+    let entries: Entries<RootItem> = toml::from_str(text).map_err(|parse_error| {
+        error(
+            parse_error.span().map(|span| span.start),
+            format!(
+                "Invalid TOML: {}",
+                parse_error.message().trim().replace('\n', "; ")
+            ),
+        )
+    })?;
+
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    let mut config = NxConfig {
+        sources: vec![path.to_path_buf()],
+        ..NxConfig::default()
+    };
+    let mut root = false;
+    for (name, item) in entries.0 {
+        match item {
+            RootItem::Table(settings) => {
+                if !TABLES.contains(&name.get_ref().as_str()) {
+                    return Err(error(
+                        Some(name.span().start),
+                        unknown_name_message("table", name.get_ref(), None, TABLES),
+                    ));
+                }
+                for (key, value) in settings {
+                    apply_setting(
+                        &mut config,
+                        &mut root,
+                        Some(name.get_ref()),
+                        key.get_ref(),
+                        value,
+                        base_dir,
+                    )
+                    .map_err(|message| error(Some(key.span().start), message))?;
+                }
+            }
+            RootItem::Value(value) => {
+                apply_setting(
+                    &mut config,
+                    &mut root,
+                    None,
+                    name.get_ref(),
+                    value,
+                    base_dir,
+                )
+                .map_err(|message| error(Some(name.span().start), message))?;
+            }
+        }
+    }
+
+    Ok((config, root))
+}
+
+fn apply_setting(
+    config: &mut NxConfig,
+    root: &mut bool,
+    table: Option<&str>,
+    key: &str,
+    value: toml::Value,
+    base_dir: &Path,
+) -> Result<(), String> {
+    match (table, key) {
+        (None, "root") => *root = expect_bool(key, value)?,
+        (Some("check"), "warnings-as-errors") => {
+            config.check.warnings_as_errors = Some(expect_bool(key, value)?);
+        }
+        (Some("check"), "max-warnings") => {
+            config.check.max_warnings = Some(expect_count(key, value, 0)?);
+        }
+        (Some("check"), "url-props") => {
+            config.check.url_props = Some(expect_strings(key, value)?);
+        }
+        (Some("check"), "event-props") => {
+            config.check.event_props = Some(expect_strings(key, value)?);
+        }
+        (Some("lints"), code) => {
+            let level = match expect_string(key, value)?.as_str() {
+                "allow" => NxLintLevel::Allow,
+                "warn" => NxLintLevel::Warn,
+                "error" => NxLintLevel::Error,
+                other => {
+                    return Err(format!(
+                        "Unknown lint level '{}' for `{}`; expected \"allow\", \"warn\", or \"error\"",
+                        other, code
+                    ));
+                }
+            };
+            config.lints.insert(code.to_string(), level);
+        }
+        (Some("format"), "indent-width") => {
+            config.format.indent_width = Some(expect_count(key, value, 1)?);
+        }
+        (Some("files"), "include") => {
+            config.files.include = expect_paths(key, value, base_dir)?;
+        }
+        (Some("files"), "exclude") => {
+            config.files.exclude = expect_paths(key, value, base_dir)?;
+        }
+        (Some("imports"), "search-paths") => {
+            config.imports.search_paths = expect_paths(key, value, base_dir)?;
+        }
+        (table, key) => {
+            let known_keys = match table {
+                None => ROOT_KEYS,
+                Some("check") => CHECK_KEYS,
+                Some("format") => FORMAT_KEYS,
+                Some("files") => FILES_KEYS,
+                Some("imports") => IMPORTS_KEYS,
+                Some(_) => &[],
+            };
+            return Err(unknown_name_message("key", key, table, known_keys));
+        }
+    }
+
+    Ok(())
+}
+
+fn unknown_name_message(kind: &str, name: &str, table: Option<&str>, known: &[&str]) -> String {
+    let location = table
+        .map(|table| format!(" in [{}]", table))
+        .unwrap_or_default();
+    let mut message = format!("Unknown {} `{}`{}", kind, name, location);
+    if let Some(suggestion) = closest_match(name, known.iter().copied()) {
+        message.push_str(&format!(". Did you mean `{}`?", suggestion));
+    }
+    message
+}
+
+fn expect_bool(key: &str, value: toml::Value) -> Result<bool, String> {
+    match value {
+        toml::Value::Boolean(value) => Ok(value),
+        other => Err(expected_message(key, "a boolean", &other)),
+    }
+}
+
+fn expect_string(key: &str, value: toml::Value) -> Result<String, String> {
+    match value {
+        toml::Value::String(value) => Ok(value),
+        other => Err(expected_message(key, "a string", &other)),
+    }
+}
+
+fn expect_count(key: &str, value: toml::Value, min: usize) -> Result<usize, String> {
+    match value {
+        toml::Value::Integer(value) => usize::try_from(value)
+            .ok()
+            .filter(|value| *value >= min)
+            .ok_or_else(|| format!("`{}` must be an integer of at least {}", key, min)),
+        other => Err(expected_message(key, "an integer", &other)),
+    }
+}
+
+fn expect_strings(key: &str, value: toml::Value) -> Result<Vec<String>, String> {
+    let toml::Value::Array(items) = value else {
+        return Err(expected_message(key, "an array of strings", &value));
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            toml::Value::String(item) => Ok(item),
+            other => Err(expected_message(key, "an array of strings", &other)),
+        })
+        .collect()
+}
+
+fn expect_paths(key: &str, value: toml::Value, base_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let toml::Value::Array(items) = value else {
+        return Err(expected_message(key, "an array of strings", &value));
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            toml::Value::String(path) => Ok(base_dir.join(path)),
+            other => Err(expected_message(key, "an array of strings", &other)),
+        })
+        .collect()
+}
+
+fn expected_message(key: &str, expected: &str, actual: &toml::Value) -> String {
+    format!(
+        "`{}` must be {}, found {}",
+        key,
+        expected,
+        type_name(actual)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_diagnostics::Label;
+    use std::fs;
+    use tempfile::TempDir;
+    use text_size::{TextRange, TextSize};
+
+    #[test]
+    fn parses_all_tables() {
+        let config = NxConfig::parse_str(
+            r#"
+            # Project settings
+            root = true
+
+            [check]
+            warnings-as-errors = true
+            max-warnings = 1_000
+            url-props = ["href", "*Url"]
+
+            [lints]
+            unused-variable = "allow"
+            "type-mismatch" = 'warn'
+
+            [format]
+            indent-width = 4 # spaces
+
+            [files]
+            include = [
+                "src/**/*.nx", # sources
+                "tests/*.nx",
+            ]
+            exclude = ["src/generated/#skip/**"]
+
+            [imports]
+            search-paths = ["../shared"]
+            "#,
+            "/project/nx.toml",
+        )
+        .unwrap();
+
+        assert_eq!(config.sources, vec![PathBuf::from("/project/nx.toml")]);
+        assert_eq!(config.check.warnings_as_errors, Some(true));
+        assert_eq!(config.check.max_warnings, Some(1000));
+        assert_eq!(
+            config.check.url_props,
+            Some(vec!["href".to_string(), "*Url".to_string()])
+        );
+        assert_eq!(config.check.event_props, None);
+        assert_eq!(
+            config.lint_level("unused-variable"),
+            Some(NxLintLevel::Allow)
+        );
+        assert_eq!(config.lint_level("type-mismatch"), Some(NxLintLevel::Warn));
+        assert_eq!(config.format.indent_width, Some(4));
+        assert_eq!(
+            config.files.include,
+            vec![
+                PathBuf::from("/project/src/**/*.nx"),
+                PathBuf::from("/project/tests/*.nx"),
+            ]
+        );
+        assert_eq!(
+            config.files.exclude,
+            vec![PathBuf::from("/project/src/generated/#skip/**")]
+        );
+        assert_eq!(
+            config.imports.search_paths,
+            vec![PathBuf::from("/project/../shared")]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_suggestions_and_line_numbers() {
+        let error = NxConfig::parse_str("[check]\nmax-warning = 3", "nx.toml").unwrap_err();
+
+        assert_eq!(error.line, Some(2));
+        assert_eq!(
+            error.to_string(),
+            "nx.toml:2: Unknown key `max-warning` in [check]. Did you mean `max-warnings`?"
+        );
+
+        let error = NxConfig::parse_str("[lint]", "nx.toml").unwrap_err();
+        assert!(error.message.contains("Did you mean `lints`?"));
+    }
+
+    #[test]
+    fn reports_invalid_values() {
+        let wrong_type = NxConfig::parse_str("[check]\nmax-warnings = \"3\"", "nx.toml");
+        assert!(wrong_type
+            .unwrap_err()
+            .message
+            .contains("must be an integer, found a string"));
+
+        let negative = NxConfig::parse_str("[check]\nmax-warnings = -1", "nx.toml");
+        assert!(negative.unwrap_err().message.contains("at least 0"));
+
+        let level = NxConfig::parse_str("[lints]\nfoo = \"loud\"", "nx.toml");
+        assert!(level.unwrap_err().message.contains("Unknown lint level"));
+
+        let unterminated =
+            NxConfig::parse_str("[files]\ninclude = [\"a\",", "nx.toml").unwrap_err();
+        assert_eq!(unterminated.line, Some(2));
+        assert_eq!(
+            unterminated.message,
+            "Invalid TOML: invalid array; expected `]`"
+        );
+    }
+
+    #[test]
+    fn discovery_merges_parent_configs_until_root() {
+        let temp = TempDir::new().unwrap();
+        let outer = temp.path().join("outer");
+        let project = outer.join("project");
+        let nested = project.join("src").join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            temp.path().join(CONFIG_FILE_NAME),
+            "[check]\nmax-warnings = 99",
+        )
+        .unwrap();
+        fs::write(
+            outer.join(CONFIG_FILE_NAME),
+            "root = true\n[check]\nmax-warnings = 5\nwarnings-as-errors = true\n[lints]\na = \"allow\"\nb = \"allow\"\n[imports]\nsearch-paths = [\"lib\"]",
+        )
+        .unwrap();
+        fs::write(
+            project.join(CONFIG_FILE_NAME),
+            "[check]\nmax-warnings = 0\n[lints]\nb = \"error\"\n[imports]\nsearch-paths = [\"vendor\"]",
+        )
+        .unwrap();
+
+        let config = NxConfig::discover(&nested).unwrap();
+
+        assert_eq!(
+            config.sources,
+            vec![outer.join(CONFIG_FILE_NAME), project.join(CONFIG_FILE_NAME)]
+        );
+        assert_eq!(config.check.max_warnings, Some(0));
+        assert_eq!(config.check.warnings_as_errors, Some(true));
+        assert_eq!(config.lint_level("a"), Some(NxLintLevel::Allow));
+        assert_eq!(config.lint_level("b"), Some(NxLintLevel::Error));
+        assert_eq!(
+            config.imports.search_paths,
+            vec![project.join("vendor"), outer.join("lib")]
+        );
+    }
+
+    #[test]
+    fn discovery_without_config_is_empty() {
+        let temp = TempDir::new().unwrap();
+
+        let config = NxConfig::discover(temp.path()).unwrap();
+
+        assert_eq!(config, NxConfig::default());
+    }
+
+    #[test]
+    fn lint_levels_and_warnings_as_errors_adjust_diagnostics() {
+        let range = TextRange::new(TextSize::from(0), TextSize::from(1));
+        let diagnostics = vec![
+            Diagnostic::error("noisy")
+                .with_label(Label::primary("a.nx", range))
+                .build(),
+            Diagnostic::error("demoted").build(),
+            Diagnostic::warning("promoted").build(),
+            Diagnostic::warning("plain-warning").build(),
+        ];
+        let config = NxConfig::parse_str(
+            "[lints]\nnoisy = \"allow\"\ndemoted = \"warn\"\npromoted = \"error\"",
+            "nx.toml",
+        )
+        .unwrap();
+
+        let adjusted = config.apply_to_diagnostics(diagnostics.clone());
+        let severities = adjusted
+            .iter()
+            .map(|diagnostic| (diagnostic.code().unwrap(), diagnostic.severity()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            severities,
+            vec![
+                ("demoted", Severity::Warning),
+                ("promoted", Severity::Error),
+                ("plain-warning", Severity::Warning),
+            ]
+        );
+
+        let mut strict = config;
+        strict.check.warnings_as_errors = Some(true);
+        let adjusted = strict.apply_to_diagnostics(diagnostics);
+        assert!(adjusted
+            .iter()
+            .all(|diagnostic| diagnostic.severity() == Severity::Error));
+    }
+
+    #[test]
+    fn configured_property_patterns_replace_the_unsafe_interpolation_defaults() {
+        let source = r#"let card(id:string) = <div>
+            <a href={"/users/" + id}>Profile</a>
+            <img photoUrl={"/photos/" + id} />
+        </div>"#;
+        let artifact = crate::build_program_artifact_from_source(
+            source,
+            "card.nx",
+            &crate::ProgramBuildContext::empty(),
+        )
+        .unwrap();
+        let flagged = |diagnostics: Vec<Diagnostic>| {
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.code() == Some(UNSAFE_INTERPOLATION))
+                .map(|diagnostic| diagnostic.message().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let defaults = NxConfig::default();
+        assert_eq!(defaults.unsafe_interpolation_lint(), None);
+        assert_eq!(
+            flagged(defaults.program_diagnostics(&artifact)),
+            vec!["The URL property 'href' is built from unsanitized text"]
+        );
+
+        let config = NxConfig::parse_str("[check]\nurl-props = [\"*Url\"]", "nx.toml").unwrap();
+        assert_eq!(
+            flagged(config.program_diagnostics(&artifact)),
+            vec!["The URL property 'photoUrl' is built from unsanitized text"]
+        );
+    }
+}
@@ -68,6 +68,26 @@ pub struct NxDiagnosticLabel {
     pub primary: bool,
 }
 
+/// A single text replacement that is part of an [`NxDiagnosticFix`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NxTextEdit {
+    /// The file to edit.
+    pub file: String,
+    /// The text span to replace. An empty span inserts [`replacement`](Self::replacement).
+    pub span: NxTextSpan,
+    /// The text to put in place of the span.
+    pub replacement: String,
+}
+
+/// A machine-applicable fix, such as an editor quick-fix, attached to a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NxDiagnosticFix {
+    /// Short description of what applying the fix does.
+    pub message: String,
+    /// Non-overlapping edits that make up the fix.
+    pub edits: Vec<NxTextEdit>,
+}
+
 /// A diagnostic message from the NX language runtime.
 ///
 /// This is a stable, serde-friendly representation suitable for serialization over FFI
@@ -89,6 +109,9 @@ pub struct NxDiagnostic {
     pub help: Option<String>,
     /// An optional note providing additional context.
     pub note: Option<String>,
+    /// Machine-applicable fixes. Empty when the issue has no automatic fix.
+    #[serde(default)]
+    pub fixes: Vec<NxDiagnosticFix>,
 }
 
 /// Converts internal [`Diagnostic`] values into the stable [`NxDiagnostic`] representation,
@@ -126,19 +149,7 @@ fn diagnostic_to_api(
 ) -> NxDiagnostic {
     let mut labels = Vec::with_capacity(diagnostic.labels().len());
     for label in diagnostic.labels() {
-        let label_source = sources
-            .and_then(|sources| sources.get(&label.file))
-            .map(|source| Cow::Borrowed(source.as_ref()))
-            .unwrap_or_else(|| {
-                if !label.file.is_empty() && Path::new(&label.file).is_file() {
-                    Cow::Owned(
-                        fs::read_to_string(&label.file)
-                            .unwrap_or_else(|_| fallback_source.to_string()),
-                    )
-                } else {
-                    Cow::Borrowed(fallback_source)
-                }
-            });
+        let label_source = source_for_file(&label.file, fallback_source, sources);
         let label_source = label_source.as_ref();
         let index = LineIndex::new(label_source);
         labels.push(NxDiagnosticLabel {
@@ -149,6 +160,28 @@ fn diagnostic_to_api(
         });
     }
 
+    let fixes = diagnostic
+        .fixes()
+        .iter()
+        .map(|fix| NxDiagnosticFix {
+            message: fix.message.clone(),
+            edits: fix
+                .edits
+                .iter()
+                .map(|edit| {
+                    let edit_source = source_for_file(&edit.file, fallback_source, sources);
+                    let edit_source = edit_source.as_ref();
+                    let index = LineIndex::new(edit_source);
+                    NxTextEdit {
+                        file: edit.file.clone(),
+                        span: text_range_to_span(edit.range, edit_source, &index),
+                        replacement: edit.replacement.clone(),
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
     NxDiagnostic {
         severity: diagnostic.severity().into(),
         code: diagnostic.code().map(ToString::to_string),
@@ -156,9 +189,27 @@ fn diagnostic_to_api(
         labels,
         help: diagnostic.help().map(ToString::to_string),
         note: diagnostic.note().map(ToString::to_string),
+        fixes,
     }
 }
 
+fn source_for_file<'a>(
+    file: &str,
+    fallback_source: &'a str,
+    sources: Option<&'a FxHashMap<String, Arc<str>>>,
+) -> Cow<'a, str> {
+    sources
+        .and_then(|sources| sources.get(file))
+        .map(|source| Cow::Borrowed(source.as_ref()))
+        .unwrap_or_else(|| {
+            if !file.is_empty() && Path::new(file).is_file() {
+                Cow::Owned(fs::read_to_string(file).unwrap_or_else(|_| fallback_source.to_string()))
+            } else {
+                Cow::Borrowed(fallback_source)
+            }
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,6 +241,30 @@ mod tests {
         assert_eq!(diagnostics[0].labels[0].span.end_line, 1);
         assert_eq!(diagnostics[0].labels[0].span.end_column, 15);
     }
+
+    #[test]
+    fn fixes_are_converted_with_line_and_column_positions() {
+        use nx_diagnostics::{Fix, TextEdit};
+
+        let source = "let a = 1\n<Swatch colour=\"red\" />";
+        let range = TextRange::new(TextSize::from(18), TextSize::from(24));
+        let diagnostic = Diagnostic::error("unknown-property")
+            .with_message("Element 'Swatch' has no property 'colour'")
+            .with_fix(Fix::new(
+                "Replace `colour` with `color`",
+                TextEdit::replace("swatch.nx", range, "color"),
+            ))
+            .build();
+
+        let diagnostics = diagnostics_to_api(&[diagnostic], source);
+
+        let fix = &diagnostics[0].fixes[0];
+        assert_eq!(fix.message, "Replace `colour` with `color`");
+        assert_eq!(fix.edits[0].replacement, "color");
+        assert_eq!(fix.edits[0].span.start_line, 2);
+        assert_eq!(fix.edits[0].span.start_column, 9);
+        assert_eq!(fix.edits[0].span.end_column, 15);
+    }
 }
 
 fn text_range_to_span(range: TextRange, source: &str, index: &LineIndex) -> NxTextSpan {
@@ -0,0 +1,198 @@
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Property holding the envelope version in a serialized [`NxEnvelope`].
+pub const ENVELOPE_VERSION_KEY: &str = "$nx";
+
+/// Newest envelope version this toolchain can write.
+///
+/// Version `0` means no envelope: the payload is serialized bare, as it was before envelopes
+/// existed. Version `1` wraps it as `{"$nx": 1, "value": ...}` or `{"$nx": 1, "diagnostics": ...}`
+/// and keeps the payload encoding unchanged. Later versions may change how records and elements
+/// are encoded inside the envelope; a consumer sees the version before it reads the payload.
+pub const LATEST_ENVELOPE_VERSION: u32 = 1;
+
+/// Picks the envelope version to use with a consumer that understands versions up to
+/// `consumer_version`.
+///
+/// Returns the newest version both sides understand, so a consumer built against an older
+/// toolchain keeps receiving the encoding it expects, and a newer consumer is capped at what this
+/// toolchain can write.
+pub fn negotiate_envelope_version(consumer_version: u32) -> u32 {
+    consumer_version.min(LATEST_ENVELOPE_VERSION)
+}
+
+/// Whether an envelope carries a successful result or the diagnostics of a failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NxEnvelopeKind {
+    /// The payload is a result, stored under `value`.
+    Value,
+    /// The payload is a list of diagnostics, stored under `diagnostics`.
+    Diagnostics,
+}
+
+impl NxEnvelopeKind {
+    /// Returns the property name the payload is stored under.
+    pub fn key(self) -> &'static str {
+        match self {
+            NxEnvelopeKind::Value => "value",
+            NxEnvelopeKind::Diagnostics => "diagnostics",
+        }
+    }
+}
+
+/// A serialized result or list of diagnostics tagged with its encoding version.
+///
+/// Serializes as a two-property map: [`ENVELOPE_VERSION_KEY`] with the version, then the payload
+/// under [`NxEnvelopeKind::key`]. The payload is usually borrowed, for example
+/// `NxEnvelope::value(1, &value)`, since envelopes are built just before serialization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NxEnvelope<T> {
+    /// Envelope version, at least `1`.
+    pub version: u32,
+    /// Whether the payload is a result or diagnostics.
+    pub kind: NxEnvelopeKind,
+    /// The wrapped payload.
+    pub payload: T,
+}
+
+impl<T> NxEnvelope<T> {
+    /// Wraps a successful result.
+    pub fn value(version: u32, payload: T) -> Self {
+        Self {
+            version,
+            kind: NxEnvelopeKind::Value,
+            payload,
+        }
+    }
+
+    /// Wraps the diagnostics of a failure.
+    pub fn diagnostics(version: u32, payload: T) -> Self {
+        Self {
+            version,
+            kind: NxEnvelopeKind::Diagnostics,
+            payload,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for NxEnvelope<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry(ENVELOPE_VERSION_KEY, &self.version)?;
+        map.serialize_entry(self.kind.key(), &self.payload)?;
+        map.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for NxEnvelope<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(EnvelopeVisitor(PhantomData))
+    }
+}
+
+struct EnvelopeVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for EnvelopeVisitor<T> {
+    type Value = NxEnvelope<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "a map with \"{}\" and either \"value\" or \"diagnostics\"",
+            ENVELOPE_VERSION_KEY
+        )
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut version = None;
+        let mut body = None;
+        while let Some(key) = map.next_key::<String>()? {
+            let kind = match key.as_str() {
+                ENVELOPE_VERSION_KEY => {
+                    version = Some(map.next_value::<u32>()?);
+                    continue;
+                }
+                "value" => NxEnvelopeKind::Value,
+                "diagnostics" => NxEnvelopeKind::Diagnostics,
+                _ => {
+                    map.next_value::<de::IgnoredAny>()?;
+                    continue;
+                }
+            };
+            if body.is_some() {
+                return Err(de::Error::custom(
+                    "envelope has both \"value\" and \"diagnostics\"",
+                ));
+            }
+            body = Some((kind, map.next_value::<T>()?));
+        }
+
+        let version = version.ok_or_else(|| de::Error::missing_field(ENVELOPE_VERSION_KEY))?;
+        if version == 0 || version > LATEST_ENVELOPE_VERSION {
+            return Err(de::Error::custom(format!(
+                "unsupported envelope version {}; this toolchain reads versions 1 to {}",
+                version, LATEST_ENVELOPE_VERSION
+            )));
+        }
+        let (kind, payload) = body.ok_or_else(|| de::Error::missing_field("value"))?;
+        Ok(NxEnvelope {
+            version,
+            kind,
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NxDiagnostic;
+    use nx_value::NxValue;
+
+    #[test]
+    fn negotiation_picks_the_newest_shared_version() {
+        assert_eq!(negotiate_envelope_version(0), 0);
+        assert_eq!(negotiate_envelope_version(1), 1);
+        assert_eq!(
+            negotiate_envelope_version(u32::MAX),
+            LATEST_ENVELOPE_VERSION
+        );
+    }
+
+    #[test]
+    fn envelopes_round_trip_through_json_and_messagepack() {
+        let value = NxValue::Array(vec![NxValue::Int(1), NxValue::Null]);
+        let json = serde_json::to_string(&NxEnvelope::value(1, &value)).unwrap();
+        assert_eq!(json, r#"{"$nx":1,"value":[1,null]}"#);
+        let decoded: NxEnvelope<NxValue> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, NxEnvelope::value(1, value.clone()));
+
+        let bytes = rmp_serde::to_vec_named(&NxEnvelope::value(1, &value)).unwrap();
+        let decoded: NxEnvelope<NxValue> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.payload, value);
+
+        let json =
+            serde_json::to_string(&NxEnvelope::diagnostics(1, Vec::<NxDiagnostic>::new())).unwrap();
+        assert_eq!(json, r#"{"$nx":1,"diagnostics":[]}"#);
+    }
+
+    #[test]
+    fn decoding_rejects_unknown_versions_and_missing_payloads() {
+        for json in [
+            r#"{"$nx":2,"value":1}"#,
+            r#"{"$nx":0,"value":1}"#,
+            r#"{"value":1}"#,
+            r#"{"$nx":1}"#,
+            r#"{"$nx":1,"value":1,"diagnostics":[]}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<NxEnvelope<NxValue>>(json).is_err(),
+                "{json}"
+            );
+        }
+    }
+}
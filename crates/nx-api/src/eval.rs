@@ -7,10 +7,18 @@ use crate::value::to_nx_value;
 use crate::NxDiagnostic;
 use nx_diagnostics::{Diagnostic, Label, Severity};
 use nx_hir::Item;
-use nx_interpreter::{Interpreter, RuntimeError};
+pub use nx_interpreter::{Backend, LogLevel, MessageCatalog, NullConcatenation};
+use nx_interpreter::{
+    Interpreter, LogRecord, RandomSource, ResourceLimits, RuntimeError, RuntimeErrorKind,
+    RuntimeModuleId, StreamEvent, Value,
+};
+use nx_types::{time_stage, Stage};
 use nx_value::NxValue;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 use text_size::{TextRange, TextSize};
 
 /// The result of evaluating NX source code.
@@ -25,8 +33,127 @@ pub enum EvalResult {
     Err(Vec<NxDiagnostic>),
 }
 
+/// An event reported while streaming the evaluation of a program.
+///
+/// An intrinsic root element, such as `<html>...</html>`, produces one `Open`, one `Child` per
+/// top-level child in source order, and then `Close`. Any other root result is reported as a
+/// single `Child` without `Open` or `Close`.
+///
+/// Events are final once sent. When evaluation fails after `Open`, the children already reported
+/// stay reported, `Close` is never sent, and the evaluation returns [`EvalResult::Err`]. A host
+/// that has written output for an `Open` without a matching `Close` should treat its output as
+/// truncated, for example by appending an error marker.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NxStreamEvent {
+    /// The root element's tag and properties, sent before any of its children.
+    Open {
+        tag: String,
+        properties: BTreeMap<String, NxValue>,
+    },
+    /// A completed top-level child, or the whole result when the root is not streamed.
+    Child(NxValue),
+    /// The root element and all of its children are complete.
+    Close,
+}
+
+/// One `log(level, message, value?)` call, as received by an [`NxLogSink`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NxLogRecord {
+    pub level: LogLevel,
+    pub message: String,
+    /// The optional third argument of the call.
+    pub value: Option<NxValue>,
+}
+
+/// Receives the `log` calls of an evaluation
+///
+/// The NX `log` builtin never writes to stdout; its records go to the sink in
+/// [`EvalOptions::log_sink`], or nowhere when there is none. A sink may be called from several
+/// threads when one program is evaluated concurrently. Sinks compare equal only to clones of
+/// themselves.
+#[derive(Clone)]
+pub struct NxLogSink(Arc<dyn Fn(NxLogRecord) + Send + Sync>);
+
+impl NxLogSink {
+    /// Creates a sink that passes every record to `receive`.
+    pub fn new(receive: impl Fn(NxLogRecord) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(receive))
+    }
+
+    /// Passes `record` to the sink.
+    pub fn log(&self, record: NxLogRecord) {
+        (self.0)(record)
+    }
+}
+
+impl fmt::Debug for NxLogSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NxLogSink(..)")
+    }
+}
+
+impl PartialEq for NxLogSink {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for NxLogSink {}
+
+/// Runtime options for evaluating a program.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvalOptions {
+    /// Seed for the `random`, `randomInt` and `uuid` builtins. `None` seeds from system entropy.
+    pub random_seed: Option<u64>,
+    /// Forbids entropy-seeded randomness, for sandboxed or reproducible evaluation. Random
+    /// builtins then fail with a runtime error unless `random_seed` is set.
+    pub deterministic: bool,
+    /// How `+` treats a null operand next to a string. Defaults to a runtime error.
+    pub null_concatenation: NullConcatenation,
+    /// Receives `log` calls at [`log_level`](Self::log_level) or above. `None` discards them.
+    pub log_sink: Option<NxLogSink>,
+    /// Least severe level passed to `log_sink`. Defaults to [`LogLevel::Info`].
+    pub log_level: LogLevel,
+    /// Top-level function to run instead of `root`. It must take no parameters; see
+    /// [`runnable_entries`] for the candidates in a program.
+    pub entry: Option<String>,
+    /// Translations looked up by the `t` builtin. `None` shows every message untranslated.
+    pub translations: Option<Arc<MessageCatalog>>,
+    /// How function bodies are executed. Defaults to [`Backend::TreeWalker`].
+    pub backend: Backend,
+}
+
+impl EvalOptions {
+    /// Returns options that seed random builtins with `seed`.
+    pub fn with_random_seed(seed: u64) -> Self {
+        Self {
+            random_seed: Some(seed),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the name of the function these options run: [`entry`](Self::entry), or `root`.
+    pub fn entry_name(&self) -> &str {
+        self.entry.as_deref().unwrap_or("root")
+    }
+
+    /// Returns the interpreter random source selected by these options.
+    pub fn random_source(&self) -> RandomSource {
+        match (self.random_seed, self.deterministic) {
+            (Some(seed), _) => RandomSource::Seeded(seed),
+            (None, true) => RandomSource::Disabled,
+            (None, false) => RandomSource::Entropy,
+        }
+    }
+}
+
 pub(crate) fn runtime_error_diagnostics(source: &str, error: RuntimeError) -> Vec<NxDiagnostic> {
-    let diag = Diagnostic::error("runtime-error")
+    let code = match error.kind() {
+        RuntimeErrorKind::Assertion { .. } => "assertion-failed",
+        RuntimeErrorKind::DeadlineExceeded => "eval-timeout",
+        _ => "runtime-error",
+    };
+    let diag = Diagnostic::error(code)
         .with_message(error.to_string())
         .build();
     diagnostics_to_api(&[diag], source)
@@ -122,18 +249,151 @@ fn full_source_span(source: &str) -> TextRange {
     TextRange::new(TextSize::from(0), TextSize::from(source_len))
 }
 
-fn no_root_diagnostics(file_name: &str, source: &str) -> Vec<NxDiagnostic> {
-    let diag = Diagnostic::error("no-root")
+fn no_root_diagnostics(file_name: &str, source: &str, runnable: &[String]) -> Vec<NxDiagnostic> {
+    let mut builder = Diagnostic::error("no-root")
         .with_message("No root element found in source")
         .with_label(Label::primary(file_name, full_source_span(source)))
-        .with_help("Add a top-level element to create an implicit root function.")
-        .build();
-    diagnostics_to_api(&[diag], source)
+        .with_help("Add a top-level element to create an implicit root function.");
+    if !runnable.is_empty() {
+        builder = builder.with_note(format!(
+            "Runnable entries: {}; select one as the entry to run it instead",
+            runnable.join(", ")
+        ));
+    }
+    diagnostics_to_api(&[builder.build()], source)
+}
+
+/// Reports that the requested entry is missing or needs arguments, listing the entries that can
+/// be run instead.
+fn unknown_entry_diagnostics(
+    file_name: &str,
+    source: &str,
+    entry: &str,
+    takes_parameters: bool,
+    runnable: &[String],
+) -> Vec<NxDiagnostic> {
+    let message = if takes_parameters {
+        format!("Entry '{entry}' cannot be run because it takes parameters")
+    } else {
+        format!("No top-level function named '{entry}' to run")
+    };
+    let builder = Diagnostic::error("unknown-entry")
+        .with_message(message)
+        .with_label(Label::primary(file_name, full_source_span(source)));
+    let builder = if runnable.is_empty() {
+        builder.with_help("Declare a top-level function without parameters to run it as an entry.")
+    } else {
+        builder.with_note(format!("Runnable entries: {}", runnable.join(", ")))
+    };
+    diagnostics_to_api(&[builder.build()], source)
+}
+
+/// Returns the names of the top-level functions in `module` that can be run as entries, which are
+/// those without parameters, in declaration order.
+fn module_runnable_entries(module: &nx_hir::LoweredModule) -> Vec<String> {
+    module
+        .items()
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(function) if function.params.is_empty() => {
+                Some(function.name.to_string())
+            }
+            _ => None,
+        })
+        .collect()
 }
 
-fn eval_program_artifact_with_source(program: &ProgramArtifact, source: &str) -> EvalResult {
+/// Returns the names of the functions in a program's entry module that can be run without
+/// arguments, in declaration order.
+///
+/// Any of these can be selected with [`EvalOptions::entry`]. Programs without an entry module
+/// have none.
+pub fn runnable_entries(program: &ProgramArtifact) -> Vec<String> {
+    program
+        .entry_module_id
+        .and_then(|module_id| program.resolved_program.module(module_id))
+        .map(|module| module_runnable_entries(&module.lowered_module))
+        .unwrap_or_default()
+}
+
+fn eval_program_artifact_with_source(
+    program: &ProgramArtifact,
+    source: &str,
+    options: &EvalOptions,
+) -> EvalResult {
+    eval_program_entry(
+        program,
+        source,
+        options,
+        Vec::new(),
+        ResourceLimits::default(),
+    )
+}
+
+/// Evaluates the entrypoint selected by `options` with `args` under `limits`.
+///
+/// An entry that takes parameters is only accepted when `args` is not empty; the interpreter then
+/// checks the arguments against them.
+pub(crate) fn eval_program_entry(
+    program: &ProgramArtifact,
+    source: &str,
+    options: &EvalOptions,
+    args: Vec<Value>,
+    limits: ResourceLimits,
+) -> EvalResult {
+    let entry_module_id =
+        match entry_module(program, source, options.entry.as_deref(), !args.is_empty()) {
+            Ok(entry_module_id) => entry_module_id,
+            Err(diagnostics) => return EvalResult::Err(diagnostics),
+        };
+    let result = time_stage(Stage::Eval, || {
+        program_interpreter(program, options).execute_resolved_program_module_function_with_limits(
+            entry_module_id,
+            options.entry_name(),
+            args,
+            limits,
+        )
+    });
+    match result {
+        Ok(value) => EvalResult::Ok(to_nx_value(&value)),
+        Err(error) => EvalResult::Err(runtime_error_diagnostics(source, error)),
+    }
+}
+
+fn program_interpreter(program: &ProgramArtifact, options: &EvalOptions) -> Interpreter {
+    let interpreter = Interpreter::from_resolved_program(program.resolved_program.clone())
+        .with_random_source(options.random_source())
+        .with_null_concatenation(options.null_concatenation)
+        .with_backend(options.backend);
+    let interpreter = match options.translations.clone() {
+        Some(catalog) => interpreter.with_translations(catalog),
+        None => interpreter,
+    };
+    match options.log_sink.clone() {
+        Some(sink) => interpreter.with_log_sink(
+            Arc::new(move |record: LogRecord| {
+                sink.log(NxLogRecord {
+                    level: record.level,
+                    message: record.message,
+                    value: record.value.as_ref().map(to_nx_value),
+                })
+            }),
+            options.log_level,
+        ),
+        None => interpreter,
+    }
+}
+
+/// Returns the module declaring the program's entrypoint: `entry`, or `root()` when `entry` is
+/// `None`. `with_args` also accepts an `entry` that takes parameters.
+fn entry_module(
+    program: &ProgramArtifact,
+    source: &str,
+    entry: Option<&str>,
+    with_args: bool,
+) -> Result<RuntimeModuleId, Vec<NxDiagnostic>> {
     if let Some(diagnostics) = program_artifact_error_diagnostics(program, source) {
-        return EvalResult::Err(diagnostics);
+        return Err(diagnostics);
     }
 
     let Some(root_module) = program
@@ -141,40 +401,111 @@ fn eval_program_artifact_with_source(program: &ProgramArtifact, source: &str) ->
         .iter()
         .find(|module| module.file_name == program.entry_identity)
     else {
-        return EvalResult::Err(no_root_diagnostics("input.nx", source));
+        return Err(no_root_diagnostics("input.nx", source, &[]));
     };
     let Some(entry_module_id) = program.entry_module_id else {
-        return EvalResult::Err(no_root_diagnostics(&root_module.file_name, source));
+        return Err(no_root_diagnostics(&root_module.file_name, source, &[]));
     };
     let Some(module) = program
         .resolved_program
         .module(entry_module_id)
         .map(|module| module.lowered_module.as_ref())
     else {
-        return EvalResult::Err(no_root_diagnostics(&root_module.file_name, source));
+        return Err(no_root_diagnostics(&root_module.file_name, source, &[]));
     };
 
-    let has_root = module
+    let Some(entry) = entry else {
+        let has_root = module
+            .items()
+            .iter()
+            .any(|item| matches!(item, Item::Function(f) if f.name.as_str() == "root"));
+        if !has_root {
+            return Err(no_root_diagnostics(
+                &root_module.file_name,
+                source,
+                &module_runnable_entries(module),
+            ));
+        }
+        return Ok(entry_module_id);
+    };
+    let runnable = module_runnable_entries(module);
+    if runnable.iter().any(|name| name == entry) {
+        return Ok(entry_module_id);
+    }
+    let takes_parameters = module
         .items()
         .iter()
-        .any(|item| matches!(item, Item::Function(f) if f.name.as_str() == "root"));
-    if !has_root {
-        return EvalResult::Err(no_root_diagnostics(&root_module.file_name, source));
-    }
-
-    let interpreter = Interpreter::from_resolved_program(program.resolved_program.clone());
-    match interpreter.execute_resolved_program_module_function(entry_module_id, "root", vec![]) {
-        Ok(value) => EvalResult::Ok(to_nx_value(&value)),
-        Err(error) => EvalResult::Err(runtime_error_diagnostics(source, error)),
+        .any(|item| matches!(item, Item::Function(f) if f.name.as_str() == entry));
+    if takes_parameters && with_args {
+        return Ok(entry_module_id);
     }
+    Err(unknown_entry_diagnostics(
+        &root_module.file_name,
+        source,
+        entry,
+        takes_parameters,
+        &runnable,
+    ))
 }
 
 /// Evaluates the `root()` entrypoint of a previously built [`ProgramArtifact`].
 ///
 /// The supplied program artifact should already be free of static-analysis errors.
 pub fn eval_program_artifact(program: &ProgramArtifact) -> EvalResult {
+    eval_program_artifact_with_options(program, &EvalOptions::default())
+}
+
+/// Evaluates the entrypoint of a previously built [`ProgramArtifact`] with explicit runtime
+/// options: `root()`, or the function named by [`EvalOptions::entry`].
+pub fn eval_program_artifact_with_options(
+    program: &ProgramArtifact,
+    options: &EvalOptions,
+) -> EvalResult {
     let source = program_root_source(program);
-    eval_program_artifact_with_source(program, &source)
+    eval_program_artifact_with_source(program, &source, options)
+}
+
+/// Evaluates the entrypoint selected by `options` of a [`ProgramArtifact`], reporting the children
+/// of the root element to `sink` as they are produced.
+///
+/// This lets a host start writing output, such as server-rendered HTML, before the whole tree has
+/// been evaluated. See [`NxStreamEvent`] for the order of events and for what happens when
+/// evaluation fails after output was sent. On success the complete value is returned as well, the
+/// same as [`eval_program_artifact_with_options`].
+pub fn eval_program_artifact_streaming(
+    program: &ProgramArtifact,
+    options: &EvalOptions,
+    sink: &mut dyn FnMut(NxStreamEvent),
+) -> EvalResult {
+    let source = program_root_source(program);
+    let entry_module_id = match entry_module(program, &source, options.entry.as_deref(), false) {
+        Ok(entry_module_id) => entry_module_id,
+        Err(diagnostics) => return EvalResult::Err(diagnostics),
+    };
+    let result = time_stage(Stage::Eval, || {
+        program_interpreter(program, options).stream_resolved_program_module_function(
+            entry_module_id,
+            options.entry_name(),
+            vec![],
+            &mut |event| {
+                sink(match event {
+                    StreamEvent::Open { tag, properties } => NxStreamEvent::Open {
+                        tag: tag.to_string(),
+                        properties: properties
+                            .iter()
+                            .map(|(name, value)| (name.to_string(), to_nx_value(value)))
+                            .collect(),
+                    },
+                    StreamEvent::Child(value) => NxStreamEvent::Child(to_nx_value(value)),
+                    StreamEvent::Close => NxStreamEvent::Close,
+                })
+            },
+        )
+    });
+    match result {
+        Ok(value) => EvalResult::Ok(to_nx_value(&value)),
+        Err(error) => EvalResult::Err(runtime_error_diagnostics(&source, error)),
+    }
 }
 
 /// Builds a reusable [`ProgramArtifact`] from source text and returns public diagnostics if static
@@ -200,19 +531,30 @@ pub fn load_program_artifact_from_source(
 ///
 /// Returns [`EvalResult::Err`] with diagnostics when:
 /// - Static analysis reports errors
-/// - No `root()` function is defined
+/// - No `root()` function is defined, or the entry selected by [`EvalOptions::entry`] is missing
+///   or takes parameters
 /// - A runtime error occurs during evaluation
 pub fn eval_source(
     source: &str,
     file_name: &str,
     build_context: &ProgramBuildContext,
+) -> EvalResult {
+    eval_source_with_options(source, file_name, build_context, &EvalOptions::default())
+}
+
+/// Like [`eval_source`], with explicit runtime options.
+pub fn eval_source_with_options(
+    source: &str,
+    file_name: &str,
+    build_context: &ProgramBuildContext,
+    options: &EvalOptions,
 ) -> EvalResult {
     let program = match load_program_artifact_from_source(source, file_name, build_context) {
         Ok(program) => program,
         Err(diagnostics) => return EvalResult::Err(diagnostics),
     };
 
-    eval_program_artifact_with_source(&program, source)
+    eval_program_artifact_with_source(&program, source, options)
 }
 
 #[cfg(test)]
@@ -221,8 +563,85 @@ mod tests {
     use crate::artifacts::{build_program_artifact_from_source, LibraryRegistry};
     use std::collections::BTreeMap;
     use std::fs;
+    use std::sync::Arc;
+    use std::thread;
     use tempfile::TempDir;
 
+    #[test]
+    fn eval_source_reports_record_default_cycles_with_their_code() {
+        let source = r#"
+            type Loop = {
+              x: int = { y }
+              y: int = { x }
+            }
+
+            let root(): int = { 1 }
+        "#;
+
+        let EvalResult::Err(diagnostics) = eval_source(
+            source,
+            "eval-default-cycle.nx",
+            &ProgramBuildContext::empty(),
+        ) else {
+            panic!("Expected evaluation to stop on the default cycle");
+        };
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("record-default-cycle")]
+        );
+    }
+
+    #[test]
+    fn eval_source_rejects_function_names_passed_as_values_before_running() {
+        let eval = |callback: &str| {
+            let source = format!(
+                r#"let double(n:int): int = {{ n * 2 }}
+let shout(text:string): string = {{ upper(text) }}
+let root() = {{ {} }}"#,
+                callback
+            );
+            eval_source(&source, "callbacks.nx", &ProgramBuildContext::empty())
+        };
+
+        for (callback, message) in [
+            (
+                "map([1, 2], double)",
+                "Function 'double' cannot be used as a value",
+            ),
+            (
+                "map([\"a\"], upper)",
+                "Builtin 'upper' cannot be used as a value",
+            ),
+        ] {
+            let EvalResult::Err(diagnostics) = eval(callback) else {
+                panic!("Expected `{callback}` to be rejected");
+            };
+            assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+            assert_eq!(diagnostics[0].code.as_deref(), Some("function-as-value"));
+            assert_eq!(diagnostics[0].message, message);
+        }
+
+        for (callback, expected) in [
+            (
+                "map([1, 2], double(_))",
+                NxValue::Array(vec![NxValue::Int(2), NxValue::Int(4)]),
+            ),
+            (
+                "map([\"a\"], shout(_))",
+                NxValue::Array(vec![NxValue::String("A".to_string())]),
+            ),
+        ] {
+            match eval(callback) {
+                EvalResult::Ok(value) => assert_eq!(value, expected, "{callback}"),
+                EvalResult::Err(diagnostics) => panic!("`{callback}` failed: {diagnostics:?}"),
+            }
+        }
+    }
+
     #[test]
     fn eval_source_returns_aggregated_static_diagnostics_before_runtime_execution() {
         let source = r#"
@@ -252,7 +671,7 @@ mod tests {
 
         assert!(diagnostics
             .iter()
-            .any(|diagnostic| diagnostic.code.as_deref() == Some("lowering-error")));
+            .any(|diagnostic| diagnostic.code.as_deref() == Some("record-base-not-abstract")));
         assert!(diagnostics
             .iter()
             .any(|diagnostic| diagnostic.code.as_deref() == Some("return-type-mismatch")));
@@ -261,6 +680,65 @@ mod tests {
             .any(|diagnostic| diagnostic.code.as_deref() == Some("runtime-error")));
     }
 
+    #[test]
+    fn eval_source_runs_the_selected_entry_and_lists_candidates_when_it_is_missing() {
+        let source = r#"
+            let summary() = { 40 + 2 }
+            let greeting() = { "hi" }
+            let double(n:int) = { n * 2 }
+        "#;
+        let entry = |name: &str| EvalOptions {
+            entry: Some(name.to_string()),
+            ..EvalOptions::default()
+        };
+        let context = ProgramBuildContext::empty();
+
+        let EvalResult::Ok(value) =
+            eval_source_with_options(source, "entries.nx", &context, &entry("summary"))
+        else {
+            panic!("Expected the selected entry to run");
+        };
+        assert_eq!(value, NxValue::Int(42));
+
+        let program = load_program_artifact_from_source(source, "entries.nx", &context)
+            .expect("program builds");
+        assert_eq!(runnable_entries(&program), vec!["summary", "greeting"]);
+
+        let EvalResult::Err(missing) =
+            eval_source_with_options(source, "entries.nx", &context, &entry("report"))
+        else {
+            panic!("Expected a missing entry to be reported");
+        };
+        assert_eq!(missing[0].code.as_deref(), Some("unknown-entry"));
+        assert_eq!(
+            missing[0].message,
+            "No top-level function named 'report' to run"
+        );
+        assert_eq!(
+            missing[0].note.as_deref(),
+            Some("Runnable entries: summary, greeting")
+        );
+
+        let EvalResult::Err(needs_args) =
+            eval_source_with_options(source, "entries.nx", &context, &entry("double"))
+        else {
+            panic!("Expected an entry with parameters to be rejected");
+        };
+        assert_eq!(
+            needs_args[0].message,
+            "Entry 'double' cannot be run because it takes parameters"
+        );
+
+        let EvalResult::Err(no_root) = eval_source(source, "entries.nx", &context) else {
+            panic!("Expected a missing root to be reported");
+        };
+        assert_eq!(no_root[0].code.as_deref(), Some("no-root"));
+        assert!(no_root[0]
+            .note
+            .as_deref()
+            .is_some_and(|note| note.starts_with("Runnable entries: summary, greeting")));
+    }
+
     #[test]
     fn eval_source_reports_missing_import_when_source_is_not_on_disk() {
         let source = r#"import { Button as Layout.Button } from "../ui"
@@ -622,8 +1100,8 @@ let root() = { answer() }"#;
             value.to_json_string().unwrap(),
             r#"{"$type":"LoadState.failed","message":"Offline","retryable":true}"#
         );
-        let bytes = value.to_msgpack_vec().unwrap();
-        assert_eq!(NxValue::from_msgpack_slice(&bytes).unwrap(), expected);
+        let bytes = rmp_serde::to_vec(&value).unwrap();
+        assert_eq!(rmp_serde::from_slice::<NxValue>(&bytes).unwrap(), expected);
     }
 
     #[test]
@@ -650,7 +1128,306 @@ let root() = { answer() }"#;
             value.to_json_string().unwrap(),
             r#"{"$type":"LoadState.idle"}"#
         );
-        let bytes = value.to_msgpack_vec().unwrap();
-        assert_eq!(NxValue::from_msgpack_slice(&bytes).unwrap(), expected);
+        let bytes = rmp_serde::to_vec(&value).unwrap();
+        assert_eq!(rmp_serde::from_slice::<NxValue>(&bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn eval_source_with_seed_is_reproducible_and_deterministic_mode_rejects_randomness() {
+        let source = r#"let root() = { uuid() + ":" + uuid() }"#;
+        let seeded = EvalOptions::with_random_seed(42);
+
+        let EvalResult::Ok(first) =
+            eval_source_with_options(source, "seeded.nx", &ProgramBuildContext::empty(), &seeded)
+        else {
+            panic!("Expected seeded evaluation to succeed");
+        };
+        let EvalResult::Ok(second) =
+            eval_source_with_options(source, "seeded.nx", &ProgramBuildContext::empty(), &seeded)
+        else {
+            panic!("Expected seeded evaluation to succeed");
+        };
+        assert_eq!(first, second);
+
+        let deterministic = EvalOptions {
+            deterministic: true,
+            ..EvalOptions::default()
+        };
+        let EvalResult::Err(diagnostics) = eval_source_with_options(
+            source,
+            "deterministic.nx",
+            &ProgramBuildContext::empty(),
+            &deterministic,
+        ) else {
+            panic!("Expected deterministic evaluation to reject uuid()");
+        };
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("randomness is disabled")));
+    }
+
+    #[test]
+    fn eval_source_applies_null_concatenation_policy() {
+        let source = r#"let root() = { "name: " + null }"#;
+        let eval = |null_concatenation| {
+            eval_source_with_options(
+                source,
+                "null-concat.nx",
+                &ProgramBuildContext::empty(),
+                &EvalOptions {
+                    null_concatenation,
+                    ..EvalOptions::default()
+                },
+            )
+        };
+
+        let EvalResult::Err(diagnostics) = eval(NullConcatenation::Error) else {
+            panic!("Expected null concatenation to fail by default");
+        };
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("Cannot concatenate null")));
+
+        let EvalResult::Ok(empty) = eval(NullConcatenation::Empty) else {
+            panic!("Expected empty null concatenation to succeed");
+        };
+        assert_eq!(empty, NxValue::String("name: ".to_string()));
+
+        let EvalResult::Ok(literal) = eval(NullConcatenation::Literal) else {
+            panic!("Expected literal null concatenation to succeed");
+        };
+        assert_eq!(literal, NxValue::String("name: null".to_string()));
+    }
+
+    #[test]
+    fn eval_source_translates_with_the_catalog_in_options() {
+        let source = r#"let root() = { t("Hello, {0}!", ["Ada"]) }"#;
+        let eval = |translations| {
+            eval_source_with_options(
+                source,
+                "translate.nx",
+                &ProgramBuildContext::empty(),
+                &EvalOptions {
+                    translations,
+                    ..EvalOptions::default()
+                },
+            )
+        };
+
+        let EvalResult::Ok(untranslated) = eval(None) else {
+            panic!("Expected untranslated evaluation to succeed");
+        };
+        assert_eq!(untranslated, NxValue::String("Hello, Ada!".to_string()));
+
+        let catalog = MessageCatalog::from_iter([("Hello, {0}!", "Bonjour, {0} !")]);
+        let EvalResult::Ok(translated) = eval(Some(Arc::new(catalog))) else {
+            panic!("Expected translated evaluation to succeed");
+        };
+        assert_eq!(translated, NxValue::String("Bonjour, Ada !".to_string()));
+    }
+
+    #[test]
+    fn eval_source_reports_failed_assertions_with_their_own_code() {
+        let eval = |title: &str| {
+            let source = format!(
+                r#"let titled(title:string) = <section>{{assert(length(title) > 0, "title is required")}}<h1 /></section>
+let grade(n:int): string = {{ if n >= 50 {{ "pass" }} else {{ fail("no grade below 50") }} }}
+let root() = {{ [titled("{}"), grade(70)] }}"#,
+                title
+            );
+            eval_source(&source, "asserts.nx", &ProgramBuildContext::empty())
+        };
+
+        if let EvalResult::Err(diagnostics) = eval("Ada") {
+            panic!("Expected evaluation to succeed, got {:?}", diagnostics);
+        }
+        let EvalResult::Err(diagnostics) = eval("") else {
+            panic!("Expected the assertion to fail");
+        };
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("assertion-failed"));
+        assert_eq!(diagnostics[0].message, "title is required");
+    }
+
+    #[test]
+    fn eval_source_sends_log_calls_to_the_sink() {
+        let records = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = {
+            let records = Arc::clone(&records);
+            NxLogSink::new(move |record| records.lock().unwrap().push(record))
+        };
+        let source = r#"let total(items:int[]) = <sum>{log("debug", "summing")}{log("warn", "items", items)}</sum>
+let root() = { total([1, 2]) }"#;
+        let eval = |log_sink: Option<NxLogSink>| {
+            eval_source_with_options(
+                source,
+                "log.nx",
+                &ProgramBuildContext::empty(),
+                &EvalOptions {
+                    log_sink,
+                    ..EvalOptions::default()
+                },
+            )
+        };
+
+        assert!(matches!(eval(None), EvalResult::Ok(_)));
+        assert!(matches!(eval(Some(sink)), EvalResult::Ok(_)));
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![NxLogRecord {
+                level: LogLevel::Warn,
+                message: "items".to_string(),
+                value: Some(NxValue::Array(vec![NxValue::Int(1), NxValue::Int(2)])),
+            }]
+        );
+    }
+
+    #[test]
+    fn eval_source_keeps_element_attribute_types() {
+        let source = r#"let root() = <div tabindex={3} hidden={true} ratio={0.5} label="x" />"#;
+
+        let EvalResult::Ok(value) =
+            eval_source(source, "attributes.nx", &ProgramBuildContext::empty())
+        else {
+            panic!("Expected element evaluation to succeed");
+        };
+
+        let expected = NxValue::Record {
+            type_name: Some("div".to_string()),
+            properties: BTreeMap::from([
+                ("hidden".to_string(), NxValue::Bool(true)),
+                ("label".to_string(), NxValue::String("x".to_string())),
+                ("ratio".to_string(), NxValue::Float(0.5)),
+                ("tabindex".to_string(), NxValue::Int(3)),
+            ]),
+        };
+        assert_eq!(value, expected);
+    }
+
+    fn stream_source(source: &str) -> (Vec<NxStreamEvent>, EvalResult) {
+        let program =
+            load_program_artifact_from_source(source, "stream.nx", &ProgramBuildContext::empty())
+                .unwrap_or_else(|diagnostics| {
+                    panic!("Expected program to build: {:?}", diagnostics)
+                });
+        let mut events = Vec::new();
+        let result =
+            eval_program_artifact_streaming(&program, &EvalOptions::default(), &mut |event| {
+                events.push(event)
+            });
+        (events, result)
+    }
+
+    fn element(tag: &str, properties: Vec<(&str, NxValue)>) -> NxValue {
+        NxValue::Record {
+            type_name: Some(tag.to_string()),
+            properties: properties
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn eval_program_artifact_streaming_reports_root_children_in_order() {
+        let (events, result) = stream_source(
+            r#"
+let items = ["b", "c"]
+let root() = {
+  <page title="Home">
+    <header />
+    {for item in items { <item name={item} /> }}
+    <footer />
+  </page>
+}
+"#,
+        );
+
+        let children = vec![
+            element("header", vec![]),
+            element("item", vec![("name", NxValue::String("b".to_string()))]),
+            element("item", vec![("name", NxValue::String("c".to_string()))]),
+            element("footer", vec![]),
+        ];
+        let mut expected = vec![NxStreamEvent::Open {
+            tag: "page".to_string(),
+            properties: BTreeMap::from([(
+                "title".to_string(),
+                NxValue::String("Home".to_string()),
+            )]),
+        }];
+        expected.extend(children.iter().cloned().map(NxStreamEvent::Child));
+        expected.push(NxStreamEvent::Close);
+        assert_eq!(events, expected);
+
+        let EvalResult::Ok(NxValue::Record { properties, .. }) = result else {
+            panic!("Expected the complete root element");
+        };
+        assert_eq!(properties["content"], NxValue::Array(children));
+    }
+
+    #[test]
+    fn eval_program_artifact_streaming_keeps_children_sent_before_an_error() {
+        let (events, result) = stream_source(
+            r#"
+let fail(n:int): int = { n / 0 }
+let root() = { <page><header /><row value={fail(1)} /><footer /></page> }
+"#,
+        );
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], NxStreamEvent::Open { .. }));
+        assert_eq!(events[1], NxStreamEvent::Child(element("header", vec![])));
+        let EvalResult::Err(diagnostics) = result else {
+            panic!("Expected a runtime error");
+        };
+        assert_eq!(diagnostics[0].code.as_deref(), Some("runtime-error"));
+    }
+
+    #[test]
+    fn eval_program_artifact_streaming_reports_other_roots_as_one_child() {
+        let (events, result) = stream_source("let root() = { 40 + 2 }");
+
+        assert_eq!(events, vec![NxStreamEvent::Child(NxValue::Int(42))]);
+        assert!(matches!(result, EvalResult::Ok(NxValue::Int(42))));
+    }
+
+    #[test]
+    fn eval_program_artifact_is_shared_across_worker_threads() {
+        let program = load_program_artifact_from_source(
+            r#"
+let roll() = { randomInt(1, 1000000) }
+let root() = { <result value={roll()} /> }
+"#,
+            "shared.nx",
+            &ProgramBuildContext::empty(),
+        )
+        .unwrap_or_else(|diagnostics| panic!("Expected program to build: {:?}", diagnostics));
+        let program = Arc::new(program);
+
+        let results = thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    let program = Arc::clone(&program);
+                    scope.spawn(move || {
+                        match eval_program_artifact_with_options(
+                            &program,
+                            &EvalOptions::with_random_seed(3),
+                        ) {
+                            EvalResult::Ok(value) => value,
+                            EvalResult::Err(diagnostics) => {
+                                panic!("Expected evaluation to succeed: {:?}", diagnostics)
+                            }
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("worker thread"))
+                .collect::<Vec<_>>()
+        });
+
+        assert!(results.windows(2).all(|pair| pair[0] == pair[1]));
     }
 }
@@ -0,0 +1,209 @@
+//! File-system resolution of import paths for hosts that load libraries from disk.
+//!
+//! Import paths are resolved in a fixed order:
+//!
+//! 1. Paths starting with `./` or `../`, and absolute paths, are resolved against the directory of
+//!    the importing file only.
+//! 2. Other (bare) paths are tried against the importing file's directory first, then against
+//!    each configured lib root in order.
+//!
+//! The first candidate that exists as a directory or as a `.nx` file wins. Resolved paths are
+//! canonicalized, so a library reached through a symlink is identified by its real location and
+//! loaded only once. [`ImportPathResolver::resolve_with`] looks candidates up through a
+//! [`ModuleResolver`] instead of the file system.
+
+use crate::module_resolver::{FilesystemModuleResolver, ModuleResolver};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Resolves import paths against the importing file and a list of lib roots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportPathResolver {
+    lib_roots: Vec<PathBuf>,
+}
+
+impl ImportPathResolver {
+    /// Creates a resolver that searches `lib_roots`, in order, for bare import paths.
+    pub fn new<I, P>(lib_roots: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            lib_roots: lib_roots.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns the configured lib roots in resolution order.
+    pub fn lib_roots(&self) -> &[PathBuf] {
+        &self.lib_roots
+    }
+
+    /// Returns every location tried for `library_path`, in resolution order.
+    pub fn search_candidates(&self, importing_file: &Path, library_path: &str) -> Vec<PathBuf> {
+        let import_path = Path::new(library_path);
+        if import_path.is_absolute() {
+            return vec![import_path.to_path_buf()];
+        }
+
+        let importing_dir = importing_file
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let mut candidates = vec![importing_dir.join(import_path)];
+        if !is_explicitly_relative(library_path) {
+            candidates.extend(self.lib_roots.iter().map(|root| root.join(import_path)));
+        }
+        candidates
+    }
+
+    /// Resolves `library_path` imported from `importing_file` to a canonical library root.
+    pub fn resolve(
+        &self,
+        importing_file: &Path,
+        library_path: &str,
+    ) -> Result<PathBuf, ImportPathError> {
+        self.resolve_with(importing_file, library_path, &FilesystemModuleResolver)
+    }
+
+    /// Resolves `library_path` like [`resolve`](Self::resolve), looking up candidates through
+    /// `modules` instead of the file system.
+    pub fn resolve_with(
+        &self,
+        importing_file: &Path,
+        library_path: &str,
+        modules: &dyn ModuleResolver,
+    ) -> Result<PathBuf, ImportPathError> {
+        let searched = self.search_candidates(importing_file, library_path);
+        for candidate in &searched {
+            if let Some(resolved) = modules.library_root(candidate) {
+                return Ok(resolved);
+            }
+        }
+
+        Err(ImportPathError {
+            library_path: library_path.to_string(),
+            searched,
+        })
+    }
+}
+
+/// An import path that did not resolve to a library directory or `.nx` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportPathError {
+    /// The import path as written in the source.
+    pub library_path: String,
+    /// Every location that was tried, in resolution order.
+    pub searched: Vec<PathBuf>,
+}
+
+impl fmt::Display for ImportPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let searched = self
+            .searched
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "Import '{}' not found, searched: {}",
+            self.library_path, searched
+        )
+    }
+}
+
+impl std::error::Error for ImportPathError {}
+
+fn is_explicitly_relative(library_path: &str) -> bool {
+    library_path == "."
+        || library_path == ".."
+        || library_path.starts_with("./")
+        || library_path.starts_with("../")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_tree(files: &[&str]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for file in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_relative_imports_ignore_lib_roots() {
+        let dir = create_tree(&["app/main.nx", "app/widgets/button.nx", "lib/widgets/x.nx"]);
+        let resolver = ImportPathResolver::new([dir.path().join("lib")]);
+        let main = dir.path().join("app").join("main.nx");
+
+        let resolved = resolver.resolve(&main, "./widgets/button.nx").unwrap();
+        assert_eq!(
+            resolved,
+            fs::canonicalize(dir.path().join("app/widgets/button.nx")).unwrap()
+        );
+
+        let error = resolver.resolve(&main, "./missing").unwrap_err();
+        assert_eq!(
+            error.searched,
+            vec![dir.path().join("app").join("./missing")]
+        );
+    }
+
+    #[test]
+    fn test_bare_imports_search_importing_directory_then_lib_roots_in_order() {
+        let dir = create_tree(&[
+            "app/main.nx",
+            "first/ui/a.nx",
+            "second/ui/b.nx",
+            "second/forms/c.nx",
+        ]);
+        let resolver =
+            ImportPathResolver::new([dir.path().join("first"), dir.path().join("second")]);
+        let main = dir.path().join("app").join("main.nx");
+
+        assert_eq!(
+            resolver.resolve(&main, "ui").unwrap(),
+            fs::canonicalize(dir.path().join("first/ui")).unwrap()
+        );
+        assert_eq!(
+            resolver.resolve(&main, "forms").unwrap(),
+            fs::canonicalize(dir.path().join("second/forms")).unwrap()
+        );
+
+        let error = resolver.resolve(&main, "charts").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Import 'charts' not found, searched: {}, {}, {}",
+                dir.path().join("app/charts").display(),
+                dir.path().join("first/charts").display(),
+                dir.path().join("second/charts").display()
+            )
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_libraries_resolve_to_their_real_location() {
+        let dir = create_tree(&["app/main.nx", "vendor/ui/a.nx"]);
+        std::os::unix::fs::symlink(dir.path().join("vendor/ui"), dir.path().join("app/ui"))
+            .unwrap();
+        let resolver = ImportPathResolver::default();
+
+        let resolved = resolver
+            .resolve(&dir.path().join("app/main.nx"), "./ui")
+            .unwrap();
+        assert_eq!(
+            resolved,
+            fs::canonicalize(dir.path().join("vendor/ui")).unwrap()
+        );
+    }
+}
@@ -4,6 +4,12 @@
 //! - [`eval_source`]: evaluate NX source text to a stable [`NxValue`](nx_value::NxValue)
 //! - [`eval_program_artifact`]: evaluate the `root()` entrypoint of a previously built
 //!   [`ProgramArtifact`]
+//! - [`EvalOptions`] with [`eval_source_with_options`] / [`eval_program_artifact_with_options`]:
+//!   seed or disable the random builtins for reproducible evaluation, and choose how string
+//!   concatenation treats null ([`NullConcatenation`]), or run an entry other than `root()`
+//!   ([`EvalOptions::entry`], with candidates listed by [`runnable_entries`]), or translate the
+//!   messages of the `t` builtin with a [`MessageCatalog`] ([`EvalOptions::translations`]), or
+//!   run function bodies on the bytecode VM ([`EvalOptions::backend`])
 //! - [`evaluate_component_source`] / [`evaluate_component_program_artifact`]: pure component
 //!   rendering from explicit props and host-owned current state, returning the rendered value
 //!   directly without lifecycle wrapper fields
@@ -14,7 +20,28 @@
 //!   initialize a named component, and dispatch action batches
 //! - [`initialize_component_program_artifact`] / [`dispatch_component_actions_program_artifact`]:
 //!   component lifecycle entry points that execute a resolved [`ProgramArtifact`]
+//! - [`TemplateSession`]: named templates for long-running hosts, reloaded in place with
+//!   [`TemplateSession::reload`] while evaluations in flight finish on the previous version
+//! - [`EvalService`]: a pool of evaluation threads with a bounded queue, per-task timeouts and
+//!   counters, for servers that evaluate sources or prepared programs concurrently
+//! - [`ImportPathResolver`] / [`LibraryRegistry::build_context_for_source`]: resolve the imports of a
+//!   file on disk against relative paths and lib roots, and load the libraries they reference
+//! - [`ModuleResolver`] / [`LibraryRegistry::with_module_resolver`]: read imported libraries from
+//!   somewhere other than disk, such as unsaved editor buffers or a template database
+//! - [`OverlayModuleResolver`]: unsaved editor buffers layered over disk, with change
+//!   notifications, for imports and [`TypeCheckSession`](nx_types::TypeCheckSession)s that should
+//!   see what the user sees
+//! - [`LibraryRegistry::load_std_library`]: `std/` imports resolve to the embedded `nx-stdlib`
+//!   modules in every build context, with no files on disk
+//! - [`read_module_metadata`]: read a module's `meta { ... }` block without evaluating it, so hosts
+//!   can list template metadata cheaply
+//! - [`toolchain_info`]: crate versions, grammar hash and supported language versions, for bug
+//!   reports and hosts that check compatibility
+//! - [`NxConfig`]: project settings discovered from `nx.toml` files, shared by the CLI and hosts
 //! - [`NxDiagnostic`]: a stable, serde-friendly diagnostic model for tooling and FFI
+//! - [`NxEnvelope`] / [`negotiate_envelope_version`]: versioned `{"$nx": 1, "value": ...}`
+//!   wrappers for serialized results and diagnostics, so their encoding can evolve without
+//!   breaking consumers that negotiated an older version
 //! - [`to_nx_value`] / [`from_nx_value`]: convert between interpreter
 //!   [`Value`](nx_interpreter::Value) and [`NxValue`](nx_value::NxValue), rejecting runtime-only
 //!   callback values on the reverse path
@@ -27,16 +54,25 @@
 
 mod artifacts;
 mod component;
+mod config;
 mod diagnostics;
+mod envelope;
 mod eval;
+mod import_paths;
+mod metadata;
+mod module_resolver;
+mod overlay;
+mod service;
+mod session;
 mod source_graph;
+mod toolchain;
 mod value;
 mod workspace;
 
 pub use artifacts::{
-    build_library_artifact_from_directory, build_program_artifact_from_source,
-    build_workspace_program_artifact, validate_workspace, LibraryArtifact, LibraryExport,
-    LibraryRegistry, ProgramArtifact, ProgramBuildContext,
+    build_library_artifact_from_directory, build_program_artifact_from_parse,
+    build_program_artifact_from_source, build_workspace_program_artifact, validate_workspace,
+    LibraryArtifact, LibraryExport, LibraryRegistry, ProgramArtifact, ProgramBuildContext,
 };
 pub use component::{
     dispatch_component_actions_program_artifact, dispatch_component_actions_source,
@@ -45,10 +81,33 @@ pub use component::{
     ComponentDispatchEvalResult, ComponentDispatchResult, ComponentEvaluateEvalResult,
     ComponentEvaluateResult, ComponentInitEvalResult, ComponentInitResult,
 };
-pub use diagnostics::{NxDiagnostic, NxDiagnosticLabel, NxSeverity, NxTextSpan};
+pub use config::{
+    NxCheckConfig, NxConfig, NxConfigError, NxFilesConfig, NxFormatConfig, NxImportsConfig,
+    NxLintLevel, CONFIG_FILE_NAME,
+};
+pub use diagnostics::{
+    diagnostics_to_api, NxDiagnostic, NxDiagnosticFix, NxDiagnosticLabel, NxSeverity, NxTextEdit,
+    NxTextSpan,
+};
+pub use envelope::{
+    negotiate_envelope_version, NxEnvelope, NxEnvelopeKind, ENVELOPE_VERSION_KEY,
+    LATEST_ENVELOPE_VERSION,
+};
 pub use eval::{
-    eval_program_artifact, eval_source, load_library_artifact_from_directory,
-    load_program_artifact_from_source, EvalResult,
+    eval_program_artifact, eval_program_artifact_streaming, eval_program_artifact_with_options,
+    eval_source, eval_source_with_options, load_library_artifact_from_directory,
+    load_program_artifact_from_source, runnable_entries, Backend, EvalOptions, EvalResult,
+    LogLevel, MessageCatalog, NullConcatenation, NxLogRecord, NxLogSink, NxStreamEvent,
+};
+pub use import_paths::{ImportPathError, ImportPathResolver};
+pub use metadata::read_module_metadata;
+pub use module_resolver::{FilesystemModuleResolver, ModuleResolver};
+pub use overlay::{OverlayChange, OverlayChangeKind, OverlayModuleResolver};
+pub use service::{
+    EvalHandle, EvalInput, EvalService, EvalServiceLimits, EvalServiceMetrics, ResourceLimits,
+    SubmitError,
 };
+pub use session::{TemplateEvent, TemplateSession};
+pub use toolchain::toolchain_info;
 pub use value::{from_nx_value, to_nx_value, FromNxValueError};
 pub use workspace::{NxWorkspace, NxWorkspaceInputError, NxWorkspaceModule};
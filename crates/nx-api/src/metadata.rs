@@ -0,0 +1,118 @@
+use crate::diagnostics::{diagnostics_to_api, NxDiagnostic};
+use nx_diagnostics::{Diagnostic, Severity};
+use nx_hir::ast::{Expr, Literal};
+use nx_hir::{lower, ExprId, LoweredModule, SourceId};
+use nx_syntax::parse_str;
+use nx_value::NxValue;
+use std::collections::BTreeMap;
+
+/// Reads the `meta { ... }` block of an NX module without type checking or evaluating it.
+///
+/// Returns `Ok(None)` when the module has no `meta` block. Otherwise the block becomes an
+/// [`NxValue::Record`] whose `type_name` is the schema named after `meta`, if any, and whose
+/// properties are the block's entries. When a name is repeated, the first entry wins; `nxlang
+/// check` reports the repetition and validates entries against the schema.
+///
+/// Syntax errors are returned as diagnostics, since the block cannot be read reliably from a
+/// module that does not parse.
+pub fn read_module_metadata(
+    source: &str,
+    file_name: &str,
+) -> Result<Option<NxValue>, Vec<NxDiagnostic>> {
+    let parse_result = parse_str(source, file_name);
+    if has_error_diagnostics(&parse_result.errors) {
+        return Err(diagnostics_to_api(&parse_result.errors, source));
+    }
+    let Some(tree) = parse_result.tree.as_ref() else {
+        return Err(diagnostics_to_api(&parse_result.errors, source));
+    };
+
+    let module = lower(tree.root(), SourceId::new(0));
+    let Some(metadata) = module.metadata() else {
+        return Ok(None);
+    };
+
+    let mut properties = BTreeMap::new();
+    for entry in &metadata.entries {
+        properties
+            .entry(entry.name.as_str().to_string())
+            .or_insert_with(|| metadata_value(&module, entry.value));
+    }
+
+    Ok(Some(NxValue::Record {
+        type_name: metadata
+            .schema
+            .as_ref()
+            .map(|schema| schema.as_str().to_string()),
+        properties,
+    }))
+}
+
+fn has_error_diagnostics(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity() == Severity::Error)
+}
+
+fn metadata_value(module: &LoweredModule, expr: ExprId) -> NxValue {
+    match module.expr(expr) {
+        Expr::Literal(Literal::String(value)) => NxValue::String(value.to_string()),
+        Expr::Literal(Literal::Int(value)) => NxValue::Int(*value),
+        Expr::Literal(Literal::Float(value)) => NxValue::Float(value.0),
+        Expr::Literal(Literal::Boolean(value)) => NxValue::Bool(*value),
+        Expr::Array { elements, .. } => NxValue::Array(
+            elements
+                .iter()
+                .map(|element| metadata_value(module, *element))
+                .collect(),
+        ),
+        _ => NxValue::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_module_metadata_returns_entries_without_evaluating() {
+        let source = r#"
+            meta TemplateInfo { title: "Invoice", version: 2, tags: ["billing", "pdf"] }
+            type TemplateInfo = { title:string version:int tags:string[] }
+            let root() = { missing() }
+        "#;
+
+        let metadata = read_module_metadata(source, "invoice.nx")
+            .expect("metadata should be readable")
+            .expect("module declares a meta block");
+        assert_eq!(
+            metadata,
+            NxValue::Record {
+                type_name: Some("TemplateInfo".to_string()),
+                properties: BTreeMap::from([
+                    ("title".to_string(), NxValue::String("Invoice".to_string())),
+                    ("version".to_string(), NxValue::Int(2)),
+                    (
+                        "tags".to_string(),
+                        NxValue::Array(vec![
+                            NxValue::String("billing".to_string()),
+                            NxValue::String("pdf".to_string()),
+                        ]),
+                    ),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn read_module_metadata_without_block_or_with_syntax_errors() {
+        assert_eq!(
+            read_module_metadata("let root() = <p />", "plain.nx"),
+            Ok(None)
+        );
+
+        let diagnostics = read_module_metadata("meta { title: }", "broken.nx")
+            .expect_err("syntax errors should be reported");
+        assert!(!diagnostics.is_empty());
+    }
+}
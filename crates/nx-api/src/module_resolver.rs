@@ -0,0 +1,145 @@
+//! Where the source text of imported libraries comes from.
+//!
+//! Library loading never touches `std::fs` directly. A [`LibraryRegistry`](crate::LibraryRegistry)
+//! asks its [`ModuleResolver`] to canonicalize import paths, to list the modules of a library
+//! directory and to read each module's source, so the same import resolution works against
+//! disk ([`FilesystemModuleResolver`], the default and what the CLI uses), unsaved editor buffers
+//! layered over disk, or templates stored in a database.
+//!
+//! Paths passed to a resolver are the ones import resolution builds: the importing file's
+//! directory joined with the import path, or a lib root joined with it. A resolver decides what
+//! those paths mean; they need not exist on any disk.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Supplies the modules that imports resolve to
+///
+/// A library is either a directory, whose `.nx` modules are found with
+/// [`list_modules`](Self::list_modules), or a single `.nx` module. Resolvers are shared by every
+/// build that uses their registry, possibly on several threads at once.
+pub trait ModuleResolver: Send + Sync {
+    /// Returns the source text of the module at `path`.
+    fn resolve(&self, path: &Path) -> io::Result<Arc<str>>;
+
+    /// Returns the canonical form of `path`, which identifies a library or module once loaded.
+    ///
+    /// Two paths naming the same module must canonicalize to the same path. Fails with
+    /// [`io::ErrorKind::NotFound`] when nothing exists at `path`.
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Returns true if `path` is a directory rather than a module.
+    fn is_directory(&self, path: &Path) -> bool;
+
+    /// Returns the `.nx` modules in the directory `dir` and its subdirectories, in any order.
+    fn list_modules(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Returns the canonical path of the library at `path`, if `path` names a directory or a
+    /// `.nx` module.
+    fn library_root(&self, path: &Path) -> Option<PathBuf> {
+        let is_module = path.extension().and_then(|ext| ext.to_str()) == Some("nx");
+        if !is_module && !self.is_directory(path) {
+            return None;
+        }
+        self.canonicalize(path).ok()
+    }
+}
+
+/// Reads modules from disk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FilesystemModuleResolver;
+
+impl ModuleResolver for FilesystemModuleResolver {
+    fn resolve(&self, path: &Path) -> io::Result<Arc<str>> {
+        fs::read_to_string(path).map(Arc::from)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn list_modules(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut modules = Vec::new();
+        collect_nx_files(dir, &mut modules)?;
+        Ok(modules)
+    }
+}
+
+fn collect_nx_files(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_nx_files(&path, out)?;
+        } else if file_type.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("nx")
+        {
+            out.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// A shared [`ModuleResolver`], as held by a registry
+#[derive(Clone)]
+pub(crate) struct SharedModuleResolver(pub Arc<dyn ModuleResolver>);
+
+impl Default for SharedModuleResolver {
+    fn default() -> Self {
+        Self(Arc::new(FilesystemModuleResolver))
+    }
+}
+
+impl fmt::Debug for SharedModuleResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ModuleResolver(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn filesystem_resolver_lists_reads_and_canonicalizes_modules() {
+        let dir = TempDir::new().unwrap();
+        let ui = dir.path().join("ui");
+        fs::create_dir_all(ui.join("forms")).unwrap();
+        fs::write(ui.join("button.nx"), "export let button() = <b />").unwrap();
+        fs::write(ui.join("forms").join("input.nx"), "").unwrap();
+        fs::write(ui.join("notes.txt"), "").unwrap();
+
+        let resolver = FilesystemModuleResolver;
+        let mut modules = resolver.list_modules(&ui).unwrap();
+        modules.sort();
+        assert_eq!(
+            modules,
+            vec![ui.join("button.nx"), ui.join("forms").join("input.nx")]
+        );
+        assert_eq!(
+            resolver.resolve(&ui.join("button.nx")).unwrap().as_ref(),
+            "export let button() = <b />"
+        );
+
+        let canonical_ui = fs::canonicalize(&ui).unwrap();
+        assert_eq!(
+            resolver.library_root(&ui.join("forms").join("..")),
+            Some(canonical_ui.clone())
+        );
+        assert_eq!(
+            resolver.library_root(&ui.join("button.nx")),
+            Some(canonical_ui.join("button.nx"))
+        );
+        assert_eq!(resolver.library_root(&ui.join("notes.txt")), None);
+        assert_eq!(resolver.library_root(&ui.join("missing")), None);
+    }
+}
@@ -0,0 +1,405 @@
+//! Unsaved editor buffers layered over another module resolver.
+//!
+//! Tooling sessions keep the text the user is looking at in an [`OverlayModuleResolver`]. Reads
+//! return a path's open buffer if it has one and fall through to the underlying resolver (disk, by
+//! default) otherwise, so both import resolution through a
+//! [`LibraryRegistry`](crate::LibraryRegistry) and a [`TypeCheckSession`] kept current with
+//! [`OverlayModuleResolver::sync_session`] see unsaved edits. Every buffer change is reported to
+//! the listeners registered with [`OverlayModuleResolver::on_change`]; a registry that outlives a
+//! change drops the libraries it affects with [`LibraryRegistry::invalidate`](crate::LibraryRegistry::invalidate).
+
+use crate::module_resolver::{FilesystemModuleResolver, ModuleResolver};
+use nx_types::TypeCheckSession;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// What happened to a buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayChangeKind {
+    /// A buffer was opened for a path that had none.
+    Opened,
+    /// An open buffer's text changed.
+    Changed,
+    /// A buffer was closed; reads fall through to the underlying resolver again.
+    Closed,
+}
+
+/// A change to one buffer of an [`OverlayModuleResolver`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayChange {
+    /// The buffer's path, canonicalized the way the overlay keys it.
+    pub path: PathBuf,
+    pub kind: OverlayChangeKind,
+}
+
+type OverlayListener = Box<dyn Fn(&OverlayChange) + Send + Sync>;
+
+/// Open buffers layered over another [`ModuleResolver`]
+///
+/// Buffers are keyed by the canonical form of their path, so `app/../app/main.nx` and
+/// `app/main.nx` name the same buffer. A buffer may also name a file that does not exist yet in
+/// the underlying resolver; it is then listed in its directory like any other module.
+pub struct OverlayModuleResolver {
+    base: Arc<dyn ModuleResolver>,
+    buffers: RwLock<BTreeMap<PathBuf, Arc<str>>>,
+    listeners: RwLock<Vec<OverlayListener>>,
+}
+
+impl OverlayModuleResolver {
+    /// Creates an overlay with no open buffers over `base`.
+    pub fn new(base: Arc<dyn ModuleResolver>) -> Self {
+        Self {
+            base,
+            buffers: RwLock::default(),
+            listeners: RwLock::default(),
+        }
+    }
+
+    /// Opens or updates the buffer for `path`.
+    ///
+    /// Listeners are told the buffer was opened or changed, unless it already held `text`.
+    pub fn set_buffer(&self, path: impl AsRef<Path>, text: impl Into<Arc<str>>) {
+        let path = self.buffer_key(path.as_ref());
+        let text = text.into();
+        let previous = self
+            .buffers
+            .write()
+            .expect("overlay buffers lock poisoned")
+            .insert(path.clone(), text.clone());
+        let kind = match previous {
+            None => OverlayChangeKind::Opened,
+            Some(previous) if previous != text => OverlayChangeKind::Changed,
+            Some(_) => return,
+        };
+        self.notify(OverlayChange { path, kind });
+    }
+
+    /// Closes the buffer for `path`, so reads see the underlying resolver's contents again.
+    ///
+    /// Returns false, without notifying listeners, if `path` had no open buffer.
+    pub fn close_buffer(&self, path: impl AsRef<Path>) -> bool {
+        let path = self.buffer_key(path.as_ref());
+        let removed = self
+            .buffers
+            .write()
+            .expect("overlay buffers lock poisoned")
+            .remove(&path)
+            .is_some();
+        if removed {
+            self.notify(OverlayChange {
+                path,
+                kind: OverlayChangeKind::Closed,
+            });
+        }
+        removed
+    }
+
+    /// Returns the text of the open buffer for `path`, if any.
+    pub fn buffer(&self, path: impl AsRef<Path>) -> Option<Arc<str>> {
+        let path = self.buffer_key(path.as_ref());
+        self.buffers
+            .read()
+            .expect("overlay buffers lock poisoned")
+            .get(&path)
+            .cloned()
+    }
+
+    /// Returns the paths of the open buffers, sorted.
+    pub fn open_paths(&self) -> Vec<PathBuf> {
+        self.buffers
+            .read()
+            .expect("overlay buffers lock poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Registers `listener` to be called after every buffer change.
+    ///
+    /// Listeners run on the thread that made the change, after the buffer is updated, so they may
+    /// read from the overlay. They must not register further listeners.
+    pub fn on_change(&self, listener: impl Fn(&OverlayChange) + Send + Sync + 'static) {
+        self.listeners
+            .write()
+            .expect("overlay listeners lock poisoned")
+            .push(Box::new(listener));
+    }
+
+    /// Brings the file for `path` in `session` up to date with what the overlay shows.
+    ///
+    /// The session file is named by the path's display form and holds the open buffer, or the
+    /// underlying resolver's contents when no buffer is open. It is removed when neither exists.
+    pub fn sync_session(&self, session: &mut TypeCheckSession, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let name = path.display().to_string();
+        match self.resolve(path) {
+            Ok(source) => session.add_file(name, source.as_ref()),
+            Err(_) => {
+                session.remove_file(&name);
+            }
+        }
+    }
+
+    fn notify(&self, change: OverlayChange) {
+        for listener in self
+            .listeners
+            .read()
+            .expect("overlay listeners lock poisoned")
+            .iter()
+        {
+            listener(&change);
+        }
+    }
+
+    fn buffer_key(&self, path: &Path) -> PathBuf {
+        self.base
+            .canonicalize(path)
+            .unwrap_or_else(|_| normalize_lexically(path))
+    }
+
+    fn has_buffers_below(&self, dir: &Path) -> bool {
+        self.buffers
+            .read()
+            .expect("overlay buffers lock poisoned")
+            .keys()
+            .any(|path| path != dir && path.starts_with(dir))
+    }
+}
+
+impl Default for OverlayModuleResolver {
+    /// Layers buffers over the file system.
+    fn default() -> Self {
+        Self::new(Arc::new(FilesystemModuleResolver))
+    }
+}
+
+impl fmt::Debug for OverlayModuleResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OverlayModuleResolver")
+            .field("open_paths", &self.open_paths())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ModuleResolver for OverlayModuleResolver {
+    fn resolve(&self, path: &Path) -> io::Result<Arc<str>> {
+        match self.buffer(path) {
+            Some(text) => Ok(text),
+            None => self.base.resolve(path),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.base.canonicalize(path).or_else(|error| {
+            let path = normalize_lexically(path);
+            let buffers = self.buffers.read().expect("overlay buffers lock poisoned");
+            if buffers.keys().any(|key| key.starts_with(&path)) {
+                Ok(path)
+            } else {
+                Err(error)
+            }
+        })
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.base.is_directory(path) || self.has_buffers_below(&self.buffer_key(path))
+    }
+
+    fn list_modules(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let key = self.buffer_key(dir);
+        let mut modules = match self.base.list_modules(dir) {
+            Ok(modules) => modules.into_iter().collect::<BTreeSet<_>>(),
+            Err(_) if self.has_buffers_below(&key) => BTreeSet::new(),
+            Err(error) => return Err(error),
+        };
+        let buffers = self.buffers.read().expect("overlay buffers lock poisoned");
+        modules.extend(
+            buffers
+                .keys()
+                .filter(|path| {
+                    path.starts_with(&key)
+                        && path.extension().and_then(|ext| ext.to_str()) == Some("nx")
+                })
+                .map(|path| dir.join(path.strip_prefix(&key).unwrap_or(path))),
+        );
+        Ok(modules.into_iter().collect())
+    }
+}
+
+/// Resolves `.` and `..` without consulting any file system.
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build_program_artifact_from_source, eval_program_artifact, EvalResult, ImportPathResolver,
+        LibraryRegistry,
+    };
+    use nx_value::NxValue;
+    use std::fs;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    #[test]
+    fn buffers_shadow_disk_until_closed_and_report_each_change() {
+        let dir = TempDir::new().unwrap();
+        let saved = dir.path().join("main.nx");
+        fs::write(&saved, "let root() = { 1 }").unwrap();
+        let overlay = OverlayModuleResolver::default();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        overlay.on_change({
+            let changes = changes.clone();
+            move |change| changes.lock().unwrap().push(change.kind)
+        });
+
+        overlay.set_buffer(&saved, "let root() = { 2 }");
+        overlay.set_buffer(dir.path().join(".").join("main.nx"), "let root() = { 2 }");
+        overlay.set_buffer(&saved, "let root() = { 3 }");
+        assert_eq!(
+            overlay.resolve(&saved).unwrap().as_ref(),
+            "let root() = { 3 }"
+        );
+        assert_eq!(
+            overlay.open_paths(),
+            vec![fs::canonicalize(&saved).unwrap()]
+        );
+
+        assert!(overlay.close_buffer(&saved));
+        assert!(!overlay.close_buffer(&saved));
+        assert_eq!(
+            overlay.resolve(&saved).unwrap().as_ref(),
+            "let root() = { 1 }"
+        );
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![
+                OverlayChangeKind::Opened,
+                OverlayChangeKind::Changed,
+                OverlayChangeKind::Closed
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_see_unsaved_and_new_files() {
+        let dir = TempDir::new().unwrap();
+        let ui = dir.path().join("ui");
+        fs::create_dir_all(&ui).unwrap();
+        fs::write(ui.join("ten.nx"), "export let ten() = { 10 }").unwrap();
+        let overlay = Arc::new(OverlayModuleResolver::default());
+        overlay.set_buffer(ui.join("ten.nx"), "export let ten() = { 11 }");
+        overlay.set_buffer(ui.join("extra.nx"), "export let extra() = { 100 }");
+
+        let registry = LibraryRegistry::with_module_resolver(overlay.clone());
+        let main_path = dir.path().join("main.nx");
+        let source = "import \"./ui\"\nlet root() = { ten() + extra() }";
+        let build_context = registry
+            .build_context_for_source(source, &main_path, &ImportPathResolver::default())
+            .expect("imports resolve");
+        let artifact = build_program_artifact_from_source(
+            source,
+            &main_path.display().to_string(),
+            &build_context,
+        )
+        .unwrap();
+        let EvalResult::Ok(value) = eval_program_artifact(&artifact) else {
+            panic!("expected the program to evaluate");
+        };
+        assert_eq!(value, NxValue::Int(111));
+    }
+
+    #[test]
+    fn invalidated_libraries_reload_changed_buffers() {
+        let dir = TempDir::new().unwrap();
+        let ui = dir.path().join("ui");
+        let app = dir.path().join("app");
+        fs::create_dir_all(&ui).unwrap();
+        fs::create_dir_all(&app).unwrap();
+        fs::write(ui.join("ten.nx"), "export let ten() = { 10 }").unwrap();
+        fs::write(
+            app.join("twice.nx"),
+            "import \"../ui\"\nexport let twice() = { ten() * 2 }",
+        )
+        .unwrap();
+        let overlay = Arc::new(OverlayModuleResolver::default());
+        let registry = LibraryRegistry::with_module_resolver(overlay.clone());
+        overlay.on_change({
+            let registry = registry.clone();
+            move |change| {
+                registry.invalidate(&change.path);
+            }
+        });
+
+        let main_path = dir.path().join("main.nx");
+        let source = "import \"./app\"\nlet root() = { twice() }";
+        let eval = || {
+            let build_context = registry
+                .build_context_for_source(source, &main_path, &ImportPathResolver::default())
+                .expect("imports resolve");
+            let artifact = build_program_artifact_from_source(
+                source,
+                &main_path.display().to_string(),
+                &build_context,
+            )
+            .unwrap();
+            let EvalResult::Ok(value) = eval_program_artifact(&artifact) else {
+                panic!("expected the program to evaluate");
+            };
+            value
+        };
+
+        assert_eq!(eval(), NxValue::Int(20));
+        overlay.set_buffer(ui.join("ten.nx"), "export let ten() = { 21 }");
+        assert_eq!(eval(), NxValue::Int(42));
+        overlay.close_buffer(ui.join("ten.nx"));
+        assert_eq!(eval(), NxValue::Int(20));
+        assert!(!registry.invalidate(dir.path().join("main.nx")));
+    }
+
+    #[test]
+    fn sessions_follow_buffer_changes() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("app.nx");
+        let overlay = Arc::new(OverlayModuleResolver::default());
+        let session = Arc::new(Mutex::new(TypeCheckSession::new()));
+        overlay.on_change({
+            let overlay = Arc::downgrade(&overlay);
+            let session = session.clone();
+            move |change| {
+                if let Some(overlay) = overlay.upgrade() {
+                    overlay.sync_session(&mut session.lock().unwrap(), &change.path);
+                }
+            }
+        });
+
+        overlay.set_buffer(&path, "let x: int = \"text\"");
+        let name = path.display().to_string();
+        assert!(!session.lock().unwrap().diagnostics().is_empty());
+
+        overlay.set_buffer(&path, "let x: int = 1");
+        assert!(session.lock().unwrap().diagnostics().is_empty());
+        assert_eq!(
+            session.lock().unwrap().file_source(&name),
+            Some("let x: int = 1")
+        );
+
+        overlay.close_buffer(&path);
+        assert!(session.lock().unwrap().is_empty());
+    }
+}
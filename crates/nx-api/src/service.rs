@@ -0,0 +1,630 @@
+//! A pool of evaluation threads for servers that run many templates concurrently.
+
+use crate::component::invalid_input_diagnostics;
+use crate::eval::{
+    build_source_program_artifact, eval_program_entry, program_root_source,
+    runtime_error_diagnostics,
+};
+use crate::value::from_nx_value;
+use crate::{
+    EvalOptions, EvalResult, NxDiagnostic, NxSeverity, ProgramArtifact, ProgramBuildContext,
+};
+pub use nx_interpreter::ResourceLimits;
+use nx_interpreter::{RuntimeError, RuntimeErrorKind};
+use nx_value::NxValue;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Queueing and time limits of an [`EvalService`]
+#[derive(Debug, Clone, Copy)]
+pub struct EvalServiceLimits {
+    /// Tasks that may wait for a worker. [`EvalService::submit`] fails with
+    /// [`SubmitError::QueueFull`] while this many are waiting, so callers can shed load instead
+    /// of queueing without bound.
+    pub max_queued: usize,
+    /// Time from submission after which a task fails with an `eval-timeout` diagnostic, whether
+    /// it is still queued or already running. Building a program from source is not interrupted;
+    /// the deadline is checked before and during evaluation.
+    pub timeout: Option<Duration>,
+    /// Interpreter limits applied to every evaluation. Its `deadline` is replaced by the one
+    /// derived from [`timeout`](Self::timeout).
+    pub resource_limits: ResourceLimits,
+}
+
+impl Default for EvalServiceLimits {
+    fn default() -> Self {
+        Self {
+            max_queued: 1024,
+            timeout: None,
+            resource_limits: ResourceLimits::default(),
+        }
+    }
+}
+
+/// What an [`EvalService`] task evaluates
+#[derive(Debug, Clone)]
+pub enum EvalInput {
+    /// Source text, built against `build_context` on the worker before it is evaluated.
+    Source {
+        source: String,
+        file_name: String,
+        build_context: ProgramBuildContext,
+    },
+    /// A program built ahead of time, such as the current artifact of a
+    /// [`TemplateSession`](crate::TemplateSession).
+    Program(Arc<ProgramArtifact>),
+}
+
+impl EvalInput {
+    /// Returns an input that builds `source` against `build_context`.
+    pub fn source(
+        source: impl Into<String>,
+        file_name: impl Into<String>,
+        build_context: ProgramBuildContext,
+    ) -> Self {
+        Self::Source {
+            source: source.into(),
+            file_name: file_name.into(),
+            build_context,
+        }
+    }
+}
+
+impl From<Arc<ProgramArtifact>> for EvalInput {
+    fn from(program: Arc<ProgramArtifact>) -> Self {
+        Self::Program(program)
+    }
+}
+
+/// Why [`EvalService::submit`] did not accept a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitError {
+    /// [`EvalServiceLimits::max_queued`] tasks are already waiting.
+    QueueFull { capacity: usize },
+    /// The service is shutting down.
+    ShutDown,
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::QueueFull { capacity } => {
+                write!(f, "evaluation queue is full ({} tasks waiting)", capacity)
+            }
+            SubmitError::ShutDown => write!(f, "evaluation service is shutting down"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+/// Counters and totals of an [`EvalService`], as returned by [`EvalService::metrics`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvalServiceMetrics {
+    /// Tasks accepted by `submit`.
+    pub submitted: u64,
+    /// Tasks refused because the queue was full.
+    pub rejected: u64,
+    /// Tasks that produced a value.
+    pub succeeded: u64,
+    /// Tasks that ended with diagnostics other than a timeout.
+    pub failed: u64,
+    /// Tasks that ran out of time, in the queue or while evaluating.
+    pub timed_out: u64,
+    /// Tasks waiting for a worker now.
+    pub queued: usize,
+    /// Tasks being evaluated now.
+    pub running: usize,
+    /// Time finished tasks spent waiting for a worker, summed.
+    pub queue_time: Duration,
+    /// Time finished tasks spent building and evaluating, summed.
+    pub eval_time: Duration,
+}
+
+/// A pool of threads that evaluate NX programs submitted from any thread
+///
+/// Tasks wait in a bounded queue and run in submission order on `num_threads` workers. Each
+/// [`submit`](Self::submit) returns an [`EvalHandle`] that can be waited on from a blocking
+/// thread or awaited as a [`Future`] from async code; the service does not depend on an async
+/// runtime. A task that panics fails with an `internal-error` diagnostic and leaves its worker
+/// running.
+///
+/// Dropping the service stops accepting tasks, lets the workers finish the ones already queued,
+/// and joins them.
+pub struct EvalService {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct Shared {
+    limits: EvalServiceLimits,
+    options: RwLock<EvalOptions>,
+    queue: Mutex<Queue>,
+    available: Condvar,
+    metrics: Mutex<EvalServiceMetrics>,
+}
+
+#[derive(Default)]
+struct Queue {
+    tasks: VecDeque<Task>,
+    shutting_down: bool,
+}
+
+struct Task {
+    input: EvalInput,
+    args: Vec<NxValue>,
+    submitted: Instant,
+    deadline: Option<Instant>,
+    slot: Arc<Slot>,
+}
+
+#[derive(Default)]
+struct Slot {
+    state: Mutex<SlotState>,
+    finished: Condvar,
+}
+
+#[derive(Default)]
+struct SlotState {
+    result: Option<EvalResult>,
+    waker: Option<Waker>,
+}
+
+impl EvalService {
+    /// Starts `num_threads` workers (at least one) that evaluate with default [`EvalOptions`].
+    pub fn new(num_threads: usize, limits: EvalServiceLimits) -> Self {
+        let shared = Arc::new(Shared {
+            limits,
+            options: RwLock::default(),
+            queue: Mutex::default(),
+            available: Condvar::new(),
+            metrics: Mutex::default(),
+        });
+        let workers = (0..num_threads.max(1))
+            .map(|index| {
+                let shared = shared.clone();
+                std::thread::Builder::new()
+                    .name(format!("nx-eval-{}", index))
+                    .spawn(move || shared.work())
+                    .expect("failed to spawn evaluation worker")
+            })
+            .collect();
+        Self { shared, workers }
+    }
+
+    /// Evaluates tasks submitted from now on with `options`.
+    pub fn with_options(self, options: EvalOptions) -> Self {
+        *self
+            .shared
+            .options
+            .write()
+            .expect("evaluation service lock poisoned") = options;
+        self
+    }
+
+    /// Queues an evaluation of `input`'s entrypoint with `args`.
+    ///
+    /// The entrypoint is `root()`, or [`EvalOptions::entry`]. With no `args` it must take no
+    /// parameters, as for [`eval_program_artifact`](crate::eval_program_artifact); otherwise the
+    /// arguments are converted with [`from_nx_value`] and checked against its parameters.
+    pub fn submit(
+        &self,
+        input: impl Into<EvalInput>,
+        args: Vec<NxValue>,
+    ) -> Result<EvalHandle, SubmitError> {
+        let submitted = Instant::now();
+        let slot = Arc::new(Slot::default());
+        let mut queue = self.shared.queue.lock().expect("evaluation queue poisoned");
+        if queue.shutting_down {
+            return Err(SubmitError::ShutDown);
+        }
+        if queue.tasks.len() >= self.shared.limits.max_queued {
+            drop(queue);
+            self.shared.update_metrics(|metrics| metrics.rejected += 1);
+            return Err(SubmitError::QueueFull {
+                capacity: self.shared.limits.max_queued,
+            });
+        }
+        queue.tasks.push_back(Task {
+            input: input.into(),
+            args,
+            submitted,
+            deadline: self
+                .shared
+                .limits
+                .timeout
+                .map(|timeout| submitted + timeout),
+            slot: slot.clone(),
+        });
+        let queued = queue.tasks.len();
+        drop(queue);
+        self.shared.update_metrics(|metrics| {
+            metrics.submitted += 1;
+            metrics.queued = queued;
+        });
+        self.shared.available.notify_one();
+        Ok(EvalHandle { slot })
+    }
+
+    /// Returns the service's counters so far.
+    pub fn metrics(&self) -> EvalServiceMetrics {
+        self.shared
+            .metrics
+            .lock()
+            .expect("evaluation metrics poisoned")
+            .clone()
+    }
+}
+
+impl Drop for EvalService {
+    fn drop(&mut self) {
+        self.shared
+            .queue
+            .lock()
+            .expect("evaluation queue poisoned")
+            .shutting_down = true;
+        self.shared.available.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl fmt::Debug for EvalService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvalService")
+            .field("workers", &self.workers.len())
+            .field("limits", &self.shared.limits)
+            .field("metrics", &self.metrics())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Shared {
+    fn work(&self) {
+        while let Some(task) = self.next_task() {
+            let started = Instant::now();
+            let result = match task.deadline {
+                Some(deadline) if started >= deadline => timeout_diagnostics(&task.input),
+                _ => catch_unwind(AssertUnwindSafe(|| self.evaluate(&task)))
+                    .unwrap_or_else(|_| EvalResult::Err(panic_diagnostics())),
+            };
+            let finished = Instant::now();
+            self.update_metrics(|metrics| {
+                metrics.running -= 1;
+                match &result {
+                    EvalResult::Ok(_) => metrics.succeeded += 1,
+                    EvalResult::Err(diagnostics) if is_timeout(diagnostics) => {
+                        metrics.timed_out += 1
+                    }
+                    EvalResult::Err(_) => metrics.failed += 1,
+                }
+                metrics.queue_time += started - task.submitted;
+                metrics.eval_time += finished - started;
+            });
+            task.slot.finish(result);
+        }
+    }
+
+    /// Waits for the next task, or returns `None` once the queue is drained after shutdown.
+    fn next_task(&self) -> Option<Task> {
+        let mut queue = self.queue.lock().expect("evaluation queue poisoned");
+        loop {
+            if let Some(task) = queue.tasks.pop_front() {
+                let queued = queue.tasks.len();
+                drop(queue);
+                self.update_metrics(|metrics| {
+                    metrics.queued = queued;
+                    metrics.running += 1;
+                });
+                return Some(task);
+            }
+            if queue.shutting_down {
+                return None;
+            }
+            queue = self
+                .available
+                .wait(queue)
+                .expect("evaluation queue poisoned");
+        }
+    }
+
+    fn evaluate(&self, task: &Task) -> EvalResult {
+        let options = self
+            .options
+            .read()
+            .expect("evaluation service lock poisoned")
+            .clone();
+        let args = match task.args.iter().map(from_nx_value).collect() {
+            Ok(args) => args,
+            Err(error) => return EvalResult::Err(invalid_input_diagnostics(error)),
+        };
+        let limits = ResourceLimits {
+            deadline: task.deadline,
+            ..self.limits.resource_limits
+        };
+        match &task.input {
+            EvalInput::Source {
+                source,
+                file_name,
+                build_context,
+            } => match build_source_program_artifact(source, file_name, build_context) {
+                Ok(program) => eval_program_entry(&program, source, &options, args, limits),
+                Err(diagnostics) => EvalResult::Err(diagnostics),
+            },
+            EvalInput::Program(program) => {
+                let source = program_root_source(program);
+                eval_program_entry(program, &source, &options, args, limits)
+            }
+        }
+    }
+
+    fn update_metrics(&self, update: impl FnOnce(&mut EvalServiceMetrics)) {
+        update(&mut self.metrics.lock().expect("evaluation metrics poisoned"));
+    }
+}
+
+fn timeout_diagnostics(input: &EvalInput) -> EvalResult {
+    let source = match input {
+        EvalInput::Source { source, .. } => source.clone(),
+        EvalInput::Program(program) => program_root_source(program),
+    };
+    EvalResult::Err(runtime_error_diagnostics(
+        &source,
+        RuntimeError::new(RuntimeErrorKind::DeadlineExceeded),
+    ))
+}
+
+fn is_timeout(diagnostics: &[NxDiagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.code.as_deref() == Some("eval-timeout"))
+}
+
+fn panic_diagnostics() -> Vec<NxDiagnostic> {
+    vec![NxDiagnostic {
+        severity: NxSeverity::Error,
+        code: Some("internal-error".to_string()),
+        message: "Evaluation panicked".to_string(),
+        labels: Vec::new(),
+        help: None,
+        note: None,
+        fixes: Vec::new(),
+    }]
+}
+
+impl Slot {
+    fn finish(&self, result: EvalResult) {
+        let mut state = self.state.lock().expect("evaluation result poisoned");
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        drop(state);
+        self.finished.notify_all();
+    }
+}
+
+/// The pending result of a task submitted to an [`EvalService`]
+///
+/// Block on it with [`wait`](Self::wait) or [`wait_timeout`](Self::wait_timeout), or `.await`
+/// it. Dropping the handle does not cancel the task.
+pub struct EvalHandle {
+    slot: Arc<Slot>,
+}
+
+impl EvalHandle {
+    /// Returns true once the task has finished.
+    pub fn is_finished(&self) -> bool {
+        self.slot
+            .state
+            .lock()
+            .expect("evaluation result poisoned")
+            .result
+            .is_some()
+    }
+
+    /// Blocks until the task finishes and returns its result.
+    pub fn wait(self) -> EvalResult {
+        let mut state = self.slot.state.lock().expect("evaluation result poisoned");
+        loop {
+            if let Some(result) = state.result.take() {
+                return result;
+            }
+            state = self
+                .slot
+                .finished
+                .wait(state)
+                .expect("evaluation result poisoned");
+        }
+    }
+
+    /// Blocks for up to `timeout`, returning the result if the task finished in that time or
+    /// the handle back if it did not.
+    pub fn wait_timeout(self, timeout: Duration) -> Result<EvalResult, EvalHandle> {
+        let state = self.slot.state.lock().expect("evaluation result poisoned");
+        let (mut state, _) = self
+            .slot
+            .finished
+            .wait_timeout_while(state, timeout, |state| state.result.is_none())
+            .expect("evaluation result poisoned");
+        match state.result.take() {
+            Some(result) => Ok(result),
+            None => {
+                drop(state);
+                Err(self)
+            }
+        }
+    }
+}
+
+impl Future for EvalHandle {
+    type Output = EvalResult;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<EvalResult> {
+        let mut state = self.slot.state.lock().expect("evaluation result poisoned");
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl fmt::Debug for EvalHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvalHandle")
+            .field("finished", &self.is_finished())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::task::Wake;
+
+    const LOOP: &str = r#"let spin(n:int): int = { if n == 0 { 0 } else { spin(n - 1) } }
+let root(): int = { spin(900) + spin(900) + spin(900) + spin(900) }"#;
+
+    fn source(text: &str) -> EvalInput {
+        EvalInput::source(text, "task.nx", ProgramBuildContext::empty())
+    }
+
+    fn int(result: EvalResult) -> i64 {
+        match result {
+            EvalResult::Ok(NxValue::Int(value)) => value,
+            EvalResult::Ok(other) => panic!("expected an int, got {:?}", other),
+            EvalResult::Err(diagnostics) => panic!("evaluation failed: {:?}", diagnostics),
+        }
+    }
+
+    #[test]
+    fn submitted_sources_and_programs_evaluate_with_arguments() {
+        let service = EvalService::new(2, EvalServiceLimits::default());
+        let program = Arc::new(
+            crate::load_program_artifact_from_source(
+                "let root(count:int): int = { count * 2 }",
+                "double.nx",
+                &ProgramBuildContext::empty(),
+            )
+            .unwrap(),
+        );
+
+        let from_source = service
+            .submit(source("let root() = { 40 + 2 }"), vec![])
+            .unwrap();
+        let from_program = service
+            .submit(program.clone(), vec![NxValue::Int(21)])
+            .unwrap();
+        let bad_argument = service
+            .submit(program, vec![NxValue::String("x".to_string())])
+            .unwrap();
+        assert_eq!(int(from_source.wait()), 42);
+        assert_eq!(int(from_program.wait()), 42);
+        assert!(matches!(bad_argument.wait(), EvalResult::Err(_)));
+
+        let metrics = service.metrics();
+        assert_eq!(metrics.submitted, 3);
+        assert_eq!(metrics.succeeded, 2);
+        assert_eq!(metrics.failed, 1);
+        assert_eq!((metrics.queued, metrics.running), (0, 0));
+    }
+
+    #[test]
+    fn full_queue_rejects_submissions_until_a_worker_is_free() {
+        let (started, has_started) = mpsc::channel();
+        let (release, released) = mpsc::channel::<()>();
+        let released = Mutex::new(released);
+        let service = EvalService::new(
+            1,
+            EvalServiceLimits {
+                max_queued: 1,
+                ..EvalServiceLimits::default()
+            },
+        )
+        .with_options(EvalOptions {
+            log_sink: Some(crate::NxLogSink::new(move |_| {
+                started.send(()).unwrap();
+                released.lock().unwrap().recv().unwrap();
+            })),
+            ..EvalOptions::default()
+        });
+
+        let hold = r#"let root() = <held>{log("info", "hold")}</held>"#;
+        let running = service.submit(source(hold), vec![]).unwrap();
+        has_started.recv().unwrap();
+        let queued = service
+            .submit(source("let root() = { 2 }"), vec![])
+            .unwrap();
+        assert_eq!(
+            service
+                .submit(source("let root() = { 3 }"), vec![])
+                .unwrap_err(),
+            SubmitError::QueueFull { capacity: 1 }
+        );
+        let metrics = service.metrics();
+        assert_eq!(
+            (metrics.running, metrics.queued, metrics.rejected),
+            (1, 1, 1)
+        );
+
+        release.send(()).unwrap();
+        assert!(matches!(running.wait(), EvalResult::Ok(_)));
+        assert_eq!(int(queued.wait()), 2);
+    }
+
+    #[test]
+    fn tasks_past_their_deadline_fail_with_eval_timeout() {
+        let service = EvalService::new(
+            1,
+            EvalServiceLimits {
+                timeout: Some(Duration::ZERO),
+                ..EvalServiceLimits::default()
+            },
+        );
+        let EvalResult::Err(diagnostics) = service.submit(source(LOOP), vec![]).unwrap().wait()
+        else {
+            panic!("expected a timeout");
+        };
+        assert_eq!(diagnostics[0].code.as_deref(), Some("eval-timeout"));
+        assert_eq!(service.metrics().timed_out, 1);
+    }
+
+    #[test]
+    fn handles_can_be_awaited() {
+        struct Notify(mpsc::Sender<()>);
+        impl Wake for Notify {
+            fn wake(self: Arc<Self>) {
+                let _ = self.0.send(());
+            }
+        }
+
+        let service = EvalService::new(1, EvalServiceLimits::default());
+        let mut handle = service
+            .submit(source("let root() = { 7 }"), vec![])
+            .unwrap();
+        let (sender, woken) = mpsc::channel();
+        let waker = Waker::from(Arc::new(Notify(sender)));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match Pin::new(&mut handle).poll(&mut cx) {
+                Poll::Ready(result) => {
+                    assert_eq!(int(result), 7);
+                    break;
+                }
+                Poll::Pending => woken.recv_timeout(Duration::from_secs(10)).unwrap(),
+            }
+        }
+    }
+}
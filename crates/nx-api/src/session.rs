@@ -0,0 +1,242 @@
+//! Named templates that a long-running host can evaluate and replace while serving requests.
+
+use crate::eval::{eval_program_artifact_with_options, load_program_artifact_from_source};
+use crate::{EvalOptions, EvalResult, NxDiagnostic, ProgramArtifact, ProgramBuildContext};
+use rustc_hash::FxHashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// A change to the templates of a [`TemplateSession`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateEvent {
+    /// A template was built and is used by every evaluation that starts from now on.
+    Swapped {
+        name: String,
+        /// Fingerprint of the artifact that was replaced, or `None` for a new template.
+        previous_fingerprint: Option<u64>,
+        fingerprint: u64,
+    },
+    /// A new source for a template had errors. Evaluations keep using the previous artifact.
+    Rejected {
+        name: String,
+        diagnostics: Vec<NxDiagnostic>,
+    },
+    /// A template was removed.
+    Removed { name: String },
+}
+
+type TemplateListener = Box<dyn Fn(&TemplateEvent) + Send + Sync>;
+
+/// Compiled templates, by name, for hosts that evaluate them repeatedly and reload them without
+/// restarting
+///
+/// [`reload`](Self::reload) parses and checks a new source, then swaps the template's artifact in
+/// one step. An evaluation takes the artifact current when it starts and finishes with it, so
+/// requests in flight during a reload are unaffected, and a source with errors never replaces a
+/// working template. Every change is reported to the listeners registered with
+/// [`on_event`](Self::on_event), so a server can log template rollouts.
+///
+/// A session is `Send + Sync`; share it between request handlers behind an [`Arc`].
+pub struct TemplateSession {
+    build_context: ProgramBuildContext,
+    options: EvalOptions,
+    templates: RwLock<FxHashMap<String, Arc<ProgramArtifact>>>,
+    listeners: RwLock<Vec<TemplateListener>>,
+}
+
+impl TemplateSession {
+    /// Creates a session with no templates whose templates import libraries from `build_context`.
+    pub fn new(build_context: ProgramBuildContext) -> Self {
+        Self {
+            build_context,
+            options: EvalOptions::default(),
+            templates: RwLock::default(),
+            listeners: RwLock::default(),
+        }
+    }
+
+    /// Evaluates templates with `options` instead of the defaults.
+    pub fn with_options(mut self, options: EvalOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Builds `source` as the template `name`, replacing the template's current artifact.
+    ///
+    /// `name` is also the file name used in diagnostics. Returns the new artifact, or the
+    /// diagnostics of a source that failed to parse or type check, in which case the template is
+    /// left as it was.
+    pub fn reload(
+        &self,
+        name: &str,
+        source: &str,
+    ) -> Result<Arc<ProgramArtifact>, Vec<NxDiagnostic>> {
+        let artifact = match load_program_artifact_from_source(source, name, &self.build_context) {
+            Ok(artifact) => Arc::new(artifact),
+            Err(diagnostics) => {
+                self.notify(TemplateEvent::Rejected {
+                    name: name.to_string(),
+                    diagnostics: diagnostics.clone(),
+                });
+                return Err(diagnostics);
+            }
+        };
+
+        let previous = self
+            .templates
+            .write()
+            .expect("template session lock poisoned")
+            .insert(name.to_string(), artifact.clone());
+        self.notify(TemplateEvent::Swapped {
+            name: name.to_string(),
+            previous_fingerprint: previous.map(|previous| previous.fingerprint),
+            fingerprint: artifact.fingerprint,
+        });
+        Ok(artifact)
+    }
+
+    /// Removes the template `name`. Returns false if there was no such template.
+    pub fn remove(&self, name: &str) -> bool {
+        let removed = self
+            .templates
+            .write()
+            .expect("template session lock poisoned")
+            .remove(name)
+            .is_some();
+        if removed {
+            self.notify(TemplateEvent::Removed {
+                name: name.to_string(),
+            });
+        }
+        removed
+    }
+
+    /// Returns the current artifact of the template `name`.
+    pub fn artifact(&self, name: &str) -> Option<Arc<ProgramArtifact>> {
+        self.templates
+            .read()
+            .expect("template session lock poisoned")
+            .get(name)
+            .cloned()
+    }
+
+    /// Returns the names of the session's templates, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self
+            .templates
+            .read()
+            .expect("template session lock poisoned")
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Evaluates the `root()` entrypoint of the template `name`, or returns `None` if the session
+    /// has no such template.
+    pub fn evaluate(&self, name: &str) -> Option<EvalResult> {
+        let artifact = self.artifact(name)?;
+        Some(eval_program_artifact_with_options(&artifact, &self.options))
+    }
+
+    /// Registers `listener` to be called after every reload and removal.
+    ///
+    /// Listeners run on the thread that made the change. They must not register further
+    /// listeners.
+    pub fn on_event(&self, listener: impl Fn(&TemplateEvent) + Send + Sync + 'static) {
+        self.listeners
+            .write()
+            .expect("template session lock poisoned")
+            .push(Box::new(listener));
+    }
+
+    fn notify(&self, event: TemplateEvent) {
+        for listener in self
+            .listeners
+            .read()
+            .expect("template session lock poisoned")
+            .iter()
+        {
+            listener(&event);
+        }
+    }
+}
+
+impl fmt::Debug for TemplateSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemplateSession")
+            .field("templates", &self.names())
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_value::NxValue;
+    use std::sync::Mutex;
+
+    fn int(result: Option<EvalResult>) -> i64 {
+        match result {
+            Some(EvalResult::Ok(NxValue::Int(value))) => value,
+            Some(EvalResult::Ok(other)) => panic!("expected an int, got {:?}", other),
+            Some(EvalResult::Err(diagnostics)) => panic!("evaluation failed: {:?}", diagnostics),
+            None => panic!("template not found"),
+        }
+    }
+
+    #[test]
+    fn reload_swaps_the_artifact_and_keeps_it_when_the_new_source_has_errors() {
+        let session = TemplateSession::new(ProgramBuildContext::empty());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        session.on_event({
+            let events = events.clone();
+            move |event| events.lock().unwrap().push(event.clone())
+        });
+
+        let first = session
+            .reload("page.nx", "let root() = { 1 }")
+            .expect("first version builds");
+        let in_flight = session.artifact("page.nx").unwrap();
+        assert_eq!(int(session.evaluate("page.nx")), 1);
+
+        let second = session
+            .reload("page.nx", "let root() = { 2 }")
+            .expect("second version builds");
+        assert_eq!(int(session.evaluate("page.nx")), 2);
+        assert!(Arc::ptr_eq(&in_flight, &first));
+
+        let diagnostics = session
+            .reload("page.nx", "let root(): int = { \"three\" }")
+            .expect_err("a type error is rejected");
+        assert!(!diagnostics.is_empty());
+        assert_eq!(int(session.evaluate("page.nx")), 2);
+
+        assert!(session.remove("page.nx"));
+        assert!(session.evaluate("page.nx").is_none());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                TemplateEvent::Swapped {
+                    name: "page.nx".to_string(),
+                    previous_fingerprint: None,
+                    fingerprint: first.fingerprint,
+                },
+                TemplateEvent::Swapped {
+                    name: "page.nx".to_string(),
+                    previous_fingerprint: Some(first.fingerprint),
+                    fingerprint: second.fingerprint,
+                },
+                TemplateEvent::Rejected {
+                    name: "page.nx".to_string(),
+                    diagnostics,
+                },
+                TemplateEvent::Removed {
+                    name: "page.nx".to_string(),
+                },
+            ]
+        );
+    }
+}
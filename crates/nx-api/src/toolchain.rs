@@ -0,0 +1,130 @@
+use nx_syntax::LanguageVersion;
+use nx_value::NxValue;
+use std::collections::BTreeMap;
+
+/// Describes the toolchain this crate was built with, for bug reports and compatibility checks.
+///
+/// The result is a record with these properties:
+///
+/// - `version`: the toolchain version.
+/// - `crates`: the version of each NX crate, keyed by crate name.
+/// - `grammar`: the tree-sitter ABI version of the generated parser (`abi_version`) and a hash of
+///   the grammar source (`hash`). Builds with the same hash parse identically.
+/// - `features`: the optional cargo features compiled in, such as `graphemes` for the
+///   `graphemeLength` builtin.
+/// - `language_versions`: the oldest and latest NX language versions the parser accepts, and
+///   every supported version in between, as the strings used in `nx.toml`.
+///
+/// Use [`NxValue::to_json_string`] for the JSON form that `nxlang --info` prints.
+pub fn toolchain_info() -> NxValue {
+    let crates = [
+        ("nx-api", env!("CARGO_PKG_VERSION")),
+        ("nx-diagnostics", nx_diagnostics::VERSION),
+        ("nx-hir", nx_hir::VERSION),
+        ("nx-interpreter", nx_interpreter::VERSION),
+        ("nx-stdlib", nx_stdlib::VERSION),
+        ("nx-syntax", nx_syntax::VERSION),
+        ("nx-types", nx_types::VERSION),
+        ("nx-value", nx_value::VERSION),
+    ];
+    let versions = LanguageVersion::all();
+    let mut features = Vec::new();
+    if cfg!(feature = "graphemes") {
+        features.push(string("graphemes"));
+    }
+
+    record([
+        ("version", string(env!("CARGO_PKG_VERSION"))),
+        (
+            "crates",
+            record(crates.map(|(name, version)| (name, string(version)))),
+        ),
+        (
+            "grammar",
+            record([
+                (
+                    "abi_version",
+                    NxValue::Int(nx_syntax::language().abi_version() as i64),
+                ),
+                ("hash", string(nx_syntax::GRAMMAR_HASH)),
+            ]),
+        ),
+        ("features", NxValue::Array(features)),
+        (
+            "language_versions",
+            record([
+                ("oldest", string(versions[0].as_str())),
+                ("latest", string(LanguageVersion::LATEST.as_str())),
+                (
+                    "supported",
+                    NxValue::Array(
+                        versions
+                            .iter()
+                            .map(|version| string(version.as_str()))
+                            .collect(),
+                    ),
+                ),
+            ]),
+        ),
+    ])
+}
+
+fn string(text: &str) -> NxValue {
+    NxValue::String(text.to_string())
+}
+
+fn record<const N: usize>(properties: [(&str, NxValue); N]) -> NxValue {
+    NxValue::Record {
+        type_name: None,
+        properties: properties
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn property<'a>(value: &'a NxValue, name: &str) -> &'a NxValue {
+        match value {
+            NxValue::Record { properties, .. } => properties
+                .get(name)
+                .unwrap_or_else(|| panic!("missing property '{}'", name)),
+            other => panic!("expected a record, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn toolchain_info_reports_versions_grammar_and_language_range() {
+        let info = toolchain_info();
+
+        let version = string(env!("CARGO_PKG_VERSION"));
+        assert_eq!(property(&info, "version"), &version);
+        assert_eq!(property(property(&info, "crates"), "nx-syntax"), &version);
+        let grammar = property(&info, "grammar");
+        assert_eq!(property(grammar, "abi_version"), &NxValue::Int(14));
+        let NxValue::String(hash) = property(grammar, "hash") else {
+            panic!("grammar hash should be a string");
+        };
+        assert_eq!(hash.len(), 16);
+        assert_eq!(
+            property(&info, "features"),
+            &NxValue::Array(if cfg!(feature = "graphemes") {
+                vec![string("graphemes")]
+            } else {
+                Vec::new()
+            })
+        );
+        let languages = property(&info, "language_versions");
+        assert_eq!(property(languages, "oldest"), &string("1"));
+        assert_eq!(
+            property(languages, "latest"),
+            &string(LanguageVersion::LATEST.as_str())
+        );
+
+        let json = info.to_json_string().unwrap();
+        assert_eq!(NxValue::from_json_str(&json).unwrap(), info);
+    }
+}
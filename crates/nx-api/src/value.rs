@@ -1,5 +1,5 @@
 use nx_hir::Name;
-use nx_interpreter::Value;
+use nx_interpreter::{Money, Value};
 use nx_value::NxValue;
 use smol_str::SmolStr;
 use std::collections::BTreeMap;
@@ -24,6 +24,23 @@ impl FromNxValueError {
         }
     }
 
+    fn unsupported_partial_application(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            message: format!(
+                "NxValue at {path} encodes a PartialApplication record, but function values are \
+                 runtime-only and cannot be provided as host input"
+            ),
+        }
+    }
+
+    fn invalid_money(path: &str, reason: String) -> Self {
+        Self {
+            path: path.to_string(),
+            message: format!("NxValue at {path} encodes an invalid Money record: {reason}"),
+        }
+    }
+
     /// Returns the path to the invalid value within the input tree.
     pub fn path(&self) -> &str {
         self.path.as_str()
@@ -43,14 +60,24 @@ impl Error for FromNxValueError {}
 /// Scalar types (`Null`, `Boolean`, `Int`, `Float`, `String`) and arrays map directly.
 ///
 /// Record values become [`NxValue::Record`] with their `type_name` preserved and fields
-/// sorted alphabetically (via [`BTreeMap`]).
+/// sorted alphabetically (via [`BTreeMap`]). Elements are records too, and their attributes keep
+/// their evaluated types; renderers coerce them to text with [`NxValue::attribute_text`].
 ///
 /// Enum values become [`NxValue::String`] carrying the bare authored member name. The
 /// declaring enum type is not preserved on the wire; consumers recover it from the target
 /// schema (declared NX type, typed DTO property, or other type annotation).
 ///
-/// `Value::ActionHandler` is encoded as a record for display and inspection only. That shape is
-/// intentionally not round-trippable through [`from_nx_value`].
+/// `Value::ActionHandler` and `Value::PartialApplication` are encoded as records for display and
+/// inspection only. Those shapes are intentionally not round-trippable through [`from_nx_value`].
+///
+/// `Value::Money` becomes a `Money` record with a decimal string `amount`, such as `"12.50"`, and
+/// its three-letter `currency` code. [`from_nx_value`] turns a `Money` record with exactly those
+/// two string properties back into money.
+///
+/// `Value::Native` host values are converted through their [`NativeValue::to_value`] hook, and
+/// come back from [`from_nx_value`] as that plain data rather than as the host value.
+///
+/// [`NativeValue::to_value`]: nx_interpreter::NativeValue::to_value
 pub fn to_nx_value(value: &Value) -> NxValue {
     match value {
         Value::Null => NxValue::Null,
@@ -60,6 +87,16 @@ pub fn to_nx_value(value: &Value) -> NxValue {
         Value::Float32(value) => NxValue::Float32(*value),
         Value::Float(value) => NxValue::Float(*value),
         Value::String(value) => NxValue::String(value.to_string()),
+        Value::Money(money) => NxValue::Record {
+            type_name: Some("Money".to_string()),
+            properties: BTreeMap::from([
+                ("amount".to_string(), NxValue::String(money.amount())),
+                (
+                    "currency".to_string(),
+                    NxValue::String(money.currency().to_string()),
+                ),
+            ]),
+        },
         Value::Array(elements) => NxValue::Array(elements.iter().map(to_nx_value).collect()),
         Value::EnumValue { member, .. } => NxValue::String(member.to_string()),
         Value::Record { type_name, fields } => NxValue::Record {
@@ -88,13 +125,27 @@ pub fn to_nx_value(value: &Value) -> NxValue {
                 ),
             ]),
         },
+        Value::PartialApplication { function, args, .. } => NxValue::Record {
+            type_name: Some("PartialApplication".to_string()),
+            properties: BTreeMap::from([
+                (
+                    "function".to_string(),
+                    NxValue::String(function.as_str().to_string()),
+                ),
+                (
+                    "arity".to_string(),
+                    NxValue::Int(args.iter().filter(|arg| arg.is_none()).count() as i64),
+                ),
+            ]),
+        },
+        Value::Native(native) => to_nx_value(&native.get().to_value()),
     }
 }
 
 /// Converts a serializable [`NxValue`] into the interpreter [`Value`] representation.
 ///
 /// This reverse conversion rejects runtime-only values that do not have a faithful public
-/// encoding, such as `ActionHandler`.
+/// encoding, such as `ActionHandler` and `PartialApplication`.
 pub fn from_nx_value(value: &NxValue) -> Result<Value, FromNxValueError> {
     from_nx_value_at_path(value, "$")
 }
@@ -122,6 +173,18 @@ fn from_nx_value_at_path(value: &NxValue, path: &str) -> Result<Value, FromNxVal
             if type_name.as_deref() == Some("ActionHandler") {
                 return Err(FromNxValueError::unsupported_action_handler(path));
             }
+            if type_name.as_deref() == Some("PartialApplication") {
+                return Err(FromNxValueError::unsupported_partial_application(path));
+            }
+            if type_name.as_deref() == Some("Money") && properties.len() == 2 {
+                if let (Some(NxValue::String(amount)), Some(NxValue::String(currency))) =
+                    (properties.get("amount"), properties.get("currency"))
+                {
+                    return Money::parse(amount, currency)
+                        .map(Value::Money)
+                        .map_err(|reason| FromNxValueError::invalid_money(path, reason));
+                }
+            }
 
             Ok(Value::Record {
                 type_name: Name::new(type_name.as_deref().unwrap_or("object")),
@@ -187,6 +250,55 @@ mod tests {
         assert_eq!(to_nx_value(&runtime), NxValue::String("active".to_string()));
     }
 
+    #[test]
+    fn native_value_converts_through_its_to_value_hook() {
+        #[derive(Debug)]
+        struct Cents(i64);
+
+        impl nx_interpreter::NativeValue for Cents {
+            fn type_name(&self) -> &str {
+                "Cents"
+            }
+
+            fn to_value(&self) -> Value {
+                Value::Array(vec![Value::Int(self.0 / 100), Value::Int(self.0 % 100)])
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+
+        let runtime = Value::Native(nx_interpreter::NativeHandle::new(Cents(1250)));
+
+        assert_eq!(
+            to_nx_value(&runtime),
+            NxValue::Array(vec![NxValue::Int(12), NxValue::Int(50)])
+        );
+    }
+
+    #[test]
+    fn money_round_trips_as_a_record_with_its_currency() {
+        let runtime = Value::Money(Money::parse("1234.5", "EUR").unwrap());
+        let value = to_nx_value(&runtime);
+
+        assert_eq!(
+            value.to_json_string().unwrap(),
+            r#"{"$type":"Money","amount":"1234.50","currency":"EUR"}"#
+        );
+        assert_eq!(from_nx_value(&value).unwrap(), runtime);
+
+        let invalid = NxValue::Record {
+            type_name: Some("Money".to_string()),
+            properties: BTreeMap::from([
+                ("amount".to_string(), NxValue::String("1.234".to_string())),
+                ("currency".to_string(), NxValue::String("EUR".to_string())),
+            ]),
+        };
+        let error = from_nx_value(&NxValue::Array(vec![invalid])).unwrap_err();
+        assert_eq!(error.path(), "$[0]");
+    }
+
     #[test]
     fn from_nx_value_rejects_action_handler_records() {
         let value = NxValue::Record {
@@ -597,7 +597,9 @@ fn csharp_type_name_inner(
             is_reference: false,
             is_nullable: false,
         },
-        "object" | "unknown" | "error" => CSharpType {
+        // Money is serialized as `{"amount": "12.50", "currency": "EUR"}`, which no .NET type
+        // models without a money library.
+        "money" | "object" | "unknown" | "error" => CSharpType {
             text: "object".to_string(),
             is_reference: true,
             is_nullable: false,
@@ -764,7 +766,7 @@ fn csharp_imported_alias_target_name(
             is_reference: false,
             is_nullable: false,
         },
-        "object" | "unknown" | "error" => CSharpType {
+        "money" | "object" | "unknown" | "error" => CSharpType {
             text: "object".to_string(),
             is_reference: true,
             is_nullable: false,
@@ -854,6 +856,7 @@ fn imported_alias_target_uses_dependency_namespace(ty: &TypeRef) -> bool {
                 | "f64"
                 | "float"
                 | "bool"
+                | "money"
                 | "void"
                 | "object"
                 | "unknown"
@@ -700,6 +700,7 @@ fn ts_type_name(name: &str) -> String {
         "string" => "string".to_string(),
         "i32" | "i64" | "int" | "f32" | "f64" | "float" => "number".to_string(),
         "bool" => "boolean".to_string(),
+        "money" => "{ amount: string; currency: string }".to_string(),
         "void" => "void".to_string(),
         "object" => "unknown".to_string(),
         other => sanitize_ts_type_name(other),
@@ -1,8 +1,8 @@
 use nx_api::{build_library_artifact_from_directory, LibraryArtifact};
 use nx_hir::{
     ast::TypeRef, Component, EnumDef, ImportKind, InterfaceItemKind, Item, LoweredModule,
-    PreparedItemKind, RecordDef, RecordKind, SelectiveImport, TypeAlias, UnionCaseDef, UnionDef,
-    Visibility,
+    PreparedItemKind, RecordDef, RecordField, RecordKind, SelectiveImport, TypeAlias, UnionCaseDef,
+    UnionDef, Visibility,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::BTreeSet;
@@ -565,7 +565,7 @@ fn collect_exported_declarations(module: &LoweredModule) -> Vec<ExportedTypeDecl
             }),
             Item::Record(record) => declarations.push(ExportedTypeDecl {
                 visibility: record.visibility,
-                item: ExportedType::Record(export_record(record)),
+                item: ExportedType::Record(export_record(module, record)),
             }),
             Item::Component(component) => {
                 if let Some(record) = export_external_component_contract(component) {
@@ -811,7 +811,7 @@ impl CachedImportedLibrary {
 
     fn type_name_is_reference(&self, name: &str, seen_aliases: &mut BTreeSet<String>) -> bool {
         match name {
-            "string" | "object" | "unknown" | "error" => true,
+            "string" | "money" | "object" | "unknown" | "error" => true,
             "i32" | "i64" | "int" | "f32" | "f64" | "float" | "bool" | "void" => false,
             other => match self.export_kinds.get(other) {
                 Some(PreparedItemKind::Enum) => false,
@@ -939,21 +939,56 @@ fn export_enum(def: &EnumDef) -> ExportedEnum {
     }
 }
 
-fn export_record(def: &RecordDef) -> ExportedRecord {
+fn export_record(module: &LoweredModule, def: &RecordDef) -> ExportedRecord {
+    let mut fields = Vec::new();
+    let mut seen = FxHashSet::default();
+    for include in &def.includes {
+        collect_included_fields(module, include.as_str(), &mut fields, &mut seen);
+    }
+    fields.extend(def.properties.iter().map(export_record_field));
+
     ExportedRecord {
         name: def.name.as_str().to_string(),
         kind: def.kind,
         is_abstract: def.is_abstract,
         base: def.base.as_ref().map(|name| name.as_str().to_string()),
-        fields: def
-            .properties
-            .iter()
-            .map(|field| ExportedRecordField {
-                name: field.name.as_str().to_string(),
-                ty: field.ty.clone(),
-                has_default: field.default.is_some(),
-            })
-            .collect(),
+        fields,
+    }
+}
+
+/// Appends the fields that `type X = Name & ...` copies from `Name`: those of its base chain,
+/// its own included records, and its own properties. Fields reached twice through a shared
+/// record are kept once. Records outside `module` are not followed.
+fn collect_included_fields(
+    module: &LoweredModule,
+    name: &str,
+    fields: &mut Vec<ExportedRecordField>,
+    seen: &mut FxHashSet<String>,
+) {
+    if !seen.insert(name.to_string()) {
+        return;
+    }
+    let Some(Item::Record(record)) = module.find_item(name) else {
+        return;
+    };
+    if let Some(base) = &record.base {
+        collect_included_fields(module, base.as_str(), fields, seen);
+    }
+    for include in &record.includes {
+        collect_included_fields(module, include.as_str(), fields, seen);
+    }
+    for field in record.properties.iter().map(export_record_field) {
+        if !fields.iter().any(|existing| existing.name == field.name) {
+            fields.push(field);
+        }
+    }
+}
+
+fn export_record_field(field: &RecordField) -> ExportedRecordField {
+    ExportedRecordField {
+        name: field.name.as_str().to_string(),
+        ty: field.ty.clone(),
+        has_default: field.default.is_some(),
     }
 }
 
@@ -1072,6 +1107,35 @@ mod tests {
         assert!(base.is_abstract);
     }
 
+    #[test]
+    fn flattens_included_record_fields_into_composed_records() {
+        let source = r#"
+            type Entity = { id:int }
+            type User extends Entity = { name:string }
+            export type Audited = { createdBy:string = "system" }
+            export type Admin = User & Audited & { role:string }
+        "#;
+        let module = lower_module(source, "types.nx");
+        let graph = ExportedTypeGraph::from_module(&module, Path::new("types.nx")).unwrap();
+
+        let admin = graph.record("Admin").expect("admin record");
+        let fields = admin
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.has_default))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("id", false),
+                ("name", false),
+                ("createdBy", true),
+                ("role", false)
+            ]
+        );
+        assert!(admin.base.is_none());
+    }
+
     #[test]
     fn collects_transitive_concrete_descendants_across_modules() {
         let temp_dir = TempDir::new().expect("temp dir");
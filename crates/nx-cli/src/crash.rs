@@ -0,0 +1,305 @@
+//! Crash reports for internal panics.
+//!
+//! Commands run under [`run_reporting_crashes`]. When one panics, the pipeline stage, the panic
+//! message and location, and the toolchain version are written to a report file, and the user is
+//! asked to attach it to an issue. If the panic happened while processing a source file, the
+//! report also includes a minimized copy of that file: top-level items are removed by binary
+//! search for as long as the same panic still reproduces, so the snippet usually holds only the
+//! declarations needed to trigger it. Set `NX_CRASH_MINIMIZE=0` to leave the source out of
+//! reports, and `NX_CRASH_DIR` to choose where they are written instead of the temp directory.
+
+use crate::status::Status;
+use nx_api::{build_program_artifact_from_source, eval_source, ProgramBuildContext};
+use nx_types::Stage;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Upper bound on pipeline runs spent minimizing, so huge files still report promptly.
+const MAX_MINIMIZE_ATTEMPTS: usize = 128;
+
+/// The most recent panic seen by the hook.
+static LAST_PANIC: Mutex<Option<PanicDetails>> = Mutex::new(None);
+
+thread_local! {
+    /// The source file most recently read by the running command.
+    static CURRENT_SOURCE: RefCell<Option<(PathBuf, String)>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Debug)]
+struct PanicDetails {
+    message: String,
+    location: Option<String>,
+    stage: Option<Stage>,
+    backtrace: String,
+}
+
+/// Records the source text the command is about to process, for minimized crash repros.
+pub(crate) fn remember_source(path: &Path, source: &str) {
+    CURRENT_SOURCE
+        .with(|current| *current.borrow_mut() = Some((path.to_owned(), source.to_owned())));
+}
+
+/// Runs a command, turning an internal panic into a crash report and [`Status::Internal`].
+pub(crate) fn run_reporting_crashes(command: impl FnOnce() -> Status) -> Status {
+    panic::set_hook(Box::new(|info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        let details = PanicDetails {
+            message,
+            location: info.location().map(ToString::to_string),
+            stage: nx_types::current_stage(),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        };
+        *LAST_PANIC.lock().unwrap_or_else(|error| error.into_inner()) = Some(details);
+    }));
+
+    if let Ok(status) = panic::catch_unwind(AssertUnwindSafe(command)) {
+        let _ = panic::take_hook();
+        return status;
+    }
+
+    let details = take_last_panic().unwrap_or_else(|| PanicDetails {
+        message: "unknown panic".to_string(),
+        location: None,
+        stage: None,
+        backtrace: String::new(),
+    });
+    let source = CURRENT_SOURCE.with(|current| current.borrow_mut().take());
+    let minimize = std::env::var("NX_CRASH_MINIMIZE").map_or(true, |value| value != "0");
+    let repro = match &source {
+        Some((path, text)) if minimize => minimize_repro(path, text, &details),
+        _ => None,
+    };
+    let _ = panic::take_hook();
+
+    let report = format_report(
+        &details,
+        source.as_ref().map(|(path, _)| path.as_path()),
+        repro,
+    );
+    let stage = details.stage.map_or("unknown", |stage| stage.as_str());
+    eprintln!(
+        "Internal error: nxlang crashed in the {} stage: {}",
+        stage, details.message
+    );
+    match write_report(&report) {
+        Ok(path) => {
+            eprintln!("A crash report was written to {}", path.display());
+            eprintln!(
+                "Please attach it to a new issue at {}/issues",
+                env!("CARGO_PKG_REPOSITORY")
+            );
+        }
+        Err(error) => {
+            eprintln!("Failed to write the crash report: {}", error);
+            eprintln!(
+                "Please report this at {}/issues with the report below:\n\n{}",
+                env!("CARGO_PKG_REPOSITORY"),
+                report
+            );
+        }
+    }
+    Status::Internal
+}
+
+fn take_last_panic() -> Option<PanicDetails> {
+    LAST_PANIC
+        .lock()
+        .unwrap_or_else(|error| error.into_inner())
+        .take()
+}
+
+/// A minimized source that still triggers the original panic.
+struct Repro {
+    source: String,
+    kept_items: usize,
+    total_items: usize,
+}
+
+/// Minimizes `source` against the pipeline stage that panicked.
+///
+/// A candidate reproduces when rerunning the pipeline, without project imports, panics at the
+/// same location. Returns `None` when even the full source does not reproduce that way.
+fn minimize_repro(path: &Path, source: &str, original: &PanicDetails) -> Option<Repro> {
+    let file_name = path.display().to_string();
+    let context = ProgramBuildContext::empty();
+    let runs_eval = matches!(original.stage, Some(Stage::Eval | Stage::Serialize));
+    minimize_items(source, |candidate| {
+        let panicked = panic::catch_unwind(AssertUnwindSafe(|| {
+            if runs_eval {
+                let _ = eval_source(candidate, &file_name, &context);
+            } else {
+                let _ = build_program_artifact_from_source(candidate, &file_name, &context);
+            }
+        }))
+        .is_err();
+        let location = take_last_panic().and_then(|details| details.location);
+        panicked && location == original.location
+    })
+}
+
+/// Removes top-level items from `source` while `reproduces` still holds.
+///
+/// Items are removed in chunks, starting with halves and shrinking to single items, which finds
+/// the items a crash needs in a logarithmic number of runs when only a few of them matter.
+fn minimize_items(source: &str, mut reproduces: impl FnMut(&str) -> bool) -> Option<Repro> {
+    if !reproduces(source) {
+        return None;
+    }
+
+    let mut items = top_level_items(source);
+    let total_items = items.len();
+    let mut attempts = 0;
+    let mut chunk = items.len() / 2;
+    while chunk > 0 && attempts < MAX_MINIMIZE_ATTEMPTS {
+        let mut start = 0;
+        while start < items.len() && attempts < MAX_MINIMIZE_ATTEMPTS {
+            let end = (start + chunk).min(items.len());
+            let candidate: Vec<_> = items[..start]
+                .iter()
+                .chain(&items[end..])
+                .cloned()
+                .collect();
+            attempts += 1;
+            if !candidate.is_empty() && reproduces(&candidate.join("\n\n")) {
+                items = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+
+    Some(Repro {
+        source: items.join("\n\n"),
+        kept_items: items.len(),
+        total_items,
+    })
+}
+
+/// Splits a source file into the text of its top-level items.
+///
+/// When the file cannot be split, for instance because parsing itself panics, the whole file is
+/// treated as a single item.
+fn top_level_items(source: &str) -> Vec<String> {
+    let items = panic::catch_unwind(|| {
+        let parse = nx_syntax::parse_str(source, "crash.nx");
+        parse
+            .root()
+            .map(|root| {
+                root.children()
+                    .map(|item| item.text().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    });
+    let _ = take_last_panic();
+    match items {
+        Ok(items) if !items.is_empty() => items,
+        _ => vec![source.to_string()],
+    }
+}
+
+fn format_report(details: &PanicDetails, input: Option<&Path>, repro: Option<Repro>) -> String {
+    let mut report = String::from("nxlang crash report\n\n");
+    let command = std::env::args().collect::<Vec<_>>().join(" ");
+    let lines = [
+        ("version", env!("CARGO_PKG_VERSION").to_string()),
+        (
+            "platform",
+            format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        ),
+        ("command", command),
+        (
+            "stage",
+            details
+                .stage
+                .map_or("unknown".to_string(), |stage| stage.to_string()),
+        ),
+        ("panic", details.message.clone()),
+        (
+            "location",
+            details.location.clone().unwrap_or("unknown".to_string()),
+        ),
+        (
+            "input",
+            input.map_or("none".to_string(), |path| path.display().to_string()),
+        ),
+    ];
+    for (key, value) in lines {
+        writeln!(report, "{}: {}", key, value).unwrap();
+    }
+
+    match repro {
+        Some(repro) => writeln!(
+            report,
+            "\nminimized source ({} of {} top-level items):\n```nx\n{}\n```",
+            repro.kept_items, repro.total_items, repro.source
+        )
+        .unwrap(),
+        None => report.push_str("\nminimized source: not available\n"),
+    }
+    if !details.backtrace.is_empty() {
+        write!(report, "\nbacktrace:\n{}", details.backtrace).unwrap();
+    }
+    report
+}
+
+fn write_report(report: &str) -> std::io::Result<PathBuf> {
+    let dir = std::env::var_os("NX_CRASH_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    std::fs::create_dir_all(&dir)?;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let path = dir.join(format!(
+        "nxlang-crash-{}-{}.txt",
+        timestamp,
+        std::process::id()
+    ));
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize_items_keeps_only_items_needed_to_reproduce() {
+        let source = [
+            "let a = 1",
+            "let b = 2",
+            "type Bad = { x:int }",
+            "let c = 3",
+            "let d = 4",
+            "let useBad = {Bad}",
+            "let e = 5",
+        ]
+        .join("\n");
+
+        let mut runs = 0;
+        let repro = minimize_items(&source, |candidate| {
+            runs += 1;
+            candidate.contains("type Bad") && candidate.contains("useBad")
+        })
+        .expect("full source reproduces");
+
+        assert_eq!(repro.source, "type Bad = { x:int }\n\nlet useBad = {Bad}");
+        assert_eq!((repro.kept_items, repro.total_items), (2, 7));
+        assert!(runs <= MAX_MINIMIZE_ATTEMPTS + 1);
+    }
+
+    #[test]
+    fn test_minimize_items_requires_the_full_source_to_reproduce() {
+        assert!(minimize_items("let a = 1", |_| false).is_none());
+    }
+}
@@ -0,0 +1,247 @@
+//! Graphviz rendering of evaluation results.
+//!
+//! Each record or element becomes a node labelled with its tag and a summary of its scalar
+//! properties. Record and array properties become edges to child nodes, labelled with the
+//! property name, so deeply nested component output can be inspected as a tree with `dot -Tsvg`.
+
+use nx_interpreter::Value;
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+use std::fmt::Write;
+
+/// Scalar properties shown in a node label before the rest are summarized as a count.
+const MAX_LABEL_PROPERTIES: usize = 6;
+
+/// Property values longer than this many characters are shortened in node labels.
+const MAX_PROPERTY_CHARS: usize = 32;
+
+/// Formats a value as a Graphviz `digraph`.
+///
+/// A top-level array produces one tree per item. Scalars produce a single node.
+pub fn format_value_dot(value: &Value) -> String {
+    let mut graph = DotGraph::default();
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                graph.add_value(item);
+            }
+        }
+        other => {
+            graph.add_value(other);
+        }
+    }
+    graph.finish()
+}
+
+#[derive(Default)]
+struct DotGraph {
+    next_id: usize,
+    lines: Vec<String>,
+}
+
+impl DotGraph {
+    /// Adds a node for `value` and its descendants, returning the node's id.
+    fn add_value(&mut self, value: &Value) -> usize {
+        match value {
+            Value::Record { type_name, fields } => self.add_record(type_name.as_str(), fields),
+            Value::Array(items) => {
+                let id = self.add_node(&format!("[{}]", items.len()), "ellipse");
+                self.add_items(id, "", items);
+                id
+            }
+            Value::ActionHandler {
+                component,
+                emit,
+                action_name,
+                ..
+            } => self.add_node(
+                &format!(
+                    "ActionHandler\ncomponent={}\nemit={}\naction={}",
+                    component, emit, action_name
+                ),
+                "box",
+            ),
+            scalar => self.add_node(&scalar_text(scalar), "plaintext"),
+        }
+    }
+
+    fn add_record(&mut self, tag: &str, fields: &FxHashMap<SmolStr, Value>) -> usize {
+        let mut fields: Vec<_> = fields.iter().collect();
+        fields.sort_by_key(|(name, _)| name.as_str());
+
+        let scalars: Vec<_> = fields
+            .iter()
+            .filter(|(_, value)| !is_nested(value) && !value.is_null())
+            .collect();
+        let mut label = tag.to_string();
+        for (name, value) in scalars.iter().take(MAX_LABEL_PROPERTIES) {
+            write!(
+                label,
+                "\n{}={}",
+                name,
+                shorten(&scalar_text(value), MAX_PROPERTY_CHARS)
+            )
+            .unwrap();
+        }
+        if scalars.len() > MAX_LABEL_PROPERTIES {
+            write!(label, "\n+{} more", scalars.len() - MAX_LABEL_PROPERTIES).unwrap();
+        }
+        let id = self.add_node(&label, "box");
+
+        for (name, value) in fields {
+            match value {
+                Value::Array(items) => self.add_items(id, name.as_str(), items),
+                nested if is_nested(nested) => {
+                    let child = self.add_value(nested);
+                    self.add_edge(id, child, name.as_str());
+                }
+                _ => {}
+            }
+        }
+        id
+    }
+
+    /// Adds an edge from `parent` to each array item, labelled `name[index]`.
+    fn add_items(&mut self, parent: usize, name: &str, items: &[Value]) {
+        for (index, item) in items.iter().enumerate() {
+            let child = self.add_value(item);
+            self.add_edge(parent, child, &format!("{}[{}]", name, index));
+        }
+    }
+
+    fn add_node(&mut self, label: &str, shape: &str) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.lines.push(format!(
+            "  n{} [label=\"{}\", shape={}];",
+            id,
+            escape_label(label),
+            shape
+        ));
+        id
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, label: &str) {
+        self.lines.push(format!(
+            "  n{} -> n{} [label=\"{}\"];",
+            from,
+            to,
+            escape_label(label)
+        ));
+    }
+
+    fn finish(self) -> String {
+        let mut output = String::from("digraph nx {\n  node [fontname=\"monospace\"];\n");
+        for line in self.lines {
+            output.push_str(&line);
+            output.push('\n');
+        }
+        output.push('}');
+        output
+    }
+}
+
+fn is_nested(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Record { .. } | Value::Array(_) | Value::ActionHandler { .. }
+    )
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => format!("{:?}", text.as_str()),
+        Value::EnumValue { type_name, member } => format!("{}.{}", type_name, member),
+        other => other.to_string(),
+    }
+}
+
+fn shorten(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut shortened: String = text.chars().take(max_chars - 1).collect();
+        shortened.push('…');
+        shortened
+    }
+}
+
+/// Escapes a label for a double-quoted DOT string; newlines become left-aligned line breaks.
+fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for ch in label.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\l"),
+            ch => escaped.push(ch),
+        }
+    }
+    if label.contains('\n') {
+        escaped.push_str("\\l");
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_hir::Name;
+
+    fn record(tag: &str, fields: Vec<(&str, Value)>) -> Value {
+        Value::Record {
+            type_name: Name::new(tag),
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| (SmolStr::new(name), value))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_format_value_dot_links_children_by_property() {
+        let value = record(
+            "div",
+            vec![
+                ("class", Value::String(SmolStr::new("card"))),
+                (
+                    "content",
+                    Value::Array(vec![
+                        record("h1", vec![("title", Value::String(SmolStr::new("Hi")))]),
+                        Value::String(SmolStr::new("body")),
+                    ]),
+                ),
+                ("hidden", Value::Null),
+            ],
+        );
+
+        assert_eq!(
+            format_value_dot(&value),
+            r#"digraph nx {
+  node [fontname="monospace"];
+  n0 [label="div\lclass=\"card\"\l", shape=box];
+  n1 [label="h1\ltitle=\"Hi\"\l", shape=box];
+  n0 -> n1 [label="content[0]"];
+  n2 [label="\"body\"", shape=plaintext];
+  n0 -> n2 [label="content[1]"];
+}"#
+        );
+    }
+
+    #[test]
+    fn test_format_value_dot_summarizes_long_and_many_properties() {
+        let fields = ["a", "b", "c", "d", "e", "f", "g", "h"]
+            .into_iter()
+            .map(|name| (name, Value::Int(1)))
+            .chain([("z", Value::String(SmolStr::new("x".repeat(40))))])
+            .collect();
+        let dot = format_value_dot(&record("Card", fields));
+
+        assert!(dot.contains("f=1\\l+3 more\\l"));
+        assert!(!dot.contains("g=1"));
+        assert_eq!(
+            shorten(&"x".repeat(40), MAX_PROPERTY_CHARS).chars().count(),
+            32
+        );
+    }
+}
@@ -3,7 +3,9 @@
 //! This module provides formatting of runtime values in NX syntax,
 //! which resembles XML with self-closing tags for elements.
 
+use nx_api::to_nx_value;
 use nx_interpreter::Value;
+use nx_value::{AttributeText, BooleanAttributes};
 use rustc_hash::FxHashMap;
 use smol_str::SmolStr;
 use std::fmt::Write;
@@ -30,6 +32,7 @@ fn format_value_inner(value: &Value, output: &mut String, indent: usize) {
         Value::String(s) => output.push_str(s.as_str()),
         Value::Boolean(b) => write!(output, "{}", b).unwrap(),
         Value::Null => output.push_str("null"),
+        Value::Money(money) => write!(output, "{}", money).unwrap(),
 
         // Enum value
         Value::EnumValue { type_name, member } => {
@@ -64,6 +67,9 @@ fn format_value_inner(value: &Value, output: &mut String, indent: usize) {
             )
             .unwrap();
         }
+        // Partial applications print as the call they stand for, such as `add(_, 10)`
+        Value::PartialApplication { .. } => write!(output, "{}", value).unwrap(),
+        Value::Native(native) => format_value_inner(&native.get().to_value(), output, indent),
     }
 }
 
@@ -87,10 +93,7 @@ fn format_record_with_name(
         // Print simple attributes on the opening tag
         for (key, value) in &field_vec {
             if !is_complex_value(value) {
-                output.push(' ');
-                output.push_str(key.as_str());
-                output.push('=');
-                format_attribute_value(value, output);
+                format_attribute(key.as_str(), value, output);
             }
         }
 
@@ -111,10 +114,7 @@ fn format_record_with_name(
         write!(output, "<{}", tag_name).unwrap();
 
         for (key, value) in &field_vec {
-            output.push(' ');
-            output.push_str(key.as_str());
-            output.push('=');
-            format_attribute_value(value, output);
+            format_attribute(key.as_str(), value, output);
         }
 
         output.push_str(" />");
@@ -158,8 +158,7 @@ fn format_nested_element(tag_name: &str, value: &Value, output: &mut String, ind
         _ => {
             // Simple value - shouldn't happen for complex values but handle gracefully
             write!(output, "<{}", tag_name).unwrap();
-            output.push('=');
-            format_attribute_value(value, output);
+            format_attribute("value", value, output);
             output.push_str(" />\n");
         }
     }
@@ -182,10 +181,7 @@ fn format_nested_record(
         // Print simple attrs, then children
         for (key, val) in &field_vec {
             if !is_complex_value(val) {
-                output.push(' ');
-                output.push_str(key.as_str());
-                output.push('=');
-                format_attribute_value(val, output);
+                format_attribute(key.as_str(), val, output);
             }
         }
         output.push_str(">\n");
@@ -203,32 +199,34 @@ fn format_nested_record(
     } else {
         // All simple - inline
         for (key, val) in &field_vec {
-            output.push(' ');
-            output.push_str(key.as_str());
-            output.push('=');
-            format_attribute_value(val, output);
+            format_attribute(key.as_str(), val, output);
         }
         output.push_str(" />\n");
     }
 }
 
-fn format_attribute_value(value: &Value, output: &mut String) {
-    match value {
-        Value::String(s) => write!(output, "\"{}\"", escape_string(s.as_str())).unwrap(),
-        Value::Int32(n) => write!(output, "\"{}\"", n).unwrap(),
-        Value::Int(n) => write!(output, "\"{}\"", n).unwrap(),
-        Value::Float32(f) => write!(output, "\"{}\"", f).unwrap(),
-        Value::Float(f) => write!(output, "\"{}\"", f).unwrap(),
-        Value::Boolean(b) => write!(output, "\"{}\"", b).unwrap(),
-        Value::Null => output.push_str("\"null\""),
-        Value::EnumValue { type_name, member } => {
-            write!(output, "\"{}.{}\"", type_name, member).unwrap()
-        }
-        Value::ActionHandler { .. } | Value::Array(_) | Value::Record { .. } => {
-            // Complex values shouldn't be formatted as attributes
-            output.push_str("\"...\"");
-        }
-    }
+/// Write ` key="value"`, coercing typed attribute values to text at this point only. Null
+/// attributes are left out.
+fn format_attribute(key: &str, value: &Value, output: &mut String) {
+    let text = match value {
+        Value::EnumValue { type_name, member } => format!("{}.{}", type_name, member),
+        Value::Money(money) => money.to_string(),
+        Value::Native(native) => return format_attribute(key, &native.get().to_value(), output),
+        // Complex values shouldn't be formatted as attributes
+        Value::ActionHandler { .. }
+        | Value::PartialApplication { .. }
+        | Value::Array(_)
+        | Value::Record { .. } => "...".to_string(),
+        scalar => match to_nx_value(scalar).attribute_text(BooleanAttributes::Text) {
+            Some(AttributeText::Text(text)) => text,
+            Some(AttributeText::Present) => {
+                write!(output, " {}", key).unwrap();
+                return;
+            }
+            Some(AttributeText::Omitted) | None => return,
+        },
+    };
+    write!(output, " {}=\"{}\"", key, escape_string(&text)).unwrap();
 }
 
 fn is_complex_value(value: &Value) -> bool {
@@ -300,6 +298,25 @@ mod tests {
         assert!(output.contains("/>"));
     }
 
+    #[test]
+    fn test_format_record_coerces_attributes_at_render_time() {
+        let mut fields = FxHashMap::default();
+        fields.insert(SmolStr::new("tabindex"), Value::Int(3));
+        fields.insert(SmolStr::new("hidden"), Value::Boolean(true));
+        fields.insert(SmolStr::new("ratio"), Value::Float(0.5));
+        fields.insert(SmolStr::new("title"), Value::Null);
+
+        let value = Value::Record {
+            type_name: nx_hir::Name::new("div"),
+            fields,
+        };
+
+        assert_eq!(
+            format_value(&value),
+            "<div hidden=\"true\" ratio=\"0.5\" tabindex=\"3\" />"
+        );
+    }
+
     #[test]
     fn test_format_enum_value() {
         let value = Value::EnumValue {
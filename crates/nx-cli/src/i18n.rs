@@ -0,0 +1,235 @@
+//! Message extraction for `nxlang i18n extract` and catalog loading for `nxlang run --translations`.
+//!
+//! Extraction lists every message passed to the `t` builtin across the files it is given. The
+//! JSON catalog is the object [`MessageCatalog`] deserializes, with every translation empty. The
+//! PO catalog is a gettext template: one `msgid` per message, preceded by `#:` comments giving
+//! the file and line of each use, for translation tools that expect that format.
+
+use nx_hir::{lower, SourceId};
+use nx_interpreter::MessageCatalog;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// How `nxlang i18n extract` writes its catalog.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CatalogFormat {
+    Json,
+    Po,
+}
+
+impl std::fmt::Display for CatalogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CatalogFormat::Json => write!(f, "json"),
+            CatalogFormat::Po => write!(f, "po"),
+        }
+    }
+}
+
+/// The messages found in a set of files, each with the `file:line` locations that use it.
+#[derive(Debug, Default)]
+pub(crate) struct Extraction {
+    messages: BTreeMap<String, Vec<String>>,
+    /// Locations of `t` calls whose message is not a string literal.
+    pub(crate) computed: Vec<String>,
+}
+
+impl Extraction {
+    /// Adds the messages of `source`, using the recovered tree if it has syntax errors.
+    pub(crate) fn collect(&mut self, file: &str, source: &str) {
+        let parse_result = nx_syntax::parse_str(source, file);
+        let Some(root) = parse_result.root() else {
+            return;
+        };
+        let module = lower(root, SourceId::new(0));
+        for reference in nx_types::message_references(&module) {
+            let location = format!(
+                "{}:{}",
+                file,
+                line_of(source, reference.span.start().into())
+            );
+            match reference.message {
+                Some(message) => self.messages.entry(message).or_default().push(location),
+                None => self.computed.push(location),
+            }
+        }
+    }
+
+    /// Renders the catalog in `format`.
+    pub(crate) fn format(&self, format: CatalogFormat) -> String {
+        match format {
+            CatalogFormat::Json => {
+                let catalog: MessageCatalog =
+                    self.messages.keys().map(|message| (message, "")).collect();
+                serde_json::to_string_pretty(&catalog).expect("catalog should serialize")
+            }
+            CatalogFormat::Po => {
+                let mut out = String::from(
+                    "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n",
+                );
+                for (message, locations) in &self.messages {
+                    out.push('\n');
+                    for location in locations {
+                        out.push_str(&format!("#: {}\n", location));
+                    }
+                    out.push_str(&format!("msgid \"{}\"\nmsgstr \"\"\n", escape_po(message)));
+                }
+                out
+            }
+        }
+    }
+}
+
+/// Reads a catalog for `nxlang run --translations`: a `.po` file, or a JSON object from message
+/// to translation.
+pub(crate) fn read_catalog(path: &Path) -> Result<MessageCatalog, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read '{}': {}", path.display(), error))?;
+    if path.extension().and_then(|ext| ext.to_str()) == Some("po") {
+        parse_po(&text).map_err(|error| format!("Invalid catalog '{}': {}", path.display(), error))
+    } else {
+        serde_json::from_str(&text)
+            .map_err(|error| format!("Invalid catalog '{}': {}", path.display(), error))
+    }
+}
+
+/// Parses the `msgid`/`msgstr` pairs of a PO file, skipping comments and the header entry.
+fn parse_po(text: &str) -> Result<MessageCatalog, String> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Field {
+        None,
+        Id,
+        Str,
+    }
+
+    let mut catalog = MessageCatalog::new();
+    let mut field = Field::None;
+    let mut msgid = String::new();
+    let mut msgstr = String::new();
+    let mut finish = |msgid: &mut String, msgstr: &mut String| {
+        if !msgid.is_empty() {
+            catalog.insert(std::mem::take(msgid), std::mem::take(msgstr));
+        }
+        msgid.clear();
+        msgstr.clear();
+    };
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let (next, rest) = if let Some(rest) = line.strip_prefix("msgid ") {
+            finish(&mut msgid, &mut msgstr);
+            (Field::Id, rest)
+        } else if let Some(rest) = line.strip_prefix("msgstr ") {
+            (Field::Str, rest)
+        } else if line.starts_with('"') && field != Field::None {
+            (field, line)
+        } else if line.is_empty() || line.starts_with('#') {
+            continue;
+        } else {
+            return Err(format!("unexpected line {}: {}", index + 1, line));
+        };
+        let text = unescape_po(rest).ok_or_else(|| format!("bad string on line {}", index + 1))?;
+        match next {
+            Field::Id => msgid.push_str(&text),
+            Field::Str => msgstr.push_str(&text),
+            Field::None => {}
+        }
+        field = next;
+    }
+    finish(&mut msgid, &mut msgstr);
+    Ok(catalog)
+}
+
+fn escape_po(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Decodes a quoted PO string, or returns `None` if it is not one.
+fn unescape_po(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            other => out.push(other),
+        }
+    }
+    Some(out)
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source
+        .get(..offset)
+        .map_or(1, |prefix| prefix.matches('\n').count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"let title(name:string) = { t("Hello, {0}!", [name]) }
+let label(key:string) = { t(key) }
+let root() = <main>
+  <h1>{title("Ada")}</h1>
+  <p>{t("Welcome back")}</p>
+  <p>{t("Hello, {0}!", ["Grace"])}</p>
+</main>
+"#;
+
+    fn extraction() -> Extraction {
+        let mut extraction = Extraction::default();
+        extraction.collect("app.nx", SOURCE);
+        extraction
+    }
+
+    #[test]
+    fn json_catalog_lists_each_message_once_with_empty_translations() {
+        let extraction = extraction();
+        assert_eq!(extraction.computed, vec!["app.nx:2"]);
+        let json: serde_json::Value =
+            serde_json::from_str(&extraction.format(CatalogFormat::Json)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "Hello, {0}!": "", "Welcome back": "" })
+        );
+    }
+
+    #[test]
+    fn po_catalog_round_trips_through_the_parser() {
+        let po = extraction().format(CatalogFormat::Po);
+        assert!(po.contains("#: app.nx:1\n#: app.nx:6\nmsgid \"Hello, {0}!\"\n"));
+        assert!(po.contains("msgid \"Welcome back\"\nmsgstr \"\"\n"));
+
+        let translated = po.replace(
+            "msgid \"Hello, {0}!\"\nmsgstr \"\"",
+            "msgid \"Hello, {0}!\"\nmsgstr \"Bonjour, \"\n\"{0} !\"",
+        );
+        let catalog = parse_po(&translated).unwrap();
+        assert_eq!(catalog.len(), 2);
+        assert_eq!(catalog.translate("Hello, {0}!"), Some("Bonjour, {0} !"));
+        assert_eq!(catalog.translate("Welcome back"), None);
+
+        let escaped = escape_po("Say \"hi\"\n");
+        assert_eq!(escaped, r#"Say \"hi\"\n"#);
+        assert_eq!(
+            unescape_po(&format!("\"{}\"", escaped)).as_deref(),
+            Some("Say \"hi\"\n")
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Expansion of command-line input paths into NX source files.
+//!
+//! Inputs may be plain file paths, directories (searched recursively for `.nx` files), or glob
+//! patterns such as `src/**/*.nx`. Patterns are expanded here so that they also work when the
+//! shell passes them through unexpanded. A single `-` reads the source from stdin instead.
+
+use nx_types::{time_stage, Stage};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// The input name that selects stdin.
+const STDIN_INPUT: &str = "-";
+
+/// File name used in diagnostics for stdin when `--stdin-filename` is not given.
+const DEFAULT_STDIN_FILE_NAME: &str = "<stdin>";
+
+/// A source to process: a file on disk or standard input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SourceInput {
+    File(PathBuf),
+    Stdin {
+        /// Name reported in diagnostics for the piped source.
+        file_name: PathBuf,
+    },
+}
+
+impl SourceInput {
+    /// Returns the path used to identify this input in output and diagnostics.
+    pub(crate) fn path(&self) -> &Path {
+        match self {
+            SourceInput::File(path) => path,
+            SourceInput::Stdin { file_name } => file_name,
+        }
+    }
+
+    /// Returns the name that identifies this input in diagnostics. See [`display_name`].
+    pub(crate) fn display_name(&self) -> String {
+        display_name(self.path())
+    }
+
+    /// Reads the full source text of this input.
+    ///
+    /// The text is remembered so that a crash while processing it can report a minimized repro.
+    pub(crate) fn read_source(&self) -> Result<String, String> {
+        let source = time_stage(Stage::Read, || match self {
+            SourceInput::File(path) => std::fs::read_to_string(path)
+                .map_err(|error| format!("Failed to read file '{}': {}", path.display(), error)),
+            SourceInput::Stdin { .. } => {
+                let mut source = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut source)
+                    .map_err(|error| format!("Failed to read stdin: {}", error))?;
+                Ok(source)
+            }
+        })?;
+        crate::crash::remember_source(self.path(), &source);
+        Ok(source)
+    }
+}
+
+/// Returns the name that identifies `path` in diagnostics: its path relative to the current
+/// directory when it lies below it, with forward slashes on every platform.
+pub(crate) fn display_name(path: &Path) -> String {
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    nx_diagnostics::display_path(path, &current_dir)
+}
+
+/// Resolves command-line inputs into sources.
+///
+/// `-` selects stdin and must be the only input; `stdin_filename` names it in diagnostics and is
+/// rejected when stdin is not being read. Other inputs are expanded with [`expand_inputs`], and
+/// files found through a directory or pattern are skipped when they match an `exclude` pattern.
+pub(crate) fn resolve_inputs(
+    inputs: &[PathBuf],
+    stdin_filename: Option<&Path>,
+    exclude: &[PathBuf],
+) -> Result<Vec<SourceInput>, String> {
+    if inputs.iter().any(|input| input.as_os_str() == STDIN_INPUT) {
+        if inputs.len() > 1 {
+            return Err("'-' (stdin) cannot be combined with other inputs".to_string());
+        }
+        let file_name = stdin_filename
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_STDIN_FILE_NAME));
+        return Ok(vec![SourceInput::Stdin { file_name }]);
+    }
+
+    if stdin_filename.is_some() {
+        return Err("--stdin-filename can only be used when reading from stdin ('-')".to_string());
+    }
+
+    let exclude = exclude
+        .iter()
+        .map(|pattern| {
+            glob::Pattern::new(&pattern.to_string_lossy()).map_err(|error| {
+                format!("Invalid exclude pattern '{}': {}", pattern.display(), error)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(expand_inputs(inputs, &exclude)?
+        .into_iter()
+        .map(SourceInput::File)
+        .collect())
+}
+
+/// Expands the given inputs into a deduplicated list of files, preserving the order in which
+/// they were first named. Files inside a directory or matched by a pattern are sorted by path.
+fn expand_inputs(inputs: &[PathBuf], exclude: &[glob::Pattern]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+
+    for input in inputs {
+        for file in expand_input(input, exclude)? {
+            if seen.insert(file.clone()) {
+                files.push(file);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn expand_input(input: &Path, exclude: &[glob::Pattern]) -> Result<Vec<PathBuf>, String> {
+    if input.is_dir() {
+        let mut files = Vec::new();
+        collect_nx_files(input, &mut files)
+            .map_err(|error| format!("Error reading directory '{}': {}", input.display(), error))?;
+        files.retain(|file| !is_excluded(file, exclude));
+        if files.is_empty() {
+            return Err(format!("No .nx files found in '{}'", input.display()));
+        }
+        files.sort();
+        return Ok(files);
+    }
+
+    if input.exists() {
+        return Ok(vec![input.to_path_buf()]);
+    }
+
+    let pattern = input.to_string_lossy();
+    if !is_glob_pattern(&pattern) {
+        return Err(format!("File not found: {}", input.display()));
+    }
+
+    let paths = glob::glob(&pattern)
+        .map_err(|error| format!("Invalid pattern '{}': {}", pattern, error))?;
+    let mut files = Vec::new();
+    for path in paths {
+        let path = path.map_err(|error| format!("Error reading '{}': {}", pattern, error))?;
+        if path.is_file() && !is_excluded(&path, exclude) {
+            files.push(path);
+        }
+    }
+    if files.is_empty() {
+        return Err(format!("No files match '{}'", pattern));
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn is_excluded(file: &Path, exclude: &[glob::Pattern]) -> bool {
+    if exclude.is_empty() {
+        return false;
+    }
+    let file = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+    exclude.iter().any(|pattern| pattern.matches_path(&file))
+}
+
+fn is_glob_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+fn collect_nx_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_nx_files(&path, files)?;
+        } else if path.extension().and_then(|extension| extension.to_str()) == Some("nx") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_tree(files: &[&str]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for relative_path in files {
+            let path = dir.path().join(relative_path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "let root() = { 1 }").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_directory_expands_to_sorted_nx_files() {
+        let dir = create_tree(&["b.nx", "a/c.nx", "a/readme.md", "a.nx"]);
+
+        let files = expand_inputs(&[dir.path().to_path_buf()], &[]).unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                dir.path().join("a").join("c.nx"),
+                dir.path().join("a.nx"),
+                dir.path().join("b.nx"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_glob_pattern_expands_recursively() {
+        let dir = create_tree(&["src/one.nx", "src/nested/two.nx", "src/other.txt"]);
+        let pattern = dir.path().join("src").join("**").join("*.nx");
+
+        let files = expand_inputs(&[pattern], &[]).unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                dir.path().join("src").join("nested").join("two.nx"),
+                dir.path().join("src").join("one.nx"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inputs_are_deduplicated_in_order() {
+        let dir = create_tree(&["a.nx", "b.nx"]);
+        let b = dir.path().join("b.nx");
+
+        let files = expand_inputs(&[b.clone(), dir.path().to_path_buf()], &[]).unwrap();
+
+        assert_eq!(files, vec![b, dir.path().join("a.nx")]);
+    }
+
+    #[test]
+    fn test_missing_inputs_are_reported() {
+        let dir = create_tree(&["notes.txt"]);
+
+        let missing = expand_inputs(&[dir.path().join("missing.nx")], &[]).unwrap_err();
+        assert!(missing.contains("File not found"));
+
+        let unmatched = expand_inputs(&[dir.path().join("*.nx")], &[]).unwrap_err();
+        assert!(unmatched.contains("No files match"));
+
+        let empty = expand_inputs(&[dir.path().to_path_buf()], &[]).unwrap_err();
+        assert!(empty.contains("No .nx files found"));
+    }
+
+    #[test]
+    fn test_exclude_patterns_skip_expanded_files_only() {
+        let dir = create_tree(&["src/a.nx", "src/generated/b.nx"]);
+        let generated = dir.path().join("src").join("generated").join("b.nx");
+        let exclude = [dir.path().join("src").join("generated").join("**")];
+
+        let inputs = resolve_inputs(&[dir.path().join("src")], None, &exclude).unwrap();
+        assert_eq!(
+            inputs,
+            vec![SourceInput::File(dir.path().join("src").join("a.nx"))]
+        );
+
+        let explicit = resolve_inputs(std::slice::from_ref(&generated), None, &exclude).unwrap();
+        assert_eq!(explicit, vec![SourceInput::File(generated)]);
+    }
+
+    #[test]
+    fn test_stdin_input_uses_given_file_name() {
+        let inputs =
+            resolve_inputs(&[PathBuf::from("-")], Some(Path::new("src/app.nx")), &[]).unwrap();
+        assert_eq!(
+            inputs,
+            vec![SourceInput::Stdin {
+                file_name: PathBuf::from("src/app.nx")
+            }]
+        );
+
+        let inputs = resolve_inputs(&[PathBuf::from("-")], None, &[]).unwrap();
+        assert_eq!(inputs[0].path(), Path::new("<stdin>"));
+    }
+
+    #[test]
+    fn test_display_name_is_relative_to_current_dir() {
+        let current_dir = std::env::current_dir().unwrap();
+        let input = SourceInput::File(current_dir.join("src").join("app.nx"));
+        assert_eq!(input.display_name(), "src/app.nx");
+        assert_eq!(display_name(Path::new("./src//app.nx")), "src/app.nx");
+
+        let stdin = SourceInput::Stdin {
+            file_name: PathBuf::from("<stdin>"),
+        };
+        assert_eq!(stdin.display_name(), "<stdin>");
+    }
+
+    #[test]
+    fn test_stdin_input_must_be_alone() {
+        let dir = create_tree(&["a.nx"]);
+
+        let combined =
+            resolve_inputs(&[PathBuf::from("-"), dir.path().join("a.nx")], None, &[]).unwrap_err();
+        assert!(combined.contains("cannot be combined"));
+
+        let stray_name =
+            resolve_inputs(&[dir.path().join("a.nx")], Some(Path::new("a.nx")), &[]).unwrap_err();
+        assert!(stray_name.contains("--stdin-filename"));
+    }
+}
@@ -1,4 +1,4 @@
-use nx_api::to_nx_value;
+use nx_api::{to_nx_value, NxDiagnostic, NxSeverity};
 use nx_interpreter::Value;
 
 pub fn format_value_json_pretty(value: &Value) -> Result<String, String> {
@@ -8,6 +8,24 @@ pub fn format_value_json_pretty(value: &Value) -> Result<String, String> {
         .map_err(|e| format!("Failed to serialize JSON: {}", e))
 }
 
+/// Formats diagnostics as a single-line JSON array.
+pub fn format_diagnostics_json(diagnostics: &[NxDiagnostic]) -> String {
+    serde_json::to_string(diagnostics).expect("NX diagnostics should always serialize to JSON")
+}
+
+/// Creates an error diagnostic without a source location, for failures outside of analysis.
+pub fn error_diagnostic(code: &str, message: String) -> NxDiagnostic {
+    NxDiagnostic {
+        severity: NxSeverity::Error,
+        code: Some(code.to_string()),
+        message,
+        labels: Vec::new(),
+        help: None,
+        note: None,
+        fixes: Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
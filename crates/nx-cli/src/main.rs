@@ -1,51 +1,108 @@
 //! NX CLI - Command-line tools for parsing, checking, and running NX code.
 //!
 //! Provides commands like:
-//! - `nxlang run <file>` - Run an NX file and output the result
+//! - `nxlang run <paths>...` - Run NX files and output their results
+//! - `nxlang check <paths>...` - Type check NX files and report errors
+//! - `nxlang format <paths>...` - Format NX files in place, or report unformatted files with
+//!   `--check`
+//! - `nxlang explain <code>` - Explain a diagnostic code, with examples
+//! - `nxlang conformance <dir>` - Run a golden-file conformance suite
 //! - `nxlang generate <path> --language <csharp|typescript>` - Generate language-specific type definitions
-//! - `nxlang parse <file>` - Parse and display AST (future)
-//! - `nxlang check <file>` - Type check and report errors (future)
-//! - `nxlang format <file>` - Format NX source code (future)
+//! - `nxlang completions <shell>` - Print a shell completion script
+//! - `nxlang man` - Print the man page
+//! - `nxlang --info` - Print toolchain versions and supported language versions as JSON
+//! - `nxlang parse <file>` - Print the syntax tree or the lowered HIR as JSON
+//! - `nxlang metrics <paths>...` - Report per-declaration complexity and element nesting depth
+//! - `nxlang i18n extract <paths>...` - Write the messages marked with `t("...")` to a JSON or PO
+//!   catalog for translators
+//!
+//! `run` and `check` read project settings from the nearest nx.toml (see [`nx_api::NxConfig`]).
+//!
+//! Every subcommand follows the exit status contract documented in the `status` module: 0 on
+//! success, 1 when the NX sources have errors, 2 for usage errors, and 3 for internal errors.
+//!
+//! `run`, `check` and `format` accept files, directories (searched recursively for `.nx` files),
+//! and glob patterns such as `src/**/*.nx`. Pass `-` to read a single source from stdin,
+//! optionally with `--stdin-filename` to name it in diagnostics.
+//!
+//! `check` and `conformance` take `--message-format json-lines` to stream newline-delimited JSON
+//! events for build tools instead of text; the `messages` module documents the events. `check
+//! --json` instead prints the diagnostics of all files as one JSON array, like `run --json`.
 
 mod codegen;
+mod crash;
+mod dot;
 mod format;
+mod i18n;
+mod inputs;
 mod json;
-
-use clap::{Parser, Subcommand};
+mod man;
+mod messages;
+mod metrics;
+mod status;
+mod timings;
+
+use clap::{CommandFactory, Parser, Subcommand};
+use i18n::{CatalogFormat, Extraction};
+use inputs::SourceInput;
+use messages::MessageFormat;
+use metrics::{FileMetrics, MetricsFormat};
 use nx_api::{
-    build_program_artifact_from_source, LibraryRegistry, NxDiagnostic, ProgramArtifact,
-    ProgramBuildContext,
+    build_program_artifact_from_source, diagnostics_to_api, eval_source_with_options,
+    runnable_entries, to_nx_value, EvalOptions, EvalResult, FilesystemModuleResolver,
+    ImportPathResolver, LibraryRegistry, LogLevel, NullConcatenation, NxConfig, NxDiagnostic,
+    NxLogRecord, NxLogSink, ProgramArtifact, ProgramBuildContext,
 };
+use nx_conformance::{Backend, BytecodeBackend, CaseOutcome, InterpreterBackend};
 use nx_diagnostics::{render_diagnostics_cli, Severity};
-use nx_hir::{lower_source_module, Item, LoweredModule};
-use nx_interpreter::{Interpreter, Value};
+use nx_fmt::{FormatError, FormatOptions};
+use nx_hir::{lower, lower_source_module, Item, LoweredModule, SourceId};
+use nx_interpreter::{Interpreter, LogRecord, Value};
+use nx_types::{time_stage, Stage, SymbolInfo, TypeCheckSession};
+use status::Status;
 use std::collections::HashMap;
 use std::path::{Component, Path, PathBuf};
 use std::process::ExitCode;
+use std::sync::Arc;
+use timings::TimingsFormat;
 
 #[derive(Parser)]
 #[command(name = "nxlang")]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 #[command(about = "NX Language CLI - Tools for NX development", long_about = None)]
 #[command(disable_version_flag = true)]
+#[command(arg_required_else_help = true, args_conflicts_with_subcommands = true)]
+#[command(
+    after_help = "Exit status:\n  0  Success\n  1  NX sources have errors, or too many warnings\n  2  Usage error\n  3  Internal error"
+)]
 struct Cli {
     /// Print version
     #[arg(short = 'v', short_alias = 'V', long = "version", action = clap::ArgAction::Version)]
     version: (),
 
+    /// Print toolchain versions, grammar hash and supported language versions as JSON
+    #[arg(long)]
+    info: bool,
+
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Run an NX file and output the result
+    /// Run NX files and output their results
     ///
-    /// Executes the root function in the NX file and prints the result.
-    /// If the file has no root element/function, an error is reported.
+    /// Executes the root function in each NX file and prints the result.
+    /// If a file has no root element/function, an error is reported that lists the functions
+    /// that can be run with --entry instead.
     Run {
-        /// Path to the NX file to run
-        file: PathBuf,
+        /// NX files, directories, or glob patterns to run
+        #[arg(required = true, value_name = "FILE")]
+        files: Vec<PathBuf>,
+
+        /// Run this top-level function instead of root; it must take no parameters
+        #[arg(long, value_name = "NAME")]
+        entry: Option<String>,
 
         /// Output format for the evaluation result
         #[arg(long, default_value_t = OutputFormat::Nx)]
@@ -54,6 +111,196 @@ enum Commands {
         /// Write output to a file instead of stdout
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Print the result as compact JSON and report diagnostics as a JSON array on stderr
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+
+        /// File name to use in diagnostics when reading source from stdin (`-`)
+        #[arg(long, value_name = "PATH")]
+        stdin_filename: Option<PathBuf>,
+
+        /// Use this configuration file instead of discovering nx.toml
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+
+        /// Seed for random(), randomInt() and uuid() so repeated runs produce the same output
+        #[arg(long, value_name = "N")]
+        seed: Option<u64>,
+
+        /// How `+` treats null next to a string: fail, use "", or use the text "null"
+        #[arg(long, value_name = "POLICY", default_value_t = NullConcat::Error)]
+        null_concat: NullConcat,
+
+        /// Print the time spent in each pipeline stage to stderr, as a table or JSON
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            num_args = 0..=1,
+            default_missing_value = "table"
+        )]
+        timings: Option<TimingsFormat>,
+
+        /// Print the program's log() calls to stderr, at every level
+        #[arg(long)]
+        verbose: bool,
+
+        /// Translate t() messages with this catalog: a .po file, or JSON from message to translation
+        #[arg(long, value_name = "FILE")]
+        translations: Option<PathBuf>,
+    },
+
+    /// Type check NX files and report diagnostics
+    ///
+    /// All files are analyzed in one session. Diagnostics are reported per file, followed by a
+    /// summary of the error and warning counts. Without paths, the `[files]` include patterns
+    /// from nx.toml are checked.
+    Check {
+        /// NX files, directories, or glob patterns to check
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+
+        /// File name to use in diagnostics when reading source from stdin (`-`)
+        #[arg(long, value_name = "PATH")]
+        stdin_filename: Option<PathBuf>,
+
+        /// Fail with exit code 1 when more than this many warnings are reported
+        #[arg(long, value_name = "N")]
+        max_warnings: Option<usize>,
+
+        /// Report diagnostics as text on stderr, or as JSON events on stdout, one per line
+        #[arg(long, value_name = "FORMAT", default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+
+        /// Print the diagnostics of all files as one JSON array on stdout
+        #[arg(long, conflicts_with = "message_format")]
+        json: bool,
+
+        /// Use this configuration file instead of discovering nx.toml
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+
+        /// Print the time spent in each pipeline stage to stderr, as a table or JSON
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            num_args = 0..=1,
+            default_missing_value = "table"
+        )]
+        timings: Option<TimingsFormat>,
+    },
+
+    /// Format NX files in place
+    ///
+    /// Only the whitespace between tokens changes: indentation, spacing, and blank lines. Element
+    /// text, strings, and comments are kept as written. Without paths, the `[files]` include
+    /// patterns from nx.toml are formatted; `-` formats stdin and prints the result to stdout.
+    Format {
+        /// NX files, directories, or glob patterns to format
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+
+        /// Report files that are not formatted without changing them, and fail if there are any
+        #[arg(long)]
+        check: bool,
+
+        /// Number of spaces per indentation level (default: `[format] indent-width` from nx.toml,
+        /// or 2)
+        #[arg(long, value_name = "N")]
+        indent_width: Option<usize>,
+
+        /// File name to use in diagnostics when reading source from stdin (`-`)
+        #[arg(long, value_name = "PATH")]
+        stdin_filename: Option<PathBuf>,
+
+        /// Use this configuration file instead of discovering nx.toml
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+    },
+
+    /// Explain a diagnostic code
+    ///
+    /// Prints what the diagnostic means, an example that reports it next to the corrected code,
+    /// and links to the language reference. Without a code, lists every code that has an
+    /// explanation.
+    Explain {
+        /// Diagnostic code, such as `type-mismatch`
+        code: Option<String>,
+    },
+
+    /// Search declarations across NX files by partial name
+    ///
+    /// Prints matching functions, components, values, types, and enum members, best matches
+    /// first, one per line as `path:line:column kind name`. Matching ignores case and may skip
+    /// characters, so `PrCa` finds `ProductCard`. Without paths, the `[files]` include patterns
+    /// from nx.toml are searched.
+    Symbols {
+        /// Full or partial symbol name to search for
+        query: String,
+
+        /// NX files, directories, or glob patterns to search
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+
+        /// Use this configuration file instead of discovering nx.toml
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+    },
+
+    /// Report the complexity and element nesting depth of each declaration
+    ///
+    /// For every function, component, and value, prints its cyclomatic complexity (one, plus one
+    /// per `if`, `for`, `&&`, `||`, and `match` arm), the deepest nesting of elements it builds,
+    /// and how many expressions it contains, followed by per-file and overall totals. With
+    /// `--max-complexity` or `--max-depth`, every declaration above a limit is reported on stderr
+    /// and the command exits with status 1. Without paths, the `[files]` include patterns from
+    /// nx.toml are measured.
+    Metrics {
+        /// NX files, directories, or glob patterns to measure
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+
+        /// How to print the report
+        #[arg(long, value_enum, default_value_t = MetricsFormat::Table)]
+        format: MetricsFormat,
+
+        /// Fail if any declaration's complexity is above this limit
+        #[arg(long, value_name = "N")]
+        max_complexity: Option<usize>,
+
+        /// Fail if any declaration nests elements deeper than this limit
+        #[arg(long, value_name = "N")]
+        max_depth: Option<usize>,
+
+        /// Use this configuration file instead of discovering nx.toml
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+    },
+
+    /// Work with the messages marked for translation with t("...")
+    I18n {
+        #[command(subcommand)]
+        command: I18nCommand,
+    },
+
+    /// Parse an NX file and print its syntax tree or lowered HIR
+    ///
+    /// `cst` prints the concrete syntax tree, one node per line with its byte range. `hir-json`
+    /// prints the lowered module as JSON: its items, then every expression and element with the
+    /// arena id other nodes refer to it by and its source span. Parse errors are reported on
+    /// stderr after the output of the recovered tree.
+    Parse {
+        /// NX file to parse, or `-` to read from stdin
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// What to print
+        #[arg(long, value_enum, default_value_t = ParseFormat::Cst)]
+        format: ParseFormat,
+
+        /// File name to use in diagnostics when reading source from stdin (`-`)
+        #[arg(long, value_name = "PATH")]
+        stdin_filename: Option<PathBuf>,
     },
 
     /// Generate language-specific type definitions from an NX file or library directory
@@ -84,12 +331,141 @@ enum Commands {
         #[arg(long = "typescript-package-prefix")]
         typescript_package_prefix: Option<String>,
     },
+
+    /// Run a golden-file conformance suite against an interpreter backend
+    ///
+    /// Every `.nx` file under the directory is a case, paired with either `name.expected.json`,
+    /// the JSON value `root()` must evaluate to, or `name.expected.diag`, the diagnostics
+    /// evaluation must fail with. Prints one line per case and exits with code 1 if any case
+    /// fails.
+    Conformance {
+        /// Directory holding the suite
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Seed for random(), randomInt() and uuid()
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        seed: u64,
+
+        /// Run the suite on the tree-walking interpreter or on the bytecode VM
+        #[arg(long, value_name = "BACKEND", default_value_t = ConformanceBackend::Interpreter)]
+        backend: ConformanceBackend,
+
+        /// Report results as text, or as JSON events on stdout, one per line
+        #[arg(long, value_name = "FORMAT", default_value_t = MessageFormat::Human)]
+        message_format: MessageFormat,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// The script is generated from the current command definitions, so it always covers every
+    /// subcommand and flag of this build. For example, for bash:
+    /// `nxlang completions bash > /usr/share/bash-completion/completions/nxlang`.
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the nxlang man page in roff format
+    Man {
+        /// Write the man page to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum I18nCommand {
+    /// Write every message passed to t() to a catalog for translators
+    ///
+    /// Each distinct string literal passed to t() becomes one entry with an empty translation.
+    /// JSON catalogs are an object from message to translation; PO catalogs are gettext
+    /// templates listing the file and line of each use. Both can be passed to `run
+    /// --translations` once translated. Calls whose message is not a string literal cannot be
+    /// extracted and are reported on stderr. Without paths, the `[files]` include patterns from
+    /// nx.toml are searched.
+    Extract {
+        /// NX files, directories, or glob patterns to search
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+
+        /// Catalog format
+        #[arg(long, value_enum, default_value_t = CatalogFormat::Json)]
+        format: CatalogFormat,
+
+        /// Write the catalog to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Use this configuration file instead of discovering nx.toml
+        #[arg(long, value_name = "PATH")]
+        config: Option<PathBuf>,
+    },
 }
 
 #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
 enum OutputFormat {
     Nx,
     Json,
+    Dot,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ParseFormat {
+    Cst,
+    HirJson,
+}
+
+impl std::fmt::Display for ParseFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseFormat::Cst => write!(f, "cst"),
+            ParseFormat::HirJson => write!(f, "hir-json"),
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum NullConcat {
+    Error,
+    Empty,
+    Null,
+}
+
+impl std::fmt::Display for NullConcat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NullConcat::Error => write!(f, "error"),
+            NullConcat::Empty => write!(f, "empty"),
+            NullConcat::Null => write!(f, "null"),
+        }
+    }
+}
+
+impl From<NullConcat> for NullConcatenation {
+    fn from(policy: NullConcat) -> Self {
+        match policy {
+            NullConcat::Error => NullConcatenation::Error,
+            NullConcat::Empty => NullConcatenation::Empty,
+            NullConcat::Null => NullConcatenation::Literal,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ConformanceBackend {
+    Interpreter,
+    Bytecode,
+}
+
+impl std::fmt::Display for ConformanceBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConformanceBackend::Interpreter => write!(f, "interpreter"),
+            ConformanceBackend::Bytecode => write!(f, "bytecode"),
+        }
+    }
 }
 
 #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
@@ -98,119 +474,1132 @@ enum GenLanguage {
     Typescript,
 }
 
-impl std::fmt::Display for OutputFormat {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            OutputFormat::Nx => write!(f, "nx"),
-            OutputFormat::Json => write!(f, "json"),
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Nx => write!(f, "nx"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Dot => write!(f, "dot"),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let Some(command) = cli.command else {
+        return print_toolchain_info().into();
+    };
+
+    let status = crash::run_reporting_crashes(|| match command {
+        Commands::Run {
+            files,
+            entry,
+            format,
+            output,
+            json,
+            stdin_filename,
+            config,
+            seed,
+            null_concat,
+            timings,
+            verbose,
+            translations,
+        } => with_timings(timings, || {
+            let translations = match translations.as_deref().map(i18n::read_catalog) {
+                Some(Ok(catalog)) => Some(Arc::new(catalog)),
+                Some(Err(message)) => {
+                    eprintln!("Error: {}", message);
+                    return Status::Usage;
+                }
+                None => None,
+            };
+            run_files(
+                &files,
+                stdin_filename.as_deref(),
+                format,
+                output.as_ref(),
+                json,
+                config.as_deref(),
+                &EvalOptions {
+                    random_seed: seed,
+                    null_concatenation: null_concat.into(),
+                    log_sink: verbose.then(|| NxLogSink::new(print_log_record)),
+                    log_level: LogLevel::Debug,
+                    entry,
+                    translations,
+                    ..EvalOptions::default()
+                },
+            )
+        }),
+        Commands::Check {
+            paths,
+            stdin_filename,
+            max_warnings,
+            message_format,
+            json,
+            config,
+            timings,
+        } => with_timings(timings, || {
+            check_files(
+                &paths,
+                stdin_filename.as_deref(),
+                max_warnings,
+                message_format,
+                json,
+                config.as_deref(),
+            )
+        }),
+        Commands::Format {
+            paths,
+            check,
+            indent_width,
+            stdin_filename,
+            config,
+        } => format_files(
+            &paths,
+            stdin_filename.as_deref(),
+            check,
+            indent_width,
+            config.as_deref(),
+        ),
+        Commands::Explain { code } => explain_code(code.as_deref()),
+        Commands::Symbols {
+            query,
+            paths,
+            config,
+        } => search_symbols(&query, &paths, config.as_deref()),
+        Commands::Metrics {
+            paths,
+            format,
+            max_complexity,
+            max_depth,
+            config,
+        } => report_metrics(&paths, format, max_complexity, max_depth, config.as_deref()),
+        Commands::I18n {
+            command:
+                I18nCommand::Extract {
+                    paths,
+                    format,
+                    output,
+                    config,
+                },
+        } => extract_messages(&paths, format, output.as_deref(), config.as_deref()),
+        Commands::Parse {
+            file,
+            format,
+            stdin_filename,
+        } => parse_file_command(&file, format, stdin_filename.as_deref()),
+        Commands::Generate {
+            file,
+            language,
+            output,
+            editorconfig,
+            csharp_namespace,
+            typescript_package_prefix,
+        } => generate_types(
+            &file,
+            language,
+            output.as_ref(),
+            editorconfig.as_ref(),
+            &csharp_namespace,
+            typescript_package_prefix.as_deref(),
+        ),
+        Commands::Conformance {
+            dir,
+            seed,
+            backend,
+            message_format,
+        } => run_conformance(&dir, seed, backend, message_format),
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "nxlang", &mut std::io::stdout());
+            Status::Success
+        }
+        Commands::Man { output } => write_man_page(output.as_ref()),
+    });
+    status.into()
+}
+
+fn print_toolchain_info() -> Status {
+    match nx_api::toolchain_info().to_json_string_pretty() {
+        Ok(json) => {
+            println!("{}", json);
+            Status::Success
+        }
+        Err(error) => {
+            eprintln!("Error encoding toolchain info: {}", error);
+            Status::Internal
+        }
+    }
+}
+
+/// Prints a `log()` call from the program to stderr, followed by its value as JSON if it has one.
+fn print_log_record(record: NxLogRecord) {
+    let value = record.value.map(|value| {
+        value
+            .to_json_string()
+            .unwrap_or_else(|error| format!("<{}>", error))
+    });
+    match value {
+        Some(value) => eprintln!("[{}] {} {}", record.level, record.message, value),
+        None => eprintln!("[{}] {}", record.level, record.message),
+    }
+}
+
+/// Runs a command, then prints the time it spent in each pipeline stage when `--timings` is set.
+fn with_timings(format: Option<TimingsFormat>, command: impl FnOnce() -> Status) -> Status {
+    let Some(format) = format else {
+        return command();
+    };
+    let (status, stage_timings) = nx_types::collect_timings(command);
+    eprintln!("{}", timings::format_timings(&stage_timings, format));
+    status
+}
+
+fn explain_code(code: Option<&str>) -> Status {
+    let Some(code) = code else {
+        for explanation in nx_diagnostics::explanations() {
+            println!("{:<32}{}", explanation.code, explanation.summary);
+        }
+        return Status::Success;
+    };
+    match nx_diagnostics::explain(code) {
+        Some(explanation) => {
+            print!("{}", explanation);
+            Status::Success
+        }
+        None => {
+            eprintln!("Error: no explanation for diagnostic code '{}'", code);
+            let codes = nx_diagnostics::explanations()
+                .iter()
+                .map(|explanation| explanation.code);
+            if let Some(suggestion) = nx_diagnostics::suggest::closest_match(code, codes) {
+                eprintln!("Did you mean `{}`?", suggestion);
+            }
+            eprintln!("Run `nxlang explain` to list the codes that have explanations.");
+            Status::Usage
+        }
+    }
+}
+
+fn write_man_page(output: Option<&PathBuf>) -> Status {
+    let page = man::render_man_page(&Cli::command());
+    match output {
+        Some(output_path) => {
+            if let Err(e) = std::fs::write(output_path, page) {
+                eprintln!("Error writing output to '{}': {}", output_path.display(), e);
+                return Status::Internal;
+            }
+        }
+        None => print!("{}", page),
+    }
+    Status::Success
+}
+
+fn run_files(
+    inputs: &[PathBuf],
+    stdin_filename: Option<&Path>,
+    format: OutputFormat,
+    output: Option<&PathBuf>,
+    json: bool,
+    config_path: Option<&Path>,
+    options: &EvalOptions,
+) -> Status {
+    let config = match load_config(config_path) {
+        Ok(config) => config,
+        Err(status) => return status,
+    };
+    let files = match inputs::resolve_inputs(inputs, stdin_filename, &config.files.exclude) {
+        Ok(files) => files,
+        Err(message) => {
+            if json {
+                eprintln!(
+                    "{}",
+                    json::format_diagnostics_json(&[json::error_diagnostic(
+                        "input-error",
+                        message
+                    )])
+                );
+            } else {
+                eprintln!("Error: {}", message);
+            }
+            return Status::Usage;
+        }
+    };
+
+    let imports = ProjectImports::new(&config);
+    if let [file] = files.as_slice() {
+        return if json {
+            run_file_json(file, output, &imports, options)
+        } else {
+            run_file(file, format, output, &imports, options)
+        };
+    }
+
+    if json {
+        eprintln!("Error: --json can only be used when running a single file");
+        return Status::Usage;
+    }
+
+    if output.is_some() {
+        eprintln!("Error: --output can only be used when running a single file");
+        return Status::Usage;
+    }
+
+    let mut failed = 0;
+    let mut status = Status::Success;
+    for file in &files {
+        println!("==> {} <==", file.path().display());
+        let file_status = run_file(file, format, None, &imports, options);
+        if file_status != Status::Success {
+            failed += 1;
+        }
+        status = status.max(file_status);
+    }
+
+    eprintln!(
+        "Ran {}: {} succeeded, {} failed",
+        pluralize(files.len(), "file"),
+        files.len() - failed,
+        failed
+    );
+
+    status
+}
+
+fn run_file(
+    input: &SourceInput,
+    format: OutputFormat,
+    output: Option<&PathBuf>,
+    imports: &ProjectImports,
+    options: &EvalOptions,
+) -> Status {
+    let path = input.path();
+    if let SourceInput::File(path) = input {
+        // Check if file exists
+        if !path.exists() {
+            eprintln!("Error: File not found: {}", path.display());
+            return Status::Usage;
+        }
+
+        // Check if it's an .nx file
+        if path.extension().and_then(|e| e.to_str()) != Some("nx") {
+            eprintln!(
+                "Warning: File '{}' does not have .nx extension",
+                path.display()
+            );
+        }
+    }
+
+    // Read the source once
+    let source = match input.read_source() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Status::Internal;
+        }
+    };
+
+    let build_context = match imports.build_context(&source, path) {
+        Ok(build_context) => build_context,
+        Err(diagnostics) => {
+            return render_source_diagnostics(&path.display().to_string(), &source, &diagnostics)
+        }
+    };
+    let program = match load_source_program_for_run(&source, path, &build_context) {
+        Ok(program) => program,
+        Err(exit_code) => return exit_code,
+    };
+    let Some(module) = program
+        .root_modules
+        .first()
+        .and_then(|artifact| artifact.lowered_module.as_ref())
+    else {
+        eprintln!("Error: No root module available for '{}'", path.display());
+        return Status::Internal;
+    };
+
+    // Check that the entry function exists and can be called without arguments
+    let entry = options.entry_name();
+    let runnable = runnable_entries(&program);
+    let has_entry = match &options.entry {
+        Some(entry) => runnable.contains(entry),
+        None => module
+            .items()
+            .iter()
+            .any(|item| matches!(item, Item::Function(f) if f.name.as_str() == "root")),
+    };
+
+    if !has_entry {
+        match &options.entry {
+            Some(entry) => eprintln!(
+                "Error: No top-level function without parameters named '{}' in '{}'",
+                entry,
+                path.display()
+            ),
+            None => {
+                eprintln!("Error: No root element found in '{}'", path.display());
+                eprintln!("Hint: Add a top-level element to create an implicit root function.");
+            }
+        }
+        if runnable.is_empty() {
+            eprintln!("No functions in this file can be run without arguments.");
+        } else {
+            eprintln!("Runnable entries (select one with --entry NAME):");
+            for name in &runnable {
+                eprintln!("  {}", name);
+            }
+        }
+        return Status::Diagnostics;
+    }
+
+    // Execute the entry function
+    let interpreter = Interpreter::from_resolved_program(program.resolved_program.clone())
+        .with_random_source(options.random_source())
+        .with_null_concatenation(options.null_concatenation);
+    let interpreter = match options.translations.clone() {
+        Some(catalog) => interpreter.with_translations(catalog),
+        None => interpreter,
+    };
+    let interpreter = match options.log_sink.clone() {
+        Some(sink) => interpreter.with_log_sink(
+            Arc::new(move |record: LogRecord| {
+                sink.log(NxLogRecord {
+                    level: record.level,
+                    message: record.message,
+                    value: record.value.as_ref().map(to_nx_value),
+                })
+            }),
+            options.log_level,
+        ),
+        None => interpreter,
+    };
+    match time_stage(Stage::Eval, || {
+        interpreter.execute_resolved_program_function(entry, vec![])
+    }) {
+        Ok(value) => {
+            let output_text = match time_stage(Stage::Serialize, || format_output(&value, format)) {
+                Ok(output) => output,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return Status::Internal;
+                }
+            };
+
+            if let Some(output_path) = output {
+                if let Err(e) = std::fs::write(output_path, format!("{}\n", output_text)) {
+                    eprintln!("Error writing output to '{}': {}", output_path.display(), e);
+                    return Status::Internal;
+                }
+            } else {
+                println!("{}", output_text);
+            }
+
+            Status::Success
+        }
+        Err(e) => {
+            eprintln!("Runtime error: {}", e);
+            Status::Diagnostics
+        }
+    }
+}
+
+/// Runs a file in machine-readable mode.
+///
+/// On success the result is written as a single line of canonical `NxValue` JSON. Diagnostics are
+/// always written to stderr as one JSON array, which is empty when evaluation succeeds.
+fn run_file_json(
+    input: &SourceInput,
+    output: Option<&PathBuf>,
+    imports: &ProjectImports,
+    options: &EvalOptions,
+) -> Status {
+    match eval_file_json(input, output, imports, options) {
+        Ok(()) => {
+            eprintln!("{}", json::format_diagnostics_json(&[]));
+            Status::Success
+        }
+        Err((status, diagnostics)) => {
+            eprintln!("{}", json::format_diagnostics_json(&diagnostics));
+            status
+        }
+    }
+}
+
+fn eval_file_json(
+    input: &SourceInput,
+    output: Option<&PathBuf>,
+    imports: &ProjectImports,
+    options: &EvalOptions,
+) -> Result<(), (Status, Vec<NxDiagnostic>)> {
+    let internal_error = |code: &str, message: String| {
+        (
+            Status::Internal,
+            vec![json::error_diagnostic(code, message)],
+        )
+    };
+    let source = input
+        .read_source()
+        .map_err(|message| internal_error("io-error", message))?;
+
+    let build_context = imports
+        .build_context(&source, input.path())
+        .map_err(|diagnostics| {
+            (
+                Status::Diagnostics,
+                diagnostics_to_api(&diagnostics, &source),
+            )
+        })?;
+    let file_name = input.display_name();
+    let value = match eval_source_with_options(&source, &file_name, &build_context, options) {
+        EvalResult::Ok(value) => value,
+        EvalResult::Err(diagnostics) => return Err((Status::Diagnostics, diagnostics)),
+    };
+    let output_text = time_stage(Stage::Serialize, || value.to_json_string()).map_err(|error| {
+        internal_error(
+            "serialization-error",
+            format!("Failed to serialize JSON: {}", error),
+        )
+    })?;
+
+    match output {
+        Some(output_path) => {
+            std::fs::write(output_path, format!("{}\n", output_text)).map_err(|error| {
+                internal_error(
+                    "io-error",
+                    format!(
+                        "Error writing output to '{}': {}",
+                        output_path.display(),
+                        error
+                    ),
+                )
+            })
+        }
+        None => {
+            println!("{}", output_text);
+            Ok(())
+        }
+    }
+}
+
+/// Error and warning counts for one or more checked files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct DiagnosticCounts {
+    errors: usize,
+    warnings: usize,
+}
+
+impl DiagnosticCounts {
+    fn add(&mut self, other: DiagnosticCounts) {
+        self.errors += other.errors;
+        self.warnings += other.warnings;
+    }
+}
+
+impl std::fmt::Display for DiagnosticCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {}",
+            pluralize(self.errors, "error"),
+            pluralize(self.warnings, "warning")
+        )
+    }
+}
+
+fn check_files(
+    inputs: &[PathBuf],
+    stdin_filename: Option<&Path>,
+    max_warnings: Option<usize>,
+    message_format: MessageFormat,
+    json: bool,
+    config_path: Option<&Path>,
+) -> Status {
+    let config = match load_config(config_path) {
+        Ok(config) => config,
+        Err(status) => return status,
+    };
+    let files = match resolve_project_inputs(inputs, stdin_filename, &config) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+
+    let imports = ProjectImports::new(&config);
+    let mut totals = DiagnosticCounts::default();
+    let mut status = Status::Success;
+    // With --json, diagnostics are collected and printed together once every file is checked.
+    let mut collected = json.then(Vec::new);
+    for file in &files {
+        let (result, stage_timings) = nx_types::collect_timings(|| {
+            check_file(file, &imports, &config, message_format, collected.as_mut())
+        });
+        let file_name = file.display_name();
+        match result {
+            Ok(counts) => {
+                if counts != DiagnosticCounts::default()
+                    && message_format == MessageFormat::Human
+                    && !json
+                {
+                    eprintln!("{}: {}", file_name, counts);
+                }
+                if counts.errors > 0 {
+                    status = status.max(Status::Diagnostics);
+                }
+                totals.add(counts);
+            }
+            Err((file_status, message)) => {
+                match (collected.as_mut(), message_format) {
+                    (Some(collected), _) => collected.push(json::error_diagnostic(
+                        check_error_code(file_status),
+                        message,
+                    )),
+                    (None, MessageFormat::Human) => eprintln!("Error: {}", message),
+                    (None, MessageFormat::JsonLines) => {
+                        messages::emit(&messages::diagnostic(
+                            &file_name,
+                            &json::error_diagnostic(check_error_code(file_status), message),
+                        ));
+                    }
+                }
+                status = status.max(file_status);
+            }
+        }
+        if message_format == MessageFormat::JsonLines {
+            for event in messages::stages_finished(&file_name, &stage_timings) {
+                messages::emit(&event);
+            }
+        }
+    }
+
+    let max_warnings = max_warnings.or(config.check.max_warnings);
+    let too_many_warnings = max_warnings.is_some_and(|max_warnings| totals.warnings > max_warnings);
+    if too_many_warnings {
+        status = status.max(Status::Diagnostics);
+    }
+
+    if let Some(collected) = collected {
+        println!("{}", json::format_diagnostics_json(&collected));
+        return status;
+    }
+    match message_format {
+        MessageFormat::Human => {
+            eprintln!("Checked {}: {}", pluralize(files.len(), "file"), totals);
+            if let (true, Some(max_warnings)) = (too_many_warnings, max_warnings) {
+                eprintln!(
+                    "Error: {} exceeds the --max-warnings limit of {}",
+                    pluralize(totals.warnings, "warning"),
+                    max_warnings
+                );
+            }
+        }
+        MessageFormat::JsonLines => messages::emit(&messages::finished(
+            status == Status::Success,
+            serde_json::json!({
+                "files": files.len(),
+                "errors": totals.errors,
+                "warnings": totals.warnings,
+                "max_warnings_exceeded": too_many_warnings,
+            }),
+        )),
+    }
+
+    status
+}
+
+/// Returns the diagnostic code for a file `check` could not analyze.
+fn check_error_code(status: Status) -> &'static str {
+    if status == Status::Internal {
+        "io-error"
+    } else {
+        "build-error"
+    }
+}
+
+/// Resolves command-line paths, falling back to the `[files]` include patterns from nx.toml.
+fn resolve_project_inputs(
+    inputs: &[PathBuf],
+    stdin_filename: Option<&Path>,
+    config: &NxConfig,
+) -> Result<Vec<SourceInput>, Status> {
+    let inputs = if inputs.is_empty() {
+        if config.files.include.is_empty() {
+            eprintln!(
+                "Error: No paths given and no [files] include patterns configured in nx.toml"
+            );
+            return Err(Status::Usage);
+        }
+        config.files.include.as_slice()
+    } else {
+        inputs
+    };
+    inputs::resolve_inputs(inputs, stdin_filename, &config.files.exclude).map_err(|message| {
+        eprintln!("Error: {}", message);
+        Status::Usage
+    })
+}
+
+/// Formats `inputs` in place, or with `check`, reports the ones that are not formatted.
+fn format_files(
+    inputs: &[PathBuf],
+    stdin_filename: Option<&Path>,
+    check: bool,
+    indent_width: Option<usize>,
+    config_path: Option<&Path>,
+) -> Status {
+    let config = match load_config(config_path) {
+        Ok(config) => config,
+        Err(status) => return status,
+    };
+    let mut options = FormatOptions::default();
+    if let Some(indent_width) = indent_width.or(config.format.indent_width) {
+        options.indent_width = indent_width;
+    }
+    let files = match resolve_project_inputs(inputs, stdin_filename, &config) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+
+    let mut status = Status::Success;
+    let mut unformatted = 0;
+    for file in &files {
+        let source = match file.read_source() {
+            Ok(source) => source,
+            Err(message) => {
+                eprintln!("Error: {}", message);
+                status = status.max(Status::Internal);
+                continue;
+            }
+        };
+        let file_name = file.display_name();
+        let formatted = match nx_fmt::format_source(&source, &file_name, &options) {
+            Ok(formatted) => formatted,
+            Err(FormatError::Syntax(diagnostics)) => {
+                print_source_diagnostics(&file_name, &source, &diagnostics);
+                status = status.max(Status::Diagnostics);
+                continue;
+            }
+            Err(error) => {
+                eprintln!("Error: Failed to format {}: {}", file_name, error);
+                status = status.max(Status::Internal);
+                continue;
+            }
+        };
+
+        if check {
+            if formatted != source {
+                eprintln!("{}: not formatted", file_name);
+                unformatted += 1;
+                status = status.max(Status::Diagnostics);
+            }
+        } else if let SourceInput::Stdin { .. } = file {
+            print!("{}", formatted);
+        } else if formatted != source {
+            if let Err(error) = std::fs::write(file.path(), formatted) {
+                eprintln!("Error: Failed to write '{}': {}", file_name, error);
+                status = status.max(Status::Internal);
+            }
+        }
+    }
+
+    if check {
+        eprintln!(
+            "Checked {}: {} not formatted",
+            pluralize(files.len(), "file"),
+            unformatted
+        );
+    }
+    status
+}
+
+fn search_symbols(query: &str, inputs: &[PathBuf], config_path: Option<&Path>) -> Status {
+    let config = match load_config(config_path) {
+        Ok(config) => config,
+        Err(status) => return status,
+    };
+    let files = match resolve_project_inputs(inputs, None, &config) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+
+    let mut session = TypeCheckSession::new();
+    let mut sources = HashMap::new();
+    for file in &files {
+        let source = match file.read_source() {
+            Ok(source) => source,
+            Err(message) => {
+                eprintln!("Error: {}", message);
+                return Status::Internal;
+            }
+        };
+        let file_name = file.display_name();
+        session.add_file(file_name.clone(), source.clone());
+        sources.insert(file_name, source);
+    }
+
+    for symbol in session.workspace_symbols(query) {
+        println!(
+            "{}",
+            format_symbol(&symbol, sources.get(&symbol.file).map(String::as_str))
+        );
+    }
+    Status::Success
+}
+
+fn report_metrics(
+    inputs: &[PathBuf],
+    format: MetricsFormat,
+    max_complexity: Option<usize>,
+    max_depth: Option<usize>,
+    config_path: Option<&Path>,
+) -> Status {
+    let config = match load_config(config_path) {
+        Ok(config) => config,
+        Err(status) => return status,
+    };
+    let files = match resolve_project_inputs(inputs, None, &config) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+
+    let mut report = Vec::new();
+    for file in &files {
+        let source = match file.read_source() {
+            Ok(source) => source,
+            Err(message) => {
+                eprintln!("Error: {}", message);
+                return Status::Internal;
+            }
+        };
+        report.push(FileMetrics::collect(file.display_name(), &source));
+    }
+
+    println!("{}", metrics::format_metrics(&report, format));
+    let violations = metrics::limit_violations(&report, max_complexity, max_depth);
+    for violation in &violations {
+        eprintln!("Error: {}", violation);
+    }
+    if violations.is_empty() {
+        Status::Success
+    } else {
+        Status::Diagnostics
+    }
+}
+
+fn extract_messages(
+    inputs: &[PathBuf],
+    format: CatalogFormat,
+    output: Option<&Path>,
+    config_path: Option<&Path>,
+) -> Status {
+    let config = match load_config(config_path) {
+        Ok(config) => config,
+        Err(status) => return status,
+    };
+    let files = match resolve_project_inputs(inputs, None, &config) {
+        Ok(files) => files,
+        Err(status) => return status,
+    };
+
+    let mut extraction = Extraction::default();
+    for file in &files {
+        let source = match file.read_source() {
+            Ok(source) => source,
+            Err(message) => {
+                eprintln!("Error: {}", message);
+                return Status::Internal;
+            }
+        };
+        extraction.collect(&file.display_name(), &source);
+    }
+    for location in &extraction.computed {
+        eprintln!(
+            "Warning: {}: t() message is not a string literal and was not extracted",
+            location
+        );
+    }
+
+    let catalog = extraction.format(format);
+    match output {
+        Some(output_path) => {
+            if let Err(e) = std::fs::write(output_path, format!("{}\n", catalog.trim_end())) {
+                eprintln!("Error writing output to '{}': {}", output_path.display(), e);
+                return Status::Internal;
+            }
         }
+        None => println!("{}", catalog.trim_end()),
     }
+    Status::Success
 }
 
-fn main() -> ExitCode {
-    let cli = Cli::parse();
+fn run_conformance(
+    dir: &Path,
+    seed: u64,
+    backend: ConformanceBackend,
+    message_format: MessageFormat,
+) -> Status {
+    if !dir.is_dir() {
+        eprintln!("Error: '{}' is not a directory", dir.display());
+        return Status::Usage;
+    }
+    let options = EvalOptions::with_random_seed(seed);
+    let backend: Box<dyn Backend> = match backend {
+        ConformanceBackend::Interpreter => Box::new(InterpreterBackend::new(options)),
+        ConformanceBackend::Bytecode => Box::new(BytecodeBackend::new(options)),
+    };
+    let cases = match nx_conformance::discover_cases(dir) {
+        Ok(cases) => cases,
+        Err(error) => {
+            eprintln!("Error reading suite '{}': {}", dir.display(), error);
+            return Status::Internal;
+        }
+    };
 
-    match cli.command {
-        Commands::Run {
-            file,
-            format,
-            output,
-        } => run_file(&file, format, output.as_ref()),
-        Commands::Generate {
-            file,
-            language,
-            output,
-            editorconfig,
-            csharp_namespace,
-            typescript_package_prefix,
-        } => generate_types(
-            &file,
-            language,
-            output.as_ref(),
-            editorconfig.as_ref(),
-            &csharp_namespace,
-            typescript_package_prefix.as_deref(),
+    let mut failed = 0;
+    for case in &cases {
+        let (outcome, stage_timings) =
+            nx_types::collect_timings(|| nx_conformance::run_case(case, backend.as_ref()));
+        let source = case.source.display().to_string();
+        let failure = match &outcome {
+            CaseOutcome::Passed => None,
+            CaseOutcome::Failed(message) => {
+                failed += 1;
+                Some(message.as_str())
+            }
+        };
+        match message_format {
+            MessageFormat::Human => match failure {
+                None => println!("PASS {}", source),
+                Some(message) => {
+                    println!("FAIL {}", source);
+                    for line in message.lines() {
+                        println!("    {}", line);
+                    }
+                }
+            },
+            MessageFormat::JsonLines => {
+                messages::emit(&messages::test_finished(&source, failure));
+                for event in messages::stages_finished(&source, &stage_timings) {
+                    messages::emit(&event);
+                }
+            }
+        }
+    }
+
+    let passed = cases.len() - failed;
+    match message_format {
+        MessageFormat::Human => println!(
+            "\n{} passed, {} failed ({})",
+            passed,
+            failed,
+            backend.name()
         ),
+        MessageFormat::JsonLines => messages::emit(&messages::finished(
+            failed == 0,
+            serde_json::json!({ "passed": passed, "failed": failed, "backend": backend.name() }),
+        )),
     }
+    if failed > 0 {
+        Status::Diagnostics
+    } else {
+        Status::Success
+    }
+}
+
+/// Formats a symbol search result as `path:line:column kind name`.
+fn format_symbol(symbol: &SymbolInfo, source: Option<&str>) -> String {
+    let offset: usize = symbol.span.start().into();
+    let (line, column) = source
+        .and_then(|source| source.get(..offset))
+        .map(|prefix| {
+            let line_start = prefix.rfind('\n').map_or(0, |index| index + 1);
+            (
+                prefix.matches('\n').count() + 1,
+                prefix[line_start..].chars().count() + 1,
+            )
+        })
+        .unwrap_or((1, 1));
+    let name = match &symbol.container {
+        Some(container) => format!("{}.{}", container, symbol.name),
+        None => symbol.name.clone(),
+    };
+    format!(
+        "{}:{}:{} {} {}",
+        symbol.file,
+        line,
+        column,
+        symbol.kind.as_str(),
+        name
+    )
 }
 
-fn run_file(path: &PathBuf, format: OutputFormat, output: Option<&PathBuf>) -> ExitCode {
-    // Check if file exists
-    if !path.exists() {
-        eprintln!("Error: File not found: {}", path.display());
-        return ExitCode::from(1);
+fn parse_file_command(input: &Path, format: ParseFormat, stdin_filename: Option<&Path>) -> Status {
+    let file = match inputs::resolve_inputs(&[input.to_path_buf()], stdin_filename, &[]) {
+        Ok(mut files) if files.len() == 1 => files.remove(0),
+        Ok(_) => {
+            eprintln!("Error: 'parse' takes a single file, not a directory or pattern");
+            return Status::Usage;
+        }
+        Err(message) => {
+            eprintln!("Error: {}", message);
+            return Status::Usage;
+        }
+    };
+    let source = match file.read_source() {
+        Ok(source) => source,
+        Err(message) => {
+            eprintln!("Error: {}", message);
+            return Status::Internal;
+        }
+    };
+    let file_name = file.display_name();
+
+    let parse_result = nx_syntax::parse_str(&source, &file_name);
+    if let Some(root) = parse_result.root() {
+        let output = match format {
+            ParseFormat::Cst => render_cst(root),
+            ParseFormat::HirJson => {
+                let module = lower(root, SourceId::new(0));
+                match serde_json::to_string_pretty(&module) {
+                    Ok(json) => json,
+                    Err(error) => {
+                        eprintln!("Error: Failed to serialize HIR: {}", error);
+                        return Status::Internal;
+                    }
+                }
+            }
+        };
+        println!("{}", output);
     }
 
-    // Check if it's an .nx file
-    if path.extension().and_then(|e| e.to_str()) != Some("nx") {
-        eprintln!(
-            "Warning: File '{}' does not have .nx extension",
-            path.display()
-        );
+    if parse_result.has_errors() {
+        render_source_diagnostics(&file_name, &source, &parse_result.errors)
+    } else {
+        Status::Success
     }
+}
 
-    // Read the source file once
-    let source = match std::fs::read_to_string(path) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("Error reading file: {}", e);
-            return ExitCode::from(1);
+/// Renders a syntax tree with one named node per line, indented by depth, as
+/// `kind start..end`. Leaf nodes also show their source text.
+fn render_cst(root: nx_syntax::SyntaxNode<'_>) -> String {
+    fn render(node: nx_syntax::SyntaxNode<'_>, depth: usize, out: &mut Vec<String>) {
+        let mut line = format!(
+            "{}{} {}..{}",
+            "  ".repeat(depth),
+            node.raw().kind(),
+            node.start_byte(),
+            node.end_byte()
+        );
+        if node.children().next().is_none() {
+            line.push_str(&format!(" {:?}", node.text()));
         }
-    };
+        out.push(line);
+        for child in node.children() {
+            render(child, depth + 1, out);
+        }
+    }
 
-    let program = match load_source_program_for_run(&source, path.as_path()) {
-        Ok(program) => program,
-        Err(exit_code) => return exit_code,
-    };
-    let Some(module) = program
-        .root_modules
-        .first()
-        .and_then(|artifact| artifact.lowered_module.as_ref())
-    else {
-        eprintln!("Error: No root module available for '{}'", path.display());
-        return ExitCode::from(1);
+    let mut lines = Vec::new();
+    render(root, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn check_file(
+    input: &SourceInput,
+    imports: &ProjectImports,
+    config: &NxConfig,
+    message_format: MessageFormat,
+    collected: Option<&mut Vec<NxDiagnostic>>,
+) -> Result<DiagnosticCounts, (Status, String)> {
+    let source = input
+        .read_source()
+        .map_err(|message| (Status::Internal, message))?;
+    let file_name = input.display_name();
+    let (diagnostics, fingerprint) = match imports.build_context(&source, input.path()) {
+        Ok(build_context) => {
+            let artifact = build_program_artifact_from_source(&source, &file_name, &build_context)
+                .map_err(|error| {
+                    (
+                        Status::Diagnostics,
+                        format!("Failed to build program artifact: {}", error),
+                    )
+                })?;
+            (
+                config.program_diagnostics(&artifact),
+                Some(artifact.fingerprint),
+            )
+        }
+        Err(diagnostics) => (diagnostics, None),
     };
 
-    // Check if there's a root function
-    let has_root = module
-        .items()
-        .iter()
-        .any(|item| matches!(item, Item::Function(f) if f.name.as_str() == "root"));
+    let diagnostics = config.apply_to_diagnostics(diagnostics);
+    let mut counts = DiagnosticCounts::default();
+    for diagnostic in &diagnostics {
+        match diagnostic.severity() {
+            Severity::Error => counts.errors += 1,
+            Severity::Warning => counts.warnings += 1,
+            Severity::Info | Severity::Hint => {}
+        }
+    }
 
-    if !has_root {
-        eprintln!("Error: No root element found in '{}'", path.display());
-        eprintln!("Hint: Add a top-level element to create an implicit root function.");
-        return ExitCode::from(1);
+    match (collected, message_format) {
+        (Some(collected), _) => collected.extend(diagnostics_to_api(&diagnostics, &source)),
+        (None, MessageFormat::Human) => print_source_diagnostics(&file_name, &source, &diagnostics),
+        (None, MessageFormat::JsonLines) => {
+            for diagnostic in diagnostics_to_api(&diagnostics, &source) {
+                messages::emit(&messages::diagnostic(&file_name, &diagnostic));
+            }
+            if let (0, Some(fingerprint)) = (counts.errors, fingerprint) {
+                messages::emit(&messages::artifact(&file_name, fingerprint));
+            }
+        }
     }
+    Ok(counts)
+}
 
-    // Execute the root function
-    let interpreter = Interpreter::from_resolved_program(program.resolved_program.clone());
-    match interpreter.execute_resolved_program_function("root", vec![]) {
-        Ok(value) => {
-            let output_text = match format_output(&value, format) {
-                Ok(output) => output,
-                Err(e) => {
-                    eprintln!("Error: {}", e);
-                    return ExitCode::from(1);
-                }
-            };
+/// Loads the configuration from `explicit_path`, or discovers nx.toml from the current directory.
+fn load_config(explicit_path: Option<&Path>) -> Result<NxConfig, Status> {
+    let config = match explicit_path {
+        Some(path) => NxConfig::load_file(path),
+        None => NxConfig::discover("."),
+    };
+    config.map_err(|error| {
+        eprintln!("Error: {}", error);
+        Status::Usage
+    })
+}
 
-            if let Some(output_path) = output {
-                if let Err(e) = std::fs::write(output_path, format!("{}\n", output_text)) {
-                    eprintln!("Error writing output to '{}': {}", output_path.display(), e);
-                    return ExitCode::from(1);
-                }
-            } else {
-                println!("{}", output_text);
-            }
+/// Libraries imported by the files of one invocation, loaded on demand and shared between files.
+struct ProjectImports {
+    registry: LibraryRegistry,
+    resolver: ImportPathResolver,
+}
 
-            ExitCode::SUCCESS
-        }
-        Err(e) => {
-            eprintln!("Runtime error: {}", e);
-            ExitCode::from(1)
+impl ProjectImports {
+    fn new(config: &NxConfig) -> Self {
+        Self {
+            registry: LibraryRegistry::with_module_resolver(Arc::new(FilesystemModuleResolver)),
+            resolver: ImportPathResolver::new(config.imports.search_paths.iter().cloned()),
         }
     }
+
+    /// Loads the libraries imported by `source` and returns a build context that can see them,
+    /// or the diagnostics for imports that could not be resolved or loaded.
+    fn build_context(
+        &self,
+        source: &str,
+        path: &Path,
+    ) -> Result<ProgramBuildContext, Vec<nx_diagnostics::Diagnostic>> {
+        self.registry
+            .build_context_for_source(source, path, &self.resolver)
+    }
+}
+
+fn pluralize(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
 }
 
 fn generate_types(
@@ -220,12 +1609,12 @@ fn generate_types(
     editorconfig: Option<&PathBuf>,
     csharp_namespace: &str,
     typescript_package_prefix: Option<&str>,
-) -> ExitCode {
+) -> Status {
     let input_kind = match classify_generate_input(path) {
         Ok(kind) => kind,
         Err(message) => {
             eprintln!("Error: {}", message);
-            return ExitCode::from(1);
+            return Status::Usage;
         }
     };
 
@@ -254,7 +1643,7 @@ fn generate_types(
         Ok(format) => format,
         Err(message) => {
             eprintln!("Error: {}", message);
-            return ExitCode::from(1);
+            return Status::Usage;
         }
     };
     let opts = codegen::GenerateTypesOptions {
@@ -316,27 +1705,24 @@ fn generate_types_from_file(
     path: &Path,
     output: Option<&PathBuf>,
     opts: &codegen::GenerateTypesOptions,
-) -> ExitCode {
+) -> Status {
     if output.is_some_and(|output_path| output_path.is_dir()) {
         eprintln!(
             "Error: Single-file generation requires --output to be a file path, not a directory"
         );
-        return ExitCode::from(1);
+        return Status::Usage;
     }
 
     let source = match std::fs::read_to_string(path) {
         Ok(source) => source,
         Err(error) => {
             eprintln!("Error reading file: {}", error);
-            return ExitCode::from(1);
+            return Status::Internal;
         }
     };
 
-    let file_name = path
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("unknown");
-    let module = match load_source_module(&source, file_name, path) {
+    let file_name = inputs::display_name(path);
+    let module = match load_source_module(&source, &file_name, path) {
         Ok(module) => module,
         Err(exit_code) => return exit_code,
     };
@@ -345,7 +1731,7 @@ fn generate_types_from_file(
         Ok(output) => output,
         Err(message) => {
             eprintln!("Error: {}", message);
-            return ExitCode::from(1);
+            return Status::Diagnostics;
         }
     };
     render_generate_warnings(&generated.warnings);
@@ -357,28 +1743,28 @@ fn generate_types_from_file(
                 output_path.display(),
                 error
             );
-            return ExitCode::from(1);
+            return Status::Internal;
         }
     } else {
         print!("{}", generated.value);
     }
 
-    ExitCode::SUCCESS
+    Status::Success
 }
 
 fn generate_types_from_library(
     path: &Path,
     output: Option<&PathBuf>,
     opts: &codegen::GenerateTypesOptions,
-) -> ExitCode {
+) -> Status {
     let Some(output_root) = output else {
         eprintln!("Error: Library generation requires an output directory");
-        return ExitCode::from(1);
+        return Status::Usage;
     };
 
     if output_root.exists() && !output_root.is_dir() {
         eprintln!("Error: Library generation requires --output to be a directory root");
-        return ExitCode::from(1);
+        return Status::Usage;
     }
 
     let registry = LibraryRegistry::new();
@@ -392,14 +1778,14 @@ fn generate_types_from_library(
             "Error: '{}' is not a valid NX library root because it contains no .nx source files",
             path.display()
         );
-        return ExitCode::from(1);
+        return Status::Usage;
     }
 
     let generated = match codegen::generate_library_types_with_warnings(library.as_ref(), opts) {
         Ok(output) => output,
         Err(message) => {
             eprintln!("Error: {}", message);
-            return ExitCode::from(1);
+            return Status::Diagnostics;
         }
     };
     render_generate_warnings(&generated.warnings);
@@ -410,7 +1796,7 @@ fn generate_types_from_library(
             output_root.display(),
             error
         );
-        return ExitCode::from(1);
+        return Status::Internal;
     }
 
     for file in generated.value {
@@ -418,7 +1804,7 @@ fn generate_types_from_library(
             Ok(path) => path,
             Err(message) => {
                 eprintln!("Error: {}", message);
-                return ExitCode::from(1);
+                return Status::Internal;
             }
         };
         if let Some(parent) = target_path.parent() {
@@ -428,7 +1814,7 @@ fn generate_types_from_library(
                     parent.display(),
                     error
                 );
-                return ExitCode::from(1);
+                return Status::Internal;
             }
         }
 
@@ -438,11 +1824,11 @@ fn generate_types_from_library(
                 target_path.display(),
                 error
             );
-            return ExitCode::from(1);
+            return Status::Internal;
         }
     }
 
-    ExitCode::SUCCESS
+    Status::Success
 }
 
 fn render_generate_warnings(warnings: &[String]) {
@@ -474,7 +1860,7 @@ fn resolve_generated_output_path(
     Ok(output_root.join(relative_path))
 }
 
-fn render_api_diagnostics(diagnostics: &[NxDiagnostic]) -> ExitCode {
+fn render_api_diagnostics(diagnostics: &[NxDiagnostic]) -> Status {
     for diagnostic in diagnostics {
         eprintln!("error: {}", diagnostic.message);
 
@@ -501,13 +1887,14 @@ fn render_api_diagnostics(diagnostics: &[NxDiagnostic]) -> ExitCode {
         }
     }
 
-    ExitCode::from(1)
+    Status::Diagnostics
 }
 
 fn format_output(value: &Value, format: OutputFormat) -> Result<String, String> {
     match format {
         OutputFormat::Nx => Ok(format::format_value(value)),
         OutputFormat::Json => json::format_value_json_pretty(value),
+        OutputFormat::Dot => Ok(dot::format_value_dot(value)),
     }
 }
 
@@ -515,21 +1902,24 @@ fn load_source_module(
     source: &str,
     file_name: &str,
     _path: &Path,
-) -> Result<LoweredModule, ExitCode> {
+) -> Result<LoweredModule, Status> {
     match lower_source_module(source, file_name) {
         Ok(module) => Ok(module),
         Err(diagnostics) => Err(render_source_diagnostics(file_name, source, &diagnostics)),
     }
 }
 
-fn load_source_program_for_run(source: &str, path: &Path) -> Result<ProgramArtifact, ExitCode> {
-    let file_name = path.display().to_string();
-    let build_context = ProgramBuildContext::empty();
-    let program = match build_program_artifact_from_source(source, &file_name, &build_context) {
+fn load_source_program_for_run(
+    source: &str,
+    path: &Path,
+    build_context: &ProgramBuildContext,
+) -> Result<ProgramArtifact, Status> {
+    let file_name = inputs::display_name(path);
+    let program = match build_program_artifact_from_source(source, &file_name, build_context) {
         Ok(program) => program,
         Err(error) => {
             eprintln!("Error: Failed to build program artifact: {}", error);
-            return Err(ExitCode::from(1));
+            return Err(Status::Diagnostics);
         }
     };
 
@@ -552,7 +1942,16 @@ fn render_source_diagnostics(
     file_name: &str,
     source: &str,
     diagnostics: &[nx_diagnostics::Diagnostic],
-) -> ExitCode {
+) -> Status {
+    print_source_diagnostics(file_name, source, diagnostics);
+    Status::Diagnostics
+}
+
+fn print_source_diagnostics(
+    file_name: &str,
+    source: &str,
+    diagnostics: &[nx_diagnostics::Diagnostic],
+) {
     let mut sources = HashMap::new();
     sources.insert(file_name.to_string(), source.to_string());
     for diagnostic in diagnostics {
@@ -564,7 +1963,6 @@ fn render_source_diagnostics(
     }
     let rendered = render_diagnostics_cli(diagnostics, &sources);
     eprint!("{}", rendered);
-    ExitCode::from(1)
 }
 
 #[cfg(test)]
@@ -643,6 +2041,42 @@ mod tests {
         artifact
     }
 
+    #[test]
+    fn test_format_symbol_reports_position_kind_and_container() {
+        let source = "let <Card /> = <div />\n\nenum Tone = | Light | Dark";
+        let mut session = TypeCheckSession::new();
+        session.add_file("ui/card.nx", source);
+
+        let lines: Vec<_> = session
+            .workspace_symbols("")
+            .iter()
+            .map(|symbol| format_symbol(symbol, Some(source)))
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "ui/card.nx:1:1 function Card",
+                "ui/card.nx:3:23 enum member Tone.Dark",
+                "ui/card.nx:3:1 type Tone",
+                "ui/card.nx:3:15 enum member Tone.Light",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_cst_shows_named_nodes_with_ranges_and_leaf_text() {
+        let parse_result = nx_syntax::parse_str("let x = { 1 }", "x.nx");
+
+        let cst = render_cst(parse_result.root().unwrap());
+
+        let lines: Vec<_> = cst.lines().collect();
+        assert_eq!(lines[0], "module_definition 0..13");
+        assert_eq!(lines[1], "  value_definition 0..13");
+        assert_eq!(lines[2], "    identifier 4..5 \"x\"");
+        assert!(lines.last().unwrap().ends_with("int_literal 10..11 \"1\""));
+    }
+
     #[test]
     fn test_run_simple_element() {
         let (_dir, path) = create_temp_nx_file("<div class=\"test\" />");
@@ -864,6 +2298,32 @@ let root() = { Ui.title() }"#,
 
     /// Helper to run the CLI binary with arguments and capture output
     fn run_cli(args: &[&str]) -> std::process::Output {
+        cli_command(args)
+            .output()
+            .expect("Failed to execute CLI - ensure 'cargo build' was run first")
+    }
+
+    /// Helper to run the CLI binary with `stdin` piped to it and capture output
+    fn run_cli_with_stdin(args: &[&str], stdin: &str) -> std::process::Output {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let mut child = cli_command(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute CLI - ensure 'cargo build' was run first");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    fn cli_command(args: &[&str]) -> std::process::Command {
         use std::process::Command;
         use std::sync::Once;
 
@@ -890,10 +2350,9 @@ let root() = { Ui.title() }"#,
             .join("debug")
             .join("nxlang");
 
-        Command::new(&target_dir)
-            .args(args)
-            .output()
-            .expect("Failed to execute CLI - ensure 'cargo build' was run first")
+        let mut command = Command::new(&target_dir);
+        command.args(args);
+        command
     }
 
     #[test]
@@ -938,53 +2397,218 @@ let root() = { Ui.title() }"#,
               age: int = 30
             }
 
-            let root() = { <User name="Bob" /> }
-        "#;
-        let (_dir, path) = create_temp_nx_file(source);
+            let root() = { <User name="Bob" /> }
+        "#;
+        let (_dir, path) = create_temp_nx_file(source);
+
+        let output = run_cli(&["run", path.to_str().unwrap(), "--format", "json"]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let value = NxValue::from_json_str(stdout.trim()).unwrap();
+
+        let NxValue::Record {
+            type_name,
+            properties,
+        } = value
+        else {
+            panic!("Expected JSON record. Got: {:?}", value);
+        };
+
+        assert_eq!(type_name.as_deref(), Some("User"));
+        assert_eq!(
+            properties.get("name"),
+            Some(&NxValue::String("Bob".to_string()))
+        );
+        assert_eq!(properties.get("age"), Some(&NxValue::Int(30)));
+    }
+
+    #[test]
+    fn test_cli_run_json_output_to_file() {
+        let (dir, file_path) = create_temp_nx_file("let root() = { 42 }");
+        let output_path = dir.path().join("out.json");
+
+        let output = run_cli(&[
+            "run",
+            file_path.to_str().unwrap(),
+            "--format",
+            "json",
+            "--output",
+            output_path.to_str().unwrap(),
+        ]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.trim(), "");
+
+        let written = fs::read_to_string(&output_path).unwrap();
+        let value = NxValue::from_json_str(written.trim()).unwrap();
+        assert_eq!(value, NxValue::Int(42));
+    }
+
+    #[test]
+    fn test_cli_run_json_mode_prints_compact_result_and_empty_diagnostics() {
+        let source = r#"
+            type User = {
+              name: string
+              age: int
+            }
+
+            let root() = { <User name="Ada" age={36} /> }
+        "#;
+        let (_dir, path) = create_temp_nx_file(source);
+
+        let output = run_cli(&["run", path.to_str().unwrap(), "--json"]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(
+            stdout.lines().count(),
+            1,
+            "result should be one line: {stdout}"
+        );
+        let value = NxValue::from_json_str(stdout.trim()).unwrap();
+        let NxValue::Record { properties, .. } = value else {
+            panic!("Expected JSON record. Got: {:?}", value);
+        };
+        assert_eq!(properties.get("age"), Some(&NxValue::Int(36)));
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let diagnostics: Vec<NxDiagnostic> = serde_json::from_str(stderr.trim()).unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_cli_run_json_mode_reports_diagnostics_as_json() {
+        let (_dir, path) = create_temp_nx_file("let root() = { missing }");
+
+        let output = run_cli(&["run", path.to_str().unwrap(), "--json"]);
+
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let diagnostics: Vec<NxDiagnostic> = serde_json::from_str(stderr.trim()).unwrap();
+        assert!(!diagnostics.is_empty());
+        assert_eq!(diagnostics[0].severity, nx_api::NxSeverity::Error);
+        assert!(diagnostics[0].labels[0].file.ends_with("test.nx"));
+    }
+
+    #[test]
+    fn test_cli_run_json_mode_conflicts_with_format() {
+        let (_dir, path) = create_temp_nx_file("let root() = { 42 }");
+
+        let output = run_cli(&["run", path.to_str().unwrap(), "--json", "--format", "nx"]);
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("cannot be used with"));
+    }
+
+    #[test]
+    fn test_cli_run_reads_source_from_stdin() {
+        let output = run_cli_with_stdin(&["run", "-"], "let root() = { 40 + 2 }");
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "42");
+    }
+
+    #[test]
+    fn test_cli_check_stdin_uses_stdin_filename_in_diagnostics() {
+        let output = run_cli_with_stdin(
+            &["check", "-", "--stdin-filename", "src/unsaved.nx"],
+            "let root() = { missing }",
+        );
+
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("src/unsaved.nx"), "stderr: {stderr}");
+        assert!(stderr.contains("Checked 1 file: 1 error, 0 warnings"));
+    }
+
+    #[test]
+    fn test_cli_run_stdin_cannot_be_combined_with_files() {
+        let (_dir, path) = create_temp_nx_file("let root() = { 1 }");
+
+        let output = run_cli_with_stdin(&["run", "-", path.to_str().unwrap()], "");
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("cannot be combined with other inputs"));
+    }
+
+    #[test]
+    fn test_cli_completions_cover_subcommand_flags() {
+        let output = run_cli(&["completions", "bash"]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("nxlang"));
+        assert!(stdout.contains("--stdin-filename"));
+        assert!(stdout.contains("--typescript-package-prefix"));
+    }
+
+    #[test]
+    fn test_cli_completions_reject_unknown_shell() {
+        let output = run_cli(&["completions", "tcsh"]);
+
+        assert!(!output.status.success());
+    }
 
-        let output = run_cli(&["run", path.to_str().unwrap(), "--format", "json"]);
+    #[test]
+    fn test_cli_man_writes_page_to_file() {
+        let dir = TempDir::new().unwrap();
+        let output_path = dir.path().join("nxlang.1");
+
+        let output = run_cli(&["man", "--output", output_path.to_str().unwrap()]);
 
         assert!(output.status.success());
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let value = NxValue::from_json_str(stdout.trim()).unwrap();
+        let page = fs::read_to_string(&output_path).unwrap();
+        assert!(page.starts_with(".TH NXLANG 1"));
+        assert!(page.contains(".SS \"nxlang check\""));
+        assert!(page.contains("\\fB\\-\\-stdin\\-filename\\fR"));
+    }
 
-        let NxValue::Record {
-            type_name,
-            properties,
-        } = value
-        else {
-            panic!("Expected JSON record. Got: {:?}", value);
-        };
+    #[test]
+    fn test_cli_exit_codes_distinguish_usage_and_diagnostics() {
+        let missing = run_cli(&["check", "/nonexistent/path/to/file.nx"]);
+        assert_eq!(missing.status.code(), Some(2));
 
-        assert_eq!(type_name.as_deref(), Some("User"));
-        assert_eq!(
-            properties.get("name"),
-            Some(&NxValue::String("Bob".to_string()))
-        );
-        assert_eq!(properties.get("age"), Some(&NxValue::Int(30)));
+        let bad_flag = run_cli(&["check", "--no-such-flag"]);
+        assert_eq!(bad_flag.status.code(), Some(2));
+
+        let (_dir, path) = create_temp_nx_file("let root() = { missing }");
+        let errors = run_cli(&["check", path.to_str().unwrap()]);
+        assert_eq!(errors.status.code(), Some(1));
+
+        let runtime = create_temp_nx_file("let root() = { 1 / 0 }");
+        let runtime_error = run_cli(&["run", runtime.1.to_str().unwrap()]);
+        assert_eq!(runtime_error.status.code(), Some(1));
     }
 
     #[test]
-    fn test_cli_run_json_output_to_file() {
-        let (dir, file_path) = create_temp_nx_file("let root() = { 42 }");
-        let output_path = dir.path().join("out.json");
+    fn test_cli_run_reports_write_failures_as_internal_errors() {
+        let (dir, path) = create_temp_nx_file("let root() = { 42 }");
+        let output_path = dir.path().join("missing-dir").join("out.txt");
 
         let output = run_cli(&[
             "run",
-            file_path.to_str().unwrap(),
-            "--format",
-            "json",
+            path.to_str().unwrap(),
             "--output",
             output_path.to_str().unwrap(),
         ]);
 
+        assert_eq!(output.status.code(), Some(3));
+    }
+
+    #[test]
+    fn test_cli_check_max_warnings() {
+        let (_dir, path) = create_temp_nx_file("let root() = { 1 }");
+
+        let output = run_cli(&["check", path.to_str().unwrap(), "--max-warnings", "0"]);
         assert!(output.status.success());
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        assert_eq!(stdout.trim(), "");
 
-        let written = fs::read_to_string(&output_path).unwrap();
-        let value = NxValue::from_json_str(written.trim()).unwrap();
-        assert_eq!(value, NxValue::Int(42));
+        let invalid = run_cli(&["check", path.to_str().unwrap(), "--max-warnings", "-1"]);
+        assert_eq!(invalid.status.code(), Some(2));
     }
 
     #[test]
@@ -1009,6 +2633,39 @@ let root() = { Ui.title() }"#,
         assert!(stderr.contains("Hint:"));
     }
 
+    #[test]
+    fn test_cli_run_entry_selects_function_and_lists_candidates() {
+        let (_dir, path) = create_temp_nx_file(
+            "let summary() = { 40 + 2 }\nlet <Button text:string /> = <button>{text}</button>",
+        );
+
+        let output = run_cli(&["run", path.to_str().unwrap(), "--entry", "summary"]);
+        assert!(output.status.success(), "{:?}", output);
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "42");
+
+        let missing = run_cli(&["run", path.to_str().unwrap(), "--entry", "Button"]);
+        assert_eq!(missing.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&missing.stderr);
+        assert!(stderr.contains("named 'Button'"), "{}", stderr);
+        assert!(stderr.contains("Runnable entries"), "{}", stderr);
+        assert!(stderr.contains("  summary"), "{}", stderr);
+
+        let no_root = run_cli(&["run", path.to_str().unwrap()]);
+        let stderr = String::from_utf8_lossy(&no_root.stderr);
+        assert!(stderr.contains("No root element found"), "{}", stderr);
+        assert!(stderr.contains("  summary"), "{}", stderr);
+
+        let json = run_cli(&[
+            "run",
+            path.to_str().unwrap(),
+            "--json",
+            "--entry",
+            "summary",
+        ]);
+        assert!(json.status.success(), "{:?}", json);
+        assert_eq!(String::from_utf8_lossy(&json.stdout).trim(), "42");
+    }
+
     #[test]
     fn test_cli_generate_file_infers_single_file_generation() {
         let source = r#"
@@ -1568,6 +3225,466 @@ export type QuestionFlowInitialExperience = {
         assert!(stderr.contains("FILE") || stderr.contains("required"));
     }
 
+    #[test]
+    fn test_cli_run_multiple_files_reports_each_result() {
+        let (dir, first) = create_temp_nx_file("let root() = { 1 }");
+        let second = dir.path().join("second.nx");
+        fs::write(&second, "let root() = { 2 }").unwrap();
+
+        let output = run_cli(&["run", first.to_str().unwrap(), second.to_str().unwrap()]);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(&format!("==> {} <==\n1", first.display())));
+        assert!(stdout.contains(&format!("==> {} <==\n2", second.display())));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Ran 2 files: 2 succeeded, 0 failed"));
+    }
+
+    #[test]
+    fn test_cli_run_multiple_files_rejects_single_output_path() {
+        let (dir, _) = create_temp_library(&[
+            ("a.nx", "let root() = { 1 }"),
+            ("b.nx", "let root() = { 2 }"),
+        ]);
+        let output_path = dir.path().join("out.txt");
+
+        let output = run_cli(&[
+            "run",
+            dir.path().join("library").to_str().unwrap(),
+            "--output",
+            output_path.to_str().unwrap(),
+        ]);
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--output can only be used when running a single file"));
+    }
+
+    #[test]
+    fn test_cli_check_directory_summarizes_diagnostics() {
+        let (_dir, library) = create_temp_library(&[
+            ("good.nx", "let root() = { 1 }"),
+            ("nested/bad.nx", "let root() = { missing }"),
+        ]);
+
+        let output = run_cli(&["check", library.to_str().unwrap()]);
+
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let bad = library.join("nested").join("bad.nx");
+        assert!(stderr.contains(&format!("{}: 1 error, 0 warnings", bad.display())));
+        assert!(!stderr.contains(&format!("{}:", library.join("good.nx").display())));
+        assert!(stderr.contains("Checked 2 files: 1 error, 0 warnings"));
+    }
+
+    #[test]
+    fn test_cli_check_json_lines_streams_events_per_file() {
+        let (_dir, library) = create_temp_library(&[
+            ("good.nx", "let root() = { 1 }"),
+            ("nested/bad.nx", "let root() = { missing }"),
+        ]);
+
+        let output = run_cli(&[
+            "check",
+            library.to_str().unwrap(),
+            "--message-format",
+            "json-lines",
+        ]);
+
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stderr.is_empty());
+        let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is a JSON object"))
+            .collect();
+        let reasons: Vec<&str> = events
+            .iter()
+            .map(|event| event["reason"].as_str().unwrap())
+            .collect();
+        let good = library.join("good.nx").display().to_string();
+        let bad = library.join("nested").join("bad.nx").display().to_string();
+
+        assert_eq!(reasons.first(), Some(&"artifact"));
+        assert_eq!(events[0]["file"], good.as_str());
+        let diagnostic = events
+            .iter()
+            .find(|event| event["reason"] == "diagnostic")
+            .expect("the bad file reports a diagnostic");
+        assert_eq!(diagnostic["file"], bad.as_str());
+        assert_eq!(diagnostic["diagnostic"]["severity"], "error");
+        assert!(events
+            .iter()
+            .any(|event| event["reason"] == "stage-finished" && event["stage"] == "check"));
+        assert_eq!(
+            events.last().unwrap(),
+            &serde_json::json!({
+                "reason": "finished",
+                "success": false,
+                "files": 2,
+                "errors": 1,
+                "warnings": 0,
+                "max_warnings_exceeded": false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_cli_check_json_prints_one_array_for_all_files() {
+        let (_dir, library) = create_temp_library(&[
+            ("a.nx", "let root() = { missing }"),
+            ("b.nx", "let root() = { 1 }"),
+            ("c.nx", "let value() = { other }"),
+        ]);
+
+        let output = run_cli(&["check", library.to_str().unwrap(), "--json"]);
+
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stderr.is_empty());
+        let diagnostics: Vec<serde_json::Value> =
+            serde_json::from_slice(&output.stdout).expect("stdout is one JSON array");
+        let files: Vec<&str> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic["labels"][0]["file"].as_str().unwrap())
+            .collect();
+        assert_eq!(files.len(), 2, "{files:?}");
+        assert!(files[0].ends_with("a.nx") && files[1].ends_with("c.nx"));
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic["severity"] == "error"));
+
+        let output = run_cli(&["check", library.join("b.nx").to_str().unwrap(), "--json"]);
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "[]");
+    }
+
+    #[test]
+    fn test_cli_check_json_reports_record_default_cycle_code() {
+        let (_dir, library) = create_temp_library(&[(
+            "cycle.nx",
+            "type Loop = {\n  x: int = { y }\n  y: int = { x }\n}\n",
+        )]);
+
+        let output = run_cli(&["check", library.to_str().unwrap(), "--json"]);
+
+        assert_eq!(output.status.code(), Some(1));
+        let diagnostics: Vec<serde_json::Value> =
+            serde_json::from_slice(&output.stdout).expect("stdout is one JSON array");
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        assert_eq!(diagnostics[0]["code"], "record-default-cycle");
+    }
+
+    #[test]
+    fn test_cli_format_rewrites_files_and_check_reports_them() {
+        let (_dir, library) = create_temp_library(&[
+            ("clean.nx", "let a = 1\n"),
+            ("messy.nx", "let   f() = {\n1+2\n}"),
+        ]);
+        let messy = library.join("messy.nx");
+
+        let output = run_cli(&["format", library.to_str().unwrap(), "--check"]);
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(&format!("{}: not formatted", messy.display())));
+        assert!(!stderr.contains("clean.nx"));
+        assert!(stderr.contains("Checked 2 files: 1 not formatted"));
+        assert_eq!(fs::read_to_string(&messy).unwrap(), "let   f() = {\n1+2\n}");
+
+        let output = run_cli(&["format", library.to_str().unwrap(), "--indent-width", "4"]);
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(&messy).unwrap(),
+            "let f() = {\n    1 + 2\n}\n"
+        );
+        let output = run_cli(&[
+            "format",
+            library.to_str().unwrap(),
+            "--check",
+            "--indent-width",
+            "4",
+        ]);
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_format_uses_config_indent_width_unless_overridden() {
+        let (_dir, library) = create_temp_library(&[("messy.nx", "let   f() = {\n1+2\n}")]);
+        let messy = library.join("messy.nx");
+        let config = library.join("nx.toml");
+        fs::write(&config, "root = true\n[format]\nindent-width = 4\n").unwrap();
+
+        let output = run_cli(&[
+            "format",
+            messy.to_str().unwrap(),
+            "--config",
+            config.to_str().unwrap(),
+        ]);
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(&messy).unwrap(),
+            "let f() = {\n    1 + 2\n}\n"
+        );
+
+        let output = run_cli(&[
+            "format",
+            messy.to_str().unwrap(),
+            "--config",
+            config.to_str().unwrap(),
+            "--indent-width",
+            "2",
+        ]);
+        assert!(output.status.success());
+        assert_eq!(
+            fs::read_to_string(&messy).unwrap(),
+            "let f() = {\n  1 + 2\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_cli_format_reports_syntax_errors_without_writing() {
+        let (_dir, library) = create_temp_library(&[("broken.nx", "let broken = {")]);
+        let broken = library.join("broken.nx");
+
+        let output = run_cli(&["format", broken.to_str().unwrap()]);
+
+        assert_eq!(output.status.code(), Some(1));
+        assert!(!output.stderr.is_empty());
+        assert_eq!(fs::read_to_string(&broken).unwrap(), "let broken = {");
+    }
+
+    #[test]
+    fn test_cli_explain_prints_the_explanation_of_a_code() {
+        let output = run_cli(&["explain", "tag-mismatch"]);
+
+        assert_eq!(output.status.code(), Some(0));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("tag-mismatch: "), "{stdout}");
+        assert!(stdout.contains("    let root() = { <div><span>Hi</span></div> }"));
+
+        let output = run_cli(&["explain"]);
+        assert_eq!(output.status.code(), Some(0));
+        assert!(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .any(|line| line.starts_with("type-mismatch ")));
+    }
+
+    #[test]
+    fn test_cli_explain_suggests_a_close_code_for_unknown_codes() {
+        let output = run_cli(&["explain", "tag-missmatch"]);
+
+        assert_eq!(output.status.code(), Some(2));
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("no explanation for diagnostic code 'tag-missmatch'"));
+        assert!(stderr.contains("Did you mean `tag-mismatch`?"), "{stderr}");
+    }
+
+    #[test]
+    fn test_cli_conformance_json_lines_reports_each_case() {
+        let (_dir, suite) = create_temp_library(&[
+            ("one.nx", "let root() = { 1 }"),
+            ("one.expected.json", "1"),
+            ("two.nx", "let root() = { 2 }"),
+            ("two.expected.json", "3"),
+        ]);
+
+        let output = run_cli(&[
+            "conformance",
+            suite.to_str().unwrap(),
+            "--message-format",
+            "json-lines",
+        ]);
+
+        assert_eq!(output.status.code(), Some(1));
+        let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .filter(|event: &serde_json::Value| event["reason"] != "stage-finished")
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["passed"], true);
+        assert_eq!(events[0]["message"], serde_json::Value::Null);
+        assert_eq!(events[1]["passed"], false);
+        assert!(events[1]["message"].is_string());
+        assert_eq!(events[2]["reason"], "finished");
+        assert_eq!(events[2]["passed"], 1);
+        assert_eq!(events[2]["failed"], 1);
+        assert_eq!(events[2]["backend"], "interpreter");
+    }
+
+    #[test]
+    fn test_cli_conformance_runs_the_bundled_suite_on_the_bytecode_vm() {
+        let suite = Path::new(env!("CARGO_MANIFEST_DIR")).join("../nx-conformance/tests/suite");
+
+        let output = run_cli(&[
+            "conformance",
+            suite.to_str().unwrap(),
+            "--backend",
+            "bytecode",
+        ]);
+
+        assert!(output.status.success(), "{output:?}");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains("PASS") && stdout.contains("evaluation_order"),
+            "{stdout}"
+        );
+        assert!(stdout.contains("0 failed (bytecode)"), "{stdout}");
+    }
+
+    #[test]
+    fn test_cli_check_glob_pattern_succeeds_for_clean_files() {
+        let (_dir, library) = create_temp_library(&[
+            ("src/a.nx", "let root() = { 1 }"),
+            ("src/nested/b.nx", "let value() = { \"b\" }"),
+            ("src/notes.txt", "not nx"),
+        ]);
+        let pattern = library.join("src").join("**").join("*.nx");
+
+        let output = run_cli(&["check", pattern.to_str().unwrap()]);
+
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Checked 2 files: 0 errors, 0 warnings"));
+    }
+
+    #[test]
+    fn test_cli_check_unmatched_pattern_fails() {
+        let dir = TempDir::new().unwrap();
+        let pattern = dir.path().join("*.nx");
+
+        let output = run_cli(&["check", pattern.to_str().unwrap()]);
+
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("No files match"));
+    }
+
+    #[test]
+    fn test_cli_check_applies_config_lint_levels() {
+        let (dir, path) = create_temp_nx_file("let root() = { missing }");
+        let config = dir.path().join("nx.toml");
+        fs::write(
+            &config,
+            "root = true\n[lints]\nundefined-identifier = \"warn\"\n",
+        )
+        .unwrap();
+
+        let output = run_cli(&[
+            "check",
+            path.to_str().unwrap(),
+            "--config",
+            config.to_str().unwrap(),
+        ]);
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Checked 1 file: 0 errors, 1 warning"));
+
+        fs::write(
+            &config,
+            "root = true\n[check]\nmax-warnings = 0\n[lints]\nundefined-identifier = \"warn\"\n",
+        )
+        .unwrap();
+        let limited = run_cli(&[
+            "check",
+            path.to_str().unwrap(),
+            "--config",
+            config.to_str().unwrap(),
+        ]);
+        assert_eq!(limited.status.code(), Some(1));
+    }
+
+    #[test]
+    fn test_cli_check_uses_config_file_patterns() {
+        let (_dir, library) = create_temp_library(&[
+            ("src/a.nx", "let root() = { 1 }"),
+            ("src/generated/b.nx", "let root() = { missing }"),
+        ]);
+        let config = library.join("nx.toml");
+        fs::write(
+            &config,
+            "root = true\n[files]\ninclude = [\"src\"]\nexclude = [\"src/generated/**\"]\n",
+        )
+        .unwrap();
+
+        let output = run_cli(&["check", "--config", config.to_str().unwrap()]);
+
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Checked 1 file: 0 errors, 0 warnings"));
+    }
+
+    #[test]
+    fn test_cli_run_resolves_relative_and_lib_root_imports() {
+        let dir = TempDir::new().unwrap();
+        let app_dir = dir.path().join("app");
+        fs::create_dir_all(dir.path().join("math")).unwrap();
+        fs::create_dir_all(dir.path().join("lib").join("ui")).unwrap();
+        fs::create_dir_all(&app_dir).unwrap();
+        fs::write(
+            dir.path().join("math").join("add.nx"),
+            "export let addOne(n:int) = { n + 1 }",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("lib").join("ui").join("ten.nx"),
+            "export let ten() = { 10 }",
+        )
+        .unwrap();
+        fs::write(
+            app_dir.join("main.nx"),
+            "import \"../math\" as Math\nimport \"ui\"\nlet root() = { Math.addOne(ten()) }",
+        )
+        .unwrap();
+        fs::write(
+            app_dir.join("missing.nx"),
+            "import \"charts\"\nlet root() = { 1 }",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("nx.toml"),
+            "root = true\n[imports]\nsearch-paths = [\"lib\"]\n",
+        )
+        .unwrap();
+
+        let output = cli_command(&["run", "main.nx"])
+            .current_dir(&app_dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "11");
+
+        let missing = cli_command(&["check", "missing.nx"])
+            .current_dir(&app_dir)
+            .output()
+            .unwrap();
+        assert_eq!(missing.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&missing.stderr);
+        assert!(stderr.contains("Import 'charts' not found, searched: ./charts, "));
+    }
+
+    #[test]
+    fn test_cli_invalid_config_is_a_usage_error() {
+        let (dir, path) = create_temp_nx_file("let root() = { 1 }");
+        let config = dir.path().join("nx.toml");
+        fs::write(&config, "[check]\nmax-warning = 3\n").unwrap();
+
+        let output = run_cli(&[
+            "run",
+            path.to_str().unwrap(),
+            "--config",
+            config.to_str().unwrap(),
+        ]);
+
+        assert_eq!(output.status.code(), Some(2));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("Did you mean `max-warnings`?"));
+    }
+
     #[test]
     fn test_cli_run_parse_error_shows_line_numbers() {
         // Create a file with a syntax error
@@ -0,0 +1,258 @@
+//! Man page generation from the clap command definition.
+//!
+//! The page is rendered directly from the `Command` metadata, so new subcommands and flags show
+//! up without any changes here.
+
+use clap::{Arg, Command};
+use std::fmt::Write;
+
+/// Renders a roff man page (section 1) for `command` and all of its subcommands.
+pub fn render_man_page(command: &Command) -> String {
+    let mut command = command.clone();
+    command.build();
+
+    let name = command.get_name().to_string();
+    let version = command.get_version().unwrap_or_default().to_string();
+    let mut page = String::new();
+
+    writeln!(
+        page,
+        ".TH {} 1 \"\" \"{} {}\" \"User Commands\"",
+        name.to_uppercase(),
+        escape(&name),
+        escape(&version)
+    )
+    .unwrap();
+
+    page.push_str(".SH NAME\n");
+    match command.get_about() {
+        Some(about) => writeln!(page, "{} \\- {}", escape(&name), escape(&about.to_string())),
+        None => writeln!(page, "{}", escape(&name)),
+    }
+    .unwrap();
+
+    page.push_str(".SH SYNOPSIS\n");
+    writeln!(page, "{}", synopsis(&name, &command)).unwrap();
+
+    if let Some(about) = command.get_long_about() {
+        page.push_str(".SH DESCRIPTION\n");
+        write_paragraphs(&mut page, &about.to_string());
+    }
+
+    write_options(&mut page, &command, ".SH OPTIONS");
+
+    let subcommands = visible_subcommands(&command).collect::<Vec<_>>();
+    if !subcommands.is_empty() {
+        page.push_str(".SH COMMANDS\n");
+        for subcommand in subcommands {
+            let full_name = format!("{} {}", name, subcommand.get_name());
+            writeln!(page, ".SS \"{}\"", escape(&full_name)).unwrap();
+            writeln!(page, "{}", synopsis(&full_name, subcommand)).unwrap();
+            if let Some(about) = subcommand
+                .get_long_about()
+                .or_else(|| subcommand.get_about())
+            {
+                page.push_str(".PP\n");
+                write_paragraphs(&mut page, &about.to_string());
+            }
+            write_options(&mut page, subcommand, ".PP\nOptions:");
+        }
+    }
+
+    if !version.is_empty() {
+        page.push_str(".SH VERSION\n");
+        writeln!(page, "v{}", escape(&version)).unwrap();
+    }
+
+    page
+}
+
+fn visible_subcommands(command: &Command) -> impl Iterator<Item = &Command> {
+    command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set() && subcommand.get_name() != "help")
+}
+
+fn visible_arguments(command: &Command) -> impl Iterator<Item = &Arg> {
+    command.get_arguments().filter(|arg| !arg.is_hide_set())
+}
+
+fn synopsis(name: &str, command: &Command) -> String {
+    let mut synopsis = format!("\\fB{}\\fR", escape(name));
+    if visible_arguments(command).any(|arg| !arg.is_positional()) {
+        synopsis.push_str(" [\\fIOPTIONS\\fR]");
+    }
+    for arg in visible_arguments(command).filter(|arg| arg.is_positional()) {
+        let value = format!("\\fI{}\\fR", value_names(arg));
+        let repeated = if arg.get_action().takes_values()
+            && arg
+                .get_num_args()
+                .is_some_and(|range| range.max_values() > 1)
+        {
+            "..."
+        } else {
+            ""
+        };
+        if arg.is_required_set() {
+            write!(synopsis, " {}{}", value, repeated).unwrap();
+        } else {
+            write!(synopsis, " [{}]{}", value, repeated).unwrap();
+        }
+    }
+    if visible_subcommands(command).next().is_some() {
+        synopsis.push_str(" <\\fICOMMAND\\fR>");
+    }
+    synopsis
+}
+
+fn write_options(page: &mut String, command: &Command, heading: &str) {
+    let args = visible_arguments(command).collect::<Vec<_>>();
+    if args.is_empty() {
+        return;
+    }
+
+    writeln!(page, "{}", heading).unwrap();
+    for arg in args {
+        page.push_str(".TP\n");
+        writeln!(page, "{}", option_signature(arg)).unwrap();
+
+        let mut help = arg
+            .get_long_help()
+            .or_else(|| arg.get_help())
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let possible_values = arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect::<Vec<_>>();
+        if !possible_values.is_empty() && arg.get_action().takes_values() {
+            write!(help, " [possible values: {}]", possible_values.join(", ")).unwrap();
+        }
+        let defaults = arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        if !defaults.is_empty() && arg.get_action().takes_values() {
+            write!(help, " [default: {}]", defaults.join(", ")).unwrap();
+        }
+        write_paragraphs(page, help.trim());
+    }
+}
+
+fn option_signature(arg: &Arg) -> String {
+    if arg.is_positional() {
+        return format!("\\fI{}\\fR", value_names(arg));
+    }
+
+    let mut names = Vec::new();
+    if let Some(short) = arg.get_short() {
+        names.push(format!("\\fB\\-{}\\fR", escape(&short.to_string())));
+    }
+    if let Some(long) = arg.get_long() {
+        names.push(format!("\\fB\\-\\-{}\\fR", escape(long)));
+    }
+    let mut signature = names.join(", ");
+    if arg.get_action().takes_values() {
+        write!(signature, " \\fI{}\\fR", value_names(arg)).unwrap();
+    }
+    signature
+}
+
+fn value_names(arg: &Arg) -> String {
+    match arg.get_value_names() {
+        Some(names) if !names.is_empty() => names
+            .iter()
+            .map(|name| format!("<{}>", escape(name)))
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => format!("<{}>", escape(&arg.get_id().to_string().to_uppercase())),
+    }
+}
+
+fn write_paragraphs(page: &mut String, text: &str) {
+    for (index, paragraph) in text.split("\n\n").enumerate() {
+        if index > 0 {
+            page.push_str(".IP\n");
+        }
+        for line in paragraph.lines() {
+            writeln!(page, "{}", escape_line(line.trim())).unwrap();
+        }
+    }
+}
+
+/// Escapes a line of text, protecting a leading control character.
+fn escape_line(line: &str) -> String {
+    let escaped = escape(line);
+    if escaped.starts_with('.') || escaped.starts_with('\'') {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\e").replace('-', "\\-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_command() -> Command {
+        Command::new("tool")
+            .version("1.2.3")
+            .about("Does things")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .short('v')
+                    .action(clap::ArgAction::SetTrue)
+                    .help("Be loud"),
+            )
+            .subcommand(
+                Command::new("build")
+                    .about("Build inputs")
+                    .long_about("Build inputs.\n\n.hidden lines stay literal")
+                    .arg(
+                        Arg::new("paths")
+                            .value_name("PATH")
+                            .required(true)
+                            .num_args(1..),
+                    )
+                    .arg(
+                        Arg::new("mode")
+                            .long("mode")
+                            .value_parser(["fast", "slow"])
+                            .default_value("fast")
+                            .help("Build mode"),
+                    ),
+            )
+    }
+
+    #[test]
+    fn test_man_page_has_standard_sections() {
+        let page = render_man_page(&sample_command());
+
+        assert!(page.starts_with(".TH TOOL 1 \"\" \"tool 1.2.3\""));
+        assert!(page.contains(".SH NAME\ntool \\- Does things\n"));
+        assert!(page.contains(".SH SYNOPSIS\n\\fBtool\\fR [\\fIOPTIONS\\fR] <\\fICOMMAND\\fR>\n"));
+        assert!(page.contains("\\fB\\-v\\fR, \\fB\\-\\-verbose\\fR\nBe loud\n"));
+        assert!(page.contains(".SH VERSION\nv1.2.3\n"));
+    }
+
+    #[test]
+    fn test_man_page_documents_subcommands() {
+        let page = render_man_page(&sample_command());
+
+        assert!(page.contains(".SS \"tool build\"\n"));
+        assert!(page.contains("\\fBtool build\\fR [\\fIOPTIONS\\fR] \\fI<PATH>\\fR...\n"));
+        assert!(page.contains(
+            "\\fB\\-\\-mode\\fR \\fI<MODE>\\fR\nBuild mode [possible values: fast, slow] [default: fast]\n"
+        ));
+        assert!(page.contains("\\&.hidden lines stay literal\n"));
+        assert!(!page.contains(".SS \"tool help\""));
+    }
+}
@@ -0,0 +1,112 @@
+//! The `--message-format json-lines` event stream.
+//!
+//! With `json-lines`, `check` and `conformance` print one JSON object per line on stdout instead
+//! of human-readable text, so editor build integrations and CI wrappers can follow progress as it
+//! happens. Every object has a `reason` naming the event:
+//!
+//! - `diagnostic`: `file` and `diagnostic`, an [`NxDiagnostic`] as `--json` prints it.
+//! - `artifact`: `file` built into a program without errors; `fingerprint` identifies the build.
+//! - `stage-finished`: `file` spent `ms` milliseconds in `stage` (`parse`, `check`, ...). Only
+//!   stages that ran are reported, after the file's other events.
+//! - `test-finished`: the conformance case `file` `passed`, or failed with `message`.
+//! - `finished`: the last line; `success` matches the exit status, and the counts summarize the
+//!   run.
+//!
+//! New fields and reasons may be added; consumers should ignore the ones they do not know.
+
+use crate::timings::millis;
+use nx_api::NxDiagnostic;
+use nx_types::StageTimings;
+use serde_json::json;
+
+/// How `check` and `conformance` report diagnostics and progress.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MessageFormat {
+    Human,
+    JsonLines,
+}
+
+impl std::fmt::Display for MessageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageFormat::Human => write!(f, "human"),
+            MessageFormat::JsonLines => write!(f, "json-lines"),
+        }
+    }
+}
+
+pub(crate) fn diagnostic(file: &str, diagnostic: &NxDiagnostic) -> serde_json::Value {
+    json!({ "reason": "diagnostic", "file": file, "diagnostic": diagnostic })
+}
+
+pub(crate) fn artifact(file: &str, fingerprint: u64) -> serde_json::Value {
+    json!({ "reason": "artifact", "file": file, "fingerprint": fingerprint })
+}
+
+/// Returns a `stage-finished` event for every stage `file` spent time in, in pipeline order.
+pub(crate) fn stages_finished(file: &str, timings: &StageTimings) -> Vec<serde_json::Value> {
+    timings
+        .iter()
+        .filter(|(_, duration)| !duration.is_zero())
+        .map(|(stage, duration)| {
+            json!({
+                "reason": "stage-finished",
+                "file": file,
+                "stage": stage.as_str(),
+                "ms": millis(duration),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn test_finished(file: &str, failure: Option<&str>) -> serde_json::Value {
+    json!({
+        "reason": "test-finished",
+        "file": file,
+        "passed": failure.is_none(),
+        "message": failure,
+    })
+}
+
+/// Returns the closing event: the fields of `summary`, with `reason` and `success` added.
+pub(crate) fn finished(success: bool, mut summary: serde_json::Value) -> serde_json::Value {
+    summary["reason"] = json!("finished");
+    summary["success"] = json!(success);
+    summary
+}
+
+/// Prints `event` as one line on stdout.
+pub(crate) fn emit(event: &serde_json::Value) {
+    println!("{}", event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_types::Stage;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stages_finished_reports_only_stages_that_ran() {
+        let mut timings = StageTimings::default();
+        timings.record(Stage::Parse, Duration::from_micros(1500));
+        timings.record(Stage::Check, Duration::from_millis(2));
+
+        let events = stages_finished("app.nx", &timings);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0],
+            json!({ "reason": "stage-finished", "file": "app.nx", "stage": "parse", "ms": 1.5 })
+        );
+        assert_eq!(events[1]["stage"], "check");
+    }
+
+    #[test]
+    fn test_finished_adds_reason_and_success_to_the_summary() {
+        assert_eq!(
+            finished(false, json!({ "errors": 1, "warnings": 0 })),
+            json!({ "reason": "finished", "success": false, "errors": 1, "warnings": 0 })
+        );
+    }
+}
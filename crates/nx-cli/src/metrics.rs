@@ -0,0 +1,289 @@
+//! Collection and rendering of `nxlang metrics` reports.
+//!
+//! The table is meant for reading in a terminal; the JSON object is meant for review bots and
+//! other scripts. Both list every function, component, and value of each file in declaration
+//! order, followed by the file's totals, and end with the totals across all files.
+
+use nx_hir::{lower, SourceId};
+use nx_types::ItemMetrics;
+
+/// How `nxlang metrics` prints its report.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MetricsFormat {
+    Table,
+    Json,
+}
+
+impl std::fmt::Display for MetricsFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricsFormat::Table => write!(f, "table"),
+            MetricsFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// The metrics of one file's declarations, with the line each starts on.
+pub(crate) struct FileMetrics {
+    pub(crate) file: String,
+    pub(crate) items: Vec<(usize, ItemMetrics)>,
+}
+
+/// Totals over a group of declarations: complexity and expressions are summed, depth is the
+/// maximum.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Totals {
+    items: usize,
+    complexity: usize,
+    max_depth: usize,
+    expressions: usize,
+}
+
+impl Totals {
+    fn add(&mut self, other: Totals) {
+        self.items += other.items;
+        self.complexity += other.complexity;
+        self.max_depth = self.max_depth.max(other.max_depth);
+        self.expressions += other.expressions;
+    }
+
+    fn to_json(self) -> serde_json::Value {
+        serde_json::json!({
+            "items": self.items,
+            "complexity": self.complexity,
+            "max_depth": self.max_depth,
+            "expressions": self.expressions,
+        })
+    }
+}
+
+impl FileMetrics {
+    /// Computes the metrics of `source`, using the recovered tree if it has syntax errors.
+    pub(crate) fn collect(file: String, source: &str) -> Self {
+        let parse_result = nx_syntax::parse_str(source, &file);
+        let items = match parse_result.root() {
+            Some(root) => nx_types::module_metrics(&lower(root, SourceId::new(0)))
+                .into_iter()
+                .map(|item| (line_of(source, item.span.start().into()), item))
+                .collect(),
+            None => Vec::new(),
+        };
+        Self { file, items }
+    }
+
+    fn totals(&self) -> Totals {
+        let mut totals = Totals::default();
+        for (_, item) in &self.items {
+            totals.add(Totals {
+                items: 1,
+                complexity: item.complexity,
+                max_depth: item.max_depth,
+                expressions: item.expressions,
+            });
+        }
+        totals
+    }
+}
+
+/// Returns one message per declaration whose complexity or element nesting exceeds a limit.
+pub(crate) fn limit_violations(
+    files: &[FileMetrics],
+    max_complexity: Option<usize>,
+    max_depth: Option<usize>,
+) -> Vec<String> {
+    let mut violations = Vec::new();
+    for file in files {
+        for (line, item) in &file.items {
+            let location = format!(
+                "{}:{}: {} '{}'",
+                file.file,
+                line,
+                item.kind.as_str(),
+                item.name
+            );
+            if let Some(limit) = max_complexity.filter(|limit| item.complexity > *limit) {
+                violations.push(format!(
+                    "{} has complexity {}, above the limit of {}",
+                    location, item.complexity, limit
+                ));
+            }
+            if let Some(limit) = max_depth.filter(|limit| item.max_depth > *limit) {
+                violations.push(format!(
+                    "{} nests elements {} deep, above the limit of {}",
+                    location, item.max_depth, limit
+                ));
+            }
+        }
+    }
+    violations
+}
+
+/// Formats the metrics of `files`.
+pub(crate) fn format_metrics(files: &[FileMetrics], format: MetricsFormat) -> String {
+    let mut totals = Totals::default();
+    for file in files {
+        totals.add(file.totals());
+    }
+    match format {
+        MetricsFormat::Table => {
+            let mut rows = vec![[
+                "location".to_string(),
+                "kind".to_string(),
+                "name".to_string(),
+                "complexity".to_string(),
+                "depth".to_string(),
+                "expressions".to_string(),
+            ]];
+            let totals_row = |location: &str, totals: Totals| {
+                [
+                    location.to_string(),
+                    "total".to_string(),
+                    format!(
+                        "{} {}",
+                        totals.items,
+                        if totals.items == 1 { "item" } else { "items" }
+                    ),
+                    totals.complexity.to_string(),
+                    totals.max_depth.to_string(),
+                    totals.expressions.to_string(),
+                ]
+            };
+            for file in files {
+                for (line, item) in &file.items {
+                    rows.push([
+                        format!("{}:{}", file.file, line),
+                        item.kind.as_str().to_string(),
+                        item.name.clone(),
+                        item.complexity.to_string(),
+                        item.max_depth.to_string(),
+                        item.expressions.to_string(),
+                    ]);
+                }
+                rows.push(totals_row(&file.file, file.totals()));
+            }
+            if files.len() > 1 {
+                rows.push(totals_row("all files", totals));
+            }
+
+            let widths: Vec<usize> = (0..6)
+                .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+                .collect();
+            rows.iter()
+                .map(|row| {
+                    format!(
+                        "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {:>w4$}  {:>w5$}",
+                        row[0],
+                        row[1],
+                        row[2],
+                        row[3],
+                        row[4],
+                        row[5],
+                        w0 = widths[0],
+                        w1 = widths[1],
+                        w2 = widths[2],
+                        w3 = widths[3],
+                        w4 = widths[4],
+                        w5 = widths[5],
+                    )
+                    .trim_end()
+                    .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        MetricsFormat::Json => {
+            let files = files
+                .iter()
+                .map(|file| {
+                    let items = file
+                        .items
+                        .iter()
+                        .map(|(line, item)| {
+                            serde_json::json!({
+                                "name": item.name,
+                                "kind": item.kind.as_str(),
+                                "line": line,
+                                "complexity": item.complexity,
+                                "max_depth": item.max_depth,
+                                "expressions": item.expressions,
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    serde_json::json!({
+                        "file": file.file,
+                        "items": items,
+                        "totals": file.totals().to_json(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({ "files": files, "totals": totals.to_json() }).to_string()
+        }
+    }
+}
+
+/// Returns the 1-based line containing byte `offset` of `source`.
+fn line_of(source: &str, offset: usize) -> usize {
+    source
+        .get(..offset)
+        .map_or(1, |prefix| prefix.matches('\n').count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<FileMetrics> {
+        vec![
+            FileMetrics::collect(
+                "a.nx".to_string(),
+                "let size = { 2 }\nlet pick(flag:bool): int = { if flag { 1 } else { 2 } }",
+            ),
+            FileMetrics::collect(
+                "b.nx".to_string(),
+                "let root() = <main><p>{\"hi\"}</p></main>",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_format_metrics_table_lists_items_and_totals() {
+        let table = format_metrics(&sample(), MetricsFormat::Table);
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(
+            lines[0],
+            "location   kind      name     complexity  depth  expressions"
+        );
+        assert!(lines[2].starts_with("a.nx:2     function  pick              2      0"));
+        assert!(lines[3].starts_with("a.nx       total     2 items           3      0"));
+        assert!(lines[5].starts_with("b.nx       total     1 item            1      2"));
+        assert!(lines[6].starts_with("all files  total     3 items           4      2"));
+    }
+
+    #[test]
+    fn test_format_metrics_json_reports_items_and_totals() {
+        let json: serde_json::Value =
+            serde_json::from_str(&format_metrics(&sample(), MetricsFormat::Json)).unwrap();
+
+        assert_eq!(json["files"][0]["items"][1]["name"], "pick");
+        assert_eq!(json["files"][0]["items"][1]["line"], 2);
+        assert_eq!(json["files"][0]["items"][1]["complexity"], 2);
+        assert_eq!(json["files"][1]["totals"]["max_depth"], 2);
+        assert_eq!(json["totals"]["items"], 3);
+        assert_eq!(json["totals"]["complexity"], 4);
+    }
+
+    #[test]
+    fn test_limit_violations_name_each_declaration_over_a_limit() {
+        assert_eq!(
+            limit_violations(&sample(), Some(1), Some(1)),
+            vec![
+                "a.nx:2: function 'pick' has complexity 2, above the limit of 1",
+                "b.nx:1: function 'root' nests elements 2 deep, above the limit of 1",
+            ]
+        );
+        assert!(limit_violations(&sample(), Some(2), Some(2)).is_empty());
+        assert!(limit_violations(&sample(), None, None).is_empty());
+    }
+}
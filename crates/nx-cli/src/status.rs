@@ -0,0 +1,27 @@
+//! Exit status contract shared by all subcommands.
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0 | Success |
+//! | 1 | The NX sources have errors, or more warnings than `--max-warnings` allows |
+//! | 2 | Usage error: invalid arguments, or inputs that do not exist |
+//! | 3 | Internal error: I/O failures or unexpected toolchain failures |
+//!
+//! Argument parsing errors are reported by clap, which also exits with code 2.
+
+use std::process::ExitCode;
+
+/// Outcome of a subcommand, ordered from least to most severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Success = 0,
+    Diagnostics = 1,
+    Usage = 2,
+    Internal = 3,
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
@@ -0,0 +1,88 @@
+//! Rendering of `--timings` reports.
+//!
+//! The table is meant for reading in a terminal; the JSON object is meant for pasting into
+//! performance issues and for scripts. Both list every stage in pipeline order, including
+//! stages that took no time, followed by the total.
+
+use nx_types::StageTimings;
+use std::time::Duration;
+
+/// How `--timings` prints the per-stage report.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TimingsFormat {
+    Table,
+    Json,
+}
+
+impl std::fmt::Display for TimingsFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimingsFormat::Table => write!(f, "table"),
+            TimingsFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Formats the time spent in each stage, in milliseconds.
+pub(crate) fn format_timings(timings: &StageTimings, format: TimingsFormat) -> String {
+    match format {
+        TimingsFormat::Table => {
+            let mut lines = vec![format!("{:<10} {:>10}", "stage", "ms")];
+            for (stage, duration) in timings.iter() {
+                lines.push(format!("{:<10} {:>10.3}", stage.as_str(), millis(duration)));
+            }
+            lines.push(format!("{:<10} {:>10.3}", "total", millis(timings.total())));
+            lines.join("\n")
+        }
+        TimingsFormat::Json => {
+            let stages = timings
+                .iter()
+                .map(|(stage, duration)| {
+                    serde_json::json!({ "stage": stage.as_str(), "ms": millis(duration) })
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({ "stages": stages, "total_ms": millis(timings.total()) }).to_string()
+        }
+    }
+}
+
+/// Converts to milliseconds, rounded to whole microseconds.
+pub(crate) fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_types::Stage;
+
+    fn sample() -> StageTimings {
+        let mut timings = StageTimings::default();
+        timings.record(Stage::Parse, Duration::from_micros(1500));
+        timings.record(Stage::Eval, Duration::from_millis(2));
+        timings
+    }
+
+    #[test]
+    fn test_format_timings_table_lists_every_stage_and_total() {
+        let table = format_timings(&sample(), TimingsFormat::Table);
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], "stage              ms");
+        assert_eq!(lines[2], "parse           1.500");
+        assert_eq!(lines[3], "lower           0.000");
+        assert_eq!(lines[8], "total           3.500");
+    }
+
+    #[test]
+    fn test_format_timings_json_reports_milliseconds() {
+        let json: serde_json::Value =
+            serde_json::from_str(&format_timings(&sample(), TimingsFormat::Json)).unwrap();
+
+        assert_eq!(json["stages"][1]["stage"], "parse");
+        assert_eq!(json["stages"][1]["ms"], 1.5);
+        assert_eq!(json["stages"].as_array().unwrap().len(), 7);
+        assert_eq!(json["total_ms"], 3.5);
+    }
+}
@@ -0,0 +1,381 @@
+//! Golden-file conformance suites for NX backends.
+//!
+//! A suite is a directory of `.nx` files, searched recursively. Each file is one case and is
+//! paired with exactly one expectation next to it:
+//!
+//! - `name.expected.json` holds the JSON encoding of the value `root()` evaluates to. Values are
+//!   compared structurally, so formatting and key order in the file do not matter.
+//! - `name.expected.diag` holds the diagnostics evaluation must fail with, one per line, in the
+//!   format produced by [`format_diagnostics`].
+//!
+//! [`run_suite`] evaluates every case with a [`Backend`] and reports which ones match. The
+//! reference [`InterpreterBackend`] runs the tree-walking interpreter through
+//! [`nx_api::eval_source_with_options`], and [`BytecodeBackend`] runs the interpreter's bytecode
+//! VM the same way; another backend proves semantic parity by passing the same suite.
+//! `nxlang conformance <dir>` runs a suite from the command line.
+//!
+//! Cases are evaluated without project imports, so each case must be self-contained apart from
+//! `std/` modules.
+
+use nx_api::{
+    eval_source_with_options, EvalOptions, EvalResult, NxDiagnostic, NxSeverity,
+    ProgramBuildContext,
+};
+use std::fmt::Write;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name suffix of an expected value, replacing the case's `.nx` extension.
+pub const EXPECTED_JSON_SUFFIX: &str = ".expected.json";
+
+/// File name suffix of expected diagnostics, replacing the case's `.nx` extension.
+pub const EXPECTED_DIAG_SUFFIX: &str = ".expected.diag";
+
+/// An execution backend under test
+///
+/// Implementations evaluate the `root()` entrypoint of a single source file and report the
+/// result the way [`nx_api::eval_source`] does. Random builtins should be seeded, so cases that
+/// use them produce the same value on every run.
+pub trait Backend {
+    /// Returns the name shown in reports, such as `"interpreter"`.
+    fn name(&self) -> &str;
+
+    /// Evaluates `source`, using `file_name` in diagnostics.
+    fn eval(&self, source: &str, file_name: &str) -> EvalResult;
+}
+
+/// The reference backend: the tree-walking interpreter behind [`nx_api`]
+#[derive(Debug, Clone)]
+pub struct InterpreterBackend {
+    options: EvalOptions,
+}
+
+impl InterpreterBackend {
+    /// Creates a backend that evaluates with `options`, on the tree walker whatever backend
+    /// `options` selects.
+    pub fn new(options: EvalOptions) -> Self {
+        Self {
+            options: EvalOptions {
+                backend: nx_api::Backend::TreeWalker,
+                ..options
+            },
+        }
+    }
+}
+
+impl Default for InterpreterBackend {
+    /// Seeds random builtins with `0`.
+    fn default() -> Self {
+        Self::new(EvalOptions::with_random_seed(0))
+    }
+}
+
+impl Backend for InterpreterBackend {
+    fn name(&self) -> &str {
+        "interpreter"
+    }
+
+    fn eval(&self, source: &str, file_name: &str) -> EvalResult {
+        eval_source_with_options(
+            source,
+            file_name,
+            &ProgramBuildContext::empty(),
+            &self.options,
+        )
+    }
+}
+
+/// The interpreter's bytecode VM ([`nx_api::Backend::Bytecode`])
+#[derive(Debug, Clone)]
+pub struct BytecodeBackend {
+    options: EvalOptions,
+}
+
+impl BytecodeBackend {
+    /// Creates a backend that evaluates with `options`, on the bytecode VM whatever backend
+    /// `options` selects.
+    pub fn new(options: EvalOptions) -> Self {
+        Self {
+            options: EvalOptions {
+                backend: nx_api::Backend::Bytecode,
+                ..options
+            },
+        }
+    }
+}
+
+impl Default for BytecodeBackend {
+    /// Seeds random builtins with `0`.
+    fn default() -> Self {
+        Self::new(EvalOptions::with_random_seed(0))
+    }
+}
+
+impl Backend for BytecodeBackend {
+    fn name(&self) -> &str {
+        "bytecode"
+    }
+
+    fn eval(&self, source: &str, file_name: &str) -> EvalResult {
+        eval_source_with_options(
+            source,
+            file_name,
+            &ProgramBuildContext::empty(),
+            &self.options,
+        )
+    }
+}
+
+/// A `.nx` file in a suite and the expectation files found next to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceCase {
+    /// Path of the `.nx` source.
+    pub source: PathBuf,
+    /// Path of the `.expected.json` file, if present.
+    pub expected_json: Option<PathBuf>,
+    /// Path of the `.expected.diag` file, if present.
+    pub expected_diag: Option<PathBuf>,
+}
+
+/// The outcome of one case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaseOutcome {
+    /// The backend produced the expected value or diagnostics.
+    Passed,
+    /// The case failed, with a message describing the mismatch.
+    Failed(String),
+}
+
+/// One case and its outcome
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseResult {
+    /// Path of the `.nx` source.
+    pub source: PathBuf,
+    /// Whether the case passed.
+    pub outcome: CaseOutcome,
+}
+
+impl CaseResult {
+    /// Returns true if the case passed.
+    pub fn passed(&self) -> bool {
+        self.outcome == CaseOutcome::Passed
+    }
+}
+
+/// The results of running a suite, in case path order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuiteReport {
+    /// Name of the backend the suite ran against.
+    pub backend: String,
+    /// One result per case.
+    pub cases: Vec<CaseResult>,
+}
+
+impl SuiteReport {
+    /// Returns the number of cases that passed.
+    pub fn passed(&self) -> usize {
+        self.cases.iter().filter(|case| case.passed()).count()
+    }
+
+    /// Returns the cases that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &CaseResult> {
+        self.cases.iter().filter(|case| !case.passed())
+    }
+
+    /// Returns true if every case passed.
+    pub fn is_success(&self) -> bool {
+        self.cases.iter().all(CaseResult::passed)
+    }
+}
+
+/// Finds the cases in `dir` and its subdirectories, sorted by path.
+///
+/// # Errors
+///
+/// Returns an error when a directory in the suite cannot be read.
+pub fn discover_cases(dir: &Path) -> io::Result<Vec<ConformanceCase>> {
+    let mut sources = Vec::new();
+    collect_sources(dir, &mut sources)?;
+    sources.sort();
+
+    Ok(sources
+        .into_iter()
+        .map(|source| {
+            let existing = |suffix: &str| {
+                let stem = source.file_stem()?.to_string_lossy();
+                let path = source.with_file_name(format!("{}{}", stem, suffix));
+                path.is_file().then_some(path)
+            };
+            ConformanceCase {
+                expected_json: existing(EXPECTED_JSON_SUFFIX),
+                expected_diag: existing(EXPECTED_DIAG_SUFFIX),
+                source,
+            }
+        })
+        .collect())
+}
+
+fn collect_sources(dir: &Path, sources: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_sources(&path, sources)?;
+        } else if path.extension().is_some_and(|extension| extension == "nx") {
+            sources.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Runs every case in `dir` against `backend`.
+///
+/// A case fails when it has no expectation file or both, when the backend's result does not match
+/// the expectation, or when one of its files cannot be read.
+///
+/// # Errors
+///
+/// Returns an error when a directory in the suite cannot be read.
+pub fn run_suite(dir: &Path, backend: &dyn Backend) -> io::Result<SuiteReport> {
+    let cases = discover_cases(dir)?
+        .iter()
+        .map(|case| CaseResult {
+            source: case.source.clone(),
+            outcome: run_case(case, backend),
+        })
+        .collect();
+    Ok(SuiteReport {
+        backend: backend.name().to_string(),
+        cases,
+    })
+}
+
+/// Runs a single case against `backend`.
+pub fn run_case(case: &ConformanceCase, backend: &dyn Backend) -> CaseOutcome {
+    let outcome = match (&case.expected_json, &case.expected_diag) {
+        (None, None) => Err(format!(
+            "no {} or {} file next to the case",
+            EXPECTED_JSON_SUFFIX, EXPECTED_DIAG_SUFFIX
+        )),
+        (Some(_), Some(_)) => Err(format!(
+            "both {} and {} exist; a case expects either a value or diagnostics",
+            EXPECTED_JSON_SUFFIX, EXPECTED_DIAG_SUFFIX
+        )),
+        (Some(expected), None) => read(&case.source).and_then(|source| {
+            check_value(
+                backend.eval(&source, &case.source.display().to_string()),
+                expected,
+            )
+        }),
+        (None, Some(expected)) => read(&case.source).and_then(|source| {
+            check_diagnostics(
+                backend.eval(&source, &case.source.display().to_string()),
+                expected,
+            )
+        }),
+    };
+    match outcome {
+        Ok(()) => CaseOutcome::Passed,
+        Err(message) => CaseOutcome::Failed(message),
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map_err(|error| format!("failed to read '{}': {}", path.display(), error))
+}
+
+fn check_value(result: EvalResult, expected_path: &Path) -> Result<(), String> {
+    let expected_text = read(expected_path)?;
+    let expected: serde_json::Value = serde_json::from_str(&expected_text)
+        .map_err(|error| format!("invalid JSON in '{}': {}", expected_path.display(), error))?;
+    let value = match result {
+        EvalResult::Ok(value) => value,
+        EvalResult::Err(diagnostics) => {
+            return Err(format!(
+                "expected a value, but evaluation failed with:\n{}",
+                format_diagnostics(&diagnostics)
+            ))
+        }
+    };
+    let actual = serde_json::to_value(&value)
+        .map_err(|error| format!("failed to encode the result as JSON: {}", error))?;
+    if actual == expected {
+        return Ok(());
+    }
+    Err(format!(
+        "value does not match '{}'\nexpected:\n{}\nactual:\n{}",
+        expected_path.display(),
+        pretty(&expected),
+        pretty(&actual)
+    ))
+}
+
+fn check_diagnostics(result: EvalResult, expected_path: &Path) -> Result<(), String> {
+    let expected = normalize_lines(&read(expected_path)?);
+    let diagnostics = match result {
+        EvalResult::Err(diagnostics) => diagnostics,
+        EvalResult::Ok(value) => {
+            let encoded = serde_json::to_value(&value)
+                .map_or_else(|error| error.to_string(), |value| pretty(&value));
+            return Err(format!(
+                "expected diagnostics, but evaluation produced:\n{}",
+                encoded
+            ));
+        }
+    };
+    let actual = normalize_lines(&format_diagnostics(&diagnostics));
+    if actual == expected {
+        return Ok(());
+    }
+    Err(format!(
+        "diagnostics do not match '{}'\nexpected:\n{}\nactual:\n{}",
+        expected_path.display(),
+        expected,
+        actual
+    ))
+}
+
+fn pretty(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// Drops trailing whitespace and trailing blank lines, so editors and platforms don't matter.
+fn normalize_lines(text: &str) -> String {
+    let lines: Vec<_> = text.lines().map(str::trim_end).collect();
+    let end = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(0, |index| index + 1);
+    lines[..end].join("\n")
+}
+
+/// Formats diagnostics the way `.expected.diag` files hold them
+///
+/// Each diagnostic is one line: its severity, its code in brackets when it has one, the line and
+/// column of its primary label when it has one, and its message. For example,
+/// `error[runtime-error]: Division by zero` or `error[no-root] 1:1: ...`. File names are left
+/// out, so a suite can move without changing its expectations.
+pub fn format_diagnostics(diagnostics: &[NxDiagnostic]) -> String {
+    let mut formatted = String::new();
+    for diagnostic in diagnostics {
+        formatted.push_str(match diagnostic.severity {
+            NxSeverity::Error => "error",
+            NxSeverity::Warning => "warning",
+            NxSeverity::Info => "info",
+            NxSeverity::Hint => "hint",
+        });
+        if let Some(code) = &diagnostic.code {
+            write!(formatted, "[{}]", code).unwrap();
+        }
+        if let Some(label) = diagnostic.labels.iter().find(|label| label.primary) {
+            write!(
+                formatted,
+                " {}:{}",
+                label.span.start_line, label.span.start_column
+            )
+            .unwrap();
+        }
+        writeln!(formatted, ": {}", diagnostic.message).unwrap();
+    }
+    formatted
+}
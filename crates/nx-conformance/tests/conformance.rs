@@ -0,0 +1,166 @@
+//! Runs the bundled suite against both interpreter backends, and checks that mismatches are
+//! reported.
+
+use nx_api::{EvalResult, NxDiagnostic, NxSeverity};
+use nx_conformance::{
+    discover_cases, format_diagnostics, run_suite, Backend, BytecodeBackend, CaseOutcome,
+    InterpreterBackend,
+};
+use nx_value::NxValue;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn suite_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/suite")
+}
+
+/// A backend that ignores its input and always returns the same result.
+struct Fixed(EvalResult);
+
+impl Backend for Fixed {
+    fn name(&self) -> &str {
+        "fixed"
+    }
+
+    fn eval(&self, _source: &str, _file_name: &str) -> EvalResult {
+        match &self.0 {
+            EvalResult::Ok(value) => EvalResult::Ok(value.clone()),
+            EvalResult::Err(diagnostics) => EvalResult::Err(diagnostics.clone()),
+        }
+    }
+}
+
+fn runtime_error(message: &str) -> NxDiagnostic {
+    NxDiagnostic {
+        severity: NxSeverity::Error,
+        code: Some("runtime-error".to_string()),
+        message: message.to_string(),
+        labels: Vec::new(),
+        help: None,
+        note: None,
+        fixes: Vec::new(),
+    }
+}
+
+fn assert_passes_the_bundled_suite(backend: &dyn Backend) {
+    let report = run_suite(&suite_dir(), backend).expect("suite runs");
+
+    let failures: Vec<_> = report
+        .failures()
+        .map(|case| format!("{}: {:?}", case.source.display(), case.outcome))
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+    assert_eq!(report.backend, backend.name());
+    assert_eq!(report.passed(), 18);
+}
+
+#[test]
+fn test_interpreter_passes_the_bundled_suite() {
+    assert_passes_the_bundled_suite(&InterpreterBackend::default());
+}
+
+#[test]
+fn test_bytecode_passes_the_bundled_suite() {
+    assert_passes_the_bundled_suite(&BytecodeBackend::default());
+}
+
+#[test]
+fn test_divergent_backend_fails_with_both_sides_of_the_mismatch() {
+    let report = run_suite(&suite_dir(), &Fixed(EvalResult::Ok(NxValue::Int(7)))).unwrap();
+
+    assert!(!report.is_success());
+    let outcome = |name: &str| {
+        report
+            .cases
+            .iter()
+            .find(|case| case.source.ends_with(name))
+            .map(|case| case.outcome.clone())
+            .unwrap()
+    };
+    let CaseOutcome::Failed(message) = outcome("values/loops.nx") else {
+        panic!("loops.nx should fail");
+    };
+    assert!(message.contains("expected:\n[\n  1,"), "{message}");
+    assert!(message.contains("actual:\n7"), "{message}");
+    let CaseOutcome::Failed(message) = outcome("diagnostics/no_root.nx") else {
+        panic!("no_root.nx should fail");
+    };
+    assert!(message.contains("expected diagnostics"), "{message}");
+
+    let report = run_suite(
+        &suite_dir(),
+        &Fixed(EvalResult::Err(vec![runtime_error("Division by zero")])),
+    )
+    .unwrap();
+    assert_eq!(passed_names(&report.cases), vec!["division_by_zero.nx"]);
+}
+
+fn passed_names(cases: &[nx_conformance::CaseResult]) -> Vec<String> {
+    cases
+        .iter()
+        .filter(|case| case.passed())
+        .map(|case| {
+            case.source
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+#[test]
+fn test_cases_need_exactly_one_expectation() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("bare.nx"), "let root() = { 1 }").unwrap();
+    std::fs::write(dir.path().join("both.nx"), "let root() = { 1 }").unwrap();
+    std::fs::write(dir.path().join("both.expected.json"), "1").unwrap();
+    std::fs::write(dir.path().join("both.expected.diag"), "").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not a case").unwrap();
+
+    let cases = discover_cases(dir.path()).unwrap();
+    assert_eq!(cases.len(), 2);
+    assert_eq!(cases[0].expected_json, None);
+    assert_eq!(
+        cases[1].expected_json,
+        Some(dir.path().join("both.expected.json"))
+    );
+
+    let report = run_suite(dir.path(), &InterpreterBackend::default()).unwrap();
+    let messages: Vec<_> = report
+        .failures()
+        .map(|case| match &case.outcome {
+            CaseOutcome::Failed(message) => message.clone(),
+            CaseOutcome::Passed => unreachable!(),
+        })
+        .collect();
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].starts_with("no .expected.json"), "{messages:?}");
+    assert!(
+        messages[1].starts_with("both .expected.json"),
+        "{messages:?}"
+    );
+}
+
+#[test]
+fn test_diagnostics_format_ignores_trailing_whitespace_in_expectations() {
+    assert_eq!(
+        format_diagnostics(&[runtime_error("Division by zero")]),
+        "error[runtime-error]: Division by zero\n"
+    );
+
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("fails.nx"),
+        "let f(n:int) = { n / 0 }\nlet root() = { f(1) }",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("fails.expected.diag"),
+        "error[runtime-error]: Division by zero  \r\n\r\n",
+    )
+    .unwrap();
+
+    let report = run_suite(dir.path(), &InterpreterBackend::default()).unwrap();
+    assert!(report.is_success(), "{:?}", report.cases);
+}
@@ -1,5 +1,6 @@
 //! Core diagnostic types for representing errors, warnings, and information messages.
 
+use crate::normalize_path;
 use text_size::TextRange;
 
 /// Severity level of a diagnostic message.
@@ -30,7 +31,7 @@ impl Severity {
 /// A label pointing to a specific location in source code.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Label {
-    /// The source file this label refers to.
+    /// The path of the source file this label refers to, with forward slashes.
     pub file: String,
     /// The text range in the source file.
     pub range: TextRange,
@@ -41,20 +42,20 @@ pub struct Label {
 }
 
 impl Label {
-    /// Creates a new primary label.
+    /// Creates a new primary label. The file path is normalized with [`normalize_path`].
     pub fn primary(file: impl Into<String>, range: TextRange) -> Self {
         Self {
-            file: file.into(),
+            file: normalize_path(&file.into()),
             range,
             message: None,
             primary: true,
         }
     }
 
-    /// Creates a new secondary label.
+    /// Creates a new secondary label. The file path is normalized with [`normalize_path`].
     pub fn secondary(file: impl Into<String>, range: TextRange) -> Self {
         Self {
-            file: file.into(),
+            file: normalize_path(&file.into()),
             range,
             message: None,
             primary: false,
@@ -68,6 +69,53 @@ impl Label {
     }
 }
 
+/// A single text replacement that is part of a [`Fix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The source file to edit.
+    pub file: String,
+    /// The text range to replace. An empty range inserts `replacement`.
+    pub range: TextRange,
+    /// The text to put in place of `range`.
+    pub replacement: String,
+}
+
+impl TextEdit {
+    /// Creates an edit that replaces `range` in `file` with `replacement`.
+    ///
+    /// The file path is normalized with [`normalize_path`].
+    pub fn replace(
+        file: impl Into<String>,
+        range: TextRange,
+        replacement: impl Into<String>,
+    ) -> Self {
+        Self {
+            file: normalize_path(&file.into()),
+            range,
+            replacement: replacement.into(),
+        }
+    }
+}
+
+/// A machine-applicable suggestion attached to a diagnostic, such as an editor quick-fix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// Short description of what applying the fix does (e.g. "Replace with `color`").
+    pub message: String,
+    /// Edits to apply together. Edits never overlap.
+    pub edits: Vec<TextEdit>,
+}
+
+impl Fix {
+    /// Creates a fix with a single edit.
+    pub fn new(message: impl Into<String>, edit: TextEdit) -> Self {
+        Self {
+            message: message.into(),
+            edits: vec![edit],
+        }
+    }
+}
+
 /// A diagnostic message (error, warning, info, or hint).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Diagnostic {
@@ -83,6 +131,8 @@ pub struct Diagnostic {
     help: Option<String>,
     /// Optional note with additional context.
     note: Option<String>,
+    /// Machine-applicable fixes for the issue.
+    fixes: Vec<Fix>,
 }
 
 impl Diagnostic {
@@ -111,6 +161,26 @@ impl Diagnostic {
         self.severity
     }
 
+    /// Returns this diagnostic with a different severity.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Returns this diagnostic with `map` applied to the range of every label and fix edit.
+    ///
+    /// Used when the analyzed text was embedded in a larger source, to point back into the
+    /// original text.
+    pub fn map_ranges(mut self, map: impl Fn(TextRange) -> TextRange) -> Self {
+        for label in &mut self.labels {
+            label.range = map(label.range);
+        }
+        for edit in self.fixes.iter_mut().flat_map(|fix| fix.edits.iter_mut()) {
+            edit.range = map(edit.range);
+        }
+        self
+    }
+
     /// Returns the error code, if any.
     pub fn code(&self) -> Option<&str> {
         self.code.as_deref()
@@ -135,6 +205,11 @@ impl Diagnostic {
     pub fn note(&self) -> Option<&str> {
         self.note.as_deref()
     }
+
+    /// Returns the machine-applicable fixes for this diagnostic.
+    pub fn fixes(&self) -> &[Fix] {
+        &self.fixes
+    }
 }
 
 /// Builder for constructing diagnostic messages.
@@ -146,6 +221,7 @@ pub struct DiagnosticBuilder {
     labels: Vec<Label>,
     help: Option<String>,
     note: Option<String>,
+    fixes: Vec<Fix>,
 }
 
 impl DiagnosticBuilder {
@@ -158,6 +234,7 @@ impl DiagnosticBuilder {
             labels: Vec::new(),
             help: None,
             note: None,
+            fixes: Vec::new(),
         }
     }
 
@@ -191,6 +268,18 @@ impl DiagnosticBuilder {
         self
     }
 
+    /// Adds a machine-applicable fix to this diagnostic.
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fixes.push(fix);
+        self
+    }
+
+    /// Adds multiple machine-applicable fixes to this diagnostic.
+    pub fn with_fixes(mut self, fixes: impl IntoIterator<Item = Fix>) -> Self {
+        self.fixes.extend(fixes);
+        self
+    }
+
     /// Builds the diagnostic.
     pub fn build(self) -> Diagnostic {
         Diagnostic {
@@ -200,6 +289,7 @@ impl DiagnosticBuilder {
             labels: self.labels,
             help: self.help,
             note: self.note,
+            fixes: self.fixes,
         }
     }
 }
@@ -217,6 +307,23 @@ mod tests {
         assert_eq!(Severity::Hint.as_str(), "hint");
     }
 
+    #[test]
+    fn test_diagnostic_with_fix() {
+        let range = TextRange::new(TextSize::from(8), TextSize::from(14));
+        let diagnostic = Diagnostic::error("unknown-property")
+            .with_message("Element 'Button' has no property 'colour'")
+            .with_fix(Fix::new(
+                "Replace with `color`",
+                TextEdit::replace("test.nx", range, "color"),
+            ))
+            .build();
+
+        assert_eq!(diagnostic.fixes().len(), 1);
+        assert_eq!(diagnostic.fixes()[0].message, "Replace with `color`");
+        assert_eq!(diagnostic.fixes()[0].edits[0].range, range);
+        assert_eq!(diagnostic.fixes()[0].edits[0].replacement, "color");
+    }
+
     #[test]
     fn test_label_creation() {
         let range = TextRange::new(TextSize::from(0), TextSize::from(5));
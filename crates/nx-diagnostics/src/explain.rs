@@ -0,0 +1,397 @@
+//! Extended explanations of diagnostic codes, as printed by `nxlang explain <code>`.
+//!
+//! Diagnostic codes are stable, so tools and users can look one up long after the message that
+//! showed it. Each [`Explanation`] describes what the diagnostic means, shows a source that
+//! reports it next to a corrected version, and links to the language reference.
+
+use std::fmt;
+
+/// Base URL of the language reference.
+const REFERENCE: &str = "https://nx-lang.github.io/nx/reference";
+
+/// Extended documentation for one diagnostic code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+    /// The diagnostic code, such as `type-mismatch`.
+    pub code: &'static str,
+    /// One-line summary of the problem.
+    pub summary: &'static str,
+    /// What the diagnostic means and how to resolve it, in paragraphs separated by blank lines.
+    pub details: &'static str,
+    /// A complete source that reports the diagnostic.
+    pub wrong: &'static str,
+    /// The same source, corrected.
+    pub right: &'static str,
+    /// Pages of the language reference, relative to its base URL.
+    pub links: &'static [&'static str],
+}
+
+impl Explanation {
+    /// Returns the full URLs of the reference pages linked from this explanation.
+    pub fn link_urls(&self) -> impl Iterator<Item = String> + '_ {
+        self.links
+            .iter()
+            .map(|link| format!("{}/{}/", REFERENCE, link))
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.code, self.summary)?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.details)?;
+        writeln!(f)?;
+        writeln!(f, "Example that reports `{}`:", self.code)?;
+        writeln!(f)?;
+        write_indented(f, self.wrong)?;
+        writeln!(f)?;
+        writeln!(f, "Corrected:")?;
+        writeln!(f)?;
+        write_indented(f, self.right)?;
+        if !self.links.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "See also:")?;
+            for url in self.link_urls() {
+                writeln!(f, "  {}", url)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_indented(f: &mut fmt::Formatter<'_>, source: &str) -> fmt::Result {
+    for line in source.lines() {
+        if line.is_empty() {
+            writeln!(f)?;
+        } else {
+            writeln!(f, "    {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the explanation of `code`, or `None` when the code has none.
+///
+/// # Examples
+///
+/// ```
+/// let explanation = nx_diagnostics::explain("type-mismatch").unwrap();
+/// assert!(explanation.to_string().starts_with("type-mismatch: "));
+/// assert!(nx_diagnostics::explain("no-such-code").is_none());
+/// ```
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+    EXPLANATIONS
+        .binary_search_by(|explanation| explanation.code.cmp(code))
+        .ok()
+        .map(|index| &EXPLANATIONS[index])
+}
+
+/// Returns every explanation, ordered by code.
+pub fn explanations() -> &'static [Explanation] {
+    EXPLANATIONS
+}
+
+/// The registry, ordered by code so that [`explain`] can search it.
+static EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: "abstract-record-instantiation",
+        summary: "An abstract record is constructed directly",
+        details: "Abstract records only exist to be extended: they can be used as types in \
+annotations, but a value always has one of the concrete records that extend them.\n\n\
+Construct a concrete record that extends the abstract one instead, or remove `abstract` if \
+the record should be constructible.",
+        wrong: r#"abstract type Entity = { id:int }
+let entity = <Entity id={1} />
+"#,
+        right: r#"abstract type Entity = { id:int }
+type User extends Entity = { name:string }
+let entity = <User id={1} name="Ada" />
+"#,
+        links: &["syntax/types"],
+    },
+    Explanation {
+        code: "arg-count-mismatch",
+        summary: "A function is called with the wrong number of arguments",
+        details: "A call must pass one argument for every parameter of the function.\n\n\
+Pass the missing arguments or remove the extra ones. To fix some arguments now and pass the \
+rest later, use partial application.",
+        wrong: r#"let add(a:int, b:int) = { a + b }
+let three() = { add(1) }
+"#,
+        right: r#"let add(a:int, b:int) = { a + b }
+let three() = { add(1, 2) }
+"#,
+        links: &["syntax/functions"],
+    },
+    Explanation {
+        code: "duplicate-field",
+        summary: "A record declares the same field twice",
+        details: "Every field of a record needs its own name, including the fields it inherits \
+from its base record.\n\n\
+Rename one of the fields, or remove it if both were meant to be the same field.",
+        wrong: r#"type Point = { x:int x:int }
+"#,
+        right: r#"type Point = { x:int y:int }
+"#,
+        links: &["syntax/types"],
+    },
+    Explanation {
+        code: "duplicate-property",
+        summary: "An element sets the same property twice",
+        details: "Each property of an element can be given one value. When a property appears \
+twice it is not clear which value was meant, so NX rejects the element.\n\n\
+Remove one of the properties.",
+        wrong: r#"let <Card title:string /> = <div title={title} title="Card" />
+"#,
+        right: r#"let <Card title:string /> = <div title={title} />
+"#,
+        links: &["syntax/elements"],
+    },
+    Explanation {
+        code: "missing-property",
+        summary: "An element leaves out a required property",
+        details: "A property without a default value or a nullable type must be given every \
+time the element is used.\n\n\
+Supply the property, or give it a default value in the declaration, as in \
+`label:string = \"OK\"`.",
+        wrong: r#"let <Button label:string /> = <button>{label}</button>
+let root() = { <Button /> }
+"#,
+        right: r#"let <Button label:string /> = <button>{label}</button>
+let root() = { <Button label="Save" /> }
+"#,
+        links: &["syntax/functions", "syntax/elements"],
+    },
+    Explanation {
+        code: "non-exhaustive-union-match",
+        summary: "A match on a union does not handle every case",
+        details: "Matching a discriminated union with `if value is { ... }` must give an arm \
+for each of its cases, so that adding a case later points out every match that needs \
+updating.\n\n\
+Add arms for the cases listed in the diagnostic.",
+        wrong: r#"type LoadState =
+  | idle
+  | failed { message:string }
+let label(state:LoadState) = {
+  if state is {
+    LoadState.idle => "Idle"
+  }
+}
+"#,
+        right: r#"type LoadState =
+  | idle
+  | failed { message:string }
+let label(state:LoadState) = {
+  if state is {
+    LoadState.idle => "Idle"
+    LoadState.failed => state.message
+  }
+}
+"#,
+        links: &["syntax/if", "syntax/types"],
+    },
+    Explanation {
+        code: "not-a-function",
+        summary: "A value that is not a function is called",
+        details: "Only functions can be called with `(...)`.\n\n\
+Check that the name refers to the function you meant, and that a parameter or local value \
+does not hide a function of the same name.",
+        wrong: r#"let count(n:int) = { n() }
+"#,
+        right: r#"let count(n:int) = { n }
+"#,
+        links: &["syntax/functions"],
+    },
+    Explanation {
+        code: "null-concatenation",
+        summary: "A string concatenation may have a null operand",
+        details: "Concatenating null with `+` fails at runtime unless the host chooses another \
+null concatenation policy.\n\n\
+Give the value a non-null type with a default, or handle null before concatenating.",
+        wrong: r#"let <Greeting name:string? /> = <p>{"Hello " + name}</p>
+"#,
+        right: r#"let <Greeting name:string = "guest" /> = <p>{"Hello " + name}</p>
+"#,
+        links: &["syntax/expressions"],
+    },
+    Explanation {
+        code: "nullable-iteration",
+        summary: "A `for` loop iterates a list that may be null",
+        details: "Iterating null is a runtime error.\n\n\
+Declare the list as non-nullable with an empty list as its default, so that a missing value \
+iterates nothing.",
+        wrong: r#"let <List items:string[]? /> = <ul>{for item in items { <li>{item}</li> }}</ul>
+"#,
+        right: r#"let <List items:string[] = [] /> = <ul>{for item in items { <li>{item}</li> }}</ul>
+"#,
+        links: &["syntax/for"],
+    },
+    Explanation {
+        code: "possible-division-by-zero",
+        summary: "A division's divisor may be zero",
+        details: "Dividing by zero is a runtime error. The warning is reported when the divisor \
+is not a non-zero constant.\n\n\
+Check the divisor before dividing.",
+        wrong: r#"let ratio(a:int, b:int) = { a / b }
+"#,
+        right: r#"let ratio(a:int, b:int) = { if b != 0 { a / b } else { 0 } }
+"#,
+        links: &["syntax/expressions"],
+    },
+    Explanation {
+        code: "return-type-mismatch",
+        summary: "A function returns a value of a different type than it declares",
+        details: "When a function declares its return type, the value of its body must have \
+that type.\n\n\
+Change the body to produce the declared type, or change the declared return type.",
+        wrong: r#"let double(n:int): int = { "twice" }
+"#,
+        right: r#"let double(n:int): int = { n * 2 }
+"#,
+        links: &["syntax/functions"],
+    },
+    Explanation {
+        code: "syntax-error",
+        summary: "The source does not parse",
+        details: "The parser could not read the source as NX. The diagnostic points at the \
+first token it could not make sense of; the real mistake is often just before it, such as an \
+unclosed bracket or a missing `=`.\n\n\
+Errors after the first one may be caused by it, so fix them in order.",
+        wrong: r#"let add(a:int, b:int = { a + b }
+"#,
+        right: r#"let add(a:int, b:int) = { a + b }
+"#,
+        links: &["syntax/expressions", "syntax/functions"],
+    },
+    Explanation {
+        code: "tag-mismatch",
+        summary: "A closing tag does not match the element it closes",
+        details: "Every element must be closed with a tag of the same name, and elements must \
+be closed in the reverse order they were opened.\n\n\
+Close the inner element before the outer one, or use a self-closing tag such as `<br />`.",
+        wrong: r#"let root() = { <div><span>Hi</div> }
+"#,
+        right: r#"let root() = { <div><span>Hi</span></div> }
+"#,
+        links: &["syntax/elements"],
+    },
+    Explanation {
+        code: "type-mismatch",
+        summary: "A value has a different type than where it is used expects",
+        details: "Arguments must match their parameter types, conditions must be `bool`, and \
+operators must be given operands they accept. NX does not convert values between types \
+implicitly, except from `int` to `float` where a `float` is expected.\n\n\
+Convert the value explicitly or change the type the code expects. When the types are long, \
+the diagnostic's note points at the part that differs.",
+        wrong: r#"let add(a:int, b:int) = { a + b }
+let three() = { add(1, "two") }
+"#,
+        right: r#"let add(a:int, b:int) = { a + b }
+let three() = { add(1, 2) }
+"#,
+        links: &["syntax/types", "syntax/expressions"],
+    },
+    Explanation {
+        code: "undefined-identifier",
+        summary: "A name is used that is not declared",
+        details: "Names must be declared in the file, imported from a library, or be a builtin \
+function. When a declared name is close to the one used, the diagnostic suggests it.\n\n\
+Check the spelling, or import the library that declares the name.",
+        wrong: r#"let total(count:int) = { cuont + 1 }
+"#,
+        right: r#"let total(count:int) = { count + 1 }
+"#,
+        links: &["syntax/modules"],
+    },
+    Explanation {
+        code: "unknown-property",
+        summary: "An element sets a property its declaration does not have",
+        details: "Elements built from markup functions and components only accept the \
+properties they declare. When a declared property is close to the one used, the diagnostic \
+suggests it.\n\n\
+Check the spelling of the property, or declare it.",
+        wrong: r#"let <Button label:string /> = <button>{label}</button>
+let root() = { <Button label="Save" colour="red" /> }
+"#,
+        right: r#"let <Button label:string color:string = "gray" /> = <button>{label}</button>
+let root() = { <Button label="Save" color="red" /> }
+"#,
+        links: &["syntax/functions", "syntax/elements"],
+    },
+    Explanation {
+        code: "unknown-record-field",
+        summary: "A record field is used that the record does not declare",
+        details: "Records are closed: constructing one with a field it does not declare, or \
+reading such a field, is an error rather than being ignored. Inherited fields count as \
+declared.\n\n\
+Check the spelling of the field, or declare it on the record.",
+        wrong: r#"type Point = { x:int y:int }
+let origin = <Point x={0} y={0} z={0} />
+"#,
+        right: r#"type Point = { x:int y:int z:int = 0 }
+let origin = <Point x={0} y={0} z={0} />
+"#,
+        links: &["syntax/types"],
+    },
+    Explanation {
+        code: "unsafe-interpolation",
+        summary: "A URL or script property is built from unsanitized text",
+        details: "Properties such as `href` and `onclick` run script when given values like \
+`javascript:...`, so building them by concatenating dynamic text can let that text inject \
+script.\n\n\
+Pass the dynamic parts through `encodeUriComponent(...)`, or the whole URL through \
+`sanitizeUrl(...)`.",
+        wrong: r#"let <Profile id:string /> = <a href={"/users/" + id}>Profile</a>
+"#,
+        right: r#"let <Profile id:string /> = <a href={"/users/" + encodeUriComponent(id)}>Profile</a>
+"#,
+        links: &["syntax/elements"],
+    },
+    Explanation {
+        code: "value-type-mismatch",
+        summary: "A value's initializer has a different type than its annotation",
+        details: "When a top-level value declares its type, its initializer must have that \
+type.\n\n\
+Change the initializer, or change the annotation.",
+        wrong: r#"let limit: int = "ten"
+"#,
+        right: r#"let limit: int = 10
+"#,
+        links: &["syntax/types"],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_sorted_by_code() {
+        for pair in EXPLANATIONS.windows(2) {
+            assert!(
+                pair[0].code < pair[1].code,
+                "{} must come before {}",
+                pair[1].code,
+                pair[0].code
+            );
+        }
+    }
+
+    #[test]
+    fn test_explain_finds_each_code() {
+        for explanation in explanations() {
+            assert_eq!(explain(explanation.code), Some(explanation));
+        }
+        assert_eq!(explain("Type-Mismatch"), None);
+    }
+
+    #[test]
+    fn test_display_shows_examples_and_links() {
+        let text = explain("tag-mismatch").unwrap().to_string();
+        assert!(text.starts_with("tag-mismatch: A closing tag does not match"));
+        assert!(text.contains("\n    let root() = { <div><span>Hi</div> }\n"));
+        assert!(text.contains("Corrected:\n\n    let root() = { <div><span>Hi</span></div> }\n"));
+        assert!(text
+            .ends_with("See also:\n  https://nx-lang.github.io/nx/reference/syntax/elements/\n"));
+    }
+}
@@ -1,18 +1,32 @@
 //! Diagnostic error reporting for the NX language.
 //!
 //! This crate provides beautiful, user-friendly error messages using the Ariadne library.
-//! It includes diagnostic types, severity levels, and rendering functionality.
+//! It includes diagnostic types, severity levels, and rendering functionality. Rendering is behind
+//! the default-on `render` feature; without it the crate has no Ariadne dependency.
+//!
+//! [`explain`] looks up the extended explanation of a diagnostic code, with examples of code that
+//! reports it and of the corrected code.
 
 mod diagnostic;
+mod explain;
+mod path;
+#[cfg(feature = "render")]
 mod render;
+pub mod suggest;
 
-pub use diagnostic::{Diagnostic, DiagnosticBuilder, Label, Severity};
+pub use diagnostic::{Diagnostic, DiagnosticBuilder, Fix, Label, Severity, TextEdit};
+pub use explain::{explain, explanations, Explanation};
+pub use path::{display_path, normalize_path};
+#[cfg(feature = "render")]
 pub use render::{render_diagnostic, render_diagnostics, render_diagnostics_cli};
 
 // Re-export text-size types with NX-specific names
 pub use text_size::TextRange as TextSpan;
 pub use text_size::TextSize;
 
+/// Version of the nx-diagnostics crate, identical to the toolchain version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg(test)]
 mod tests {
     use super::*;
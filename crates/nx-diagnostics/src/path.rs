@@ -0,0 +1,105 @@
+//! Platform-independent source paths for diagnostics.
+//!
+//! Diagnostics identify files by the path they were parsed under, so that two files with the same
+//! base name in different directories stay distinct. Paths are normalized to forward slashes so
+//! that a diagnostic produced on Windows renders, compares, and hashes the same way as on Unix.
+
+use std::path::Path;
+
+/// Normalizes a source path for use in diagnostics.
+///
+/// Backslashes become forward slashes, the Windows verbatim prefix (`\\?\`) is dropped, and `.`
+/// segments and repeated separators are removed. `..` segments are kept, since resolving them
+/// lexically is wrong in the presence of symlinks. Names that are not paths, such as `<stdin>`,
+/// are returned unchanged.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path
+        .strip_prefix("//?/UNC/")
+        .map(|rest| format!("//{rest}"))
+        .or_else(|| path.strip_prefix("//?/").map(str::to_string))
+        .unwrap_or(path);
+
+    // A leading `//` is a UNC share and must survive the separator collapsing below.
+    let (prefix, rest) = if let Some(rest) = path.strip_prefix("//") {
+        ("//", rest)
+    } else if let Some(rest) = path.strip_prefix('/') {
+        ("/", rest)
+    } else {
+        ("", path.as_str())
+    };
+
+    let segments = rest
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect::<Vec<_>>();
+    if segments.is_empty() {
+        return if prefix.is_empty() {
+            ".".to_string()
+        } else {
+            prefix.to_string()
+        };
+    }
+
+    format!("{prefix}{}", segments.join("/"))
+}
+
+/// Returns the normalized display path of `path`, relative to `root` when it lies below it.
+///
+/// Paths outside `root` are returned normalized but otherwise unchanged, so a diagnostic for an
+/// imported library elsewhere on disk still names the file unambiguously.
+pub fn display_path(path: &Path, root: &Path) -> String {
+    let relative = path
+        .strip_prefix(root)
+        .ok()
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .unwrap_or(path);
+    normalize_path(&relative.to_string_lossy())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_path_uses_forward_slashes() {
+        assert_eq!(normalize_path(r"src\ui\button.nx"), "src/ui/button.nx");
+        assert_eq!(
+            normalize_path(r"C:\work\app\main.nx"),
+            "C:/work/app/main.nx"
+        );
+        assert_eq!(normalize_path(r"\\?\C:\work\main.nx"), "C:/work/main.nx");
+        assert_eq!(
+            normalize_path(r"\\?\UNC\server\share\main.nx"),
+            "//server/share/main.nx"
+        );
+        assert_eq!(
+            normalize_path(r"\\server\share\main.nx"),
+            "//server/share/main.nx"
+        );
+    }
+
+    #[test]
+    fn test_normalize_path_drops_current_dir_segments_and_repeated_separators() {
+        assert_eq!(normalize_path("./src//ui/./button.nx"), "src/ui/button.nx");
+        assert_eq!(normalize_path("/tmp//app/main.nx"), "/tmp/app/main.nx");
+        assert_eq!(normalize_path("../shared/main.nx"), "../shared/main.nx");
+        assert_eq!(normalize_path("./"), ".");
+        assert_eq!(normalize_path("<stdin>"), "<stdin>");
+        assert_eq!(normalize_path("main.nx"), "main.nx");
+    }
+
+    #[test]
+    fn test_display_path_is_relative_to_root() {
+        let root = Path::new("/work/app");
+        assert_eq!(
+            display_path(Path::new("/work/app/src/main.nx"), root),
+            "src/main.nx"
+        );
+        assert_eq!(
+            display_path(Path::new("/work/lib/main.nx"), root),
+            "/work/lib/main.nx"
+        );
+        assert_eq!(display_path(Path::new("src/main.nx"), root), "src/main.nx");
+    }
+}
@@ -1,6 +1,6 @@
 //! Rendering functionality for displaying diagnostics with beautiful formatting.
 
-use crate::{Diagnostic, Severity};
+use crate::{normalize_path, Diagnostic, Severity};
 use ariadne::{sources, Color, Fmt, Label as AriadneLabel, Report, ReportKind};
 use std::collections::HashMap;
 use std::fmt::Write as _;
@@ -11,7 +11,8 @@ use std::fmt::Write as _;
 /// # Arguments
 ///
 /// * `diagnostic` - The diagnostic to render
-/// * `sources` - Map of file names to their source code
+/// * `sources` - Map of file paths to their source code. Paths are matched against labels after
+///   [`normalize_path`], so either separator style may be used.
 ///
 /// # Returns
 ///
@@ -82,7 +83,10 @@ pub fn render_diagnostic(diagnostic: &Diagnostic, source_map: &HashMap<String, S
     let report = report.finish();
 
     // Build a cache of sources for ariadne, ensuring every labeled file has an entry.
-    let mut cache_inputs: HashMap<String, String> = source_map.clone();
+    let mut cache_inputs: HashMap<String, String> = source_map
+        .iter()
+        .map(|(file, source)| (normalize_path(file), source.clone()))
+        .collect();
     for label in diagnostic.labels() {
         cache_inputs
             .entry(label.file.clone())
@@ -139,7 +143,7 @@ pub fn render_diagnostics_cli(
         };
 
         // Resolve source and compute line/col
-        let src = sources.get(&file).map(String::as_str).unwrap_or("");
+        let src = source_for(sources, &file);
         let (line_num, col_num, line_text, col_in_line, highlight_len) = locate(src, start, end);
 
         // Header: severity file:line:col: message
@@ -187,6 +191,20 @@ pub fn render_diagnostics_cli(
     out
 }
 
+/// Looks up the source of a labeled file, whose path is normalized, in a map that may not be.
+fn source_for<'a>(sources: &'a HashMap<String, String>, file: &str) -> &'a str {
+    sources
+        .get(file)
+        .or_else(|| {
+            sources
+                .iter()
+                .find(|(name, _)| normalize_path(name) == file)
+                .map(|(_, source)| source)
+        })
+        .map(String::as_str)
+        .unwrap_or("")
+}
+
 // Compute 1-based line/col and a single-line highlight presentation
 #[cfg_attr(not(test), allow(dead_code))]
 fn locate(src: &str, start: usize, end: usize) -> (usize, usize, &str, usize, usize) {
@@ -0,0 +1,99 @@
+//! Edit-distance helpers for "did you mean" suggestions.
+
+/// Maximum edit distance for a candidate to be offered as a suggestion.
+pub const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Names shorter than this are only matched with candidates that differ from them in case.
+pub const MIN_FUZZY_MATCH_LENGTH: usize = 3;
+
+/// Returns the Levenshtein edit distance between two strings, counted in characters.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b_chars.len()]
+}
+
+/// Returns the candidate closest to `name` within [`MAX_SUGGESTION_DISTANCE`] edits.
+///
+/// Exact matches are never suggested. When `name` or a candidate is shorter than
+/// [`MIN_FUZZY_MATCH_LENGTH`] characters, they only match if they differ in case alone, since a
+/// couple of edits turn any short name into any other. Ties are broken by the order of
+/// `candidates`.
+pub fn closest_match<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let name_len = name.chars().count();
+    let mut best: Option<(&'a str, usize)> = None;
+    for candidate in candidates {
+        if candidate == name {
+            continue;
+        }
+        let candidate_len = candidate.chars().count();
+        if name_len < MIN_FUZZY_MATCH_LENGTH || candidate_len < MIN_FUZZY_MATCH_LENGTH {
+            if candidate.to_lowercase() == name.to_lowercase() {
+                return Some(candidate);
+            }
+            continue;
+        }
+        // The length difference is a lower bound on the edit distance.
+        if candidate_len.abs_diff(name_len) > MAX_SUGGESTION_DISTANCE {
+            continue;
+        }
+        let distance = levenshtein(name, candidate);
+        if distance > MAX_SUGGESTION_DISTANCE {
+            continue;
+        }
+        if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+            best = Some((candidate, distance));
+        }
+    }
+
+    best.map(|(candidate, _)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein_counts_edits() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("type", "type"), 0);
+        assert_eq!(levenshtein("colour", "color"), 1);
+        assert_eq!(levenshtein("tpye", "type"), 2);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_closest_match_prefers_smallest_distance() {
+        let candidates = ["userId", "userName", "username"];
+        assert_eq!(closest_match("usrName", candidates), Some("userName"));
+    }
+
+    #[test]
+    fn test_closest_match_ignores_exact_and_distant_candidates() {
+        assert_eq!(closest_match("type", ["type"]), None);
+        assert_eq!(closest_match("component", ["enum", "let"]), None);
+    }
+
+    #[test]
+    fn test_closest_match_only_changes_the_case_of_short_names() {
+        assert_eq!(closest_match("x", ["t", "xs"]), None);
+        assert_eq!(closest_match("id", ["if"]), None);
+        assert_eq!(closest_match("fro", ["f"]), None);
+        assert_eq!(closest_match("X", ["y", "x"]), Some("x"));
+        assert_eq!(closest_match("iD", ["if", "Id"]), Some("Id"));
+    }
+}
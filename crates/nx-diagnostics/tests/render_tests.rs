@@ -1,6 +1,6 @@
 //! Integration tests for diagnostic rendering.
 
-use nx_diagnostics::{render_diagnostic, Diagnostic, Label};
+use nx_diagnostics::{render_diagnostic, render_diagnostics_cli, Diagnostic, Label};
 use std::collections::HashMap;
 use text_size::{TextRange, TextSize};
 
@@ -72,3 +72,38 @@ fn test_render_warning() {
 
     assert!(rendered.contains("Variable 'unused' is never used"));
 }
+
+#[test]
+fn test_render_cli_keeps_directories_and_normalizes_separators() {
+    let mut sources = HashMap::new();
+    sources.insert(r"app\ui\main.nx".to_string(), "let a = 1".to_string());
+    sources.insert("lib/ui/main.nx".to_string(), "let b = 2".to_string());
+
+    let range = TextRange::new(TextSize::from(4), TextSize::from(5));
+    let diagnostics = [
+        Diagnostic::error("first")
+            .with_message("first")
+            .with_label(Label::primary(r"app\ui\main.nx", range))
+            .build(),
+        Diagnostic::error("second")
+            .with_message("second")
+            .with_label(Label::primary("./lib//ui/main.nx", range))
+            .build(),
+    ];
+
+    let rendered = render_diagnostics_cli(&diagnostics, &sources);
+
+    assert!(
+        rendered.contains("error app/ui/main.nx:1:5: first"),
+        "{rendered}"
+    );
+    assert!(rendered.contains("let a = 1"), "{rendered}");
+    assert!(
+        rendered.contains("error lib/ui/main.nx:1:5: second"),
+        "{rendered}"
+    );
+    assert!(rendered.contains("let b = 2"), "{rendered}");
+
+    let rendered = render_diagnostic(&diagnostics[0], &sources);
+    assert!(rendered.contains("app/ui/main.nx:1:5"), "{rendered}");
+}
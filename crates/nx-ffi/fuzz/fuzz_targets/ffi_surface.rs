@@ -0,0 +1,17 @@
+//! libFuzzer target for the nx-ffi C ABI.
+//!
+//! Run from `crates/nx-ffi` with a nightly toolchain and debug assertions, which enable the
+//! buffer and handle registry that stale and tampered arguments rely on:
+//!
+//! ```text
+//! cargo +nightly fuzz run ffi_surface --debug-assertions
+//! ```
+//!
+//! The operations are shared with `tests/ffi_fuzz.rs`, which replays a fixed corpus on stable.
+
+#![no_main]
+
+#[path = "../../tests/fuzz_driver/mod.rs"]
+mod fuzz_driver;
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| fuzz_driver::drive(data));
@@ -0,0 +1,233 @@
+//! Pointer contracts for buffers and handles crossing the C ABI.
+//!
+//! A host owns every [`NxBuffer`] and handle an entry point returns until it passes the value back
+//! to the matching `nx_free_*` function, exactly once and unchanged. Debug builds record each
+//! buffer and handle as it is handed out and check it again when it comes back, so a double free,
+//! a buffer whose `len` or `cap` was modified, a handle used after it was freed, or a handle of the
+//! wrong kind is reported instead of corrupting the heap. The offending call is skipped: free
+//! functions leak the value and entry points return `NxEvalStatus::InvalidArgument`.
+//!
+//! Reports go to the callback registered with `nx_set_log_callback`, at `Error` level, or to
+//! stderr when there is none. They never panic, because a panic cannot unwind out of an
+//! `extern "C"` function. Release builds keep no registry and only run the checks that need no
+//! bookkeeping: null and misaligned pointers, and buffers whose `len` exceeds their `cap`.
+
+use crate::{NxBuffer, NxEvalStatus, LOG_CALLBACK};
+use nx_api::{LogLevel, NxLogRecord};
+
+/// The kind of value an opaque handle points to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HandleKind {
+    LibraryRegistry,
+    BuildContext,
+    ProgramArtifact,
+}
+
+impl HandleKind {
+    #[cfg_attr(not(debug_assertions), allow(dead_code))]
+    fn name(self) -> &'static str {
+        match self {
+            HandleKind::LibraryRegistry => "library registry",
+            HandleKind::BuildContext => "program build context",
+            HandleKind::ProgramArtifact => "program artifact",
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+mod registry {
+    use super::HandleKind;
+    use std::collections::BTreeMap;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// Outstanding buffers by address, with the `len` and `cap` they were handed out with.
+    static BUFFERS: Mutex<BTreeMap<usize, (usize, usize)>> = Mutex::new(BTreeMap::new());
+
+    /// Live handles by address.
+    static HANDLES: Mutex<BTreeMap<usize, HandleKind>> = Mutex::new(BTreeMap::new());
+
+    pub(super) fn buffers() -> MutexGuard<'static, BTreeMap<usize, (usize, usize)>> {
+        BUFFERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(super) fn handles() -> MutexGuard<'static, BTreeMap<usize, HandleKind>> {
+        HANDLES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Records a buffer that is about to be handed to the host.
+pub(crate) fn track_buffer(buffer: &NxBuffer) {
+    // Zero-capacity buffers own no allocation and may share a dangling address.
+    #[cfg(debug_assertions)]
+    if buffer.cap > 0 {
+        registry::buffers().insert(buffer.ptr as usize, (buffer.len, buffer.cap));
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = buffer;
+}
+
+/// Checks a buffer passed to `nx_free_buffer`, returning whether it is safe to free.
+///
+/// A null buffer with zero `len` and `cap` is valid and has nothing to free.
+pub(crate) fn release_buffer(buffer: &NxBuffer) -> bool {
+    if buffer.ptr.is_null() {
+        if buffer.len != 0 || buffer.cap != 0 {
+            report_violation(format!(
+                "nx_free_buffer received a null buffer with len {} and cap {}",
+                buffer.len, buffer.cap
+            ));
+        }
+        return false;
+    }
+    if buffer.len > buffer.cap {
+        report_violation(format!(
+            "nx_free_buffer received buffer {:p} with len {} greater than cap {}",
+            buffer.ptr, buffer.len, buffer.cap
+        ));
+        return false;
+    }
+
+    #[cfg(debug_assertions)]
+    if buffer.cap > 0 {
+        let address = buffer.ptr as usize;
+        let mut buffers = registry::buffers();
+        match buffers.get(&address).copied() {
+            Some((len, cap)) if (len, cap) == (buffer.len, buffer.cap) => {
+                buffers.remove(&address);
+            }
+            Some((len, cap)) => {
+                drop(buffers);
+                report_violation(format!(
+                    "nx_free_buffer received buffer {:p} with len {} and cap {}, \
+                     but it was returned with len {} and cap {}",
+                    buffer.ptr, buffer.len, buffer.cap, len, cap
+                ));
+                return false;
+            }
+            None => {
+                drop(buffers);
+                report_violation(format!(
+                    "nx_free_buffer received buffer {:p}, which was already freed or was not \
+                     returned by nx-ffi",
+                    buffer.ptr
+                ));
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Records a handle that is about to be handed to the host.
+pub(crate) fn track_handle<T>(handle: *mut T, kind: HandleKind) {
+    #[cfg(debug_assertions)]
+    registry::handles().insert(handle as usize, kind);
+    #[cfg(not(debug_assertions))]
+    let _ = (handle, kind);
+}
+
+/// Checks a handle passed to an entry point.
+///
+/// Null handles are rejected without a report, because hosts are allowed to pass them.
+pub(crate) fn check_handle<T>(handle: *const T, kind: HandleKind) -> Result<(), NxEvalStatus> {
+    if handle.is_null() {
+        return Err(NxEvalStatus::InvalidArgument);
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        let live = registry::handles().get(&(handle as usize)).copied();
+        if live != Some(kind) {
+            report_violation(describe_bad_handle(handle, kind, live));
+            return Err(NxEvalStatus::InvalidArgument);
+        }
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = kind;
+
+    Ok(())
+}
+
+/// Checks a handle passed to an `nx_free_*` function, returning whether it is safe to free.
+pub(crate) fn release_handle<T>(handle: *mut T, kind: HandleKind) -> bool {
+    if handle.is_null() {
+        return false;
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        let mut handles = registry::handles();
+        let live = handles.get(&(handle as usize)).copied();
+        if live != Some(kind) {
+            drop(handles);
+            report_violation(describe_bad_handle(handle, kind, live));
+            return false;
+        }
+        handles.remove(&(handle as usize));
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = kind;
+
+    true
+}
+
+#[cfg(debug_assertions)]
+fn describe_bad_handle<T>(
+    handle: *const T,
+    expected: HandleKind,
+    live: Option<HandleKind>,
+) -> String {
+    match live {
+        Some(actual) => format!(
+            "expected a {} handle but received a {} handle {:p}",
+            expected.name(),
+            actual.name(),
+            handle
+        ),
+        None => format!(
+            "{} handle {:p} was already freed or was not created by nx-ffi",
+            expected.name(),
+            handle
+        ),
+    }
+}
+
+/// Checks a pointer the entry point will write through or read an array from.
+///
+/// Null pointers are rejected without a report; misaligned ones are reported.
+pub(crate) fn check_pointer<T>(ptr: *const T) -> Result<(), NxEvalStatus> {
+    if ptr.is_null() {
+        return Err(NxEvalStatus::InvalidArgument);
+    }
+    if (ptr as usize) % std::mem::align_of::<T>() != 0 {
+        report_violation(format!(
+            "pointer {:p} is not aligned to {} bytes for {}",
+            ptr,
+            std::mem::align_of::<T>(),
+            std::any::type_name::<T>()
+        ));
+        return Err(NxEvalStatus::InvalidArgument);
+    }
+
+    Ok(())
+}
+
+fn report_violation(message: String) {
+    let message = format!("nx-ffi contract violation: {message}");
+    let registered = *LOG_CALLBACK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match registered {
+        Some(registered) => registered.log(NxLogRecord {
+            level: LogLevel::Error,
+            message,
+            value: None,
+        }),
+        None => eprintln!("{message}"),
+    }
+}
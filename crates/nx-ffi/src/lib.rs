@@ -1,25 +1,49 @@
 //! C ABI wrapper for NX evaluation, intended for P/Invoke and other FFI consumers.
+//!
+//! Buffers and handles returned to the host are checked when they come back; see [`contracts`]
+//! for what debug builds detect.
+
+mod contracts;
 
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine;
+use contracts::HandleKind;
 use nx_api::{
     build_workspace_program_artifact,
     dispatch_component_actions_program_artifact as api_dispatch_component_actions_program_artifact,
-    eval_program_artifact as api_eval_program_artifact, eval_source,
+    eval_program_artifact_with_options as api_eval_program_artifact_with_options,
+    eval_source_with_options,
     evaluate_component_program_artifact as api_evaluate_component_program_artifact,
     initialize_component_program_artifact as api_initialize_component_program_artifact,
-    load_program_artifact_from_source, validate_workspace, ComponentDispatchEvalResult,
-    ComponentDispatchResult, ComponentEvaluateEvalResult, ComponentInitEvalResult,
-    ComponentInitResult, EvalResult, LibraryRegistry, NxDiagnostic, NxSeverity, NxWorkspace,
-    NxWorkspaceModule as ApiNxWorkspaceModule, ProgramArtifact, ProgramBuildContext,
+    load_program_artifact_from_source, negotiate_envelope_version, read_module_metadata,
+    validate_workspace, ComponentDispatchEvalResult, ComponentDispatchResult,
+    ComponentEvaluateEvalResult, ComponentInitEvalResult, ComponentInitResult, EvalOptions,
+    EvalResult, LibraryRegistry, LogLevel, NxDiagnostic, NxEnvelope, NxEnvelopeKind, NxLogRecord,
+    NxLogSink, NxSeverity, NxWorkspace, NxWorkspaceModule as ApiNxWorkspaceModule, ProgramArtifact,
+    ProgramBuildContext, LATEST_ENVELOPE_VERSION,
 };
 use nx_value::NxValue;
 use serde::Serialize;
 use std::any::Any;
+use std::ffi::c_void;
 use std::panic;
+use std::sync::RwLock;
 
 pub const NX_FFI_ABI_VERSION: u32 = 10;
 
+/// Bits of an `output_format` argument above this shift select the envelope version.
+///
+/// The low bits hold the [`NxOutputFormat`]. Pass
+/// `format | (version << NX_OUTPUT_ENVELOPE_SHIFT)`, with a version agreed through
+/// [`nx_ffi_negotiate_envelope_version`], to receive results and diagnostics wrapped as
+/// `{"$nx": version, "value": ...}` or `{"$nx": version, "diagnostics": [...]}`. Version `0`, the
+/// default, writes bare payloads.
+pub const NX_OUTPUT_ENVELOPE_SHIFT: u32 = 8;
+
+/// Size of the chunks passed to an [`NxStreamCallback`]. Only the last chunk of a payload may be
+/// shorter.
+pub const NX_STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
 #[repr(C)]
 pub struct NxBuffer {
     pub ptr: *mut u8,
@@ -90,6 +114,190 @@ impl TryFrom<u32> for NxOutputFormat {
     }
 }
 
+/// Severity of an NX `log(level, message, value?)` call, from least to most severe.
+#[repr(u32)]
+#[derive(Clone, Copy)]
+pub enum NxLogLevel {
+    Debug = 0,
+    Info = 1,
+    Warn = 2,
+    Error = 3,
+}
+
+impl TryFrom<u32> for NxLogLevel {
+    type Error = NxEvalStatus;
+
+    fn try_from(value: u32) -> Result<Self, NxEvalStatus> {
+        match value {
+            0 => Ok(NxLogLevel::Debug),
+            1 => Ok(NxLogLevel::Info),
+            2 => Ok(NxLogLevel::Warn),
+            3 => Ok(NxLogLevel::Error),
+            _ => Err(NxEvalStatus::InvalidArgument),
+        }
+    }
+}
+
+impl From<NxLogLevel> for LogLevel {
+    fn from(level: NxLogLevel) -> Self {
+        match level {
+            NxLogLevel::Debug => LogLevel::Debug,
+            NxLogLevel::Info => LogLevel::Info,
+            NxLogLevel::Warn => LogLevel::Warn,
+            NxLogLevel::Error => LogLevel::Error,
+        }
+    }
+}
+
+impl From<LogLevel> for NxLogLevel {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Debug => NxLogLevel::Debug,
+            LogLevel::Info => NxLogLevel::Info,
+            LogLevel::Warn => NxLogLevel::Warn,
+            LogLevel::Error => NxLogLevel::Error,
+        }
+    }
+}
+
+/// Receives the `log` calls of programs evaluated by `nx_eval_source`, `nx_eval_source_stream`, and
+/// `nx_eval_program_artifact`.
+///
+/// `message_ptr` / `message_len` hold the UTF-8 message. `value_json_ptr` / `value_json_len` hold
+/// the optional third argument as JSON, or are null / `0` when the call had none. The buffers are
+/// only valid until the callback returns. The callback runs on the thread evaluating the program.
+pub type NxLogCallback = extern "C" fn(
+    user_data: *mut c_void,
+    level: NxLogLevel,
+    message_ptr: *const u8,
+    message_len: usize,
+    value_json_ptr: *const u8,
+    value_json_len: usize,
+);
+
+/// Receives the output of `nx_eval_source_stream` one MessagePack chunk at a time.
+///
+/// Chunks arrive in order on the calling thread, and together they hold the bytes `nx_eval_source`
+/// would write for `NxOutputFormat_MessagePack`. `chunk_ptr` is only valid until the callback
+/// returns.
+pub type NxStreamCallback =
+    extern "C" fn(user_data: *mut c_void, chunk_ptr: *const u8, chunk_len: usize);
+
+#[derive(Clone, Copy)]
+struct RegisteredLogCallback {
+    callback: NxLogCallback,
+    user_data: *mut c_void,
+    min_level: LogLevel,
+}
+
+// The host promises `user_data` may be used from any thread evaluating a program.
+unsafe impl Send for RegisteredLogCallback {}
+unsafe impl Sync for RegisteredLogCallback {}
+
+impl RegisteredLogCallback {
+    fn log(&self, record: NxLogRecord) {
+        let value_json = record
+            .value
+            .map(|value| serde_json::to_string(&value).unwrap_or_else(|_| "null".to_string()));
+        let (value_json_ptr, value_json_len) = match &value_json {
+            Some(json) => (json.as_ptr(), json.len()),
+            None => (std::ptr::null(), 0),
+        };
+        (self.callback)(
+            self.user_data,
+            record.level.into(),
+            record.message.as_ptr(),
+            record.message.len(),
+            value_json_ptr,
+            value_json_len,
+        );
+    }
+}
+
+static LOG_CALLBACK: RwLock<Option<RegisteredLogCallback>> = RwLock::new(None);
+
+/// Returns the evaluation options for the current process-wide settings.
+fn eval_options() -> EvalOptions {
+    let registered = *LOG_CALLBACK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match registered {
+        Some(registered) => EvalOptions {
+            log_sink: Some(NxLogSink::new(move |record| registered.log(record))),
+            log_level: registered.min_level,
+            ..EvalOptions::default()
+        },
+        None => EvalOptions::default(),
+    }
+}
+
+/// Passes written bytes to an [`NxStreamCallback`] in chunks of [`NX_STREAM_CHUNK_SIZE`].
+struct ChunkWriter {
+    callback: NxStreamCallback,
+    user_data: *mut c_void,
+    chunk: Vec<u8>,
+    /// Whether any chunk has reached the callback.
+    started: bool,
+}
+
+impl ChunkWriter {
+    fn new(callback: NxStreamCallback, user_data: *mut c_void) -> Self {
+        Self {
+            callback,
+            user_data,
+            chunk: Vec::with_capacity(NX_STREAM_CHUNK_SIZE),
+            started: false,
+        }
+    }
+
+    fn emit(&mut self) {
+        if self.chunk.is_empty() {
+            return;
+        }
+        (self.callback)(self.user_data, self.chunk.as_ptr(), self.chunk.len());
+        self.chunk.clear();
+        self.started = true;
+    }
+
+    /// Streams `payload` as named MessagePack, flushing the final partial chunk.
+    ///
+    /// Nothing reaches the callback when encoding fails within the first chunk.
+    fn write_msgpack<T: Serialize + ?Sized>(&mut self, payload: &T) -> Result<(), String> {
+        match rmp_serde::encode::write_named(self, payload) {
+            Ok(()) => {
+                self.emit();
+                Ok(())
+            }
+            Err(e) => {
+                self.chunk.clear();
+                Err(format!("messagepack serialize failed: {e}"))
+            }
+        }
+    }
+}
+
+impl std::io::Write for ChunkWriter {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        let len = bytes.len().min(NX_STREAM_CHUNK_SIZE - self.chunk.len());
+        self.chunk.extend_from_slice(&bytes[..len]);
+        if self.chunk.len() == NX_STREAM_CHUNK_SIZE {
+            self.emit();
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The output format and envelope version selected by an `output_format` argument.
+#[derive(Clone, Copy)]
+struct OutputEncoding {
+    format: NxOutputFormat,
+    envelope_version: u32,
+}
+
 #[derive(Serialize)]
 struct JsonComponentInitResult<'a> {
     rendered: &'a NxValue,
@@ -121,9 +329,47 @@ pub extern "C" fn nx_ffi_abi_version() -> u32 {
     NX_FFI_ABI_VERSION
 }
 
+/// Returns the envelope version to pass in `output_format` for a consumer that reads envelope
+/// versions up to `consumer_version`.
+///
+/// The result is the newest version both sides understand, or `0` when the consumer reads no
+/// envelopes. See [`NX_OUTPUT_ENVELOPE_SHIFT`].
+#[no_mangle]
+pub extern "C" fn nx_ffi_negotiate_envelope_version(consumer_version: u32) -> u32 {
+    negotiate_envelope_version(consumer_version)
+}
+
+/// Registers the callback that receives `log` calls, replacing any previous one.
+///
+/// The registration is process-wide and applies to evaluations that start after this call.
+/// Calls below `min_level`, an [`NxLogLevel`] value, are dropped before reaching the callback.
+/// Pass a null callback to stop receiving them; without a callback, `log` does nothing.
+/// `user_data` is passed back unchanged and must stay valid until the callback is replaced.
+/// Returns `NxEvalStatus_InvalidArgument` when `min_level` is not a level.
+#[no_mangle]
+pub extern "C" fn nx_set_log_callback(
+    callback: Option<NxLogCallback>,
+    user_data: *mut c_void,
+    min_level: u32,
+) -> NxEvalStatus {
+    let min_level = match NxLogLevel::try_from(min_level) {
+        Ok(min_level) => LogLevel::from(min_level),
+        Err(status) => return status,
+    };
+    let registered = callback.map(|callback| RegisteredLogCallback {
+        callback,
+        user_data,
+        min_level,
+    });
+    *LOG_CALLBACK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = registered;
+    NxEvalStatus::Ok
+}
+
 #[no_mangle]
 pub extern "C" fn nx_free_buffer(buffer: NxBuffer) {
-    if buffer.ptr.is_null() {
+    if !contracts::release_buffer(&buffer) {
         return;
     }
 
@@ -140,6 +386,7 @@ fn ffi_error_diagnostics(message: String) -> Vec<NxDiagnostic> {
         labels: Vec::new(),
         help: None,
         note: None,
+        fixes: Vec::new(),
     }]
 }
 
@@ -156,28 +403,31 @@ fn write_json_payload(out_buffer: *mut NxBuffer, payload: String) {
 }
 
 fn prepare_out_buffer(out_buffer: *mut NxBuffer) -> Result<(), NxEvalStatus> {
+    contracts::check_pointer(out_buffer)?;
     unsafe {
-        if out_buffer.is_null() {
-            return Err(NxEvalStatus::InvalidArgument);
-        }
         *out_buffer = NxBuffer::empty();
     }
 
     Ok(())
 }
 
-fn parse_output_format(output_format: u32) -> Result<NxOutputFormat, NxEvalStatus> {
-    NxOutputFormat::try_from(output_format)
+fn parse_output_format(output_format: u32) -> Result<OutputEncoding, NxEvalStatus> {
+    let envelope_version = output_format >> NX_OUTPUT_ENVELOPE_SHIFT;
+    if envelope_version > LATEST_ENVELOPE_VERSION {
+        return Err(NxEvalStatus::InvalidArgument);
+    }
+
+    Ok(OutputEncoding {
+        format: NxOutputFormat::try_from(output_format & ((1 << NX_OUTPUT_ENVELOPE_SHIFT) - 1))?,
+        envelope_version,
+    })
 }
 
 fn prepare_out_program_artifact_handle(
     out_handle: *mut *mut NxProgramArtifactHandle,
 ) -> Result<(), NxEvalStatus> {
+    contracts::check_pointer(out_handle)?;
     unsafe {
-        if out_handle.is_null() {
-            return Err(NxEvalStatus::InvalidArgument);
-        }
-
         *out_handle = std::ptr::null_mut();
     }
 
@@ -187,11 +437,8 @@ fn prepare_out_program_artifact_handle(
 fn prepare_out_library_registry_handle(
     out_handle: *mut *mut NxLibraryRegistryHandle,
 ) -> Result<(), NxEvalStatus> {
+    contracts::check_pointer(out_handle)?;
     unsafe {
-        if out_handle.is_null() {
-            return Err(NxEvalStatus::InvalidArgument);
-        }
-
         *out_handle = std::ptr::null_mut();
     }
 
@@ -201,11 +448,8 @@ fn prepare_out_library_registry_handle(
 fn prepare_out_build_context_handle(
     out_handle: *mut *mut NxProgramBuildContextHandle,
 ) -> Result<(), NxEvalStatus> {
+    contracts::check_pointer(out_handle)?;
     unsafe {
-        if out_handle.is_null() {
-            return Err(NxEvalStatus::InvalidArgument);
-        }
-
         *out_handle = std::ptr::null_mut();
     }
 
@@ -233,7 +477,7 @@ fn finish_msgpack_entry(
 
 fn finish_output_entry(
     out_buffer: *mut NxBuffer,
-    output_format: NxOutputFormat,
+    output_format: OutputEncoding,
     result: Result<Result<(NxEvalStatus, FfiPayload), String>, Box<dyn Any + Send>>,
 ) -> NxEvalStatus {
     match result {
@@ -253,6 +497,10 @@ fn finish_output_entry(
     }
 }
 
+fn parse_source<'a>(source_ptr: *const u8, source_len: usize) -> Result<&'a str, String> {
+    unsafe { slice_to_str(source_ptr, source_len) }
+}
+
 fn parse_file_name(file_name_ptr: *const u8, file_name_len: usize) -> Result<String, String> {
     let file_name = unsafe { slice_to_str(file_name_ptr, file_name_len) }.unwrap_or("input.nx");
     if file_name.is_empty() {
@@ -266,8 +514,11 @@ fn parse_workspace_modules(
     modules_ptr: *const NxWorkspaceModule,
     module_count: usize,
 ) -> Result<NxWorkspace, NxEvalStatus> {
-    if module_count > 0 && modules_ptr.is_null() {
-        return Err(NxEvalStatus::InvalidArgument);
+    if module_count > 0 {
+        contracts::check_pointer(modules_ptr)?;
+        if module_count > isize::MAX as usize / std::mem::size_of::<NxWorkspaceModule>() {
+            return Err(NxEvalStatus::InvalidArgument);
+        }
     }
 
     let descriptors = if module_count == 0 {
@@ -341,77 +592,100 @@ fn parse_msgpack_actions(bytes: &[u8]) -> Result<Vec<NxValue>, String> {
     rmp_serde::from_slice(bytes).map_err(|e| format!("messagepack decode failed: {e}"))
 }
 
-fn json_component_init_payload(result: &ComponentInitResult) -> Result<String, String> {
-    serde_json::to_string(&JsonComponentInitResult {
+fn json_component_init_payload(result: &ComponentInitResult) -> JsonComponentInitResult<'_> {
+    JsonComponentInitResult {
         rendered: &result.rendered,
         state_snapshot: BASE64_STANDARD.encode(&result.state_snapshot),
-    })
-    .map_err(|e| format!("json serialize failed: {e}"))
+    }
 }
 
-fn json_component_dispatch_payload(result: &ComponentDispatchResult) -> Result<String, String> {
-    serde_json::to_string(&JsonComponentDispatchResult {
+fn json_component_dispatch_payload(
+    result: &ComponentDispatchResult,
+) -> JsonComponentDispatchResult<'_> {
+    JsonComponentDispatchResult {
         effects: &result.effects,
         state_snapshot: BASE64_STANDARD.encode(&result.state_snapshot),
-    })
-    .map_err(|e| format!("json serialize failed: {e}"))
+    }
 }
 
-fn serialize_eval_payload(
-    output_format: NxOutputFormat,
-    value: &NxValue,
+/// Serializes `payload` in the selected format, inside an envelope when one was requested.
+fn serialize_payload<T: Serialize + ?Sized>(
+    output_format: OutputEncoding,
+    kind: NxEnvelopeKind,
+    payload: &T,
 ) -> Result<FfiPayload, String> {
-    match output_format {
+    let version = output_format.envelope_version;
+    match output_format.format {
         NxOutputFormat::MessagePack => Ok(FfiPayload::Msgpack(
-            rmp_serde::to_vec(value).map_err(|e| format!("messagepack serialize failed: {e}"))?,
+            if version == 0 {
+                rmp_serde::to_vec_named(payload)
+            } else {
+                rmp_serde::to_vec_named(&NxEnvelope {
+                    version,
+                    kind,
+                    payload,
+                })
+            }
+            .map_err(|e| format!("messagepack serialize failed: {e}"))?,
         )),
         NxOutputFormat::Json => Ok(FfiPayload::Json(
-            value
-                .to_json_string()
-                .map_err(|e| format!("json serialize failed: {e}"))?,
+            if version == 0 {
+                serde_json::to_string(payload)
+            } else {
+                serde_json::to_string(&NxEnvelope {
+                    version,
+                    kind,
+                    payload,
+                })
+            }
+            .map_err(|e| format!("json serialize failed: {e}"))?,
         )),
     }
 }
 
+fn serialize_eval_payload(
+    output_format: OutputEncoding,
+    value: &NxValue,
+) -> Result<FfiPayload, String> {
+    serialize_payload(output_format, NxEnvelopeKind::Value, value)
+}
+
 fn serialize_diagnostics_payload(
-    output_format: NxOutputFormat,
+    output_format: OutputEncoding,
     diagnostics: &[NxDiagnostic],
 ) -> Result<FfiPayload, String> {
-    match output_format {
-        NxOutputFormat::MessagePack => Ok(FfiPayload::Msgpack(
-            rmp_serde::to_vec_named(diagnostics)
-                .map_err(|e| format!("messagepack serialize failed: {e}"))?,
-        )),
-        NxOutputFormat::Json => Ok(FfiPayload::Json(
-            serde_json::to_string(diagnostics)
-                .map_err(|e| format!("json serialize failed: {e}"))?,
-        )),
-    }
+    serialize_payload(output_format, NxEnvelopeKind::Diagnostics, diagnostics)
 }
 
 fn serialize_component_init_payload(
-    output_format: NxOutputFormat,
+    output_format: OutputEncoding,
     result: &ComponentInitResult,
 ) -> Result<FfiPayload, String> {
-    match output_format {
-        NxOutputFormat::MessagePack => Ok(FfiPayload::Msgpack(
-            rmp_serde::to_vec_named(result)
-                .map_err(|e| format!("messagepack serialize failed: {e}"))?,
-        )),
-        NxOutputFormat::Json => Ok(FfiPayload::Json(json_component_init_payload(result)?)),
+    match output_format.format {
+        NxOutputFormat::MessagePack => {
+            serialize_payload(output_format, NxEnvelopeKind::Value, result)
+        }
+        NxOutputFormat::Json => serialize_payload(
+            output_format,
+            NxEnvelopeKind::Value,
+            &json_component_init_payload(result),
+        ),
     }
 }
 
 fn serialize_component_dispatch_payload(
-    output_format: NxOutputFormat,
+    output_format: OutputEncoding,
     result: &ComponentDispatchResult,
 ) -> Result<FfiPayload, String> {
-    match output_format {
-        NxOutputFormat::MessagePack => Ok(FfiPayload::Msgpack(
-            rmp_serde::to_vec_named(result)
-                .map_err(|e| format!("messagepack serialize failed: {e}"))?,
-        )),
-        NxOutputFormat::Json => Ok(FfiPayload::Json(json_component_dispatch_payload(result)?)),
+    match output_format.format {
+        NxOutputFormat::MessagePack => {
+            serialize_payload(output_format, NxEnvelopeKind::Value, result)
+        }
+        NxOutputFormat::Json => serialize_payload(
+            output_format,
+            NxEnvelopeKind::Value,
+            &json_component_dispatch_payload(result),
+        ),
     }
 }
 
@@ -438,12 +712,122 @@ pub extern "C" fn nx_eval_source(
         let file_name = parse_file_name(file_name_ptr, file_name_len)?;
         let build_context = ProgramBuildContext::empty();
 
-        let payload = match eval_source(source, &file_name, &build_context) {
-            EvalResult::Ok(value) => (
+        let payload =
+            match eval_source_with_options(source, &file_name, &build_context, &eval_options()) {
+                EvalResult::Ok(value) => (
+                    NxEvalStatus::Ok,
+                    serialize_eval_payload(output_format, &value)?,
+                ),
+                EvalResult::Err(diagnostics) => (
+                    NxEvalStatus::Error,
+                    serialize_diagnostics_payload(output_format, &diagnostics)?,
+                ),
+            };
+
+        Ok(payload)
+    });
+
+    finish_output_entry(out_buffer, output_format, result)
+}
+
+/// Evaluates NX source like `nx_eval_source`, passing the MessagePack output to `callback` in
+/// chunks instead of one buffer.
+///
+/// The result value is streamed with `NxEvalStatus_Ok` and diagnostics with `NxEvalStatus_Error`,
+/// so a host can decode or store a very large document piece by piece instead of allocating it
+/// whole. The chunks end early, with `NxEvalStatus_Error`, only if the value cannot be encoded
+/// after part of it was delivered. A null `callback` returns `NxEvalStatus_InvalidArgument`.
+#[no_mangle]
+pub extern "C" fn nx_eval_source_stream(
+    source_ptr: *const u8,
+    source_len: usize,
+    file_name_ptr: *const u8,
+    file_name_len: usize,
+    callback: Option<NxStreamCallback>,
+    user_data: *mut c_void,
+) -> NxEvalStatus {
+    let Some(callback) = callback else {
+        return NxEvalStatus::InvalidArgument;
+    };
+
+    let result = panic::catch_unwind(|| {
+        let mut writer = ChunkWriter::new(callback, user_data);
+        let status = stream_eval_source(
+            &mut writer,
+            source_ptr,
+            source_len,
+            file_name_ptr,
+            file_name_len,
+        );
+
+        match status {
+            Ok(status) => status,
+            Err(_) if writer.started => NxEvalStatus::Error,
+            Err(message) => {
+                let _ = writer.write_msgpack(&ffi_error_diagnostics(message));
+                NxEvalStatus::Error
+            }
+        }
+    });
+
+    result.unwrap_or(NxEvalStatus::Panic)
+}
+
+fn stream_eval_source(
+    writer: &mut ChunkWriter,
+    source_ptr: *const u8,
+    source_len: usize,
+    file_name_ptr: *const u8,
+    file_name_len: usize,
+) -> Result<NxEvalStatus, String> {
+    let source = unsafe { slice_to_str(source_ptr, source_len) }?;
+    let file_name = parse_file_name(file_name_ptr, file_name_len)?;
+    let build_context = ProgramBuildContext::empty();
+
+    match eval_source_with_options(source, &file_name, &build_context, &eval_options()) {
+        EvalResult::Ok(value) => {
+            writer.write_msgpack(&value)?;
+            Ok(NxEvalStatus::Ok)
+        }
+        EvalResult::Err(diagnostics) => {
+            writer.write_msgpack(&diagnostics)?;
+            Ok(NxEvalStatus::Error)
+        }
+    }
+}
+
+/// Reads the `meta { ... }` block of NX source text without type checking or evaluating it.
+///
+/// Successful output is the metadata record in the selected format, or `null` when the module has
+/// no `meta` block. Syntax errors are returned as diagnostics with `NxEvalStatus::Error`.
+#[no_mangle]
+pub extern "C" fn nx_read_module_metadata(
+    source_ptr: *const u8,
+    source_len: usize,
+    file_name_ptr: *const u8,
+    file_name_len: usize,
+    output_format: u32,
+    out_buffer: *mut NxBuffer,
+) -> NxEvalStatus {
+    if let Err(status) = prepare_out_buffer(out_buffer) {
+        return status;
+    }
+
+    let output_format = match parse_output_format(output_format) {
+        Ok(output_format) => output_format,
+        Err(status) => return status,
+    };
+
+    let result = panic::catch_unwind(|| {
+        let source = parse_source(source_ptr, source_len)?;
+        let file_name = parse_file_name(file_name_ptr, file_name_len)?;
+
+        let payload = match read_module_metadata(source, &file_name) {
+            Ok(metadata) => (
                 NxEvalStatus::Ok,
-                serialize_eval_payload(output_format, &value)?,
+                serialize_eval_payload(output_format, &metadata.unwrap_or(NxValue::Null))?,
             ),
-            EvalResult::Err(diagnostics) => (
+            Err(diagnostics) => (
                 NxEvalStatus::Error,
                 serialize_diagnostics_payload(output_format, &diagnostics)?,
             ),
@@ -473,8 +857,8 @@ pub extern "C" fn nx_build_program_artifact(
         return status;
     }
 
-    if build_context_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(build_context_ptr, HandleKind::BuildContext) {
+        return status;
     }
 
     let result = panic::catch_unwind(|| {
@@ -485,9 +869,11 @@ pub extern "C" fn nx_build_program_artifact(
 
         match load_program_artifact_from_source(source, &file_name, &build_context) {
             Ok(program_artifact) => {
-                let handle = Box::new(ProgramArtifactHandleInner { program_artifact });
+                let handle =
+                    Box::into_raw(Box::new(ProgramArtifactHandleInner { program_artifact }));
+                contracts::track_handle(handle, HandleKind::ProgramArtifact);
                 unsafe {
-                    *out_handle = Box::into_raw(handle).cast::<NxProgramArtifactHandle>();
+                    *out_handle = handle.cast::<NxProgramArtifactHandle>();
                 }
                 Ok((NxEvalStatus::Ok, Vec::new()))
             }
@@ -513,8 +899,8 @@ pub extern "C" fn nx_validate_workspace(
         return status;
     }
 
-    if build_context_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(build_context_ptr, HandleKind::BuildContext) {
+        return status;
     }
 
     let workspace = match parse_workspace_modules(modules_ptr, module_count) {
@@ -551,8 +937,8 @@ pub extern "C" fn nx_build_workspace_program_artifact(
         return status;
     }
 
-    if build_context_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(build_context_ptr, HandleKind::BuildContext) {
+        return status;
     }
 
     let workspace = match parse_workspace_modules(modules_ptr, module_count) {
@@ -568,9 +954,11 @@ pub extern "C" fn nx_build_workspace_program_artifact(
         let handle = unsafe { &*build_context_ptr.cast::<ProgramBuildContextHandleInner>() };
         match build_workspace_program_artifact(&workspace, &entry_identity, &handle.build_context) {
             Ok(program_artifact) => {
-                let handle = Box::new(ProgramArtifactHandleInner { program_artifact });
+                let handle =
+                    Box::into_raw(Box::new(ProgramArtifactHandleInner { program_artifact }));
+                contracts::track_handle(handle, HandleKind::ProgramArtifact);
                 unsafe {
-                    *out_handle = Box::into_raw(handle).cast::<NxProgramArtifactHandle>();
+                    *out_handle = handle.cast::<NxProgramArtifactHandle>();
                 }
                 Ok((NxEvalStatus::Ok, Vec::new()))
             }
@@ -593,23 +981,25 @@ pub extern "C" fn nx_create_library_registry(
         return status;
     }
 
-    let handle = Box::new(LibraryRegistryHandleInner {
+    let handle = Box::into_raw(Box::new(LibraryRegistryHandleInner {
         registry: LibraryRegistry::new(),
-    });
+    }));
+    contracts::track_handle(handle, HandleKind::LibraryRegistry);
     unsafe {
-        *out_handle = Box::into_raw(handle).cast::<NxLibraryRegistryHandle>();
+        *out_handle = handle.cast::<NxLibraryRegistryHandle>();
     }
     NxEvalStatus::Ok
 }
 
 #[no_mangle]
 pub extern "C" fn nx_free_library_registry(handle: *mut NxLibraryRegistryHandle) {
-    if handle.is_null() {
+    let handle = handle.cast::<LibraryRegistryHandleInner>();
+    if !contracts::release_handle(handle, HandleKind::LibraryRegistry) {
         return;
     }
 
     unsafe {
-        let _ = Box::from_raw(handle.cast::<LibraryRegistryHandleInner>());
+        let _ = Box::from_raw(handle);
     }
 }
 
@@ -624,8 +1014,8 @@ pub extern "C" fn nx_load_library_into_registry(
         return status;
     }
 
-    if registry_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(registry_ptr, HandleKind::LibraryRegistry) {
+        return status;
     }
 
     let result = panic::catch_unwind(|| {
@@ -660,16 +1050,17 @@ pub extern "C" fn nx_create_program_build_context(
         return status;
     }
 
-    if registry_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(registry_ptr, HandleKind::LibraryRegistry) {
+        return status;
     }
 
     let result = panic::catch_unwind(|| {
         let build_context =
             with_library_registry(registry_ptr, |registry| Ok(registry.build_context()))?;
-        let handle = Box::new(ProgramBuildContextHandleInner { build_context });
+        let handle = Box::into_raw(Box::new(ProgramBuildContextHandleInner { build_context }));
+        contracts::track_handle(handle, HandleKind::BuildContext);
         unsafe {
-            *out_handle = Box::into_raw(handle).cast::<NxProgramBuildContextHandle>();
+            *out_handle = handle.cast::<NxProgramBuildContextHandle>();
         }
         Ok::<(), String>(())
     });
@@ -683,23 +1074,25 @@ pub extern "C" fn nx_create_program_build_context(
 
 #[no_mangle]
 pub extern "C" fn nx_free_program_build_context(handle: *mut NxProgramBuildContextHandle) {
-    if handle.is_null() {
+    let handle = handle.cast::<ProgramBuildContextHandleInner>();
+    if !contracts::release_handle(handle, HandleKind::BuildContext) {
         return;
     }
 
     unsafe {
-        let _ = Box::from_raw(handle.cast::<ProgramBuildContextHandleInner>());
+        let _ = Box::from_raw(handle);
     }
 }
 
 #[no_mangle]
 pub extern "C" fn nx_free_program_artifact(handle: *mut NxProgramArtifactHandle) {
-    if handle.is_null() {
+    let handle = handle.cast::<ProgramArtifactHandleInner>();
+    if !contracts::release_handle(handle, HandleKind::ProgramArtifact) {
         return;
     }
 
     unsafe {
-        let _ = Box::from_raw(handle.cast::<ProgramArtifactHandleInner>());
+        let _ = Box::from_raw(handle);
     }
 }
 
@@ -718,13 +1111,14 @@ pub extern "C" fn nx_eval_program_artifact(
         Err(status) => return status,
     };
 
-    if program_artifact_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(program_artifact_ptr, HandleKind::ProgramArtifact)
+    {
+        return status;
     }
 
     let result = panic::catch_unwind(|| {
         let payload = with_program_artifact(program_artifact_ptr, |program_artifact| {
-            match api_eval_program_artifact(program_artifact) {
+            match api_eval_program_artifact_with_options(program_artifact, &eval_options()) {
                 EvalResult::Ok(value) => Ok((
                     NxEvalStatus::Ok,
                     serialize_eval_payload(output_format, &value)?,
@@ -761,8 +1155,9 @@ pub extern "C" fn nx_component_init_program_artifact(
         Err(status) => return status,
     };
 
-    if program_artifact_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(program_artifact_ptr, HandleKind::ProgramArtifact)
+    {
+        return status;
     }
 
     let result = panic::catch_unwind(|| {
@@ -822,8 +1217,9 @@ pub extern "C" fn nx_component_evaluate_program_artifact(
         Err(status) => return status,
     };
 
-    if program_artifact_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(program_artifact_ptr, HandleKind::ProgramArtifact)
+    {
+        return status;
     }
 
     let result = panic::catch_unwind(|| {
@@ -884,8 +1280,9 @@ pub extern "C" fn nx_component_dispatch_actions_program_artifact(
         Err(status) => return status,
     };
 
-    if program_artifact_ptr.is_null() {
-        return NxEvalStatus::InvalidArgument;
+    if let Err(status) = contracts::check_handle(program_artifact_ptr, HandleKind::ProgramArtifact)
+    {
+        return status;
     }
 
     let result = panic::catch_unwind(|| {
@@ -925,6 +1322,9 @@ pub extern "C" fn nx_component_dispatch_actions_program_artifact(
 }
 
 unsafe fn slice_to_str<'a>(ptr: *const u8, len: usize) -> Result<&'a str, String> {
+    if len > isize::MAX as usize {
+        return Err(format!("length {len} exceeds isize::MAX"));
+    }
     if len == 0 {
         return Ok("");
     }
@@ -937,6 +1337,9 @@ unsafe fn slice_to_str<'a>(ptr: *const u8, len: usize) -> Result<&'a str, String
 }
 
 unsafe fn slice_to_bytes<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], String> {
+    if len > isize::MAX as usize {
+        return Err(format!("length {len} exceeds isize::MAX"));
+    }
     if len == 0 {
         return Ok(&[]);
     }
@@ -949,9 +1352,11 @@ unsafe fn slice_to_bytes<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], Str
 
 fn vec_to_buffer(vec: Vec<u8>) -> NxBuffer {
     let mut vec = std::mem::ManuallyDrop::new(vec);
-    NxBuffer {
+    let buffer = NxBuffer {
         ptr: vec.as_mut_ptr(),
         len: vec.len(),
         cap: vec.capacity(),
-    }
+    };
+    contracts::track_buffer(&buffer);
+    buffer
 }
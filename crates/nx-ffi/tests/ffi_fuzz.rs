@@ -0,0 +1,260 @@
+mod fuzz_driver;
+
+use nx_ffi::{
+    nx_create_library_registry, nx_create_program_build_context, nx_eval_source, nx_free_buffer,
+    nx_free_library_registry, nx_free_program_build_context, nx_set_log_callback,
+    nx_validate_workspace, NxBuffer, NxEvalStatus, NxLibraryRegistryHandle, NxLogLevel,
+    NxProgramBuildContextHandle, NxWorkspaceModule,
+};
+use std::ffi::c_void;
+use std::sync::{Mutex, MutexGuard};
+
+/// Contract violations reported through the log callback.
+static VIOLATIONS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Serializes the tests in this file, since the log callback is process-wide.
+static CALLBACK_LOCK: Mutex<()> = Mutex::new(());
+
+extern "C" fn record_violation(
+    _user_data: *mut c_void,
+    _level: NxLogLevel,
+    message_ptr: *const u8,
+    message_len: usize,
+    _value_json_ptr: *const u8,
+    _value_json_len: usize,
+) {
+    let message = unsafe { std::slice::from_raw_parts(message_ptr, message_len) };
+    VIOLATIONS
+        .lock()
+        .unwrap()
+        .push(String::from_utf8_lossy(message).into_owned());
+}
+
+/// Routes contract violations into [`VIOLATIONS`] until the guard is dropped.
+struct CapturedViolations {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl CapturedViolations {
+    fn start() -> Self {
+        let lock = CALLBACK_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        VIOLATIONS.lock().unwrap().clear();
+        assert!(matches!(
+            nx_set_log_callback(
+                Some(record_violation),
+                std::ptr::null_mut(),
+                NxLogLevel::Error as u32
+            ),
+            NxEvalStatus::Ok
+        ));
+        Self { _lock: lock }
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *VIOLATIONS.lock().unwrap())
+    }
+}
+
+impl Drop for CapturedViolations {
+    fn drop(&mut self) {
+        nx_set_log_callback(None, std::ptr::null_mut(), 0);
+    }
+}
+
+fn empty_buffer() -> NxBuffer {
+    NxBuffer {
+        ptr: std::ptr::null_mut(),
+        len: 0,
+        cap: 0,
+    }
+}
+
+fn create_build_context() -> (
+    *mut NxLibraryRegistryHandle,
+    *mut NxProgramBuildContextHandle,
+) {
+    let mut registry = std::ptr::null_mut();
+    assert!(matches!(
+        nx_create_library_registry(&mut registry),
+        NxEvalStatus::Ok
+    ));
+    let mut build_context = std::ptr::null_mut();
+    assert!(matches!(
+        nx_create_program_build_context(registry, &mut build_context),
+        NxEvalStatus::Ok
+    ));
+    (registry, build_context)
+}
+
+/// Xorshift, so the corpus is the same on every run and platform.
+fn pseudo_random_corpus(seed: u64, count: usize) -> Vec<Vec<u8>> {
+    let mut state = seed;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    (0..count)
+        .map(|_| {
+            let len = (next() % 96) as usize;
+            (0..len).map(|_| next() as u8).collect()
+        })
+        .collect()
+}
+
+#[test]
+fn ffi_fuzz_driver_survives_pseudo_random_corpus() {
+    let captured = CapturedViolations::start();
+    for input in pseudo_random_corpus(0x6e78_2d66_6669, 200) {
+        fuzz_driver::drive(&input);
+    }
+    // Every operation, including the ones that misuse buffers, on its own.
+    for op in 0u8..11 {
+        fuzz_driver::drive(&[op, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    for violation in captured.take() {
+        assert!(
+            violation.starts_with("nx-ffi contract violation: "),
+            "{violation}"
+        );
+    }
+}
+
+#[test]
+fn ffi_rejects_oversized_lengths_and_misaligned_arrays() {
+    let captured = CapturedViolations::start();
+    let source = b"let root() = { 1 }";
+    let mut out = empty_buffer();
+    let status = nx_eval_source(
+        source.as_ptr(),
+        usize::MAX,
+        std::ptr::null(),
+        0,
+        1,
+        &mut out,
+    );
+    assert!(matches!(status, NxEvalStatus::Error));
+    let diagnostics = unsafe { std::slice::from_raw_parts(out.ptr, out.len) };
+    assert!(String::from_utf8_lossy(diagnostics).contains("exceeds isize::MAX"));
+    nx_free_buffer(out);
+
+    let (registry, build_context) = create_build_context();
+    let descriptors = [0u64; 8];
+    let misaligned = descriptors
+        .as_ptr()
+        .cast::<u8>()
+        .wrapping_add(1)
+        .cast::<NxWorkspaceModule>();
+    let mut out = empty_buffer();
+    let status = nx_validate_workspace(build_context, misaligned, 1, &mut out);
+    assert!(matches!(status, NxEvalStatus::InvalidArgument));
+    assert!(out.ptr.is_null());
+
+    let status = nx_validate_workspace(
+        build_context,
+        descriptors.as_ptr().cast(),
+        usize::MAX,
+        &mut out,
+    );
+    assert!(matches!(status, NxEvalStatus::InvalidArgument));
+
+    nx_free_program_build_context(build_context);
+    nx_free_library_registry(registry);
+
+    let violations = captured.take();
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert!(violations[0].contains("is not aligned"), "{violations:?}");
+}
+
+#[test]
+fn ffi_null_buffer_with_length_is_reported_instead_of_freed() {
+    let captured = CapturedViolations::start();
+    nx_free_buffer(empty_buffer());
+    nx_free_buffer(NxBuffer {
+        ptr: std::ptr::null_mut(),
+        len: 3,
+        cap: 8,
+    });
+
+    assert_eq!(
+        captured.take(),
+        vec![
+            "nx-ffi contract violation: nx_free_buffer received a null buffer with len 3 and cap 8"
+                .to_string()
+        ]
+    );
+}
+
+#[cfg(debug_assertions)]
+mod debug_registry {
+    use super::*;
+    use nx_ffi::nx_eval_program_artifact;
+
+    fn eval_json_buffer(source: &str) -> NxBuffer {
+        let mut out = empty_buffer();
+        let status = nx_eval_source(
+            source.as_ptr(),
+            source.len(),
+            std::ptr::null(),
+            0,
+            1,
+            &mut out,
+        );
+        assert!(matches!(status, NxEvalStatus::Ok));
+        out
+    }
+
+    #[test]
+    fn ffi_debug_registry_reports_double_and_tampered_buffer_frees() {
+        let captured = CapturedViolations::start();
+        let out = eval_json_buffer("let root() = { \"twice\" }");
+        let copy = NxBuffer { ..out };
+        nx_free_buffer(out);
+        nx_free_buffer(copy);
+
+        let out = eval_json_buffer("let root() = { \"tampered\" }");
+        let copy = NxBuffer { ..out };
+        nx_free_buffer(NxBuffer {
+            len: out.len - 1,
+            ..out
+        });
+        nx_free_buffer(copy);
+
+        let violations = captured.take();
+        assert_eq!(violations.len(), 2, "{violations:?}");
+        assert!(violations[0].contains("already freed or was not returned by nx-ffi"));
+        assert!(violations[1].contains("but it was returned with len"));
+    }
+
+    #[test]
+    fn ffi_debug_registry_rejects_freed_and_mistyped_handles() {
+        let captured = CapturedViolations::start();
+        let (registry, build_context) = create_build_context();
+
+        let mut out = empty_buffer();
+        let status = nx_eval_program_artifact(build_context.cast_const().cast(), 1, &mut out);
+        assert!(matches!(status, NxEvalStatus::InvalidArgument));
+
+        nx_free_library_registry(registry);
+        nx_free_library_registry(registry);
+        let mut context_from_freed_registry = std::ptr::null_mut();
+        let status = nx_create_program_build_context(registry, &mut context_from_freed_registry);
+        assert!(matches!(status, NxEvalStatus::InvalidArgument));
+        assert!(context_from_freed_registry.is_null());
+
+        nx_free_program_build_context(build_context);
+
+        let violations = captured.take();
+        assert_eq!(violations.len(), 3, "{violations:?}");
+        assert!(violations[0].contains(
+            "expected a program artifact handle but received a program build context handle"
+        ));
+        assert!(violations[1].contains("library registry handle"));
+        assert!(violations[1].contains("was already freed or was not created by nx-ffi"));
+        assert!(violations[2].contains("was already freed or was not created by nx-ffi"));
+    }
+}
@@ -2,21 +2,25 @@ use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine;
 use nx_api::{
     load_program_artifact_from_source, ComponentDispatchResult, ComponentInitResult, NxDiagnostic,
-    ProgramBuildContext,
+    NxEnvelope, NxEnvelopeKind, ProgramBuildContext,
 };
 use nx_ffi::{
     nx_build_program_artifact, nx_build_workspace_program_artifact,
     nx_component_dispatch_actions_program_artifact, nx_component_evaluate_program_artifact,
     nx_component_init_program_artifact, nx_create_library_registry,
-    nx_create_program_build_context, nx_eval_program_artifact, nx_eval_source, nx_ffi_abi_version,
-    nx_free_buffer, nx_free_library_registry, nx_free_program_artifact,
-    nx_free_program_build_context, nx_load_library_into_registry, nx_validate_workspace, NxBuffer,
-    NxEvalStatus, NxLibraryRegistryHandle, NxOutputFormat, NxProgramArtifactHandle,
-    NxProgramBuildContextHandle, NxWorkspaceModule, NX_FFI_ABI_VERSION,
+    nx_create_program_build_context, nx_eval_program_artifact, nx_eval_source,
+    nx_eval_source_stream, nx_ffi_abi_version, nx_ffi_negotiate_envelope_version, nx_free_buffer,
+    nx_free_library_registry, nx_free_program_artifact, nx_free_program_build_context,
+    nx_load_library_into_registry, nx_read_module_metadata, nx_set_log_callback,
+    nx_validate_workspace, NxBuffer, NxEvalStatus, NxLibraryRegistryHandle, NxLogLevel,
+    NxOutputFormat, NxProgramArtifactHandle, NxProgramBuildContextHandle, NxWorkspaceModule,
+    NX_FFI_ABI_VERSION, NX_OUTPUT_ENVELOPE_SHIFT, NX_STREAM_CHUNK_SIZE,
 };
 use nx_interpreter::Interpreter;
 use nx_value::NxValue;
 use serde::Deserialize;
+use std::ffi::c_void;
+use std::sync::Mutex;
 use tempfile::TempDir;
 
 fn empty_buffer() -> NxBuffer {
@@ -427,6 +431,79 @@ fn ffi_json_success_round_trip() {
     assert_eq!(json, "42");
 }
 
+#[test]
+fn ffi_negotiated_envelope_wraps_results_and_diagnostics() {
+    let version = nx_ffi_negotiate_envelope_version(u32::MAX);
+    assert_eq!(version, 1);
+    assert_eq!(nx_ffi_negotiate_envelope_version(0), 0);
+
+    let eval = |source: &str, output_format: u32| {
+        let mut out = empty_buffer();
+        let status = nx_eval_source(
+            source.as_ptr(),
+            source.len(),
+            b"test.nx".as_ptr(),
+            "test.nx".len(),
+            output_format,
+            &mut out as *mut NxBuffer,
+        );
+        (status, copy_and_free_buffer(out))
+    };
+    let json = output_format_value(NxOutputFormat::Json) | (version << NX_OUTPUT_ENVELOPE_SHIFT);
+    let msgpack =
+        output_format_value(NxOutputFormat::MessagePack) | (version << NX_OUTPUT_ENVELOPE_SHIFT);
+
+    let (status, bytes) = eval("let root() = { 42 }", json);
+    assert!(matches!(status, NxEvalStatus::Ok));
+    assert_eq!(String::from_utf8(bytes).unwrap(), r#"{"$nx":1,"value":42}"#);
+
+    let (status, bytes) = eval("let root() = { 42 }", msgpack);
+    assert!(matches!(status, NxEvalStatus::Ok));
+    let envelope: NxEnvelope<NxValue> = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(envelope, NxEnvelope::value(1, NxValue::Int(42)));
+
+    let (status, bytes) = eval("let helper() = { 1 }", json);
+    assert!(matches!(status, NxEvalStatus::Error));
+    let envelope: NxEnvelope<Vec<NxDiagnostic>> = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(envelope.kind, NxEnvelopeKind::Diagnostics);
+    assert_eq!(envelope.payload[0].code.as_deref(), Some("no-root"));
+
+    let (status, bytes) = eval(
+        "let root() = { 42 }",
+        output_format_value(NxOutputFormat::Json) | (2 << NX_OUTPUT_ENVELOPE_SHIFT),
+    );
+    assert!(matches!(status, NxEvalStatus::InvalidArgument));
+    assert!(bytes.is_empty());
+}
+
+#[test]
+fn ffi_read_module_metadata_returns_json_record_or_null() {
+    let read_json = |source: &str| {
+        let file_name = "template.nx";
+        let mut out = empty_buffer();
+        let status = nx_read_module_metadata(
+            source.as_ptr(),
+            source.len(),
+            file_name.as_ptr(),
+            file_name.len(),
+            output_format_value(NxOutputFormat::Json),
+            &mut out as *mut NxBuffer,
+        );
+        (
+            status,
+            String::from_utf8(copy_and_free_buffer(out)).unwrap(),
+        )
+    };
+
+    let (status, json) = read_json(r#"meta { title: "Invoice", version: 2 } let root() = <p />"#);
+    assert!(matches!(status, NxEvalStatus::Ok));
+    assert_eq!(json, r#"{"title":"Invoice","version":2}"#);
+
+    let (status, json) = read_json("let root() = <p />");
+    assert!(matches!(status, NxEvalStatus::Ok));
+    assert_eq!(json, "null");
+}
+
 #[test]
 fn ffi_msgpack_enum_value_emits_bare_authored_member_string() {
     let (status, bytes) = eval_msgpack(
@@ -505,6 +582,117 @@ let root() = { answer() }"#;
     );
 }
 
+type LoggedCall = (u32, String, Option<String>);
+
+extern "C" fn record_log_call(
+    user_data: *mut c_void,
+    level: NxLogLevel,
+    message_ptr: *const u8,
+    message_len: usize,
+    value_json_ptr: *const u8,
+    value_json_len: usize,
+) {
+    let calls = unsafe { &*user_data.cast::<Mutex<Vec<LoggedCall>>>() };
+    let text = |ptr: *const u8, len: usize| {
+        String::from_utf8(unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec()).unwrap()
+    };
+    let value = (!value_json_ptr.is_null()).then(|| text(value_json_ptr, value_json_len));
+    calls
+        .lock()
+        .unwrap()
+        .push((level as u32, text(message_ptr, message_len), value));
+}
+
+#[test]
+fn ffi_log_callback_receives_calls_at_or_above_the_minimum_level() {
+    let calls = Box::new(Mutex::new(Vec::<LoggedCall>::new()));
+    let user_data = (&*calls as *const Mutex<Vec<LoggedCall>>)
+        .cast_mut()
+        .cast::<c_void>();
+    assert!(matches!(
+        nx_set_log_callback(Some(record_log_call), user_data, 7),
+        NxEvalStatus::InvalidArgument
+    ));
+    assert!(matches!(
+        nx_set_log_callback(Some(record_log_call), user_data, NxLogLevel::Info as u32),
+        NxEvalStatus::Ok
+    ));
+
+    let (status, json) = eval_json(
+        r#"let traced(n:int) = <trace>{log("debug", "hidden")}{log("info", "n", n)}{log("error", "done")}</trace>
+let root() = { traced(3) }"#,
+    );
+    nx_set_log_callback(None, std::ptr::null_mut(), 0);
+
+    assert!(matches!(status, NxEvalStatus::Ok), "{json}");
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            (
+                NxLogLevel::Info as u32,
+                "n".to_string(),
+                Some("3".to_string())
+            ),
+            (NxLogLevel::Error as u32, "done".to_string(), None),
+        ]
+    );
+}
+
+extern "C" fn record_stream_chunk(user_data: *mut c_void, chunk_ptr: *const u8, chunk_len: usize) {
+    let chunks = unsafe { &mut *user_data.cast::<Vec<Vec<u8>>>() };
+    chunks.push(unsafe { std::slice::from_raw_parts(chunk_ptr, chunk_len) }.to_vec());
+}
+
+fn eval_stream(source: &str) -> (NxEvalStatus, Vec<Vec<u8>>) {
+    let mut chunks = Vec::<Vec<u8>>::new();
+    let status = nx_eval_source_stream(
+        source.as_ptr(),
+        source.len(),
+        b"stream.nx".as_ptr(),
+        9,
+        Some(record_stream_chunk),
+        (&mut chunks as *mut Vec<Vec<u8>>).cast::<c_void>(),
+    );
+    (status, chunks)
+}
+
+#[test]
+fn ffi_eval_source_stream_splits_large_results_into_chunks() {
+    let source = r#"let root() = <doc body={padLeft("", 200000)} tail={padRight("end", 8)} />"#;
+    let (status, chunks) = eval_stream(source);
+    assert!(matches!(status, NxEvalStatus::Ok));
+    assert_eq!(chunks.len(), 4);
+    assert!(chunks[..3]
+        .iter()
+        .all(|chunk| chunk.len() == NX_STREAM_CHUNK_SIZE));
+
+    let (buffer_status, buffer_bytes) = eval_msgpack(source);
+    assert!(matches!(buffer_status, NxEvalStatus::Ok));
+    assert_eq!(chunks.concat(), buffer_bytes);
+}
+
+#[test]
+fn ffi_eval_source_stream_streams_diagnostics_and_rejects_null_callback() {
+    let (status, chunks) = eval_stream("let root() = { missing }");
+    assert!(matches!(status, NxEvalStatus::Error));
+    let diagnostics: Vec<NxDiagnostic> = rmp_serde::from_slice(&chunks.concat()).unwrap();
+    assert!(!diagnostics.is_empty());
+
+    let (status, chunks) = eval_stream("let root() = { 1 }");
+    assert!(matches!(status, NxEvalStatus::Ok));
+    assert_eq!(chunks, vec![NxValue::Int(1).to_msgpack_vec().unwrap()]);
+
+    let status = nx_eval_source_stream(
+        std::ptr::null(),
+        0,
+        std::ptr::null(),
+        0,
+        None,
+        std::ptr::null_mut(),
+    );
+    assert!(matches!(status, NxEvalStatus::InvalidArgument));
+}
+
 #[test]
 fn ffi_eval_program_artifact_returns_json_success_directly() {
     let build_context = create_empty_build_context();
@@ -0,0 +1,532 @@
+//! Drives the nx-ffi surface from arbitrary bytes.
+//!
+//! Shared by `tests/ffi_fuzz.rs`, which replays a fixed pseudo-random corpus on every test run, and
+//! `fuzz/fuzz_targets/ffi_surface.rs`, which lets libFuzzer search for new inputs. An input is read
+//! as a sequence of operations that call entry points with null pointers, truncated or oversized
+//! lengths, invalid UTF-8, unknown output formats, and garbage MessagePack, and that free buffers
+//! and handles. Debug builds also pass back stale, mistyped, and tampered buffers and handles,
+//! which the contract registry must reject. Pointers are never wild: nothing can validate a pointer
+//! into unmapped memory.
+//!
+//! [`drive`] panics when an entry point returns `NxEvalStatus::Panic` or breaks a buffer contract.
+
+use nx_ffi::{
+    nx_build_program_artifact, nx_build_workspace_program_artifact,
+    nx_component_dispatch_actions_program_artifact, nx_component_evaluate_program_artifact,
+    nx_component_init_program_artifact, nx_create_library_registry,
+    nx_create_program_build_context, nx_eval_program_artifact, nx_eval_source,
+    nx_eval_source_stream, nx_free_buffer, nx_free_library_registry, nx_free_program_artifact,
+    nx_free_program_build_context, nx_read_module_metadata, nx_validate_workspace, NxBuffer,
+    NxEvalStatus, NxLibraryRegistryHandle, NxProgramArtifactHandle, NxProgramBuildContextHandle,
+    NxWorkspaceModule,
+};
+
+/// Sources the driver starts from before mutating them.
+const SOURCES: &[&str] = &[
+    "let root() = { 1 + 2 }",
+    "let root() = <ul><li>one</li><li>two</li></ul>",
+    r#"meta { title: "Fuzz" } let root() = { "meta" }"#,
+    r#"action Saved = { id:int }
+component <Editor title:string = "Draft" emits { Saved } /> = {
+  state { text:string = {title} }
+  <TextInput value={text} />
+}"#,
+    "let root(): int = { \"oops\" }",
+    "let broken( = ",
+];
+
+const COMPONENTS: &[&str] = &["Editor", "Missing", ""];
+
+/// Runs every operation encoded in `data`, then frees everything it created.
+pub fn drive(data: &[u8]) {
+    let mut input = Input { data, pos: 0 };
+    let mut state = State::new();
+    while !input.is_empty() {
+        match input.byte() % 11 {
+            0 => state.eval_source(&mut input),
+            10 => state.eval_source_stream(&mut input),
+            1 => state.read_module_metadata(&mut input),
+            2 => state.build_program_artifact(&mut input),
+            3 => state.eval_program_artifact(&mut input),
+            4 => state.component_init(&mut input),
+            5 => state.component_evaluate(&mut input),
+            6 => state.component_dispatch(&mut input),
+            7 => state.workspace(&mut input),
+            8 => state.free_program_artifact(&mut input),
+            _ => state.misuse_buffer(&mut input),
+        }
+    }
+}
+
+struct Input<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Input<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn byte(&mut self) -> u8 {
+        let byte = self.data.get(self.pos).copied().unwrap_or(0);
+        self.pos += 1;
+        byte
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes([self.byte(), self.byte(), self.byte(), self.byte()])
+    }
+
+    fn bytes(&mut self) -> &'a [u8] {
+        let len = usize::from(self.byte() % 32);
+        let start = self.pos.min(self.data.len());
+        let end = (start + len).min(self.data.len());
+        self.pos = end;
+        &self.data[start..end]
+    }
+
+    /// A source from [`SOURCES`], possibly cut short or followed by input bytes.
+    fn source(&mut self) -> Vec<u8> {
+        let mut source = SOURCES[usize::from(self.byte()) % SOURCES.len()]
+            .as_bytes()
+            .to_vec();
+        match self.byte() % 4 {
+            0 => {}
+            1 => source.truncate(usize::from(self.byte()) % (source.len() + 1)),
+            2 => source.extend_from_slice(self.bytes()),
+            _ => source.extend(self.bytes().iter().map(|byte| b' ' + byte % 95)),
+        }
+        source
+    }
+
+    /// MessagePack-ish bytes: empty, a small valid map or array, or raw input bytes.
+    fn payload(&mut self) -> Vec<u8> {
+        match self.byte() % 4 {
+            1 => vec![0x81, 0xa5, b't', b'i', b't', b'l', b'e', 0xa2, b'o', b'k'],
+            2 => vec![0x91, 0x80],
+            3 => self.bytes().to_vec(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A pointer and length describing `bytes`, possibly made null, shortened, or oversized.
+fn slice_arg(input: &mut Input<'_>, bytes: &[u8]) -> (*const u8, usize) {
+    let ptr = if input.byte() % 8 == 7 {
+        std::ptr::null()
+    } else {
+        bytes.as_ptr()
+    };
+    let len = match input.byte() % 16 {
+        12 => 0,
+        13 => bytes.len() / 2,
+        14 => usize::MAX,
+        15 => isize::MAX as usize + 1,
+        _ => bytes.len(),
+    };
+    // A non-null pointer with a length past the end would be a wild read, so only lengths the
+    // entry points must reject before reading may exceed the slice.
+    if !ptr.is_null() && len > bytes.len() && len <= isize::MAX as usize {
+        return (ptr, bytes.len());
+    }
+    (ptr, len)
+}
+
+fn output_format(input: &mut Input<'_>) -> u32 {
+    match input.byte() % 8 {
+        6 => 1 | (1 << nx_ffi::NX_OUTPUT_ENVELOPE_SHIFT),
+        7 => input.u32(),
+        choice => u32::from(choice % 2),
+    }
+}
+
+/// Calls `f` with an output buffer, or with null when the input asks for it, and frees the result.
+fn with_out_buffer(input: &mut Input<'_>, f: impl FnOnce(*mut NxBuffer) -> NxEvalStatus) {
+    let null_out = input.byte() % 16 == 15;
+    let mut out = NxBuffer {
+        ptr: std::ptr::null_mut(),
+        len: 0,
+        cap: 0,
+    };
+    let status = f(if null_out {
+        std::ptr::null_mut()
+    } else {
+        &mut out
+    });
+    check_output(status, &out, null_out);
+    nx_free_buffer(out);
+}
+
+fn check_output(status: NxEvalStatus, out: &NxBuffer, null_out: bool) {
+    assert!(
+        !matches!(status, NxEvalStatus::Panic),
+        "entry point panicked"
+    );
+    if null_out {
+        assert!(matches!(status, NxEvalStatus::InvalidArgument));
+    }
+    assert!(out.len <= out.cap, "returned buffer has len > cap");
+    if out.ptr.is_null() {
+        assert_eq!((out.len, out.cap), (0, 0), "null buffer with a length");
+    }
+    if matches!(status, NxEvalStatus::InvalidArgument) {
+        assert!(out.ptr.is_null(), "rejected call returned a buffer");
+    }
+}
+
+extern "C" fn count_chunk(user_data: *mut std::ffi::c_void, _chunk_ptr: *const u8, len: usize) {
+    assert!(len > 0, "empty stream chunk");
+    unsafe { *user_data.cast::<usize>() += len };
+}
+
+struct State {
+    registry: *mut NxLibraryRegistryHandle,
+    build_context: *mut NxProgramBuildContextHandle,
+    artifacts: Vec<*mut NxProgramArtifactHandle>,
+    freed_artifacts: Vec<*mut NxProgramArtifactHandle>,
+}
+
+impl State {
+    fn new() -> Self {
+        let mut registry = std::ptr::null_mut();
+        assert!(matches!(
+            nx_create_library_registry(&mut registry),
+            NxEvalStatus::Ok
+        ));
+        let mut build_context = std::ptr::null_mut();
+        assert!(matches!(
+            nx_create_program_build_context(registry, &mut build_context),
+            NxEvalStatus::Ok
+        ));
+        let mut state = Self {
+            registry,
+            build_context,
+            artifacts: Vec::new(),
+            freed_artifacts: Vec::new(),
+        };
+        // Start with a component artifact so the artifact entry points get past argument checks.
+        let source = SOURCES[3];
+        let mut handle = std::ptr::null_mut();
+        let mut out = NxBuffer {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+            cap: 0,
+        };
+        let status = nx_build_program_artifact(
+            build_context,
+            source.as_ptr(),
+            source.len(),
+            std::ptr::null(),
+            0,
+            &mut handle,
+            &mut out,
+        );
+        assert!(matches!(status, NxEvalStatus::Ok) && !handle.is_null());
+        nx_free_buffer(out);
+        state.artifacts.push(handle);
+        state
+    }
+
+    /// A build context handle: the live one, null, or in debug builds a handle of another kind.
+    fn build_context_arg(&self, input: &mut Input<'_>) -> *const NxProgramBuildContextHandle {
+        match input.byte() % 8 {
+            6 => std::ptr::null(),
+            7 if cfg!(debug_assertions) => self.registry.cast_const().cast(),
+            _ => self.build_context,
+        }
+    }
+
+    /// An artifact handle: a live one, null, or in debug builds a freed or mistyped handle.
+    fn artifact_arg(&self, input: &mut Input<'_>) -> *const NxProgramArtifactHandle {
+        let choice = input.byte();
+        match choice % 8 {
+            5 => std::ptr::null(),
+            6 if cfg!(debug_assertions) && !self.freed_artifacts.is_empty() => {
+                self.freed_artifacts[usize::from(choice) % self.freed_artifacts.len()]
+            }
+            7 if cfg!(debug_assertions) => self.build_context.cast_const().cast(),
+            _ if self.artifacts.is_empty() => std::ptr::null(),
+            _ => self.artifacts[usize::from(choice) % self.artifacts.len()],
+        }
+    }
+
+    fn eval_source(&mut self, input: &mut Input<'_>) {
+        let source = input.source();
+        let (source_ptr, source_len) = slice_arg(input, &source);
+        let file_name = input.bytes();
+        let (file_name_ptr, file_name_len) = slice_arg(input, file_name);
+        let output_format = output_format(input);
+        with_out_buffer(input, |out| {
+            nx_eval_source(
+                source_ptr,
+                source_len,
+                file_name_ptr,
+                file_name_len,
+                output_format,
+                out,
+            )
+        });
+    }
+
+    fn eval_source_stream(&mut self, input: &mut Input<'_>) {
+        let source = input.source();
+        let (source_ptr, source_len) = slice_arg(input, &source);
+        let mut streamed = 0usize;
+        let callback = (input.byte() % 16 != 15).then_some(count_chunk as _);
+        let status = nx_eval_source_stream(
+            source_ptr,
+            source_len,
+            std::ptr::null(),
+            0,
+            callback,
+            (&mut streamed as *mut usize).cast(),
+        );
+        assert!(
+            !matches!(status, NxEvalStatus::Panic),
+            "entry point panicked"
+        );
+        if callback.is_none() {
+            assert!(matches!(status, NxEvalStatus::InvalidArgument));
+            assert_eq!(streamed, 0);
+        }
+    }
+
+    fn read_module_metadata(&mut self, input: &mut Input<'_>) {
+        let source = input.source();
+        let (source_ptr, source_len) = slice_arg(input, &source);
+        let output_format = output_format(input);
+        with_out_buffer(input, |out| {
+            nx_read_module_metadata(
+                source_ptr,
+                source_len,
+                std::ptr::null(),
+                0,
+                output_format,
+                out,
+            )
+        });
+    }
+
+    fn build_program_artifact(&mut self, input: &mut Input<'_>) {
+        let build_context = self.build_context_arg(input);
+        let source = input.source();
+        let (source_ptr, source_len) = slice_arg(input, &source);
+        let mut handle = std::ptr::null_mut();
+        with_out_buffer(input, |out| {
+            nx_build_program_artifact(
+                build_context,
+                source_ptr,
+                source_len,
+                b"fuzz.nx".as_ptr(),
+                7,
+                &mut handle,
+                out,
+            )
+        });
+        if !handle.is_null() {
+            self.artifacts.push(handle);
+        }
+    }
+
+    fn eval_program_artifact(&mut self, input: &mut Input<'_>) {
+        let artifact = self.artifact_arg(input);
+        let output_format = output_format(input);
+        with_out_buffer(input, |out| {
+            nx_eval_program_artifact(artifact, output_format, out)
+        });
+    }
+
+    fn component_init(&mut self, input: &mut Input<'_>) {
+        let artifact = self.artifact_arg(input);
+        let name = COMPONENTS[usize::from(input.byte()) % COMPONENTS.len()].as_bytes();
+        let (name_ptr, name_len) = slice_arg(input, name);
+        let props = input.payload();
+        let (props_ptr, props_len) = slice_arg(input, &props);
+        let output_format = output_format(input);
+        with_out_buffer(input, |out| {
+            nx_component_init_program_artifact(
+                artifact,
+                name_ptr,
+                name_len,
+                props_ptr,
+                props_len,
+                output_format,
+                out,
+            )
+        });
+    }
+
+    fn component_evaluate(&mut self, input: &mut Input<'_>) {
+        let artifact = self.artifact_arg(input);
+        let name = COMPONENTS[usize::from(input.byte()) % COMPONENTS.len()].as_bytes();
+        let (name_ptr, name_len) = slice_arg(input, name);
+        let props = input.payload();
+        let (props_ptr, props_len) = slice_arg(input, &props);
+        let state = input.payload();
+        let (state_ptr, state_len) = slice_arg(input, &state);
+        let output_format = output_format(input);
+        with_out_buffer(input, |out| {
+            nx_component_evaluate_program_artifact(
+                artifact,
+                name_ptr,
+                name_len,
+                props_ptr,
+                props_len,
+                state_ptr,
+                state_len,
+                output_format,
+                out,
+            )
+        });
+    }
+
+    fn component_dispatch(&mut self, input: &mut Input<'_>) {
+        let artifact = self.artifact_arg(input);
+        let snapshot = input.bytes();
+        let (snapshot_ptr, snapshot_len) = slice_arg(input, snapshot);
+        let actions = input.payload();
+        let (actions_ptr, actions_len) = slice_arg(input, &actions);
+        let output_format = output_format(input);
+        with_out_buffer(input, |out| {
+            nx_component_dispatch_actions_program_artifact(
+                artifact,
+                snapshot_ptr,
+                snapshot_len,
+                actions_ptr,
+                actions_len,
+                output_format,
+                out,
+            )
+        });
+    }
+
+    fn workspace(&mut self, input: &mut Input<'_>) {
+        let build_context = self.build_context_arg(input);
+        let sources = [input.source(), input.source()];
+        let identities = [
+            b"main.nx".as_slice(),
+            if input.byte() % 4 == 3 {
+                input.bytes()
+            } else {
+                b"lib.nx"
+            },
+        ];
+        let mut descriptors = Vec::new();
+        for (identity, source) in identities.iter().zip(&sources) {
+            let (identity_ptr, identity_len) = slice_arg(input, identity);
+            let (source_utf8_ptr, source_utf8_len) = slice_arg(input, source);
+            descriptors.push(NxWorkspaceModule {
+                identity_ptr,
+                identity_len,
+                source_utf8_ptr,
+                source_utf8_len,
+            });
+        }
+
+        // Copy the descriptors one byte past an aligned address to pass a misaligned array.
+        let misaligned = input.byte() % 8 == 7;
+        let size = std::mem::size_of_val(descriptors.as_slice());
+        let mut shifted = vec![0u64; size / 8 + 2];
+        let modules_ptr = if misaligned {
+            let start = shifted.as_mut_ptr().cast::<u8>().wrapping_add(1);
+            unsafe {
+                std::ptr::copy_nonoverlapping(descriptors.as_ptr().cast::<u8>(), start, size);
+            }
+            start.cast_const().cast::<NxWorkspaceModule>()
+        } else {
+            descriptors.as_ptr()
+        };
+        // Counts past the array are limited to ones too large for any array, which are rejected
+        // before the descriptors are read.
+        let module_count = match input.byte() % 8 {
+            6 => 0,
+            7 => usize::MAX,
+            _ => descriptors.len(),
+        };
+
+        if input.byte() % 2 == 0 {
+            with_out_buffer(input, |out| {
+                nx_validate_workspace(build_context, modules_ptr, module_count, out)
+            });
+        } else {
+            let mut handle = std::ptr::null_mut();
+            with_out_buffer(input, |out| {
+                nx_build_workspace_program_artifact(
+                    build_context,
+                    modules_ptr,
+                    module_count,
+                    b"main.nx".as_ptr(),
+                    7,
+                    &mut handle,
+                    out,
+                )
+            });
+            if !handle.is_null() {
+                self.artifacts.push(handle);
+            }
+        }
+    }
+
+    fn free_program_artifact(&mut self, input: &mut Input<'_>) {
+        let choice = usize::from(input.byte());
+        if self.artifacts.is_empty() {
+            nx_free_program_artifact(std::ptr::null_mut());
+            return;
+        }
+        let handle = self.artifacts.swap_remove(choice % self.artifacts.len());
+        nx_free_program_artifact(handle);
+        if cfg!(debug_assertions) {
+            if choice % 2 == 0 {
+                nx_free_program_artifact(handle);
+            }
+            self.freed_artifacts.push(handle);
+        }
+    }
+
+    /// Frees a returned buffer twice or with a changed `len`, which debug builds must reject.
+    fn misuse_buffer(&mut self, input: &mut Input<'_>) {
+        let mut out = NxBuffer {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+            cap: 0,
+        };
+        let source = input.source();
+        let status = nx_eval_source(
+            source.as_ptr(),
+            source.len(),
+            std::ptr::null(),
+            0,
+            1,
+            &mut out,
+        );
+        check_output(status, &out, false);
+        if !cfg!(debug_assertions) {
+            nx_free_buffer(out);
+            return;
+        }
+
+        let copy = NxBuffer { ..out };
+        if input.byte() % 2 == 0 {
+            nx_free_buffer(out);
+            nx_free_buffer(copy);
+        } else {
+            let cap = out.cap;
+            nx_free_buffer(NxBuffer {
+                len: cap.wrapping_add(1),
+                ..out
+            });
+            nx_free_buffer(copy);
+        }
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        for handle in self.artifacts.drain(..) {
+            nx_free_program_artifact(handle);
+        }
+        nx_free_program_build_context(self.build_context);
+        nx_free_library_registry(self.registry);
+    }
+}
@@ -0,0 +1,109 @@
+//! Source formatter for the NX language.
+//!
+//! [`format_source`] reprints an NX file from its concrete syntax tree. It rewrites only the
+//! whitespace between tokens, so every token, comment included, is kept:
+//!
+//! - Lines are indented one level per enclosing bracket or element, with closing brackets and
+//!   tags aligned with the line that opened them. Attributes on their own lines, and lines that
+//!   continue a definition or expression from the line before, are indented one extra level.
+//! - Runs of spaces become a single space, with spaces added after commas and around binary
+//!   operators and the `=` of definitions. Spaces before commas are removed.
+//! - Trailing whitespace is removed, consecutive blank lines collapse to one, and the file ends
+//!   with a single newline.
+//!
+//! Line breaks are kept where the author put them; the formatter does not wrap or join lines.
+//! [`format_range`] applies the same rules to only the lines an edit touched, for editors.
+//!
+//! Element text is part of the value an element evaluates to, including the whitespace at its
+//! end, so text runs, text elements such as `<p:>`, and raw elements are printed verbatim, as are
+//! strings and block comments. Line comments lose their trailing whitespace.
+//!
+//! # Examples
+//!
+//! ```
+//! use nx_fmt::{format_source, FormatOptions};
+//!
+//! let source = "let double(n:int): int = {\nn*2\n}\nlet   xs = [1,2]\n";
+//! let formatted = format_source(source, "example.nx", &FormatOptions::default()).unwrap();
+//! assert_eq!(formatted, "let double(n:int): int = {\n  n * 2\n}\nlet xs = [1, 2]\n");
+//! ```
+
+mod printer;
+mod range;
+
+use nx_diagnostics::Diagnostic;
+use printer::Printed;
+
+pub use range::format_range;
+
+/// Version of the nx-fmt crate, identical to the toolchain version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Layout settings for [`format_source`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Number of spaces per indentation level.
+    pub indent_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self { indent_width: 2 }
+    }
+}
+
+/// Errors that prevent a source from being formatted.
+#[derive(Debug, thiserror::Error)]
+pub enum FormatError {
+    /// The source does not parse. Formatting needs a complete tree, so the diagnostics are
+    /// returned for the caller to report.
+    #[error("the source has syntax errors")]
+    Syntax(Vec<Diagnostic>),
+
+    /// The formatted text would not parse to the same tokens as the source. This is a bug in the
+    /// formatter; the source is left as it is.
+    #[error("formatting would change the source at byte {offset}")]
+    Unstable { offset: usize },
+}
+
+/// Formats `source`, returning the formatted text.
+///
+/// `file_name` is used in the diagnostics of [`FormatError::Syntax`]. A source that is already
+/// formatted is returned unchanged, and formatting the result again changes nothing.
+pub fn format_source(
+    source: &str,
+    file_name: &str,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    print_checked(source, file_name, options).map(|printed| printed.text)
+}
+
+/// Parses and prints `source`, checking that the printed text reads the same.
+fn print_checked(
+    source: &str,
+    file_name: &str,
+    options: &FormatOptions,
+) -> Result<Printed, FormatError> {
+    let parsed = nx_syntax::parse_str(source, file_name);
+    if parsed.has_errors() {
+        return Err(FormatError::Syntax(parsed.errors));
+    }
+    let Some(tree) = parsed.tree.as_ref() else {
+        return Err(FormatError::Syntax(parsed.errors));
+    };
+    let printed = printer::print(tree, options);
+
+    // The printer only changes whitespace between tokens; make sure the result still reads the
+    // same before handing it out.
+    let reparsed = nx_syntax::parse_str(&printed.text, file_name);
+    let difference = match reparsed.tree.as_ref() {
+        Some(formatted_tree) if !reparsed.has_errors() => {
+            printer::first_token_difference(tree, formatted_tree)
+        }
+        _ => Some(0),
+    };
+    if let Some(offset) = difference {
+        return Err(FormatError::Unstable { offset });
+    }
+    Ok(printed)
+}
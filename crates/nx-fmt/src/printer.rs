@@ -0,0 +1,351 @@
+//! Reprinting a syntax tree with normalized whitespace.
+
+use crate::FormatOptions;
+use nx_syntax::{SyntaxNode, SyntaxTree};
+use std::ops::Range;
+
+/// Nodes printed exactly as written. Their text, and any whitespace inside it, is significant.
+const VERBATIM_KINDS: &[&str] = &[
+    "text_run",
+    "embed_text_run",
+    "raw_text_run",
+    "text_content",
+    "embed_text_content",
+    "string_literal",
+    "line_comment",
+    "block_comment",
+    "html_block_comment",
+];
+
+/// Nodes whose children are separate items, such as declarations or element children. A child
+/// that starts a line is indented like its siblings rather than as a continuation.
+const ITEM_LIST_KINDS: &[&str] = &[
+    "module_definition",
+    "mixed_content",
+    "content",
+    "elements_expression",
+    "property_list",
+    "component_body",
+];
+
+/// Nodes whose `=` token is spaced like a definition, as in `let x = 1`.
+const DEFINITION_KINDS: &[&str] = &[
+    "value_definition",
+    "function_definition",
+    "component_definition",
+    "type_definition",
+    "record_definition",
+    "action_definition",
+    "union_definition",
+    "enum_definition",
+];
+
+/// Nodes that may hold an element tag: elements, and the signatures of markup functions and
+/// components.
+const TAG_KINDS: &[&str] = &["element", "function_definition", "component_signature"];
+
+/// A token of the tree: a leaf, or a node printed verbatim.
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    node: SyntaxNode<'a>,
+    text: &'a str,
+    /// Whether this is a punctuation or keyword token rather than a named node.
+    anonymous: bool,
+}
+
+impl Token<'_> {
+    fn is(&self, text: &str) -> bool {
+        self.anonymous && self.text == text
+    }
+
+    fn parent_kind(&self) -> &'static str {
+        self.node.raw().parent().map_or("", |parent| parent.kind())
+    }
+
+    fn is_line_comment(&self) -> bool {
+        self.node.raw().kind() == "line_comment"
+    }
+
+    /// Returns the text to print. Line comments drop trailing whitespace.
+    fn printed_text(&self) -> &str {
+        if self.is_line_comment() {
+            self.text.trim_end()
+        } else {
+            self.text
+        }
+    }
+
+    /// Binary operators, including the `&` between the parts of a composed record.
+    fn is_binary_operator(&self) -> bool {
+        self.anonymous
+            && (self.parent_kind() == "binary_expression"
+                || (self.text == "&" && self.parent_kind() == "record_composition"))
+    }
+
+    /// The `@` that starts a field constraint, as in `age:int @min(0)`.
+    fn starts_field_constraint(&self) -> bool {
+        self.is("@") && self.parent_kind() == "field_constraint"
+    }
+
+    fn is_definition_equals(&self) -> bool {
+        self.is("=") && DEFINITION_KINDS.contains(&self.parent_kind())
+    }
+
+    fn is_tag_punctuation(&self, text: &str) -> bool {
+        self.is(text) && TAG_KINDS.contains(&self.parent_kind())
+    }
+
+    fn closes_bracket(&self) -> bool {
+        self.is("}") || self.is(")") || self.is("]")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameKind {
+    Brace,
+    Paren,
+    Bracket,
+    /// Inside a tag, between `<` and `>` or `/>`.
+    Tag {
+        closing: bool,
+    },
+    /// Between an element's opening and closing tags.
+    Element,
+}
+
+/// A bracket, tag or element that is open at the current token.
+struct Frame {
+    kind: FrameKind,
+    /// Indentation level of the line the frame opened on.
+    level: usize,
+    /// Byte offset of the token that opened the frame.
+    opener: usize,
+}
+
+/// The text printed for a tree, with where each of its tokens was printed.
+pub(crate) struct Printed {
+    pub(crate) text: String,
+    /// The tokens of the tree in source order.
+    pub(crate) tokens: Vec<PrintedToken>,
+}
+
+/// A token's byte range in the source and the offset it was printed at.
+pub(crate) struct PrintedToken {
+    pub(crate) source: Range<usize>,
+    pub(crate) printed: usize,
+}
+
+/// Prints `tree` with normalized whitespace.
+pub(crate) fn print(tree: &SyntaxTree, options: &FormatOptions) -> Printed {
+    let source = tree.source();
+    let newline = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let tokens = tokens(tree.root());
+    let mut output = String::new();
+    let mut printed = Vec::with_capacity(tokens.len());
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut level = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        let next = tokens.get(index + 1);
+        if let Some(previous) = index.checked_sub(1).map(|previous| &tokens[previous]) {
+            let gap = &source[previous.node.end_byte()..token.node.start_byte()];
+            let newlines = gap.matches('\n').count();
+            if newlines > 0 {
+                output.push_str(newline);
+                if newlines > 1 {
+                    output.push_str(newline);
+                }
+                level = line_level(token, next, &frames);
+                output.push_str(&" ".repeat(level * options.indent_width));
+            } else {
+                output.push_str(spacing(previous, token, !gap.is_empty()));
+            }
+        }
+
+        let previous = index.checked_sub(1).map(|previous| &tokens[previous]);
+        update_frames(&mut frames, token, previous, next, level);
+        printed.push(PrintedToken {
+            source: token.node.start_byte()..token.node.end_byte(),
+            printed: output.len(),
+        });
+        output.push_str(token.printed_text());
+    }
+
+    if !output.is_empty() {
+        output.push_str(newline);
+    }
+    Printed {
+        text: output,
+        tokens: printed,
+    }
+}
+
+/// Returns the whitespace to print between two tokens on the same line. `spaced` tells whether
+/// the source had whitespace between them.
+fn spacing(previous: &Token, token: &Token, spaced: bool) -> &'static str {
+    let required = (previous.is(",") && !token.closes_bracket())
+        || token.is_binary_operator()
+        || previous.is_binary_operator()
+        || token.is_definition_equals()
+        || previous.is_definition_equals()
+        || token.starts_field_constraint();
+    if token.is(",") || !(required || spaced) {
+        ""
+    } else {
+        " "
+    }
+}
+
+/// Returns the indentation level of a line that starts with `token`, followed by `next`.
+fn line_level(token: &Token, next: Option<&Token>, frames: &[Frame]) -> usize {
+    let Some(frame) = frames.last() else {
+        return usize::from(continues_item(token, None));
+    };
+    match frame.kind {
+        FrameKind::Tag { .. } if token.is(">") || token.is("/") => frame.level,
+        FrameKind::Tag { .. } => frame.level + 1,
+        FrameKind::Element if starts_closing_tag(token, next) => frame.level,
+        FrameKind::Brace | FrameKind::Paren | FrameKind::Bracket if token.closes_bracket() => {
+            frame.level
+        }
+        _ => frame.level + 1 + usize::from(continues_item(token, Some(frame))),
+    }
+}
+
+/// Returns true when a line starting with `token` continues a construct begun on an earlier
+/// line within the same frame, such as the second operand of a binary expression or the value
+/// of a definition.
+fn continues_item(token: &Token, frame: Option<&Frame>) -> bool {
+    if token.is("else") {
+        return false;
+    }
+    // The largest node that starts at the token is the item the line begins.
+    let start = token.node.start_byte();
+    let mut item = token.node.raw();
+    while let Some(parent) = item.parent() {
+        if parent.start_byte() != start {
+            break;
+        }
+        item = parent;
+    }
+    let Some(parent) = item.parent() else {
+        return false;
+    };
+    !ITEM_LIST_KINDS.contains(&parent.kind())
+        && frame.is_none_or(|frame| parent.start_byte() > frame.opener)
+}
+
+fn starts_closing_tag(token: &Token, next: Option<&Token>) -> bool {
+    token.is_tag_punctuation("<") && next.is_some_and(|next| next.is("/"))
+}
+
+/// Opens or closes the frame that `token` starts or ends. `level` is the indentation level of
+/// the token's line.
+fn update_frames(
+    frames: &mut Vec<Frame>,
+    token: &Token,
+    previous: Option<&Token>,
+    next: Option<&Token>,
+    level: usize,
+) {
+    let opener = token.node.start_byte();
+    let top = frames.last().map(|frame| frame.kind);
+    let opened = if token.is("{") || token.is("@{") {
+        Some(FrameKind::Brace)
+    } else if token.is("(") {
+        Some(FrameKind::Paren)
+    } else if token.is("[") {
+        Some(FrameKind::Bracket)
+    } else if starts_closing_tag(token, next) {
+        if top == Some(FrameKind::Element) {
+            frames.pop();
+        }
+        Some(FrameKind::Tag { closing: true })
+    } else if token.is_tag_punctuation("<") {
+        Some(FrameKind::Tag { closing: false })
+    } else {
+        None
+    };
+    if let Some(kind) = opened {
+        frames.push(Frame {
+            kind,
+            level,
+            opener,
+        });
+        return;
+    }
+
+    let closes = match top {
+        Some(FrameKind::Brace) => token.is("}"),
+        Some(FrameKind::Paren) => token.is(")"),
+        Some(FrameKind::Bracket) => token.is("]"),
+        Some(FrameKind::Tag { .. }) => token.is_tag_punctuation(">"),
+        _ => false,
+    };
+    if !closes {
+        return;
+    }
+    let Some(frame) = frames.pop() else {
+        return;
+    };
+    // A tag that is neither closing nor self-closing opens the element's content.
+    let self_closing = previous.is_some_and(|previous| previous.is_tag_punctuation("/"));
+    if frame.kind == (FrameKind::Tag { closing: false })
+        && !self_closing
+        && token.parent_kind() == "element"
+    {
+        frames.push(Frame {
+            kind: FrameKind::Element,
+            level: frame.level,
+            opener: frame.opener,
+        });
+    }
+}
+
+/// Returns the first byte offset, in the formatted text, where the tokens of `original` and
+/// `formatted` differ, or `None` when they match.
+pub(crate) fn first_token_difference(
+    original: &SyntaxTree,
+    formatted: &SyntaxTree,
+) -> Option<usize> {
+    let original = tokens(original.root());
+    let formatted = tokens(formatted.root());
+    for index in 0..original.len().max(formatted.len()) {
+        match (original.get(index), formatted.get(index)) {
+            (Some(left), Some(right))
+                if left.anonymous == right.anonymous
+                    && left.node.raw().kind() == right.node.raw().kind()
+                    && left.printed_text() == right.printed_text() => {}
+            (_, Some(right)) => return Some(right.node.start_byte()),
+            (_, None) => return Some(formatted.last().map_or(0, |last| last.node.end_byte())),
+        }
+    }
+    None
+}
+
+fn tokens(root: SyntaxNode) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    collect_tokens(root, &mut tokens);
+    tokens
+}
+
+fn collect_tokens<'a>(node: SyntaxNode<'a>, tokens: &mut Vec<Token<'a>>) {
+    let raw = node.raw();
+    if node.child_count() == 0 || VERBATIM_KINDS.contains(&raw.kind()) {
+        if node.start_byte() < node.end_byte() {
+            tokens.push(Token {
+                node,
+                text: node.text(),
+                anonymous: !raw.is_named(),
+            });
+        }
+        return;
+    }
+    for child in node.children_with_tokens() {
+        collect_tokens(child, tokens);
+    }
+}
@@ -0,0 +1,216 @@
+//! Formatting only the lines an edit touched.
+//!
+//! The whole file is printed as [`format_source`](crate::format_source) would print it, and each
+//! touched line is replaced by the line it was printed as. The printer never joins or splits
+//! lines, so every source line that is not blank has exactly one printed line.
+
+use crate::printer::{Printed, PrintedToken};
+use crate::{print_checked, FormatError, FormatOptions};
+use nx_diagnostics::TextEdit;
+use text_size::{TextRange, TextSize};
+
+/// Returns the edits that format the lines of `source` that `range` touches.
+///
+/// The lines are formatted with the same rules as [`format_source`](crate::format_source), and
+/// every other byte of the source is left as it is, so format-on-save in a large file does not
+/// churn unrelated lines. A range that ends at the start of a line does not touch that line.
+///
+/// The edits are made to `file_name` and returned in source order. Like `format_source`, this
+/// fails when the source has syntax errors. A range outside the source or inside a UTF-8
+/// character yields no edits.
+///
+/// # Examples
+///
+/// ```
+/// use nx_fmt::{format_range, FormatOptions};
+/// use text_size::TextRange;
+///
+/// let source = "let xs = [\n  1,\n      x+y   ,\n]\nlet   g = 2\n";
+/// let line = source.find("x+y").unwrap() as u32;
+/// let range = TextRange::new(line.into(), line.into());
+/// let edits = format_range(source, "example.nx", range, &FormatOptions::default()).unwrap();
+///
+/// let mut formatted = source.to_string();
+/// for edit in edits.iter().rev() {
+///     let range = usize::from(edit.range.start())..usize::from(edit.range.end());
+///     formatted.replace_range(range, &edit.replacement);
+/// }
+/// assert_eq!(formatted, "let xs = [\n  1,\n  x + y,\n]\nlet   g = 2\n");
+/// ```
+pub fn format_range(
+    source: &str,
+    file_name: &str,
+    range: TextRange,
+    options: &FormatOptions,
+) -> Result<Vec<TextEdit>, FormatError> {
+    let start = usize::from(range.start());
+    let end = usize::from(range.end());
+    if end > source.len() || !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+        return Ok(Vec::new());
+    }
+
+    let printed = print_checked(source, file_name, options)?;
+    let lines = Lines {
+        source,
+        printed: &printed,
+    };
+    let mut edits = Vec::new();
+    let mut line = source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+    loop {
+        let line_end = lines.line_end(line);
+        lines.format_line(line, line_end, &mut |range, replacement| {
+            edits.push(TextEdit::replace(file_name, text_range(range), replacement));
+        });
+        if line_end == source.len() {
+            // The printer ends the file with a newline.
+            if !lines.is_blank(line, line_end) && !source.ends_with('\n') {
+                let newline = if printed.text.ends_with("\r\n") {
+                    "\r\n"
+                } else {
+                    "\n"
+                };
+                edits.push(TextEdit::replace(
+                    file_name,
+                    text_range(line_end..line_end),
+                    newline.to_string(),
+                ));
+            }
+            break;
+        }
+        line = line_end + 1;
+        if line >= end {
+            break;
+        }
+    }
+    Ok(edits)
+}
+
+fn text_range(range: std::ops::Range<usize>) -> TextRange {
+    TextRange::new(
+        TextSize::from(range.start as u32),
+        TextSize::from(range.end as u32),
+    )
+}
+
+struct Lines<'a> {
+    source: &'a str,
+    printed: &'a Printed,
+}
+
+impl Lines<'_> {
+    /// Returns the offset of the newline ending the line at `line`, or the end of the source.
+    fn line_end(&self, line: usize) -> usize {
+        self.source[line..]
+            .find('\n')
+            .map_or(self.source.len(), |newline| line + newline)
+    }
+
+    /// Returns the first token that ends after `offset`.
+    fn token_after(&self, offset: usize) -> Option<&PrintedToken> {
+        let tokens = &self.printed.tokens;
+        tokens.get(tokens.partition_point(|token| token.source.end <= offset))
+    }
+
+    /// Returns true when no token is on the line `line..line_end`, which then holds only
+    /// whitespace between tokens.
+    fn is_blank(&self, line: usize, line_end: usize) -> bool {
+        self.token_after(line)
+            .is_none_or(|token| token.source.start >= line_end)
+    }
+
+    /// Passes the edits that turn the line `line..line_end` into its printed form to `edit`.
+    fn format_line(
+        &self,
+        line: usize,
+        line_end: usize,
+        edit: &mut dyn FnMut(std::ops::Range<usize>, String),
+    ) {
+        let source = self.source;
+        let content_end = line_end - usize::from(source[line..line_end].ends_with('\r'));
+        let Some(token) = self
+            .token_after(line)
+            .filter(|token| token.source.start < line_end)
+        else {
+            self.format_blank_line(line, line_end, content_end, edit);
+            return;
+        };
+
+        let text = &self.printed.text;
+        let printed_start = if token.source.start < line {
+            // The line starts inside a token spanning several lines, which is printed as is.
+            token.printed + (line - token.source.start)
+        } else {
+            text[..token.printed]
+                .rfind('\n')
+                .map_or(0, |newline| newline + 1)
+        };
+        let printed_end = text[printed_start..]
+            .find('\n')
+            .map_or(text.len(), |newline| printed_start + newline);
+        let printed_line = text[printed_start..printed_end].trim_end_matches('\r');
+
+        let old = &source[line..content_end];
+        if old != printed_line {
+            let (prefix, suffix) = common_affixes(old, printed_line);
+            edit(
+                line + prefix..content_end - suffix,
+                printed_line[prefix..printed_line.len() - suffix].to_string(),
+            );
+        }
+    }
+
+    /// The printer keeps one blank line between tokens and drops the rest, along with blank
+    /// lines before the first token and after the last.
+    fn format_blank_line(
+        &self,
+        line: usize,
+        line_end: usize,
+        content_end: usize,
+        edit: &mut dyn FnMut(std::ops::Range<usize>, String),
+    ) {
+        let tokens = &self.printed.tokens;
+        let after_first = tokens
+            .first()
+            .is_some_and(|first| first.source.start < line);
+        let before_last = tokens.last().is_some_and(|last| last.source.end > line);
+        let follows_blank = line > 0 && {
+            let previous = self.source[..line - 1]
+                .rfind('\n')
+                .map_or(0, |newline| newline + 1);
+            self.is_blank(previous, line - 1)
+        };
+
+        if after_first && before_last && !follows_blank {
+            if content_end > line {
+                edit(line..content_end, String::new());
+            }
+        } else if line_end < self.source.len() {
+            edit(line..line_end + 1, String::new());
+        } else if line < line_end {
+            edit(line..line_end, String::new());
+        }
+    }
+}
+
+/// Returns the lengths of the longest common prefix and, after it, suffix of `old` and `new`,
+/// both ending on character boundaries.
+fn common_affixes(old: &str, new: &str) -> (usize, usize) {
+    let mut prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let mut suffix = old[prefix..]
+        .bytes()
+        .rev()
+        .zip(new[prefix..].bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) {
+        suffix -= 1;
+    }
+    (prefix, suffix)
+}
@@ -0,0 +1,126 @@
+//! Formatting tests for whole sources.
+
+use nx_fmt::{format_source, FormatError, FormatOptions};
+use std::path::Path;
+
+fn format(source: &str) -> String {
+    format_source(source, "test.nx", &FormatOptions::default()).expect("source formats")
+}
+
+#[test]
+fn test_indents_blocks_and_normalizes_spacing() {
+    let source = "let f(a:int,b:int) = {\n    // sum   \n  a+\nb\n}\nlet   xs = [\n1 ,\n   2,\n]\n";
+    assert_eq!(
+        format(source),
+        "let f(a:int, b:int) = {\n  // sum\n  a +\n    b\n}\nlet xs = [\n  1,\n  2,\n]\n"
+    );
+}
+
+#[test]
+fn test_indents_elements_and_tags() {
+    let source = r#"let <Card title:string
+      subtitle:string
+  /> =
+      <div class="card"
+   id="x"
+      >
+        <h1>{title}</h1>
+  <Badge label="new"
+  count={1+2} />
+        </div>
+"#;
+    assert_eq!(
+        format(source),
+        r#"let <Card title:string
+  subtitle:string
+/> =
+  <div class="card"
+    id="x"
+  >
+    <h1>{title}</h1>
+    <Badge label="new"
+      count={1 + 2} />
+  </div>
+"#
+    );
+}
+
+#[test]
+fn test_spaces_record_composition() {
+    assert_eq!(
+        format("type Admin = User&Audited   &{\nrole:string\n}\n"),
+        "type Admin = User & Audited & {\n  role:string\n}\n"
+    );
+}
+
+#[test]
+fn test_spaces_field_constraints() {
+    assert_eq!(
+        format("type Person = {\nage:int@min(0)   @max(150)\nscore:float @min(-1.5)\n}\n"),
+        "type Person = {\n  age:int @min(0) @max(150)\n  score:float @min(-1.5)\n}\n"
+    );
+}
+
+#[test]
+fn test_keeps_element_text_strings_and_block_comments() {
+    // The whitespace at the end of a text run belongs to the text, so the line after it keeps
+    // its indentation.
+    let source = "let <Note /> = <div>\n      Hello   there\n          <b>x</b>\n<p:>Some   text <b>bold</b>  </p>\n</div>\nlet s = \"a   b\"\n/* block\n      comment */\n";
+    assert_eq!(
+        format(source),
+        "let <Note /> = <div>\n  Hello   there\n          <b>x</b>\n  <p:>Some   text <b>bold</b>  </p>\n</div>\nlet s = \"a   b\"\n/* block\n      comment */\n"
+    );
+}
+
+#[test]
+fn test_collapses_blank_lines_and_ends_with_one_newline() {
+    assert_eq!(
+        format("\n\nlet a = 1\n\n\n\nlet b = 2   \n\n\n"),
+        "let a = 1\n\nlet b = 2\n"
+    );
+    assert_eq!(format(""), "");
+}
+
+#[test]
+fn test_uses_indent_width_and_keeps_crlf_line_endings() {
+    let options = FormatOptions { indent_width: 4 };
+    let formatted = format_source("let f() = {\r\n1\r\n}\r\n", "test.nx", &options).unwrap();
+    assert_eq!(formatted, "let f() = {\r\n    1\r\n}\r\n");
+}
+
+#[test]
+fn test_rejects_sources_with_syntax_errors() {
+    let error = format_source("let broken = {", "broken.nx", &FormatOptions::default())
+        .expect_err("syntax errors are reported");
+    let FormatError::Syntax(diagnostics) = error else {
+        panic!("expected syntax diagnostics, got {error:?}");
+    };
+    assert!(!diagnostics.is_empty());
+}
+
+#[test]
+fn test_example_files_format_idempotently() {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/nx");
+    let mut pending = vec![examples];
+    let mut formatted_files = 0;
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if path.extension().is_none_or(|extension| extension != "nx") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            let name = path.display().to_string();
+            let Ok(formatted) = format_source(&source, &name, &FormatOptions::default()) else {
+                continue;
+            };
+            assert_eq!(format(&formatted), formatted, "{name} is not stable");
+            formatted_files += 1;
+        }
+    }
+    assert!(formatted_files > 0);
+}
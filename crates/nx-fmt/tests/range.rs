@@ -0,0 +1,121 @@
+//! Formatting tests for ranges of a source.
+
+use nx_diagnostics::TextEdit;
+use nx_fmt::{format_range, format_source, FormatError, FormatOptions};
+use text_size::{TextRange, TextSize};
+
+fn apply(source: &str, edits: &[TextEdit]) -> String {
+    let mut text = source.to_string();
+    for edit in edits.iter().rev() {
+        text.replace_range(
+            usize::from(edit.range.start())..usize::from(edit.range.end()),
+            &edit.replacement,
+        );
+    }
+    text
+}
+
+fn format_in(source: &str, range: TextRange) -> String {
+    let edits =
+        format_range(source, "test.nx", range, &FormatOptions::default()).expect("source formats");
+    apply(source, &edits)
+}
+
+/// Formats the lines between the `«` and `»` markers of `source`.
+fn format_marked(source: &str) -> String {
+    let start = source.find('«').expect("source marks the range start");
+    let source = source.replacen('«', "", 1);
+    let end = source.find('»').expect("source marks the range end");
+    let source = source.replacen('»', "", 1);
+    let range = TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32));
+    format_in(&source, range)
+}
+
+fn whole(source: &str) -> TextRange {
+    TextRange::up_to(TextSize::from(source.len() as u32))
+}
+
+#[test]
+fn test_whole_range_matches_format_source() {
+    for source in [
+        "let f(a:int,b:int) = {\n    a+b\n}\nlet xs = [1,2,3]",
+        "\n\nlet a = 1\n\n\n   \nlet b =   2\n\n\n",
+        "let <Card /> =\n<div>\n     Hello   there\n      <b>x   y</b>\n  </div>\n",
+        "let s = \"a   b\"   // keep   this   \n/* a\n     b */   let   c = 3\n",
+        "let f() = {\r\n      1   +  2  \r\n}\r\n",
+    ] {
+        let expected = format_source(source, "test.nx", &FormatOptions::default()).unwrap();
+        assert_eq!(format_in(source, whole(source)), expected, "{source:?}");
+        assert!(
+            format_range(
+                &expected,
+                "test.nx",
+                whole(&expected),
+                &FormatOptions::default()
+            )
+            .unwrap()
+            .is_empty(),
+            "{expected:?}"
+        );
+    }
+}
+
+#[test]
+fn test_only_touches_lines_in_range() {
+    let source =
+        "let   a =   1\nlet xs = [\n  1 ,\n«      x   +   1   ,\n    [1,2]»\n]\nlet   b =   2\n";
+    assert_eq!(
+        format_marked(source),
+        "let   a =   1\nlet xs = [\n  1 ,\n  x + 1,\n  [1, 2]\n]\nlet   b =   2\n"
+    );
+    // A range ending at the start of a line does not touch it.
+    assert_eq!(
+        format_marked("«let   a = 1\n»let   b = 2\n"),
+        "let a = 1\nlet   b = 2\n"
+    );
+}
+
+#[test]
+fn test_uses_indent_width() {
+    let source = "let f() = {\n1+2\n}\n";
+    let edits = format_range(
+        source,
+        "test.nx",
+        whole(source),
+        &FormatOptions { indent_width: 4 },
+    )
+    .unwrap();
+    assert_eq!(apply(source, &edits), "let f() = {\n    1 + 2\n}\n");
+    assert!(edits.iter().all(|edit| edit.file == "test.nx"));
+}
+
+#[test]
+fn test_collapses_blank_lines() {
+    assert_eq!(
+        format_marked("let a = 1\n«\n  \n\nlet b = 2\n»\n\nlet c = 3"),
+        "let a = 1\n\nlet b = 2\n\n\nlet c = 3"
+    );
+    assert_eq!(
+        format_marked("let a = 1\n\n«\n»let b = 2"),
+        "let a = 1\n\nlet b = 2"
+    );
+}
+
+#[test]
+fn test_rejects_syntax_errors_and_ignores_invalid_ranges() {
+    let broken = "let broken = {";
+    assert!(matches!(
+        format_range(broken, "test.nx", whole(broken), &FormatOptions::default()),
+        Err(FormatError::Syntax(_))
+    ));
+
+    let source = "let a = \"é\"";
+    for (start, end) in [(0, 100), (10, 10)] {
+        let range = TextRange::new(TextSize::from(start), TextSize::from(end));
+        assert!(
+            format_range(source, "test.nx", range, &FormatOptions::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
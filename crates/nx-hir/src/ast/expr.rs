@@ -2,10 +2,11 @@
 
 use crate::{ElementId, ExprId, Name};
 use nx_diagnostics::{TextSize, TextSpan};
+use serde::Serialize;
 use smol_str::SmolStr;
 
 /// Literal value in source code.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub enum Literal {
     /// String literal.
     ///
@@ -37,7 +38,7 @@ pub enum Literal {
 ///
 /// This is needed because f64 doesn't implement Eq/Hash due to NaN != NaN in IEEE 754.
 /// For AST comparison purposes, we treat all NaN values as equivalent.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct OrderedFloat(pub f64);
 
 impl PartialEq for OrderedFloat {
@@ -64,18 +65,19 @@ impl std::hash::Hash for OrderedFloat {
 }
 
 /// Property assignment inside a record literal.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct RecordLiteralProperty {
     /// Property key.
     pub name: Name,
     /// Property value expression.
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub value: ExprId,
     /// Source span for the property assignment.
     pub span: TextSpan,
 }
 
 /// Binary operator.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum BinOp {
     // Arithmetic
     Add, // +
@@ -101,7 +103,7 @@ pub enum BinOp {
 }
 
 /// Unary operator.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum UnOp {
     /// Negation: `-`
     Neg,
@@ -110,19 +112,37 @@ pub enum UnOp {
 }
 
 /// One arm of a match-style `if value is { ... }` expression.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct MatchArm {
     /// Patterns accepted by this arm.
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub patterns: Vec<ExprId>,
     /// Body evaluated when any pattern matches.
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub body: ExprId,
 }
 
+/// The part of a destructured value that one pattern binding receives.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum DestructurePart {
+    /// Record field of the same name: `let { name } = ...`
+    Field(Name),
+    /// Array element `index` of a pattern with `len` element bindings. Without a rest binding the
+    /// array must have exactly `len` elements; with one it must have at least `len`.
+    Element {
+        index: usize,
+        len: usize,
+        has_rest: bool,
+    },
+    /// Array elements after the first `skip`: `let [first, ...rest] = ...`
+    Rest { skip: usize },
+}
+
 /// Expression AST node.
 ///
 /// All expressions are stored in an arena and referenced by `ExprId`.
 /// This enables efficient memory management and supports cyclic references.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Expr {
     /// Literal value.
     ///
@@ -138,8 +158,10 @@ pub enum Expr {
     ///
     /// Example: `a + b`, `x == y`
     BinaryOp {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         lhs: ExprId,
         op: BinOp,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         rhs: ExprId,
         span: TextSpan,
     },
@@ -149,6 +171,7 @@ pub enum Expr {
     /// Example: `-x`, `!flag`
     UnaryOp {
         op: UnOp,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         expr: ExprId,
         span: TextSpan,
     },
@@ -157,17 +180,36 @@ pub enum Expr {
     ///
     /// Example: `foo(1, 2)`, `bar()`
     Call {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         func: ExprId,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         args: Vec<ExprId>,
         span: TextSpan,
     },
 
+    /// Partial application of a function.
+    ///
+    /// Each `_` argument is left open (`None`); the result is a function value taking the open
+    /// arguments in order.
+    ///
+    /// Example: `add(_, 10)`
+    PartialApply {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
+        func: ExprId,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
+        args: Vec<Option<ExprId>>,
+        span: TextSpan,
+    },
+
     /// If expression.
     ///
     /// Example: `if x { y } else { z }`
     If {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         condition: ExprId,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         then_branch: ExprId,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         else_branch: Option<ExprId>,
         span: TextSpan,
     },
@@ -176,8 +218,10 @@ pub enum Expr {
     ///
     /// Example: `if state is { LoadState.failed => state.message else => "" }`
     Match {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         scrutinee: ExprId,
         arms: Vec<MatchArm>,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         else_branch: Option<ExprId>,
         span: TextSpan,
     },
@@ -190,7 +234,9 @@ pub enum Expr {
     /// Example: `let x = expensive() in x + x`
     Let {
         name: Name,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         value: ExprId,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         body: ExprId,
         span: TextSpan,
     },
@@ -200,6 +246,7 @@ pub enum Expr {
     /// Example: `{ let x = 1; x + 2 }`
     Block {
         stmts: Vec<super::Stmt>,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         expr: Option<ExprId>,
         span: TextSpan,
     },
@@ -208,6 +255,7 @@ pub enum Expr {
     ///
     /// Example: `[1, 2, 3]`
     Array {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         elements: Vec<ExprId>,
         span: TextSpan,
     },
@@ -216,7 +264,9 @@ pub enum Expr {
     ///
     /// Example: `arr[0]`, `matrix[i][j]`
     Index {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         base: ExprId,
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         index: ExprId,
         span: TextSpan,
     },
@@ -225,6 +275,7 @@ pub enum Expr {
     ///
     /// Example: `obj.field`
     Member {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         base: ExprId,
         member: Name,
         span: TextSpan,
@@ -245,7 +296,11 @@ pub enum Expr {
     /// Element literal expression.
     ///
     /// Example: `<button class="primary" />`
-    Element { element: ElementId, span: TextSpan },
+    Element {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
+        element: ElementId,
+        span: TextSpan,
+    },
 
     /// Lazy component action handler callback.
     ///
@@ -259,6 +314,7 @@ pub enum Expr {
         /// Exported action type name expected at invocation time
         action_name: Name,
         /// Handler body expression
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         body: ExprId,
         /// Source span
         span: TextSpan,
@@ -274,12 +330,51 @@ pub enum Expr {
         /// Optional index variable
         index: Option<Name>,
         /// Iterable expression
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         iterable: ExprId,
         /// Loop body expression
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         body: ExprId,
         span: TextSpan,
     },
 
+    /// Context provider expression.
+    ///
+    /// Makes `value` the current value of the top-level value `key` while `body` is evaluated,
+    /// including inside every function and component the body calls.
+    ///
+    /// Example: `provide theme = darkTheme { <App /> }`
+    Provide {
+        /// Top-level value used as the context key
+        key: Name,
+        /// Value provided to descendants
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
+        value: ExprId,
+        /// Expression evaluated with the value provided
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
+        body: ExprId,
+        span: TextSpan,
+    },
+
+    /// Context lookup expression.
+    ///
+    /// Reads the nearest value provided for `key`, or the top-level value itself when no
+    /// enclosing `provide` supplies one.
+    ///
+    /// Example: `use theme`
+    UseContext { key: Name, span: TextSpan },
+
+    /// One binding of a destructuring `let`.
+    ///
+    /// `let [first, ...rest] = {items}` lowers to a private value holding `items` plus one value
+    /// per binding whose initializer projects its part out of that source.
+    Destructure {
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
+        source: ExprId,
+        part: DestructurePart,
+        span: TextSpan,
+    },
+
     /// Error placeholder for malformed expressions.
     ///
     /// This is used during lowering when the CST contains errors.
@@ -295,6 +390,7 @@ impl Expr {
             Expr::BinaryOp { span, .. } => *span,
             Expr::UnaryOp { span, .. } => *span,
             Expr::Call { span, .. } => *span,
+            Expr::PartialApply { span, .. } => *span,
             Expr::If { span, .. } => *span,
             Expr::Match { span, .. } => *span,
             Expr::Let { span, .. } => *span,
@@ -306,6 +402,9 @@ impl Expr {
             Expr::Element { span, .. } => *span,
             Expr::ActionHandler { span, .. } => *span,
             Expr::For { span, .. } => *span,
+            Expr::Provide { span, .. } => *span,
+            Expr::UseContext { span, .. } => *span,
+            Expr::Destructure { span, .. } => *span,
             Expr::Error(span) => *span,
         }
     }
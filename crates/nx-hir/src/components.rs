@@ -465,7 +465,10 @@ fn collect_handler_rewrites_in_expr(
     rewrites: &mut Vec<PendingHandlerRewrite>,
 ) {
     match module.raw_module().expr(expr_id) {
-        ast::Expr::Literal(_) | ast::Expr::Ident(_) | ast::Expr::Error(_) => {}
+        ast::Expr::Literal(_)
+        | ast::Expr::Ident(_)
+        | ast::Expr::UseContext { .. }
+        | ast::Expr::Error(_) => {}
         ast::Expr::BinaryOp { lhs, rhs, .. } => {
             collect_handler_rewrites_in_expr(module, *lhs, rewrites);
             collect_handler_rewrites_in_expr(module, *rhs, rewrites);
@@ -479,6 +482,12 @@ fn collect_handler_rewrites_in_expr(
                 collect_handler_rewrites_in_expr(module, *arg, rewrites);
             }
         }
+        ast::Expr::PartialApply { func, args, .. } => {
+            collect_handler_rewrites_in_expr(module, *func, rewrites);
+            for arg in args.iter().flatten() {
+                collect_handler_rewrites_in_expr(module, *arg, rewrites);
+            }
+        }
         ast::Expr::If {
             condition,
             then_branch,
@@ -554,6 +563,13 @@ fn collect_handler_rewrites_in_expr(
             collect_handler_rewrites_in_expr(module, *iterable, rewrites);
             collect_handler_rewrites_in_expr(module, *body, rewrites);
         }
+        ast::Expr::Destructure { source, .. } => {
+            collect_handler_rewrites_in_expr(module, *source, rewrites);
+        }
+        ast::Expr::Provide { value, body, .. } => {
+            collect_handler_rewrites_in_expr(module, *value, rewrites);
+            collect_handler_rewrites_in_expr(module, *body, rewrites);
+        }
     }
 }
 
@@ -621,6 +637,9 @@ fn collect_handler_rewrites_in_property_entries(
 
                 collect_handler_rewrites_in_expr(module, property.value, rewrites);
             }
+            PropertyEntry::Spread { value, .. } => {
+                collect_handler_rewrites_in_expr(module, *value, rewrites);
+            }
             PropertyEntry::If {
                 condition,
                 then_entries,
@@ -715,7 +734,7 @@ fn rewrite_property_entry_handler(
                 property.value = handler;
                 return true;
             }
-            PropertyEntry::Value(_) => {}
+            PropertyEntry::Value(_) | PropertyEntry::Spread { .. } => {}
             PropertyEntry::If {
                 then_entries,
                 else_entries,
@@ -0,0 +1,143 @@
+//! Validation constraints declared on record fields, such as `age: int @min(0) @max(150)`.
+//!
+//! Constraints are checked whenever a record value is constructed, from element syntax,
+//! constructor calls or host input, and every failed constraint is reported together.
+
+use crate::ast::OrderedFloat;
+use nx_diagnostics::TextSpan;
+use serde::Serialize;
+use std::fmt;
+
+/// One `@name(arguments)` constraint on a record field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FieldConstraint {
+    /// What the constraint requires
+    pub kind: FieldConstraintKind,
+    /// Source span of the whole `@name(...)` annotation
+    pub span: TextSpan,
+}
+
+/// The requirement a [`FieldConstraint`] places on a field value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FieldConstraintKind {
+    /// `@min(n)`: a number no smaller than `n`.
+    Min(ConstraintNumber),
+    /// `@max(n)`: a number no larger than `n`.
+    Max(ConstraintNumber),
+    /// `@minLength(n)`: a string of at least `n` characters or an array of at least `n` items.
+    MinLength(u64),
+    /// `@maxLength(n)`: a string of at most `n` characters or an array of at most `n` items.
+    MaxLength(u64),
+}
+
+/// A numeric constraint argument, kept as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ConstraintNumber {
+    Int(i64),
+    Float(OrderedFloat),
+}
+
+impl ConstraintNumber {
+    /// Returns the argument as a float, for comparisons with float values.
+    pub fn as_f64(self) -> f64 {
+        match self {
+            ConstraintNumber::Int(value) => value as f64,
+            ConstraintNumber::Float(OrderedFloat(value)) => value,
+        }
+    }
+}
+
+impl fmt::Display for ConstraintNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintNumber::Int(value) => write!(f, "{value}"),
+            ConstraintNumber::Float(OrderedFloat(value)) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+/// Names of the supported constraints, for diagnostics.
+pub const CONSTRAINT_NAMES: &[&str] = &["max", "maxLength", "min", "minLength"];
+
+impl FieldConstraintKind {
+    /// Builds the constraint `@name(arguments)`, or describes why it is invalid.
+    pub fn new(name: &str, arguments: &[ConstraintNumber]) -> Result<Self, String> {
+        let number = || match arguments {
+            [value] => Ok(*value),
+            _ => Err(format!(
+                "Constraint '@{name}' takes one number, got {} arguments",
+                arguments.len()
+            )),
+        };
+        let length = || match number()? {
+            ConstraintNumber::Int(value) if value >= 0 => Ok(value as u64),
+            other => Err(format!(
+                "Constraint '@{name}' takes a non-negative integer, got {other}"
+            )),
+        };
+        match name {
+            "min" => number().map(FieldConstraintKind::Min),
+            "max" => number().map(FieldConstraintKind::Max),
+            "minLength" => length().map(FieldConstraintKind::MinLength),
+            "maxLength" => length().map(FieldConstraintKind::MaxLength),
+            _ => Err(format!(
+                "Unknown constraint '@{name}'; supported constraints are {}",
+                CONSTRAINT_NAMES
+                    .iter()
+                    .map(|name| format!("'@{name}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    /// Returns true for `@min` and `@max`, which apply to numbers; the others apply to strings
+    /// and arrays.
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            FieldConstraintKind::Min(_) | FieldConstraintKind::Max(_)
+        )
+    }
+}
+
+impl fmt::Display for FieldConstraintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldConstraintKind::Min(value) => write!(f, "@min({value})"),
+            FieldConstraintKind::Max(value) => write!(f, "@max({value})"),
+            FieldConstraintKind::MinLength(value) => write!(f, "@minLength({value})"),
+            FieldConstraintKind::MaxLength(value) => write!(f, "@maxLength({value})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constraints_check_their_arguments() {
+        assert_eq!(
+            FieldConstraintKind::new("min", &[ConstraintNumber::Int(-5)]),
+            Ok(FieldConstraintKind::Min(ConstraintNumber::Int(-5)))
+        );
+        assert_eq!(
+            FieldConstraintKind::new("maxLength", &[ConstraintNumber::Int(3)])
+                .unwrap()
+                .to_string(),
+            "@maxLength(3)"
+        );
+        assert_eq!(
+            FieldConstraintKind::new("minLength", &[ConstraintNumber::Float(OrderedFloat(1.5))]),
+            Err("Constraint '@minLength' takes a non-negative integer, got 1.5".to_string())
+        );
+        assert_eq!(
+            FieldConstraintKind::new("max", &[]),
+            Err("Constraint '@max' takes one number, got 0 arguments".to_string())
+        );
+        assert!(FieldConstraintKind::new("pattern", &[])
+            .unwrap_err()
+            .starts_with("Unknown constraint '@pattern'"));
+    }
+}
@@ -0,0 +1,713 @@
+//! Import resolution across the lowered modules of one program.
+//!
+//! A [`ModuleGraph`] holds lowered modules keyed by a host-chosen identity such as
+//! `app/main.nx`. It never reads files: hosts lower each source file and add it, and the graph
+//! resolves every [`Import`] against the modules it holds.
+
+use crate::{
+    binding_specs_for_item, local_definition_id, prepared_item_kind, Import, ImportKind, Item,
+    LocalDefinitionId, LoweredModule, LoweringDiagnostic, Name, PreparedBinding,
+    PreparedBindingOrigin, PreparedBindingTarget, PreparedItemKind, PreparedModule,
+    PreparedNamespace, ResolvedPreparedItem, Visibility,
+};
+use nx_diagnostics::TextSpan;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::Arc;
+
+/// Lowered modules of one program and the imports between them.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleGraph {
+    modules: FxHashMap<String, Arc<LoweredModule>>,
+    identities: Vec<String>,
+}
+
+/// Problem found while resolving the imports of a [`ModuleGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleGraphError {
+    UnresolvedImport {
+        module_identity: String,
+        library_path: String,
+        span: TextSpan,
+    },
+    MissingExport {
+        module_identity: String,
+        library_path: String,
+        name: Name,
+        span: TextSpan,
+    },
+    ImportCycle {
+        module_identity: String,
+        cycle: Vec<String>,
+        span: TextSpan,
+    },
+}
+
+impl ModuleGraphError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ModuleGraphError::UnresolvedImport { .. } => "unresolved-import",
+            ModuleGraphError::MissingExport { .. } => "import-missing-export",
+            ModuleGraphError::ImportCycle { .. } => "import-cycle",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ModuleGraphError::UnresolvedImport { library_path, .. } => {
+                format!("Import '{}' does not match any module", library_path)
+            }
+            ModuleGraphError::MissingExport {
+                library_path, name, ..
+            } => format!("'{}' does not export '{}'", library_path, name),
+            ModuleGraphError::ImportCycle { cycle, .. } => {
+                format!("Import cycle: {}", cycle.join(" -> "))
+            }
+        }
+    }
+
+    /// Returns the identity of the module whose import is reported.
+    pub fn module_identity(&self) -> &str {
+        match self {
+            ModuleGraphError::UnresolvedImport {
+                module_identity, ..
+            }
+            | ModuleGraphError::MissingExport {
+                module_identity, ..
+            }
+            | ModuleGraphError::ImportCycle {
+                module_identity, ..
+            } => module_identity,
+        }
+    }
+
+    pub fn span(&self) -> TextSpan {
+        match self {
+            ModuleGraphError::UnresolvedImport { span, .. }
+            | ModuleGraphError::MissingExport { span, .. }
+            | ModuleGraphError::ImportCycle { span, .. } => *span,
+        }
+    }
+}
+
+/// One exported declaration an import makes visible in an importing module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleGraphImport {
+    pub visible_name: Name,
+    pub module_identity: String,
+    pub definition_id: LocalDefinitionId,
+    pub kind: PreparedItemKind,
+}
+
+/// One exported item made visible in an importing module.
+struct ImportedName<'a> {
+    visible_name: Name,
+    module_identity: &'a str,
+    index: usize,
+    item: &'a Item,
+}
+
+impl ModuleGraph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a lowered module under `identity`, replacing any module already added with it.
+    pub fn add_module(&mut self, identity: impl Into<String>, module: LoweredModule) {
+        let identity = normalize_path(&identity.into());
+        if self
+            .modules
+            .insert(identity.clone(), Arc::new(module))
+            .is_none()
+        {
+            self.identities.push(identity);
+        }
+    }
+
+    /// Returns the module added under `identity`.
+    pub fn module(&self, identity: &str) -> Option<&LoweredModule> {
+        self.modules.get(identity).map(|module| module.as_ref())
+    }
+
+    /// Returns every module identity in the order the modules were added.
+    pub fn module_identities(&self) -> impl Iterator<Item = &str> {
+        self.identities.iter().map(String::as_str)
+    }
+
+    /// Returns the modules an import of `library_path` from `importer` refers to.
+    ///
+    /// Paths starting with `.` are relative to the importer's directory. A path names one module
+    /// when a module has that identity, with or without an `.nx` extension, and otherwise names
+    /// the library made of every module under that directory.
+    pub fn resolve_import_path(&self, importer: &str, library_path: &str) -> Vec<&str> {
+        let path = if library_path.starts_with('.') {
+            let directory = importer.rsplit_once('/').map_or("", |(dir, _)| dir);
+            normalize_path(&format!("{}/{}", directory, library_path))
+        } else {
+            normalize_path(library_path)
+        };
+
+        let file = format!("{}.nx", path);
+        if let Some((identity, _)) = self
+            .modules
+            .get_key_value(&path)
+            .or_else(|| self.modules.get_key_value(&file))
+        {
+            return vec![identity.as_str()];
+        }
+
+        let prefix = format!("{}/", path);
+        self.module_identities()
+            .filter(|identity| identity.starts_with(&prefix))
+            .collect()
+    }
+
+    /// Returns the identities of the modules `identity` imports, without duplicates.
+    pub fn imported_modules(&self, identity: &str) -> Vec<&str> {
+        let Some(module) = self.modules.get(identity) else {
+            return Vec::new();
+        };
+
+        let mut seen = FxHashSet::default();
+        module
+            .imports
+            .iter()
+            .flat_map(|import| self.resolve_import_path(identity, &import.library_path))
+            .filter(|target| seen.insert(*target))
+            .collect()
+    }
+
+    /// Looks `name` up in one namespace of the module `identity`.
+    ///
+    /// The module's own declarations come first, then the exported declarations its imports
+    /// bring in, with wildcard aliases and selective qualifiers applied as `Alias.Name`.
+    pub fn lookup(
+        &self,
+        identity: &str,
+        namespace: PreparedNamespace,
+        name: &Name,
+    ) -> Option<ResolvedPreparedItem> {
+        let module = self.modules.get(identity)?;
+
+        if let Some((index, item)) = module
+            .items()
+            .iter()
+            .enumerate()
+            .find(|(_, item)| item.name() == name && contributes_to(item, namespace))
+        {
+            return Some(ResolvedPreparedItem::Raw {
+                module_identity: identity.to_string(),
+                definition_id: local_definition_id(index),
+                item: item.clone(),
+                origin: PreparedBindingOrigin::Local,
+            });
+        }
+
+        self.imported_names(identity, module)
+            .into_iter()
+            .find(|imported| {
+                &imported.visible_name == name && contributes_to(imported.item, namespace)
+            })
+            .map(|imported| ResolvedPreparedItem::Raw {
+                module_identity: imported.module_identity.to_string(),
+                definition_id: local_definition_id(imported.index),
+                item: imported.item.clone(),
+                origin: PreparedBindingOrigin::Peer {
+                    module_identity: imported.module_identity.to_string(),
+                },
+            })
+    }
+
+    /// Returns the exported declarations the imports of `identity` make visible, in import order.
+    ///
+    /// A name imported twice keeps its first declaration; local declarations are not consulted.
+    pub fn visible_imports(&self, identity: &str) -> Vec<ModuleGraphImport> {
+        let Some(module) = self.modules.get(identity) else {
+            return Vec::new();
+        };
+
+        let mut seen = FxHashSet::default();
+        self.imported_names(identity, module)
+            .into_iter()
+            .filter(|imported| seen.insert(imported.visible_name.clone()))
+            .map(|imported| ModuleGraphImport {
+                visible_name: imported.visible_name,
+                module_identity: imported.module_identity.to_string(),
+                definition_id: local_definition_id(imported.index),
+                kind: prepared_item_kind(imported.item),
+            })
+            .collect()
+    }
+
+    /// Returns module identities ordered so every module follows the modules it imports.
+    pub fn topological_order(&self) -> Result<Vec<&str>, ModuleGraphError> {
+        let mut order = Vec::new();
+        let mut state = FxHashMap::default();
+        for identity in self.module_identities() {
+            self.visit(identity, &mut state, &mut Vec::new(), &mut order)?;
+        }
+        Ok(order)
+    }
+
+    /// Returns every unresolved import, missing export, and import cycle in the graph.
+    pub fn diagnostics(&self) -> Vec<ModuleGraphError> {
+        let mut errors = Vec::new();
+        for identity in self.module_identities() {
+            let module = &self.modules[identity];
+            for import in &module.imports {
+                let targets = self.resolve_import_path(identity, &import.library_path);
+                if targets.is_empty() {
+                    errors.push(ModuleGraphError::UnresolvedImport {
+                        module_identity: identity.to_string(),
+                        library_path: import.library_path.clone(),
+                        span: import.span,
+                    });
+                    continue;
+                }
+
+                if let ImportKind::Selective { entries } = &import.kind {
+                    for entry in entries {
+                        if self.find_export(&targets, &entry.name).is_none() {
+                            errors.push(ModuleGraphError::MissingExport {
+                                module_identity: identity.to_string(),
+                                library_path: import.library_path.clone(),
+                                name: entry.name.clone(),
+                                span: entry.span,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Err(cycle) = self.topological_order() {
+            errors.push(cycle);
+        }
+        errors
+    }
+
+    /// Builds the prepared module for `identity`, binding the names its imports bring in.
+    ///
+    /// Imported modules are registered as peers, so the type checker and interpreter resolve
+    /// imported names to their raw declarations. Local declarations shadow imported names, and
+    /// the graph's diagnostics for this module are recorded on the prepared module.
+    pub fn prepare(&self, identity: &str) -> Option<PreparedModule> {
+        let module = self.modules.get(identity)?;
+        let mut prepared = PreparedModule::new(identity, module.as_ref().clone());
+
+        for target in self.imported_modules(identity) {
+            prepared.add_peer_module(target, Arc::clone(&self.modules[target]));
+        }
+
+        for imported in self.imported_names(identity, module) {
+            for (namespace, kind) in binding_specs_for_item(imported.item) {
+                if prepared.has_binding(namespace, &imported.visible_name) {
+                    continue;
+                }
+                prepared.insert_binding(PreparedBinding {
+                    visible_name: imported.visible_name.clone(),
+                    namespace,
+                    kind,
+                    origin: PreparedBindingOrigin::Peer {
+                        module_identity: imported.module_identity.to_string(),
+                    },
+                    target: PreparedBindingTarget::Peer {
+                        module_identity: imported.module_identity.to_string(),
+                        definition_id: local_definition_id(imported.index),
+                    },
+                });
+            }
+        }
+
+        for error in self.diagnostics() {
+            if error.module_identity() == identity {
+                prepared.add_diagnostic(LoweringDiagnostic {
+                    code: Some(error.code()),
+                    message: error.message(),
+                    span: error.span(),
+                });
+            }
+        }
+
+        Some(prepared)
+    }
+
+    fn imported_names<'a>(
+        &'a self,
+        identity: &str,
+        module: &'a LoweredModule,
+    ) -> Vec<ImportedName<'a>> {
+        module
+            .imports
+            .iter()
+            .flat_map(|import| self.names_from_import(identity, import))
+            .collect()
+    }
+
+    fn names_from_import<'a>(&'a self, identity: &str, import: &Import) -> Vec<ImportedName<'a>> {
+        let targets = self.resolve_import_path(identity, &import.library_path);
+        match &import.kind {
+            ImportKind::Wildcard { alias } => targets
+                .iter()
+                .flat_map(|target| {
+                    self.exports(target).map(|(index, item)| ImportedName {
+                        visible_name: qualified_name(alias.as_ref(), item.name()),
+                        module_identity: target,
+                        index,
+                        item,
+                    })
+                })
+                .collect(),
+            ImportKind::Selective { entries } => entries
+                .iter()
+                .filter_map(|entry| {
+                    let (module_identity, index, item) = self.find_export(&targets, &entry.name)?;
+                    Some(ImportedName {
+                        visible_name: qualified_name(entry.qualifier.as_ref(), &entry.name),
+                        module_identity,
+                        index,
+                        item,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn exports<'a>(&'a self, identity: &str) -> impl Iterator<Item = (usize, &'a Item)> {
+        self.modules
+            .get(identity)
+            .into_iter()
+            .flat_map(|module| module.items().iter().enumerate())
+            .filter(|(_, item)| item.visibility() == Visibility::Export)
+    }
+
+    fn find_export<'a>(
+        &'a self,
+        targets: &[&'a str],
+        name: &Name,
+    ) -> Option<(&'a str, usize, &'a Item)> {
+        targets.iter().find_map(|target| {
+            self.exports(target)
+                .find(|(_, item)| item.name() == name)
+                .map(|(index, item)| (*target, index, item))
+        })
+    }
+
+    fn visit<'a>(
+        &'a self,
+        identity: &'a str,
+        state: &mut FxHashMap<&'a str, bool>,
+        stack: &mut Vec<&'a str>,
+        order: &mut Vec<&'a str>,
+    ) -> Result<(), ModuleGraphError> {
+        match state.get(identity) {
+            Some(true) => return Ok(()),
+            Some(false) => {
+                let start = stack
+                    .iter()
+                    .position(|entry| *entry == identity)
+                    .unwrap_or(0);
+                let mut cycle: Vec<String> = stack[start..]
+                    .iter()
+                    .map(|entry| entry.to_string())
+                    .collect();
+                cycle.push(identity.to_string());
+                let importer = stack.last().copied().unwrap_or(identity);
+                let span = self.modules[importer]
+                    .imports
+                    .iter()
+                    .find(|import| {
+                        self.resolve_import_path(importer, &import.library_path)
+                            .contains(&identity)
+                    })
+                    .map_or_else(TextSpan::default, |import| import.span);
+                return Err(ModuleGraphError::ImportCycle {
+                    module_identity: importer.to_string(),
+                    cycle,
+                    span,
+                });
+            }
+            None => {}
+        }
+
+        state.insert(identity, false);
+        stack.push(identity);
+        for target in self.imported_modules(identity) {
+            self.visit(target, state, stack, order)?;
+        }
+        stack.pop();
+        state.insert(identity, true);
+        order.push(identity);
+        Ok(())
+    }
+}
+
+fn contributes_to(item: &Item, namespace: PreparedNamespace) -> bool {
+    binding_specs_for_item(item)
+        .iter()
+        .any(|(item_namespace, _)| *item_namespace == namespace)
+}
+
+fn qualified_name(prefix: Option<&Name>, name: &Name) -> Name {
+    match prefix {
+        Some(prefix) => Name::new(&format!("{}.{}", prefix, name)),
+        None => name.clone(),
+    }
+}
+
+/// Removes `.` segments, applies `..` segments, and collapses repeated separators.
+fn normalize_path(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if segments.last().is_some_and(|last| *last != "..") {
+                    segments.pop();
+                } else {
+                    segments.push(segment);
+                }
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lower_source_module;
+
+    fn graph(files: &[(&str, &str)]) -> ModuleGraph {
+        let mut graph = ModuleGraph::new();
+        for (identity, source) in files {
+            let module = lower_source_module(source, identity).expect("module should lower");
+            graph.add_module(*identity, module);
+        }
+        graph
+    }
+
+    fn lookup_module(
+        graph: &ModuleGraph,
+        identity: &str,
+        namespace: PreparedNamespace,
+        name: &str,
+    ) -> Option<String> {
+        graph
+            .lookup(identity, namespace, &Name::new(name))
+            .map(|item| item.module_identity().to_string())
+    }
+
+    #[test]
+    fn test_resolve_import_path_is_relative_to_the_importer() {
+        let graph = graph(&[
+            ("app/main.nx", "let root() = 1"),
+            ("app/util.nx", "let helper() = 1"),
+            ("lib/ui/button.nx", "let button() = 1"),
+            ("lib/ui/stack.nx", "let stack() = 1"),
+        ]);
+
+        assert_eq!(
+            graph.resolve_import_path("app/main.nx", "./util"),
+            vec!["app/util.nx"]
+        );
+        assert_eq!(
+            graph.resolve_import_path("app/main.nx", "./util.nx"),
+            vec!["app/util.nx"]
+        );
+        assert_eq!(
+            graph.resolve_import_path("app/main.nx", "../lib/ui"),
+            vec!["lib/ui/button.nx", "lib/ui/stack.nx"]
+        );
+        assert!(graph
+            .resolve_import_path("app/main.nx", "./missing")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_lookup_finds_exported_names_through_imports() {
+        let graph = graph(&[
+            (
+                "app/main.nx",
+                "import \"./math\"\nimport { greet as Text.greet } from \"./text\"\nlet root() = 1",
+            ),
+            (
+                "app/math.nx",
+                "export let double(x: int) = { x * 2 }\nlet hidden() = 1",
+            ),
+            ("app/text.nx", "export let greet() = \"hi\""),
+        ]);
+
+        assert_eq!(
+            lookup_module(&graph, "app/main.nx", PreparedNamespace::Value, "double").as_deref(),
+            Some("app/math.nx")
+        );
+        assert_eq!(
+            lookup_module(
+                &graph,
+                "app/main.nx",
+                PreparedNamespace::Value,
+                "Text.greet"
+            )
+            .as_deref(),
+            Some("app/text.nx")
+        );
+        assert_eq!(
+            lookup_module(&graph, "app/main.nx", PreparedNamespace::Value, "root").as_deref(),
+            Some("app/main.nx")
+        );
+        assert_eq!(
+            lookup_module(&graph, "app/main.nx", PreparedNamespace::Value, "hidden"),
+            None
+        );
+        assert_eq!(
+            lookup_module(&graph, "app/main.nx", PreparedNamespace::Value, "greet"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_lookup_applies_wildcard_aliases_and_prefers_local_declarations() {
+        let graph = graph(&[
+            ("main.nx", "import \"./shapes\" as Shapes\nlet area() = 0"),
+            ("shapes.nx", "export let area() = 1\nexport type Size = int"),
+        ]);
+
+        assert_eq!(
+            lookup_module(&graph, "main.nx", PreparedNamespace::Value, "area").as_deref(),
+            Some("main.nx")
+        );
+        assert_eq!(
+            lookup_module(&graph, "main.nx", PreparedNamespace::Value, "Shapes.area").as_deref(),
+            Some("shapes.nx")
+        );
+        assert_eq!(
+            lookup_module(&graph, "main.nx", PreparedNamespace::Type, "Shapes.Size").as_deref(),
+            Some("shapes.nx")
+        );
+        assert_eq!(
+            lookup_module(&graph, "main.nx", PreparedNamespace::Value, "Shapes.Size"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_visible_imports_list_each_imported_name_once() {
+        let graph = graph(&[
+            (
+                "main.nx",
+                "import \"./math\"\nimport { double as Math.double } from \"./math\"\nlet root() = 1",
+            ),
+            (
+                "math.nx",
+                "let hidden() = 1\nexport let double(x: int) = { x * 2 }",
+            ),
+        ]);
+
+        assert_eq!(
+            graph.visible_imports("main.nx"),
+            vec![
+                ModuleGraphImport {
+                    visible_name: Name::new("double"),
+                    module_identity: "math.nx".to_string(),
+                    definition_id: LocalDefinitionId::new(1),
+                    kind: PreparedItemKind::Function,
+                },
+                ModuleGraphImport {
+                    visible_name: Name::new("Math.double"),
+                    module_identity: "math.nx".to_string(),
+                    definition_id: LocalDefinitionId::new(1),
+                    kind: PreparedItemKind::Function,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_report_unresolved_imports_and_missing_exports() {
+        let graph = graph(&[
+            (
+                "main.nx",
+                "import \"./missing\"\nimport { double, triple } from \"./math\"\nlet root() = 1",
+            ),
+            ("math.nx", "export let double(x: int) = { x * 2 }"),
+        ]);
+
+        let codes: Vec<_> = graph
+            .diagnostics()
+            .iter()
+            .map(|error| (error.code(), error.message()))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                (
+                    "unresolved-import",
+                    "Import './missing' does not match any module".to_string()
+                ),
+                (
+                    "import-missing-export",
+                    "'./math' does not export 'triple'".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_topological_order_puts_imports_first_and_reports_cycles() {
+        let ordered = graph(&[
+            ("main.nx", "import \"./a\"\nlet root() = 1"),
+            ("a.nx", "import \"./b\"\nexport let a() = 1"),
+            ("b.nx", "export let b() = 1"),
+        ]);
+        assert_eq!(
+            ordered.topological_order().unwrap(),
+            vec!["b.nx", "a.nx", "main.nx"]
+        );
+
+        let cyclic = graph(&[
+            ("a.nx", "import \"./b\"\nexport let a() = 1"),
+            ("b.nx", "import \"./a\"\nexport let b() = 1"),
+        ]);
+        let error = cyclic.topological_order().unwrap_err();
+        assert_eq!(error.code(), "import-cycle");
+        assert_eq!(error.module_identity(), "b.nx");
+        assert_eq!(error.message(), "Import cycle: a.nx -> b.nx -> a.nx");
+    }
+
+    #[test]
+    fn test_prepare_binds_imported_names_to_peer_modules() {
+        let graph = graph(&[
+            (
+                "main.nx",
+                "import { double } from \"./math\"\nimport \"./nowhere\"\nlet root() = { double(2) }",
+            ),
+            ("math.nx", "export let double(x: int) = { x * 2 }"),
+        ]);
+
+        let prepared = graph.prepare("main.nx").expect("module should be prepared");
+        let binding = prepared
+            .resolve_binding(PreparedNamespace::Value, &Name::new("double"))
+            .expect("imported name should be bound");
+        let resolved = prepared
+            .resolve_prepared_item(binding)
+            .expect("binding should resolve to the peer module");
+        assert_eq!(resolved.module_identity(), "math.nx");
+        assert!(matches!(
+            resolved,
+            ResolvedPreparedItem::Raw {
+                item: Item::Function(_),
+                ..
+            }
+        ));
+        assert_eq!(
+            prepared
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect::<Vec<_>>(),
+            vec![Some("unresolved-import")]
+        );
+    }
+}
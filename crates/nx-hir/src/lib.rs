@@ -10,6 +10,17 @@
 //! - **HIR** (this crate): Simplified, typed AST for semantic analysis
 //! - **Type System** (nx-types): Type checking and inference
 //!
+//! # Imports
+//!
+//! Lowering records each `import` statement as an [`Import`] on the module; this crate never reads
+//! files. Hosts lower each source file and add it to a [`ModuleGraph`], which resolves imports
+//! between the modules it holds, reports unresolved imports, missing exports, and import cycles,
+//! and looks names up across modules with [`ModuleGraph::lookup`]. [`ModuleGraph::prepare`]
+//! turns one module into a [`PreparedModule`] whose imported names are bound to peer modules,
+//! which is what the type checker consumes, and `nx-interpreter` builds a runnable program from a
+//! whole graph, so a program split across files is checked and run as one. `nx-api` builds prepared modules the same way for `nxlang run` and
+//! `nxlang check`, adding library roots and the standard library on top.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -22,19 +33,26 @@
 
 pub mod ast;
 pub mod components;
+pub mod constraints;
 pub mod db;
+pub mod graph;
 pub mod lower;
 pub mod prepared;
 pub mod records;
 pub mod scope;
+mod serialize;
 pub mod unions;
+pub mod walk;
 
 use la_arena::{Arena, Idx};
 use nx_diagnostics::{Diagnostic, Label, Severity, TextSpan};
+use serde::Serialize;
 use smol_str::SmolStr;
 
+pub use graph::{ModuleGraph, ModuleGraphError, ModuleGraphImport};
+
 // Re-export lowering function
-pub use lower::lower;
+pub use lower::{lower, MAX_NESTING_DEPTH};
 pub use prepared::{
     binding_specs_for_item, interface_component, interface_enum, interface_function_signature,
     interface_record, interface_type_alias, interface_union, local_definition_id,
@@ -50,15 +68,16 @@ pub use components::{
     validate_component_definitions, ComponentResolutionError, EffectiveComponentContract,
     InvalidComponentBaseReason,
 };
+pub use constraints::{ConstraintNumber, FieldConstraint, FieldConstraintKind};
 
 // Re-export database types
 pub use db::{DatabaseImpl, NxDatabase};
 
 // Re-export scope and symbol types
 pub use records::{
-    effective_record_shape, effective_record_shape_for_name, is_record_subtype,
-    resolve_record_definition, validate_record_definitions, EffectiveRecordShape,
-    InvalidBaseReason, RecordResolutionError,
+    default_evaluation_order, effective_record_shape, effective_record_shape_for_name,
+    is_record_subtype, resolve_record_definition, validate_record_definitions,
+    EffectiveRecordShape, InvalidBaseReason, RecordResolutionError,
 };
 pub use scope::{
     build_scopes, check_undefined_identifiers, Scope, ScopeId, ScopeManager, Symbol, SymbolKind,
@@ -68,6 +87,9 @@ pub use unions::{
     UnionValidationError,
 };
 
+/// Version of the nx-hir crate, identical to the toolchain version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Parses, lowers, and validates a module from source text.
 ///
 /// `file_name` is used for diagnostic labels. Import resolution is intentionally out of scope for
@@ -104,7 +126,7 @@ pub fn lower_source_module(
             .diagnostics()
             .iter()
             .map(|diagnostic| {
-                Diagnostic::error("lowering-error")
+                Diagnostic::error(diagnostic.code.unwrap_or("lowering-error"))
                     .with_message(diagnostic.message.clone())
                     .with_label(Label::primary(file_name, diagnostic.span))
                     .build()
@@ -120,7 +142,8 @@ pub fn lower_source_module(
 ///
 /// Uses `SmolStr` for efficient storage and cloning of small strings.
 /// Most identifiers in code are short, so this optimizes for the common case.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
 pub struct Name(SmolStr);
 
 impl Name {
@@ -154,7 +177,7 @@ impl std::fmt::Display for Name {
 }
 
 /// Visibility for top-level declarations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum Visibility {
     /// Visible to the declaring file, peer library files, and consumers.
     Export,
@@ -174,7 +197,7 @@ impl Default for Visibility {
 ///
 /// This is used to track which source file AST nodes came from, enabling
 /// proper error reporting and cross-file analysis.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct SourceId(u32);
 
 impl SourceId {
@@ -190,7 +213,7 @@ impl SourceId {
 }
 
 /// Function parameter with name and type annotation.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Param {
     /// Parameter name
     pub name: Name,
@@ -198,6 +221,9 @@ pub struct Param {
     pub ty: ast::TypeRef,
     /// Whether this parameter receives markup body content for element-style invocation.
     pub is_content: bool,
+    /// Default value used when an element-style invocation omits the parameter.
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
+    pub default: Option<ExprId>,
     /// Source location
     pub span: TextSpan,
 }
@@ -214,13 +240,21 @@ impl Param {
             name,
             ty,
             is_content,
+            default: None,
             span,
         }
     }
+
+    /// Returns true when element-style invocations must supply this parameter.
+    ///
+    /// Parameters with a default value or a nullable type are optional.
+    pub fn is_required(&self) -> bool {
+        self.default.is_none() && !matches!(self.ty, ast::TypeRef::Nullable(_))
+    }
 }
 
 /// Function declaration with parameters, return type, and body.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Function {
     /// Function name
     pub name: Name,
@@ -231,6 +265,7 @@ pub struct Function {
     /// Return type annotation (None means inferred)
     pub return_type: Option<ast::TypeRef>,
     /// Function body expression
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub body: ExprId,
     /// Source location
     pub span: TextSpan,
@@ -244,7 +279,7 @@ impl Function {
 }
 
 /// Top-level value declaration.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ValueDef {
     /// Bound name
     pub name: Name,
@@ -253,13 +288,14 @@ pub struct ValueDef {
     /// Optional type annotation
     pub ty: Option<ast::TypeRef>,
     /// Initializer expression
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub value: ExprId,
     /// Source location
     pub span: TextSpan,
 }
 
 /// Type alias definition.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct TypeAlias {
     /// Alias name
     pub name: Name,
@@ -272,7 +308,7 @@ pub struct TypeAlias {
 }
 
 /// Enum member.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct EnumMember {
     /// Member name
     pub name: Name,
@@ -281,7 +317,7 @@ pub struct EnumMember {
 }
 
 /// Enum definition.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct EnumDef {
     /// Enum name
     pub name: Name,
@@ -294,7 +330,7 @@ pub struct EnumDef {
 }
 
 /// Field declared on one discriminated union case.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct UnionCaseField {
     /// Field name
     pub name: Name,
@@ -303,6 +339,7 @@ pub struct UnionCaseField {
     /// Whether this field receives markup body content for element-style construction.
     pub is_content: bool,
     /// Default value expression, if present.
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub default: Option<ExprId>,
     /// Source span
     pub span: TextSpan,
@@ -339,7 +376,7 @@ impl UnionCaseField {
 }
 
 /// One case in a discriminated union declaration.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct UnionCaseDef {
     /// Case name scoped to the owning union.
     pub name: Name,
@@ -357,7 +394,7 @@ impl UnionCaseDef {
 }
 
 /// Discriminated union definition.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct UnionDef {
     /// Union name
     pub name: Name,
@@ -372,7 +409,7 @@ pub struct UnionDef {
 }
 
 /// Record field definition.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct RecordField {
     /// Field name
     pub name: Name,
@@ -381,7 +418,11 @@ pub struct RecordField {
     /// Whether this field receives markup body content for element-style construction.
     pub is_content: bool,
     /// Default value expression (if present)
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub default: Option<ExprId>,
+    /// Validation constraints such as `@min(0)`, in source order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<FieldConstraint>,
     /// Source span
     pub span: TextSpan,
 }
@@ -400,6 +441,7 @@ impl RecordField {
             ty,
             is_content,
             default,
+            constraints: Vec::new(),
             span,
         }
     }
@@ -439,6 +481,8 @@ pub struct EffectiveField {
     pub default: Option<QualifiedExprRef>,
     /// Whether callers must provide this field explicitly.
     pub is_required: bool,
+    /// Validation constraints checked when a record is constructed.
+    pub constraints: Vec<FieldConstraint>,
     /// Source span
     pub span: TextSpan,
 }
@@ -458,6 +502,7 @@ impl EffectiveField {
             module_identity,
             default,
             is_required,
+            constraints: field.constraints,
             span: field.span,
         }
     }
@@ -474,13 +519,14 @@ impl EffectiveField {
             module_identity: module_identity.into(),
             default: None,
             is_required: field.is_required,
+            constraints: field.constraints.clone(),
             span: field.span,
         }
     }
 }
 
 /// Distinguishes ordinary records from action records.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum RecordKind {
     /// Standard `type Name = { ... }` record declaration.
     Plain,
@@ -489,7 +535,7 @@ pub enum RecordKind {
 }
 
 /// Record type definition.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct RecordDef {
     /// Record name
     pub name: Name,
@@ -501,6 +547,9 @@ pub struct RecordDef {
     pub is_abstract: bool,
     /// Optional abstract base record or alias name.
     pub base: Option<Name>,
+    /// Records whose fields are copied into this one, from `type Admin = User & { ... }`, in
+    /// source order.
+    pub includes: Vec<Name>,
     /// Property definitions
     pub properties: Vec<RecordField>,
     /// Source span
@@ -525,7 +574,7 @@ impl RecordDef {
 }
 
 /// Distinguishes inline emitted actions from shared emitted action references.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum ComponentEmitKind {
     /// `ActionName { ... }` declared inline inside `emits`.
     Inline,
@@ -534,7 +583,7 @@ pub enum ComponentEmitKind {
 }
 
 /// Metadata for a component-emitted action.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ComponentEmit {
     /// Local emitted action name used for `on<ActionName>` bindings.
     pub name: Name,
@@ -547,7 +596,7 @@ pub struct ComponentEmit {
 }
 
 /// Executable component declaration preserved in HIR.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Component {
     /// Component name
     pub name: Name,
@@ -561,11 +610,14 @@ pub struct Component {
     pub base: Option<Name>,
     /// Declared props, including optional default expressions
     pub props: Vec<RecordField>,
+    /// Name of the `...rest:props` binding that captures undeclared props, if declared.
+    pub rest: Option<Name>,
     /// Declared emitted actions
     pub emits: Vec<ComponentEmit>,
     /// Declared state fields, including optional default expressions
     pub state: Vec<RecordField>,
     /// Lowered component body expression when this is a concrete NX-bodied component.
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub body: Option<ExprId>,
     /// Source span
     pub span: TextSpan,
@@ -579,20 +631,22 @@ impl Component {
 }
 
 /// Element property (key-value pair).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Property {
     /// Property key
     pub key: Name,
     /// Property value expression
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub value: ExprId,
     /// Source location
     pub span: TextSpan,
 }
 
 /// One arm in a condition-list property fragment.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PropertyConditionArm {
     /// Boolean condition that activates this arm.
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub condition: ExprId,
     /// Property entries produced by this arm.
     pub entries: Vec<PropertyEntry>,
@@ -601,9 +655,10 @@ pub struct PropertyConditionArm {
 }
 
 /// One arm in a match-style property fragment.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PropertyMatchArm {
     /// Patterns accepted by this arm.
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub patterns: Vec<ExprId>,
     /// Property entries produced by this arm.
     pub entries: Vec<PropertyEntry>,
@@ -612,13 +667,14 @@ pub struct PropertyMatchArm {
 }
 
 /// Ordered entry in an element property list.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum PropertyEntry {
     /// Direct key/value property.
     Value(Property),
     /// Simple conditional property fragment.
     If {
         /// Boolean condition that selects the then branch.
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         condition: ExprId,
         /// Entries active when the condition is true.
         then_entries: Vec<PropertyEntry>,
@@ -639,6 +695,7 @@ pub enum PropertyEntry {
     /// Match-style property fragment.
     Match {
         /// Scrutinee matched against each arm.
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
         scrutinee: ExprId,
         /// Ordered match arms.
         arms: Vec<PropertyMatchArm>,
@@ -647,6 +704,16 @@ pub enum PropertyEntry {
         /// Source location.
         span: TextSpan,
     },
+    /// Spread of a record's fields, as in `{...rest}`.
+    ///
+    /// Directly supplied properties take precedence over spread fields with the same name.
+    Spread {
+        /// Record whose fields become properties.
+        #[serde(serialize_with = "crate::serialize::arena_ids")]
+        value: ExprId,
+        /// Source location.
+        span: TextSpan,
+    },
 }
 
 impl PropertyEntry {
@@ -656,13 +723,14 @@ impl PropertyEntry {
             PropertyEntry::Value(property) => property.span,
             PropertyEntry::If { span, .. }
             | PropertyEntry::ConditionList { span, .. }
-            | PropertyEntry::Match { span, .. } => *span,
+            | PropertyEntry::Match { span, .. }
+            | PropertyEntry::Spread { span, .. } => *span,
         }
     }
 }
 
 /// NX element (XML-like syntax).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Element {
     /// Element tag name
     pub tag: Name,
@@ -674,6 +742,7 @@ pub struct Element {
     /// Ordered property-list entries, including conditional and match fragments.
     pub property_entries: Vec<PropertyEntry>,
     /// Nested body-content expressions in source order
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
     pub content: Vec<ExprId>,
     /// Closing tag name (must match opening tag)
     pub close_name: Option<Name>,
@@ -689,7 +758,7 @@ impl Element {
 }
 
 /// Top-level item in a module.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Item {
     /// Function declaration
     Function(Function),
@@ -736,7 +805,7 @@ impl Item {
 }
 
 /// Import kind.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ImportKind {
     /// `import "<path>" [as Alias]`
     Wildcard {
@@ -751,7 +820,7 @@ pub enum ImportKind {
 }
 
 /// Individual selective import entry.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SelectiveImport {
     /// Imported symbol name
     pub name: Name,
@@ -762,7 +831,7 @@ pub struct SelectiveImport {
 }
 
 /// Lowered import statement.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Import {
     /// Library path from the import statement.
     pub library_path: String,
@@ -772,6 +841,32 @@ pub struct Import {
     pub span: TextSpan,
 }
 
+/// Module metadata declared by a `meta { ... }` block.
+///
+/// Values are literals or arrays of literals, lowered into the module's expression arena so hosts
+/// can read them without evaluating the module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModuleMetadata {
+    /// Record type the block is validated against, as in `meta TemplateInfo { ... }`.
+    pub schema: Option<Name>,
+    /// Entries in source order.
+    pub entries: Vec<MetadataEntry>,
+    /// Source span for the full block.
+    pub span: TextSpan,
+}
+
+/// One `name: value` entry of a `meta` block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MetadataEntry {
+    /// Entry name
+    pub name: Name,
+    /// Literal or array-of-literals value expression
+    #[serde(serialize_with = "crate::serialize::arena_ids")]
+    pub value: ExprId,
+    /// Source span for the entry.
+    pub span: TextSpan,
+}
+
 /// Arena index for expressions.
 pub type ExprId = Idx<ast::Expr>;
 
@@ -784,12 +879,17 @@ pub type ElementId = Idx<Element>;
 /// items (functions, type aliases, enums, records) along with the expression
 /// and element arenas. Top-level elements are represented as implicit 'root'
 /// functions rather than as separate items.
+///
+/// Lowered modules are plain data and `Send + Sync`. Analysis and evaluation only read them, so
+/// one `Arc<LoweredModule>` can be shared by any number of threads.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LoweredModule {
     /// Source file identifier
     pub source_id: SourceId,
     /// Import statements in source order.
     pub imports: Vec<Import>,
+    /// Metadata from the module's `meta` block, if it has one.
+    metadata: Option<ModuleMetadata>,
     /// Top-level items
     items: Vec<Item>,
     /// Lowering-time diagnostics
@@ -800,12 +900,18 @@ pub struct LoweredModule {
     elements: Arena<Element>,
 }
 
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<LoweredModule>();
+};
+
 impl LoweredModule {
     /// Create a new empty module.
     pub fn new(source_id: SourceId) -> Self {
         Self {
             source_id,
             imports: Vec::new(),
+            metadata: None,
             items: Vec::new(),
             diagnostics: Vec::new(),
             exprs: Arena::new(),
@@ -813,6 +919,16 @@ impl LoweredModule {
         }
     }
 
+    /// Get the metadata declared by the module's `meta` block.
+    pub fn metadata(&self) -> Option<&ModuleMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Set the module metadata.
+    pub fn set_metadata(&mut self, metadata: ModuleMetadata) {
+        self.metadata = Some(metadata);
+    }
+
     /// Get all top-level items.
     pub fn items(&self) -> &[Item] {
         &self.items
@@ -873,6 +989,11 @@ impl LoweredModule {
         &mut self.exprs[id]
     }
 
+    /// Iterates over every expression in the arena, including nested ones, in allocation order.
+    pub fn exprs(&self) -> impl Iterator<Item = (ExprId, &ast::Expr)> {
+        self.exprs.iter()
+    }
+
     /// Get the number of lowered expressions in the arena.
     pub fn expr_count(&self) -> usize {
         self.exprs.len()
@@ -888,6 +1009,11 @@ impl LoweredModule {
         &self.elements[id]
     }
 
+    /// Iterates over every element in the arena, including nested ones, in allocation order.
+    pub fn elements(&self) -> impl Iterator<Item = (ElementId, &Element)> {
+        self.elements.iter()
+    }
+
     /// Get mutable access to an element by ID.
     pub fn element_mut(&mut self, id: ElementId) -> &mut Element {
         &mut self.elements[id]
@@ -895,8 +1021,10 @@ impl LoweredModule {
 }
 
 /// Lowering diagnostic produced while converting syntax to HIR.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct LoweringDiagnostic {
+    /// Specific diagnostic code, or `None` for the generic `"lowering-error"`.
+    pub code: Option<&'static str>,
     /// Human-readable message
     pub message: String,
     /// Source span
@@ -4,11 +4,13 @@
 //! our typed High-level Intermediate Representation (HIR).
 
 use crate::ast::{
-    BinOp, Expr, Literal, MatchArm, OrderedFloat, RecordLiteralProperty, Stmt, TypeRef, UnOp,
+    BinOp, DestructurePart, Expr, Literal, MatchArm, OrderedFloat, RecordLiteralProperty, Stmt,
+    TypeRef, UnOp,
 };
 use crate::{
-    Component, ComponentEmit, ComponentEmitKind, Element, EnumDef, EnumMember, ExprId, Function,
-    Import, ImportKind, Item, LoweredModule, LoweringDiagnostic, Name, Param, Property,
+    Component, ComponentEmit, ComponentEmitKind, ConstraintNumber, Element, EnumDef, EnumMember,
+    ExprId, FieldConstraint, FieldConstraintKind, Function, Import, ImportKind, Item,
+    LoweredModule, LoweringDiagnostic, MetadataEntry, ModuleMetadata, Name, Param, Property,
     PropertyConditionArm, PropertyEntry, PropertyMatchArm, RecordDef, RecordField, RecordKind,
     SelectiveImport, SourceId, TypeAlias, UnionCaseDef, UnionCaseField, UnionDef, ValueDef,
     Visibility,
@@ -18,6 +20,12 @@ use nx_syntax::{SyntaxKind, SyntaxNode};
 use rustc_hash::FxHashMap;
 use smol_str::SmolStr;
 
+/// Maximum depth of nested expressions and markup that lowering descends into.
+///
+/// Lowering and the later stages that walk the HIR recurse once per nesting level, so deeper
+/// sources are rejected with a `nesting-too-deep` diagnostic instead of overflowing the stack.
+pub const MAX_NESTING_DEPTH: usize = 128;
+
 /// Context for lowering operations.
 ///
 /// Maintains the module being built and provides helper methods for
@@ -102,6 +110,8 @@ pub struct LoweringContext {
     predeclared_components: FxHashMap<Name, PredeclaredComponent>,
     component_emit_records: FxHashMap<Name, Vec<RecordDef>>,
     predeclared_action_records: FxHashMap<Name, RecordDef>,
+    nesting_depth: usize,
+    reported_nesting_too_deep: bool,
 }
 
 impl LoweringContext {
@@ -114,6 +124,18 @@ impl LoweringContext {
             predeclared_components: FxHashMap::default(),
             component_emit_records: FxHashMap::default(),
             predeclared_action_records: FxHashMap::default(),
+            nesting_depth: 0,
+            reported_nesting_too_deep: false,
+        }
+    }
+
+    /// Creates a context that lowers further syntax into `module`, so that it can refer to the
+    /// module's declarations. Expressions lowered by the context are allocated after the
+    /// module's own.
+    pub fn extending(module: LoweredModule) -> Self {
+        Self {
+            module,
+            ..Self::new(SourceId::new(0))
         }
     }
 
@@ -134,6 +156,60 @@ impl LoweringContext {
         self.alloc_expr(Expr::Error(span))
     }
 
+    /// Lowers a numeric literal, negated when `negate` is set.
+    ///
+    /// Integer literals keep their exact `i64` value. One that does not fit is reported as
+    /// `integer-literal-overflow` instead of being rounded to a float, and a float literal too
+    /// large for `f64` is reported as `float-literal-overflow`.
+    fn lower_number_literal(&mut self, node: SyntaxNode, negate: bool) -> ExprId {
+        let text = node.text();
+        let digits: String = text.chars().filter(|&c| c != '_').collect();
+        let value = match node.kind() {
+            kind if is_integer_literal(kind) => integer_literal_value(kind, &digits, negate),
+            _ if digits.starts_with("0x") || digits.starts_with("0X") => {
+                Some(Literal::Float(OrderedFloat(parse_hex_float(&digits[2..]))))
+            }
+            _ if digits.bytes().all(|byte| byte.is_ascii_digit()) => {
+                integer_literal_value(SyntaxKind::INT_LITERAL, &digits, negate)
+            }
+            _ => match digits.parse::<f64>() {
+                Ok(value) => Some(Literal::Float(OrderedFloat(value))),
+                Err(_) => return self.error_expr(node.span()),
+            },
+        };
+
+        let (code, message) = match value {
+            Some(Literal::Float(OrderedFloat(value))) if value.is_infinite() => (
+                "float-literal-overflow",
+                format!("Float literal '{}' is too large to represent", text),
+            ),
+            Some(literal) => {
+                let ty = match literal {
+                    Literal::Float(_) => TypeTag::Float,
+                    _ => TypeTag::Int,
+                };
+                let expr = self.alloc_expr(Expr::Literal(literal));
+                self.set_expr_type(expr, ty);
+                return expr;
+            }
+            None => (
+                "integer-literal-overflow",
+                format!(
+                    "Integer literal '{}' does not fit in a 64-bit integer ({} to {})",
+                    text,
+                    i64::MIN,
+                    i64::MAX
+                ),
+            ),
+        };
+        self.module.add_diagnostic(LoweringDiagnostic {
+            code: Some(code),
+            message,
+            span: node.span(),
+        });
+        self.error_expr(node.span())
+    }
+
     fn lower_qualified_name_expr(&mut self, node: SyntaxNode) -> ExprId {
         let mut parts = node
             .text()
@@ -179,6 +255,7 @@ impl LoweringContext {
 
     fn add_diagnostic(&mut self, message: impl Into<String>, span: TextSpan) {
         self.module.add_diagnostic(LoweringDiagnostic {
+            code: None,
             message: message.into(),
             span,
         });
@@ -585,6 +662,78 @@ impl LoweringContext {
         (field_name, ty, default, is_content)
     }
 
+    /// Lowers the `@name(...)` constraints of a property definition, reporting invalid ones.
+    fn lower_field_constraints(&mut self, prop: SyntaxNode) -> Vec<FieldConstraint> {
+        let mut constraints = Vec::new();
+        for node in prop
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::FIELD_CONSTRAINT)
+        {
+            let name = node
+                .child_by_field("name")
+                .map(|name| name.text().to_string())
+                .unwrap_or_default();
+            let mut arguments = Vec::new();
+            for argument in node
+                .children()
+                .filter(|child| child.kind() == SyntaxKind::CONSTRAINT_ARGUMENT)
+            {
+                let Some(value) = argument.child_by_field("value") else {
+                    continue;
+                };
+                let negate = argument.child_by_field("sign").is_some();
+                let digits: String = value.text().chars().filter(|&c| c != '_').collect();
+                let number = if is_integer_literal(value.kind()) {
+                    match integer_literal_value(value.kind(), &digits, negate) {
+                        Some(Literal::Int(value)) => ConstraintNumber::Int(value),
+                        _ => {
+                            self.add_diagnostic(
+                                format!(
+                                    "Constraint argument '{}' does not fit in a 64-bit integer",
+                                    argument.text()
+                                ),
+                                argument.span(),
+                            );
+                            continue;
+                        }
+                    }
+                } else {
+                    let value = digits.parse::<f64>().unwrap_or(f64::NAN);
+                    ConstraintNumber::Float(OrderedFloat(if negate { -value } else { value }))
+                };
+                arguments.push(number);
+            }
+
+            match FieldConstraintKind::new(&name, &arguments) {
+                Ok(kind) => constraints.push(FieldConstraint {
+                    kind,
+                    span: node.span(),
+                }),
+                Err(message) => self.add_diagnostic(message, node.span()),
+            }
+        }
+        constraints
+    }
+
+    /// Reports constraints written where they are never validated, on function parameters and
+    /// union case payloads, and drops them.
+    fn reject_field_constraints(
+        &mut self,
+        name: &Name,
+        constraints: Vec<FieldConstraint>,
+        place: &str,
+    ) {
+        for constraint in constraints {
+            self.add_diagnostic(
+                format!(
+                    "Constraint '{}' on {} '{}' is not checked; constraints apply to record fields",
+                    constraint.kind, place, name
+                ),
+                constraint.span,
+            );
+        }
+    }
+
     fn lower_record_fields_from_node(
         &mut self,
         node: SyntaxNode,
@@ -617,13 +766,10 @@ impl LoweringContext {
                 self.define_name(&field_name, TypeTag::from_type_ref(&ty));
             }
 
-            properties.push(RecordField::with_content(
-                field_name,
-                ty,
-                is_content,
-                default,
-                prop.span(),
-            ));
+            let mut field =
+                RecordField::with_content(field_name, ty, is_content, default, prop.span());
+            field.constraints = self.lower_field_constraints(prop);
+            properties.push(field);
         }
 
         properties
@@ -666,6 +812,7 @@ impl LoweringContext {
                             base: emit_node
                                 .child_by_field("base")
                                 .map(|base| Name::new(base.text())),
+                            includes: Vec::new(),
                             properties: self.lower_record_fields_from_node(emit_node, false),
                             span: emit_node.span(),
                         };
@@ -771,10 +918,19 @@ impl LoweringContext {
                 )
             });
 
+        let rest = node
+            .child_by_field("signature")
+            .and_then(|signature| signature.child_by_field("rest"))
+            .and_then(|rest| rest.child_by_field("name"))
+            .map(|name| Name::new(name.text()));
+
         self.push_scope();
         for prop in &props {
             self.define_name(&prop.name, TypeTag::from_type_ref(&prop.ty));
         }
+        if let Some(rest) = rest.as_ref() {
+            self.define_name(rest, TypeTag::Unknown);
+        }
 
         let state = body_node
             .and_then(|body| body.child_by_field("state"))
@@ -792,6 +948,7 @@ impl LoweringContext {
             is_external,
             base,
             props,
+            rest,
             emits,
             state,
             body,
@@ -799,7 +956,32 @@ impl LoweringContext {
         }
     }
     /// Lowers a SyntaxNode to an expression.
+    ///
+    /// Expressions nested more than [`MAX_NESTING_DEPTH`] levels deep lower to error expressions;
+    /// the first one is reported as `nesting-too-deep`.
     pub fn lower_expr(&mut self, node: SyntaxNode) -> ExprId {
+        if self.nesting_depth >= MAX_NESTING_DEPTH {
+            if !self.reported_nesting_too_deep {
+                self.reported_nesting_too_deep = true;
+                self.module.add_diagnostic(LoweringDiagnostic {
+                    code: Some("nesting-too-deep"),
+                    message: format!(
+                        "Expression is nested more than {} levels deep",
+                        MAX_NESTING_DEPTH
+                    ),
+                    span: node.span(),
+                });
+            }
+            return self.error_expr(node.span());
+        }
+
+        self.nesting_depth += 1;
+        let expr = self.lower_expr_node(node);
+        self.nesting_depth -= 1;
+        expr
+    }
+
+    fn lower_expr_node(&mut self, node: SyntaxNode) -> ExprId {
         if node.is_error() {
             return self.error_expr(node.span());
         }
@@ -813,47 +995,12 @@ impl LoweringContext {
                 expr
             }
 
-            SyntaxKind::INT_LITERAL => {
-                let text = node.text();
-                match text.parse::<i64>() {
-                    Ok(value) => {
-                        let expr = self.alloc_expr(Expr::Literal(Literal::Int(value)));
-                        self.set_expr_type(expr, TypeTag::Int);
-                        expr
-                    }
-                    Err(_) => self.error_expr(node.span()),
-                }
-            }
-
-            SyntaxKind::HEX_LITERAL => {
-                let text = node.text();
-                let digits = text.trim_start_matches("0x").trim_start_matches("0X");
-                match i64::from_str_radix(digits, 16) {
-                    Ok(value) => {
-                        let expr = self.alloc_expr(Expr::Literal(Literal::Int(value)));
-                        self.set_expr_type(expr, TypeTag::Int);
-                        expr
-                    }
-                    Err(_) => self.error_expr(node.span()),
-                }
-            }
-
-            SyntaxKind::NUMBER_LITERAL
-            | SyntaxKind::NUMBER_EXPRESSION
-            | SyntaxKind::REAL_LITERAL => {
-                let text = node.text();
-                if let Ok(value) = text.parse::<i64>() {
-                    let expr = self.alloc_expr(Expr::Literal(Literal::Int(value)));
-                    self.set_expr_type(expr, TypeTag::Int);
-                    expr
-                } else if let Ok(value) = text.parse::<f64>() {
-                    let expr = self.alloc_expr(Expr::Literal(Literal::Float(OrderedFloat(value))));
-                    self.set_expr_type(expr, TypeTag::Float);
-                    expr
-                } else {
-                    self.error_expr(node.span())
-                }
-            }
+            SyntaxKind::INT_LITERAL
+            | SyntaxKind::HEX_LITERAL
+            | SyntaxKind::BINARY_LITERAL
+            | SyntaxKind::REAL_LITERAL
+            | SyntaxKind::NUMBER_LITERAL
+            | SyntaxKind::NUMBER_EXPRESSION => self.lower_number_literal(node, false),
 
             SyntaxKind::BOOLEAN_LITERAL
             | SyntaxKind::BOOL_LITERAL
@@ -967,8 +1114,6 @@ impl LoweringContext {
                     .child_by_field("operand")
                     .or_else(|| node.children().last())
                     .unwrap();
-                let expr = self.lower_expr(expr_node);
-
                 let op = node
                     .child_by_field("operator")
                     .map(|n| match n.kind() {
@@ -998,6 +1143,14 @@ impl LoweringContext {
                         }
                     });
 
+                // `-9223372036854775808` is the one integer literal that only fits once negated.
+                if op == UnOp::Neg {
+                    if let Some(literal) = literal_fitting_only_when_negated(expr_node) {
+                        return self.lower_number_literal(literal, true);
+                    }
+                }
+                let expr = self.lower_expr(expr_node);
+
                 let expr_id = self.alloc_expr(Expr::UnaryOp {
                     op,
                     expr,
@@ -1034,14 +1187,29 @@ impl LoweringContext {
                             SyntaxKind::LPAREN | SyntaxKind::RPAREN | SyntaxKind::COMMA
                         )
                     })
-                    .map(|n| self.lower_expr(n))
-                    .collect();
+                    .map(|n| {
+                        // A `_` argument is a placeholder for partial application.
+                        if n.text() == "_" {
+                            None
+                        } else {
+                            Some(self.lower_expr(n))
+                        }
+                    })
+                    .collect::<Vec<_>>();
 
-                self.alloc_expr(Expr::Call {
-                    func,
-                    args,
-                    span: node.span(),
-                })
+                if args.iter().any(Option::is_none) {
+                    self.alloc_expr(Expr::PartialApply {
+                        func,
+                        args,
+                        span: node.span(),
+                    })
+                } else {
+                    self.alloc_expr(Expr::Call {
+                        func,
+                        args: args.into_iter().flatten().collect(),
+                        span: node.span(),
+                    })
+                }
             }
 
             // Member access
@@ -1193,6 +1361,40 @@ impl LoweringContext {
                 })
             }
 
+            SyntaxKind::PROVIDE_EXPRESSION => {
+                let key = node
+                    .child_by_field("key")
+                    .map(|n| Name::new(n.text()))
+                    .unwrap_or_else(|| Name::new("_"));
+                let value = node
+                    .child_by_field("value")
+                    .map(|n| self.lower_expr(n))
+                    .unwrap_or_else(|| self.error_expr(node.span()));
+                let body = node
+                    .child_by_field("body")
+                    .map(|n| self.lower_expr(n))
+                    .unwrap_or_else(|| self.error_expr(node.span()));
+
+                self.alloc_expr(Expr::Provide {
+                    key,
+                    value,
+                    body,
+                    span: node.span(),
+                })
+            }
+
+            SyntaxKind::USE_EXPRESSION => {
+                let key = node
+                    .child_by_field("key")
+                    .map(|n| Name::new(n.text()))
+                    .unwrap_or_else(|| Name::new("_"));
+
+                self.alloc_expr(Expr::UseContext {
+                    key,
+                    span: node.span(),
+                })
+            }
+
             // Ternary expression: condition ? consequent : alternative
             SyntaxKind::CONDITIONAL_EXPRESSION => {
                 let condition = node
@@ -1440,6 +1642,93 @@ impl LoweringContext {
         }
     }
 
+    /// Lowers `let [first, ...rest] = ...` or `let { name } = ...` into a private value holding
+    /// the destructured value, named after the pattern text, followed by one value per binding
+    /// that projects its part out of it.
+    fn lower_destructuring_definition(
+        &mut self,
+        node: SyntaxNode,
+        pattern: SyntaxNode,
+    ) -> Vec<ValueDef> {
+        let source_name = Name::new(pattern.text());
+        let ty = node
+            .child_by_field("type")
+            .map(|type_node| self.lower_type(type_node));
+        let value = node
+            .child_by_field("value")
+            .map(|value_node| self.lower_expr(value_node))
+            .unwrap_or_else(|| self.error_expr(node.span()));
+        let visibility = Self::lower_visibility(node);
+
+        let source_ty = ty
+            .as_ref()
+            .map(TypeTag::from_type_ref)
+            .unwrap_or_else(|| self.expr_type(value));
+        self.define_name(&source_name, source_ty);
+
+        let mut values = vec![ValueDef {
+            name: source_name.clone(),
+            visibility: Visibility::Private,
+            ty,
+            value,
+            span: node.span(),
+        }];
+
+        let bindings = pattern
+            .children()
+            .filter(|child| {
+                matches!(
+                    child.kind(),
+                    SyntaxKind::IDENTIFIER | SyntaxKind::REST_BINDING
+                )
+            })
+            .collect::<Vec<_>>();
+        let len = bindings
+            .iter()
+            .filter(|binding| binding.kind() == SyntaxKind::IDENTIFIER)
+            .count();
+        let has_rest = bindings.len() > len;
+
+        for (index, binding) in bindings.into_iter().enumerate() {
+            let (name_node, part) = match (pattern.kind(), binding.kind()) {
+                (SyntaxKind::RECORD_BINDING_PATTERN, _) => (
+                    Some(binding),
+                    DestructurePart::Field(Name::new(binding.text())),
+                ),
+                (_, SyntaxKind::REST_BINDING) => (
+                    binding.child_by_field("name"),
+                    DestructurePart::Rest { skip: len },
+                ),
+                _ => (
+                    Some(binding),
+                    DestructurePart::Element {
+                        index,
+                        len,
+                        has_rest,
+                    },
+                ),
+            };
+            let Some(name_node) = name_node else {
+                continue;
+            };
+
+            let name = Name::new(name_node.text());
+            let span = binding.span();
+            let source = self.alloc_expr(Expr::Ident(source_name.clone()));
+            let value = self.alloc_expr(Expr::Destructure { source, part, span });
+            self.define_name(&name, TypeTag::Unknown);
+            values.push(ValueDef {
+                name,
+                visibility,
+                ty: None,
+                value,
+                span,
+            });
+        }
+
+        values
+    }
+
     /// Lowers a record definition node.
     pub fn lower_record_definition(&mut self, node: SyntaxNode) -> RecordDef {
         self.lower_record_like_definition(node, RecordKind::Plain)
@@ -1456,6 +1745,15 @@ impl LoweringContext {
             .map(|n| Name::new(n.text()))
             .unwrap_or_else(|| Name::new("unknown"));
 
+        // A composed record declares its own fields inside the composition node.
+        let composition = node.child_by_field("composition");
+        let includes = composition
+            .iter()
+            .flat_map(|composition| composition.children())
+            .filter(|child| child.kind() == SyntaxKind::QUALIFIED_NAME)
+            .map(|include| Name::new(include.text()))
+            .collect();
+
         RecordDef {
             name,
             visibility: Self::lower_visibility(node),
@@ -1464,7 +1762,8 @@ impl LoweringContext {
             base: node
                 .child_by_field("base")
                 .map(|base| Name::new(base.text())),
-            properties: self.lower_record_fields_from_node(node, false),
+            includes,
+            properties: self.lower_record_fields_from_node(composition.unwrap_or(node), false),
             span: node.span(),
         }
     }
@@ -1530,8 +1829,12 @@ impl LoweringContext {
             .child_by_field("name")
             .map(|name| Name::new(name.text()))
             .unwrap_or_else(|| Name::new("unknown"));
-        let fields = self
-            .lower_record_fields_from_node(node, false)
+        let mut fields = self.lower_record_fields_from_node(node, false);
+        for field in &mut fields {
+            let constraints = std::mem::take(&mut field.constraints);
+            self.reject_field_constraints(&field.name, constraints, "union case field");
+        }
+        let fields = fields
             .into_iter()
             .map(UnionCaseField::from_record_field)
             .collect();
@@ -1561,9 +1864,11 @@ impl LoweringContext {
         let mut content_param_name: Option<Name> = None;
         for child in node.children() {
             if child.kind() == SyntaxKind::PROPERTY_DEFINITION {
-                let (param_name, param_type, _default, is_content) =
+                let (param_name, param_type, default, is_content) =
                     self.lower_property_definition(child);
                 let param_span = child.span();
+                let constraints = self.lower_field_constraints(child);
+                self.reject_field_constraints(&param_name, constraints, "parameter");
 
                 if is_content {
                     if let Some(existing_name) = content_param_name.as_ref() {
@@ -1580,12 +1885,9 @@ impl LoweringContext {
                     }
                 }
 
-                params.push(Param::with_content(
-                    param_name, param_type, is_content, param_span,
-                ));
-
-                // Note: Default values are part of property_definition grammar
-                // but we don't store them in Param yet (future enhancement)
+                let mut param = Param::with_content(param_name, param_type, is_content, param_span);
+                param.default = default;
+                params.push(param);
             }
         }
 
@@ -1745,8 +2047,28 @@ impl LoweringContext {
         node: SyntaxNode,
         component: Option<&PredeclaredComponent>,
     ) -> Vec<PropertyEntry> {
+        let mut entries = Vec::new();
+        for child in node.children() {
+            if child.kind() == SyntaxKind::PROPERTY_SHORTHAND {
+                entries.extend(self.lower_property_shorthand(child));
+            } else if let Some(entry) = self.lower_property_entry(child, component) {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    /// Lowers `{name, age}` into `name={name} age={age}`.
+    fn lower_property_shorthand(&mut self, node: SyntaxNode) -> Vec<PropertyEntry> {
         node.children()
-            .filter_map(|child| self.lower_property_entry(child, component))
+            .filter(|child| child.kind() == SyntaxKind::IDENTIFIER)
+            .map(|name_node| {
+                PropertyEntry::Value(Property {
+                    key: Name::new(name_node.text()),
+                    value: self.lower_expr(name_node),
+                    span: name_node.span(),
+                })
+            })
             .collect()
     }
 
@@ -1759,6 +2081,16 @@ impl LoweringContext {
             SyntaxKind::PROPERTY_VALUE => Some(PropertyEntry::Value(
                 self.lower_property_value(child, component),
             )),
+            SyntaxKind::PROPERTY_SPREAD => {
+                let value = child
+                    .child_by_field("value")
+                    .map(|n| self.lower_expr(n))
+                    .unwrap_or_else(|| self.error_expr(child.span()));
+                Some(PropertyEntry::Spread {
+                    value,
+                    span: child.span(),
+                })
+            }
             SyntaxKind::PROPERTY_LIST_IF_EXPRESSION => child
                 .children()
                 .find_map(|nested| self.lower_property_entry(nested, component)),
@@ -1914,11 +2246,59 @@ impl LoweringContext {
         }
     }
 
+    /// Lowers a `meta { ... }` block into module metadata.
+    fn lower_meta_block(&mut self, node: SyntaxNode) -> ModuleMetadata {
+        let schema = node
+            .child_by_field("schema")
+            .map(|schema| Name::new(schema.text()));
+
+        let mut entries = Vec::new();
+        for entry in node
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::META_ENTRY)
+        {
+            let Some(name) = entry.child_by_field("name") else {
+                continue;
+            };
+            let value = match entry.child_by_field("value") {
+                Some(value) => self.lower_meta_value(value),
+                None => self.error_expr(entry.span()),
+            };
+            entries.push(MetadataEntry {
+                name: Name::new(name.text()),
+                value,
+                span: entry.span(),
+            });
+        }
+
+        ModuleMetadata {
+            schema,
+            entries,
+            span: node.span(),
+        }
+    }
+
+    fn lower_meta_value(&mut self, node: SyntaxNode) -> ExprId {
+        if node.kind() != SyntaxKind::META_ARRAY {
+            return self.lower_expr(node);
+        }
+
+        let elements = node
+            .children()
+            .map(|element| self.lower_meta_value(element))
+            .collect();
+        self.alloc_expr(Expr::Array {
+            elements,
+            span: node.span(),
+        })
+    }
+
     /// Lowers a module (source file).
     pub fn lower_module(&mut self, root: SyntaxNode) {
         self.predeclare_components(root);
 
         // Process all top-level items
+        let mut implicit_roots = Vec::new();
         for child in root.children() {
             match child.kind() {
                 SyntaxKind::IMPORT_STATEMENT => {
@@ -1926,13 +2306,23 @@ impl LoweringContext {
                         self.module.imports.push(import);
                     }
                 }
+                SyntaxKind::META_BLOCK => {
+                    let metadata = self.lower_meta_block(child);
+                    self.module.set_metadata(metadata);
+                }
                 SyntaxKind::FUNCTION_DEFINITION => {
                     let func = self.lower_function(child);
                     self.module.add_item(Item::Function(func));
                 }
                 SyntaxKind::VALUE_DEFINITION => {
-                    let value = self.lower_value_definition(child);
-                    self.module.add_item(Item::Value(value));
+                    if let Some(pattern) = child.child_by_field("pattern") {
+                        for value in self.lower_destructuring_definition(child, pattern) {
+                            self.module.add_item(Item::Value(value));
+                        }
+                    } else {
+                        let value = self.lower_value_definition(child);
+                        self.module.add_item(Item::Value(value));
+                    }
                 }
                 SyntaxKind::COMPONENT_DEFINITION => {
                     let component = self.lower_component_definition(child);
@@ -1991,13 +2381,105 @@ impl LoweringContext {
                     };
 
                     self.module.add_item(Item::Function(root_func));
+                    implicit_roots.push(span);
                 }
                 _ => {
                     // Skip other node types for now
                 }
             }
         }
+
+        self.resolve_root_precedence(&implicit_roots);
+    }
+
+    /// Keeps one `root` function when top-level elements compete with each other or with an
+    /// explicit `root` function.
+    ///
+    /// An explicit `root` function takes precedence over the implicit roots created for
+    /// top-level elements, and otherwise the first element wins, so the choice never depends on
+    /// item order. Validation reports the competing definitions.
+    fn resolve_root_precedence(&mut self, implicit_roots: &[TextSpan]) {
+        let Some(root_span) = self
+            .module
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Function(func) if func.name.as_str() == "root" => Some(func.span),
+                _ => None,
+            })
+            .min_by_key(|span| implicit_roots.contains(span))
+        else {
+            return;
+        };
+
+        self.module.items.retain(|item| match item {
+            Item::Function(func) if func.name.as_str() == "root" => {
+                func.span == root_span || !implicit_roots.contains(&func.span)
+            }
+            _ => true,
+        });
+    }
+}
+
+fn is_integer_literal(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::INT_LITERAL | SyntaxKind::HEX_LITERAL | SyntaxKind::BINARY_LITERAL
+    )
+}
+
+/// Returns the value of an integer literal's digits, stripped of `_`, or `None` when the value,
+/// after negation, does not fit in an `i64`.
+fn integer_literal_value(kind: SyntaxKind, digits: &str, negate: bool) -> Option<Literal> {
+    let (digits, radix) = match kind {
+        SyntaxKind::HEX_LITERAL => (&digits[2..], 16),
+        SyntaxKind::BINARY_LITERAL => (&digits[2..], 2),
+        _ => (digits, 10),
+    };
+    let magnitude = u64::from_str_radix(digits, radix).ok()?;
+    let value = if negate {
+        0i64.checked_sub_unsigned(magnitude)?
+    } else {
+        i64::try_from(magnitude).ok()?
+    };
+    Some(Literal::Int(value))
+}
+
+/// Finds the integer literal wrapped by a negation's operand when the literal only fits in an
+/// `i64` once negated.
+fn literal_fitting_only_when_negated(operand: SyntaxNode) -> Option<SyntaxNode> {
+    let mut node = operand;
+    while !is_integer_literal(node.kind()) {
+        let mut children = node.children();
+        node = children.next()?;
+        if children.next().is_some() {
+            return None;
+        }
     }
+    let digits: String = node.text().chars().filter(|&c| c != '_').collect();
+    (integer_literal_value(node.kind(), &digits, false).is_none()
+        && integer_literal_value(node.kind(), &digits, true).is_some())
+    .then_some(node)
+}
+
+/// Parses a hex float such as `1.8p3` (the `0x` prefix and `_` already removed), returning
+/// infinity when it is too large for `f64`.
+fn parse_hex_float(text: &str) -> f64 {
+    let (mantissa, exponent) = text.split_once(['p', 'P']).unwrap_or((text, "0"));
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let mut value = 0.0;
+    for digit in whole.chars().chain(fraction.chars()) {
+        value = value * 16.0 + f64::from(digit.to_digit(16).unwrap_or(0));
+    }
+    let exponent = exponent
+        .parse::<i32>()
+        .unwrap_or(if exponent.starts_with('-') {
+            i32::MIN
+        } else {
+            i32::MAX
+        });
+    let fraction_bits = 4 * fraction.len() as i32;
+    value * 2f64.powi(exponent.saturating_sub(fraction_bits))
 }
 
 /// Lower a CST root node to a HIR LoweredModule.
@@ -2144,6 +2626,68 @@ import "./controls" as UI
         );
     }
 
+    #[test]
+    fn test_lower_reports_markup_nested_beyond_limit() {
+        let depth = MAX_NESTING_DEPTH + 20;
+        let source = format!(
+            "let <Root /> = {}x{}",
+            "<div>".repeat(depth),
+            "</div>".repeat(depth)
+        );
+        let parse_result = parse_str(&source, "deep.nx");
+        let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+
+        let diagnostics = module.diagnostics();
+        assert_eq!(diagnostics.len(), 1, "got {:?}", diagnostics);
+        assert_eq!(diagnostics[0].code, Some("nesting-too-deep"));
+    }
+
+    #[test]
+    fn test_lower_reports_overflowing_number_literals() {
+        let source = "let a() = { 9_223_372_036_854_775_808 }
+let b() = { 0xFFFF_FFFF_FFFF_FFFF }
+let c() = { 1e400 }
+let d() = { -9223372036854775808 }
+let e() = { -0b1000000000000000000000000000000000000000000000000000000000000001 }";
+        let parse_result = parse_str(source, "numbers.nx");
+        let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+
+        let codes: Vec<_> = module.diagnostics().iter().map(|diag| diag.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                Some("integer-literal-overflow"),
+                Some("integer-literal-overflow"),
+                Some("float-literal-overflow"),
+                Some("integer-literal-overflow"),
+            ],
+            "got {:?}",
+            module.diagnostics()
+        );
+        assert_eq!(
+            &source[module.diagnostics()[0].span.start().into()..][..25],
+            "9_223_372_036_854_775_808"
+        );
+    }
+
+    #[test]
+    fn test_lower_accepts_markup_nested_within_limit() {
+        let depth = MAX_NESTING_DEPTH / 2;
+        let source = format!(
+            "let <Root /> = {}x{}",
+            "<div>".repeat(depth),
+            "</div>".repeat(depth)
+        );
+        let parse_result = parse_str(&source, "nested.nx");
+        let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+
+        assert!(
+            module.diagnostics().is_empty(),
+            "got {:?}",
+            module.diagnostics()
+        );
+    }
+
     #[test]
     fn test_lower_visibility_modifiers() {
         let source = r#"private let footerText: string = "Built with NX"
@@ -2850,6 +3394,174 @@ enum Mode = light | dark"#;
         assert_eq!(ancestors, vec!["SearchAction", "InputAction"]);
     }
 
+    #[test]
+    fn test_lower_record_composition_merges_included_fields() {
+        let source = r#"
+            abstract type Entity = {
+              id: int
+            }
+
+            type User extends Entity = {
+              name: string
+              email: string?
+            }
+
+            type Audited extends Entity = {
+              createdBy: string = "system"
+            }
+
+            type Admin = User & Audited & {
+              role: string = "admin"
+            }
+        "#;
+        let parse_result = parse_str(source, "record-composition.nx");
+        let tree = parse_result.tree.expect("Should parse record composition");
+        let module = lower(tree.root(), SourceId::new(0));
+        let prepared = PreparedModule::standalone("record-composition.nx", module.clone());
+
+        let admin = module
+            .items()
+            .iter()
+            .find_map(|item| match item {
+                Item::Record(def) if def.name.as_str() == "Admin" => Some(def),
+                _ => None,
+            })
+            .expect("Should lower Admin record");
+        let includes: Vec<_> = admin.includes.iter().map(|name| name.as_str()).collect();
+        assert_eq!(includes, vec!["User", "Audited"]);
+        assert!(admin.base.is_none());
+        assert_eq!(admin.properties.len(), 1);
+
+        assert!(prepared_record_validation_messages(&module).is_empty());
+        let shape = effective_record_shape(&prepared, admin).expect("Admin shape should resolve");
+        let fields: Vec<_> = shape
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(fields, vec!["id", "name", "email", "createdBy", "role"]);
+        let ancestors: Vec<_> = shape.ancestors.iter().map(|name| name.as_str()).collect();
+        assert_eq!(ancestors, vec!["User", "Entity", "Audited"]);
+    }
+
+    #[test]
+    fn test_lower_record_composition_validation_diagnostics() {
+        let source = r#"
+            type User = {
+              name: string
+            }
+
+            type Customer = {
+              name: string
+            }
+
+            enum Color = | red | green
+
+            type Both = User & Customer & { }
+            type Missing = Nope & { }
+            type Colored = User & Color
+            type Again = User & {
+              name: string
+            }
+        "#;
+        let parse_result = parse_str(source, "record-composition-errors.nx");
+        let tree = parse_result
+            .tree
+            .expect("Should parse record composition error source");
+        let module = lower(tree.root(), SourceId::new(0));
+
+        assert!(module.diagnostics().is_empty());
+        let messages = prepared_record_validation_messages(&module);
+
+        for expected in [
+            "Record 'Both' includes field 'name' from both 'User' and 'Customer'",
+            "Record 'Missing' includes 'Nope', but 'Nope' could not be resolved",
+            "Record 'Colored' includes 'Color'",
+            "Record 'Again' redeclares inherited field 'name' from 'User'",
+        ] {
+            assert!(
+                messages.iter().any(|message| message.contains(expected)),
+                "Expected {expected:?}, got {:?}",
+                messages
+            );
+        }
+    }
+
+    #[test]
+    fn test_lower_record_default_cycle_diagnostic() {
+        let source = r#"
+            type Loop = {
+              x: int = { y }
+              y: int = { x }
+            }
+
+            type Invoice = {
+              total: int = { count }
+              count: int = 3
+            }
+        "#;
+        let parse_result = parse_str(source, "record-default-cycle.nx");
+        let tree = parse_result
+            .tree
+            .expect("Should parse record default cycle source");
+        let module = lower(tree.root(), SourceId::new(0));
+
+        assert!(module.diagnostics().is_empty());
+        let messages = prepared_record_validation_messages(&module);
+        assert_eq!(
+            messages,
+            vec!["Defaults of record 'Loop' depend on each other: x -> y -> x".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_lower_record_field_constraints() {
+        let source = r#"
+            type Person = {
+              age: int @min(0) @max(-1.5)
+              name: string @pattern(1) @minLength(-1) @maxLength(1, 2)
+            }
+            let double(count:int @min(1)): int = { count * 2 }
+            type Shape = | circle { radius: float @min(0) }
+        "#;
+        let parse_result = parse_str(source, "record-constraints.nx");
+        assert!(parse_result.errors.is_empty(), "{:?}", parse_result.errors);
+        let tree = parse_result.tree.expect("Should parse constraint source");
+        let module = lower(tree.root(), SourceId::new(0));
+
+        let Some(Item::Record(person)) = module.find_item("Person") else {
+            panic!("Person record should lower");
+        };
+        assert_eq!(
+            person.properties[0]
+                .constraints
+                .iter()
+                .map(|constraint| constraint.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                FieldConstraintKind::Min(ConstraintNumber::Int(0)),
+                FieldConstraintKind::Max(ConstraintNumber::Float(OrderedFloat(-1.5))),
+            ]
+        );
+        assert!(person.properties[1].constraints.is_empty());
+
+        let messages = module
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.message.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown constraint '@pattern'; supported constraints are '@max', '@maxLength', '@min', '@minLength'".to_string(),
+                "Constraint '@minLength' takes a non-negative integer, got -1".to_string(),
+                "Constraint '@maxLength' takes one number, got 2 arguments".to_string(),
+                "Constraint '@min(1)' on parameter 'count' is not checked; constraints apply to record fields".to_string(),
+                "Constraint '@min(0)' on union case field 'radius' is not checked; constraints apply to record fields".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn test_lower_record_inheritance_validation_diagnostics() {
         let source = r#"
@@ -3415,6 +4127,31 @@ enum Mode = light | dark"#;
         assert_eq!(enum_def.members[1].name.as_str(), "vertical");
     }
 
+    #[test]
+    fn test_lower_resolves_competing_roots_by_precedence() {
+        let roots = |source: &str| -> Vec<String> {
+            let parse_result = parse_str(source, "roots.nx");
+            let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+            module
+                .items()
+                .iter()
+                .filter_map(|item| match item {
+                    Item::Function(func) if func.name.as_str() == "root" => {
+                        Some(source[func.span.start().into()..func.span.end().into()].to_string())
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            roots("let root() = { <main /> }\n<header />"),
+            vec!["let root() = { <main /> }"]
+        );
+        assert_eq!(roots("<header />\n<footer />"), vec!["<header />"]);
+        assert_eq!(roots("<main />"), vec!["<main />"]);
+    }
+
     #[test]
     fn test_lower_simple_element() {
         let source = "<button />";
@@ -4788,4 +5525,79 @@ enum Mode = light | dark"#;
             "Expected onClick to remain a normal prop"
         );
     }
+
+    #[test]
+    fn test_lower_placeholder_arguments_as_partial_application() {
+        let source = r#"
+            let add(a:int, b:int) = { a + b }
+            let addTen() = { add(_, 10) }
+            let eleven() = { add(1, 10) }
+        "#;
+
+        let parse_result = parse_str(source, "partial-application.nx");
+        let tree = parse_result
+            .tree
+            .expect("Partial application source should parse");
+        let module = lower(tree.root(), SourceId::new(0));
+        let body = |name: &str| {
+            module
+                .items()
+                .iter()
+                .find_map(|item| match item {
+                    Item::Function(function) if function.name.as_str() == name => {
+                        Some(module.expr(function.body))
+                    }
+                    _ => None,
+                })
+                .expect("Expected function")
+        };
+
+        match body("addTen") {
+            Expr::PartialApply { args, .. } => {
+                assert!(args[0].is_none(), "Expected `_` to stay open");
+                assert!(
+                    matches!(args[1].map(|arg| module.expr(arg)), Some(Expr::Literal(_))),
+                    "Expected the supplied argument to be lowered"
+                );
+            }
+            other => panic!("Expected partial application, got {:?}", other),
+        }
+        assert!(matches!(body("eleven"), Expr::Call { .. }));
+    }
+
+    #[test]
+    fn test_lower_property_shorthand_as_properties_named_after_locals() {
+        let source = r#"
+            type User = { name:string age:int }
+            let make(name:string, age:int) = <User {name, age} />
+        "#;
+
+        let parse_result = parse_str(source, "property-shorthand.nx");
+        let tree = parse_result
+            .tree
+            .expect("Property shorthand source should parse");
+        let module = lower(tree.root(), SourceId::new(0));
+
+        let make = module
+            .items()
+            .iter()
+            .find_map(|item| match item {
+                Item::Function(function) if function.name.as_str() == "make" => Some(function),
+                _ => None,
+            })
+            .expect("Expected make function");
+        let properties = match module.expr(make.body) {
+            Expr::RecordLiteral { properties, .. } => properties,
+            other => panic!("Expected record literal, got {:?}", other),
+        };
+
+        let fields: Vec<_> = properties
+            .iter()
+            .map(|property| match module.expr(property.value) {
+                Expr::Ident(name) => (property.name.as_str(), name.as_str()),
+                other => panic!("Expected identifier value, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(fields, vec![("name", "name"), ("age", "age")]);
+    }
 }
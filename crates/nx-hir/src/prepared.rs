@@ -70,6 +70,7 @@ pub struct InterfaceParam {
     pub name: Name,
     pub ty: ast::TypeRef,
     pub is_content: bool,
+    pub is_required: bool,
     pub span: TextSpan,
 }
 
@@ -80,6 +81,7 @@ pub struct InterfaceField {
     pub ty: ast::TypeRef,
     pub is_content: bool,
     pub is_required: bool,
+    pub constraints: Vec<crate::FieldConstraint>,
     pub span: TextSpan,
 }
 
@@ -115,6 +117,7 @@ pub enum InterfaceItemKind {
         is_external: bool,
         base: Option<Name>,
         props: Vec<InterfaceField>,
+        rest: Option<Name>,
         emits: Vec<ComponentEmit>,
         state: Vec<InterfaceField>,
         span: TextSpan,
@@ -136,6 +139,7 @@ pub enum InterfaceItemKind {
         kind: RecordKind,
         is_abstract: bool,
         base: Option<Name>,
+        includes: Vec<Name>,
         properties: Vec<InterfaceField>,
         span: TextSpan,
     },
@@ -368,6 +372,17 @@ impl PreparedModule {
         self.peer_modules.insert(module_identity.into(), module);
     }
 
+    /// Returns the raw module with `module_identity`: this module or one of its peers.
+    pub fn module_for_identity(&self, module_identity: &str) -> Option<&LoweredModule> {
+        if module_identity == self.module_identity {
+            Some(&self.raw_module)
+        } else {
+            self.peer_modules
+                .get(module_identity)
+                .map(|module| module.as_ref())
+        }
+    }
+
     /// Inserts one prepared visible binding.
     pub fn insert_binding(&mut self, binding: PreparedBinding) {
         self.bindings
@@ -511,6 +526,7 @@ pub fn interface_record(item: &InterfaceItem) -> Option<crate::RecordDef> {
             kind,
             is_abstract,
             base,
+            includes,
             properties,
             span,
         } => Some(crate::RecordDef {
@@ -519,6 +535,7 @@ pub fn interface_record(item: &InterfaceItem) -> Option<crate::RecordDef> {
             kind: *kind,
             is_abstract: *is_abstract,
             base: base.clone(),
+            includes: includes.clone(),
             properties: properties
                 .iter()
                 .map(|field| RecordField {
@@ -526,6 +543,7 @@ pub fn interface_record(item: &InterfaceItem) -> Option<crate::RecordDef> {
                     ty: field.ty.clone(),
                     is_content: field.is_content,
                     default: None,
+                    constraints: field.constraints.clone(),
                     span: field.span,
                 })
                 .collect(),
@@ -543,6 +561,7 @@ pub fn interface_component(item: &InterfaceItem) -> Option<Component> {
             is_external,
             base,
             props,
+            rest,
             emits,
             state,
             span,
@@ -559,9 +578,11 @@ pub fn interface_component(item: &InterfaceItem) -> Option<Component> {
                     ty: field.ty.clone(),
                     is_content: field.is_content,
                     default: None,
+                    constraints: field.constraints.clone(),
                     span: field.span,
                 })
                 .collect(),
+            rest: rest.clone(),
             emits: emits.clone(),
             state: state
                 .iter()
@@ -570,6 +591,7 @@ pub fn interface_component(item: &InterfaceItem) -> Option<Component> {
                     ty: field.ty.clone(),
                     is_content: field.is_content,
                     default: None,
+                    constraints: field.constraints.clone(),
                     span: field.span,
                 })
                 .collect(),
@@ -638,11 +660,13 @@ pub fn interface_function_signature(
             item.visibility,
             params
                 .iter()
-                .map(|param| Param {
-                    name: param.name.clone(),
-                    ty: param.ty.clone(),
-                    is_content: param.is_content,
-                    span: param.span,
+                .map(|param| {
+                    Param::with_content(
+                        param.name.clone(),
+                        param.ty.clone(),
+                        param.is_content,
+                        param.span,
+                    )
                 })
                 .collect(),
             return_type.clone(),
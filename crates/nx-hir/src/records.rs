@@ -58,6 +58,30 @@ pub enum RecordResolutionError {
         field: Name,
         span: TextSpan,
     },
+    InvalidInclude {
+        record: Name,
+        include: Name,
+        span: TextSpan,
+        reason: InvalidBaseReason,
+    },
+    ConflictingIncludedField {
+        record: Name,
+        field: Name,
+        first_owner: Name,
+        second_owner: Name,
+        span: TextSpan,
+    },
+    ConflictingIncludedContent {
+        record: Name,
+        first_owner: Name,
+        second_owner: Name,
+        span: TextSpan,
+    },
+    DefaultCycle {
+        record: Name,
+        span: TextSpan,
+        cycle: Vec<Name>,
+    },
 }
 
 impl RecordResolutionError {
@@ -77,6 +101,21 @@ impl RecordResolutionError {
             RecordResolutionError::DuplicateContentProperty { .. } => {
                 "record-duplicate-content-property"
             }
+            RecordResolutionError::InvalidInclude { reason, .. } => match reason {
+                InvalidBaseReason::NotFound => "record-include-not-found",
+                InvalidBaseReason::NotRecord | InvalidBaseReason::ConcreteRecord => {
+                    "record-include-not-record"
+                }
+                InvalidBaseReason::AliasCycle => "record-include-alias-cycle",
+                InvalidBaseReason::KindMismatch { .. } => "record-include-kind-mismatch",
+            },
+            RecordResolutionError::ConflictingIncludedField { .. } => {
+                "record-include-field-conflict"
+            }
+            RecordResolutionError::ConflictingIncludedContent { .. } => {
+                "record-include-content-conflict"
+            }
+            RecordResolutionError::DefaultCycle { .. } => "record-default-cycle",
         }
     }
 
@@ -163,6 +202,59 @@ impl RecordResolutionError {
                     )
                 }
             }
+            RecordResolutionError::InvalidInclude {
+                record,
+                include,
+                reason,
+                ..
+            } => match reason {
+                InvalidBaseReason::NotFound => format!(
+                    "Record '{}' includes '{}', but '{}' could not be resolved",
+                    record, include, include
+                ),
+                InvalidBaseReason::NotRecord | InvalidBaseReason::ConcreteRecord => format!(
+                    "Record '{}' includes '{}', but '{}' does not resolve to a record declaration",
+                    record, include, include
+                ),
+                InvalidBaseReason::AliasCycle => format!(
+                    "Record '{}' includes '{}', but resolving '{}' encountered a type alias cycle",
+                    record, include, include
+                ),
+                InvalidBaseReason::KindMismatch { .. } => format!(
+                    "Record '{}' includes '{}', but actions cannot be included in records",
+                    record, include
+                ),
+            },
+            RecordResolutionError::ConflictingIncludedField {
+                record,
+                field,
+                first_owner,
+                second_owner,
+                ..
+            } => format!(
+                "Record '{}' includes field '{}' from both '{}' and '{}'",
+                record, field, first_owner, second_owner
+            ),
+            RecordResolutionError::ConflictingIncludedContent {
+                record,
+                first_owner,
+                second_owner,
+                ..
+            } => format!(
+                "Record '{}' includes a content property from both '{}' and '{}', but a record has at most one content property",
+                record, first_owner, second_owner
+            ),
+            RecordResolutionError::DefaultCycle { record, cycle, .. } => {
+                let chain = cycle
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                format!(
+                    "Defaults of record '{}' depend on each other: {}",
+                    record, chain
+                )
+            }
         }
     }
 
@@ -171,7 +263,11 @@ impl RecordResolutionError {
             RecordResolutionError::InvalidBase { span, .. }
             | RecordResolutionError::InheritanceCycle { span, .. }
             | RecordResolutionError::DuplicateInheritedField { span, .. }
-            | RecordResolutionError::DuplicateContentProperty { span, .. } => *span,
+            | RecordResolutionError::DuplicateContentProperty { span, .. }
+            | RecordResolutionError::InvalidInclude { span, .. }
+            | RecordResolutionError::ConflictingIncludedField { span, .. }
+            | RecordResolutionError::ConflictingIncludedContent { span, .. }
+            | RecordResolutionError::DefaultCycle { span, .. } => *span,
         }
     }
 }
@@ -396,17 +492,29 @@ fn validate_record_definition(
 
     stack.push(record.name.clone());
 
-    let status = match resolve_base_record(module, record) {
-        Ok(Some(base_record)) => {
-            if validate_record_definition(module, &base_record.record, statuses, stack, errors)
-                == RecordValidationStatus::Invalid
-            {
-                RecordValidationStatus::Invalid
-            } else {
+    // The base and the included records are validated first, so a broken one is reported once
+    // rather than again for every record built on it.
+    let parents = resolve_base_record(module, record).and_then(|base_record| {
+        let mut parents: Vec<_> = base_record.into_iter().collect();
+        parents.extend(resolve_included_records(module, record)?);
+        Ok(parents)
+    });
+    let status = match parents {
+        Ok(parents) => {
+            let mut status = RecordValidationStatus::Valid;
+            for parent in &parents {
+                if validate_record_definition(module, &parent.record, statuses, stack, errors)
+                    == RecordValidationStatus::Invalid
+                {
+                    status = RecordValidationStatus::Invalid;
+                }
+            }
+            if status == RecordValidationStatus::Valid {
                 validate_record_shape(module, record, errors)
+            } else {
+                status
             }
         }
-        Ok(None) => validate_record_shape(module, record, errors),
         Err(error) => {
             push_unique_record_error(errors, error);
             RecordValidationStatus::Invalid
@@ -424,7 +532,31 @@ fn validate_record_shape(
     errors: &mut Vec<RecordResolutionError>,
 ) -> RecordValidationStatus {
     match effective_record_shape(module, record) {
-        Ok(_) => RecordValidationStatus::Valid,
+        Ok(shape) => {
+            // A cycle is reported by the record that declares one of its fields, not again by
+            // every record that inherits it.
+            match default_evaluation_order(&shape.fields, |field| {
+                default_referenced_names(module, field)
+            }) {
+                Err(cycle)
+                    if record
+                        .properties
+                        .iter()
+                        .any(|property| cycle.contains(&property.name)) =>
+                {
+                    push_unique_record_error(
+                        errors,
+                        RecordResolutionError::DefaultCycle {
+                            record: record.name.clone(),
+                            span: record.span,
+                            cycle,
+                        },
+                    );
+                    RecordValidationStatus::Invalid
+                }
+                _ => RecordValidationStatus::Valid,
+            }
+        }
         Err(error) => {
             push_unique_record_error(errors, error);
             RecordValidationStatus::Invalid
@@ -432,6 +564,78 @@ fn validate_record_shape(
     }
 }
 
+/// Returns the order in which the defaults of `fields` are evaluated, as indices into `fields`.
+///
+/// A default may read any field of the record. The fields it names, as reported by
+/// `referenced_names`, come before it; otherwise fields keep their declaration order, base
+/// fields first. When defaults depend on each other in a cycle, the cycle is returned as the
+/// field names along it, starting and ending with the same field.
+pub fn default_evaluation_order(
+    fields: &[EffectiveField],
+    mut referenced_names: impl FnMut(&EffectiveField) -> FxHashSet<Name>,
+) -> Result<Vec<usize>, Vec<Name>> {
+    let dependencies = fields
+        .iter()
+        .map(|field| {
+            if field.default.is_none() {
+                return Vec::new();
+            }
+            let names = referenced_names(field);
+            fields
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| names.contains(&other.name))
+                .map(|(index, _)| index)
+                .collect()
+        })
+        .collect::<Vec<Vec<usize>>>();
+
+    fn visit(
+        index: usize,
+        dependencies: &[Vec<usize>],
+        fields: &[EffectiveField],
+        visiting: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<(), Vec<Name>> {
+        if order.contains(&index) {
+            return Ok(());
+        }
+        if let Some(start) = visiting.iter().position(|visited| *visited == index) {
+            let mut cycle = visiting[start..]
+                .iter()
+                .map(|visited| fields[*visited].name.clone())
+                .collect::<Vec<_>>();
+            cycle.push(fields[index].name.clone());
+            return Err(cycle);
+        }
+        visiting.push(index);
+        for dependency in &dependencies[index] {
+            visit(*dependency, dependencies, fields, visiting, order)?;
+        }
+        visiting.pop();
+        order.push(index);
+        Ok(())
+    }
+
+    let mut order = Vec::with_capacity(fields.len());
+    for index in 0..fields.len() {
+        visit(index, &dependencies, fields, &mut Vec::new(), &mut order)?;
+    }
+    Ok(order)
+}
+
+/// Returns the names read by the default of `field`, or none when its expression is not
+/// available, as for records imported from a library interface.
+fn default_referenced_names(module: &PreparedModule, field: &EffectiveField) -> FxHashSet<Name> {
+    let mut names = FxHashSet::default();
+    if let Some(default) = field.default.as_ref() {
+        if let Some(owner) = module.module_for_identity(&default.module_identity) {
+            crate::walk::collect_referenced_names(owner, default.expr_id, &mut names);
+        }
+    }
+    names
+}
+
 fn push_unique_record_error(errors: &mut Vec<RecordResolutionError>, error: RecordResolutionError) {
     if !errors.contains(&error) {
         errors.push(error);
@@ -502,16 +706,49 @@ fn resolve_record_shape_inner(
     }
 
     stack.push(record.record.name.clone());
+    let result = build_record_shape(module, record, stack);
+    stack.pop();
+    result
+}
 
-    let result = if let Some(base_record) = resolve_base_record(module, &record.record)? {
+/// Collects the fields of `record`: those of its base, then those of each included record in
+/// order, then its own.
+fn build_record_shape(
+    module: &PreparedModule,
+    record: &ResolvedRecordDefinition,
+    stack: &mut Vec<Name>,
+) -> Result<ResolvedRecordShape, RecordResolutionError> {
+    let mut fields = Vec::new();
+    let mut ancestors = Vec::new();
+
+    if let Some(base_record) = resolve_base_record(module, &record.record)? {
         let base_shape = resolve_record_shape_inner(module, &base_record, stack)?;
-        let mut fields = base_shape.fields;
-        let declared_fields = record.declared_fields();
+        fields = base_shape.fields;
+        ancestors.push(base_record.record.name.clone());
+        ancestors.extend(base_shape.ancestors);
+    }
 
-        for field in &declared_fields {
+    // An included record counts as an ancestor, so the composed record can be used wherever one
+    // of its parts is expected.
+    for included in resolve_included_records(module, &record.record)? {
+        let included_shape = resolve_record_shape_inner(module, &included, stack)?;
+        for field in included_shape.fields {
+            merge_included_field(&record.record, &mut fields, field)?;
+        }
+        for ancestor in
+            std::iter::once(included.record.name.clone()).chain(included_shape.ancestors)
+        {
+            if !ancestors.contains(&ancestor) {
+                ancestors.push(ancestor);
+            }
+        }
+    }
+
+    let inherits = record.record.base.is_some() || !record.record.includes.is_empty();
+    for field in record.declared_fields() {
+        if inherits {
             if field.is_content {
                 if let Some(existing) = fields.iter().find(|existing| existing.field.is_content) {
-                    stack.pop();
                     return Err(RecordResolutionError::DuplicateContentProperty {
                         record: record.record.name.clone(),
                         existing_field: existing.field.name.clone(),
@@ -526,7 +763,6 @@ fn resolve_record_shape_inner(
                 .iter()
                 .find(|existing| existing.field.name == field.name)
             {
-                stack.pop();
                 return Err(RecordResolutionError::DuplicateInheritedField {
                     record: record.record.name.clone(),
                     field: field.name.clone(),
@@ -534,39 +770,62 @@ fn resolve_record_shape_inner(
                     span: field.span,
                 });
             }
-
-            fields.push(OwnedRecordField {
-                field: field.clone(),
-                owner: record.record.name.clone(),
-            });
         }
 
-        let mut ancestors = vec![base_record.record.name.clone()];
-        ancestors.extend(base_shape.ancestors);
+        fields.push(OwnedRecordField {
+            field,
+            owner: record.record.name.clone(),
+        });
+    }
 
-        ResolvedRecordShape {
-            record: record.record.clone(),
-            fields,
-            ancestors,
+    Ok(ResolvedRecordShape {
+        record: record.record.clone(),
+        fields,
+        ancestors,
+    })
+}
+
+/// Adds a field of an included record to `fields`.
+///
+/// A field that is already present from the same declaration, as when two included records share
+/// an abstract base, is kept once. Any other field of the same name, or a second content
+/// property, is a conflict.
+fn merge_included_field(
+    record: &RecordDef,
+    fields: &mut Vec<OwnedRecordField>,
+    field: OwnedRecordField,
+) -> Result<(), RecordResolutionError> {
+    if let Some(existing) = fields
+        .iter()
+        .find(|existing| existing.field.name == field.field.name)
+    {
+        if existing.owner == field.owner
+            && existing.field.module_identity == field.field.module_identity
+        {
+            return Ok(());
         }
-    } else {
-        let declared_fields = record.declared_fields();
-        ResolvedRecordShape {
-            record: record.record.clone(),
-            fields: declared_fields
-                .iter()
-                .cloned()
-                .map(|field| OwnedRecordField {
-                    field,
-                    owner: record.record.name.clone(),
-                })
-                .collect(),
-            ancestors: Vec::new(),
+        return Err(RecordResolutionError::ConflictingIncludedField {
+            record: record.name.clone(),
+            field: field.field.name,
+            first_owner: existing.owner.clone(),
+            second_owner: field.owner,
+            span: record.span,
+        });
+    }
+
+    if field.field.is_content {
+        if let Some(existing) = fields.iter().find(|existing| existing.field.is_content) {
+            return Err(RecordResolutionError::ConflictingIncludedContent {
+                record: record.name.clone(),
+                first_owner: existing.owner.clone(),
+                second_owner: field.owner,
+                span: record.span,
+            });
         }
-    };
+    }
 
-    stack.pop();
-    Ok(result)
+    fields.push(field);
+    Ok(())
 }
 
 fn resolve_base_record(
@@ -577,52 +836,79 @@ fn resolve_base_record(
         return Ok(None);
     };
 
-    let mut seen = FxHashSet::default();
-    resolve_base_record_inner(module, record, base_name, &mut seen).map(Some)
+    let base_record = resolve_record_reference(module, base_name, &mut FxHashSet::default())
+        .map_err(|reason| invalid_base(record, base_name, reason))?;
+    validate_base_record(record, base_name, &base_record).map(Some)
 }
 
-fn resolve_base_record_inner(
+fn resolve_included_records(
     module: &PreparedModule,
     record: &RecordDef,
-    base_name: &Name,
+) -> Result<Vec<ResolvedRecordDefinition>, RecordResolutionError> {
+    record
+        .includes
+        .iter()
+        .map(|include| {
+            let included = resolve_record_reference(module, include, &mut FxHashSet::default())
+                .map_err(|reason| invalid_include(record, include, reason))?;
+            if included.record.kind != record.kind {
+                return Err(invalid_include(
+                    record,
+                    include,
+                    InvalidBaseReason::KindMismatch {
+                        expected: record.kind,
+                        found: included.record.kind,
+                    },
+                ));
+            }
+            Ok(included)
+        })
+        .collect()
+}
+
+/// Resolves `name` to a record definition, following type aliases.
+fn resolve_record_reference(
+    module: &PreparedModule,
+    name: &Name,
     seen: &mut FxHashSet<Name>,
-) -> Result<ResolvedRecordDefinition, RecordResolutionError> {
-    if !seen.insert(base_name.clone()) {
-        return Err(RecordResolutionError::InvalidBase {
-            record: record.name.clone(),
-            record_kind: record.kind,
-            base: record.base.clone().unwrap_or_else(|| base_name.clone()),
-            span: record.span,
-            reason: InvalidBaseReason::AliasCycle,
-        });
+) -> Result<ResolvedRecordDefinition, InvalidBaseReason> {
+    if !seen.insert(name.clone()) {
+        return Err(InvalidBaseReason::AliasCycle);
     }
 
     let result = match module
-        .resolve_binding(PreparedNamespace::Type, base_name)
+        .resolve_binding(PreparedNamespace::Type, name)
         .and_then(|binding| module.resolve_prepared_item(binding))
     {
         Some(resolved) => {
-            if let Some(base_record) =
-                record_definition_from_prepared_item(module, resolved.clone())
-            {
-                validate_base_record(record, base_name, &base_record)
+            if let Some(record) = record_definition_from_prepared_item(module, resolved.clone()) {
+                Ok(record)
             } else if let Some(target) = type_alias_target_from_prepared_item(&resolved) {
-                resolve_base_record_inner(module, record, &target, seen)
+                resolve_record_reference(module, &target, seen)
             } else {
-                Err(invalid_base(
-                    record,
-                    base_name,
-                    InvalidBaseReason::NotRecord,
-                ))
+                Err(InvalidBaseReason::NotRecord)
             }
         }
-        None => Err(invalid_base(record, base_name, InvalidBaseReason::NotFound)),
+        None => Err(InvalidBaseReason::NotFound),
     };
 
-    seen.remove(base_name);
+    seen.remove(name);
     result
 }
 
+fn invalid_include(
+    record: &RecordDef,
+    include: &Name,
+    reason: InvalidBaseReason,
+) -> RecordResolutionError {
+    RecordResolutionError::InvalidInclude {
+        record: record.name.clone(),
+        include: include.clone(),
+        span: record.span,
+        reason,
+    }
+}
+
 fn invalid_base(
     record: &RecordDef,
     base_name: &Name,
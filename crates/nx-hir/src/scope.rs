@@ -4,11 +4,12 @@
 //! definitions, tracking scopes, and detecting undefined references.
 
 use crate::{
-    ast, ExprId, Item, Name, PreparedItemKind, PreparedModule, PreparedNamespace, PropertyEntry,
+    ast, ExprId, InterfaceItemKind, Item, Name, PreparedItemKind, PreparedModule,
+    PreparedNamespace, PropertyEntry, ResolvedPreparedItem,
 };
 use la_arena::{Arena, Idx};
-use nx_diagnostics::{Diagnostic, Label, TextSpan};
-use rustc_hash::FxHashMap;
+use nx_diagnostics::{suggest, Diagnostic, Label, TextSpan};
+use rustc_hash::{FxHashMap, FxHashSet};
 
 /// Index into the scope arena.
 pub type ScopeId = Idx<Scope>;
@@ -28,6 +29,8 @@ pub enum SymbolKind {
     Type,
     /// An enum member
     EnumMember,
+    /// A builtin function provided by the runtime
+    Builtin,
 }
 
 impl SymbolKind {
@@ -40,6 +43,7 @@ impl SymbolKind {
             SymbolKind::Parameter => "parameter",
             SymbolKind::Type => "type",
             SymbolKind::EnumMember => "enum member",
+            SymbolKind::Builtin => "builtin",
         }
     }
 }
@@ -206,6 +210,38 @@ impl ScopeManager {
         None
     }
 
+    /// Returns the names visible from the given scope, innermost scope first.
+    ///
+    /// Names within one scope are sorted so callers that pick the first of several equally good
+    /// candidates behave deterministically. Shadowed names appear once.
+    pub fn visible_names(&self, scope: ScopeId) -> Vec<&Name> {
+        self.visible_symbols(scope)
+            .into_iter()
+            .map(|symbol| &symbol.name)
+            .collect()
+    }
+
+    /// Returns the symbols visible from `scope`, in the same order as
+    /// [`visible_names`](Self::visible_names).
+    pub fn visible_symbols(&self, scope: ScopeId) -> Vec<&Symbol> {
+        let mut symbols: Vec<&Symbol> = Vec::new();
+        let mut seen: FxHashSet<&Name> = FxHashSet::default();
+        let mut current = Some(scope);
+
+        while let Some(scope_id) = current {
+            let scope = &self.scopes[scope_id];
+            let mut local: Vec<&Symbol> = scope
+                .symbols()
+                .filter(|symbol| seen.insert(&symbol.name))
+                .collect();
+            local.sort_by(|a, b| a.name.as_str().cmp(b.name.as_str()));
+            symbols.extend(local);
+            current = scope.parent;
+        }
+
+        symbols
+    }
+
     /// Defines a symbol in the given scope.
     pub fn define(&mut self, scope: ScopeId, symbol: Symbol) {
         self.scopes[scope].define(symbol);
@@ -258,6 +294,9 @@ pub fn build_scopes(module: &PreparedModule) -> (ScopeManager, Vec<Diagnostic>)
 }
 
 /// Checks all identifier expressions in a prepared module and reports undefined references.
+///
+/// Functions and builtins are only values once partially applied, so a bare function name
+/// outside a call, as in `map(xs, double)`, is reported as well.
 pub fn check_undefined_identifiers(
     module: &PreparedModule,
     scope_manager: &ScopeManager,
@@ -306,6 +345,9 @@ struct UndefinedIdentifierChecker<'a> {
     module: &'a PreparedModule,
     scope_manager: ScopeManager,
     diagnostics: Vec<Diagnostic>,
+    /// Span of the innermost expression being checked that records one. Identifiers do not
+    /// record their own span, so their diagnostics point here.
+    enclosing_span: TextSpan,
 }
 
 impl<'a> UndefinedIdentifierChecker<'a> {
@@ -314,6 +356,7 @@ impl<'a> UndefinedIdentifierChecker<'a> {
             module,
             scope_manager: scope_manager.clone(),
             diagnostics: Vec::new(),
+            enclosing_span: TextSpan::default(),
         }
     }
 
@@ -325,14 +368,17 @@ impl<'a> UndefinedIdentifierChecker<'a> {
         for item in self.module.raw_module().items() {
             match item {
                 Item::Function(function) => {
+                    self.enclosing_span = function.span;
                     let scope = self.scope_manager.create_child(self.scope_manager.root());
                     self.define_params(scope, &function.params);
                     self.check_expr(function.body, scope);
                 }
                 Item::Value(value) => {
+                    self.enclosing_span = value.span;
                     self.check_expr(value.value, self.scope_manager.root());
                 }
                 Item::Component(component) => {
+                    self.enclosing_span = component.span;
                     let scope = self.scope_manager.create_child(self.scope_manager.root());
                     let mut symbols =
                         crate::effective_component_contract_for_name(self.module, &component.name)
@@ -354,6 +400,9 @@ impl<'a> UndefinedIdentifierChecker<'a> {
                                     })
                                     .collect::<Vec<_>>()
                             });
+                    if let Some(rest) = component.rest.as_ref() {
+                        symbols.push((rest.clone(), SymbolKind::Parameter, component.span));
+                    }
                     symbols.extend(
                         component
                             .state
@@ -367,7 +416,29 @@ impl<'a> UndefinedIdentifierChecker<'a> {
                         self.check_expr(body, scope);
                     }
                 }
-                Item::TypeAlias(_) | Item::Enum(_) | Item::Union(_) | Item::Record(_) => {}
+                Item::Record(record) => {
+                    if record.properties.iter().all(|prop| prop.default.is_none()) {
+                        continue;
+                    }
+                    self.enclosing_span = record.span;
+                    // Defaults may read any field of the record, inherited ones included.
+                    let scope = self.scope_manager.create_child(self.scope_manager.root());
+                    let fields = crate::effective_record_shape(self.module, record)
+                        .map(|shape| shape.fields)
+                        .unwrap_or_default();
+                    for field in fields {
+                        self.scope_manager_define(
+                            scope,
+                            field.name,
+                            SymbolKind::Parameter,
+                            field.span,
+                        );
+                    }
+                    for default in record.properties.iter().filter_map(|prop| prop.default) {
+                        self.check_expr(default, scope);
+                    }
+                }
+                Item::TypeAlias(_) | Item::Enum(_) | Item::Union(_) => {}
             }
         }
     }
@@ -413,11 +484,30 @@ impl<'a> UndefinedIdentifierChecker<'a> {
     }
 
     fn check_expr(&mut self, expr_id: ExprId, scope: ScopeId) {
+        let span = self.module.raw_module().expr(expr_id).span();
+        let enclosing_span = self.enclosing_span;
+        if !span.is_empty() {
+            self.enclosing_span = span;
+        }
+        self.check_expr_kind(expr_id, scope);
+        self.enclosing_span = enclosing_span;
+    }
+
+    fn check_expr_kind(&mut self, expr_id: ExprId, scope: ScopeId) {
         match self.module.raw_module().expr(expr_id) {
-            ast::Expr::Literal(_) | ast::Expr::Error(_) => {}
+            ast::Expr::Literal(_) | ast::Expr::UseContext { .. } | ast::Expr::Error(_) => {}
             ast::Expr::Ident(name) => {
-                if self.scope_manager.resolve(name, scope).is_none() {
-                    self.report_undefined(name, self.module.raw_module().expr(expr_id).span());
+                let span = self.enclosing_span;
+                match self
+                    .scope_manager
+                    .resolve(name, scope)
+                    .map(|symbol| symbol.kind)
+                {
+                    None => self.report_undefined(name, span, scope),
+                    Some(kind @ (SymbolKind::Function | SymbolKind::Builtin)) => {
+                        self.report_function_value(name, kind, span);
+                    }
+                    Some(_) => {}
                 }
             }
             ast::Expr::BinaryOp { lhs, rhs, .. } => {
@@ -428,11 +518,17 @@ impl<'a> UndefinedIdentifierChecker<'a> {
                 self.check_expr(*expr, scope);
             }
             ast::Expr::Call { func, args, .. } => {
-                self.check_expr(*func, scope);
+                self.check_callee(*func, scope);
                 for arg in args {
                     self.check_expr(*arg, scope);
                 }
             }
+            ast::Expr::PartialApply { func, args, .. } => {
+                self.check_callee(*func, scope);
+                for arg in args.iter().flatten() {
+                    self.check_expr(*arg, scope);
+                }
+            }
             ast::Expr::If {
                 condition,
                 then_branch,
@@ -552,6 +648,13 @@ impl<'a> UndefinedIdentifierChecker<'a> {
                 }
                 self.check_expr(*body, for_scope);
             }
+            ast::Expr::Destructure { source, .. } => {
+                self.check_expr(*source, scope);
+            }
+            ast::Expr::Provide { value, body, .. } => {
+                self.check_expr(*value, scope);
+                self.check_expr(*body, scope);
+            }
             ast::Expr::Let {
                 name, value, body, ..
             } => {
@@ -574,6 +677,9 @@ impl<'a> UndefinedIdentifierChecker<'a> {
                 PropertyEntry::Value(property) => {
                     self.check_expr(property.value, scope);
                 }
+                PropertyEntry::Spread { value, .. } => {
+                    self.check_expr(*value, scope);
+                }
                 PropertyEntry::If {
                     condition,
                     then_entries,
@@ -612,17 +718,89 @@ impl<'a> UndefinedIdentifierChecker<'a> {
         }
     }
 
-    fn report_undefined(&mut self, name: &Name, span: TextSpan) {
+    /// Checks the function of a call, where a function name is not used as a value.
+    fn check_callee(&mut self, func: ExprId, scope: ScopeId) {
+        match self.module.raw_module().expr(func) {
+            ast::Expr::Ident(name) => {
+                if self.scope_manager.resolve(name, scope).is_none() {
+                    self.report_undefined(name, self.enclosing_span, scope);
+                }
+            }
+            _ => self.check_expr(func, scope),
+        }
+    }
+
+    fn report_function_value(&mut self, name: &Name, kind: SymbolKind, span: TextSpan) {
+        let (message, help) = if kind == SymbolKind::Builtin {
+            (
+                format!("Builtin '{name}' cannot be used as a value"),
+                format!(
+                    "Builtins cannot be partially applied; declare a function that calls `{name}` \
+                     and pass it as a partial application such as `f(_)`"
+                ),
+            )
+        } else {
+            let placeholders = vec!["_"; self.function_arity(name).unwrap_or(1)].join(", ");
+            (
+                format!("Function '{name}' cannot be used as a value"),
+                format!("Pass a partial application instead: `{name}({placeholders})`"),
+            )
+        };
         self.diagnostics.push(
-            Diagnostic::error("undefined-identifier")
-                .with_message(format!("Undefined identifier '{}'", name))
+            Diagnostic::error("function-as-value")
+                .with_message(message)
                 .with_label(Label::primary(
                     self.module.module_identity().to_string(),
                     span,
                 ))
+                .with_help(help)
                 .build(),
         );
     }
+
+    /// Returns the number of parameters of the local or imported function `name`.
+    fn function_arity(&self, name: &Name) -> Option<usize> {
+        let binding = [PreparedNamespace::Value, PreparedNamespace::Element]
+            .iter()
+            .find_map(|namespace| self.module.resolve_binding(*namespace, name))?;
+        match self.module.resolve_prepared_item(binding)? {
+            ResolvedPreparedItem::Raw {
+                item: Item::Function(function),
+                ..
+            } => Some(function.params.len()),
+            ResolvedPreparedItem::Imported { item, .. } => match item.item {
+                InterfaceItemKind::Function { params, .. } => Some(params.len()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn report_undefined(&mut self, name: &Name, span: TextSpan, scope: ScopeId) {
+        let mut builder = Diagnostic::error("undefined-identifier")
+            .with_message(format!("Undefined identifier '{}'", name))
+            .with_label(Label::primary(
+                self.module.module_identity().to_string(),
+                span,
+            ));
+
+        // A misspelled keyword is not a call to a builtin that happens to be a few edits away.
+        let keywords = nx_syntax::language_metadata().keywords;
+        let keyword_shaped = keywords.contains(&name.as_str())
+            || suggest::closest_match(name.as_str(), keywords.iter().copied()).is_some();
+        let candidates = self.scope_manager.visible_symbols(scope);
+        if let Some(suggestion) = suggest::closest_match(
+            name.as_str(),
+            candidates
+                .iter()
+                .filter(|symbol| !(keyword_shaped && symbol.kind == SymbolKind::Builtin))
+                .map(|symbol| symbol.name.as_str()),
+        ) {
+            builder = builder.with_help(format!("Did you mean `{suggestion}`?"));
+        }
+
+        self.diagnostics.push(builder.build());
+    }
 }
 
 #[cfg(test)]
@@ -798,6 +976,7 @@ mod tests {
             is_external: false,
             base: None,
             props: Vec::new(),
+            rest: None,
             emits: Vec::new(),
             state: Vec::new(),
             body: Some(body),
@@ -883,6 +1062,7 @@ mod tests {
                     name: Name::new("n"),
                     ty: ast::TypeRef::name("int"),
                     is_content: false,
+                    is_required: true,
                     span: TextSpan::default(),
                 }],
                 return_type: ast::TypeRef::name("int"),
@@ -965,4 +1145,35 @@ mod tests {
             diagnostics
         );
     }
+
+    #[test]
+    fn test_scope_manager_visible_names_prefers_inner_scopes() {
+        let mut manager = ScopeManager::new();
+        let span = TextSpan::new(TextSize::from(0), TextSize::from(1));
+        let root = manager.root();
+        manager.define(
+            root,
+            Symbol::new(Name::new("b"), SymbolKind::Function, span),
+        );
+        manager.define(
+            root,
+            Symbol::new(Name::new("a"), SymbolKind::Function, span),
+        );
+        let child = manager.create_child(root);
+        manager.define(
+            child,
+            Symbol::new(Name::new("z"), SymbolKind::Variable, span),
+        );
+        manager.define(
+            child,
+            Symbol::new(Name::new("a"), SymbolKind::Variable, span),
+        );
+
+        let names: Vec<_> = manager
+            .visible_names(child)
+            .into_iter()
+            .map(|name| name.as_str().to_string())
+            .collect();
+        assert_eq!(names, vec!["a", "z", "b"]);
+    }
 }
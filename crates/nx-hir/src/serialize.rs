@@ -0,0 +1,130 @@
+//! Serde serialization of lowered modules.
+//!
+//! A serialized [`LoweredModule`] lists its items, then every expression and element with the
+//! arena id that other nodes use to refer to it. Ids are plain numbers, so an `ExprId` field
+//! holding `3` points at the `exprs` entry whose `id` is `3`. Spans are `[start, end]` byte
+//! offsets into the source. The format mirrors the HIR types and changes with them; it is
+//! meant for analysis scripts and golden tests of lowering, not as a stable interchange format.
+
+use crate::{ast, Element, LoweredModule};
+use la_arena::{Arena, Idx};
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct};
+use serde::{Serialize, Serializer};
+
+impl Serialize for LoweredModule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut module = serializer.serialize_struct("LoweredModule", 7)?;
+        module.serialize_field("source_id", &self.source_id)?;
+        module.serialize_field("imports", &self.imports)?;
+        module.serialize_field("metadata", &self.metadata)?;
+        module.serialize_field("items", &self.items)?;
+        module.serialize_field("exprs", &ArenaEntries::<ast::Expr>(&self.exprs, "expr"))?;
+        module.serialize_field(
+            "elements",
+            &ArenaEntries::<Element>(&self.elements, "element"),
+        )?;
+        module.serialize_field("diagnostics", &self.diagnostics)?;
+        module.end()
+    }
+}
+
+/// Serializes an arena as a list of `{ "id": n, <field>: node }` entries in allocation order.
+struct ArenaEntries<'a, T>(&'a Arena<T>, &'static str);
+
+impl<T: Serialize> Serialize for ArenaEntries<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for (id, node) in self.0.iter() {
+            seq.serialize_element(&ArenaEntry {
+                id: raw_id(id),
+                field: self.1,
+                node,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct ArenaEntry<'a, T> {
+    id: u32,
+    field: &'static str,
+    node: &'a T,
+}
+
+impl<T: Serialize> Serialize for ArenaEntry<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entry = serializer.serialize_map(Some(2))?;
+        entry.serialize_entry("id", &self.id)?;
+        entry.serialize_entry(self.field, self.node)?;
+        entry.end()
+    }
+}
+
+fn raw_id<T>(id: Idx<T>) -> u32 {
+    u32::from(id.into_raw())
+}
+
+/// Arena indices, alone or in an `Option` or `Vec`, that serialize as raw numbers.
+pub(crate) trait ArenaIds {
+    fn serialize_ids<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+impl<T> ArenaIds for Idx<T> {
+    fn serialize_ids<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(raw_id(*self))
+    }
+}
+
+impl<T> ArenaIds for Option<Idx<T>> {
+    fn serialize_ids<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.map(raw_id).serialize(serializer)
+    }
+}
+
+impl<T> ArenaIds for Vec<Idx<T>> {
+    fn serialize_ids<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|id| raw_id(*id)))
+    }
+}
+
+impl<T> ArenaIds for Vec<Option<Idx<T>>> {
+    fn serialize_ids<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(|id| id.map(raw_id)))
+    }
+}
+
+/// `serialize_with` target for fields that hold arena indices.
+pub(crate) fn arena_ids<T: ArenaIds, S: Serializer>(
+    ids: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    ids.serialize_ids(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{lower, SourceId};
+
+    #[test]
+    fn test_serialized_module_lists_exprs_with_ids() {
+        let parse = nx_syntax::parse_str("let answer = { 40 + 2 }", "answer.nx");
+        let module = lower(parse.root().expect("root"), SourceId::new(0));
+
+        let json = serde_json::to_value(&module).expect("serialize");
+        let value = &json["items"][0]["Value"];
+        assert_eq!(value["name"], "answer");
+
+        let body_id = value["value"].as_u64().expect("expr id");
+        let exprs = json["exprs"].as_array().expect("exprs");
+        let body = exprs
+            .iter()
+            .find(|entry| entry["id"].as_u64() == Some(body_id))
+            .expect("body expr");
+        let binary = &body["expr"]["BinaryOp"];
+        assert_eq!(binary["op"], "Add");
+        assert!(binary["span"].is_array());
+
+        let lhs_id = binary["lhs"].as_u64().expect("lhs id");
+        assert_eq!(exprs[lhs_id as usize]["expr"]["Literal"]["Int"], 40);
+    }
+}
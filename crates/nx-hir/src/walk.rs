@@ -0,0 +1,183 @@
+//! Traversal of lowered expressions.
+//!
+//! These helpers see names syntactically: an identifier counts as referenced wherever it appears,
+//! even where a loop variable or local binding of the same name shadows it. Callers use them for
+//! conservative questions such as "might this expression read `x`".
+
+use crate::ast::{Expr, Stmt};
+use crate::{ExprId, LoweredModule, Name, PropertyEntry};
+use rustc_hash::FxHashSet;
+
+/// Adds every name referenced in `expr_id` or nested in it to `out`: identifiers, and the keys
+/// of `provide` and context reads, which name top-level values.
+pub fn collect_referenced_names(
+    module: &LoweredModule,
+    expr_id: ExprId,
+    out: &mut FxHashSet<Name>,
+) {
+    walk_expr(module, expr_id, &mut |expr| match expr {
+        Expr::Ident(name)
+        | Expr::Provide { key: name, .. }
+        | Expr::UseContext { key: name, .. } => {
+            out.insert(name.clone());
+        }
+        _ => {}
+    });
+}
+
+/// Calls `visit` on `expr_id` and then on every expression nested in it, including those inside
+/// element properties and content.
+pub fn walk_expr(module: &LoweredModule, expr_id: ExprId, visit: &mut dyn FnMut(&Expr)) {
+    visit(module.expr(expr_id));
+    let mut children = Vec::new();
+    child_exprs(module, expr_id, &mut children);
+    for child in children {
+        walk_expr(module, child, visit);
+    }
+}
+
+/// Adds the expressions directly nested in `expr_id` to `out`.
+pub fn child_exprs(module: &LoweredModule, expr_id: ExprId, out: &mut Vec<ExprId>) {
+    match module.expr(expr_id) {
+        Expr::Literal(_) | Expr::Error(_) | Expr::Ident(_) | Expr::UseContext { .. } => {}
+        Expr::BinaryOp { lhs, rhs, .. } => out.extend([*lhs, *rhs]),
+        Expr::UnaryOp { expr, .. } => out.push(*expr),
+        Expr::Call { func, args, .. } => {
+            out.push(*func);
+            out.extend(args);
+        }
+        Expr::PartialApply { func, args, .. } => {
+            out.push(*func);
+            out.extend(args.iter().flatten());
+        }
+        Expr::If {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            out.extend([*condition, *then_branch]);
+            out.extend(else_branch);
+        }
+        Expr::Match {
+            scrutinee,
+            arms,
+            else_branch,
+            ..
+        } => {
+            out.push(*scrutinee);
+            for arm in arms {
+                out.extend(&arm.patterns);
+                out.push(arm.body);
+            }
+            out.extend(else_branch);
+        }
+        Expr::Let { value, body, .. } => out.extend([*value, *body]),
+        Expr::Block { stmts, expr, .. } => {
+            for stmt in stmts {
+                match stmt {
+                    Stmt::Let { init, .. } => out.push(*init),
+                    Stmt::Expr(expr, _) => out.push(*expr),
+                }
+            }
+            out.extend(expr);
+        }
+        Expr::Array { elements, .. } => out.extend(elements),
+        Expr::Index { base, index, .. } => out.extend([*base, *index]),
+        Expr::Member { base, .. } => out.push(*base),
+        Expr::RecordLiteral { properties, .. } => {
+            out.extend(properties.iter().map(|property| property.value));
+        }
+        Expr::Element { element, .. } => {
+            let element = module.element(*element);
+            property_entry_exprs(element.property_entries(), out);
+            out.extend(&element.content);
+        }
+        Expr::ActionHandler { body, .. } => out.push(*body),
+        Expr::For { iterable, body, .. } => out.extend([*iterable, *body]),
+        Expr::Provide { value, body, .. } => out.extend([*value, *body]),
+        Expr::Destructure { source, .. } => out.push(*source),
+    }
+}
+
+fn property_entry_exprs(entries: &[PropertyEntry], out: &mut Vec<ExprId>) {
+    for entry in entries {
+        match entry {
+            PropertyEntry::Value(property) => out.push(property.value),
+            PropertyEntry::Spread { value, .. } => out.push(*value),
+            PropertyEntry::If {
+                condition,
+                then_entries,
+                else_entries,
+                ..
+            } => {
+                out.push(*condition);
+                property_entry_exprs(then_entries, out);
+                property_entry_exprs(else_entries, out);
+            }
+            PropertyEntry::ConditionList {
+                arms, else_entries, ..
+            } => {
+                for arm in arms {
+                    out.push(arm.condition);
+                    property_entry_exprs(&arm.entries, out);
+                }
+                property_entry_exprs(else_entries, out);
+            }
+            PropertyEntry::Match {
+                scrutinee,
+                arms,
+                else_entries,
+                ..
+            } => {
+                out.push(*scrutinee);
+                for arm in arms {
+                    out.extend(&arm.patterns);
+                    property_entry_exprs(&arm.entries, out);
+                }
+                property_entry_exprs(else_entries, out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lower, Item, SourceId};
+    use nx_syntax::parse_str;
+
+    fn referenced_names(source: &str, function: &str) -> Vec<String> {
+        let parse_result = parse_str(source, "walk.nx");
+        let tree = parse_result.tree.expect("Should parse walk source");
+        let module = lower(tree.root(), SourceId::new(0));
+        let body = module
+            .items()
+            .iter()
+            .find_map(|item| match item {
+                Item::Function(func) if func.name.as_str() == function => Some(func.body),
+                _ => None,
+            })
+            .expect("function exists");
+        let mut names = FxHashSet::default();
+        collect_referenced_names(&module, body, &mut names);
+        let mut names = names
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_referenced_names_include_provide_and_use_context_keys() {
+        let source = r#"
+            let theme: string = "light"
+            let label() = { use theme }
+            let nested() = { provide theme = "dark" { label() } }
+        "#;
+
+        assert_eq!(referenced_names(source, "label"), vec!["theme"]);
+        assert_eq!(referenced_names(source, "nested"), vec!["label", "theme"]);
+    }
+}
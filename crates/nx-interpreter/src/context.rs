@@ -1,9 +1,16 @@
 //! Execution context for managing runtime state during interpretation.
 
 use crate::error::{CallFrame, RuntimeError, RuntimeErrorKind};
+use crate::i18n::MessageCatalog;
+use crate::interpreter::bytecode::Chunk;
+use crate::log::{LogLevel, LogRecord, LogSink, Logger};
+use crate::resolved_program::RuntimeModuleId;
 use crate::value::Value;
+use nx_hir::ExprId;
 use rustc_hash::FxHashMap;
 use smol_str::SmolStr;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Resource limits for execution
 ///
@@ -14,6 +21,8 @@ use smol_str::SmolStr;
 /// # Default Values
 /// - `max_operations`: 1,000,000 (prevents infinite loops)
 /// - `max_recursion_depth`: 1,000 (prevents stack overflow)
+/// - `max_captured_values`: 100,000 (bounds the environment each action handler keeps)
+/// - `deadline`: none
 ///
 /// # Examples
 /// ```
@@ -22,6 +31,7 @@ use smol_str::SmolStr;
 /// let strict_limits = ResourceLimits {
 ///     max_operations: 10_000,
 ///     max_recursion_depth: 100,
+///     ..ResourceLimits::default()
 /// };
 /// ```
 #[derive(Debug, Clone, Copy)]
@@ -35,17 +45,73 @@ pub struct ResourceLimits {
     ///
     /// Each function call increments depth. Prevents stack overflow.
     pub max_recursion_depth: usize,
+
+    /// Maximum number of values one action handler may capture
+    ///
+    /// A handler keeps a copy of every variable visible where it is created. Each captured
+    /// value counts once, and so does every item and field nested in it, so a captured array of
+    /// 1,000 integers counts 1,001.
+    pub max_captured_values: usize,
+
+    /// Time at which execution stops with [`RuntimeErrorKind::DeadlineExceeded`]
+    ///
+    /// The clock is read every [`DEADLINE_CHECK_INTERVAL`] operations, so a deadline bounds
+    /// evaluation time without slowing down every step.
+    pub deadline: Option<Instant>,
 }
 
+/// Operations between two checks of [`ResourceLimits::deadline`]
+pub const DEADLINE_CHECK_INTERVAL: usize = 256;
+
 impl Default for ResourceLimits {
     fn default() -> Self {
         Self {
             max_operations: 1_000_000,
             max_recursion_depth: 1000,
+            max_captured_values: 100_000,
+            deadline: None,
         }
     }
 }
 
+/// Resource usage of an execution, reported by [`ExecutionContext::stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvalStats {
+    /// Operations counted against [`ResourceLimits::max_operations`]
+    pub operations: usize,
+    /// Action handlers created
+    pub action_handlers: usize,
+    /// Values captured by all action handlers, counted as for
+    /// [`ResourceLimits::max_captured_values`]
+    pub captured_values: usize,
+    /// Values captured by the largest single action handler
+    pub largest_capture: usize,
+}
+
+/// Source of randomness for the `random`, `randomInt` and `uuid` builtins
+///
+/// Each execution context starts its own generator, so a seeded evaluation produces the same
+/// sequence every time it runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RandomSource {
+    /// Seed from system entropy
+    #[default]
+    Entropy,
+    /// Seed from a fixed value, for reproducible output such as test snapshots
+    Seeded(u64),
+    /// Reject calls to random builtins, for sandboxed or deterministic evaluation
+    Disabled,
+}
+
+/// Top-level value that keys a context supplied by `provide`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ContextKey {
+    /// Module declaring the value, or `None` when evaluating a module without a resolved program
+    pub module: Option<RuntimeModuleId>,
+    /// Declared name of the value
+    pub name: SmolStr,
+}
+
 /// Variable scope for local variables
 #[derive(Debug, Clone)]
 struct Scope {
@@ -81,8 +147,11 @@ impl Scope {
 ///
 /// Manages the runtime state during expression evaluation, including:
 /// - Variable scopes (with proper lexical scoping)
+/// - Globals seeded by the host before execution
 /// - Call stack (for recursion tracking)
+/// - Context values supplied by enclosing `provide` expressions
 /// - Operation counting (for infinite loop protection)
+/// - Accounting of the values captured by action handlers
 /// - Resource limits enforcement
 ///
 /// The context is passed through all evaluation functions and tracks
@@ -91,12 +160,26 @@ impl Scope {
 pub struct ExecutionContext {
     /// Stack of scopes (innermost scope is last)
     scopes: Vec<Scope>,
+    /// Host-supplied bindings, visible wherever no scope defines the same name
+    globals: FxHashMap<SmolStr, Value>,
     /// Call stack for function calls
     call_stack: Vec<CallFrame>,
+    /// Values supplied by enclosing `provide` expressions (innermost is last)
+    provided: Vec<(ContextKey, Value)>,
     /// Operation counter
     operation_count: usize,
+    /// Action handler captures so far; `operations` is unused, see `operation_count`
+    captures: EvalStats,
     /// Resource limits
     limits: ResourceLimits,
+    /// Generator for random builtins, or `None` when randomness is disabled
+    rng: Option<fastrand::Rng>,
+    /// Receiver of `log` calls, or `None` to discard them
+    logger: Option<Logger>,
+    /// Translations used by the `t` builtin, or `None` to show messages untranslated
+    translations: Option<Arc<MessageCatalog>>,
+    /// Bytecode compiled during this execution, by module address and body expression
+    chunks: FxHashMap<(usize, ExprId), Arc<Chunk>>,
 }
 
 impl ExecutionContext {
@@ -109,9 +192,66 @@ impl ExecutionContext {
     pub fn with_limits(limits: ResourceLimits) -> Self {
         Self {
             scopes: vec![Scope::new()],
+            globals: FxHashMap::default(),
             call_stack: Vec::new(),
+            provided: Vec::new(),
             operation_count: 0,
+            captures: EvalStats::default(),
             limits,
+            rng: Some(fastrand::Rng::new()),
+            logger: None,
+            translations: None,
+            chunks: FxHashMap::default(),
+        }
+    }
+
+    /// Replace the generator used by random builtins
+    pub fn with_random_source(mut self, source: RandomSource) -> Self {
+        self.rng = match source {
+            RandomSource::Entropy => Some(fastrand::Rng::new()),
+            RandomSource::Seeded(seed) => Some(fastrand::Rng::with_seed(seed)),
+            RandomSource::Disabled => None,
+        };
+        self
+    }
+
+    /// Get the generator for random builtins, or `None` when randomness is disabled
+    pub fn rng(&mut self) -> Option<&mut fastrand::Rng> {
+        self.rng.as_mut()
+    }
+
+    /// Send `log` calls at `min_level` or above to `sink`
+    pub fn with_log_sink(self, sink: Arc<dyn LogSink>, min_level: LogLevel) -> Self {
+        self.with_logger(Some(Logger::new(sink, min_level)))
+    }
+
+    pub(crate) fn with_logger(mut self, logger: Option<Logger>) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    /// Translate the messages of `t` calls with `catalog`, or show them untranslated when `None`
+    pub fn with_translations(mut self, catalog: Option<Arc<MessageCatalog>>) -> Self {
+        self.translations = catalog;
+        self
+    }
+
+    /// Get the catalog used by the `t` builtin, if any
+    pub fn translations(&self) -> Option<&MessageCatalog> {
+        self.translations.as_deref()
+    }
+
+    /// Returns true if a `log` call at `level` reaches a sink.
+    pub fn log_enabled(&self, level: LogLevel) -> bool {
+        self.logger
+            .as_ref()
+            .is_some_and(|logger| logger.enabled(level))
+    }
+
+    /// Send a record to the log sink, if there is one and the record's level passes its filter.
+    pub fn log(&self, record: LogRecord) {
+        if let Some(logger) = &self.logger {
+            logger.log(record);
         }
     }
 
@@ -152,12 +292,36 @@ impl ExecutionContext {
                 return Some(value.clone());
             }
         }
-        None
+        self.globals.get(name).cloned()
+    }
+
+    /// Bind a global that the program can read by name
+    ///
+    /// Globals let a host inject data without declaring it in NX source. They sit outside every
+    /// scope: top-level values, parameters and local bindings with the same name shadow them.
+    /// Setting a global that already exists replaces its value.
+    pub fn set_global(&mut self, name: impl Into<SmolStr>, value: Value) {
+        self.globals.insert(name.into(), value);
+    }
+
+    /// Get the value of a global seeded with [`Self::set_global`]
+    pub fn global(&self, name: &str) -> Option<&Value> {
+        self.globals.get(name)
+    }
+
+    /// Get all globals seeded with [`Self::set_global`]
+    pub fn globals(&self) -> &FxHashMap<SmolStr, Value> {
+        &self.globals
     }
 
     /// Snapshot all currently visible variables with lexical shadowing preserved.
+    ///
+    /// Globals are included unless a scope shadows them. After an execution through
+    /// [`Interpreter::execute_function_in_context`](crate::Interpreter::execute_function_in_context)
+    /// this is the final environment: the globals, the module's top-level values and the entry
+    /// function's parameters.
     pub fn snapshot_visible_variables(&self) -> FxHashMap<SmolStr, Value> {
-        let mut variables = FxHashMap::default();
+        let mut variables = self.globals.clone();
         for scope in &self.scopes {
             for (name, value) in &scope.variables {
                 variables.insert(name.clone(), value.clone());
@@ -166,20 +330,49 @@ impl ExecutionContext {
         variables
     }
 
-    /// Fork one isolated execution context that keeps limits and accounting but starts with
-    /// a fresh variable scope.
+    /// Supply `value` for `key` until the matching [`Self::pop_provided`]
+    pub(crate) fn push_provided(&mut self, key: ContextKey, value: Value) {
+        self.provided.push((key, value));
+    }
+
+    /// Remove the innermost provided context value
+    pub(crate) fn pop_provided(&mut self) {
+        self.provided.pop();
+    }
+
+    /// Get the innermost value provided for `key`, if any
+    pub(crate) fn provided(&self, key: &ContextKey) -> Option<Value> {
+        self.provided
+            .iter()
+            .rev()
+            .find(|(provided_key, _)| provided_key == key)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Fork one isolated execution context that keeps limits, accounting and provided context
+    /// values but starts with a fresh variable scope.
     pub fn fork_isolated(&self) -> Self {
         Self {
             scopes: vec![Scope::new()],
+            globals: self.globals.clone(),
             call_stack: self.call_stack.clone(),
+            provided: self.provided.clone(),
             operation_count: self.operation_count,
+            captures: self.captures,
             limits: self.limits,
+            rng: self.rng.clone(),
+            logger: self.logger.clone(),
+            translations: self.translations.clone(),
+            chunks: self.chunks.clone(),
         }
     }
 
-    /// Synchronize operation accounting from another context that branched from this one.
+    /// Synchronize operation accounting and random generator state from another context that
+    /// branched from this one.
     pub fn sync_usage_from(&mut self, other: &Self) {
         self.operation_count = other.operation_count;
+        self.captures = other.captures;
+        self.rng.clone_from(&other.rng);
     }
 
     /// Update a variable in the scope stack
@@ -227,6 +420,12 @@ impl ExecutionContext {
             })
             .with_call_stack(self.call_stack.clone()));
         }
+        if let Some(deadline) = self.limits.deadline {
+            if self.operation_count % DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= deadline {
+                return Err(RuntimeError::new(RuntimeErrorKind::DeadlineExceeded)
+                    .with_call_stack(self.call_stack.clone()));
+            }
+        }
         Ok(())
     }
 
@@ -235,10 +434,62 @@ impl ExecutionContext {
         self.operation_count
     }
 
+    /// Account for the variables captured by a new action handler and check the capture limit
+    pub fn record_capture(
+        &mut self,
+        captured: &FxHashMap<SmolStr, Value>,
+    ) -> Result<(), RuntimeError> {
+        let size = captured.values().map(value_count).sum();
+        if size > self.limits.max_captured_values {
+            return Err(RuntimeError::new(RuntimeErrorKind::CaptureLimitExceeded {
+                limit: self.limits.max_captured_values,
+                size,
+            })
+            .with_call_stack(self.call_stack.clone()));
+        }
+        self.captures.action_handlers += 1;
+        self.captures.captured_values += size;
+        self.captures.largest_capture = self.captures.largest_capture.max(size);
+        Ok(())
+    }
+
+    /// Get the resource usage of this context so far
+    pub fn stats(&self) -> EvalStats {
+        EvalStats {
+            operations: self.operation_count,
+            ..self.captures
+        }
+    }
+
     /// Get the current call stack depth
     pub fn call_stack_depth(&self) -> usize {
         self.call_stack.len()
     }
+
+    pub(crate) fn compiled_chunk(&self, key: (usize, ExprId)) -> Option<Arc<Chunk>> {
+        self.chunks.get(&key).cloned()
+    }
+
+    pub(crate) fn cache_compiled_chunk(&mut self, key: (usize, ExprId), chunk: Arc<Chunk>) {
+        self.chunks.insert(key, chunk);
+    }
+
+    /// Forget compiled bytecode, whose modules may not outlive the execution that compiled it
+    pub(crate) fn clear_compiled_chunks(&mut self) {
+        self.chunks.clear();
+    }
+}
+
+/// Counts `value` and every array item, record field, captured variable and bound argument
+/// nested in it.
+fn value_count(value: &Value) -> usize {
+    1 + match value {
+        Value::Array(items) => items.iter().map(value_count).sum(),
+        Value::Record { fields, .. } => fields.values().map(value_count).sum(),
+        Value::ActionHandler { captured, .. } => captured.values().map(value_count).sum(),
+        Value::PartialApplication { args, .. } => args.iter().flatten().map(value_count).sum(),
+        _ => 0,
+    }
 }
 
 impl Default for ExecutionContext {
@@ -293,6 +544,7 @@ mod tests {
         let mut ctx = ExecutionContext::with_limits(ResourceLimits {
             max_operations: 5,
             max_recursion_depth: 10,
+            ..ResourceLimits::default()
         });
 
         for _ in 0..5 {
@@ -301,6 +553,60 @@ mod tests {
         assert!(ctx.check_operation_limit().is_err());
     }
 
+    #[test]
+    fn test_deadline_is_checked_every_interval() {
+        let mut ctx = ExecutionContext::with_limits(ResourceLimits {
+            deadline: Some(Instant::now()),
+            ..ResourceLimits::default()
+        });
+
+        for _ in 1..DEADLINE_CHECK_INTERVAL {
+            assert!(ctx.check_operation_limit().is_ok());
+        }
+        let error = ctx.check_operation_limit().unwrap_err();
+        assert_eq!(error.kind(), &RuntimeErrorKind::DeadlineExceeded);
+    }
+
+    #[test]
+    fn test_seeded_contexts_repeat_and_forks_continue_the_sequence() {
+        let mut first = ExecutionContext::new().with_random_source(RandomSource::Seeded(7));
+        let mut second = ExecutionContext::new().with_random_source(RandomSource::Seeded(7));
+        assert_eq!(first.rng().unwrap().u64(..), second.rng().unwrap().u64(..));
+
+        let mut fork = first.fork_isolated();
+        let from_fork = fork.rng().unwrap().u64(..);
+        first.sync_usage_from(&fork);
+        assert_ne!(first.rng().unwrap().u64(..), from_fork);
+
+        let mut disabled = ExecutionContext::new().with_random_source(RandomSource::Disabled);
+        assert!(disabled.rng().is_none());
+    }
+
+    #[test]
+    fn test_globals_are_shadowed_by_scopes_and_kept_by_forks() {
+        let mut ctx = ExecutionContext::new();
+        ctx.set_global("theme", Value::String(SmolStr::new("dark")));
+        assert_eq!(
+            ctx.lookup_variable("theme").unwrap(),
+            Value::String(SmolStr::new("dark"))
+        );
+
+        ctx.push_scope();
+        ctx.define_variable(SmolStr::new("theme"), Value::String(SmolStr::new("light")));
+        assert_eq!(
+            ctx.lookup_variable("theme").unwrap(),
+            Value::String(SmolStr::new("light"))
+        );
+        ctx.pop_scope();
+
+        let fork = ctx.fork_isolated();
+        assert_eq!(
+            fork.global("theme"),
+            Some(&Value::String(SmolStr::new("dark")))
+        );
+        assert_eq!(fork.globals().len(), 1);
+    }
+
     #[test]
     fn test_variable_update() {
         let mut ctx = ExecutionContext::new();
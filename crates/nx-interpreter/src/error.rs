@@ -1,5 +1,6 @@
 //! Runtime error types and handling for the NX interpreter.
 
+#[cfg(feature = "render")]
 use ariadne::{sources, Color, Label, Report, ReportKind};
 use smol_str::SmolStr;
 use std::fmt;
@@ -61,11 +62,24 @@ pub enum RuntimeErrorKind {
     /// Triggered when execution exceeds the configured operation count limit
     OperationLimitExceeded { limit: usize },
 
+    /// Deadline exceeded
+    ///
+    /// Triggered when execution is still running at [`ResourceLimits::deadline`]
+    ///
+    /// [`ResourceLimits::deadline`]: crate::ResourceLimits::deadline
+    DeadlineExceeded,
+
     /// Stack overflow (recursion depth exceeded)
     ///
     /// Triggered when recursion depth exceeds the configured limit
     StackOverflow { depth: usize },
 
+    /// Action handler capture limit exceeded
+    ///
+    /// Triggered when the variables visible where an action handler is created hold more
+    /// values than the configured limit
+    CaptureLimitExceeded { limit: usize, size: usize },
+
     /// Enum type referenced at runtime could not be found
     EnumNotFound { name: SmolStr },
 
@@ -95,6 +109,21 @@ pub enum RuntimeErrorKind {
     /// Attempted to instantiate an abstract record
     AbstractRecordInstantiation { record: SmolStr, operation: String },
 
+    /// Record field defaults that read each other, so none can be evaluated first
+    RecordDefaultCycle {
+        record: SmolStr,
+        cycle: Vec<SmolStr>,
+    },
+
+    /// Record value whose fields fail their declared constraints, such as `@min(0)`
+    ///
+    /// Lists every failed constraint of the record, in field order.
+    RecordValidation {
+        record: SmolStr,
+        operation: String,
+        violations: Vec<ConstraintViolation>,
+    },
+
     /// Attempted to instantiate an abstract component
     AbstractComponentInstantiation {
         component: SmolStr,
@@ -122,6 +151,56 @@ pub enum RuntimeErrorKind {
 
     /// Dispatched action is not declared by the target component
     UnsupportedComponentAction { component: SmolStr, action: SmolStr },
+
+    /// Builtin function called with an argument outside its domain
+    ///
+    /// Triggered by calls such as `sqrt(-1.0)` or `pow(2, -1)`
+    InvalidArgument { function: SmolStr, reason: String },
+
+    /// Random builtin called while the evaluation has randomness disabled
+    RandomnessDisabled { function: SmolStr },
+
+    /// Builtin whose implementation was left out of this build by a disabled cargo feature
+    BuiltinFeatureDisabled {
+        function: SmolStr,
+        feature: &'static str,
+    },
+
+    /// `assert(cond, message?)` with a false condition, or `fail(message)`
+    ///
+    /// The error's location is the span of the failing call.
+    Assertion { message: String },
+
+    /// String concatenation with a null operand under [`NullConcatenation::Error`]
+    ///
+    /// [`NullConcatenation::Error`]: crate::NullConcatenation::Error
+    NullConcatenation,
+
+    /// Arithmetic on two amounts of money in different currencies, as in `EUR + USD`
+    CurrencyMismatch {
+        lhs: SmolStr,
+        rhs: SmolStr,
+        operation: String,
+    },
+
+    /// Amount of money too large to represent in the currency's minor unit
+    MoneyOverflow { operation: String },
+
+    /// Element or record literal that supplies the same property more than once
+    ///
+    /// The checker rejects these statically; unchecked programs fail here instead of letting one
+    /// value silently win.
+    DuplicateProperty { target: SmolStr, property: SmolStr },
+
+    /// `provide` or `use` keyed by a name that is not a top-level value
+    UnknownContext { name: SmolStr },
+
+    /// Destructured array with a length the array pattern cannot bind
+    ArrayPatternMismatch {
+        expected: usize,
+        has_rest: bool,
+        actual: usize,
+    },
 }
 
 impl fmt::Display for RuntimeErrorKind {
@@ -161,9 +240,15 @@ impl fmt::Display for RuntimeErrorKind {
             RuntimeErrorKind::OperationLimitExceeded { limit } => {
                 write!(f, "Operation limit exceeded: {} operations", limit)
             }
+            RuntimeErrorKind::DeadlineExceeded => write!(f, "Evaluation did not finish in time"),
             RuntimeErrorKind::StackOverflow { depth } => {
                 write!(f, "Stack overflow: recursion depth {} exceeded", depth)
             }
+            RuntimeErrorKind::CaptureLimitExceeded { limit, size } => write!(
+                f,
+                "Action handler capture limit exceeded: {} values captured, limit is {}",
+                size, limit
+            ),
             RuntimeErrorKind::EnumNotFound { name } => {
                 write!(f, "Enum not found: {}", name)
             }
@@ -199,6 +284,31 @@ impl fmt::Display for RuntimeErrorKind {
                 "Cannot instantiate abstract record '{}' in {}",
                 record, operation
             ),
+            RuntimeErrorKind::RecordDefaultCycle { record, cycle } => write!(
+                f,
+                "Defaults of record '{}' depend on each other: {}",
+                record,
+                cycle
+                    .iter()
+                    .map(SmolStr::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            ),
+            RuntimeErrorKind::RecordValidation {
+                record,
+                operation,
+                violations,
+            } => write!(
+                f,
+                "Record '{}' failed validation in {}: {}",
+                record,
+                operation,
+                violations
+                    .iter()
+                    .map(ConstraintViolation::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
             RuntimeErrorKind::AbstractComponentInstantiation {
                 component,
                 operation,
@@ -230,6 +340,56 @@ impl fmt::Display for RuntimeErrorKind {
             RuntimeErrorKind::InvalidComponentStateSnapshot { reason } => {
                 write!(f, "Invalid component state snapshot: {}", reason)
             }
+            RuntimeErrorKind::InvalidArgument { function, reason } => {
+                write!(f, "Invalid argument to {}: {}", function, reason)
+            }
+            RuntimeErrorKind::RandomnessDisabled { function } => write!(
+                f,
+                "{} is unavailable because randomness is disabled for this evaluation",
+                function
+            ),
+            RuntimeErrorKind::BuiltinFeatureDisabled { function, feature } => write!(
+                f,
+                "{} is unavailable because this build does not enable the '{}' feature",
+                function, feature
+            ),
+            RuntimeErrorKind::Assertion { message } => write!(f, "{}", message),
+            RuntimeErrorKind::NullConcatenation => write!(
+                f,
+                "Cannot concatenate null with a string; give the value a non-null default or \
+                 evaluate with an empty or \"null\" null concatenation policy"
+            ),
+            RuntimeErrorKind::CurrencyMismatch {
+                lhs,
+                rhs,
+                operation,
+            } => write!(
+                f,
+                "Cannot perform {} on amounts in different currencies: {} and {}",
+                operation, lhs, rhs
+            ),
+            RuntimeErrorKind::MoneyOverflow { operation } => {
+                write!(f, "Amount of money is too large in {}", operation)
+            }
+            RuntimeErrorKind::DuplicateProperty { target, property } => write!(
+                f,
+                "Cannot build '{}' with duplicate property '{}'",
+                target, property
+            ),
+            RuntimeErrorKind::UnknownContext { name } => {
+                write!(f, "Context '{}' is not a top-level value", name)
+            }
+            RuntimeErrorKind::ArrayPatternMismatch {
+                expected,
+                has_rest,
+                actual,
+            } => write!(
+                f,
+                "Array pattern needs {} {} elements, found {}",
+                if *has_rest { "at least" } else { "exactly" },
+                expected,
+                actual
+            ),
             RuntimeErrorKind::UnsupportedComponentAction { component, action } => write!(
                 f,
                 "Component '{}' does not declare emitted action '{}'",
@@ -239,6 +399,23 @@ impl fmt::Display for RuntimeErrorKind {
     }
 }
 
+/// One failed field constraint of a [`RuntimeErrorKind::RecordValidation`] error
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintViolation {
+    /// Path of the field from the validated record, such as `age` or `address.zip`
+    pub field: SmolStr,
+    /// The failed constraint as declared, such as `@min(0)`
+    pub constraint: String,
+    /// How the value fails the constraint, such as `must be at least 0, got -1`
+    pub message: String,
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ({})", self.field, self.message, self.constraint)
+    }
+}
+
 /// Call stack frame for error reporting
 #[derive(Debug, Clone, PartialEq)]
 pub struct CallFrame {
@@ -329,6 +506,7 @@ impl RuntimeError {
     ///
     /// # Returns
     /// Formatted error string with ANSI colors and source highlighting
+    #[cfg(feature = "render")]
     pub fn format(&self, filename: &str, source: &str) -> String {
         let mut output = Vec::new();
 
@@ -4,6 +4,57 @@ use crate::error::{RuntimeError, RuntimeErrorKind};
 use crate::value::Value;
 use nx_hir::ast::BinOp;
 
+/// How string concatenation treats a null operand, as in `"x" + null`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NullConcatenation {
+    /// Fail with [`RuntimeErrorKind::NullConcatenation`]
+    #[default]
+    Error,
+    /// Treat null as the empty string
+    Empty,
+    /// Treat null as the text `null`
+    Literal,
+}
+
+/// Evaluate `lhs + rhs` where one side is null and the other a string, or `None` for any other
+/// operands
+///
+/// Operands lowered as [`BinOp::Concat`] were checked as strings, so two nulls also concatenate.
+pub fn eval_null_concat(
+    lhs: &Value,
+    op: BinOp,
+    rhs: &Value,
+    policy: NullConcatenation,
+) -> Option<Result<Value, RuntimeError>> {
+    let is_text = |value: &Value| matches!(value, Value::String(_) | Value::Null);
+    let applies = match op {
+        BinOp::Concat => is_text(lhs) && is_text(rhs),
+        BinOp::Add => {
+            matches!(
+                (lhs, rhs),
+                (Value::String(_), Value::Null) | (Value::Null, Value::String(_))
+            )
+        }
+        _ => false,
+    };
+    if !applies || !(lhs.is_null() || rhs.is_null()) {
+        return None;
+    }
+
+    let text = |value: &Value| match value {
+        Value::String(text) => Some(text.to_string()),
+        _ => match policy {
+            NullConcatenation::Error => None,
+            NullConcatenation::Empty => Some(String::new()),
+            NullConcatenation::Literal => Some("null".to_string()),
+        },
+    };
+    Some(match (text(lhs), text(rhs)) {
+        (Some(a), Some(b)) => Ok(Value::String((a + &b).into())),
+        _ => Err(RuntimeError::new(RuntimeErrorKind::NullConcatenation)),
+    })
+}
+
 /// Evaluate an arithmetic binary operation
 pub fn eval_arithmetic_op(lhs: Value, op: BinOp, rhs: Value) -> Result<Value, RuntimeError> {
     // Check for null operands
@@ -47,6 +98,9 @@ fn eval_add(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
         // Cross-width float promotion → f64
         (Value::Float32(a), Value::Float(b)) => Ok(Value::Float(a as f64 + b)),
         (Value::Float(a), Value::Float32(b)) => Ok(Value::Float(a + b as f64)),
+        // Operands only known to be strings at check time are lowered as `Add`
+        (a @ Value::String(_), b @ Value::String(_)) => eval_concat(a, b),
+        (Value::Money(a), Value::Money(b)) => a.checked_add(&b).map(Value::Money),
         // Cross-category is a type error
         (a, b) => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
             expected: "same numeric category (integer or float)".to_string(),
@@ -66,6 +120,7 @@ fn eval_sub(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
         (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
         (Value::Float32(a), Value::Float(b)) => Ok(Value::Float(a as f64 - b)),
         (Value::Float(a), Value::Float32(b)) => Ok(Value::Float(a - b as f64)),
+        (Value::Money(a), Value::Money(b)) => a.checked_sub(&b).map(Value::Money),
         (a, b) => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
             expected: "same numeric category (integer or float)".to_string(),
             actual: format!("{} and {}", a.type_name(), b.type_name()),
@@ -84,6 +139,18 @@ fn eval_mul(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
         (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
         (Value::Float32(a), Value::Float(b)) => Ok(Value::Float(a as f64 * b)),
         (Value::Float(a), Value::Float32(b)) => Ok(Value::Float(a * b as f64)),
+        (Value::Money(money), Value::Int32(n)) | (Value::Int32(n), Value::Money(money)) => {
+            money.checked_mul_int(n as i64).map(Value::Money)
+        }
+        (Value::Money(money), Value::Int(n)) | (Value::Int(n), Value::Money(money)) => {
+            money.checked_mul_int(n).map(Value::Money)
+        }
+        (Value::Money(money), Value::Float32(n)) | (Value::Float32(n), Value::Money(money)) => {
+            money.checked_mul_float(n as f64).map(Value::Money)
+        }
+        (Value::Money(money), Value::Float(n)) | (Value::Float(n), Value::Money(money)) => {
+            money.checked_mul_float(n).map(Value::Money)
+        }
         (a, b) => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
             expected: "same numeric category (integer or float)".to_string(),
             actual: format!("{} and {}", a.type_name(), b.type_name()),
@@ -142,6 +209,10 @@ fn eval_div(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
             }
             Ok(Value::Float(a / b as f64))
         }
+        (Value::Money(money), Value::Int32(n)) => money.checked_div(n as f64).map(Value::Money),
+        (Value::Money(money), Value::Int(n)) => money.checked_div(n as f64).map(Value::Money),
+        (Value::Money(money), Value::Float32(n)) => money.checked_div(n as f64).map(Value::Money),
+        (Value::Money(money), Value::Float(n)) => money.checked_div(n).map(Value::Money),
         (a, b) => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
             expected: "same numeric category (integer or float)".to_string(),
             actual: format!("{} and {}", a.type_name(), b.type_name()),
@@ -234,6 +305,50 @@ mod tests {
         assert_eq!(result, Value::Int(5));
     }
 
+    #[test]
+    fn test_add_strings_concatenates() {
+        let result = eval_add(
+            Value::String(SmolStr::new("ab")),
+            Value::String(SmolStr::new("cd")),
+        )
+        .unwrap();
+        assert_eq!(result, Value::String(SmolStr::new("abcd")));
+    }
+
+    #[test]
+    fn test_null_concatenation_follows_policy() {
+        let text = Value::String(SmolStr::new("x"));
+        let concat = |policy| eval_null_concat(&text, BinOp::Add, &Value::Null, policy).unwrap();
+
+        assert!(concat(NullConcatenation::Error).is_err());
+        assert_eq!(
+            concat(NullConcatenation::Empty).unwrap(),
+            Value::String(SmolStr::new("x"))
+        );
+        assert_eq!(
+            concat(NullConcatenation::Literal).unwrap(),
+            Value::String(SmolStr::new("xnull"))
+        );
+        assert!(eval_null_concat(
+            &Value::Int(1),
+            BinOp::Add,
+            &Value::Null,
+            NullConcatenation::Empty
+        )
+        .is_none());
+        assert_eq!(
+            eval_null_concat(
+                &Value::Null,
+                BinOp::Concat,
+                &Value::Null,
+                NullConcatenation::Empty
+            )
+            .unwrap()
+            .unwrap(),
+            Value::String(SmolStr::new(""))
+        );
+    }
+
     #[test]
     fn test_add_int32() {
         let result = eval_add(Value::Int32(2), Value::Int32(3)).unwrap();
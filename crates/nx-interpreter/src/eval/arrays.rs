@@ -0,0 +1,150 @@
+//! Higher-order array builtin evaluation
+//!
+//! `map`, `filter` and `reduce` take a function value, such as the partial application
+//! `add(_, 1)`, and call it once per item in array order. Only the interpreter knows how to call
+//! a function value, so it passes that in as `apply`. An error from the function stops the
+//! iteration and is returned unchanged.
+
+use crate::context::ExecutionContext;
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::value::Value;
+
+/// Calls function value `function` with `args`
+pub type Apply<'a> =
+    dyn FnMut(&mut ExecutionContext, &Value, Vec<Value>) -> Result<Value, RuntimeError> + 'a;
+
+fn array_arg<'a>(function: &str, value: &'a Value) -> Result<&'a [Value], RuntimeError> {
+    match value {
+        Value::Array(items) => Ok(items),
+        other => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+            expected: "array".to_string(),
+            actual: other.type_name().to_string(),
+            operation: function.to_string(),
+        })),
+    }
+}
+
+/// Evaluate `map(items, f)`, the array of `f(item)` for each item
+pub fn eval_map(
+    ctx: &mut ExecutionContext,
+    items: &Value,
+    function: &Value,
+    apply: &mut Apply,
+) -> Result<Value, RuntimeError> {
+    let items = array_arg("map", items)?;
+    let mut mapped = Vec::with_capacity(items.len());
+    for item in items {
+        mapped.push(apply(ctx, function, vec![item.clone()])?);
+    }
+    Ok(Value::Array(mapped))
+}
+
+/// Evaluate `filter(items, keep)`, the items for which `keep(item)` is `true`
+pub fn eval_filter(
+    ctx: &mut ExecutionContext,
+    items: &Value,
+    keep: &Value,
+    apply: &mut Apply,
+) -> Result<Value, RuntimeError> {
+    let items = array_arg("filter", items)?;
+    let mut kept = Vec::new();
+    for item in items {
+        match apply(ctx, keep, vec![item.clone()])? {
+            Value::Boolean(true) => kept.push(item.clone()),
+            Value::Boolean(false) => {}
+            other => {
+                return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+                    expected: "bool".to_string(),
+                    actual: other.type_name().to_string(),
+                    operation: "filter".to_string(),
+                }))
+            }
+        }
+    }
+    Ok(Value::Array(kept))
+}
+
+/// Evaluate `reduce(items, initial, f)`, folding the items into `initial` with `f(acc, item)`
+pub fn eval_reduce(
+    ctx: &mut ExecutionContext,
+    items: &Value,
+    initial: &Value,
+    function: &Value,
+    apply: &mut Apply,
+) -> Result<Value, RuntimeError> {
+    let items = array_arg("reduce", items)?;
+    let mut acc = initial.clone();
+    for item in items {
+        acc = apply(ctx, function, vec![acc, item.clone()])?;
+    }
+    Ok(acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Treats the function value as an int to add to every int argument
+    fn add(
+        _ctx: &mut ExecutionContext,
+        function: &Value,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let Value::Int(step) = function else {
+            panic!("test functions are ints");
+        };
+        Ok(Value::Int(
+            args.iter()
+                .map(|arg| match arg {
+                    Value::Int(value) => *value,
+                    other => panic!("unexpected argument {:?}", other),
+                })
+                .sum::<i64>()
+                + step,
+        ))
+    }
+
+    fn ints(values: &[i64]) -> Value {
+        Value::Array(values.iter().copied().map(Value::Int).collect())
+    }
+
+    #[test]
+    fn test_map_and_reduce_call_the_function_in_order() {
+        let ctx = &mut ExecutionContext::new();
+        assert_eq!(
+            eval_map(ctx, &ints(&[1, 2, 3]), &Value::Int(10), &mut add).unwrap(),
+            ints(&[11, 12, 13])
+        );
+        assert_eq!(
+            eval_reduce(
+                ctx,
+                &ints(&[1, 2, 3]),
+                &Value::Int(0),
+                &Value::Int(0),
+                &mut add
+            )
+            .unwrap(),
+            Value::Int(6)
+        );
+        assert_eq!(
+            eval_reduce(ctx, &ints(&[]), &Value::Int(7), &Value::Int(0), &mut add).unwrap(),
+            Value::Int(7)
+        );
+        assert!(eval_map(ctx, &Value::Int(1), &Value::Int(0), &mut add).is_err());
+    }
+
+    #[test]
+    fn test_filter_requires_a_bool_result() {
+        let ctx = &mut ExecutionContext::new();
+        let mut odd = |_: &mut ExecutionContext, _: &Value, args: Vec<Value>| match &args[0] {
+            Value::Int(value) => Ok(Value::Boolean(value % 2 == 1)),
+            _ => Ok(Value::Null),
+        };
+        assert_eq!(
+            eval_filter(ctx, &ints(&[1, 2, 3]), &Value::Null, &mut odd).unwrap(),
+            ints(&[1, 3])
+        );
+        let strings = Value::Array(vec![Value::Null]);
+        assert!(eval_filter(ctx, &strings, &Value::Null, &mut odd).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! CSV builtin evaluation
+//!
+//! `parseCsv(text, delimiter?)` reads a table whose first row names the columns and returns one
+//! record per later row, with a string field for each column. The delimiter defaults to `","`;
+//! pass `"\t"` for TSV, which is read as a tab even though NX string literals keep the backslash.
+//! Parsing follows [`nx_value::CsvTable`], and a malformed table fails evaluation with the line of
+//! the problem.
+
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::value::Value;
+use nx_hir::Name;
+use nx_value::CsvTable;
+use smol_str::SmolStr;
+
+/// Evaluate `parseCsv(text, delimiter?)`
+pub fn eval_parse_csv(text: &Value, delimiter: Option<&Value>) -> Result<Value, RuntimeError> {
+    let text = match text {
+        Value::String(text) => text.as_str(),
+        other => return Err(type_mismatch("string", other)),
+    };
+    let delimiter = match delimiter {
+        None => ',',
+        Some(Value::String(delimiter)) if delimiter == "\\t" => '\t',
+        Some(Value::String(delimiter)) => {
+            let mut chars = delimiter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) if !matches!(ch, '"' | '\n' | '\r') => ch,
+                _ => {
+                    return Err(invalid_argument(format!(
+                    "delimiter must be one character other than a quote or line break, got \"{}\"",
+                    delimiter
+                )))
+                }
+            }
+        }
+        Some(other) => return Err(type_mismatch("string", other)),
+    };
+
+    let table =
+        CsvTable::parse(text, delimiter).map_err(|error| invalid_argument(error.to_string()))?;
+    let headers: Vec<SmolStr> = table.headers.iter().map(SmolStr::new).collect();
+    Ok(Value::Array(
+        table
+            .rows
+            .into_iter()
+            .map(|row| Value::Record {
+                type_name: Name::new("object"),
+                fields: headers
+                    .iter()
+                    .cloned()
+                    .zip(row.into_iter().map(|cell| Value::String(cell.into())))
+                    .collect(),
+            })
+            .collect(),
+    ))
+}
+
+fn type_mismatch(expected: &str, actual: &Value) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+        expected: expected.to_string(),
+        actual: actual.type_name().to_string(),
+        operation: "parseCsv".to_string(),
+    })
+}
+
+fn invalid_argument(reason: String) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::InvalidArgument {
+        function: SmolStr::new("parseCsv"),
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(text: &str) -> Value {
+        Value::String(SmolStr::new(text))
+    }
+
+    #[test]
+    fn test_parse_csv_returns_records_and_rejects_bad_delimiters() {
+        let rows = eval_parse_csv(&string("name\tqty\nBolt\t4\n"), Some(&string("\\t"))).unwrap();
+        let Value::Array(rows) = rows else {
+            panic!("expected an array, got {:?}", rows);
+        };
+        let [Value::Record { type_name, fields }] = rows.as_slice() else {
+            panic!("expected one record, got {:?}", rows);
+        };
+        assert_eq!(type_name.as_str(), "object");
+        assert_eq!(fields.get("name"), Some(&string("Bolt")));
+        assert_eq!(fields.get("qty"), Some(&string("4")));
+
+        let error = eval_parse_csv(&string("a,b\n1\n"), None).unwrap_err();
+        assert_eq!(
+            error.kind().to_string(),
+            "Invalid argument to parseCsv: line 2: row has 1 fields but the header has 2"
+        );
+        assert!(eval_parse_csv(&string("a"), Some(&string(";;"))).is_err());
+    }
+}
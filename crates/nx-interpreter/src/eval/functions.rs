@@ -1,3 +1,338 @@
-//! Function call evaluation
+//! Builtin function evaluation
+//!
+//! Native implementations of the functions declared in `nx_types::builtins`. Calls reach these
+//! only when no item in the program has the same name.
 
-// Placeholder module - will be implemented in Phase 7
+use crate::context::ExecutionContext;
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::eval::arrays::{self, Apply};
+use crate::eval::{csv, math, money, random, strings};
+use crate::log::{LogLevel, LogRecord};
+use crate::value::Value;
+use smol_str::SmolStr;
+
+/// Evaluate a call to builtin `name`, or return `None` when `name` is not a builtin
+///
+/// `apply` calls the function values passed to `map`, `filter` and `reduce`.
+pub fn call_builtin(
+    ctx: &mut ExecutionContext,
+    name: &str,
+    args: &[Value],
+    apply: &mut Apply,
+) -> Option<Result<Value, RuntimeError>> {
+    let result = match name {
+        "length" => expect_arity(name, args, 1).and_then(|()| eval_length(&args[0])),
+        "byteLength" => {
+            expect_arity(name, args, 1).and_then(|()| strings::eval_byte_length(&args[0]))
+        }
+        "graphemeLength" => {
+            expect_arity(name, args, 1).and_then(|()| strings::eval_grapheme_length(&args[0]))
+        }
+        "abs" => expect_arity(name, args, 1).and_then(|()| math::eval_abs(&args[0])),
+        "floor" => expect_arity(name, args, 1).and_then(|()| math::eval_floor(&args[0])),
+        "ceil" => expect_arity(name, args, 1).and_then(|()| math::eval_ceil(&args[0])),
+        "round" => match args {
+            [value] => math::eval_round(value, None),
+            [value, digits] => math::eval_round(value, Some(digits)),
+            _ => Err(arity_error(name, args, 1)),
+        },
+        "pow" => expect_arity(name, args, 2).and_then(|()| math::eval_pow(&args[0], &args[1])),
+        "sqrt" => expect_arity(name, args, 1).and_then(|()| math::eval_sqrt(&args[0])),
+        "min" | "max" => {
+            expect_arity(name, args, 2).and_then(|()| math::eval_min_max(name, &args[0], &args[1]))
+        }
+        "clamp" => expect_arity(name, args, 3)
+            .and_then(|()| math::eval_clamp(&args[0], &args[1], &args[2])),
+        "money" => expect_arity(name, args, 2).and_then(|()| money::eval_money(&args[0], &args[1])),
+        "formatMoney" => {
+            expect_arity(name, args, 1).and_then(|()| money::eval_format_money(&args[0]))
+        }
+        "currencyCode" => {
+            expect_arity(name, args, 1).and_then(|()| money::eval_currency_code(&args[0]))
+        }
+        "parseCsv" => match args {
+            [text] => csv::eval_parse_csv(text, None),
+            [text, delimiter] => csv::eval_parse_csv(text, Some(delimiter)),
+            _ => Err(arity_error(name, args, 1)),
+        },
+        "random" => expect_arity(name, args, 0).and_then(|()| random::eval_random(ctx)),
+        "randomInt" => expect_arity(name, args, 2)
+            .and_then(|()| random::eval_random_int(ctx, &args[0], &args[1])),
+        "uuid" => expect_arity(name, args, 0).and_then(|()| random::eval_uuid(ctx)),
+        "upper" => expect_arity(name, args, 1).and_then(|()| strings::eval_upper(&args[0])),
+        "lower" => expect_arity(name, args, 1).and_then(|()| strings::eval_lower(&args[0])),
+        "trim" => expect_arity(name, args, 1).and_then(|()| strings::eval_trim(&args[0])),
+        "sanitizeUrl" => {
+            expect_arity(name, args, 1).and_then(|()| strings::eval_sanitize_url(&args[0]))
+        }
+        "encodeUriComponent" => {
+            expect_arity(name, args, 1).and_then(|()| strings::eval_encode_uri_component(&args[0]))
+        }
+        "substring" => match args {
+            [text, start] => strings::eval_substring(text, start, None),
+            [text, start, end] => strings::eval_substring(text, start, Some(end)),
+            _ => Err(arity_error(name, args, 2)),
+        },
+        "map" => expect_arity(name, args, 2)
+            .and_then(|()| arrays::eval_map(ctx, &args[0], &args[1], apply)),
+        "filter" => expect_arity(name, args, 2)
+            .and_then(|()| arrays::eval_filter(ctx, &args[0], &args[1], apply)),
+        "reduce" => expect_arity(name, args, 3)
+            .and_then(|()| arrays::eval_reduce(ctx, &args[0], &args[1], &args[2], apply)),
+        "startsWith" | "endsWith" | "contains" => expect_arity(name, args, 2)
+            .and_then(|()| strings::eval_string_test(name, &args[0], &args[1])),
+        "truncate" => match args {
+            [text, max] => strings::eval_truncate(text, max, None),
+            [text, max, ellipsis] => strings::eval_truncate(text, max, Some(ellipsis)),
+            _ => Err(arity_error(name, args, 2)),
+        },
+        "wrap" => expect_arity(name, args, 2).and_then(|()| strings::eval_wrap(&args[0], &args[1])),
+        "t" => match args {
+            [message] => strings::eval_translate(ctx.translations(), message, None),
+            [message, values] => strings::eval_translate(ctx.translations(), message, Some(values)),
+            _ => Err(arity_error(name, args, 1)),
+        },
+        "assert" => match args {
+            [condition] => eval_assert(condition, None),
+            [condition, message] => eval_assert(condition, Some(message)),
+            _ => Err(arity_error(name, args, 1)),
+        },
+        "fail" => expect_arity(name, args, 1).and_then(|()| eval_fail(&args[0])),
+        "log" => match args {
+            [level, message] => eval_log(ctx, level, message, None),
+            [level, message, value] => eval_log(ctx, level, message, Some(value)),
+            _ => Err(arity_error(name, args, 2)),
+        },
+        "padLeft" | "padRight" => match args {
+            [text, width] => strings::eval_pad(name, text, width, None),
+            [text, width, fill] => strings::eval_pad(name, text, width, Some(fill)),
+            _ => Err(arity_error(name, args, 2)),
+        },
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn expect_arity(name: &str, args: &[Value], expected: usize) -> Result<(), RuntimeError> {
+    if args.len() == expected {
+        Ok(())
+    } else {
+        Err(arity_error(name, args, expected))
+    }
+}
+
+fn arity_error(name: &str, args: &[Value], expected: usize) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::ParameterCountMismatch {
+        expected,
+        actual: args.len(),
+        function: SmolStr::new(name),
+    })
+}
+
+fn eval_length(value: &Value) -> Result<Value, RuntimeError> {
+    match value {
+        Value::String(text) => Ok(Value::Int(text.chars().count() as i64)),
+        Value::Array(items) => Ok(Value::Int(items.len() as i64)),
+        other => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+            expected: "string or array".to_string(),
+            actual: other.type_name().to_string(),
+            operation: "length".to_string(),
+        })),
+    }
+}
+
+/// Evaluate `assert(condition, message?)`, which is `null` when `condition` holds
+fn eval_assert(condition: &Value, message: Option<&Value>) -> Result<Value, RuntimeError> {
+    let holds = match condition {
+        Value::Boolean(holds) => *holds,
+        other => return Err(type_mismatch("assert", "bool", other)),
+    };
+    if holds {
+        return Ok(Value::Null);
+    }
+    let message = match message {
+        Some(Value::String(message)) => message.to_string(),
+        Some(other) => return Err(type_mismatch("assert", "string", other)),
+        None => "Assertion failed".to_string(),
+    };
+    Err(RuntimeError::new(RuntimeErrorKind::Assertion { message }))
+}
+
+/// Evaluate `fail(message)`, which always fails
+fn eval_fail(message: &Value) -> Result<Value, RuntimeError> {
+    match message {
+        Value::String(message) => Err(RuntimeError::new(RuntimeErrorKind::Assertion {
+            message: message.to_string(),
+        })),
+        other => Err(type_mismatch("fail", "string", other)),
+    }
+}
+
+/// Evaluate `log(level, message, value?)`, which is always `null`
+///
+/// The record goes to the context's log sink, never to stdout; see [`crate::LogSink`].
+fn eval_log(
+    ctx: &ExecutionContext,
+    level: &Value,
+    message: &Value,
+    value: Option<&Value>,
+) -> Result<Value, RuntimeError> {
+    let level = match level {
+        Value::String(name) => LogLevel::from_name(name).ok_or_else(|| {
+            RuntimeError::new(RuntimeErrorKind::InvalidArgument {
+                function: SmolStr::new("log"),
+                reason: format!(
+                    "unknown level '{}'; expected \"debug\", \"info\", \"warn\" or \"error\"",
+                    name
+                ),
+            })
+        })?,
+        other => return Err(type_mismatch("log", "string", other)),
+    };
+    let message = match message {
+        Value::String(message) => message,
+        other => return Err(type_mismatch("log", "string", other)),
+    };
+    if ctx.log_enabled(level) {
+        ctx.log(LogRecord {
+            level,
+            message: message.to_string(),
+            value: value.cloned(),
+        });
+    }
+    Ok(Value::Null)
+}
+
+fn type_mismatch(function: &str, expected: &str, actual: &Value) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+        expected: expected.to_string(),
+        actual: actual.type_name().to_string(),
+        operation: function.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn call_builtin(
+        ctx: &mut ExecutionContext,
+        name: &str,
+        args: &[Value],
+    ) -> Option<Result<Value, RuntimeError>> {
+        super::call_builtin(ctx, name, args, &mut |_, _, _| {
+            panic!("test calls pass no function values")
+        })
+    }
+
+    #[test]
+    fn test_every_checked_builtin_has_an_implementation() {
+        for name in nx_types::builtin_names() {
+            let arity = nx_types::builtin_signatures(name)[0].params.len();
+            let args = vec![Value::Null; arity];
+            assert!(
+                call_builtin(&mut ExecutionContext::new(), name, &args).is_some(),
+                "builtin '{}' has no runtime implementation",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_length_counts_characters_and_items() {
+        let ctx = &mut ExecutionContext::new();
+        let text = call_builtin(ctx, "length", &[Value::String(SmolStr::new("héllo"))]);
+        assert_eq!(text.unwrap().unwrap(), Value::Int(5));
+
+        let items = call_builtin(
+            ctx,
+            "length",
+            &[Value::Array(vec![Value::Int(1), Value::Int(2)])],
+        );
+        assert_eq!(items.unwrap().unwrap(), Value::Int(2));
+
+        assert!(call_builtin(ctx, "length", &[Value::Boolean(true)])
+            .unwrap()
+            .is_err());
+        assert!(call_builtin(ctx, "missing", &[]).is_none());
+    }
+
+    #[test]
+    fn test_log_sends_records_at_or_above_the_minimum_level_to_the_sink() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = {
+            let records = Arc::clone(&records);
+            move |record: LogRecord| records.lock().unwrap().push(record)
+        };
+        let ctx = &mut ExecutionContext::new().with_log_sink(Arc::new(sink), LogLevel::Info);
+        let text = |text: &str| Value::String(SmolStr::new(text));
+
+        for args in [
+            vec![text("debug"), text("hidden")],
+            vec![text("info"), text("loaded")],
+            vec![text("error"), text("bad total"), Value::Int(-1)],
+        ] {
+            assert_eq!(
+                call_builtin(ctx, "log", &args).unwrap().unwrap(),
+                Value::Null
+            );
+        }
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                LogRecord {
+                    level: LogLevel::Info,
+                    message: "loaded".to_string(),
+                    value: None,
+                },
+                LogRecord {
+                    level: LogLevel::Error,
+                    message: "bad total".to_string(),
+                    value: Some(Value::Int(-1)),
+                },
+            ]
+        );
+
+        assert!(call_builtin(ctx, "log", &[text("loud"), text("x")])
+            .unwrap()
+            .is_err());
+        let without_sink = &mut ExecutionContext::new();
+        assert!(
+            call_builtin(without_sink, "log", &[text("error"), text("x")])
+                .unwrap()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_assert_and_fail_raise_assertion_errors() {
+        let ctx = &mut ExecutionContext::new();
+        let message = Value::String(SmolStr::new("count must be positive"));
+        let passed = call_builtin(ctx, "assert", &[Value::Boolean(true), message.clone()]);
+        assert_eq!(passed.unwrap().unwrap(), Value::Null);
+
+        let failed = call_builtin(ctx, "assert", &[Value::Boolean(false), message.clone()]);
+        assert_eq!(
+            failed.unwrap().unwrap_err().kind(),
+            &RuntimeErrorKind::Assertion {
+                message: "count must be positive".to_string()
+            }
+        );
+        let failed = call_builtin(ctx, "assert", &[Value::Boolean(false)]);
+        assert_eq!(failed.unwrap().unwrap_err().to_string(), "Assertion failed");
+        let failed = call_builtin(ctx, "fail", &[message]);
+        assert!(matches!(
+            failed.unwrap().unwrap_err().kind(),
+            RuntimeErrorKind::Assertion { message } if message == "count must be positive"
+        ));
+
+        assert!(matches!(
+            call_builtin(ctx, "assert", &[Value::Int(1)])
+                .unwrap()
+                .unwrap_err()
+                .kind(),
+            RuntimeErrorKind::TypeMismatch { .. }
+        ));
+    }
+}
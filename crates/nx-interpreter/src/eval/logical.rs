@@ -88,6 +88,7 @@ fn eval_eq(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
         }
         (Value::String(a), Value::String(b)) => a == b,
         (Value::Boolean(a), Value::Boolean(b)) => a == b,
+        (Value::Money(a), Value::Money(b)) => a == b,
         (
             Value::EnumValue {
                 type_name: a_type,
@@ -121,6 +122,7 @@ fn eval_lt(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
             as_f64(&lhs).unwrap() < as_f64(&rhs).unwrap()
         }
         (Value::String(a), Value::String(b)) => a < b,
+        (Value::Money(a), Value::Money(b)) => a.compare(b, "less than")?.is_lt(),
         _ => {
             return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
                 expected: "comparable types within same category".to_string(),
@@ -141,6 +143,7 @@ fn eval_le(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
             as_f64(&lhs).unwrap() <= as_f64(&rhs).unwrap()
         }
         (Value::String(a), Value::String(b)) => a <= b,
+        (Value::Money(a), Value::Money(b)) => a.compare(b, "less than or equal")?.is_le(),
         _ => {
             return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
                 expected: "comparable types within same category".to_string(),
@@ -161,6 +164,7 @@ fn eval_gt(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
             as_f64(&lhs).unwrap() > as_f64(&rhs).unwrap()
         }
         (Value::String(a), Value::String(b)) => a > b,
+        (Value::Money(a), Value::Money(b)) => a.compare(b, "greater than")?.is_gt(),
         _ => {
             return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
                 expected: "comparable types within same category".to_string(),
@@ -181,6 +185,7 @@ fn eval_ge(lhs: Value, rhs: Value) -> Result<Value, RuntimeError> {
             as_f64(&lhs).unwrap() >= as_f64(&rhs).unwrap()
         }
         (Value::String(a), Value::String(b)) => a >= b,
+        (Value::Money(a), Value::Money(b)) => a.compare(b, "greater than or equal")?.is_ge(),
         _ => {
             return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
                 expected: "comparable types within same category".to_string(),
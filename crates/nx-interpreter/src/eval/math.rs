@@ -0,0 +1,283 @@
+//! Math builtin evaluation
+//!
+//! Integer and float arguments stay in their own category, matching the arithmetic operators:
+//! integer overloads return `int` and float overloads return `float`. `round` rounds half away
+//! from zero (`round(2.5) == 3.0`, `round(-2.5) == -3.0`).
+
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::value::Value;
+use smol_str::SmolStr;
+
+enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+fn number(function: &str, value: &Value) -> Result<Number, RuntimeError> {
+    match value {
+        Value::Int32(value) => Ok(Number::Int(*value as i64)),
+        Value::Int(value) => Ok(Number::Int(*value)),
+        Value::Float32(value) => Ok(Number::Float(*value as f64)),
+        Value::Float(value) => Ok(Number::Float(*value)),
+        other => Err(type_mismatch(function, "int or float", other.type_name())),
+    }
+}
+
+fn int_arg(function: &str, value: &Value) -> Result<i64, RuntimeError> {
+    match value {
+        Value::Int32(value) => Ok(*value as i64),
+        Value::Int(value) => Ok(*value),
+        other => Err(type_mismatch(function, "int", other.type_name())),
+    }
+}
+
+fn float_arg(function: &str, value: &Value) -> Result<f64, RuntimeError> {
+    match value {
+        Value::Float32(value) => Ok(*value as f64),
+        Value::Float(value) => Ok(*value),
+        other => Err(type_mismatch(function, "float", other.type_name())),
+    }
+}
+
+fn type_mismatch(function: &str, expected: &str, actual: &str) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+        expected: expected.to_string(),
+        actual: actual.to_string(),
+        operation: function.to_string(),
+    })
+}
+
+fn invalid_argument(function: &str, reason: impl Into<String>) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::InvalidArgument {
+        function: SmolStr::new(function),
+        reason: reason.into(),
+    })
+}
+
+/// Evaluate `abs(x)`
+pub fn eval_abs(value: &Value) -> Result<Value, RuntimeError> {
+    match number("abs", value)? {
+        Number::Int(value) => value
+            .checked_abs()
+            .map(Value::Int)
+            .ok_or_else(|| invalid_argument("abs", format!("{} has no int absolute value", value))),
+        Number::Float(value) => Ok(Value::Float(value.abs())),
+    }
+}
+
+/// Evaluate `floor(x)`
+pub fn eval_floor(value: &Value) -> Result<Value, RuntimeError> {
+    match number("floor", value)? {
+        Number::Int(value) => Ok(Value::Int(value)),
+        Number::Float(value) => Ok(Value::Float(value.floor())),
+    }
+}
+
+/// Evaluate `ceil(x)`
+pub fn eval_ceil(value: &Value) -> Result<Value, RuntimeError> {
+    match number("ceil", value)? {
+        Number::Int(value) => Ok(Value::Int(value)),
+        Number::Float(value) => Ok(Value::Float(value.ceil())),
+    }
+}
+
+/// Evaluate `round(x)` or `round(x, digits)`
+pub fn eval_round(value: &Value, digits: Option<&Value>) -> Result<Value, RuntimeError> {
+    let Some(digits) = digits else {
+        return match number("round", value)? {
+            Number::Int(value) => Ok(Value::Int(value)),
+            Number::Float(value) => Ok(Value::Float(value.round())),
+        };
+    };
+
+    let value = float_arg("round", value)?;
+    let digits = int_arg("round", digits)?;
+    if !(0..=15).contains(&digits) {
+        return Err(invalid_argument(
+            "round",
+            format!("digits must be between 0 and 15, got {}", digits),
+        ));
+    }
+    let scale = 10f64.powi(digits as i32);
+    Ok(Value::Float((value * scale).round() / scale))
+}
+
+/// Evaluate `pow(base, exponent)`
+pub fn eval_pow(base: &Value, exponent: &Value) -> Result<Value, RuntimeError> {
+    match (number("pow", base)?, number("pow", exponent)?) {
+        (Number::Int(base), Number::Int(exponent)) => {
+            let exponent = u32::try_from(exponent).map_err(|_| {
+                invalid_argument(
+                    "pow",
+                    format!("int exponent must be non-negative, got {}", exponent),
+                )
+            })?;
+            base.checked_pow(exponent).map(Value::Int).ok_or_else(|| {
+                invalid_argument("pow", format!("{}^{} overflows int", base, exponent))
+            })
+        }
+        (Number::Float(base), Number::Float(exponent)) => Ok(Value::Float(base.powf(exponent))),
+        _ => Err(type_mismatch(
+            "pow",
+            "same numeric category (integer or float)",
+            &format!("{} and {}", base.type_name(), exponent.type_name()),
+        )),
+    }
+}
+
+/// Evaluate `sqrt(x)`
+pub fn eval_sqrt(value: &Value) -> Result<Value, RuntimeError> {
+    let value = match number("sqrt", value)? {
+        Number::Int(value) => value as f64,
+        Number::Float(value) => value,
+    };
+    if value < 0.0 {
+        return Err(invalid_argument(
+            "sqrt",
+            format!("cannot take the square root of {}", value),
+        ));
+    }
+    Ok(Value::Float(value.sqrt()))
+}
+
+/// Evaluate `min(a, b)` or `max(a, b)`, named by `function`
+///
+/// A float NaN argument gives NaN.
+pub fn eval_min_max(function: &str, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+    let smaller = function == "min";
+    match (number(function, left)?, number(function, right)?) {
+        (Number::Int(left), Number::Int(right)) => Ok(Value::Int(if smaller {
+            left.min(right)
+        } else {
+            left.max(right)
+        })),
+        (Number::Float(left), Number::Float(right)) => {
+            Ok(Value::Float(if left.is_nan() || right.is_nan() {
+                f64::NAN
+            } else if smaller {
+                left.min(right)
+            } else {
+                left.max(right)
+            }))
+        }
+        _ => Err(type_mismatch(
+            function,
+            "same numeric category (integer or float)",
+            &format!("{} and {}", left.type_name(), right.type_name()),
+        )),
+    }
+}
+
+/// Evaluate `clamp(x, min, max)`
+pub fn eval_clamp(value: &Value, min: &Value, max: &Value) -> Result<Value, RuntimeError> {
+    match (
+        number("clamp", value)?,
+        number("clamp", min)?,
+        number("clamp", max)?,
+    ) {
+        (Number::Int(value), Number::Int(min), Number::Int(max)) => {
+            if min > max {
+                return Err(invalid_argument(
+                    "clamp",
+                    format!("min {} is greater than max {}", min, max),
+                ));
+            }
+            Ok(Value::Int(value.clamp(min, max)))
+        }
+        (Number::Float(value), Number::Float(min), Number::Float(max)) => {
+            if min.is_nan() || max.is_nan() || min > max {
+                return Err(invalid_argument(
+                    "clamp",
+                    format!("min {} is greater than max {}", min, max),
+                ));
+            }
+            Ok(Value::Float(value.clamp(min, max)))
+        }
+        _ => Err(type_mismatch(
+            "clamp",
+            "same numeric category (integer or float)",
+            &format!(
+                "{}, {} and {}",
+                value.type_name(),
+                min.type_name(),
+                max.type_name()
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abs_keeps_category() {
+        assert_eq!(eval_abs(&Value::Int(-3)).unwrap(), Value::Int(3));
+        assert_eq!(eval_abs(&Value::Float(-1.5)).unwrap(), Value::Float(1.5));
+        assert!(eval_abs(&Value::Int(i64::MIN)).is_err());
+    }
+
+    #[test]
+    fn test_round_half_away_from_zero() {
+        assert_eq!(
+            eval_round(&Value::Float(2.5), None).unwrap(),
+            Value::Float(3.0)
+        );
+        assert_eq!(
+            eval_round(&Value::Float(-2.5), None).unwrap(),
+            Value::Float(-3.0)
+        );
+        assert_eq!(
+            eval_round(&Value::Float(1.2345), Some(&Value::Int(2))).unwrap(),
+            Value::Float(1.23)
+        );
+        assert_eq!(eval_floor(&Value::Float(-1.5)).unwrap(), Value::Float(-2.0));
+        assert_eq!(eval_ceil(&Value::Float(-1.5)).unwrap(), Value::Float(-1.0));
+        assert_eq!(eval_floor(&Value::Int(7)).unwrap(), Value::Int(7));
+    }
+
+    #[test]
+    fn test_pow_rejects_negative_int_exponent_and_overflow() {
+        assert_eq!(
+            eval_pow(&Value::Int(2), &Value::Int(10)).unwrap(),
+            Value::Int(1024)
+        );
+        assert_eq!(
+            eval_pow(&Value::Float(4.0), &Value::Float(0.5)).unwrap(),
+            Value::Float(2.0)
+        );
+        assert!(eval_pow(&Value::Int(2), &Value::Int(-1)).is_err());
+        assert!(eval_pow(&Value::Int(2), &Value::Int(64)).is_err());
+        assert!(eval_pow(&Value::Int(2), &Value::Float(2.0)).is_err());
+    }
+
+    #[test]
+    fn test_sqrt_and_clamp() {
+        assert_eq!(eval_sqrt(&Value::Int(16)).unwrap(), Value::Float(4.0));
+        assert!(eval_sqrt(&Value::Float(-1.0)).is_err());
+        assert_eq!(
+            eval_clamp(&Value::Int(12), &Value::Int(0), &Value::Int(10)).unwrap(),
+            Value::Int(10)
+        );
+        assert_eq!(
+            eval_clamp(&Value::Float(-0.5), &Value::Float(0.0), &Value::Float(1.0)).unwrap(),
+            Value::Float(0.0)
+        );
+        assert!(eval_clamp(&Value::Int(1), &Value::Int(5), &Value::Int(0)).is_err());
+    }
+
+    #[test]
+    fn test_min_and_max_keep_category() {
+        assert_eq!(
+            eval_min_max("min", &Value::Int(3), &Value::Int(-2)).unwrap(),
+            Value::Int(-2)
+        );
+        assert_eq!(
+            eval_min_max("max", &Value::Float(1.5), &Value::Float(2.0)).unwrap(),
+            Value::Float(2.0)
+        );
+        let nan = eval_min_max("max", &Value::Float(f64::NAN), &Value::Float(1.0)).unwrap();
+        assert!(matches!(nan, Value::Float(value) if value.is_nan()));
+        assert!(eval_min_max("min", &Value::Int(1), &Value::Float(1.0)).is_err());
+    }
+}
@@ -1,6 +1,12 @@
 //! Expression evaluation modules
 
 pub mod arithmetic;
+pub mod arrays;
 pub mod control;
+pub mod csv;
 pub mod functions;
 pub mod logical;
+pub mod math;
+pub mod money;
+pub mod random;
+pub mod strings;
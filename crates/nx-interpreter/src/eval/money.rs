@@ -0,0 +1,86 @@
+//! Money builtin evaluation
+//!
+//! `money(amount, currency)` accepts an int, a float, or a decimal string amount. Float amounts
+//! are rounded half away from zero to the currency's minor unit; string amounts must already fit
+//! it, so `money("19.999", "EUR")` fails rather than rounding.
+
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::money::Money;
+use crate::value::Value;
+use smol_str::SmolStr;
+
+/// Evaluate `money(amount, currency)`
+pub fn eval_money(amount: &Value, currency: &Value) -> Result<Value, RuntimeError> {
+    let currency = match currency {
+        Value::String(currency) => currency.as_str(),
+        other => return Err(type_mismatch("money", "string", other)),
+    };
+    let money = match amount {
+        Value::Int32(amount) => Money::from_int(*amount as i64, currency),
+        Value::Int(amount) => Money::from_int(*amount, currency),
+        Value::Float32(amount) => Money::from_float(*amount as f64, currency),
+        Value::Float(amount) => Money::from_float(*amount, currency),
+        Value::String(amount) => Money::parse(amount, currency),
+        other => return Err(type_mismatch("money", "int, float or string", other)),
+    };
+    money.map(Value::Money).map_err(|reason| {
+        RuntimeError::new(RuntimeErrorKind::InvalidArgument {
+            function: SmolStr::new("money"),
+            reason,
+        })
+    })
+}
+
+/// Evaluate `formatMoney(amount)`
+pub fn eval_format_money(amount: &Value) -> Result<Value, RuntimeError> {
+    match amount {
+        Value::Money(money) => Ok(Value::String(money.format().into())),
+        other => Err(type_mismatch("formatMoney", "money", other)),
+    }
+}
+
+/// Evaluate `currencyCode(amount)`
+pub fn eval_currency_code(amount: &Value) -> Result<Value, RuntimeError> {
+    match amount {
+        Value::Money(money) => Ok(Value::String(SmolStr::new(money.currency()))),
+        other => Err(type_mismatch("currencyCode", "money", other)),
+    }
+}
+
+fn type_mismatch(function: &str, expected: &str, actual: &Value) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+        expected: expected.to_string(),
+        actual: actual.type_name().to_string(),
+        operation: function.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(amount: Value, currency: &str) -> Result<Value, RuntimeError> {
+        eval_money(&amount, &Value::String(SmolStr::new(currency)))
+    }
+
+    #[test]
+    fn test_money_accepts_numbers_and_decimal_strings() {
+        let from_string = money(Value::String(SmolStr::new("19.99")), "EUR").unwrap();
+        let from_float = money(Value::Float(19.99), "EUR").unwrap();
+        assert_eq!(from_string, from_float);
+        assert_eq!(
+            eval_format_money(&money(Value::Int(1200), "USD").unwrap()).unwrap(),
+            Value::String(SmolStr::new("$1,200.00"))
+        );
+        assert_eq!(
+            eval_currency_code(&from_string).unwrap(),
+            Value::String(SmolStr::new("EUR"))
+        );
+
+        let error = money(Value::String(SmolStr::new("19.999")), "EUR").unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            RuntimeErrorKind::InvalidArgument { function, .. } if function == "money"
+        ));
+    }
+}
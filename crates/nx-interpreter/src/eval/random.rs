@@ -0,0 +1,139 @@
+//! Random builtin evaluation
+//!
+//! Values come from the generator on the [`ExecutionContext`], configured through
+//! [`RandomSource`](crate::RandomSource). When randomness is disabled every random builtin fails
+//! with [`RuntimeErrorKind::RandomnessDisabled`].
+
+use crate::context::ExecutionContext;
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::value::Value;
+use smol_str::SmolStr;
+
+fn rng<'a>(
+    ctx: &'a mut ExecutionContext,
+    function: &str,
+) -> Result<&'a mut fastrand::Rng, RuntimeError> {
+    ctx.rng().ok_or_else(|| {
+        RuntimeError::new(RuntimeErrorKind::RandomnessDisabled {
+            function: SmolStr::new(function),
+        })
+    })
+}
+
+fn int_arg(value: &Value) -> Result<i64, RuntimeError> {
+    match value {
+        Value::Int32(value) => Ok(*value as i64),
+        Value::Int(value) => Ok(*value),
+        other => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+            expected: "int".to_string(),
+            actual: other.type_name().to_string(),
+            operation: "randomInt".to_string(),
+        })),
+    }
+}
+
+/// Evaluate `random()`: a float in `[0, 1)`
+pub fn eval_random(ctx: &mut ExecutionContext) -> Result<Value, RuntimeError> {
+    Ok(Value::Float(rng(ctx, "random")?.f64()))
+}
+
+/// Evaluate `randomInt(lo, hi)`: an int in `[lo, hi]`, both ends inclusive
+pub fn eval_random_int(
+    ctx: &mut ExecutionContext,
+    lo: &Value,
+    hi: &Value,
+) -> Result<Value, RuntimeError> {
+    let (lo, hi) = (int_arg(lo)?, int_arg(hi)?);
+    if lo > hi {
+        return Err(RuntimeError::new(RuntimeErrorKind::InvalidArgument {
+            function: SmolStr::new("randomInt"),
+            reason: format!("lo {} is greater than hi {}", lo, hi),
+        }));
+    }
+    Ok(Value::Int(rng(ctx, "randomInt")?.i64(lo..=hi)))
+}
+
+/// Evaluate `uuid()`: a random (version 4) UUID in lowercase hyphenated form
+pub fn eval_uuid(ctx: &mut ExecutionContext) -> Result<Value, RuntimeError> {
+    let mut bytes = rng(ctx, "uuid")?.u128(..).to_be_bytes();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    Ok(Value::String(SmolStr::new(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::RandomSource;
+
+    fn seeded() -> ExecutionContext {
+        ExecutionContext::new().with_random_source(RandomSource::Seeded(42))
+    }
+
+    #[test]
+    fn test_seeded_values_are_reproducible() {
+        let (mut first, mut second) = (seeded(), seeded());
+        for _ in 0..3 {
+            assert_eq!(
+                eval_random(&mut first).unwrap(),
+                eval_random(&mut second).unwrap()
+            );
+        }
+        assert_eq!(
+            eval_uuid(&mut first).unwrap(),
+            eval_uuid(&mut second).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_random_int_is_inclusive_and_validates_bounds() {
+        let mut ctx = seeded();
+        for _ in 0..50 {
+            let Value::Int(value) =
+                eval_random_int(&mut ctx, &Value::Int(1), &Value::Int(3)).unwrap()
+            else {
+                panic!("expected an int");
+            };
+            assert!((1..=3).contains(&value));
+        }
+        assert_eq!(
+            eval_random_int(&mut ctx, &Value::Int(5), &Value::Int(5)).unwrap(),
+            Value::Int(5)
+        );
+        assert!(eval_random_int(&mut ctx, &Value::Int(2), &Value::Int(1)).is_err());
+    }
+
+    #[test]
+    fn test_uuid_has_version_4_layout() {
+        let Value::String(uuid) = eval_uuid(&mut seeded()).unwrap() else {
+            panic!("expected a string");
+        };
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+    }
+
+    #[test]
+    fn test_disabled_randomness_is_a_runtime_error() {
+        let mut ctx = ExecutionContext::new().with_random_source(RandomSource::Disabled);
+        let error = eval_random(&mut ctx).unwrap_err();
+        assert_eq!(
+            error.kind(),
+            &RuntimeErrorKind::RandomnessDisabled {
+                function: SmolStr::new("random")
+            }
+        );
+    }
+}
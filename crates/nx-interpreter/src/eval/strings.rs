@@ -0,0 +1,607 @@
+//! String builtin evaluation
+//!
+//! `upper` and `lower` apply the full, locale-independent Unicode case mapping, so a character
+//! may map to several (`upper("ß") == "SS"`) and a final sigma lowercases to `ς`. `trim` removes
+//! characters with the Unicode `White_Space` property. Widths passed to `padLeft` and `padRight`
+//! count Unicode scalar values, the same unit `length` uses.
+//!
+//! `length` is not the only way to measure text: `byteLength` counts UTF-8 bytes, for storage
+//! and wire limits, and `graphemeLength` counts extended grapheme clusters, which is what a
+//! reader sees as one character (`"e\u{301}"` and `"👍🏽"` each have a grapheme length of 1 but a
+//! `length` of 2). `graphemeLength` needs the `graphemes` cargo feature, which is on by default.
+//!
+//! `truncate` and `wrap` measure in grapheme clusters too, so they never cut an accented letter
+//! from its mark or split an emoji sequence. Without the `graphemes` feature they fall back to
+//! Unicode scalar values.
+//!
+//! `wrap(text, width)` breaks `text` into lines of at most `width` grapheme clusters:
+//!
+//! 1. Line breaks already in `text` (`\n` or `\r\n`) always end a line, so paragraphs are wrapped
+//!    separately and blank lines are kept.
+//! 2. Each paragraph is split into words at whitespace. Runs of whitespace collapse, and
+//!    whitespace at the start or end of a paragraph is dropped.
+//! 3. Words are placed greedily: a word joins the current line, after one space, when it fits,
+//!    and starts a new line otherwise.
+//! 4. A word longer than `width` is broken into pieces of exactly `width` clusters, the last piece
+//!    continuing like any other word.
+//!
+//! `sanitizeUrl` and `encodeUriComponent` make text safe to place in URL-bearing attributes.
+//! `sanitizeUrl` trims the URL and returns `about:blank` when it has a scheme other than `http`,
+//! `https`, `mailto` or `tel`, so `javascript:` and `data:` URLs never reach an `href`. Relative
+//! URLs pass through. `encodeUriComponent` percent-encodes the UTF-8 bytes of every character
+//! except ASCII letters, digits and `-_.!~*'()`, like its JavaScript namesake.
+//!
+//! `t(message, args?)` returns the translation of `message` from the evaluation's
+//! [`MessageCatalog`], or `message` itself when there is no catalog or no translation. With
+//! `args`, each `{0}`, `{1}`, ... in the result is then replaced by the text of that item, so
+//! translators can move placeholders around: `t("{0} items", [count])`. Braces around anything
+//! but digits are kept as written.
+
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::i18n::MessageCatalog;
+use crate::value::Value;
+use smol_str::SmolStr;
+
+fn string_arg<'a>(function: &str, value: &'a Value) -> Result<&'a str, RuntimeError> {
+    match value {
+        Value::String(text) => Ok(text.as_str()),
+        other => Err(type_mismatch(function, "string", other.type_name())),
+    }
+}
+
+fn int_arg(function: &str, value: &Value) -> Result<i64, RuntimeError> {
+    match value {
+        Value::Int32(value) => Ok(*value as i64),
+        Value::Int(value) => Ok(*value),
+        other => Err(type_mismatch(function, "int", other.type_name())),
+    }
+}
+
+fn type_mismatch(function: &str, expected: &str, actual: &str) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+        expected: expected.to_string(),
+        actual: actual.to_string(),
+        operation: function.to_string(),
+    })
+}
+
+fn invalid_argument(function: &str, reason: impl Into<String>) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::InvalidArgument {
+        function: SmolStr::new(function),
+        reason: reason.into(),
+    })
+}
+
+/// Evaluate `upper(text)`
+pub fn eval_upper(value: &Value) -> Result<Value, RuntimeError> {
+    Ok(Value::String(SmolStr::new(
+        string_arg("upper", value)?.to_uppercase(),
+    )))
+}
+
+/// Evaluate `lower(text)`
+pub fn eval_lower(value: &Value) -> Result<Value, RuntimeError> {
+    Ok(Value::String(SmolStr::new(
+        string_arg("lower", value)?.to_lowercase(),
+    )))
+}
+
+/// Evaluate `trim(text)`
+pub fn eval_trim(value: &Value) -> Result<Value, RuntimeError> {
+    Ok(Value::String(SmolStr::new(
+        string_arg("trim", value)?.trim(),
+    )))
+}
+
+/// Evaluate `byteLength(text)`, the length of `text` in UTF-8 bytes
+pub fn eval_byte_length(value: &Value) -> Result<Value, RuntimeError> {
+    Ok(Value::Int(string_arg("byteLength", value)?.len() as i64))
+}
+
+/// Evaluate `graphemeLength(text)`, the number of extended grapheme clusters in `text`
+pub fn eval_grapheme_length(value: &Value) -> Result<Value, RuntimeError> {
+    let text = string_arg("graphemeLength", value)?;
+    #[cfg(feature = "graphemes")]
+    {
+        use unicode_segmentation::UnicodeSegmentation;
+        Ok(Value::Int(text.graphemes(true).count() as i64))
+    }
+    #[cfg(not(feature = "graphemes"))]
+    {
+        let _ = text;
+        Err(RuntimeError::new(
+            RuntimeErrorKind::BuiltinFeatureDisabled {
+                function: SmolStr::new("graphemeLength"),
+                feature: "graphemes",
+            },
+        ))
+    }
+}
+
+/// Splits `text` into grapheme clusters, or into Unicode scalar values without the `graphemes`
+/// feature.
+fn clusters(text: &str) -> Vec<&str> {
+    #[cfg(feature = "graphemes")]
+    {
+        use unicode_segmentation::UnicodeSegmentation;
+        text.graphemes(true).collect()
+    }
+    #[cfg(not(feature = "graphemes"))]
+    {
+        text.char_indices()
+            .map(|(start, c)| &text[start..start + c.len_utf8()])
+            .collect()
+    }
+}
+
+fn non_negative_arg(function: &str, name: &str, value: &Value) -> Result<usize, RuntimeError> {
+    let value = int_arg(function, value)?;
+    usize::try_from(value)
+        .map_err(|_| invalid_argument(function, format!("{} {} is negative", name, value)))
+}
+
+/// Evaluate `truncate(text, max, ellipsis?)`
+///
+/// Text of at most `max` grapheme clusters is returned unchanged. Longer text is cut so that,
+/// with trailing whitespace removed and the ellipsis (default `…`) appended, the result is at
+/// most `max` clusters. When even the ellipsis does not fit, the text is cut to `max` clusters
+/// without one.
+pub fn eval_truncate(
+    text: &Value,
+    max: &Value,
+    ellipsis: Option<&Value>,
+) -> Result<Value, RuntimeError> {
+    let text = string_arg("truncate", text)?;
+    let max = non_negative_arg("truncate", "max", max)?;
+    let ellipsis = match ellipsis {
+        Some(ellipsis) => string_arg("truncate", ellipsis)?,
+        None => "…",
+    };
+
+    let parts = clusters(text);
+    if parts.len() <= max {
+        return Ok(Value::String(SmolStr::new(text)));
+    }
+    let ellipsis_len = clusters(ellipsis).len();
+    let truncated = if ellipsis_len > max {
+        parts[..max].concat()
+    } else {
+        let kept = parts[..max - ellipsis_len].concat();
+        format!("{}{}", kept.trim_end(), ellipsis)
+    };
+    Ok(Value::String(SmolStr::new(truncated)))
+}
+
+/// Evaluate `wrap(text, width)`, returning the lines described in the module documentation
+pub fn eval_wrap(text: &Value, width: &Value) -> Result<Value, RuntimeError> {
+    let text = string_arg("wrap", text)?;
+    let width = non_negative_arg("wrap", "width", width)?;
+    if width == 0 {
+        return Err(invalid_argument("wrap", "width must be at least 1"));
+    }
+
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let paragraph = paragraph.strip_suffix('\r').unwrap_or(paragraph);
+        let mut line = String::new();
+        let mut line_len = 0;
+        for word in paragraph.split_whitespace() {
+            let mut word = clusters(word);
+            if line_len > 0 && line_len + 1 + word.len() <= width {
+                line.push(' ');
+                line.push_str(&word.concat());
+                line_len += 1 + word.len();
+                continue;
+            }
+            if line_len > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            while word.len() > width {
+                lines.push(word[..width].concat());
+                word.drain(..width);
+            }
+            line = word.concat();
+            line_len = word.len();
+        }
+        lines.push(line);
+    }
+    Ok(Value::Array(
+        lines
+            .into_iter()
+            .map(|line| Value::String(SmolStr::new(line)))
+            .collect(),
+    ))
+}
+
+/// Evaluate `startsWith(text, prefix)`, `endsWith(text, suffix)` or `contains(text, part)`
+pub fn eval_string_test(function: &str, text: &Value, part: &Value) -> Result<Value, RuntimeError> {
+    let (text, part) = (string_arg(function, text)?, string_arg(function, part)?);
+    let result = match function {
+        "startsWith" => text.starts_with(part),
+        "endsWith" => text.ends_with(part),
+        _ => text.contains(part),
+    };
+    Ok(Value::Boolean(result))
+}
+
+/// Evaluate `substring(text, start, end?)`
+///
+/// Indices count Unicode scalar values and are clamped to the length of `text`; `end` defaults to
+/// the length, and an `end` before `start` gives the empty string.
+pub fn eval_substring(
+    text: &Value,
+    start: &Value,
+    end: Option<&Value>,
+) -> Result<Value, RuntimeError> {
+    let text = string_arg("substring", text)?;
+    let start = non_negative_arg("substring", "start", start)?;
+    let end = match end {
+        Some(end) => Some(non_negative_arg("substring", "end", end)?),
+        None => None,
+    };
+    let part: String = match end {
+        Some(end) => text
+            .chars()
+            .skip(start)
+            .take(end.saturating_sub(start))
+            .collect(),
+        None => text.chars().skip(start).collect(),
+    };
+    Ok(Value::String(SmolStr::new(part)))
+}
+
+/// Evaluate `t(message, args?)`
+pub fn eval_translate(
+    catalog: Option<&MessageCatalog>,
+    message: &Value,
+    args: Option<&Value>,
+) -> Result<Value, RuntimeError> {
+    let message = string_arg("t", message)?;
+    let text = catalog
+        .and_then(|catalog| catalog.translate(message))
+        .unwrap_or(message);
+    let items = match args {
+        None => return Ok(Value::String(SmolStr::new(text))),
+        Some(Value::Array(items)) => items,
+        Some(other) => return Err(type_mismatch("t", "array", other.type_name())),
+    };
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let index = after[..digits].parse::<usize>().ok();
+        match index.filter(|_| after[digits..].starts_with('}')) {
+            Some(index) => {
+                let item = items.get(index).ok_or_else(|| {
+                    RuntimeError::new(RuntimeErrorKind::InvalidArgument {
+                        function: SmolStr::new("t"),
+                        reason: format!(
+                            "'{}' uses placeholder {{{}}} but only {} argument(s) were given",
+                            text,
+                            index,
+                            items.len()
+                        ),
+                    })
+                })?;
+                result.push_str(&item.to_string());
+                rest = &after[digits + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    Ok(Value::String(result.into()))
+}
+
+/// URL schemes `sanitizeUrl` lets through.
+const SAFE_URL_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// Evaluate `sanitizeUrl(url)`
+pub fn eval_sanitize_url(value: &Value) -> Result<Value, RuntimeError> {
+    let url = string_arg("sanitizeUrl", value)?.trim_matches(|c: char| c <= ' ');
+    let safe = match url_scheme(url) {
+        Some(scheme) => SAFE_URL_SCHEMES
+            .iter()
+            .any(|safe| scheme.eq_ignore_ascii_case(safe)),
+        None => true,
+    };
+    Ok(Value::String(SmolStr::new(if safe {
+        url
+    } else {
+        "about:blank"
+    })))
+}
+
+/// Returns the scheme of `url`, or `None` for a relative URL. Tabs and line breaks inside the
+/// scheme are dropped, as browsers do when parsing `java\tscript:`.
+fn url_scheme(url: &str) -> Option<String> {
+    let end = url.find([':', '/', '?', '#'])?;
+    url[end..].starts_with(':').then(|| {
+        url[..end]
+            .chars()
+            .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+            .collect()
+    })
+}
+
+/// Evaluate `encodeUriComponent(text)`
+pub fn eval_encode_uri_component(value: &Value) -> Result<Value, RuntimeError> {
+    let text = string_arg("encodeUriComponent", value)?;
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    Ok(Value::String(SmolStr::new(encoded)))
+}
+
+/// Evaluate `padLeft(text, width, fill?)` or `padRight(text, width, fill?)`
+///
+/// The fill defaults to a single space. It is repeated and cut to fit, so the result is exactly
+/// `width` characters unless `text` is already at least that long.
+pub fn eval_pad(
+    function: &str,
+    text: &Value,
+    width: &Value,
+    fill: Option<&Value>,
+) -> Result<Value, RuntimeError> {
+    let text = string_arg(function, text)?;
+    let width = int_arg(function, width)?;
+    let fill = match fill {
+        Some(fill) => string_arg(function, fill)?,
+        None => " ",
+    };
+    if width < 0 {
+        return Err(invalid_argument(
+            function,
+            format!("width {} is negative", width),
+        ));
+    }
+    if fill.is_empty() {
+        return Err(invalid_argument(function, "fill must not be empty"));
+    }
+
+    let missing = (width as usize).saturating_sub(text.chars().count());
+    let padding: String = fill.chars().cycle().take(missing).collect();
+    let padded = if function == "padLeft" {
+        padding + text
+    } else {
+        format!("{}{}", text, padding)
+    };
+    Ok(Value::String(SmolStr::new(padded)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(text: &str) -> Value {
+        Value::String(SmolStr::new(text))
+    }
+
+    #[test]
+    fn test_case_mapping_is_full_unicode() {
+        assert_eq!(eval_upper(&string("straße")).unwrap(), string("STRASSE"));
+        assert_eq!(eval_lower(&string("ΟΔΟΣ")).unwrap(), string("οδος"));
+        assert_eq!(eval_lower(&string("İ")).unwrap(), string("i\u{307}"));
+        assert!(eval_upper(&Value::Int(1)).is_err());
+    }
+
+    #[test]
+    fn test_trim_and_substring_tests() {
+        assert_eq!(eval_trim(&string("\u{3000} hi\n\t")).unwrap(), string("hi"));
+        let text = string("naïve café");
+        assert_eq!(
+            eval_string_test("startsWith", &text, &string("naï")).unwrap(),
+            Value::Boolean(true)
+        );
+        assert_eq!(
+            eval_string_test("endsWith", &text, &string("cafe")).unwrap(),
+            Value::Boolean(false)
+        );
+        assert_eq!(
+            eval_string_test("contains", &text, &string("ve c")).unwrap(),
+            Value::Boolean(true)
+        );
+    }
+
+    #[test]
+    fn test_substring_counts_characters_and_clamps() {
+        let text = string("naïve café");
+        let int = Value::Int;
+        assert_eq!(
+            eval_substring(&text, &int(2), Some(&int(5))).unwrap(),
+            string("ïve")
+        );
+        assert_eq!(
+            eval_substring(&text, &int(6), None).unwrap(),
+            string("café")
+        );
+        assert_eq!(
+            eval_substring(&text, &int(8), Some(&int(99))).unwrap(),
+            string("fé")
+        );
+        assert_eq!(
+            eval_substring(&text, &int(5), Some(&int(2))).unwrap(),
+            string("")
+        );
+        assert!(eval_substring(&text, &int(-1), None).is_err());
+    }
+
+    #[test]
+    fn test_length_variants_count_bytes_and_graphemes() {
+        let text = string("cafe\u{301} 👍🏽");
+        assert_eq!(eval_byte_length(&text).unwrap(), Value::Int(15));
+        assert_eq!(eval_byte_length(&string("")).unwrap(), Value::Int(0));
+        assert!(eval_byte_length(&Value::Int(1)).is_err());
+
+        #[cfg(feature = "graphemes")]
+        assert_eq!(eval_grapheme_length(&text).unwrap(), Value::Int(6));
+        #[cfg(not(feature = "graphemes"))]
+        assert!(eval_grapheme_length(&text).is_err());
+    }
+
+    fn lines(lines: &[&str]) -> Value {
+        Value::Array(lines.iter().map(|line| string(line)).collect())
+    }
+
+    #[test]
+    fn test_truncate_keeps_the_result_within_max() {
+        let text = string("Hello wide world");
+        assert_eq!(eval_truncate(&text, &Value::Int(16), None).unwrap(), text);
+        assert_eq!(
+            eval_truncate(&text, &Value::Int(7), None).unwrap(),
+            string("Hello…")
+        );
+        assert_eq!(
+            eval_truncate(&text, &Value::Int(10), Some(&string("..."))).unwrap(),
+            string("Hello w...")
+        );
+        assert_eq!(
+            eval_truncate(&text, &Value::Int(2), Some(&string("..."))).unwrap(),
+            string("He")
+        );
+        assert!(eval_truncate(&text, &Value::Int(-1), None).is_err());
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn test_truncate_and_wrap_do_not_split_grapheme_clusters() {
+        let text = string("cafe\u{301}👍🏽🇫🇷x");
+        assert_eq!(
+            eval_truncate(&text, &Value::Int(6), None).unwrap(),
+            string("cafe\u{301}👍🏽…")
+        );
+        assert_eq!(
+            eval_wrap(&text, &Value::Int(3)).unwrap(),
+            lines(&["caf", "e\u{301}👍🏽🇫🇷", "x"])
+        );
+    }
+
+    #[test]
+    fn test_wrap_fills_lines_greedily_and_keeps_paragraphs() {
+        assert_eq!(
+            eval_wrap(
+                &string("the quick  brown fox jumps\r\n\n  over"),
+                &Value::Int(10)
+            )
+            .unwrap(),
+            lines(&["the quick", "brown fox", "jumps", "", "over"])
+        );
+        assert_eq!(
+            eval_wrap(&string("a abcdefg b"), &Value::Int(3)).unwrap(),
+            lines(&["a", "abc", "def", "g b"])
+        );
+        assert_eq!(
+            eval_wrap(&string(""), &Value::Int(5)).unwrap(),
+            lines(&[""])
+        );
+        assert!(eval_wrap(&string("x"), &Value::Int(0)).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_url_keeps_safe_schemes_and_relative_urls() {
+        for url in [
+            "https://example.com/a?b=1",
+            "HTTP://example.com",
+            "mailto:team@example.com",
+            "/users/7",
+            "page.html#top",
+            "?q=a:b",
+        ] {
+            assert_eq!(eval_sanitize_url(&string(url)).unwrap(), string(url));
+        }
+        assert_eq!(
+            eval_sanitize_url(&string("  tel:+15551234 \n")).unwrap(),
+            string("tel:+15551234")
+        );
+        for url in [
+            "javascript:alert(1)",
+            " JavaScript:alert(1)",
+            "java\tscript:alert(1)",
+            "data:text/html,<script>",
+            "vbscript:msgbox",
+        ] {
+            assert_eq!(
+                eval_sanitize_url(&string(url)).unwrap(),
+                string("about:blank")
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_uri_component_escapes_reserved_and_non_ascii_bytes() {
+        assert_eq!(
+            eval_encode_uri_component(&string("a b&c=d/é?(ok)")).unwrap(),
+            string("a%20b%26c%3Dd%2F%C3%A9%3F(ok)")
+        );
+        assert!(eval_encode_uri_component(&Value::Int(1)).is_err());
+    }
+
+    #[test]
+    fn test_translate_looks_up_the_catalog_then_fills_placeholders() {
+        let catalog: MessageCatalog = [
+            ("{0} items in {1}", "{1} : {0} articles"),
+            ("Save", "Enregistrer"),
+            ("Quit", ""),
+        ]
+        .into_iter()
+        .collect();
+        let args = Value::Array(vec![Value::Int(3), string("Cart")]);
+
+        assert_eq!(
+            eval_translate(Some(&catalog), &string("Save"), None).unwrap(),
+            string("Enregistrer")
+        );
+        assert_eq!(
+            eval_translate(Some(&catalog), &string("Quit"), None).unwrap(),
+            string("Quit")
+        );
+        assert_eq!(
+            eval_translate(Some(&catalog), &string("{0} items in {1}"), Some(&args)).unwrap(),
+            string("Cart : 3 articles")
+        );
+        assert_eq!(
+            eval_translate(None, &string("{0} items in {1}"), Some(&args)).unwrap(),
+            string("3 items in Cart")
+        );
+        assert_eq!(
+            eval_translate(None, &string("{name} {0}"), Some(&args)).unwrap(),
+            string("{name} 3")
+        );
+        assert!(eval_translate(None, &string("{2}"), Some(&args)).is_err());
+    }
+
+    #[test]
+    fn test_pad_counts_characters_and_cycles_fill() {
+        assert_eq!(
+            eval_pad("padLeft", &string("7"), &Value::Int(3), Some(&string("0"))).unwrap(),
+            string("007")
+        );
+        assert_eq!(
+            eval_pad(
+                "padRight",
+                &string("é"),
+                &Value::Int(4),
+                Some(&string("-="))
+            )
+            .unwrap(),
+            string("é-=-")
+        );
+        assert_eq!(
+            eval_pad("padLeft", &string("long"), &Value::Int(2), None).unwrap(),
+            string("long")
+        );
+        assert!(eval_pad("padLeft", &string("x"), &Value::Int(-1), None).is_err());
+        assert!(eval_pad("padRight", &string("x"), &Value::Int(3), Some(&string(""))).is_err());
+    }
+}
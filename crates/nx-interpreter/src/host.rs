@@ -0,0 +1,38 @@
+//! Rust functions that an embedding host exposes to NX code.
+
+use crate::error::RuntimeError;
+use crate::value::Value;
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+use std::fmt;
+use std::sync::Arc;
+
+/// A function registered with [`Interpreter::register_function`](crate::Interpreter::register_function)
+///
+/// It receives the evaluated arguments of the NX call in order. Host functions are shared by
+/// every execution of an interpreter, possibly on several threads at once.
+pub type HostFunction = dyn Fn(&[Value]) -> Result<Value, RuntimeError> + Send + Sync;
+
+/// Host functions by the name NX code calls them with
+#[derive(Clone, Default)]
+pub(crate) struct HostFunctions {
+    functions: FxHashMap<SmolStr, Arc<HostFunction>>,
+}
+
+impl HostFunctions {
+    pub(crate) fn insert(&mut self, name: SmolStr, function: Arc<HostFunction>) {
+        self.functions.insert(name, function);
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&Arc<HostFunction>> {
+        self.functions.get(name)
+    }
+}
+
+impl fmt::Debug for HostFunctions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = self.functions.keys().collect::<Vec<_>>();
+        names.sort();
+        f.debug_set().entries(names).finish()
+    }
+}
@@ -0,0 +1,72 @@
+//! Message catalogs for the `t` builtin.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Translations of the messages passed to the `t` builtin, keyed by source message
+///
+/// Serializes as a JSON object from message to translation, the shape `nxlang i18n extract`
+/// writes with empty translations for translators to fill in. A message with no translation, or
+/// with an empty one, is shown untranslated.
+///
+/// # Example
+/// ```
+/// use nx_interpreter::MessageCatalog;
+///
+/// let catalog: MessageCatalog = serde_json::from_str(r#"{"Save": "Enregistrer", "Quit": ""}"#).unwrap();
+/// assert_eq!(catalog.translate("Save"), Some("Enregistrer"));
+/// assert_eq!(catalog.translate("Quit"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MessageCatalog {
+    messages: BTreeMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Create an empty catalog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the translation of `message`, replacing any previous one
+    pub fn insert(&mut self, message: impl Into<String>, translation: impl Into<String>) {
+        self.messages.insert(message.into(), translation.into());
+    }
+
+    /// Get the translation of `message`, or `None` when it has none
+    pub fn translate(&self, message: &str) -> Option<&str> {
+        self.messages
+            .get(message)
+            .map(String::as_str)
+            .filter(|translation| !translation.is_empty())
+    }
+
+    /// Iterate over the messages and their translations in message order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.messages
+            .iter()
+            .map(|(message, translation)| (message.as_str(), translation.as_str()))
+    }
+
+    /// Get the number of messages in the catalog
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns true if the catalog has no messages
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+impl<M: Into<String>, T: Into<String>> FromIterator<(M, T)> for MessageCatalog {
+    fn from_iter<I: IntoIterator<Item = (M, T)>>(iter: I) -> Self {
+        Self {
+            messages: iter
+                .into_iter()
+                .map(|(message, translation)| (message.into(), translation.into()))
+                .collect(),
+        }
+    }
+}
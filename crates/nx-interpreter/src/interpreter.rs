@@ -1,16 +1,22 @@
 //! Core interpreter implementation for executing NX HIR.
 
-use crate::context::{ExecutionContext, ResourceLimits};
+use crate::context::{ContextKey, ExecutionContext, RandomSource, ResourceLimits};
 use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::eval::arithmetic::NullConcatenation;
+use crate::host::{HostFunction, HostFunctions};
+use crate::i18n::MessageCatalog;
+use crate::log::{LogLevel, LogSink, Logger};
+use crate::money::Money;
 use crate::resolved_program::{ResolvedItemKind, ResolvedProgram, RuntimeModuleId};
+use crate::stream::StreamEvent;
 use crate::value::Value;
 use la_arena::RawIdx;
 use nx_hir::{
     ast, effective_component_contract, effective_component_contract_for_name,
     effective_record_shape_for_name, resolve_record_definition as resolve_hir_record_definition,
-    EffectiveField, ElementId, ExprId, Function, Item, LoweredModule, Name, PreparedBinding,
-    PreparedBindingOrigin, PreparedBindingTarget, PreparedItemKind, PreparedModule, PropertyEntry,
-    RecordKind, UnionCaseDef, UnionCaseField, UnionDef,
+    EffectiveField, Element, ElementId, ExprId, Function, Item, LoweredModule, Name,
+    PreparedBinding, PreparedBindingOrigin, PreparedBindingTarget, PreparedItemKind,
+    PreparedModule, PropertyEntry, RecordKind, UnionCaseDef, UnionCaseField, UnionDef, ValueDef,
 };
 use nx_types::{
     common_supertype, is_object_type, resolve_type_ref_with, resolve_type_ref_with_seen,
@@ -20,17 +26,62 @@ use rustc_hash::FxHashMap;
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
-use std::cell::RefCell;
 use std::collections::BTreeMap;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError, RwLock};
+use text_size::TextRange;
+
+pub(crate) mod bytecode;
+mod constraints;
+mod expression;
+mod prepared;
+mod vm;
+
+pub use expression::ExprEvalError;
+pub use prepared::PreparedFunction;
 
 const COMPONENT_SNAPSHOT_VERSION: u32 = 1;
 
-/// Tree-walking interpreter for NX HIR
+/// How an [`Interpreter`] executes function bodies
+///
+/// Both backends produce the same values, errors, operation counts and call stacks for every
+/// program; they differ only in speed and in how deep NX recursion uses the native stack.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Evaluate the HIR directly, recursing on the native stack for every expression.
+    #[default]
+    TreeWalker,
+    /// Compile each function body to bytecode on first call and run it on a stack-based VM.
+    ///
+    /// This is a hybrid backend. Only literals, names, blocks, `let`, operators, `if`, arrays and
+    /// calls of named functions are compiled; every other expression, including elements,
+    /// records, member access, `for` loops, `match` and partial application, is handed whole to
+    /// the tree walker.
+    ///
+    /// Calls made from compiled code push VM frames instead of native ones, so recursion through
+    /// them is bounded only by [`ResourceLimits::max_recursion_depth`]. A call inside an
+    /// expression the tree walker evaluates, such as one in a `for` body or an element property,
+    /// recurses on the native stack as it does with [`Backend::TreeWalker`].
+    Bytecode,
+}
+
+/// Interpreter for NX HIR, with a tree-walking and a bytecode [`Backend`]
+///
+/// # Thread safety
+///
+/// `Interpreter` is `Send + Sync`. Every execution creates its own [`ExecutionContext`] and only
+/// reads the program, so one interpreter, or an `Arc` of one, can serve concurrent requests from
+/// several worker threads. The only shared mutable state is an internal cache of prepared modules,
+/// which is guarded by a lock.
 #[derive(Debug)]
 pub struct Interpreter {
     program: Option<ResolvedProgram>,
-    runtime_prepared_cache: RefCell<FxHashMap<RuntimeModuleId, Arc<PreparedModule>>>,
+    runtime_prepared_cache: RwLock<FxHashMap<RuntimeModuleId, Arc<PreparedModule>>>,
+    random_source: RandomSource,
+    null_concatenation: NullConcatenation,
+    logger: Option<Logger>,
+    translations: Option<Arc<MessageCatalog>>,
+    backend: Backend,
+    host_functions: HostFunctions,
 }
 
 /// Result of component initialization.
@@ -77,6 +128,10 @@ enum SerializedValue {
     String(String),
     Boolean(bool),
     Null,
+    Money {
+        units: i64,
+        currency: String,
+    },
     Array(Vec<SerializedValue>),
     EnumValue {
         type_name: String,
@@ -94,6 +149,23 @@ enum SerializedValue {
         body: u32,
         captured: BTreeMap<String, SerializedValue>,
     },
+    PartialApplication {
+        module_id: Option<u32>,
+        function: String,
+        args: Vec<Option<SerializedValue>>,
+    },
+}
+
+/// What [`Interpreter::call_named`] did with a call
+enum CallOutcome<'a> {
+    /// The call was evaluated to a value.
+    Value(Value),
+    /// The call targets a program function, whose body the caller runs.
+    Enter {
+        module: &'a LoweredModule,
+        function: &'a Function,
+        args: Vec<Value>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -109,7 +181,13 @@ impl Interpreter {
     pub fn new() -> Self {
         Self {
             program: None,
-            runtime_prepared_cache: RefCell::new(FxHashMap::default()),
+            runtime_prepared_cache: RwLock::new(FxHashMap::default()),
+            random_source: RandomSource::default(),
+            null_concatenation: NullConcatenation::default(),
+            logger: None,
+            translations: None,
+            backend: Backend::default(),
+            host_functions: HostFunctions::default(),
         }
     }
 
@@ -117,10 +195,82 @@ impl Interpreter {
     pub fn from_resolved_program(program: ResolvedProgram) -> Self {
         Self {
             program: Some(program),
-            runtime_prepared_cache: RefCell::new(FxHashMap::default()),
+            runtime_prepared_cache: RwLock::new(FxHashMap::default()),
+            random_source: RandomSource::default(),
+            null_concatenation: NullConcatenation::default(),
+            logger: None,
+            translations: None,
+            backend: Backend::default(),
+            host_functions: HostFunctions::default(),
         }
     }
 
+    /// Set where the `random`, `randomInt` and `uuid` builtins draw from.
+    ///
+    /// Every execution starts a fresh generator, so [`RandomSource::Seeded`] produces the same
+    /// values on each run.
+    pub fn with_random_source(mut self, source: RandomSource) -> Self {
+        self.random_source = source;
+        self
+    }
+
+    /// Set how string concatenation treats a null operand. The default is
+    /// [`NullConcatenation::Error`].
+    pub fn with_null_concatenation(mut self, policy: NullConcatenation) -> Self {
+        self.null_concatenation = policy;
+        self
+    }
+
+    /// Send `log` calls at `min_level` or above to `sink`.
+    ///
+    /// Without a sink, `log` calls are discarded. The sink is shared by every execution, including
+    /// concurrent ones.
+    pub fn with_log_sink(mut self, sink: Arc<dyn LogSink>, min_level: LogLevel) -> Self {
+        self.logger = Some(Logger::new(sink, min_level));
+        self
+    }
+
+    /// Translate the messages of `t` calls with `catalog`.
+    ///
+    /// Without a catalog, `t` shows its message untranslated. The catalog is shared by every
+    /// execution, including concurrent ones.
+    pub fn with_translations(mut self, catalog: Arc<MessageCatalog>) -> Self {
+        self.translations = Some(catalog);
+        self
+    }
+
+    /// Set how function bodies are executed. The default is [`Backend::TreeWalker`].
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Make `function` callable from NX code as `name(...)`.
+    ///
+    /// A function or record declared in the program under the same name takes precedence, and a
+    /// host function takes precedence over a builtin of the same name. Registering a name again
+    /// replaces the previous function. Declare the function's type with
+    /// `TypeCheckSession::declare_host_function` so that calls to it type check.
+    pub fn register_function<F>(&mut self, name: impl Into<SmolStr>, function: F)
+    where
+        F: Fn(&[Value]) -> Result<Value, RuntimeError> + Send + Sync + 'static,
+    {
+        let function: Arc<HostFunction> = Arc::new(function);
+        self.host_functions.insert(name.into(), function);
+    }
+
+    /// Create an execution context that draws from this interpreter's random source, logs to
+    /// its log sink and translates with its message catalog
+    ///
+    /// Hosts seed globals on the returned context with [`ExecutionContext::set_global`] and pass
+    /// it to [`execute_function_in_context`](Self::execute_function_in_context).
+    pub fn new_context(&self, limits: ResourceLimits) -> ExecutionContext {
+        ExecutionContext::with_limits(limits)
+            .with_random_source(self.random_source)
+            .with_logger(self.logger.clone())
+            .with_translations(self.translations.clone())
+    }
+
     /// Execute a function by name with the given arguments
     ///
     /// Uses default resource limits (recursion: 1000, operations: 1M).
@@ -296,6 +446,125 @@ impl Interpreter {
         args: Vec<Value>,
         limits: ResourceLimits,
     ) -> Result<Value, RuntimeError> {
+        let (module, target_name) =
+            self.resolve_program_module_function(module_id, function_name)?;
+        self.execute_function_with_limits(module, target_name, args, limits)
+    }
+
+    /// Execute a resolved-program function, reporting the root element's children to `sink` as
+    /// they are produced.
+    ///
+    /// See [`StreamEvent`] for the events and for what happens when evaluation fails after some
+    /// children were reported. On success the complete result is returned as well, identical to
+    /// [`execute_resolved_program_module_function`](Self::execute_resolved_program_module_function).
+    pub fn stream_resolved_program_module_function(
+        &self,
+        module_id: RuntimeModuleId,
+        function_name: &str,
+        args: Vec<Value>,
+        sink: &mut dyn FnMut(StreamEvent<'_>),
+    ) -> Result<Value, RuntimeError> {
+        self.stream_resolved_program_module_function_with_limits(
+            module_id,
+            function_name,
+            args,
+            ResourceLimits::default(),
+            sink,
+        )
+    }
+
+    /// Stream a resolved-program function from a specific module id with custom limits.
+    pub fn stream_resolved_program_module_function_with_limits(
+        &self,
+        module_id: RuntimeModuleId,
+        function_name: &str,
+        args: Vec<Value>,
+        limits: ResourceLimits,
+        sink: &mut dyn FnMut(StreamEvent<'_>),
+    ) -> Result<Value, RuntimeError> {
+        let (module, target_name) =
+            self.resolve_program_module_function(module_id, function_name)?;
+        let (function, mut ctx) = self.enter_function(module, target_name, args, limits)?;
+        let result = self.stream_expr(module, &mut ctx, function.body, sink)?;
+        self.coerce_return_value(module, function, result)
+    }
+
+    /// Evaluate an entry function body, streaming the children of an intrinsic root element.
+    fn stream_expr(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        expr_id: ExprId,
+        sink: &mut dyn FnMut(StreamEvent<'_>),
+    ) -> Result<Value, RuntimeError> {
+        match module.expr(expr_id) {
+            ast::Expr::Block {
+                stmts,
+                expr: Some(tail),
+                ..
+            } if stmts.is_empty() => self.stream_expr(module, ctx, *tail, sink),
+            ast::Expr::Element { element, .. }
+                if self.is_intrinsic_tag(module, module.element(*element).tag.as_str()) =>
+            {
+                ctx.check_operation_limit()?;
+                let element = module.element(*element);
+                let mut fields = self.eval_element_fields(module, ctx, element)?;
+                sink(StreamEvent::Open {
+                    tag: &element.tag,
+                    properties: &fields,
+                });
+
+                let mut children = Vec::new();
+                for content_expr in &element.content {
+                    let start = children.len();
+                    match self.eval_expr(module, ctx, *content_expr)? {
+                        Value::Array(items) => children.extend(items),
+                        other => children.push(other),
+                    }
+                    for child in &children[start..] {
+                        sink(StreamEvent::Child(child));
+                    }
+                }
+
+                self.inject_element_content_field(
+                    &mut fields,
+                    self.normalize_content_values(children),
+                    Some("content"),
+                    "intrinsic element content channel",
+                    "element intrinsic call",
+                )?;
+                sink(StreamEvent::Close);
+                Ok(Value::Record {
+                    type_name: element.tag.clone(),
+                    fields,
+                })
+            }
+            _ => {
+                let value = self.eval_body(module, ctx, expr_id)?;
+                sink(StreamEvent::Child(&value));
+                Ok(value)
+            }
+        }
+    }
+
+    /// Returns true if an element tag names no union case, function, component or record, so the
+    /// element evaluates to a plain record of its properties and content.
+    fn is_intrinsic_tag(&self, module: &LoweredModule, tag_name: &str) -> bool {
+        self.resolve_union_case_definition(module, tag_name)
+            .is_none()
+            && !matches!(
+                self.resolve_item(module, tag_name),
+                Some((_, Item::Function(_) | Item::Component(_)))
+            )
+            && self.resolve_record_definition(module, tag_name).is_none()
+    }
+
+    /// Find a function in a resolved-program module, returning its module and declared name.
+    fn resolve_program_module_function<'a>(
+        &'a self,
+        module_id: RuntimeModuleId,
+        function_name: &'a str,
+    ) -> Result<(&'a LoweredModule, &'a str), RuntimeError> {
         let program = self.program.as_ref().ok_or_else(|| {
             RuntimeError::new(RuntimeErrorKind::FunctionNotFound {
                 name: SmolStr::new(function_name),
@@ -327,7 +596,7 @@ impl Interpreter {
             _ => function_name,
         };
 
-        self.execute_function_with_limits(module.lowered_module.as_ref(), target_name, args, limits)
+        Ok((module.lowered_module.as_ref(), target_name))
     }
 
     /// Initialize a resolved-program component entrypoint.
@@ -585,17 +854,18 @@ impl Interpreter {
             return Arc::new(PreparedModule::standalone(module_identity, module.clone()));
         };
 
-        if let Some(prepared) = self.runtime_prepared_cache.borrow().get(&module_id) {
+        if let Some(prepared) = self
+            .runtime_prepared_cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&module_id)
+        {
             return Arc::clone(prepared);
         };
 
         let mut prepared = PreparedModule::standalone(module_identity, module.clone());
         let Some(visible_items) = program.imported_items(module_id) else {
-            let prepared = Arc::new(prepared);
-            self.runtime_prepared_cache
-                .borrow_mut()
-                .insert(module_id, Arc::clone(&prepared));
-            return prepared;
+            return self.cache_prepared_module(module_id, prepared);
         };
 
         for (visible_name, item_ref) in visible_items {
@@ -628,11 +898,20 @@ impl Interpreter {
             }
         }
 
-        let prepared = Arc::new(prepared);
-        self.runtime_prepared_cache
-            .borrow_mut()
-            .insert(module_id, Arc::clone(&prepared));
-        prepared
+        self.cache_prepared_module(module_id, prepared)
+    }
+
+    /// Store a prepared module, keeping the first one if another thread cached it meanwhile.
+    fn cache_prepared_module(
+        &self,
+        module_id: RuntimeModuleId,
+        prepared: PreparedModule,
+    ) -> Arc<PreparedModule> {
+        let mut cache = self
+            .runtime_prepared_cache
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(cache.entry(module_id).or_insert_with(|| Arc::new(prepared)))
     }
 
     fn resolve_item<'a>(
@@ -702,6 +981,68 @@ impl Interpreter {
         args: Vec<Value>,
         limits: ResourceLimits,
     ) -> Result<Value, RuntimeError> {
+        let (function, mut ctx) = self.enter_function(module, function_name, args, limits)?;
+
+        // Execute the function body
+        let result = self.eval_body(module, &mut ctx, function.body)?;
+        self.coerce_return_value(module, function, result)
+    }
+
+    /// Execute a function by name in a context supplied by the host
+    ///
+    /// Globals set on `ctx` with [`ExecutionContext::set_global`] are visible to the program,
+    /// unless a top-level value, parameter or local binding shadows them. The context's own
+    /// limits and random source apply; [`new_context`](Self::new_context) creates one that
+    /// matches this interpreter.
+    ///
+    /// When execution returns, `ctx` still holds the module's top-level values and the entry
+    /// function's parameters, so hosts can inspect the final environment with
+    /// [`ExecutionContext::snapshot_visible_variables`]. Reusing `ctx` for another execution
+    /// keeps its globals and its operation count.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use nx_interpreter::{Interpreter, ResourceLimits, Value};
+    ///
+    /// let interpreter = Interpreter::new();
+    /// let mut ctx = interpreter.new_context(ResourceLimits::default());
+    /// ctx.set_global("theme", Value::String("dark".into()));
+    /// let result = interpreter.execute_function_in_context(&module, "root", vec![], &mut ctx)?;
+    /// ```
+    pub fn execute_function_in_context(
+        &self,
+        module: &LoweredModule,
+        function_name: &str,
+        args: Vec<Value>,
+        ctx: &mut ExecutionContext,
+    ) -> Result<Value, RuntimeError> {
+        let function = self.bind_function_arguments(module, function_name, args, ctx)?;
+        ctx.clear_compiled_chunks();
+        let result = self.eval_body(module, ctx, function.body)?;
+        self.coerce_return_value(module, function, result)
+    }
+
+    /// Find a function, create its execution context, and bind its arguments
+    fn enter_function<'a>(
+        &'a self,
+        module: &'a LoweredModule,
+        function_name: &str,
+        args: Vec<Value>,
+        limits: ResourceLimits,
+    ) -> Result<(&'a Function, ExecutionContext), RuntimeError> {
+        let mut ctx = self.new_context(limits);
+        let function = self.bind_function_arguments(module, function_name, args, &mut ctx)?;
+        Ok((function, ctx))
+    }
+
+    /// Find a function and bind the module's top-level values and its arguments in `ctx`
+    fn bind_function_arguments<'a>(
+        &'a self,
+        module: &'a LoweredModule,
+        function_name: &str,
+        args: Vec<Value>,
+        ctx: &mut ExecutionContext,
+    ) -> Result<&'a Function, RuntimeError> {
         // T011: Find function in module
         let function = self.find_function(module, function_name)?;
 
@@ -716,20 +1057,32 @@ impl Interpreter {
             ));
         }
 
-        // T011: Create execution context
-        let mut ctx = ExecutionContext::with_limits(limits);
-        self.bind_top_level_values(module, &mut ctx)?;
+        self.bind_top_level_values(module, ctx)?;
 
         let coerced_args =
             self.coerce_arguments_for_params(module, args, &function.params, "function call")?;
+        for (param, arg) in function.params.iter().zip(coerced_args.iter()) {
+            self.check_host_value_constraints(
+                module,
+                arg,
+                &format!("function call parameter '{}'", param.name.as_str()),
+            )?;
+        }
 
         // T012: Bind parameters to argument values
         for (param, arg) in function.params.iter().zip(coerced_args.iter()) {
             ctx.define_variable(SmolStr::new(param.name.as_str()), arg.clone());
         }
 
-        // Execute the function body
-        let result = self.eval_expr(module, &mut ctx, function.body)?;
+        Ok(function)
+    }
+
+    fn coerce_return_value(
+        &self,
+        module: &LoweredModule,
+        function: &Function,
+        result: Value,
+    ) -> Result<Value, RuntimeError> {
         if let Some(return_type) = function.return_type.as_ref() {
             self.coerce_value_to_type(
                 module,
@@ -772,7 +1125,7 @@ impl Interpreter {
         let action =
             self.validate_handler_input(handler_module, action, action_name, component, emit)?;
 
-        let mut ctx = ExecutionContext::with_limits(limits);
+        let mut ctx = self.new_context(limits);
         self.bind_top_level_values(handler_module, &mut ctx)?;
         for (name, value) in captured {
             ctx.define_variable(name.clone(), value.clone());
@@ -794,7 +1147,7 @@ impl Interpreter {
         let component = self.find_component(module, component_name)?;
         let contract = self.effective_component_contract(module, component);
         self.ensure_concrete_component(&contract, "component initialization")?;
-        let mut ctx = ExecutionContext::with_limits(limits);
+        let mut ctx = self.new_context(limits);
         self.bind_top_level_values(module, &mut ctx)?;
         let normalized_props =
             self.normalize_component_props(module, &mut ctx, component, &contract, props)?;
@@ -855,7 +1208,7 @@ impl Interpreter {
         let contract = self.effective_component_contract(module, component);
         self.ensure_concrete_component(&contract, "component evaluation")?;
 
-        let mut ctx = ExecutionContext::with_limits(limits);
+        let mut ctx = self.new_context(limits);
         self.bind_top_level_values(module, &mut ctx)?;
         let normalized_props =
             self.normalize_component_props(module, &mut ctx, component, &contract, props)?;
@@ -1051,6 +1404,36 @@ impl Interpreter {
         self.module_for_identity(current_module, field.module_identity.as_str(), operation)
     }
 
+    /// Returns the order in which the fields of `shape` are materialized, as indices into its
+    /// fields; see [`nx_hir::default_evaluation_order`].
+    fn record_default_order(
+        &self,
+        module: &LoweredModule,
+        shape: &nx_hir::EffectiveRecordShape,
+    ) -> Result<Vec<usize>, RuntimeError> {
+        nx_hir::default_evaluation_order(&shape.fields, |field| {
+            let mut names = FxHashSet::default();
+            let owner = field.default.as_ref().and_then(|default| {
+                self.module_for_identity(module, &default.module_identity, "record construction")
+                    .ok()
+                    .map(|owner| (owner, default.expr_id))
+            });
+            if let Some((owner, expr_id)) = owner {
+                nx_hir::walk::collect_referenced_names(owner, expr_id, &mut names);
+            }
+            names
+        })
+        .map_err(|cycle| {
+            RuntimeError::new(RuntimeErrorKind::RecordDefaultCycle {
+                record: SmolStr::new(shape.record.name.as_str()),
+                cycle: cycle
+                    .iter()
+                    .map(|name| SmolStr::new(name.as_str()))
+                    .collect(),
+            })
+        })
+    }
+
     fn eval_effective_field_default(
         &self,
         current_module: &LoweredModule,
@@ -1078,6 +1461,19 @@ impl Interpreter {
         result
     }
 
+    fn eval_param_default(
+        &self,
+        owner_module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        default_expr: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        let mut default_ctx = ctx.fork_isolated();
+        self.bind_top_level_values(owner_module, &mut default_ctx)?;
+        let result = self.eval_expr(owner_module, &mut default_ctx, default_expr);
+        ctx.sync_usage_from(&default_ctx);
+        result
+    }
+
     fn flattened_expr_name(&self, module: &LoweredModule, expr_id: ExprId) -> Option<String> {
         match module.expr(expr_id) {
             ast::Expr::Ident(name) => Some(name.as_str().to_string()),
@@ -1237,6 +1633,7 @@ impl Interpreter {
             "prop initialization",
         )?;
 
+        let mut rest_fields = FxHashMap::default();
         for (name, value) in overrides {
             if let Some(emit) = contract
                 .emits
@@ -1273,6 +1670,8 @@ impl Interpreter {
                 }
 
                 normalized.insert(name, value);
+            } else if contract.component.rest.is_some() {
+                rest_fields.insert(name, value);
             } else {
                 return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
                     expected: "declared component prop".to_string(),
@@ -1285,6 +1684,15 @@ impl Interpreter {
             }
         }
 
+        if let Some(rest) = contract.component.rest.as_ref() {
+            let rest_value = Value::Record {
+                type_name: Name::new("props"),
+                fields: rest_fields,
+            };
+            ctx.define_variable(SmolStr::new(rest.as_str()), rest_value.clone());
+            normalized.insert(SmolStr::new(rest.as_str()), rest_value);
+        }
+
         Ok(normalized)
     }
 
@@ -1529,6 +1937,10 @@ impl Interpreter {
             Value::String(value) => SerializedValue::String(value.to_string()),
             Value::Boolean(value) => SerializedValue::Boolean(*value),
             Value::Null => SerializedValue::Null,
+            Value::Money(money) => SerializedValue::Money {
+                units: money.minor_units(),
+                currency: money.currency().to_string(),
+            },
             Value::Array(values) => {
                 SerializedValue::Array(values.iter().map(Self::serialize_runtime_value).collect())
             }
@@ -1561,6 +1973,20 @@ impl Interpreter {
                     .map(|(name, value)| (name.to_string(), Self::serialize_runtime_value(value)))
                     .collect(),
             },
+            Value::PartialApplication {
+                module_id,
+                function,
+                args,
+            } => SerializedValue::PartialApplication {
+                module_id: module_id.as_ref().map(RuntimeModuleId::as_u32),
+                function: function.as_str().to_string(),
+                args: args
+                    .iter()
+                    .map(|arg| arg.as_ref().map(Self::serialize_runtime_value))
+                    .collect(),
+            },
+            // Snapshots outlive the host objects, so only the native value's data is kept.
+            Value::Native(native) => Self::serialize_runtime_value(&native.get().to_value()),
         }
     }
 
@@ -1577,6 +2003,11 @@ impl Interpreter {
             SerializedValue::String(value) => Ok(Value::String(SmolStr::new(value.as_str()))),
             SerializedValue::Boolean(value) => Ok(Value::Boolean(value)),
             SerializedValue::Null => Ok(Value::Null),
+            SerializedValue::Money { units, currency } => Money::from_minor_units(units, &currency)
+                .map(Value::Money)
+                .map_err(|reason| {
+                    RuntimeError::new(RuntimeErrorKind::InvalidComponentStateSnapshot { reason })
+                }),
             SerializedValue::Array(values) => Ok(Value::Array(
                 values
                     .into_iter()
@@ -1642,6 +2073,55 @@ impl Interpreter {
                         .collect::<Result<FxHashMap<_, _>, RuntimeError>>()?,
                 })
             }
+            SerializedValue::PartialApplication {
+                module_id,
+                function,
+                args,
+            } => {
+                let module_id = module_id.map(RuntimeModuleId::new);
+                let function_module = match module_id {
+                    Some(module_id) => self.module_for_id(module, module_id)?,
+                    None => module,
+                };
+                if !matches!(
+                    function_module.find_item(&function),
+                    Some(Item::Function(_))
+                ) {
+                    return Err(RuntimeError::new(
+                        RuntimeErrorKind::InvalidComponentStateSnapshot {
+                            reason: format!(
+                                "partial application references unknown function '{}'",
+                                function
+                            ),
+                        },
+                    ));
+                }
+
+                Ok(Value::PartialApplication {
+                    module_id,
+                    function: Name::new(&function),
+                    args: args
+                        .into_iter()
+                        .map(|arg| {
+                            arg.map(|arg| self.deserialize_runtime_value(module, arg))
+                                .transpose()
+                        })
+                        .collect::<Result<Vec<_>, RuntimeError>>()?,
+                })
+            }
+        }
+    }
+
+    /// Evaluate a function body with the interpreter's [`Backend`]
+    fn eval_body(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        body: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        match self.backend {
+            Backend::TreeWalker => self.eval_expr(module, ctx, body),
+            Backend::Bytecode => self.run_bytecode(module, ctx, body),
         }
     }
 
@@ -1681,7 +2161,10 @@ impl Interpreter {
             ast::Expr::Let {
                 name, value, body, ..
             } => self.eval_let(module, ctx, name, *value, *body),
-            ast::Expr::Call { func, args, .. } => self.eval_call(module, ctx, *func, args),
+            ast::Expr::Call { func, args, span } => self.eval_call(module, ctx, *func, args, *span),
+            ast::Expr::PartialApply { func, args, .. } => {
+                self.eval_partial_apply(module, ctx, *func, args)
+            }
             ast::Expr::For {
                 item,
                 index,
@@ -1708,6 +2191,13 @@ impl Interpreter {
                 record, properties, ..
             } => self.eval_record_literal(module, ctx, record, properties),
             ast::Expr::Member { base, member, .. } => self.eval_member(module, ctx, *base, member),
+            ast::Expr::Provide {
+                key, value, body, ..
+            } => self.eval_provide(module, ctx, key, *value, *body),
+            ast::Expr::UseContext { key, .. } => self.eval_use_context(module, ctx, key),
+            ast::Expr::Destructure { source, part, .. } => {
+                self.eval_destructure(module, ctx, *source, part)
+            }
             _ => {
                 // Other expression types not yet implemented
                 Ok(Value::Null)
@@ -1730,7 +2220,7 @@ impl Interpreter {
     fn eval_action_handler_expr(
         &self,
         module: &LoweredModule,
-        ctx: &ExecutionContext,
+        ctx: &mut ExecutionContext,
         component: &Name,
         emit: &Name,
         action_name: &Name,
@@ -1738,6 +2228,7 @@ impl Interpreter {
     ) -> Result<Value, RuntimeError> {
         let mut captured = ctx.snapshot_visible_variables();
         captured.remove("action");
+        ctx.record_capture(&captured)?;
         let module_id = self.require_current_module_id(module, "action handler creation")?;
 
         Ok(Value::ActionHandler {
@@ -1860,35 +2351,46 @@ impl Interpreter {
             _ => {
                 let lhs_val = self.eval_expr(module, ctx, lhs)?;
                 let rhs_val = self.eval_expr(module, ctx, rhs)?;
-
-                match op {
-                    // Arithmetic operators
-                    ast::BinOp::Add
-                    | ast::BinOp::Sub
-                    | ast::BinOp::Mul
-                    | ast::BinOp::Div
-                    | ast::BinOp::Mod
-                    | ast::BinOp::Concat => {
-                        crate::eval::arithmetic::eval_arithmetic_op(lhs_val, op, rhs_val)
-                    }
-
-                    // Comparison operators (T036)
-                    ast::BinOp::Eq
-                    | ast::BinOp::Ne
-                    | ast::BinOp::Lt
-                    | ast::BinOp::Le
-                    | ast::BinOp::Gt
-                    | ast::BinOp::Ge => {
-                        crate::eval::logical::eval_comparison_op(lhs_val, op, rhs_val)
-                    }
-
-                    // And/Or already handled above
-                    ast::BinOp::And | ast::BinOp::Or => unreachable!(),
-                }
+                self.apply_binary_op(lhs_val, op, rhs_val)
             }
         }
     }
 
+    /// Apply an eagerly evaluated binary operator to its operands
+    fn apply_binary_op(
+        &self,
+        lhs_val: Value,
+        op: ast::BinOp,
+        rhs_val: Value,
+    ) -> Result<Value, RuntimeError> {
+        match op {
+            // Arithmetic operators
+            ast::BinOp::Add
+            | ast::BinOp::Sub
+            | ast::BinOp::Mul
+            | ast::BinOp::Div
+            | ast::BinOp::Mod
+            | ast::BinOp::Concat => crate::eval::arithmetic::eval_null_concat(
+                &lhs_val,
+                op,
+                &rhs_val,
+                self.null_concatenation,
+            )
+            .unwrap_or_else(|| crate::eval::arithmetic::eval_arithmetic_op(lhs_val, op, rhs_val)),
+
+            // Comparison operators (T036)
+            ast::BinOp::Eq
+            | ast::BinOp::Ne
+            | ast::BinOp::Lt
+            | ast::BinOp::Le
+            | ast::BinOp::Gt
+            | ast::BinOp::Ge => crate::eval::logical::eval_comparison_op(lhs_val, op, rhs_val),
+
+            // And/Or short-circuit and are evaluated by their callers
+            ast::BinOp::And | ast::BinOp::Or => unreachable!(),
+        }
+    }
+
     /// Evaluate a unary operation (T038)
     fn eval_unary_op(
         &self,
@@ -1898,7 +2400,11 @@ impl Interpreter {
         expr: ExprId,
     ) -> Result<Value, RuntimeError> {
         let operand = self.eval_expr(module, ctx, expr)?;
+        Self::apply_unary_op(op, operand)
+    }
 
+    /// Apply a unary operator to its evaluated operand
+    fn apply_unary_op(op: ast::UnOp, operand: Value) -> Result<Value, RuntimeError> {
         match op {
             ast::UnOp::Not => crate::eval::logical::eval_logical_unary(op, operand),
             ast::UnOp::Neg => {
@@ -1908,6 +2414,7 @@ impl Interpreter {
                     Value::Int(n) => Ok(Value::Int(-n)),
                     Value::Float32(f) => Ok(Value::Float32(-f)),
                     Value::Float(f) => Ok(Value::Float(-f)),
+                    Value::Money(money) => money.checked_neg().map(Value::Money),
                     v => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
                         expected: "number".to_string(),
                         actual: v.type_name().to_string(),
@@ -2052,6 +2559,137 @@ impl Interpreter {
         result
     }
 
+    /// Resolve the top-level value that keys a context, with the module declaring it
+    fn resolve_context_key<'a>(
+        &'a self,
+        module: &'a LoweredModule,
+        key: &Name,
+    ) -> Result<(ContextKey, &'a LoweredModule, &'a ValueDef), RuntimeError> {
+        match self.resolve_item(module, key.as_str()) {
+            Some((owner_module, Item::Value(value))) => Ok((
+                ContextKey {
+                    module: self.current_module_id(owner_module),
+                    name: SmolStr::new(value.name.as_str()),
+                },
+                owner_module,
+                value,
+            )),
+            _ => Err(RuntimeError::new(RuntimeErrorKind::UnknownContext {
+                name: SmolStr::new(key.as_str()),
+            })),
+        }
+    }
+
+    /// Evaluate `provide key = value { body }`
+    ///
+    /// The provided value stays visible to `use key` in everything the body evaluates, including
+    /// called functions and components, and is removed again once the body finishes.
+    fn eval_provide(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        key: &Name,
+        value: ExprId,
+        body: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        let (key, owner_module, value_def) = self.resolve_context_key(module, key)?;
+        let mut provided = self.eval_expr(module, ctx, value)?;
+        if let Some(ty) = value_def.ty.as_ref() {
+            provided = self.coerce_value_to_type(
+                owner_module,
+                provided,
+                ty,
+                &format!("value provided for context '{}'", key.name),
+            )?;
+        }
+
+        ctx.push_provided(key, provided);
+        let result = self.eval_expr(module, ctx, body);
+        ctx.pop_provided();
+
+        result
+    }
+
+    /// Evaluate `use key`, falling back to the top-level value when nothing provides it
+    fn eval_use_context(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        key: &Name,
+    ) -> Result<Value, RuntimeError> {
+        let (key, owner_module, _) = self.resolve_context_key(module, key)?;
+        if let Some(value) = ctx.provided(&key) {
+            return Ok(value);
+        }
+
+        let mut default_ctx = ctx.fork_isolated();
+        self.bind_top_level_values(owner_module, &mut default_ctx)?;
+        let result = default_ctx.lookup_variable(key.name.as_str());
+        ctx.sync_usage_from(&default_ctx);
+        result
+    }
+
+    /// Evaluate one binding of a destructuring `let`
+    fn eval_destructure(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        source: ExprId,
+        part: &ast::DestructurePart,
+    ) -> Result<Value, RuntimeError> {
+        let array_pattern_mismatch = |expected: usize, has_rest: bool, actual: usize| {
+            RuntimeError::new(RuntimeErrorKind::ArrayPatternMismatch {
+                expected,
+                has_rest,
+                actual,
+            })
+        };
+
+        match (part, self.eval_expr(module, ctx, source)?) {
+            (ast::DestructurePart::Field(name), Value::Record { type_name, fields }) => {
+                fields.get(name.as_str()).cloned().ok_or_else(|| {
+                    RuntimeError::new(RuntimeErrorKind::RecordFieldNotFound {
+                        record: SmolStr::new(type_name.as_str()),
+                        field: SmolStr::new(name.as_str()),
+                    })
+                })
+            }
+            (
+                ast::DestructurePart::Element {
+                    index,
+                    len,
+                    has_rest,
+                },
+                Value::Array(mut elements),
+            ) => {
+                let fits = if *has_rest {
+                    elements.len() >= *len
+                } else {
+                    elements.len() == *len
+                };
+                if !fits {
+                    return Err(array_pattern_mismatch(*len, *has_rest, elements.len()));
+                }
+                Ok(elements.swap_remove(*index))
+            }
+            (ast::DestructurePart::Rest { skip }, Value::Array(elements)) => {
+                if elements.len() < *skip {
+                    return Err(array_pattern_mismatch(*skip, true, elements.len()));
+                }
+                Ok(Value::Array(elements.into_iter().skip(*skip).collect()))
+            }
+            (part, other) => Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+                expected: match part {
+                    ast::DestructurePart::Field(_) => "record",
+                    _ => "array",
+                }
+                .to_string(),
+                actual: other.type_name().to_string(),
+                operation: "destructuring".to_string(),
+            })),
+        }
+    }
+
     /// Evaluate a function call expression (T053)
     fn eval_call(
         &self,
@@ -2059,6 +2697,7 @@ impl Interpreter {
         ctx: &mut ExecutionContext,
         func_expr: ExprId,
         args: &[ExprId],
+        span: TextRange,
     ) -> Result<Value, RuntimeError> {
         let func_name = self.flattened_expr_name(module, func_expr).ok_or_else(|| {
             RuntimeError::new(RuntimeErrorKind::TypeMismatch {
@@ -2073,36 +2712,153 @@ impl Interpreter {
             arg_values.push(self.eval_expr(module, ctx, *arg_expr)?);
         }
 
-        match self.resolve_item(module, func_name.as_str()) {
-            Some((target_module, Item::Function(function))) => self.eval_function_call(
-                target_module,
-                ctx,
-                func_name.as_str(),
+        match self.call_named(module, ctx, func_name.as_str(), arg_values, span)? {
+            CallOutcome::Value(value) => Ok(value),
+            CallOutcome::Enter {
+                module: target_module,
                 function,
-                arg_values,
-            ),
+                args,
+            } => self.eval_function_call(target_module, ctx, func_name.as_str(), function, args),
+        }
+    }
+
+    /// Call `func_name` with evaluated arguments
+    ///
+    /// Partial applications, record constructors, host functions and builtins are called here. A function
+    /// declared in the program is returned for the caller to enter, so that each backend can
+    /// run its body in its own way.
+    fn call_named<'a>(
+        &'a self,
+        module: &'a LoweredModule,
+        ctx: &mut ExecutionContext,
+        func_name: &str,
+        arg_values: Vec<Value>,
+        span: TextRange,
+    ) -> Result<CallOutcome<'a>, RuntimeError> {
+        if let Some(Value::PartialApplication {
+            module_id,
+            function,
+            args: bound,
+        }) = ctx.try_lookup_variable(func_name)
+        {
+            let supplied = arg_values.into_iter().map(Some).collect();
+            let args = Self::fill_partial_arguments(&function, bound, supplied)?;
+            return self
+                .call_partial_application(module, ctx, module_id, &function, args)
+                .map(CallOutcome::Value);
+        }
+
+        let value = match self.resolve_item(module, func_name) {
+            Some((target_module, Item::Function(function))) => {
+                return Ok(CallOutcome::Enter {
+                    module: target_module,
+                    function,
+                    args: arg_values,
+                })
+            }
             Some((target_module, Item::Record(record_def))) => self.eval_record_constructor_call(
                 target_module,
                 ctx,
-                func_name.as_str(),
+                func_name,
                 record_def,
                 arg_values,
             ),
             Some((target_module, Item::TypeAlias(_))) => {
-                if let Some(record_def) = self.resolve_record_definition(module, func_name.as_str())
-                {
+                if let Some(record_def) = self.resolve_record_definition(module, func_name) {
                     self.eval_record_constructor_call(
                         target_module,
                         ctx,
-                        func_name.as_str(),
+                        func_name,
                         &record_def,
                         arg_values,
                     )
                 } else {
                     Err(RuntimeError::new(RuntimeErrorKind::FunctionNotFound {
-                        name: SmolStr::new(func_name.as_str()),
+                        name: SmolStr::new(func_name),
+                    }))
+                }
+            }
+            _ => match self.host_functions.get(func_name) {
+                Some(function) => function(&arg_values),
+                None => crate::eval::functions::call_builtin(
+                    ctx,
+                    func_name,
+                    &arg_values,
+                    &mut |ctx, function, args| {
+                        self.call_function_value(module, ctx, function, args)
+                    },
+                )
+                .unwrap_or_else(|| {
+                    Err(RuntimeError::new(RuntimeErrorKind::FunctionNotFound {
+                        name: SmolStr::new(func_name),
                     }))
+                })
+                .map_err(|error| match error.kind() {
+                    RuntimeErrorKind::Assertion { .. } => error.with_location(span),
+                    _ => error,
+                }),
+            },
+        };
+        value.map(CallOutcome::Value)
+    }
+
+    /// Evaluate `f(a, _)` into a function value holding the supplied arguments.
+    ///
+    /// Applying placeholders to a variable that already holds a partial application fills its
+    /// open parameters in order instead of wrapping it again.
+    fn eval_partial_apply(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        func_expr: ExprId,
+        args: &[Option<ExprId>],
+    ) -> Result<Value, RuntimeError> {
+        let func_name = self.flattened_expr_name(module, func_expr).ok_or_else(|| {
+            RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+                expected: "function name".to_string(),
+                actual: "complex expression".to_string(),
+                operation: "partial application".to_string(),
+            })
+        })?;
+
+        let mut arg_values = Vec::with_capacity(args.len());
+        for arg_expr in args {
+            arg_values.push(match arg_expr {
+                Some(arg_expr) => Some(self.eval_expr(module, ctx, *arg_expr)?),
+                None => None,
+            });
+        }
+
+        if let Some(Value::PartialApplication {
+            module_id,
+            function,
+            args: bound,
+        }) = ctx.try_lookup_variable(func_name.as_str())
+        {
+            let args = Self::fill_partial_arguments(&function, bound, arg_values)?;
+            return Ok(Value::PartialApplication {
+                module_id,
+                function,
+                args,
+            });
+        }
+
+        match self.resolve_item(module, func_name.as_str()) {
+            Some((target_module, Item::Function(function))) => {
+                if function.params.len() != arg_values.len() {
+                    return Err(RuntimeError::new(
+                        RuntimeErrorKind::ParameterCountMismatch {
+                            expected: function.params.len(),
+                            actual: arg_values.len(),
+                            function: SmolStr::new(func_name.as_str()),
+                        },
+                    ));
                 }
+                Ok(Value::PartialApplication {
+                    module_id: self.current_module_id(target_module),
+                    function: function.name.clone(),
+                    args: arg_values,
+                })
             }
             _ => Err(RuntimeError::new(RuntimeErrorKind::FunctionNotFound {
                 name: SmolStr::new(func_name.as_str()),
@@ -2110,6 +2866,80 @@ impl Interpreter {
         }
     }
 
+    /// Fill the open parameters of a partial application, in order, with `supplied`.
+    fn fill_partial_arguments(
+        function: &Name,
+        bound: Vec<Option<Value>>,
+        supplied: Vec<Option<Value>>,
+    ) -> Result<Vec<Option<Value>>, RuntimeError> {
+        let open = bound.iter().filter(|arg| arg.is_none()).count();
+        if open != supplied.len() {
+            return Err(RuntimeError::new(
+                RuntimeErrorKind::ParameterCountMismatch {
+                    expected: open,
+                    actual: supplied.len(),
+                    function: SmolStr::new(function.as_str()),
+                },
+            ));
+        }
+
+        let mut supplied = supplied.into_iter();
+        Ok(bound
+            .into_iter()
+            .map(|arg| arg.or_else(|| supplied.next().flatten()))
+            .collect())
+    }
+
+    /// Call a function value, such as one passed to `map`, with `args`
+    fn call_function_value(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        function: &Value,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let Value::PartialApplication {
+            module_id,
+            function,
+            args: bound,
+        } = function
+        else {
+            return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+                expected: "function".to_string(),
+                actual: function.type_name().to_string(),
+                operation: "function call".to_string(),
+            }));
+        };
+        let supplied = args.into_iter().map(Some).collect();
+        let args = Self::fill_partial_arguments(function, bound.clone(), supplied)?;
+        self.call_partial_application(module, ctx, *module_id, function, args)
+    }
+
+    fn call_partial_application(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        module_id: Option<RuntimeModuleId>,
+        function_name: &Name,
+        args: Vec<Option<Value>>,
+    ) -> Result<Value, RuntimeError> {
+        let function_module = match module_id {
+            Some(module_id) => self.module_for_id(module, module_id)?,
+            None => module,
+        };
+        let Some(Item::Function(function)) = function_module.find_item(function_name.as_str())
+        else {
+            return Err(RuntimeError::new(RuntimeErrorKind::FunctionNotFound {
+                name: SmolStr::new(function_name.as_str()),
+            }));
+        };
+        let args = args
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .expect("every parameter is supplied once the open arguments are filled");
+        self.eval_function_call(function_module, ctx, function_name.as_str(), function, args)
+    }
+
     fn eval_function_call(
         &self,
         module: &LoweredModule,
@@ -2118,6 +2948,20 @@ impl Interpreter {
         function: &Function,
         arg_values: Vec<Value>,
     ) -> Result<Value, RuntimeError> {
+        self.enter_function_call(module, ctx, func_name, function, arg_values)?;
+        let result = self.eval_body(module, ctx, function.body);
+        self.leave_function_call(module, ctx, function, result)
+    }
+
+    /// Push a call frame and a scope binding the function's coerced parameters
+    fn enter_function_call(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        func_name: &str,
+        function: &Function,
+        arg_values: Vec<Value>,
+    ) -> Result<(), RuntimeError> {
         if function.params.len() != arg_values.len() {
             return Err(RuntimeError::new(
                 RuntimeErrorKind::ParameterCountMismatch {
@@ -2142,21 +2986,30 @@ impl Interpreter {
         for (param, arg) in function.params.iter().zip(coerced_args.iter()) {
             ctx.define_variable(SmolStr::new(param.name.as_str()), arg.clone());
         }
+        Ok(())
+    }
 
-        let result = self
-            .eval_expr(module, ctx, function.body)
-            .and_then(|value| {
-                if let Some(return_type) = function.return_type.as_ref() {
-                    self.coerce_value_to_type(
-                        module,
-                        value,
-                        return_type,
-                        &format!("return value for '{}'", function.name.as_str()),
-                    )
-                } else {
-                    Ok(value)
-                }
-            });
+    /// Coerce the result of a function body to its return type and pop what
+    /// [`enter_function_call`](Self::enter_function_call) pushed
+    fn leave_function_call(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        function: &Function,
+        result: Result<Value, RuntimeError>,
+    ) -> Result<Value, RuntimeError> {
+        let result = result.and_then(|value| {
+            if let Some(return_type) = function.return_type.as_ref() {
+                self.coerce_value_to_type(
+                    module,
+                    value,
+                    return_type,
+                    &format!("return value for '{}'", function.name.as_str()),
+                )
+            } else {
+                Ok(value)
+            }
+        });
 
         ctx.pop_scope();
         ctx.pop_call_frame();
@@ -2200,25 +3053,7 @@ impl Interpreter {
     ) -> Result<Value, RuntimeError> {
         let element = module.element(element_id);
         let tag_name = element.tag.as_str();
-
-        let mut active_properties = Vec::new();
-        self.eval_property_entries(
-            module,
-            ctx,
-            element.property_entries(),
-            &mut active_properties,
-        )?;
-
-        let mut fields = FxHashMap::default();
-        for (key, value) in active_properties {
-            if fields.insert(SmolStr::new(key.as_str()), value).is_some() {
-                return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
-                    expected: "unique active property names".to_string(),
-                    actual: format!("duplicate property '{}'", key.as_str()),
-                    operation: "element property evaluation".to_string(),
-                }));
-            }
-        }
+        let mut fields = self.eval_element_fields(module, ctx, element)?;
 
         let content_values = self.eval_content_expressions(module, ctx, &element.content)?;
         let normalized_content = self.normalize_content_values(content_values);
@@ -2247,7 +3082,7 @@ impl Interpreter {
                 "element function call",
             )?;
 
-            if fields.len() != function.params.len() {
+            if fields.len() > function.params.len() {
                 return Err(RuntimeError::new(
                     RuntimeErrorKind::ParameterCountMismatch {
                         expected: function.params.len(),
@@ -2259,23 +3094,28 @@ impl Interpreter {
 
             let mut arg_values = Vec::with_capacity(function.params.len());
             for param in &function.params {
-                match fields.remove(param.name.as_str()) {
-                    Some(value) => {
-                        arg_values.push(self.coerce_value_to_type(
-                            target_module,
-                            value,
-                            &param.ty,
-                            &format!("parameter '{}'", param.name.as_str()),
-                        )?);
+                // Parameters with a default or a nullable type are optional when a function is
+                // invoked as an element.
+                let value = match (fields.remove(param.name.as_str()), param.default) {
+                    (Some(value), _) => value,
+                    (None, Some(default_expr)) => {
+                        self.eval_param_default(target_module, ctx, default_expr)?
                     }
-                    None => {
+                    (None, None) if matches!(param.ty, ast::TypeRef::Nullable(_)) => Value::Null,
+                    (None, None) => {
                         return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
                             expected: format!("argument '{}'", param.name.as_str()),
                             actual: "missing".to_string(),
                             operation: "element function call".to_string(),
                         }))
                     }
-                }
+                };
+                arg_values.push(self.coerce_value_to_type(
+                    target_module,
+                    value,
+                    &param.ty,
+                    &format!("parameter '{}'", param.name.as_str()),
+                )?);
             }
 
             if !fields.is_empty() {
@@ -2358,12 +3198,45 @@ impl Interpreter {
         })
     }
 
+    /// Evaluate an element's properties and spreads into its fields, in source order
+    fn eval_element_fields(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        element: &Element,
+    ) -> Result<FxHashMap<SmolStr, Value>, RuntimeError> {
+        let mut active_properties = Vec::new();
+        let mut spread_properties = Vec::new();
+        self.eval_property_entries(
+            module,
+            ctx,
+            element.property_entries(),
+            &mut active_properties,
+            &mut spread_properties,
+        )?;
+
+        let mut fields = FxHashMap::default();
+        for (key, value) in active_properties {
+            if fields.insert(SmolStr::new(key.as_str()), value).is_some() {
+                return Err(duplicate_property(element.tag.as_str(), key.as_str()));
+            }
+        }
+        // Directly supplied properties win over spread fields; later spreads win over earlier ones.
+        let mut spread_fields = FxHashMap::default();
+        spread_fields.extend(spread_properties);
+        for (key, value) in spread_fields {
+            fields.entry(key).or_insert(value);
+        }
+        Ok(fields)
+    }
+
     fn eval_property_entries(
         &self,
         module: &LoweredModule,
         ctx: &mut ExecutionContext,
         entries: &[PropertyEntry],
         output: &mut Vec<(Name, Value)>,
+        spread_output: &mut Vec<(SmolStr, Value)>,
     ) -> Result<(), RuntimeError> {
         for entry in entries {
             match entry {
@@ -2371,6 +3244,18 @@ impl Interpreter {
                     let value = self.eval_expr(module, ctx, property.value)?;
                     output.push((property.key.clone(), value));
                 }
+                PropertyEntry::Spread { value, .. } => {
+                    match self.eval_expr(module, ctx, *value)? {
+                        Value::Record { fields, .. } => spread_output.extend(fields),
+                        other => {
+                            return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+                                expected: "record".to_string(),
+                                actual: other.type_name().to_string(),
+                                operation: "property spread".to_string(),
+                            }))
+                        }
+                    }
+                }
                 PropertyEntry::If {
                     condition,
                     then_entries,
@@ -2380,10 +3265,22 @@ impl Interpreter {
                     let condition_value = self.eval_expr(module, ctx, *condition)?;
                     match condition_value {
                         Value::Boolean(true) => {
-                            self.eval_property_entries(module, ctx, then_entries, output)?;
+                            self.eval_property_entries(
+                                module,
+                                ctx,
+                                then_entries,
+                                output,
+                                spread_output,
+                            )?;
                         }
                         Value::Boolean(false) => {
-                            self.eval_property_entries(module, ctx, else_entries, output)?;
+                            self.eval_property_entries(
+                                module,
+                                ctx,
+                                else_entries,
+                                output,
+                                spread_output,
+                            )?;
                         }
                         other => {
                             return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
@@ -2402,7 +3299,13 @@ impl Interpreter {
                         let condition_value = self.eval_expr(module, ctx, arm.condition)?;
                         match condition_value {
                             Value::Boolean(true) => {
-                                self.eval_property_entries(module, ctx, &arm.entries, output)?;
+                                self.eval_property_entries(
+                                    module,
+                                    ctx,
+                                    &arm.entries,
+                                    output,
+                                    spread_output,
+                                )?;
                                 matched = true;
                                 break;
                             }
@@ -2418,7 +3321,13 @@ impl Interpreter {
                     }
 
                     if !matched {
-                        self.eval_property_entries(module, ctx, else_entries, output)?;
+                        self.eval_property_entries(
+                            module,
+                            ctx,
+                            else_entries,
+                            output,
+                            spread_output,
+                        )?;
                     }
                 }
                 PropertyEntry::Match {
@@ -2433,7 +3342,13 @@ impl Interpreter {
                         for pattern in &arm.patterns {
                             let pattern_value = self.eval_match_pattern(module, ctx, *pattern)?;
                             if self.values_match(&scrutinee_value, &pattern_value)? {
-                                self.eval_property_entries(module, ctx, &arm.entries, output)?;
+                                self.eval_property_entries(
+                                    module,
+                                    ctx,
+                                    &arm.entries,
+                                    output,
+                                    spread_output,
+                                )?;
                                 matched = true;
                                 break;
                             }
@@ -2445,7 +3360,13 @@ impl Interpreter {
                     }
 
                     if !matched {
-                        self.eval_property_entries(module, ctx, else_entries, output)?;
+                        self.eval_property_entries(
+                            module,
+                            ctx,
+                            else_entries,
+                            output,
+                            spread_output,
+                        )?;
                     }
                 }
             }
@@ -2675,6 +3596,7 @@ impl Interpreter {
             Value::Float(_) => Type::float(),
             Value::String(_) => Type::string(),
             Value::Boolean(_) => Type::bool(),
+            Value::Money(_) => Type::money(),
             Value::Null => Type::nullable(Type::named("object")),
             Value::Array(values) => {
                 if values.is_empty() {
@@ -2692,6 +3614,9 @@ impl Interpreter {
             Value::Record { type_name, .. } => Type::named(type_name.clone()),
             // Handlers are opaque runtime callback objects rather than first-class typed functions.
             Value::ActionHandler { .. } => Type::named("action_handler"),
+            Value::PartialApplication { .. } => Type::named("function"),
+            // Host values are opaque to NX code, which can only pass them along as `object`.
+            Value::Native(_) => Type::named("object"),
         }
     }
 
@@ -2854,7 +3779,7 @@ impl Interpreter {
         module: &LoweredModule,
         record_name: &str,
     ) -> Result<Value, RuntimeError> {
-        let mut ctx = ExecutionContext::new();
+        let mut ctx = self.new_context(ResourceLimits::default());
         self.build_record_value(module, &mut ctx, record_name, FxHashMap::default())
     }
 
@@ -2868,35 +3793,41 @@ impl Interpreter {
         iterable_expr: ExprId,
         body_expr: ExprId,
     ) -> Result<Value, RuntimeError> {
-        // Evaluate the iterable expression
-        let iterable_value = self.eval_expr(module, ctx, iterable_expr)?;
-
-        // Extract array elements
-        let elements = match iterable_value {
-            Value::Array(ref arr) => arr.clone(),
-            _ => {
+        // Arrays yield each element with its int index; records yield each field value with its
+        // name, in a deterministic order.
+        let entries: Vec<(Value, Value)> = match self.eval_expr(module, ctx, iterable_expr)? {
+            Value::Array(elements) => elements
+                .into_iter()
+                .enumerate()
+                .map(|(idx, element)| (Value::Int(idx as i64), element))
+                .collect(),
+            Value::Record { type_name, fields } => self
+                .ordered_record_fields(module, &type_name, fields)
+                .into_iter()
+                .map(|(name, value)| (Value::String(name), value))
+                .collect(),
+            other => {
                 return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
-                    expected: "array".to_string(),
-                    actual: iterable_value.type_name().to_string(),
+                    expected: "array or record".to_string(),
+                    actual: other.type_name().to_string(),
                     operation: "for loop iteration".to_string(),
                 }))
             }
         };
 
         // Collect results from each iteration
-        let mut results = Vec::with_capacity(elements.len());
+        let mut results = Vec::with_capacity(entries.len());
 
-        // Iterate over elements
-        for (idx, element) in elements.iter().enumerate() {
+        for (key, element) in entries {
             // Create new scope for loop variables
             ctx.push_scope();
 
             // Bind item variable
-            ctx.define_variable(SmolStr::new(item.as_str()), element.clone());
+            ctx.define_variable(SmolStr::new(item.as_str()), element);
 
-            // Bind index variable if present
+            // Bind index or field name variable if present
             if let Some(index_name) = index {
-                ctx.define_variable(SmolStr::new(index_name.as_str()), Value::Int(idx as i64));
+                ctx.define_variable(SmolStr::new(index_name.as_str()), key);
             }
 
             // Evaluate body
@@ -2911,6 +3842,29 @@ impl Interpreter {
         Ok(Value::Array(results))
     }
 
+    /// Order a record's fields for iteration: the declaration order of its record type (base
+    /// fields first), then any fields the type does not declare, sorted by name.
+    fn ordered_record_fields(
+        &self,
+        module: &LoweredModule,
+        type_name: &Name,
+        mut fields: FxHashMap<SmolStr, Value>,
+    ) -> Vec<(SmolStr, Value)> {
+        let mut ordered = Vec::with_capacity(fields.len());
+        if let Ok(shape) = self.effective_record_shape(module, type_name) {
+            for field in &shape.fields {
+                if let Some(entry) = fields.remove_entry(field.name.as_str()) {
+                    ordered.push(entry);
+                }
+            }
+        }
+
+        let mut undeclared = fields.into_iter().collect::<Vec<_>>();
+        undeclared.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        ordered.extend(undeclared);
+        ordered
+    }
+
     fn resolve_enum_definition<'a>(
         &'a self,
         module: &'a LoweredModule,
@@ -3106,6 +4060,11 @@ impl Interpreter {
                     actual_type_name,
                     expected_inner,
                 ),
+                // Elements and records share a runtime representation; only records have a
+                // declaration.
+                Type::Element => self
+                    .resolve_record_definition(module, actual_type_name.as_str())
+                    .is_none(),
                 _ => false,
             }
     }
@@ -3190,7 +4149,7 @@ impl Interpreter {
             }));
         }
 
-        let mut ctx = ExecutionContext::new();
+        let mut ctx = self.new_context(ResourceLimits::default());
         let actual_shape = self.effective_record_shape(module, &actual_def.name)?;
         self.build_record_value_from_shape(module, &mut ctx, actual_shape, fields, Some(operation))
     }
@@ -3353,6 +4312,13 @@ fn runtime_prepared_item_kind(kind: ResolvedItemKind) -> Option<PreparedItemKind
     }
 }
 
+fn duplicate_property(target: &str, property: &str) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::DuplicateProperty {
+        target: SmolStr::new(target),
+        property: SmolStr::new(property),
+    })
+}
+
 impl Interpreter {
     fn eval_record_literal(
         &self,
@@ -3364,7 +4330,12 @@ impl Interpreter {
         let mut overrides = FxHashMap::default();
         for property in properties {
             let value = self.eval_expr(module, ctx, property.value)?;
-            overrides.insert(SmolStr::new(property.name.as_str()), value);
+            if overrides
+                .insert(SmolStr::new(property.name.as_str()), value)
+                .is_some()
+            {
+                return Err(duplicate_property(record.as_str(), property.name.as_str()));
+            }
         }
 
         self.build_record_value(module, ctx, record.as_str(), overrides)
@@ -3557,8 +4528,10 @@ impl Interpreter {
             }
         }
 
+        // Defaults may read other fields, so the fields they read are materialized first.
+        let order = self.record_default_order(module, &record_shape)?;
         let mut materialized = FxHashMap::default();
-        for prop in &record_shape.fields {
+        for prop in order.into_iter().map(|index| &record_shape.fields[index]) {
             let value = if let Some(value) = overrides.remove(prop.name.as_str()) {
                 value
             } else if prop.default.is_some() {
@@ -3579,14 +4552,12 @@ impl Interpreter {
                     &prop.name,
                     &format!("record field '{}.{}'", record_def.name, prop.name),
                 ));
-            } else if let Some(operation) = missing_operation {
+            } else {
                 return Err(self.missing_required_record_field_error(
                     &record_def.name,
                     &prop.name,
-                    operation,
+                    missing_operation.unwrap_or("record construction"),
                 ));
-            } else {
-                Value::Null
             };
 
             let owner_module = self.owner_module_for_effective_field(
@@ -3606,6 +4577,13 @@ impl Interpreter {
             materialized.insert(SmolStr::new(prop.name.as_str()), value);
         }
 
+        self.check_record_constraints(
+            &record_def.name,
+            &record_shape.fields,
+            &materialized,
+            missing_operation.unwrap_or("record construction"),
+        )?;
+
         Ok(Value::Record {
             type_name: record_shape.record.name,
             fields: materialized,
@@ -3751,7 +4729,7 @@ mod tests {
         let mut ctx = ExecutionContext::new();
         let mut output = Vec::new();
         interpreter
-            .eval_property_entries(&module, &mut ctx, &entries, &mut output)
+            .eval_property_entries(&module, &mut ctx, &entries, &mut output, &mut Vec::new())
             .expect("Expected property entries to evaluate");
 
         assert_eq!(
@@ -4053,6 +5031,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_action_handler_captures_are_counted_and_limited() {
+        let source = r#"
+            action SearchSubmitted = { searchString:string }
+            action DoSearch = { userId:string search:string }
+
+            component <SearchBox emits { SearchSubmitted } /> = {
+              <TextInput />
+            }
+
+            let render(userId:string, rows:int[]) = <SearchBox onSearchSubmitted=<DoSearch userId={userId} search={action.searchString} /> />
+        "#;
+        let args = || {
+            vec![
+                Value::String(SmolStr::new("u1")),
+                Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]),
+            ]
+        };
+
+        let (module, interpreter) = lower_module_runtime(source);
+        let mut ctx = interpreter.new_context(ResourceLimits::default());
+        interpreter
+            .execute_function_in_context(module.as_ref(), "render", args(), &mut ctx)
+            .expect("Expected render to succeed");
+        let stats = ctx.stats();
+        assert_eq!(stats.action_handlers, 1);
+        assert_eq!(stats.captured_values, 5, "userId, plus rows and its items");
+        assert_eq!(stats.largest_capture, 5);
+
+        let limits = ResourceLimits {
+            max_captured_values: 4,
+            ..ResourceLimits::default()
+        };
+        let error = interpreter
+            .execute_function_with_limits(module.as_ref(), "render", args(), limits)
+            .expect_err("Expected the capture limit to stop render");
+        assert_eq!(
+            error.kind(),
+            &RuntimeErrorKind::CaptureLimitExceeded { limit: 4, size: 5 }
+        );
+    }
+
     #[test]
     fn test_record_literal_reports_missing_record_type() {
         let mut module = LoweredModule::new(SourceId::new(0));
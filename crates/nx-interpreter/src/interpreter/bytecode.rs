@@ -0,0 +1,231 @@
+//! Compilation of function bodies to bytecode for [`Backend::Bytecode`](super::Backend).
+//!
+//! A body compiles to a flat [`Chunk`] of stack-machine operations. Literals, identifiers,
+//! blocks, `let`, operators, `if`, arrays and calls of named functions are compiled; every other
+//! expression becomes a single [`Op::Eval`] that hands it to the tree walker, so the backend is a
+//! hybrid of the two. Each compiled expression starts with [`Op::Tick`], so operation counts
+//! match the tree walker's exactly.
+
+use super::Interpreter;
+use crate::error::RuntimeError;
+use crate::value::Value;
+use nx_hir::{ast, ExprId, LoweredModule};
+use smol_str::SmolStr;
+use text_size::TextRange;
+
+/// The compiled form of one function body
+#[derive(Debug)]
+pub(crate) struct Chunk {
+    pub(super) ops: Vec<Op>,
+}
+
+/// What opened a scope, which decides whether it is popped when an error unwinds through it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ScopeKind {
+    /// A block, whose scope the tree walker leaves in place on error.
+    Block,
+    /// A `let` expression, whose scope is popped on error as well.
+    Let,
+}
+
+/// A single VM instruction
+#[derive(Debug)]
+pub(super) enum Op {
+    /// Count one evaluated expression against the operation limit.
+    Tick,
+    /// Push a constant.
+    Const(Value),
+    /// Push the value of a variable.
+    Load(SmolStr),
+    /// Pop a value and define it in the innermost scope.
+    Define(SmolStr),
+    /// Discard the top of the stack.
+    Pop,
+    PushScope(ScopeKind),
+    PopScope,
+    /// Pop two operands and push the result of an eagerly evaluated operator.
+    Binary(ast::BinOp),
+    /// Pop an operand and push the result of a unary operator.
+    Unary(ast::UnOp),
+    /// Pop the left operand of `&&` or `||`. When it decides the result, push it and jump to
+    /// `target`; otherwise fall through to the right operand.
+    ShortCircuit {
+        op: ast::BinOp,
+        target: usize,
+    },
+    /// Check that the right operand of `&&` or `||`, on top of the stack, is a bool.
+    ExpectBool {
+        op: ast::BinOp,
+    },
+    /// Pop an `if` condition and jump to `target` when it is false.
+    JumpIfFalse {
+        target: usize,
+    },
+    Jump {
+        target: usize,
+    },
+    /// Pop `len` values and push them as an array.
+    Array {
+        len: usize,
+    },
+    /// Pop `argc` arguments and call the function named `name`.
+    Call {
+        name: SmolStr,
+        argc: usize,
+        span: TextRange,
+    },
+    /// Evaluate an expression with the tree walker and push its value.
+    Eval(ExprId),
+    /// Fail with an error found while compiling, such as a literal that cannot be evaluated.
+    ///
+    /// The error is raised when execution reaches it, as the tree walker would raise it.
+    Fail(RuntimeError),
+}
+
+impl Interpreter {
+    /// Compile the expression `body` of `module` to a chunk
+    pub(super) fn compile_chunk(&self, module: &LoweredModule, body: ExprId) -> Chunk {
+        let mut compiler = Compiler {
+            interpreter: self,
+            module,
+            ops: Vec::new(),
+        };
+        compiler.expr(body);
+        Chunk { ops: compiler.ops }
+    }
+}
+
+struct Compiler<'a> {
+    interpreter: &'a Interpreter,
+    module: &'a LoweredModule,
+    ops: Vec<Op>,
+}
+
+impl Compiler<'_> {
+    fn expr(&mut self, expr_id: ExprId) {
+        let module = self.module;
+        match module.expr(expr_id) {
+            ast::Expr::Literal(lit) => {
+                self.ops.push(Op::Tick);
+                match self.interpreter.eval_literal(lit) {
+                    Ok(value) => self.ops.push(Op::Const(value)),
+                    Err(error) => self.ops.push(Op::Fail(error)),
+                }
+            }
+            ast::Expr::Ident(name) => {
+                self.ops.push(Op::Tick);
+                self.ops.push(Op::Load(SmolStr::new(name.as_str())));
+            }
+            ast::Expr::Block { stmts, expr, .. } => {
+                self.ops.push(Op::Tick);
+                self.ops.push(Op::PushScope(ScopeKind::Block));
+                for stmt in stmts {
+                    match stmt {
+                        ast::Stmt::Let { name, init, .. } => {
+                            self.expr(*init);
+                            self.ops.push(Op::Define(SmolStr::new(name.as_str())));
+                        }
+                        ast::Stmt::Expr(expr_id, _) => {
+                            self.expr(*expr_id);
+                            self.ops.push(Op::Pop);
+                        }
+                    }
+                }
+                match expr {
+                    Some(tail) => self.expr(*tail),
+                    None => self.ops.push(Op::Const(Value::Null)),
+                }
+                self.ops.push(Op::PopScope);
+            }
+            ast::Expr::Let {
+                name, value, body, ..
+            } => {
+                self.ops.push(Op::Tick);
+                self.expr(*value);
+                self.ops.push(Op::PushScope(ScopeKind::Let));
+                self.ops.push(Op::Define(SmolStr::new(name.as_str())));
+                self.expr(*body);
+                self.ops.push(Op::PopScope);
+            }
+            ast::Expr::BinaryOp { lhs, op, rhs, .. } => {
+                self.ops.push(Op::Tick);
+                self.expr(*lhs);
+                match op {
+                    ast::BinOp::And | ast::BinOp::Or => {
+                        let short_circuit = self.placeholder();
+                        self.expr(*rhs);
+                        self.ops.push(Op::ExpectBool { op: *op });
+                        self.ops[short_circuit] = Op::ShortCircuit {
+                            op: *op,
+                            target: self.ops.len(),
+                        };
+                    }
+                    _ => {
+                        self.expr(*rhs);
+                        self.ops.push(Op::Binary(*op));
+                    }
+                }
+            }
+            ast::Expr::UnaryOp { op, expr, .. } => {
+                self.ops.push(Op::Tick);
+                self.expr(*expr);
+                self.ops.push(Op::Unary(*op));
+            }
+            ast::Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.ops.push(Op::Tick);
+                self.expr(*condition);
+                let jump_to_else = self.placeholder();
+                self.expr(*then_branch);
+                let jump_to_end = self.placeholder();
+                self.ops[jump_to_else] = Op::JumpIfFalse {
+                    target: self.ops.len(),
+                };
+                match else_branch {
+                    Some(else_branch) => self.expr(*else_branch),
+                    None => self.ops.push(Op::Const(Value::Null)),
+                }
+                self.ops[jump_to_end] = Op::Jump {
+                    target: self.ops.len(),
+                };
+            }
+            ast::Expr::Array { elements, .. } => {
+                self.ops.push(Op::Tick);
+                for element in elements {
+                    self.expr(*element);
+                }
+                self.ops.push(Op::Array {
+                    len: elements.len(),
+                });
+            }
+            ast::Expr::Call { func, args, span } => {
+                // Calls of anything but a name fail before their arguments are evaluated, which
+                // the tree walker reports.
+                let Some(name) = self.interpreter.flattened_expr_name(module, *func) else {
+                    self.ops.push(Op::Eval(expr_id));
+                    return;
+                };
+                self.ops.push(Op::Tick);
+                for arg in args {
+                    self.expr(*arg);
+                }
+                self.ops.push(Op::Call {
+                    name: SmolStr::new(name),
+                    argc: args.len(),
+                    span: *span,
+                });
+            }
+            _ => self.ops.push(Op::Eval(expr_id)),
+        }
+    }
+
+    /// Reserve an instruction to be patched once its jump target is known.
+    fn placeholder(&mut self) -> usize {
+        self.ops.push(Op::Pop);
+        self.ops.len() - 1
+    }
+}
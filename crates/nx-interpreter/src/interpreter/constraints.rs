@@ -0,0 +1,174 @@
+//! Checking of field constraints such as `@min(0)` against record values.
+//!
+//! Records built by the interpreter check their own fields as they are constructed. Records that
+//! a host passes in as arguments were never constructed here, so they are checked as a whole,
+//! nested records and arrays included, before the function runs.
+
+use super::Interpreter;
+use crate::error::{ConstraintViolation, RuntimeError, RuntimeErrorKind};
+use crate::value::Value;
+use nx_hir::{ConstraintNumber, EffectiveField, FieldConstraintKind, LoweredModule, Name};
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+use std::cmp::Ordering;
+
+impl Interpreter {
+    /// Fails with every constraint that the materialized fields of `record` break.
+    pub(super) fn check_record_constraints(
+        &self,
+        record: &Name,
+        fields: &[EffectiveField],
+        values: &FxHashMap<SmolStr, Value>,
+        operation: &str,
+    ) -> Result<(), RuntimeError> {
+        let mut violations = Vec::new();
+        collect_field_violations(fields, values, "", &mut violations);
+        validation_result(record, operation, violations)
+    }
+
+    /// Fails with every constraint broken anywhere inside a host-supplied record value.
+    pub(super) fn check_host_value_constraints(
+        &self,
+        module: &LoweredModule,
+        value: &Value,
+        operation: &str,
+    ) -> Result<(), RuntimeError> {
+        let Value::Record { type_name, .. } = value else {
+            return Ok(());
+        };
+        let mut violations = Vec::new();
+        self.collect_value_violations(module, value, "", &mut violations);
+        validation_result(type_name, operation, violations)
+    }
+
+    fn collect_value_violations(
+        &self,
+        module: &LoweredModule,
+        value: &Value,
+        path: &str,
+        violations: &mut Vec<ConstraintViolation>,
+    ) {
+        match value {
+            Value::Record { type_name, fields } => {
+                if self
+                    .resolve_record_definition(module, type_name.as_str())
+                    .is_none()
+                {
+                    return;
+                }
+                let Ok(shape) = self.effective_record_shape(module, type_name) else {
+                    return;
+                };
+                let prefix = if path.is_empty() {
+                    String::new()
+                } else {
+                    format!("{path}.")
+                };
+                collect_field_violations(&shape.fields, fields, &prefix, violations);
+                for field in &shape.fields {
+                    if let Some(field_value) = fields.get(field.name.as_str()) {
+                        self.collect_value_violations(
+                            module,
+                            field_value,
+                            &format!("{prefix}{}", field.name),
+                            violations,
+                        );
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.collect_value_violations(
+                        module,
+                        item,
+                        &format!("{path}[{index}]"),
+                        violations,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn validation_result(
+    record: &Name,
+    operation: &str,
+    violations: Vec<ConstraintViolation>,
+) -> Result<(), RuntimeError> {
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(RuntimeError::new(RuntimeErrorKind::RecordValidation {
+        record: SmolStr::new(record.as_str()),
+        operation: operation.to_string(),
+        violations,
+    }))
+}
+
+fn collect_field_violations(
+    fields: &[EffectiveField],
+    values: &FxHashMap<SmolStr, Value>,
+    prefix: &str,
+    violations: &mut Vec<ConstraintViolation>,
+) {
+    for field in fields {
+        let Some(value) = values.get(field.name.as_str()) else {
+            continue;
+        };
+        for constraint in &field.constraints {
+            if let Some(message) = violation_message(constraint.kind, value) {
+                violations.push(ConstraintViolation {
+                    field: SmolStr::new(format!("{prefix}{}", field.name)),
+                    constraint: constraint.kind.to_string(),
+                    message,
+                });
+            }
+        }
+    }
+}
+
+/// Describes how `value` breaks `constraint`, or returns `None` if it satisfies it.
+///
+/// Null satisfies every constraint, so optional fields are only checked when present. Values of
+/// a kind the constraint does not apply to are left to type checking.
+fn violation_message(constraint: FieldConstraintKind, value: &Value) -> Option<String> {
+    match constraint {
+        FieldConstraintKind::Min(bound) => (compare_number(value, bound)? == Ordering::Less)
+            .then(|| format!("must be at least {bound}, got {value}")),
+        FieldConstraintKind::Max(bound) => (compare_number(value, bound)? == Ordering::Greater)
+            .then(|| format!("must be at most {bound}, got {value}")),
+        FieldConstraintKind::MinLength(bound) => {
+            let (length, unit) = length_of(value, bound)?;
+            (length < bound).then(|| format!("must have at least {bound} {unit}, got {length}"))
+        }
+        FieldConstraintKind::MaxLength(bound) => {
+            let (length, unit) = length_of(value, bound)?;
+            (length > bound).then(|| format!("must have at most {bound} {unit}, got {length}"))
+        }
+    }
+}
+
+fn compare_number(value: &Value, bound: ConstraintNumber) -> Option<Ordering> {
+    let value = match value {
+        Value::Int(value) => *value,
+        Value::Int32(value) => i64::from(*value),
+        Value::Float(value) => return value.partial_cmp(&bound.as_f64()),
+        Value::Float32(value) => return f64::from(*value).partial_cmp(&bound.as_f64()),
+        _ => return None,
+    };
+    match bound {
+        ConstraintNumber::Int(bound) => Some(value.cmp(&bound)),
+        ConstraintNumber::Float(_) => (value as f64).partial_cmp(&bound.as_f64()),
+    }
+}
+
+/// Returns the length of a string or array, with the unit to describe `bound` of them in.
+fn length_of(value: &Value, bound: u64) -> Option<(u64, &'static str)> {
+    let (length, one, many) = match value {
+        Value::String(text) => (text.chars().count() as u64, "character", "characters"),
+        Value::Array(items) => (items.len() as u64, "item", "items"),
+        _ => return None,
+    };
+    Some((length, if bound == 1 { one } else { many }))
+}
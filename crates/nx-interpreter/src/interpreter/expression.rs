@@ -0,0 +1,171 @@
+//! Evaluation of expression text against a module, for debugger watch windows and REPLs.
+
+use super::Interpreter;
+use crate::context::ResourceLimits;
+use crate::error::RuntimeError;
+use crate::value::Value;
+use nx_diagnostics::{Diagnostic, Label, Severity, TextSize};
+use nx_hir::lower::LoweringContext;
+use nx_hir::{ast, Item, LoweredModule, Name, Param};
+use nx_syntax::SyntaxKind;
+use nx_types::Type;
+use std::fmt;
+use text_size::TextRange;
+
+/// Name of the function an expression is lowered into.
+const EXPR_FUNCTION: &str = "__nx_eval_expr";
+
+/// File name that diagnostics about the expression text refer to.
+const EXPR_FILE_NAME: &str = "<expr>";
+
+/// Why [`Interpreter::eval_expr_in`] produced no value
+#[derive(Debug, Clone)]
+pub enum ExprEvalError {
+    /// The expression failed to parse, named something that does not exist, or did not type
+    /// check. Label ranges are byte offsets into the expression text.
+    Diagnostics(Vec<Diagnostic>),
+    /// Evaluation failed. The error's location is a byte range of the expression text, if it
+    /// failed in the expression itself rather than in a function it called.
+    Runtime(RuntimeError),
+}
+
+impl fmt::Display for ExprEvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprEvalError::Diagnostics(diagnostics) => {
+                let messages = diagnostics
+                    .iter()
+                    .map(Diagnostic::message)
+                    .collect::<Vec<_>>();
+                write!(f, "{}", messages.join("; "))
+            }
+            ExprEvalError::Runtime(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ExprEvalError {}
+
+impl Interpreter {
+    /// Evaluate `expr`, the text of a single expression, as if it appeared in `module`
+    ///
+    /// The expression can call the module's functions and use its values and types, and
+    /// `bindings` adds variables on top, such as the locals of a paused stack frame. Each
+    /// binding's type is taken from its value. The expression is parsed, lowered against the
+    /// module, and type checked before it runs, so a typo or a type error is reported as
+    /// diagnostics instead of evaluating.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let label = interpreter.eval_expr_in(
+    ///     &module,
+    ///     "user.age > 18 ? \"adult\" : \"minor\"",
+    ///     &[("user", user)],
+    /// )?;
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`ExprEvalError::Diagnostics`] when the expression has errors, and
+    /// [`ExprEvalError::Runtime`] when evaluating it fails. Warnings do not prevent evaluation.
+    pub fn eval_expr_in(
+        &self,
+        module: &LoweredModule,
+        expr: &str,
+        bindings: &[(&str, Value)],
+    ) -> Result<Value, ExprEvalError> {
+        let prefix = format!("let {}() = {{", EXPR_FUNCTION);
+        let source = format!("{}{}\n}}", prefix, expr);
+        let offset = TextSize::of(prefix.as_str());
+        let expr_end = TextSize::of(expr);
+        let to_expr_range = |range: TextRange| {
+            let clamp = |position: TextSize| position.checked_sub(offset).unwrap_or_default();
+            TextRange::new(
+                clamp(range.start()).min(expr_end),
+                clamp(range.end()).min(expr_end),
+            )
+        };
+        let errors = |diagnostics: Vec<Diagnostic>| {
+            let diagnostics = diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.map_ranges(to_expr_range))
+                .filter(|diagnostic| diagnostic.severity() == Severity::Error)
+                .collect::<Vec<_>>();
+            (!diagnostics.is_empty()).then_some(ExprEvalError::Diagnostics(diagnostics))
+        };
+
+        let parse_result = nx_syntax::parse_str(&source, EXPR_FILE_NAME);
+        if let Some(error) = errors(parse_result.errors.clone()) {
+            return Err(error);
+        }
+        let Some(function_node) = parse_result.root().and_then(|root| {
+            root.children()
+                .find(|child| child.kind() == SyntaxKind::FUNCTION_DEFINITION)
+        }) else {
+            return Err(ExprEvalError::Diagnostics(vec![Diagnostic::error(
+                "invalid-expression",
+            )
+            .with_message("Expected a single expression")
+            .with_label(Label::primary(EXPR_FILE_NAME, TextRange::up_to(expr_end)))
+            .build()]));
+        };
+
+        let lowering_diagnostics = module.diagnostics().len();
+        let mut ctx = LoweringContext::extending(module.clone());
+        let mut function = ctx.lower_function(function_node);
+        let mut extended = ctx.finish();
+        function.params = bindings
+            .iter()
+            .map(|(name, value)| {
+                Param::new(
+                    Name::new(name),
+                    type_ref_of(&self.runtime_type_of_value(value)),
+                    function.span,
+                )
+            })
+            .collect();
+        extended.add_item(Item::Function(function));
+
+        let mut diagnostics = extended.diagnostics()[lowering_diagnostics..]
+            .iter()
+            .map(|diagnostic| {
+                Diagnostic::error(diagnostic.code.unwrap_or("lowering-error"))
+                    .with_message(diagnostic.message.clone())
+                    .with_label(Label::primary(EXPR_FILE_NAME, diagnostic.span))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        diagnostics.extend(nx_types::check_added_function(
+            &extended,
+            EXPR_FUNCTION,
+            EXPR_FILE_NAME,
+        ));
+        if let Some(error) = errors(diagnostics) {
+            return Err(error);
+        }
+
+        let args = bindings.iter().map(|(_, value)| value.clone()).collect();
+        self.execute_function_with_limits(&extended, EXPR_FUNCTION, args, ResourceLimits::default())
+            .map_err(|error| {
+                let error = match error.location() {
+                    Some(location) if location.start() >= offset => {
+                        let location = to_expr_range(location);
+                        error.with_location(location)
+                    }
+                    _ => error,
+                };
+                ExprEvalError::Runtime(error)
+            })
+    }
+}
+
+/// Spells `ty` as a type annotation, falling back to `object` for types without one.
+fn type_ref_of(ty: &Type) -> ast::TypeRef {
+    match ty {
+        Type::Array(element) => ast::TypeRef::Array(Box::new(type_ref_of(element))),
+        Type::Nullable(inner) => ast::TypeRef::Nullable(Box::new(type_ref_of(inner))),
+        Type::Primitive(_) | Type::Named(_) | Type::Enum(_) | Type::Union(_) | Type::Element => {
+            ast::TypeRef::Name(Name::new(&ty.to_string()))
+        }
+        _ => ast::TypeRef::Name(Name::new("object")),
+    }
+}
@@ -0,0 +1,145 @@
+//! Functions resolved once for repeated calls from a host.
+
+use super::Interpreter;
+use crate::context::ResourceLimits;
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::value::Value;
+use nx_hir::{ast, Function, LoweredModule, Param};
+use nx_types::Type;
+use smol_str::SmolStr;
+
+/// A function looked up once by [`Interpreter::prepare`] and called many times
+///
+/// Preparing resolves the function by name and resolves each parameter's declared type up front,
+/// so [`call`](Self::call) only validates and binds the arguments before running the body. Hosts
+/// that call the same entrypoint on every request avoid the item lookup and type resolution
+/// that [`Interpreter::execute_function`] repeats on each call.
+///
+/// Trailing parameters that have a default, or a nullable type, may be left out of the
+/// arguments. Omitted parameters take their default, or `null`, as they do when the function
+/// is invoked as an element.
+#[derive(Debug)]
+pub struct PreparedFunction<'a> {
+    interpreter: &'a Interpreter,
+    module: &'a LoweredModule,
+    function: &'a Function,
+    param_types: Vec<Type>,
+    required_params: usize,
+}
+
+impl Interpreter {
+    /// Resolve a function in `module` for repeated calls
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RuntimeErrorKind::FunctionNotFound`] when `module` has no function with that
+    /// name.
+    pub fn prepare<'a>(
+        &'a self,
+        module: &'a LoweredModule,
+        function_name: &str,
+    ) -> Result<PreparedFunction<'a>, RuntimeError> {
+        let (owner_module, function) = match self.resolve_item(module, function_name) {
+            Some((owner_module, nx_hir::Item::Function(function))) => (owner_module, function),
+            _ => {
+                return Err(RuntimeError::new(RuntimeErrorKind::FunctionNotFound {
+                    name: SmolStr::new(function_name),
+                }))
+            }
+        };
+
+        let param_types = function
+            .params
+            .iter()
+            .map(|param| self.runtime_type_from_type_ref(owner_module, &param.ty))
+            .collect();
+        let required_params = function
+            .params
+            .iter()
+            .rposition(|param| !is_optional(param))
+            .map_or(0, |index| index + 1);
+
+        Ok(PreparedFunction {
+            interpreter: self,
+            module: owner_module,
+            function,
+            param_types,
+            required_params,
+        })
+    }
+}
+
+impl<'a> PreparedFunction<'a> {
+    /// Returns the function's declared name.
+    pub fn name(&self) -> &'a str {
+        self.function.name.as_str()
+    }
+
+    /// Returns the declared parameters, with their names, types and defaults.
+    pub fn params(&self) -> &'a [Param] {
+        &self.function.params
+    }
+
+    /// Returns how many leading arguments every call must supply.
+    pub fn required_params(&self) -> usize {
+        self.required_params
+    }
+
+    /// Call the function with default resource limits
+    pub fn call(&self, args: Vec<Value>) -> Result<Value, RuntimeError> {
+        self.call_with_limits(args, ResourceLimits::default())
+    }
+
+    /// Call the function with custom resource limits
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RuntimeErrorKind::ParameterCountMismatch`] when fewer than
+    /// [`required_params`](Self::required_params) or more than all parameters are supplied, and
+    /// [`RuntimeErrorKind::TypeMismatch`] when an argument does not fit its parameter's type.
+    pub fn call_with_limits(
+        &self,
+        args: Vec<Value>,
+        limits: ResourceLimits,
+    ) -> Result<Value, RuntimeError> {
+        let params = &self.function.params;
+        if args.len() < self.required_params || args.len() > params.len() {
+            return Err(RuntimeError::new(
+                RuntimeErrorKind::ParameterCountMismatch {
+                    expected: params.len(),
+                    actual: args.len(),
+                    function: SmolStr::new(self.name()),
+                },
+            ));
+        }
+
+        let interpreter = self.interpreter;
+        let mut ctx = interpreter.new_context(limits);
+        interpreter.bind_top_level_values(self.module, &mut ctx)?;
+
+        let mut args = args.into_iter();
+        for (param, ty) in params.iter().zip(&self.param_types) {
+            let value = match (args.next(), param.default) {
+                (Some(value), _) => interpreter.coerce_value_to_resolved_type(
+                    self.module,
+                    value,
+                    ty,
+                    &format!("function call parameter '{}'", param.name.as_str()),
+                )?,
+                (None, Some(default_expr)) => {
+                    interpreter.eval_param_default(self.module, &mut ctx, default_expr)?
+                }
+                (None, None) => Value::Null,
+            };
+            ctx.define_variable(SmolStr::new(param.name.as_str()), value);
+        }
+
+        let result = interpreter.eval_body(self.module, &mut ctx, self.function.body)?;
+        interpreter.coerce_return_value(self.module, self.function, result)
+    }
+}
+
+/// Returns true if a call may leave the parameter out.
+fn is_optional(param: &Param) -> bool {
+    param.default.is_some() || matches!(param.ty, ast::TypeRef::Nullable(_))
+}
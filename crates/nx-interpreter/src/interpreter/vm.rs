@@ -0,0 +1,213 @@
+//! Stack-based VM that runs the chunks of [`bytecode`](super::bytecode).
+//!
+//! Calls between program functions push a [`Frame`] instead of recursing on the native stack.
+//! Function entry and exit, call resolution and operators are shared with the tree walker, so
+//! both backends agree on values, errors and the state left in the [`ExecutionContext`].
+
+use super::bytecode::{Chunk, Op, ScopeKind};
+use super::{CallOutcome, Interpreter};
+use crate::context::ExecutionContext;
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use crate::value::Value;
+use nx_hir::{ast, ExprId, Function, LoweredModule};
+use std::sync::Arc;
+
+/// A chunk being run, with the function it is the body of
+struct Frame<'a> {
+    module: &'a LoweredModule,
+    /// The called function, or `None` for the body the VM was started on.
+    function: Option<&'a Function>,
+    chunk: Arc<Chunk>,
+    /// Where to resume once a call made by this frame returns.
+    ip: usize,
+    /// Scopes opened by this frame and not yet closed, innermost last.
+    scopes: Vec<ScopeKind>,
+}
+
+impl Interpreter {
+    /// Evaluate `body` by compiling it to bytecode and running it
+    pub(super) fn run_bytecode(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        body: ExprId,
+    ) -> Result<Value, RuntimeError> {
+        let mut frames = vec![Frame {
+            module,
+            function: None,
+            chunk: self.compiled_chunk(module, ctx, body),
+            ip: 0,
+            scopes: Vec::new(),
+        }];
+        let result = self.run_frames(ctx, &mut frames, &mut Vec::new());
+
+        if result.is_err() {
+            // Unwind like the tree walker: `let` scopes and function calls are popped, while
+            // block scopes are left in place.
+            for frame in frames.iter().rev() {
+                for kind in frame.scopes.iter().rev() {
+                    if *kind == ScopeKind::Let {
+                        ctx.pop_scope();
+                    }
+                }
+                if frame.function.is_some() {
+                    ctx.pop_scope();
+                    ctx.pop_call_frame();
+                }
+            }
+        }
+        result
+    }
+
+    /// Return the chunk for `body`, compiling it on first use in this execution
+    fn compiled_chunk(
+        &self,
+        module: &LoweredModule,
+        ctx: &mut ExecutionContext,
+        body: ExprId,
+    ) -> Arc<Chunk> {
+        let key = (module as *const LoweredModule as usize, body);
+        if let Some(chunk) = ctx.compiled_chunk(key) {
+            return chunk;
+        }
+        let chunk = Arc::new(self.compile_chunk(module, body));
+        ctx.cache_compiled_chunk(key, Arc::clone(&chunk));
+        chunk
+    }
+
+    fn run_frames<'a>(
+        &'a self,
+        ctx: &mut ExecutionContext,
+        frames: &mut Vec<Frame<'a>>,
+        stack: &mut Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let mut chunk = Arc::clone(&frames.last().expect("the VM starts with a frame").chunk);
+        let mut ip = 0;
+
+        loop {
+            let Some(op) = chunk.ops.get(ip) else {
+                let frame = frames.pop().expect("a frame is running");
+                let value = stack.pop().expect("a chunk leaves its value on the stack");
+                let Some(function) = frame.function else {
+                    return Ok(value);
+                };
+                stack.push(self.leave_function_call(frame.module, ctx, function, Ok(value))?);
+                let caller = frames.last().expect("a called function has a caller");
+                chunk = Arc::clone(&caller.chunk);
+                ip = caller.ip;
+                continue;
+            };
+            ip += 1;
+
+            match op {
+                Op::Tick => ctx.check_operation_limit()?,
+                Op::Const(value) => stack.push(value.clone()),
+                Op::Load(name) => stack.push(ctx.lookup_variable(name)?),
+                Op::Define(name) => {
+                    let value = pop(stack);
+                    ctx.define_variable(name.clone(), value);
+                }
+                Op::Pop => {
+                    pop(stack);
+                }
+                Op::PushScope(kind) => {
+                    ctx.push_scope();
+                    current(frames).scopes.push(*kind);
+                }
+                Op::PopScope => {
+                    ctx.pop_scope();
+                    current(frames).scopes.pop();
+                }
+                Op::Binary(op) => {
+                    let rhs = pop(stack);
+                    let lhs = pop(stack);
+                    stack.push(self.apply_binary_op(lhs, *op, rhs)?);
+                }
+                Op::Unary(op) => {
+                    let operand = pop(stack);
+                    stack.push(Self::apply_unary_op(*op, operand)?);
+                }
+                Op::ShortCircuit { op, target } => match pop(stack) {
+                    Value::Boolean(lhs) if lhs == (*op == ast::BinOp::Or) => {
+                        stack.push(Value::Boolean(lhs));
+                        ip = *target;
+                    }
+                    Value::Boolean(_) => {}
+                    lhs => return Err(logical_operand_mismatch(*op, &lhs)),
+                },
+                Op::ExpectBool { op } => {
+                    if !matches!(stack.last(), Some(Value::Boolean(_))) {
+                        return Err(logical_operand_mismatch(*op, &pop(stack)));
+                    }
+                }
+                Op::JumpIfFalse { target } => match pop(stack) {
+                    Value::Boolean(true) => {}
+                    Value::Boolean(false) => ip = *target,
+                    condition => {
+                        return Err(RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+                            expected: "bool".to_string(),
+                            actual: condition.type_name().to_string(),
+                            operation: "if condition".to_string(),
+                        }))
+                    }
+                },
+                Op::Jump { target } => ip = *target,
+                Op::Array { len } => {
+                    let items = stack.split_off(stack.len() - len);
+                    stack.push(Value::Array(items));
+                }
+                Op::Call { name, argc, span } => {
+                    let args = stack.split_off(stack.len() - argc);
+                    let caller_module = current(frames).module;
+                    match self.call_named(caller_module, ctx, name, args, *span)? {
+                        CallOutcome::Value(value) => stack.push(value),
+                        CallOutcome::Enter {
+                            module,
+                            function,
+                            args,
+                        } => {
+                            self.enter_function_call(module, ctx, name, function, args)?;
+                            current(frames).ip = ip;
+                            let callee = self.compiled_chunk(module, ctx, function.body);
+                            frames.push(Frame {
+                                module,
+                                function: Some(function),
+                                chunk: Arc::clone(&callee),
+                                ip: 0,
+                                scopes: Vec::new(),
+                            });
+                            chunk = callee;
+                            ip = 0;
+                        }
+                    }
+                }
+                Op::Eval(expr_id) => {
+                    let module = current(frames).module;
+                    stack.push(self.eval_expr(module, ctx, *expr_id)?);
+                }
+                Op::Fail(error) => return Err(error.clone()),
+            }
+        }
+    }
+}
+
+fn current<'f, 'a>(frames: &'f mut [Frame<'a>]) -> &'f mut Frame<'a> {
+    frames.last_mut().expect("a frame is running")
+}
+
+fn pop(stack: &mut Vec<Value>) -> Value {
+    stack.pop().expect("bytecode never pops an empty stack")
+}
+
+/// The error for an operand of `&&` or `||` that is not a bool
+fn logical_operand_mismatch(op: ast::BinOp, operand: &Value) -> RuntimeError {
+    let operation = match op {
+        ast::BinOp::Or => "logical or",
+        _ => "logical and",
+    };
+    RuntimeError::new(RuntimeErrorKind::TypeMismatch {
+        expected: "bool".to_string(),
+        actual: operand.type_name().to_string(),
+        operation: operation.to_string(),
+    })
+}
@@ -1,28 +1,55 @@
 //! NX Interpreter - Runtime execution engine for NX HIR
 //!
 //! This crate provides a tree-walking interpreter for executing NX functions
-//! represented in High-level Intermediate Representation (HIR). It supports
-//! arithmetic, logical, and control flow operations with comprehensive error
-//! reporting and resource limits for safe execution.
+//! represented in High-level Intermediate Representation (HIR), and a bytecode
+//! VM backend with the same semantics. It supports arithmetic, logical, and
+//! control flow operations with comprehensive error reporting and resource
+//! limits for safe execution.
 
 mod context;
 mod error;
+mod host;
+mod i18n;
 mod interpreter;
+mod log;
+mod money;
 mod resolved_program;
+mod stream;
 mod value;
 
 pub mod eval;
 
-pub use context::{ExecutionContext, ResourceLimits};
-pub use error::{RuntimeError, RuntimeErrorKind};
+pub use context::{
+    EvalStats, ExecutionContext, RandomSource, ResourceLimits, DEADLINE_CHECK_INTERVAL,
+};
+pub use error::{ConstraintViolation, RuntimeError, RuntimeErrorKind};
+pub use eval::arithmetic::NullConcatenation;
+pub use host::HostFunction;
+pub use i18n::MessageCatalog;
 pub use interpreter::{
-    ComponentDispatchResult, ComponentEvaluateResult, ComponentInitResult, Interpreter,
+    Backend, ComponentDispatchResult, ComponentEvaluateResult, ComponentInitResult, ExprEvalError,
+    Interpreter, PreparedFunction,
 };
+pub use log::{LogLevel, LogRecord, LogSink};
+pub use money::Money;
 pub use resolved_program::{
     ModuleQualifiedExprRef, ModuleQualifiedItemRef, ResolvedItemKind, ResolvedModule,
     ResolvedModuleSource, ResolvedProgram, RuntimeModuleId,
 };
-pub use value::Value;
+pub use stream::StreamEvent;
+pub use value::{NativeHandle, NativeValue, Value};
+
+/// Version of the nx-interpreter crate, identical to the toolchain version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Hosts share one interpreter and its resolved program between request threads. Values cross
+// threads as evaluation results.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Interpreter>();
+    assert_send_sync::<ResolvedProgram>();
+    assert_send_sync::<Value>();
+};
 
 #[cfg(test)]
 mod tests {
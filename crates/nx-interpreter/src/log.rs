@@ -0,0 +1,115 @@
+//! Messages from the `log` builtin
+//!
+//! `log(level, message, value?)` never writes to stdout or stderr. Each call becomes a
+//! [`LogRecord`] passed to the [`LogSink`] the host installed with
+//! [`Interpreter::with_log_sink`](crate::Interpreter::with_log_sink) or
+//! [`ExecutionContext::with_log_sink`](crate::ExecutionContext::with_log_sink), and calls below
+//! the sink's minimum level are dropped without building a record. Without a sink, `log` does
+//! nothing.
+
+use crate::value::Value;
+use std::fmt;
+use std::sync::Arc;
+
+/// Severity of a `log` call, from least to most severe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Returns every level, from least to most severe.
+    pub fn all() -> &'static [LogLevel] {
+        &[
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Warn,
+            LogLevel::Error,
+        ]
+    }
+
+    /// Returns the name programs pass to `log`, such as `"warn"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+
+    /// Parses a level name as written in a `log` call.
+    pub fn from_name(name: &str) -> Option<LogLevel> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|level| level.as_str() == name)
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One `log` call that passed the level filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub message: String,
+    /// The optional third argument, such as the value being inspected.
+    pub value: Option<Value>,
+}
+
+/// Receives the records of `log` calls
+///
+/// Sinks are shared by every execution of an interpreter, possibly on several threads at once.
+/// Closures taking a [`LogRecord`] are sinks.
+pub trait LogSink: Send + Sync {
+    fn log(&self, record: LogRecord);
+}
+
+impl<F> LogSink for F
+where
+    F: Fn(LogRecord) + Send + Sync,
+{
+    fn log(&self, record: LogRecord) {
+        self(record)
+    }
+}
+
+/// A sink and the minimum level it receives
+#[derive(Clone)]
+pub(crate) struct Logger {
+    sink: Arc<dyn LogSink>,
+    min_level: LogLevel,
+}
+
+impl Logger {
+    pub(crate) fn new(sink: Arc<dyn LogSink>, min_level: LogLevel) -> Self {
+        Self { sink, min_level }
+    }
+
+    pub(crate) fn enabled(&self, level: LogLevel) -> bool {
+        level >= self.min_level
+    }
+
+    pub(crate) fn log(&self, record: LogRecord) {
+        if self.enabled(record.level) {
+            self.sink.log(record);
+        }
+    }
+}
+
+impl fmt::Debug for Logger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Logger")
+            .field("min_level", &self.min_level)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,358 @@
+//! Exact amounts of money, the runtime representation of the `money` type.
+//!
+//! An amount is a whole number of its currency's minor unit: cents for `EUR`, whole yen for
+//! `JPY`, fils for `KWD`. Amounts in the same currency add, subtract and compare exactly;
+//! multiplying or dividing by a number rounds half away from zero to the minor unit.
+
+use crate::error::{RuntimeError, RuntimeErrorKind};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use smol_str::SmolStr;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Currencies whose minor unit is not a hundredth, with its number of decimal places
+///
+/// From ISO 4217; every other currency has two decimal places.
+const MINOR_UNIT_DIGITS: &[(&str, u32)] = &[
+    ("BHD", 3),
+    ("BIF", 0),
+    ("CLP", 0),
+    ("DJF", 0),
+    ("GNF", 0),
+    ("IQD", 3),
+    ("ISK", 0),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KMF", 0),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("LYD", 3),
+    ("OMR", 3),
+    ("PYG", 0),
+    ("RWF", 0),
+    ("TND", 3),
+    ("UGX", 0),
+    ("VND", 0),
+    ("VUV", 0),
+    ("XAF", 0),
+    ("XOF", 0),
+    ("XPF", 0),
+];
+
+/// Symbols `formatMoney` writes before the amount; other currencies are written after it.
+const SYMBOLS: &[(&str, &str)] = &[
+    ("EUR", "€"),
+    ("GBP", "£"),
+    ("INR", "₹"),
+    ("JPY", "¥"),
+    ("KRW", "₩"),
+    ("USD", "$"),
+];
+
+/// An amount of money in one currency
+///
+/// # Example
+/// ```
+/// use nx_interpreter::Money;
+///
+/// let price = Money::parse("1234.5", "EUR").unwrap();
+/// assert_eq!(price.minor_units(), 123450);
+/// assert_eq!(price.to_string(), "1234.50 EUR");
+/// assert_eq!(price.format(), "€1,234.50");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Money {
+    units: i64,
+    currency: SmolStr,
+}
+
+impl Money {
+    /// Create an amount from a count of the currency's minor unit, such as cents
+    ///
+    /// Fails when `currency` is not a three-letter uppercase code.
+    pub fn from_minor_units(units: i64, currency: &str) -> Result<Self, String> {
+        if currency.len() != 3 || !currency.bytes().all(|byte| byte.is_ascii_uppercase()) {
+            return Err(format!(
+                "'{}' is not a currency code; use three uppercase letters such as \"EUR\"",
+                currency
+            ));
+        }
+        Ok(Self {
+            units,
+            currency: SmolStr::new(currency),
+        })
+    }
+
+    /// Create an amount from a whole number of the currency's major unit
+    pub fn from_int(amount: i64, currency: &str) -> Result<Self, String> {
+        let scale = 10i64.pow(currency_digits(currency));
+        let units = amount
+            .checked_mul(scale)
+            .ok_or_else(|| format!("{} {} is too large", amount, currency))?;
+        Self::from_minor_units(units, currency)
+    }
+
+    /// Create an amount from a float, rounded half away from zero to the minor unit
+    pub fn from_float(amount: f64, currency: &str) -> Result<Self, String> {
+        let units = (amount * 10f64.powi(currency_digits(currency) as i32)).round();
+        if !units.is_finite() || units.abs() >= i64::MAX as f64 {
+            return Err(format!(
+                "{} {} is not a representable amount",
+                amount, currency
+            ));
+        }
+        Self::from_minor_units(units as i64, currency)
+    }
+
+    /// Parse a decimal amount such as `"19.99"` or `"-5"` exactly
+    ///
+    /// Fails when the amount has more decimal places than the currency's minor unit.
+    pub fn parse(amount: &str, currency: &str) -> Result<Self, String> {
+        let digits = currency_digits(currency);
+        let invalid = || format!("'{}' is not a decimal amount", amount);
+        let (negative, unsigned) = match amount.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, amount),
+        };
+        let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let all_digits = |text: &str| text.bytes().all(|byte| byte.is_ascii_digit());
+        if whole.is_empty() || !all_digits(whole) || !all_digits(fraction) {
+            return Err(invalid());
+        }
+        if fraction.len() > digits as usize {
+            return Err(format!(
+                "'{}' has more than {} decimal places, the precision of {}",
+                amount, digits, currency
+            ));
+        }
+        let padded = format!("{}{:0<width$}", whole, fraction, width = digits as usize);
+        let units: i64 = padded
+            .parse()
+            .map_err(|_| format!("{} {} is too large", amount, currency))?;
+        Self::from_minor_units(if negative { -units } else { units }, currency)
+    }
+
+    /// The amount as a count of the currency's minor unit
+    pub fn minor_units(&self) -> i64 {
+        self.units
+    }
+
+    /// The three-letter currency code
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    /// The number of decimal places of the currency's minor unit
+    pub fn digits(&self) -> u32 {
+        currency_digits(&self.currency)
+    }
+
+    /// The amount in decimal notation with every decimal place, such as `"-1234.50"`
+    pub fn amount(&self) -> String {
+        self.write_amount(false)
+    }
+
+    /// The amount with thousands separators and the currency's symbol, such as `"€1,234.50"`,
+    /// or followed by its code when it has no symbol, such as `"1,234.50 CHF"`
+    pub fn format(&self) -> String {
+        let amount = self.write_amount(true);
+        match SYMBOLS.iter().find(|(code, _)| *code == self.currency) {
+            Some((_, symbol)) => match amount.strip_prefix('-') {
+                Some(amount) => format!("-{}{}", symbol, amount),
+                None => format!("{}{}", symbol, amount),
+            },
+            None => format!("{} {}", amount, self.currency),
+        }
+    }
+
+    /// Add an amount in the same currency
+    pub fn checked_add(&self, other: &Money) -> Result<Money, RuntimeError> {
+        self.same_currency(other, "addition")?;
+        let units = self
+            .units
+            .checked_add(other.units)
+            .ok_or_else(|| overflow("addition"))?;
+        Ok(self.with_units(units))
+    }
+
+    /// Subtract an amount in the same currency
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, RuntimeError> {
+        self.same_currency(other, "subtraction")?;
+        let units = self
+            .units
+            .checked_sub(other.units)
+            .ok_or_else(|| overflow("subtraction"))?;
+        Ok(self.with_units(units))
+    }
+
+    /// Negate the amount
+    pub fn checked_neg(&self) -> Result<Money, RuntimeError> {
+        let units = self
+            .units
+            .checked_neg()
+            .ok_or_else(|| overflow("negation"))?;
+        Ok(self.with_units(units))
+    }
+
+    /// Multiply by a whole number exactly
+    pub fn checked_mul_int(&self, factor: i64) -> Result<Money, RuntimeError> {
+        let units = self
+            .units
+            .checked_mul(factor)
+            .ok_or_else(|| overflow("multiplication"))?;
+        Ok(self.with_units(units))
+    }
+
+    /// Multiply by a float, rounding half away from zero to the minor unit
+    pub fn checked_mul_float(&self, factor: f64) -> Result<Money, RuntimeError> {
+        self.scaled(self.units as f64 * factor, "multiplication")
+    }
+
+    /// Divide by a number, rounding half away from zero to the minor unit
+    pub fn checked_div(&self, divisor: f64) -> Result<Money, RuntimeError> {
+        if divisor == 0.0 {
+            return Err(RuntimeError::new(RuntimeErrorKind::DivisionByZero));
+        }
+        self.scaled(self.units as f64 / divisor, "division")
+    }
+
+    /// Compare with an amount in the same currency
+    pub fn compare(&self, other: &Money, operation: &str) -> Result<Ordering, RuntimeError> {
+        self.same_currency(other, operation)?;
+        Ok(self.units.cmp(&other.units))
+    }
+
+    fn same_currency(&self, other: &Money, operation: &str) -> Result<(), RuntimeError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(RuntimeError::new(RuntimeErrorKind::CurrencyMismatch {
+                lhs: self.currency.clone(),
+                rhs: other.currency.clone(),
+                operation: operation.to_string(),
+            }))
+        }
+    }
+
+    fn scaled(&self, units: f64, operation: &str) -> Result<Money, RuntimeError> {
+        let units = units.round();
+        if !units.is_finite() || units.abs() >= i64::MAX as f64 {
+            return Err(overflow(operation));
+        }
+        Ok(self.with_units(units as i64))
+    }
+
+    fn with_units(&self, units: i64) -> Money {
+        Money {
+            units,
+            currency: self.currency.clone(),
+        }
+    }
+
+    fn write_amount(&self, group_thousands: bool) -> String {
+        let digits = self.digits() as usize;
+        let magnitude = format!("{:0>width$}", self.units.unsigned_abs(), width = digits + 1);
+        let (whole, fraction) = magnitude.split_at(magnitude.len() - digits);
+
+        let mut out = String::new();
+        if self.units < 0 {
+            out.push('-');
+        }
+        for (index, ch) in whole.chars().enumerate() {
+            if group_thousands && index > 0 && (whole.len() - index) % 3 == 0 {
+                out.push(',');
+            }
+            out.push(ch);
+        }
+        if digits > 0 {
+            out.push('.');
+            out.push_str(fraction);
+        }
+        out
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount(), self.currency)
+    }
+}
+
+/// Serializes as a `Money` record whose amount is a decimal string, so no precision is lost:
+/// `{"$type": "Money", "amount": "12.50", "currency": "EUR"}`.
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("$type", "Money")?;
+        map.serialize_entry("amount", &self.amount())?;
+        map.serialize_entry("currency", self.currency.as_str())?;
+        map.end()
+    }
+}
+
+fn currency_digits(currency: &str) -> u32 {
+    MINOR_UNIT_DIGITS
+        .iter()
+        .find(|(code, _)| *code == currency)
+        .map_or(2, |(_, digits)| *digits)
+}
+
+fn overflow(operation: &str) -> RuntimeError {
+    RuntimeError::new(RuntimeErrorKind::MoneyOverflow {
+        operation: operation.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_use_the_currency_minor_unit() {
+        assert_eq!(Money::parse("19.9", "EUR").unwrap().minor_units(), 1990);
+        assert_eq!(Money::from_int(1500, "JPY").unwrap().amount(), "1500");
+        assert_eq!(Money::from_float(1.2345, "KWD").unwrap().amount(), "1.235");
+        assert_eq!(Money::from_float(-0.005, "USD").unwrap().amount(), "-0.01");
+        assert!(Money::parse("1.999", "EUR").is_err());
+        assert!(Money::parse("1.5", "JPY").is_err());
+        assert!(Money::parse("12,50", "EUR").is_err());
+        assert!(Money::parse("1", "eur").is_err());
+    }
+
+    #[test]
+    fn arithmetic_requires_one_currency() {
+        let eur = Money::parse("10.00", "EUR").unwrap();
+        let usd = Money::parse("10.00", "USD").unwrap();
+        assert_eq!(
+            eur.checked_add(&eur).unwrap(),
+            Money::parse("20", "EUR").unwrap()
+        );
+        let error = eur.checked_add(&usd).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot perform addition on amounts in different currencies: EUR and USD"
+        );
+        assert_eq!(eur.checked_div(3.0).unwrap().amount(), "3.33");
+        assert_eq!(eur.checked_mul_float(0.19).unwrap().amount(), "1.90");
+        assert!(eur.checked_div(0.0).is_err());
+    }
+
+    #[test]
+    fn formatting_groups_thousands_and_places_the_symbol() {
+        assert_eq!(
+            Money::parse("-1234567.8", "EUR").unwrap().format(),
+            "-€1,234,567.80"
+        );
+        assert_eq!(Money::parse("0.05", "USD").unwrap().format(), "$0.05");
+        assert_eq!(
+            Money::from_int(1000, "CHF").unwrap().format(),
+            "1,000.00 CHF"
+        );
+        assert_eq!(Money::from_int(0, "JPY").unwrap().format(), "¥0");
+        assert_eq!(
+            serde_json::to_string(&Money::parse("12.5", "EUR").unwrap()).unwrap(),
+            r#"{"$type":"Money","amount":"12.50","currency":"EUR"}"#
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use nx_hir::{LocalDefinitionId, LoweredModule};
+use nx_hir::{LocalDefinitionId, LoweredModule, ModuleGraph, PreparedItemKind};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -171,6 +171,117 @@ impl ResolvedProgram {
         )
     }
 
+    /// Creates a resolved program from every module of `graph`, with `root_identity` as the root.
+    ///
+    /// Entrypoints are the root module's declarations followed by the names its imports bring
+    /// in, and each module's imports are resolved through the graph. Returns `None` when the
+    /// graph has no module with `root_identity`.
+    pub fn from_module_graph(
+        fingerprint: u64,
+        graph: &ModuleGraph,
+        root_identity: &str,
+    ) -> Option<Self> {
+        graph.module(root_identity)?;
+
+        let identities = std::iter::once(root_identity)
+            .chain(
+                graph
+                    .module_identities()
+                    .filter(|identity| *identity != root_identity),
+            )
+            .collect::<Vec<_>>();
+        let module_ids = identities
+            .iter()
+            .enumerate()
+            .map(|(index, identity)| (*identity, RuntimeModuleId::new(index as u32)))
+            .collect::<FxHashMap<_, _>>();
+        let modules = identities
+            .iter()
+            .filter_map(|identity| {
+                let lowered_module = graph.module(identity)?.clone();
+                Some(ResolvedModule {
+                    id: module_ids[identity],
+                    source: ResolvedModuleSource::SourceProvider {
+                        identity: identity.to_string(),
+                    },
+                    lowered_module: Arc::new(lowered_module),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut imports = FxHashMap::default();
+        for identity in &identities {
+            let visible_imports = graph
+                .visible_imports(identity)
+                .into_iter()
+                .filter_map(|import| {
+                    let module_id = *module_ids.get(import.module_identity.as_str())?;
+                    Some((
+                        import.visible_name.as_str().to_string(),
+                        ModuleQualifiedItemRef {
+                            module_id,
+                            definition_id: import.definition_id,
+                            kind: resolved_item_kind_from_prepared(import.kind),
+                        },
+                    ))
+                })
+                .collect::<FxHashMap<_, _>>();
+            if !visible_imports.is_empty() {
+                imports.insert(module_ids[identity], visible_imports);
+            }
+        }
+
+        let root_module_id = module_ids[root_identity];
+        let mut entry_functions = FxHashMap::default();
+        let mut entry_components = FxHashMap::default();
+        let mut entry_records = FxHashMap::default();
+        let mut entry_enums = FxHashMap::default();
+        let root_items = modules[0]
+            .lowered_module
+            .items()
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                (
+                    item.name().as_str().to_string(),
+                    ModuleQualifiedItemRef {
+                        module_id: root_module_id,
+                        definition_id: LocalDefinitionId::new(index as u32),
+                        kind: resolved_item_kind(item),
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        let root_imports = imports
+            .get(&root_module_id)
+            .into_iter()
+            .flatten()
+            .map(|(name, item_ref)| (name.clone(), item_ref.clone()));
+        for (visible_name, item_ref) in root_items.into_iter().chain(root_imports) {
+            let entries = match item_ref.kind {
+                ResolvedItemKind::Function => &mut entry_functions,
+                ResolvedItemKind::Component => &mut entry_components,
+                ResolvedItemKind::Enum => &mut entry_enums,
+                ResolvedItemKind::Record
+                | ResolvedItemKind::TypeAlias
+                | ResolvedItemKind::Value
+                | ResolvedItemKind::Union => &mut entry_records,
+            };
+            entries.entry(visible_name).or_insert(item_ref);
+        }
+
+        Some(Self::new(
+            fingerprint,
+            vec![root_module_id],
+            modules,
+            entry_functions,
+            entry_components,
+            entry_records,
+            entry_enums,
+            imports,
+        ))
+    }
+
     /// Returns the preserved root module identifiers for this program.
     pub fn root_modules(&self) -> &[RuntimeModuleId] {
         &self.root_modules
@@ -297,6 +408,18 @@ fn build_local_items(
     local_items
 }
 
+fn resolved_item_kind_from_prepared(kind: PreparedItemKind) -> ResolvedItemKind {
+    match kind {
+        PreparedItemKind::Function => ResolvedItemKind::Function,
+        PreparedItemKind::Value => ResolvedItemKind::Value,
+        PreparedItemKind::Component => ResolvedItemKind::Component,
+        PreparedItemKind::TypeAlias => ResolvedItemKind::TypeAlias,
+        PreparedItemKind::Enum => ResolvedItemKind::Enum,
+        PreparedItemKind::Union => ResolvedItemKind::Union,
+        PreparedItemKind::Record => ResolvedItemKind::Record,
+    }
+}
+
 fn resolved_item_kind(item: &nx_hir::Item) -> ResolvedItemKind {
     match item {
         nx_hir::Item::Function(_) => ResolvedItemKind::Function,
@@ -0,0 +1,36 @@
+//! Events for streaming evaluation
+//!
+//! [`Interpreter::stream_resolved_program_module_function`](crate::Interpreter::stream_resolved_program_module_function)
+//! reports the children of the root element as they finish, so a host can start writing output
+//! before the whole tree is evaluated.
+
+use crate::value::Value;
+use nx_hir::Name;
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+
+/// An event passed to the sink of a streaming evaluation
+///
+/// When the entry function returns an intrinsic element literal, such as `<html>...</html>`, the
+/// sink receives one [`Open`](Self::Open), then one [`Child`](Self::Child) per top-level child in
+/// order, then [`Close`](Self::Close). For any other result the whole value is reported as a
+/// single `Child` with no `Open` or `Close`.
+///
+/// # Errors after partial emission
+///
+/// Events are final once sent. If evaluation fails after `Open`, the children already reported
+/// stay reported, no `Close` follows, and the evaluation returns the error. A sink that has seen
+/// `Open` without `Close` therefore holds an incomplete element and should end its output
+/// accordingly, for example by writing an error marker before closing the connection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamEvent<'a> {
+    /// The root element's tag and properties, reported before any of its children.
+    Open {
+        tag: &'a Name,
+        properties: &'a FxHashMap<SmolStr, Value>,
+    },
+    /// A completed top-level child, or the whole result when the root is not streamed.
+    Child(&'a Value),
+    /// Every child of the root element has been reported and the element is complete.
+    Close,
+}
@@ -1,9 +1,16 @@
 //! Runtime value representation for the NX interpreter.
 
+use crate::money::Money;
 use crate::RuntimeModuleId;
 use nx_hir::Name;
 use rustc_hash::FxHashMap;
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
 use smol_str::SmolStr;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
 
 /// Runtime value types supported by the NX interpreter
 ///
@@ -57,6 +64,9 @@ pub enum Value {
     /// Represents the absence of a value
     Null,
 
+    /// Amount of money in a currency, created by the `money` builtin
+    Money(Money),
+
     /// Array of values
     ///
     /// Represents a collection of values, used for iteration and collections
@@ -97,6 +107,97 @@ pub enum Value {
         /// Captured lexical variables from the handler definition site
         captured: FxHashMap<SmolStr, Value>,
     },
+
+    /// Function value produced by partial application, such as `add(_, 10)`.
+    PartialApplication {
+        /// Owning lowered module for the function, when evaluating a resolved program.
+        module_id: Option<RuntimeModuleId>,
+        /// Function name within its owning module
+        function: Name,
+        /// Arguments in parameter order; `None` marks a parameter still to be supplied
+        args: Vec<Option<Value>>,
+    },
+
+    /// Host data with no NX representation, passed through evaluation unchanged.
+    ///
+    /// NX code can hold, store, and pass native values but cannot inspect them. At the output
+    /// boundary they are converted with [`NativeValue::to_value`].
+    Native(NativeHandle),
+}
+
+/// A host-defined data type that embedders pass into NX as [`Value::Native`]
+///
+/// Implement this for host types, such as a database handle or a decimal from the host's money
+/// library, that must survive a round trip through NX code. The interpreter never looks inside;
+/// [`to_value`](NativeValue::to_value) decides how the value appears when results are
+/// serialized or converted to `NxValue`.
+///
+/// # Example
+/// ```
+/// use nx_interpreter::{NativeHandle, NativeValue, Value};
+/// use smol_str::SmolStr;
+/// use std::any::Any;
+///
+/// #[derive(Debug)]
+/// struct Money { cents: i64, currency: &'static str }
+///
+/// impl NativeValue for Money {
+///     fn type_name(&self) -> &str { "Money" }
+///     fn to_value(&self) -> Value {
+///         Value::String(SmolStr::new(format!("{}.{:02} {}", self.cents / 100, self.cents % 100, self.currency)))
+///     }
+///     fn as_any(&self) -> &dyn Any { self }
+/// }
+///
+/// let price = Value::Native(NativeHandle::new(Money { cents: 1250, currency: "EUR" }));
+/// assert_eq!(serde_json::to_string(&price).unwrap(), "\"12.50 EUR\"");
+/// ```
+pub trait NativeValue: fmt::Debug + Send + Sync + 'static {
+    /// Name of the host type, reported as the value's runtime type.
+    fn type_name(&self) -> &str;
+
+    /// Converts this value to NX data for output. Records, arrays, and nested native values are
+    /// all allowed.
+    fn to_value(&self) -> Value;
+
+    /// Returns `self`, so hosts can downcast with [`NativeHandle::downcast_ref`].
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Shared reference to a [`NativeValue`]
+///
+/// Cloning shares the underlying host value. Two handles are equal only when they refer to the
+/// same host value.
+#[derive(Clone)]
+pub struct NativeHandle(Arc<dyn NativeValue>);
+
+impl NativeHandle {
+    /// Wraps a host value.
+    pub fn new(value: impl NativeValue) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Returns the host value.
+    pub fn get(&self) -> &dyn NativeValue {
+        self.0.as_ref()
+    }
+
+    /// Returns the host value if it is a `T`.
+    pub fn downcast_ref<T: NativeValue>(&self) -> Option<&T> {
+        self.0.as_any().downcast_ref::<T>()
+    }
+}
+
+impl fmt::Debug for NativeHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq for NativeHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 impl Value {
@@ -151,10 +252,13 @@ impl Value {
             Value::String(_) => "string",
             Value::Boolean(_) => "bool",
             Value::Null => "null",
+            Value::Money(_) => "money",
             Value::Array(_) => "array",
             Value::EnumValue { .. } => "enum",
             Value::Record { .. } => "record",
             Value::ActionHandler { .. } => "action_handler",
+            Value::PartialApplication { .. } => "function",
+            Value::Native(_) => "native",
         }
     }
 }
@@ -169,6 +273,7 @@ impl std::fmt::Display for Value {
             Value::String(s) => write!(f, "{}", s),
             Value::Boolean(b) => write!(f, "{}", b),
             Value::Null => write!(f, "null"),
+            Value::Money(money) => write!(f, "{}", money),
             Value::Array(elements) => {
                 write!(f, "[")?;
                 for (i, elem) in elements.iter().enumerate() {
@@ -196,6 +301,78 @@ impl std::fmt::Display for Value {
                 action_name,
                 ..
             } => write!(f, "<action-handler {} {} {}>", component, emit, action_name),
+            Value::PartialApplication { function, args, .. } => {
+                write!(f, "{}(", function)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match arg {
+                        Some(arg) => write!(f, "{}", arg)?,
+                        None => write!(f, "_")?,
+                    }
+                }
+                write!(f, ")")
+            }
+            Value::Native(native) => write!(f, "{}", native.get().to_value()),
+        }
+    }
+}
+
+/// Serializes values in the JSON shape of `NxValue`: records become maps with a leading `$type`
+/// entry and fields in name order, enum values become their member name, money becomes a `Money`
+/// record with a decimal string amount and its currency code, and native values are
+/// serialized as the result of [`NativeValue::to_value`]. Action handlers and partial
+/// applications are written as descriptive records, as `to_nx_value` does.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Boolean(value) => serializer.serialize_bool(*value),
+            Value::Int32(value) => serializer.serialize_i32(*value),
+            Value::Int(value) => serializer.serialize_i64(*value),
+            Value::Float32(value) => serializer.serialize_f32(*value),
+            Value::Float(value) => serializer.serialize_f64(*value),
+            Value::String(value) => serializer.serialize_str(value),
+            Value::EnumValue { member, .. } => serializer.serialize_str(member),
+            Value::Money(money) => money.serialize(serializer),
+            Value::Array(elements) => {
+                let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
+            Value::Record { type_name, fields } => {
+                let fields: BTreeMap<_, _> = fields.iter().collect();
+                let mut map = serializer.serialize_map(Some(fields.len() + 1))?;
+                map.serialize_entry("$type", type_name.as_str())?;
+                for (key, value) in fields {
+                    map.serialize_entry(key.as_str(), value)?;
+                }
+                map.end()
+            }
+            Value::ActionHandler {
+                component,
+                emit,
+                action_name,
+                ..
+            } => {
+                let mut map = serializer.serialize_map(Some(4))?;
+                map.serialize_entry("$type", "ActionHandler")?;
+                map.serialize_entry("action", action_name.as_str())?;
+                map.serialize_entry("component", component.as_str())?;
+                map.serialize_entry("emit", emit.as_str())?;
+                map.end()
+            }
+            Value::PartialApplication { function, args, .. } => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry("$type", "PartialApplication")?;
+                map.serialize_entry("arity", &args.iter().filter(|arg| arg.is_none()).count())?;
+                map.serialize_entry("function", function.as_str())?;
+                map.end()
+            }
+            Value::Native(native) => native.get().to_value().serialize(serializer),
         }
     }
 }
@@ -281,4 +458,74 @@ mod tests {
             "record"
         );
     }
+
+    #[derive(Debug)]
+    struct Point {
+        x: i64,
+        y: i64,
+    }
+
+    impl NativeValue for Point {
+        fn type_name(&self) -> &str {
+            "Point"
+        }
+
+        fn to_value(&self) -> Value {
+            let mut fields = FxHashMap::default();
+            fields.insert(SmolStr::new("x"), Value::Int(self.x));
+            fields.insert(SmolStr::new("y"), Value::Int(self.y));
+            Value::Record {
+                type_name: Name::new("Point"),
+                fields,
+            }
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn test_value_serializes_in_nx_value_json_shape() {
+        let mut fields = FxHashMap::default();
+        fields.insert(SmolStr::new("name"), Value::String(SmolStr::new("Ada")));
+        fields.insert(
+            SmolStr::new("status"),
+            Value::EnumValue {
+                type_name: Name::new("Status"),
+                member: SmolStr::new("active"),
+            },
+        );
+        fields.insert(
+            SmolStr::new("tags"),
+            Value::Array(vec![Value::Int(1), Value::Null]),
+        );
+        let user = Value::Record {
+            type_name: Name::new("User"),
+            fields,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&user).unwrap(),
+            r#"{"$type":"User","name":"Ada","status":"active","tags":[1,null]}"#
+        );
+    }
+
+    #[test]
+    fn test_native_values_serialize_through_their_hook() {
+        let point = NativeHandle::new(Point { x: 1, y: 2 });
+        let value = Value::Native(point.clone());
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"$type":"Point","x":1,"y":2}"#
+        );
+        assert_eq!(value.type_name(), "native");
+        assert_eq!(value, Value::Native(point.clone()));
+        assert_ne!(
+            value,
+            Value::Native(NativeHandle::new(Point { x: 1, y: 2 }))
+        );
+        assert_eq!(point.downcast_ref::<Point>().map(|point| point.y), Some(2));
+    }
 }
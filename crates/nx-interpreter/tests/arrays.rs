@@ -324,6 +324,42 @@ fn test_transform_array() {
     );
 }
 
+// ============================================================================
+// Higher-order builtins
+// ============================================================================
+
+#[test]
+fn test_map_filter_and_reduce_call_partial_applications() {
+    let source = r#"
+        let add(a:int, b:int): int = { a + b }
+        let isEven(n:int): bool = { n % 2 == 0 }
+        let scaled(items:int[]): int[] = { map(items, add(_, 10)) }
+        let evens(items:int[]): int[] = { filter(items, isEven(_)) }
+        let total(items:int[]): int = { reduce(items, 0, add(_, _)) }
+    "#;
+    let ints = |values: &[i64]| Value::Array(values.iter().copied().map(Value::Int).collect());
+
+    let scaled = execute_function(source, "scaled", vec![ints(&[1, 2, 3])])
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(scaled, ints(&[11, 12, 13]));
+    let evens = execute_function(source, "evens", vec![ints(&[1, 2, 3, 4])])
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(evens, ints(&[2, 4]));
+    let total = execute_function(source, "total", vec![ints(&[1, 2, 3])])
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(total, Value::Int(6));
+}
+
+#[test]
+fn test_map_rejects_a_value_that_is_not_a_function() {
+    let source = r#"
+        let root(): int[] = { map([1, 2], 3) }
+    "#;
+
+    let error = execute_function(source, "root", vec![]).expect_err("3 is not a function");
+    assert!(error.contains("function"), "{}", error);
+}
+
 // ============================================================================
 // Mixed Type Arrays (ignored until parser support is added)
 // ============================================================================
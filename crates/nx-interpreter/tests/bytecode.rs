@@ -0,0 +1,279 @@
+//! Integration tests for the bytecode backend
+//!
+//! Every program runs on both backends, which must agree on the result, the error, and the
+//! operations counted along the way.
+
+use nx_hir::{lower, LoweredModule, SourceId};
+use nx_interpreter::{
+    Backend, ExecutionContext, Interpreter, ResourceLimits, RuntimeError, RuntimeErrorKind, Value,
+};
+use nx_syntax::parse_str;
+
+fn lower_source(source: &str) -> LoweredModule {
+    let parse_result = parse_str(source, "bytecode.nx");
+    assert!(parse_result.errors.is_empty(), "{:?}", parse_result.errors);
+    lower(
+        parse_result.root().expect("source parses"),
+        SourceId::new(0),
+    )
+}
+
+/// Runs `function_name` on one backend, returning its result and the final context.
+fn run_on(
+    backend: Backend,
+    module: &LoweredModule,
+    function_name: &str,
+    args: Vec<Value>,
+    limits: ResourceLimits,
+) -> (Result<Value, RuntimeError>, ExecutionContext) {
+    let interpreter = Interpreter::new().with_backend(backend);
+    let mut ctx = interpreter.new_context(limits);
+    let result = interpreter.execute_function_in_context(module, function_name, args, &mut ctx);
+    (result, ctx)
+}
+
+/// Runs `function_name` on both backends, checks that they agree, and returns the result.
+fn run_both_with_limits(
+    source: &str,
+    function_name: &str,
+    args: Vec<Value>,
+    limits: ResourceLimits,
+) -> Result<Value, RuntimeError> {
+    let module = lower_source(source);
+    let (tree_result, tree_ctx) = run_on(
+        Backend::TreeWalker,
+        &module,
+        function_name,
+        args.clone(),
+        limits,
+    );
+    let (bytecode_result, bytecode_ctx) =
+        run_on(Backend::Bytecode, &module, function_name, args, limits);
+
+    // Errors carry their location and call stack, which must match as well.
+    assert_eq!(
+        format!("{tree_result:?}"),
+        format!("{bytecode_result:?}"),
+        "results differ for {source}"
+    );
+    assert_eq!(
+        tree_ctx.operation_count(),
+        bytecode_ctx.operation_count(),
+        "operation counts differ for {source}"
+    );
+    assert_eq!(
+        tree_ctx.call_stack_depth(),
+        bytecode_ctx.call_stack_depth(),
+        "call stacks differ for {source}"
+    );
+    assert_eq!(
+        tree_ctx.snapshot_visible_variables(),
+        bytecode_ctx.snapshot_visible_variables(),
+        "scopes differ for {source}"
+    );
+    bytecode_result
+}
+
+fn run_both(source: &str, function_name: &str, args: Vec<Value>) -> Result<Value, RuntimeError> {
+    run_both_with_limits(source, function_name, args, ResourceLimits::default())
+}
+
+#[test]
+fn test_bytecode_matches_tree_walker_for_arithmetic_and_conditionals() {
+    let source = r#"
+        let abs(n:int) = { if n < 0 { -n } else { n } }
+        let clamp(n:int, lo:int, hi:int) = { if { n < lo => lo n > hi => hi else => n } }
+        let score(a:int, b:int) = { abs(a - b) * 2 + clamp(a, 0, 10) % 3 }
+    "#;
+
+    assert_eq!(
+        run_both(source, "score", vec![Value::Int(3), Value::Int(11)]).unwrap(),
+        Value::Int(16)
+    );
+    assert_eq!(
+        run_both(
+            source,
+            "clamp",
+            vec![Value::Int(42), Value::Int(0), Value::Int(10)]
+        )
+        .unwrap(),
+        Value::Int(10)
+    );
+}
+
+#[test]
+fn test_bytecode_matches_tree_walker_for_recursion() {
+    let source = r#"
+        let fib(n:int): int = { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } }
+        let isEven(n:int): bool = { if n == 0 { true } else { isOdd(n - 1) } }
+        let isOdd(n:int): bool = { if n == 0 { false } else { isEven(n - 1) } }
+    "#;
+
+    assert_eq!(
+        run_both(source, "fib", vec![Value::Int(15)]).unwrap(),
+        Value::Int(610)
+    );
+    assert_eq!(
+        run_both(source, "isEven", vec![Value::Int(101)]).unwrap(),
+        Value::Boolean(false)
+    );
+}
+
+#[test]
+fn test_bytecode_matches_tree_walker_for_short_circuits_and_strings() {
+    let source = r#"
+        let check(a:bool, n:int) = { a && n > 0 || !a && n < 0 }
+        let greet(name:string) = { "Hello, " + name + "!" }
+        let guarded(n:int) = { n != 0 && 10 / n > 1 }
+        let items(n:int) = { [n, n + 1, greet("x")] }
+    "#;
+
+    assert_eq!(
+        run_both(source, "check", vec![Value::Boolean(false), Value::Int(-1)]).unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        run_both(source, "greet", vec![Value::String("NX".into())]).unwrap(),
+        Value::String("Hello, NX!".into())
+    );
+    assert_eq!(
+        run_both(source, "guarded", vec![Value::Int(0)]).unwrap(),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        run_both(source, "items", vec![Value::Int(1)]).unwrap(),
+        Value::Array(vec![
+            Value::Int(1),
+            Value::Int(2),
+            Value::String("Hello, x!".into())
+        ])
+    );
+}
+
+#[test]
+fn test_bytecode_matches_tree_walker_for_expressions_it_delegates() {
+    let source = r#"
+        type User = { name: string age: int = 30 }
+        let double(n:int) = { n * 2 }
+        let total(items:int[]) = { for item in items { double(item) } }
+        let describe(n:int) = { if n is { 1 => "one" 2 => "two" else => "many" } }
+        let user(name:string) = { <User name={name} /> }
+        let shout(s:string) = { upper(s) + "!" }
+    "#;
+
+    assert_eq!(
+        run_both(
+            source,
+            "total",
+            vec![Value::Array(vec![Value::Int(1), Value::Int(2)])]
+        )
+        .unwrap(),
+        Value::Array(vec![Value::Int(2), Value::Int(4)])
+    );
+    assert_eq!(
+        run_both(source, "describe", vec![Value::Int(2)]).unwrap(),
+        Value::String("two".into())
+    );
+    let tree_walker_user = Interpreter::new()
+        .execute_function(
+            &lower_source(source),
+            "user",
+            vec![Value::String("Ada".into())],
+        )
+        .unwrap();
+    assert_eq!(
+        run_both(source, "user", vec![Value::String("Ada".into())]).unwrap(),
+        tree_walker_user
+    );
+    assert_eq!(
+        run_both(source, "shout", vec![Value::String("nx".into())]).unwrap(),
+        Value::String("NX!".into())
+    );
+}
+
+#[test]
+fn test_bytecode_matches_tree_walker_errors() {
+    let source = r#"
+        let notBool(n:int) = { if n { 1 } else { 2 } }
+        let badAnd(n:int) = { n && true }
+        let badOr(b:bool, n:int) = { b || n }
+        let divide(a:int, b:int) = { a / b }
+        let nested(a:int) = { divide(a, 0) + 1 }
+        let missing(a:int) = { undefinedThing + a }
+        let typed(a:int): string = { a }
+    "#;
+
+    for (function_name, args) in [
+        ("notBool", vec![Value::Int(1)]),
+        ("badAnd", vec![Value::Int(1)]),
+        ("badOr", vec![Value::Boolean(false), Value::Int(1)]),
+        ("nested", vec![Value::Int(1)]),
+        ("missing", vec![Value::Int(1)]),
+        ("typed", vec![Value::Int(1)]),
+    ] {
+        assert!(
+            run_both(source, function_name, args).is_err(),
+            "{function_name}"
+        );
+    }
+}
+
+#[test]
+fn test_bytecode_enforces_limits_like_tree_walker() {
+    let source = r#"
+        let countdown(n:int): int = { if n == 0 { 0 } else { countdown(n - 1) } }
+    "#;
+
+    let error = run_both_with_limits(
+        source,
+        "countdown",
+        vec![Value::Int(50)],
+        ResourceLimits {
+            max_recursion_depth: 10,
+            ..ResourceLimits::default()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        RuntimeErrorKind::StackOverflow { depth: 10 }
+    ));
+
+    let error = run_both_with_limits(
+        source,
+        "countdown",
+        vec![Value::Int(50)],
+        ResourceLimits {
+            max_operations: 100,
+            ..ResourceLimits::default()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        RuntimeErrorKind::OperationLimitExceeded { limit: 100 }
+    ));
+}
+
+#[test]
+fn test_bytecode_runs_recursion_deeper_than_the_native_stack_allows() {
+    let module =
+        lower_source(r#"let sum(n:int): int = { if n == 0 { 0 } else { n + sum(n - 1) } }"#);
+    let limits = ResourceLimits {
+        max_recursion_depth: 10_000,
+        ..ResourceLimits::default()
+    };
+
+    let result = std::thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(move || {
+            Interpreter::new()
+                .with_backend(Backend::Bytecode)
+                .execute_function_with_limits(&module, "sum", vec![Value::Int(3_000)], limits)
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(result.unwrap(), Value::Int(4_501_500));
+}
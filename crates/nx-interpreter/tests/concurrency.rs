@@ -0,0 +1,47 @@
+//! Integration tests for sharing one interpreter and module between threads
+
+use nx_hir::{lower, SourceId};
+use nx_interpreter::{Interpreter, RandomSource, Value};
+use nx_syntax::parse_str;
+use std::sync::Arc;
+use std::thread;
+
+const SOURCE: &str = r#"
+let scale = 10
+let scaled(n:int) = { n * scale }
+let roll() = { randomInt(1, 1000000) }
+"#;
+
+#[test]
+fn test_shared_interpreter_evaluates_requests_on_worker_threads() {
+    let parse_result = parse_str(SOURCE, "shared.nx");
+    let module = Arc::new(lower(
+        parse_result.root().expect("Failed to get root"),
+        SourceId::new(0),
+    ));
+    let interpreter = Arc::new(Interpreter::new().with_random_source(RandomSource::Seeded(9)));
+    let expected_roll = interpreter
+        .execute_function(&module, "roll", vec![])
+        .expect("roll should succeed");
+
+    thread::scope(|scope| {
+        for n in 0..8 {
+            let module = Arc::clone(&module);
+            let interpreter = Arc::clone(&interpreter);
+            let expected_roll = expected_roll.clone();
+            scope.spawn(move || {
+                let scaled = interpreter
+                    .execute_function(&module, "scaled", vec![Value::Int(n)])
+                    .expect("scaled should succeed");
+                assert_eq!(scaled, Value::Int(n * 10));
+
+                // Each request gets its own execution context, so seeded output does not depend
+                // on what other threads evaluate concurrently.
+                let roll = interpreter
+                    .execute_function(&module, "roll", vec![])
+                    .expect("roll should succeed");
+                assert_eq!(roll, expected_roll);
+            });
+        }
+    });
+}
@@ -514,3 +514,28 @@ fn test_empty_record() {
         other => panic!("Expected empty Record, got {:?}", other),
     }
 }
+
+/// Test numeric literal forms: separators, binary, hex floats, and exact large integers
+#[test]
+fn test_numeric_literal_forms() {
+    let source = r#"
+        let <numbers /> = {
+          [1_000_000, 0b1010, 0xFF_FF, 9007199254740993, -9223372036854775808, 1e6, 0x1.8p3, 2_5e-1]
+        }
+    "#;
+
+    let result = execute_function(source, "numbers", vec![]).unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Int(1_000_000),
+            Value::Int(10),
+            Value::Int(0xFFFF),
+            Value::Int(9_007_199_254_740_993),
+            Value::Int(i64::MIN),
+            Value::Float(1e6),
+            Value::Float(12.0),
+            Value::Float(2.5),
+        ])
+    );
+}
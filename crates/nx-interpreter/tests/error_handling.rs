@@ -200,3 +200,55 @@ fn test_enum_not_found_runtime_error() {
         other => panic!("Expected EnumNotFound, got {:?}", other),
     }
 }
+
+#[test]
+fn test_duplicate_properties_fail_instead_of_last_wins() {
+    let source = r#"
+        type User = { name:string }
+        let record() = <User name="a" name="b" />
+        let element() = <div id="a" id="b" />
+    "#;
+    let module = module_from_source(source);
+    let interpreter = Interpreter::new();
+
+    for (function, target, name) in [("record", "User", "name"), ("element", "div", "id")] {
+        let err = interpreter
+            .execute_function(&module, function, vec![])
+            .expect_err("Supplying a property twice should fail");
+        match err.kind() {
+            RuntimeErrorKind::DuplicateProperty {
+                target: actual,
+                property,
+            } => {
+                assert_eq!(actual.as_str(), target);
+                assert_eq!(property.as_str(), name);
+            }
+            other => panic!("Expected DuplicateProperty, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_failed_assertion_reports_message_and_call_span() {
+    let source = r#"let check(n:int) = { assert(n > 0, "n must be positive") }"#;
+    let module = module_from_source(source);
+
+    let interpreter = Interpreter::new();
+    assert_eq!(
+        interpreter
+            .execute_function(&module, "check", vec![Value::Int(1)])
+            .unwrap(),
+        Value::Null
+    );
+    let error = interpreter
+        .execute_function(&module, "check", vec![Value::Int(0)])
+        .expect_err("Expected the assertion to fail");
+    assert_eq!(
+        error.kind(),
+        &RuntimeErrorKind::Assertion {
+            message: "n must be positive".to_string()
+        }
+    );
+    let location = error.location().expect("Expected the call span");
+    assert_eq!(&source[location], r#"assert(n > 0, "n must be positive")"#);
+}
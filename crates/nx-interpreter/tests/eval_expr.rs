@@ -0,0 +1,94 @@
+//! Integration tests for evaluating expression text against a module
+
+use nx_hir::{lower, LoweredModule, SourceId};
+use nx_interpreter::{ExprEvalError, Interpreter, RuntimeErrorKind, Value};
+use nx_syntax::parse_str;
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+
+fn module_from_source(source: &str) -> LoweredModule {
+    let parse_result = parse_str(source, "eval-expr.nx");
+    assert!(
+        parse_result.is_ok(),
+        "Parser diagnostics: {:?}",
+        parse_result.errors
+    );
+    lower(
+        parse_result.root().expect("Should have root node"),
+        SourceId::new(0),
+    )
+}
+
+fn user(age: i64) -> Value {
+    let mut fields = FxHashMap::default();
+    fields.insert(SmolStr::new("name"), Value::String(SmolStr::new("Ada")));
+    fields.insert(SmolStr::new("age"), Value::Int(age));
+    Value::Record {
+        type_name: nx_hir::Name::new("User"),
+        fields,
+    }
+}
+
+const SOURCE: &str = r#"
+    type User = { name:string age:int }
+    let adultAge = { 18 }
+    let greet(name:string): string = { "Hello, " + name }
+    let ratio(total:int, count:int): int = { total / count }
+"#;
+
+#[test]
+fn test_eval_expr_uses_bindings_and_module_declarations() {
+    let module = module_from_source(SOURCE);
+    let interpreter = Interpreter::new();
+
+    let label = interpreter
+        .eval_expr_in(
+            &module,
+            "user.age > adultAge ? \"adult\" : \"minor\"",
+            &[("user", user(30))],
+        )
+        .expect("expression evaluates");
+    assert_eq!(label, Value::String(SmolStr::new("adult")));
+
+    let greeting = interpreter
+        .eval_expr_in(&module, "greet(user.name)", &[("user", user(12))])
+        .expect("expression evaluates");
+    assert_eq!(greeting, Value::String(SmolStr::new("Hello, Ada")));
+
+    let sum = interpreter
+        .eval_expr_in(&module, "count * 2 + 1", &[("count", Value::Int(4))])
+        .expect("expression evaluates");
+    assert_eq!(sum, Value::Int(9));
+}
+
+#[test]
+fn test_eval_expr_reports_errors_against_the_expression_text() {
+    let module = module_from_source(SOURCE);
+    let interpreter = Interpreter::new();
+
+    let Err(ExprEvalError::Diagnostics(diagnostics)) =
+        interpreter.eval_expr_in(&module, "greet(count)", &[("count", Value::Int(1))])
+    else {
+        panic!("a type error is reported before evaluation");
+    };
+    let range = diagnostics[0].labels()[0].range;
+    assert!(usize::from(range.end()) <= "greet(count)".len());
+
+    let Err(ExprEvalError::Diagnostics(diagnostics)) =
+        interpreter.eval_expr_in(&module, "1 + missing", &[])
+    else {
+        panic!("an undefined name is reported before evaluation");
+    };
+    assert!(diagnostics[0].message().contains("missing"));
+
+    let Err(ExprEvalError::Diagnostics(_)) = interpreter.eval_expr_in(&module, "1 +", &[]) else {
+        panic!("a syntax error is reported before evaluation");
+    };
+
+    let Err(ExprEvalError::Runtime(error)) =
+        interpreter.eval_expr_in(&module, "ratio(10, count)", &[("count", Value::Int(0))])
+    else {
+        panic!("division by zero fails at runtime");
+    };
+    assert_eq!(error.kind(), &RuntimeErrorKind::DivisionByZero);
+}
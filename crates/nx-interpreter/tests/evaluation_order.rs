@@ -0,0 +1,195 @@
+//! Conformance tests for evaluation order
+//!
+//! NX guarantees the order in which subexpressions run, so hosts that observe side effects see the
+//! same sequence on every backend. Each test calls `tick()`, which draws from a seeded generator,
+//! and maps the drawn values back to their position in the seeded sequence: a value of `0` ran
+//! first, `1` second, and so on. The guarantees are described in
+//! `docs/src/content/docs/reference/concepts/evaluation-order.md`.
+
+use nx_hir::{lower, SourceId};
+use nx_interpreter::{Interpreter, RandomSource, Value};
+use nx_syntax::parse_str;
+
+const SEED: u64 = 4210;
+const TICK_MAX: i64 = 1_000_000_000;
+
+const PRELUDE: &str = "let tick() = { randomInt(0, 1000000000) }\n";
+
+/// Evaluates `root()` in `source` with the seeded generator that `tick()` draws from.
+fn eval_root(source: &str) -> Value {
+    let source = format!("{}{}", PRELUDE, source);
+    let parse_result = parse_str(&source, "order.nx");
+    assert!(
+        parse_result.errors.is_empty(),
+        "parse errors: {:?}",
+        parse_result.errors
+    );
+    let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+    Interpreter::new()
+        .with_random_source(RandomSource::Seeded(SEED))
+        .execute_function(&module, "root", vec![])
+        .unwrap_or_else(|error| panic!("runtime error: {}", error))
+}
+
+/// Returns the position in the seeded sequence of a value produced by `tick()`.
+fn tick_index(value: &Value) -> usize {
+    let Value::Int(drawn) = value else {
+        panic!("expected a tick value, got {:?}", value);
+    };
+    let mut rng = fastrand::Rng::with_seed(SEED);
+    (0..64)
+        .find(|_| rng.i64(0..=TICK_MAX) == *drawn)
+        .unwrap_or_else(|| panic!("{} is not one of the first 64 ticks", drawn))
+}
+
+fn tick_indices(values: &[Value]) -> Vec<usize> {
+    values.iter().map(tick_index).collect()
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> &'a Value {
+    match value {
+        Value::Record { fields, .. } => fields
+            .get(name)
+            .unwrap_or_else(|| panic!("missing field '{}' in {:?}", name, value)),
+        other => panic!("expected a record, got {:?}", other),
+    }
+}
+
+fn array(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(items) => items,
+        other => panic!("expected an array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_properties_evaluate_left_to_right_in_source_order() {
+    let value = eval_root("let root() = { <Probe c={tick()} a={tick()} b={tick()} /> }");
+
+    assert_eq!(
+        tick_indices(&[
+            field(&value, "c").clone(),
+            field(&value, "a").clone(),
+            field(&value, "b").clone(),
+        ]),
+        vec![0, 1, 2]
+    );
+}
+
+#[test]
+fn test_properties_evaluate_before_children_and_children_in_order() {
+    let value = eval_root(
+        "let root() = { <Probe a={tick()}><Item n={tick()} /><Item n={tick()} /></Probe> }",
+    );
+
+    let content = array(field(&value, "content"));
+    assert_eq!(tick_index(field(&value, "a")), 0);
+    assert_eq!(
+        tick_indices(&[
+            field(&content[0], "n").clone(),
+            field(&content[1], "n").clone()
+        ]),
+        vec![1, 2]
+    );
+}
+
+#[test]
+fn test_nested_element_properties_complete_before_the_next_property() {
+    let value = eval_root(
+        "let root() = { <Probe first=<Item n={tick()} m={tick()} /> second={tick()} /> }",
+    );
+
+    let first = field(&value, "first");
+    assert_eq!(
+        tick_indices(&[
+            field(first, "n").clone(),
+            field(first, "m").clone(),
+            field(&value, "second").clone(),
+        ]),
+        vec![0, 1, 2]
+    );
+}
+
+#[test]
+fn test_arguments_evaluate_left_to_right_before_the_call() {
+    let value = eval_root(
+        r#"
+let triple(x:int, y:int) = { [x, y, tick()] }
+let root() = { triple(tick(), tick()) }
+"#,
+    );
+
+    assert_eq!(tick_indices(array(&value)), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_element_function_properties_evaluate_before_defaults_and_body() {
+    let value = eval_root(
+        r#"
+let <Card a:int b:int = {tick()} /> = <Probe a={a} b={b} body={tick()} />
+let root() = { <Card a={tick()} /> }
+"#,
+    );
+
+    assert_eq!(
+        tick_indices(&[
+            field(&value, "a").clone(),
+            field(&value, "b").clone(),
+            field(&value, "body").clone(),
+        ]),
+        vec![0, 1, 2]
+    );
+}
+
+#[test]
+fn test_top_level_values_evaluate_in_declaration_order_before_root() {
+    let value = eval_root(
+        r#"
+let first = {tick()}
+let second = {tick()}
+let root() = { [second, first, tick()] }
+"#,
+    );
+
+    assert_eq!(tick_indices(array(&value)), vec![1, 0, 2]);
+}
+
+#[test]
+fn test_binary_operands_evaluate_left_to_right() {
+    let value = eval_root("let root() = { [tick() - tick(), tick()] }");
+
+    let mut rng = fastrand::Rng::with_seed(SEED);
+    let (lhs, rhs) = (rng.i64(0..=TICK_MAX), rng.i64(0..=TICK_MAX));
+    let items = array(&value);
+    assert_eq!(items[0], Value::Int(lhs - rhs));
+    assert_eq!(tick_index(&items[1]), 2);
+}
+
+#[test]
+fn test_short_circuit_skips_the_right_operand() {
+    let value = eval_root("let root() = { [false && tick() == 0, true || tick() == 0, tick()] }");
+
+    let items = array(&value);
+    assert_eq!(items[0], Value::Boolean(false));
+    assert_eq!(items[1], Value::Boolean(true));
+    assert_eq!(tick_index(&items[2]), 0);
+}
+
+#[test]
+fn test_loop_iterations_and_array_items_evaluate_in_order() {
+    let value = eval_root("let root() = { [tick(), for n in [1, 2, 3] { tick() }, tick()] }");
+
+    let items = array(&value);
+    assert_eq!(tick_index(&items[0]), 0);
+    assert_eq!(tick_indices(array(&items[1])), vec![1, 2, 3]);
+    assert_eq!(tick_index(&items[2]), 4);
+}
+
+#[test]
+fn test_only_the_taken_branch_of_a_conditional_evaluates() {
+    let value = eval_root("let root() = { [if false { tick() } else { 0 }, tick()] }");
+
+    let items = array(&value);
+    assert_eq!(items[0], Value::Int(0));
+    assert_eq!(tick_index(&items[1]), 0);
+}
@@ -611,3 +611,52 @@ fn test_for_loop_preserves_order() {
         ])
     );
 }
+
+/// Test for loop over a record yields field values and names in declaration order
+#[test]
+fn test_for_loop_over_record_uses_declaration_order() {
+    let source = r#"
+        abstract type Entity = { id:string }
+        type User extends Entity = { name:string email:string }
+        let pairs() = {
+            for value, key in <User email="ada@example.com" name="Ada" id="u1" /> {
+                key + "=" + value
+            }
+        }
+        let values() = { for value in <User id="u2" name="Bo" email="bo@example.com" /> { value } }
+    "#;
+
+    let strings = |items: &[&str]| {
+        Value::Array(
+            items
+                .iter()
+                .map(|item| Value::String(SmolStr::new(*item)))
+                .collect(),
+        )
+    };
+    assert_eq!(
+        execute_function(source, "pairs", vec![]).unwrap_or_else(|e| panic!("{}", e)),
+        strings(&["id=u1", "name=Ada", "email=ada@example.com"])
+    );
+    assert_eq!(
+        execute_function(source, "values", vec![]).unwrap_or_else(|e| panic!("{}", e)),
+        strings(&["u2", "Bo", "bo@example.com"])
+    );
+}
+
+/// Test for loop over the records returned by parseCsv
+#[test]
+fn test_for_loop_over_parsed_csv_rows() {
+    let source = r#"
+        let labels(table:string) = { for row in parseCsv(table) { row.name + " x" + row.qty } }
+    "#;
+
+    let table = Value::String(SmolStr::new("name,qty\nBolt,4\n\"Nut, M6\",12\n"));
+    assert_eq!(
+        execute_function(source, "labels", vec![table]).unwrap_or_else(|e| panic!("{}", e)),
+        Value::Array(vec![
+            Value::String(SmolStr::new("Bolt x4")),
+            Value::String(SmolStr::new("Nut, M6 x12")),
+        ])
+    );
+}
@@ -9,6 +9,7 @@
 //! - Unary NOT (!expr)
 //! - Short-circuit evaluation for && and ||
 //! - Chained comparison/logical expressions
+//! - Money arithmetic and its currency checks
 //!
 //! All tests use source parsing, not direct HIR construction.
 
@@ -1136,3 +1137,34 @@ fn test_nested_short_circuit() {
     );
     assert_eq!(result.unwrap(), Value::Boolean(true));
 }
+
+// ============================================================================
+// Money
+// ============================================================================
+
+#[test]
+fn test_money_arithmetic_stays_in_one_currency() {
+    let source = r#"
+        let invoice(quantity:int): string = {
+          formatMoney(money("19.99", "EUR") * quantity + money(5, "EUR") - money(0.5, "EUR"))
+        }
+        let split(): string = { formatMoney(money(10, "USD") / 3) }
+        let cheaper(): bool = { money("9.99", "GBP") < money(10, "GBP") }
+        let mixed(): money = { money(10, "EUR") + money(10, "USD") }
+    "#;
+
+    let invoice = execute_function(source, "invoice", vec![Value::Int(3)])
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(invoice, Value::String("€64.47".into()));
+    let split = execute_function(source, "split", vec![]).unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(split, Value::String("$3.33".into()));
+    let cheaper = execute_function(source, "cheaper", vec![]).unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(cheaper, Value::Boolean(true));
+
+    let error = execute_function(source, "mixed", vec![]).unwrap_err();
+    assert!(
+        error.contains("Cannot perform addition on amounts in different currencies: EUR and USD"),
+        "{}",
+        error
+    );
+}
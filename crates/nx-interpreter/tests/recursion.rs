@@ -2,8 +2,9 @@
 
 use nx_diagnostics::{TextSize, TextSpan};
 use nx_hir::ast::{BinOp, Expr, Literal};
-use nx_hir::{Function, Item, LoweredModule, Name, Param, SourceId};
+use nx_hir::{lower, Function, Item, LoweredModule, Name, Param, SourceId};
 use nx_interpreter::{Interpreter, ResourceLimits, RuntimeErrorKind, Value};
+use nx_syntax::parse_str;
 
 /// Helper to create a text span
 fn span(start: u32, end: u32) -> TextSpan {
@@ -256,6 +257,7 @@ fn test_recursion_depth_limit() {
     let limits = ResourceLimits {
         max_operations: 1_000_000,
         max_recursion_depth: 10,
+        ..ResourceLimits::default()
     };
 
     let result =
@@ -335,11 +337,12 @@ fn test_deep_recursion_within_limit() {
     let limits = ResourceLimits {
         max_operations: 1_000_000,
         max_recursion_depth: 100,
+        ..ResourceLimits::default()
     };
 
     // Test with 50 (within limit)
     let result = interpreter
-        .execute_function_with_limits(&module, "countdown", vec![Value::Int(50)], limits.clone())
+        .execute_function_with_limits(&module, "countdown", vec![Value::Int(50)], limits)
         .unwrap();
     assert_eq!(result, Value::Int(0));
 
@@ -352,3 +355,71 @@ fn test_deep_recursion_within_limit() {
     );
     assert!(result.is_err());
 }
+
+/// Mutually recursive parity functions, written without return type annotations.
+const PARITY_SOURCE: &str = r#"
+    let isEven(n:int) = { if n == 0 { true } else { isOdd(n - 1) } }
+    let isOdd(n:int) = { if n == 0 { false } else { isEven(n - 1) } }
+"#;
+
+/// Helper to lower NX source code into a module
+fn lower_source(source: &str) -> LoweredModule {
+    let parse_result = parse_str(source, "test.nx");
+    assert!(
+        parse_result.errors.is_empty(),
+        "Parse errors: {:?}",
+        parse_result.errors
+    );
+    lower(
+        parse_result.root().expect("Failed to get root"),
+        SourceId::new(0),
+    )
+}
+
+/// Test mutual recursion between two functions
+#[test]
+fn test_mutual_recursion() {
+    let module = lower_source(PARITY_SOURCE);
+    let interpreter = Interpreter::new();
+
+    let result = interpreter
+        .execute_function(&module, "isEven", vec![Value::Int(10)])
+        .unwrap();
+    assert_eq!(result, Value::Boolean(true));
+
+    let result = interpreter
+        .execute_function(&module, "isOdd", vec![Value::Int(7)])
+        .unwrap();
+    assert_eq!(result, Value::Boolean(true));
+
+    let result = interpreter
+        .execute_function(&module, "isEven", vec![Value::Int(7)])
+        .unwrap();
+    assert_eq!(result, Value::Boolean(false));
+}
+
+/// Test that calls through both functions count toward the recursion depth limit
+#[test]
+fn test_mutual_recursion_depth_limit() {
+    let module = lower_source(PARITY_SOURCE);
+    let interpreter = Interpreter::new();
+    let limits = ResourceLimits {
+        max_operations: 1_000_000,
+        max_recursion_depth: 20,
+        ..ResourceLimits::default()
+    };
+
+    // isEven(10) alternates through 11 frames, within the limit
+    let result = interpreter
+        .execute_function_with_limits(&module, "isEven", vec![Value::Int(10)], limits)
+        .unwrap();
+    assert_eq!(result, Value::Boolean(true));
+
+    // isOdd(25) needs 26 frames, exceeding the limit
+    let result =
+        interpreter.execute_function_with_limits(&module, "isOdd", vec![Value::Int(25)], limits);
+    match result.unwrap_err().kind() {
+        RuntimeErrorKind::StackOverflow { depth } => assert_eq!(*depth, 20),
+        other => panic!("Expected StackOverflow, got {:?}", other),
+    }
+}
@@ -339,3 +339,36 @@ fn resolved_program_component_snapshots_accept_matching_program_and_reject_misma
             if reason.contains("requires a resolved program runtime")
     ));
 }
+
+#[test]
+fn resolved_program_from_module_graph_runs_functions_split_across_files() {
+    let mut graph = nx_hir::ModuleGraph::new();
+    for (identity, source) in [
+        (
+            "app/main.nx",
+            r#"import { double } from "./math"
+import "./text" as Text
+let root() = { double(Text.offset()) }"#,
+        ),
+        ("app/math.nx", "export let double(x: int) = { x * 2 }"),
+        ("app/text.nx", "export let offset() = { 21 }"),
+    ] {
+        let module = lower_source_module(source, identity).unwrap_or_else(|diagnostics| {
+            panic!("Expected {identity} to lower, got {diagnostics:?}")
+        });
+        graph.add_module(identity, module);
+    }
+
+    let program = ResolvedProgram::from_module_graph(7, &graph, "app/main.nx")
+        .expect("root module should be in the graph");
+    assert_eq!(program.modules().len(), 3);
+    assert!(program.entry_function("double").is_some());
+
+    let interpreter = Interpreter::from_resolved_program(program);
+    let value = interpreter
+        .execute_resolved_program_function("root", vec![])
+        .expect("Expected cross-module root evaluation to succeed");
+    assert_eq!(value, Value::Int(42));
+
+    assert!(ResolvedProgram::from_module_graph(7, &graph, "app/missing.nx").is_none());
+}
@@ -4,7 +4,10 @@
 
 use nx_diagnostics::render_diagnostics_cli;
 use nx_hir::{lower, SourceId};
-use nx_interpreter::{Interpreter, Value};
+use nx_interpreter::{
+    Backend, ConstraintViolation, Interpreter, ResourceLimits, RuntimeError, RuntimeErrorKind,
+    Value,
+};
 use nx_syntax::parse_str;
 use rustc_hash::FxHashMap;
 use smol_str::SmolStr;
@@ -147,6 +150,211 @@ fn test_payload_union_case_construction_applies_defaults() {
     assert_eq!(fields.get("code"), Some(&Value::Null));
 }
 
+#[test]
+fn test_element_function_fills_optional_params_and_rejects_missing_required() {
+    let source = r#"
+        let base = 10
+        let <Badge tone:string note:string? size:int = {base + 1} /> = {
+            <BadgeOut tone={tone} note={note} size={size} />
+        }
+        type BadgeOut = { tone:string note:string? size:int }
+        type User = { name:string }
+        let badge() = <Badge tone="neutral" />
+        let user() = <User />
+    "#;
+
+    let Value::Record { fields, .. } =
+        execute_function(source, "badge", vec![]).expect("badge should evaluate")
+    else {
+        panic!("Expected record result");
+    };
+    assert_eq!(fields.get("note"), Some(&Value::Null));
+    assert_eq!(fields.get("size"), Some(&Value::Int(11)));
+
+    let error = execute_function(source, "user", vec![]).expect_err("User requires name");
+    assert!(
+        error.contains("name"),
+        "Expected missing required field error, got {error}"
+    );
+}
+
+#[test]
+fn test_prepared_function_validates_and_fills_arguments_on_each_call() {
+    let source = r#"
+        let bang = "!"
+        let greet(name:string, punctuation:string = {bang}, suffix:string?) = {
+            <Greeting text={name + punctuation} suffix={suffix} />
+        }
+    "#;
+    let parse_result = parse_str(source, "test.nx");
+    let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+    let interpreter = Interpreter::new();
+    let greet = interpreter
+        .prepare(&module, "greet")
+        .expect("greet should resolve");
+
+    assert_eq!(greet.name(), "greet");
+    assert_eq!(greet.params().len(), 3);
+    assert_eq!(greet.required_params(), 1);
+    let string = |text: &str| Value::String(SmolStr::new(text));
+    for (args, text, suffix) in [
+        (vec!["Ada"], "Ada!", Value::Null),
+        (vec!["Ada", "?"], "Ada?", Value::Null),
+        (vec!["Ada", ".", "x"], "Ada.", string("x")),
+    ] {
+        let Value::Record { fields, .. } = greet
+            .call(args.into_iter().map(string).collect())
+            .expect("call should succeed")
+        else {
+            panic!("Expected record result");
+        };
+        assert_eq!(fields.get("text"), Some(&string(text)));
+        assert_eq!(fields.get("suffix"), Some(&suffix));
+    }
+
+    let missing = greet.call(vec![]).expect_err("name is required");
+    assert!(missing.to_string().contains("parameter"), "{missing}");
+    let wrong_type = greet
+        .call(vec![Value::Int(1)])
+        .expect_err("name must be a string");
+    assert!(wrong_type.to_string().contains("name"), "{wrong_type}");
+    assert!(interpreter.prepare(&module, "missing").is_err());
+}
+
+#[test]
+fn test_host_globals_are_visible_to_the_program_and_the_environment_is_inspectable() {
+    let source = r#"
+        let greeting = {"Hello, " + user}
+        let badge(name:string) = { name + " (" + theme + ")" }
+        let root(theme:string) = { <Page title={badge(greeting)} theme={theme} /> }
+    "#;
+    let parse_result = parse_str(source, "test.nx");
+    let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+    let interpreter = Interpreter::new();
+    let string = |text: &str| Value::String(SmolStr::new(text));
+
+    let mut ctx = interpreter.new_context(ResourceLimits::default());
+    ctx.set_global("user", string("Ada"));
+    ctx.set_global("theme", string("dark"));
+    let Value::Record { fields, .. } = interpreter
+        .execute_function_in_context(&module, "root", vec![string("light")], &mut ctx)
+        .expect("root should evaluate")
+    else {
+        panic!("Expected record result");
+    };
+
+    // The parameter shadows the global inside `root` and the functions it calls.
+    assert_eq!(fields.get("title"), Some(&string("Hello, Ada (light)")));
+    assert_eq!(fields.get("theme"), Some(&string("light")));
+
+    let environment = ctx.snapshot_visible_variables();
+    assert_eq!(environment.get("greeting"), Some(&string("Hello, Ada")));
+    assert_eq!(environment.get("user"), Some(&string("Ada")));
+    assert_eq!(environment.get("theme"), Some(&string("light")));
+    assert_eq!(ctx.global("theme"), Some(&string("dark")));
+
+    let undefined = interpreter
+        .execute_function(&module, "root", vec![string("light")])
+        .expect_err("user is only defined by the host");
+    assert!(undefined.to_string().contains("user"), "{undefined}");
+}
+
+#[test]
+fn test_provided_context_reaches_called_functions_until_scope_ends() {
+    let source = r#"
+        let theme: string = "light"
+        let label() = { use theme }
+        let nested() = {
+            provide theme = "dark" {
+                label() + "/" + provide theme = "contrast" { label() } + "/" + label()
+            }
+        }
+        let outside() = { label() }
+        let wrong() = { provide missing = "x" { label() } }
+    "#;
+
+    assert_eq!(
+        execute_function(source, "nested", vec![]).expect("nested should evaluate"),
+        Value::String(SmolStr::new("dark/contrast/dark"))
+    );
+    assert_eq!(
+        execute_function(source, "outside", vec![]).expect("outside should evaluate"),
+        Value::String(SmolStr::new("light"))
+    );
+
+    let error = execute_function(source, "wrong", vec![]).expect_err("missing is not a value");
+    assert!(
+        error.contains("Context 'missing'"),
+        "Expected unknown context error, got {error}"
+    );
+}
+
+#[test]
+fn test_partial_application_fills_open_arguments_in_order() {
+    let source = r#"
+        let clamp(lo:int, hi:int, x:int) = {
+            if x < lo { lo } else { if x > hi { hi } else { x } }
+        }
+        let between = { clamp(0, _, _) }
+        let toFive = { between(5, _) }
+        let capped() = { toFive(9) }
+        let direct() = { between(3, 2) }
+        let tooMany() = { toFive(1, 2) }
+    "#;
+
+    assert_eq!(
+        execute_function(source, "capped", vec![]).expect("capped should evaluate"),
+        Value::Int(5)
+    );
+    assert_eq!(
+        execute_function(source, "direct", vec![]).expect("direct should evaluate"),
+        Value::Int(2)
+    );
+
+    let error = execute_function(source, "tooMany", vec![]).expect_err("toFive takes one");
+    assert!(
+        error.contains("Function clamp expects 1 parameter(s), got 2"),
+        "Expected parameter count mismatch, got {error}"
+    );
+}
+
+#[test]
+fn test_destructured_values_bind_elements_rest_and_fields() {
+    let source = r#"
+        type User = { name:string age:int }
+        let scores: int[] = {3 5 8 13}
+        let [first, second, ...others] = {scores}
+        let { name, age } = <User name="Ada" age={36} />
+        let total() = { first + second + age }
+        let greeting() = { "Hi " + name }
+        let tail() = { others }
+    "#;
+
+    assert_eq!(
+        execute_function(source, "total", vec![]).expect("total should evaluate"),
+        Value::Int(44)
+    );
+    assert_eq!(
+        execute_function(source, "greeting", vec![]).expect("greeting should evaluate"),
+        Value::String(SmolStr::new("Hi Ada"))
+    );
+    assert_eq!(
+        execute_function(source, "tail", vec![]).expect("tail should evaluate"),
+        Value::Array(vec![Value::Int(8), Value::Int(13)])
+    );
+
+    let short = r#"
+        let scores: int[] = {3 5 8 13}
+        let [a, b, c, d, e] = {scores}
+        let missing() = { e }
+    "#;
+    let error = execute_function(short, "missing", vec![]).expect_err("scores is too short");
+    assert!(
+        error.contains("Array pattern needs exactly 5 elements, found 4"),
+        "Expected array pattern mismatch, got {error}"
+    );
+}
+
 #[test]
 fn test_fieldless_union_case_shorthand_constructs_record_value() {
     let source = r#"
@@ -608,6 +816,166 @@ fn test_record_inheritance_applies_inherited_defaults() {
     }
 }
 
+#[test]
+fn test_record_composition_applies_defaults_from_every_part() {
+    let source = r#"
+        type User = {
+          name: string = "Anon"
+        }
+
+        type Audited = {
+          createdBy: string = "system"
+        }
+
+        type Admin = User & Audited & {
+          role: string = "admin"
+        }
+    "#;
+
+    let parse_result = parse_str(source, "record-composition-defaults.nx");
+    assert!(
+        parse_result.errors.is_empty(),
+        "Parse errors: {:?}",
+        parse_result.errors
+    );
+    let root = parse_result.root().expect("root");
+    let module = lower(root, SourceId::new(0));
+
+    let interpreter = Interpreter::new();
+    let record = interpreter
+        .instantiate_record_defaults(&module, "Admin")
+        .expect("instantiate composed defaults");
+
+    match record {
+        Value::Record { type_name, fields } => {
+            assert_eq!(type_name.as_str(), "Admin");
+            assert_eq!(
+                fields.get("name"),
+                Some(&Value::String(SmolStr::new("Anon")))
+            );
+            assert_eq!(
+                fields.get("createdBy"),
+                Some(&Value::String(SmolStr::new("system")))
+            );
+            assert_eq!(
+                fields.get("role"),
+                Some(&Value::String(SmolStr::new("admin")))
+            );
+        }
+        other => panic!(
+            "Expected composed defaults to produce a record, got {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn test_record_defaults_read_sibling_fields_declared_later() {
+    let source = r#"
+        type Invoice = {
+          total: int = { reduce(items, 0, add(_, _)) }
+          count: int = { length(items) }
+          items: int[]
+        }
+
+        let add(a: int, b: int): int = { a + b }
+        let summarize(invoice: Invoice): int = { invoice.total * 10 + invoice.count }
+        let main(): int = { summarize(<Invoice items={[1, 2, 3]} />) }
+    "#;
+
+    let result = execute_function(source, "main", vec![]).expect("Should evaluate defaults");
+    assert_eq!(result, Value::Int(63));
+}
+
+#[test]
+fn test_record_constraints_report_every_violation() {
+    let source = r#"
+        type Person = {
+          name: string @minLength(1) @maxLength(5)
+          age: int @min(0) @max(150)
+          nickname: string? @minLength(2)
+        }
+        type Team = { lead: Person  members: Person[] @maxLength(1) }
+
+        let age(person: Person): int = { person.age }
+        let valid(): int = { age(<Person name="Ada" age={36} />) }
+        let invalid(): int = { age(<Person name="Adelaide" age={-1} />) }
+        let team(team: Team): int = { team.lead.age }
+    "#;
+    let parse_result = parse_str(source, "record-constraints.nx");
+    assert!(parse_result.errors.is_empty(), "{:?}", parse_result.errors);
+    let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+    assert!(
+        module.diagnostics().is_empty(),
+        "{:?}",
+        module.diagnostics()
+    );
+    let interpreter = Interpreter::new();
+
+    assert_eq!(
+        interpreter
+            .execute_function(&module, "valid", vec![])
+            .expect("valid person"),
+        Value::Int(36)
+    );
+
+    let error = interpreter
+        .execute_function(&module, "invalid", vec![])
+        .expect_err("invalid person");
+    let RuntimeErrorKind::RecordValidation {
+        record, violations, ..
+    } = error.kind()
+    else {
+        panic!("expected a validation error, got {error:?}");
+    };
+    assert_eq!(record, "Person");
+    assert_eq!(
+        violations,
+        &vec![
+            ConstraintViolation {
+                field: SmolStr::new("name"),
+                constraint: "@maxLength(5)".to_string(),
+                message: "must have at most 5 characters, got 8".to_string(),
+            },
+            ConstraintViolation {
+                field: SmolStr::new("age"),
+                constraint: "@min(0)".to_string(),
+                message: "must be at least 0, got -1".to_string(),
+            },
+        ]
+    );
+
+    // Records supplied by the host are checked as a whole before the function runs.
+    let person = |name: &str, age: i64| Value::Record {
+        type_name: "Person".into(),
+        fields: FxHashMap::from_iter([
+            (SmolStr::new("name"), Value::String(SmolStr::new(name))),
+            (SmolStr::new("age"), Value::Int(age)),
+            (SmolStr::new("nickname"), Value::Null),
+        ]),
+    };
+    let team = Value::Record {
+        type_name: "Team".into(),
+        fields: FxHashMap::from_iter([
+            (SmolStr::new("lead"), person("Ada", 200)),
+            (
+                SmolStr::new("members"),
+                Value::Array(vec![person("Bo", 20), person("", 30)]),
+            ),
+        ]),
+    };
+    let error = interpreter
+        .execute_function(&module, "team", vec![team])
+        .expect_err("invalid team");
+    assert_eq!(
+        error.to_string(),
+        "Record 'Team' failed validation in function call parameter 'team': \
+         members must have at most 1 item, got 2 (@maxLength(1)); \
+         lead.age must be at most 150, got 200 (@max(150)); \
+         members[1].name must have at least 1 character, got 0 (@minLength(1))"
+    );
+}
+
 #[test]
 fn test_action_inheritance_applies_inherited_defaults() {
     let source = r#"
@@ -1408,3 +1776,99 @@ fn test_component_invocation_binds_body_to_declared_content_prop() {
         }
     );
 }
+
+#[derive(Debug)]
+struct Session(u32);
+
+impl nx_interpreter::NativeValue for Session {
+    fn type_name(&self) -> &str {
+        "Session"
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Int(i64::from(self.0))
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[test]
+fn test_native_values_pass_through_functions_unchanged() {
+    let source = r#"
+        type Request = { session:object path:string }
+        let root(session:object) = { <Request session={session} path="/" /> }
+    "#;
+    let session = nx_interpreter::NativeHandle::new(Session(7));
+
+    let result = execute_function(source, "root", vec![Value::Native(session.clone())])
+        .unwrap_or_else(|err| panic!("Native argument was rejected:\n{}", err));
+
+    let Value::Record { fields, .. } = result else {
+        panic!("Expected a Request record, got {:?}", result);
+    };
+    assert_eq!(fields["session"], Value::Native(session));
+}
+
+#[test]
+fn test_element_parameters_accept_markup_but_not_records() {
+    let source = r#"
+        type User = { name:string }
+        let wrap(child:Element) = <div>{child}</div>
+        let markup() = { wrap(<p />) }
+        let record() = { wrap(<User name="Ada" />) }
+    "#;
+
+    let result = execute_function(source, "markup", vec![])
+        .unwrap_or_else(|err| panic!("Element argument was rejected:\n{}", err));
+    let Value::Record { type_name, .. } = result else {
+        panic!("Expected a div element, got {:?}", result);
+    };
+    assert_eq!(type_name.as_str(), "div");
+
+    let error = execute_function(source, "record", vec![]).expect_err("records are not elements");
+    assert!(error.contains("expected Element, got User"), "{}", error);
+}
+
+#[test]
+fn test_registered_host_functions_are_callable_from_nx() {
+    let source = r#"
+        let greet(name: string): string = { "Hello, " + name }
+        let root(): string = { greet(shout("ada")) + lower("!") }
+        let failing(): string = { shout(1) }
+    "#;
+    let parse_result = parse_str(source, "host-functions.nx");
+    assert!(parse_result.errors.is_empty(), "{:?}", parse_result.errors);
+    let module = lower(parse_result.root().expect("root"), SourceId::new(0));
+
+    for backend in [Backend::TreeWalker, Backend::Bytecode] {
+        let mut interpreter = Interpreter::new().with_backend(backend);
+        interpreter.register_function("shout", |args: &[Value]| match args {
+            [Value::String(text)] => Ok(Value::String(SmolStr::new(text.to_uppercase()))),
+            _ => Err(RuntimeError::new(RuntimeErrorKind::InvalidArgument {
+                function: SmolStr::new("shout"),
+                reason: "expected one string".to_string(),
+            })),
+        });
+        // Host functions shadow builtins, and program functions shadow host functions.
+        interpreter.register_function("lower", |_: &[Value]| Ok(Value::String(SmolStr::new("?"))));
+        interpreter.register_function("greet", |_: &[Value]| {
+            Ok(Value::String(SmolStr::new("unused")))
+        });
+
+        assert_eq!(
+            interpreter
+                .execute_function(&module, "root", vec![])
+                .expect("root evaluates"),
+            Value::String(SmolStr::new("Hello, ADA?"))
+        );
+        let error = interpreter
+            .execute_function(&module, "failing", vec![])
+            .expect_err("shout rejects an int");
+        assert!(
+            matches!(error.kind(), RuntimeErrorKind::InvalidArgument { function, .. } if function == "shout"),
+            "{error:?}"
+        );
+    }
+}
@@ -0,0 +1,475 @@
+//! Open documents and the language features computed from them.
+//!
+//! A document keeps its syntax tree current through incremental reparsing, and is analyzed from
+//! that tree after every change, with its imports resolved through the [`Workspace`], so that
+//! diagnostics, hovers and definitions reflect the latest text of every open document.
+
+use crate::line_index::LineIndex;
+use crate::protocol::{
+    self, DiagnosticRelatedInformation, DiagnosticSeverity, Hover, Location, MarkupContent,
+    Position, Range, TextDocumentContentChangeEvent,
+};
+use crate::workspace::Workspace;
+use nx_api::NxConfig;
+use nx_diagnostics::{Diagnostic, Severity, TextEdit};
+use nx_hir::{Item, LoweredModule};
+use nx_syntax::{parse_str, ParseResult, SyntaxKind, SyntaxNode, SyntaxTree};
+use nx_types::{ModuleArtifact, Type};
+use std::path::Path;
+use text_size::{TextRange, TextSize};
+
+/// A document the client has opened.
+pub struct Document {
+    pub uri: String,
+    /// The file name the document is analyzed under, derived from its URI.
+    pub file_name: String,
+    pub version: i32,
+    text: String,
+    lines: LineIndex,
+    /// The parse of `text`, or `None` when an edit could not be applied incrementally.
+    parse: Option<ParseResult>,
+    /// The configuration of the document's directory, discovered when it is opened or saved.
+    config: NxConfig,
+    artifact: ModuleArtifact,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// An identifier in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identifier {
+    pub name: String,
+    /// The target of a member access such as `Tone.Dark`, when the identifier is the member.
+    pub container: Option<String>,
+    pub range: TextRange,
+}
+
+impl Document {
+    pub fn new(uri: String, version: i32, text: String, workspace: &Workspace) -> Self {
+        let file_name = file_name_from_uri(&uri);
+        let parse = parse_str(&text, &file_name);
+        let config = discover_config(&file_name);
+        workspace.set_buffer(&file_name, &text);
+        let analysis = workspace.analyze(&file_name, &text, &parse, &config);
+        Self {
+            uri,
+            file_name,
+            version,
+            lines: LineIndex::new(&text),
+            text,
+            parse: Some(parse),
+            config,
+            artifact: analysis.artifact,
+            diagnostics: analysis.diagnostics,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the lowered module of the latest analysis, if the document parsed.
+    pub fn module(&self) -> Option<&LoweredModule> {
+        self.artifact.lowered_module.as_deref()
+    }
+
+    /// Returns true if the document imports a library.
+    pub fn has_imports(&self) -> bool {
+        self.module()
+            .is_some_and(|module| !module.imports.is_empty())
+    }
+
+    /// Applies the changes of one `didChange` notification, in order, and analyzes the result.
+    pub fn apply_changes(
+        &mut self,
+        version: i32,
+        changes: Vec<TextDocumentContentChangeEvent>,
+        workspace: &Workspace,
+    ) {
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let range = self.lines.text_range(&self.text, range);
+                    let edit = TextEdit::replace(&self.file_name, range, change.text.as_str());
+                    // A failed edit leaves the tree behind the text, so it is parsed again below.
+                    self.parse = self
+                        .parse
+                        .take()
+                        .and_then(|parse| parse.tree)
+                        .map(|mut tree| tree.apply_edit(edit))
+                        .filter(|parse| parse.tree.is_some());
+                    self.text.replace_range(
+                        usize::from(range.start())..usize::from(range.end()),
+                        &change.text,
+                    );
+                }
+                None => {
+                    self.text = change.text;
+                    self.parse = None;
+                }
+            }
+            self.lines = LineIndex::new(&self.text);
+        }
+        self.version = version;
+        workspace.set_buffer(&self.file_name, &self.text);
+        self.analyze(workspace);
+    }
+
+    /// Handles a save: replaces the whole text of the document when the save includes it, and
+    /// picks up changes to the `nx.toml` files that configure the document.
+    pub fn saved(&mut self, text: Option<String>, workspace: &Workspace) {
+        if let Some(text) = text.filter(|text| *text != self.text) {
+            self.lines = LineIndex::new(&text);
+            self.text = text;
+            self.parse = None;
+            workspace.set_buffer(&self.file_name, &self.text);
+        }
+        self.config = discover_config(&self.file_name);
+        self.analyze(workspace);
+    }
+
+    /// Analyzes the document again, as after a change to a document it imports.
+    pub fn analyze(&mut self, workspace: &Workspace) {
+        let parse = self
+            .parse
+            .get_or_insert_with(|| parse_str(&self.text, &self.file_name));
+        let analysis = workspace.analyze(&self.file_name, &self.text, parse, &self.config);
+        self.artifact = analysis.artifact;
+        self.diagnostics = analysis.diagnostics;
+    }
+
+    fn tree(&self) -> Option<&SyntaxTree> {
+        self.parse.as_ref()?.tree.as_ref()
+    }
+
+    /// Returns the byte offset of `position`.
+    pub fn offset(&self, position: Position) -> usize {
+        self.lines.offset(&self.text, position)
+    }
+
+    /// Returns the LSP range of byte range `range`.
+    pub fn range(&self, range: TextRange) -> Range {
+        self.lines.range(&self.text, range)
+    }
+
+    pub fn location(&self, range: TextRange) -> Location {
+        Location {
+            uri: self.uri.clone(),
+            range: self.range(range),
+        }
+    }
+
+    /// Returns the diagnostics of the latest analysis, located at their primary labels.
+    ///
+    /// Notes and help are appended to the message, and labels other than the primary one become
+    /// related information.
+    pub fn diagnostics(&self) -> Vec<protocol::Diagnostic> {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let labels = diagnostic.labels();
+                let primary = labels.iter().find(|label| label.primary).or(labels.first());
+                let mut message = diagnostic.message().to_string();
+                for extra in [diagnostic.note(), diagnostic.help()].into_iter().flatten() {
+                    message.push('\n');
+                    message.push_str(extra);
+                }
+                protocol::Diagnostic {
+                    range: self.range(primary.map_or(TextRange::default(), |label| label.range)),
+                    severity: match diagnostic.severity() {
+                        Severity::Error => DiagnosticSeverity::Error,
+                        Severity::Warning => DiagnosticSeverity::Warning,
+                        Severity::Info => DiagnosticSeverity::Information,
+                        Severity::Hint => DiagnosticSeverity::Hint,
+                    },
+                    code: diagnostic.code().map(str::to_string),
+                    source: "nx",
+                    message,
+                    related_information: labels
+                        .iter()
+                        .filter(|label| {
+                            !primary.is_some_and(|primary| std::ptr::eq(*label, primary))
+                        })
+                        .filter_map(|label| {
+                            Some(DiagnosticRelatedInformation {
+                                location: self.location(label.range),
+                                message: label.message.clone()?,
+                            })
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the identifier at or just before `offset`.
+    pub fn identifier_at(&self, offset: usize) -> Option<Identifier> {
+        let tree = self.tree()?;
+        let node = std::iter::once(offset)
+            .chain(offset.checked_sub(1))
+            .filter_map(|offset| tree.node_at(offset))
+            .find(|node| node.kind() == SyntaxKind::IDENTIFIER)?;
+        let container = node
+            .parent()
+            .filter(|parent| {
+                parent.kind() == SyntaxKind::MEMBER_ACCESS_EXPRESSION
+                    && parent.child_by_field("member").map(|member| member.span())
+                        == Some(node.span())
+            })
+            .and_then(|parent| parent.child_by_field("target"))
+            .map(|target| target.text().trim().to_string());
+        Some(Identifier {
+            name: node.text().to_string(),
+            container,
+            range: node.span(),
+        })
+    }
+
+    /// Returns the declaration of the parameter or property `identifier` refers to, when it is
+    /// used inside the function or component that declares it.
+    pub fn parameter_definition(&self, identifier: &Identifier) -> Option<TextRange> {
+        if identifier.container.is_some() {
+            return None;
+        }
+        let module = self.module()?;
+        let enclosing = module
+            .items()
+            .iter()
+            .find(|item| item_span(item).contains_range(identifier.range))?;
+        match enclosing {
+            Item::Function(function) => function
+                .params
+                .iter()
+                .find(|param| param.name.as_str() == identifier.name)
+                .map(|param| param.span),
+            Item::Component(component) => component
+                .props
+                .iter()
+                .chain(&component.state)
+                .find(|prop| prop.name.as_str() == identifier.name)
+                .map(|prop| prop.span),
+            _ => None,
+        }
+    }
+
+    /// Narrows the span of a declaration to its name, so that a definition lands on the name.
+    pub fn declaration_name(&self, span: TextRange) -> TextRange {
+        let Some(tree) = self.tree() else {
+            return span;
+        };
+        let mut node = tree.node_at(span.start().into());
+        while let Some(current) = node {
+            if current.span() == span {
+                return current
+                    .child_by_field("name")
+                    .and_then(first_identifier)
+                    .map_or(span, |name| name.span());
+            }
+            if !span.contains_range(current.span()) {
+                break;
+            }
+            node = current.parent();
+        }
+        span
+    }
+
+    /// Describes the identifier or expression at `offset`.
+    ///
+    /// Declared names show their kind and type, or their declaration for types. Any other
+    /// position shows the type of the innermost expression containing it.
+    pub fn hover(&self, offset: usize) -> Option<Hover> {
+        let module = self.module()?;
+        if let Some(identifier) = self.identifier_at(offset) {
+            if let Some(description) = self.describe(module, &identifier) {
+                return Some(hover(description, self.range(identifier.range)));
+            }
+        }
+
+        let offset = TextSize::from(offset as u32);
+        let (span, ty) = module
+            .exprs()
+            .filter_map(|(id, expr)| {
+                let span = expr.span();
+                if span.is_empty() || !span.contains_inclusive(offset) {
+                    return None;
+                }
+                Some((span, self.artifact.type_of(id)?))
+            })
+            .min_by_key(|(span, _)| span.len())?;
+        if matches!(ty, Type::Error) {
+            return None;
+        }
+        Some(hover(ty.to_string(), self.range(span)))
+    }
+
+    fn describe(&self, module: &LoweredModule, identifier: &Identifier) -> Option<String> {
+        if let Some(container) = &identifier.container {
+            let Some(Item::Enum(enum_def)) = module.find_item(container) else {
+                return None;
+            };
+            return enum_def
+                .members
+                .iter()
+                .any(|member| member.name.as_str() == identifier.name)
+                .then(|| format!("{container}.{}: {container}", identifier.name));
+        }
+        if let Some(span) = self.parameter_definition(identifier) {
+            return Some(format!("(parameter) {}", &self.text[span]));
+        }
+
+        let item = module.find_item(&identifier.name)?;
+        let ty = || {
+            self.artifact
+                .type_env
+                .lookup_global(item.name())
+                .map(|ty| ty.to_string())
+        };
+        Some(match item {
+            Item::Function(_) => format!("function {}: {}", identifier.name, ty()?),
+            Item::Component(_) => format!("component {}: {}", identifier.name, ty()?),
+            Item::Value(_) => format!("let {}: {}", identifier.name, ty()?),
+            Item::TypeAlias(_) | Item::Enum(_) | Item::Union(_) | Item::Record(_) => {
+                self.text[item_span(item)].trim_end().to_string()
+            }
+        })
+    }
+}
+
+fn item_span(item: &Item) -> TextRange {
+    match item {
+        Item::Function(function) => function.span,
+        Item::Value(value) => value.span,
+        Item::Component(component) => component.span,
+        Item::TypeAlias(alias) => alias.span,
+        Item::Enum(enum_def) => enum_def.span,
+        Item::Union(union_def) => union_def.span,
+        Item::Record(record) => record.span,
+    }
+}
+
+fn first_identifier(node: SyntaxNode<'_>) -> Option<SyntaxNode<'_>> {
+    if node.kind() == SyntaxKind::IDENTIFIER {
+        return Some(node);
+    }
+    node.children().find_map(first_identifier)
+}
+
+fn hover(description: String, range: Range) -> Hover {
+    Hover {
+        contents: MarkupContent::markdown(format!("```nx\n{description}\n```")),
+        range,
+    }
+}
+
+/// Returns the configuration of the directory holding `file_name`.
+///
+/// Documents outside a directory on disk, and directories whose `nx.toml` cannot be read, use
+/// the default configuration.
+fn discover_config(file_name: &str) -> NxConfig {
+    Path::new(file_name)
+        .parent()
+        .filter(|dir| dir.is_dir())
+        .and_then(|dir| NxConfig::discover(dir).ok())
+        .unwrap_or_default()
+}
+
+/// Returns the file name to analyze the document at `uri` under.
+///
+/// `file:` URIs become their percent-decoded path, with the leading slash of a Windows drive
+/// path removed; other URIs, such as those of unsaved editors, are used as they are.
+pub fn file_name_from_uri(uri: &str) -> String {
+    let Some(rest) = uri.strip_prefix("file://") else {
+        return uri.to_string();
+    };
+    // Skip the authority, which is empty or `localhost` for local files.
+    let path = rest.find('/').map_or("", |slash| &rest[slash..]);
+
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    let path = String::from_utf8_lossy(&decoded).into_owned();
+
+    match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => path[1..].to_string(),
+        _ => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name_from_uri_decodes_paths() {
+        assert_eq!(
+            file_name_from_uri("file:///home/ada/my%20app/main.nx"),
+            "/home/ada/my app/main.nx"
+        );
+        assert_eq!(
+            file_name_from_uri("file:///c%3A/Users/ada/main.nx"),
+            "c:/Users/ada/main.nx"
+        );
+        assert_eq!(file_name_from_uri("file://localhost/tmp/a.nx"), "/tmp/a.nx");
+        assert_eq!(
+            file_name_from_uri("untitled:Untitled-1"),
+            "untitled:Untitled-1"
+        );
+    }
+
+    #[test]
+    fn test_incremental_changes_match_a_fresh_document() {
+        let text = "let a = 1\nlet f(x:int) = { x + a }\n";
+        let workspace = Workspace::new();
+        let mut document = Document::new("file:///a.nx".into(), 1, text.into(), &workspace);
+        let change = |line, start, end, text: &str| TextDocumentContentChangeEvent {
+            range: Some(Range {
+                start: Position {
+                    line,
+                    character: start,
+                },
+                end: Position {
+                    line,
+                    character: end,
+                },
+            }),
+            text: text.into(),
+        };
+
+        document.apply_changes(
+            2,
+            vec![change(0, 8, 9, "\"é\""), change(1, 17, 22, "x + a + ")],
+            &workspace,
+        );
+        let expected_text = "let a = \"é\"\nlet f(x:int) = { x + a +  }\n";
+        assert_eq!(document.text(), expected_text);
+        assert_eq!(document.version, 2);
+
+        let fresh = Document::new(
+            "file:///a.nx".into(),
+            2,
+            expected_text.into(),
+            &Workspace::new(),
+        );
+        assert_eq!(document.diagnostics(), fresh.diagnostics());
+        assert!(!document.diagnostics().is_empty());
+        assert_eq!(
+            document.tree().map(|tree| tree.root().raw().to_sexp()),
+            fresh.tree().map(|tree| tree.root().raw().to_sexp())
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! NX language server.
+//!
+//! Implements the Language Server Protocol for NX on top of `nx-syntax`, `nx-hir`, `nx-types`
+//! and `nx-api`:
+//!
+//! - **Diagnostics** are published whenever a document is opened, changed or saved
+//! - **Go to definition** resolves functions, components, values, types, enum members and
+//!   parameters across the open documents
+//! - **Hover** shows the type of a declaration, parameter or expression
+//!
+//! Documents are synchronized incrementally: each change is applied to the document's syntax
+//! tree with [`SyntaxTree::apply_edit`](nx_syntax::SyntaxTree::apply_edit), then the document is
+//! analyzed again from that tree. Imports resolve like they do for `nx check`, against the
+//! document's directory and the `[imports]` search paths of its `nx.toml`, which also sets the
+//! lint levels of its diagnostics. Open documents are read from their unsaved text, through an
+//! [`OverlayModuleResolver`](nx_api::OverlayModuleResolver), and a change to one is analyzed
+//! again in every open document that imports a library.
+//!
+//! The `nx-lsp` binary serves the protocol over stdin and stdout. Hosts that embed the server
+//! can call [`run`] with their own streams, or feed decoded messages to a [`Server`].
+//!
+//! ```
+//! use nx_lsp::Server;
+//! use serde_json::json;
+//!
+//! let mut server = Server::new();
+//! server.handle(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }));
+//! let messages = server.handle(json!({
+//!     "jsonrpc": "2.0",
+//!     "method": "textDocument/didOpen",
+//!     "params": { "textDocument": {
+//!         "uri": "file:///app.nx", "languageId": "nx", "version": 1,
+//!         "text": "let answer(): int = { \"forty-two\" }",
+//!     } },
+//! }));
+//!
+//! assert_eq!(messages[0]["method"], "textDocument/publishDiagnostics");
+//! assert_eq!(messages[0]["params"]["diagnostics"][0]["severity"], 1);
+//! ```
+
+mod document;
+mod line_index;
+mod protocol;
+mod server;
+mod transport;
+mod workspace;
+
+pub use server::{ResponseError, Server};
+
+use protocol::PARSE_ERROR;
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// Version of the nx-lsp crate, reported to clients as the server version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Serves the protocol on `input` and `output` until the client sends `exit` or closes `input`.
+///
+/// Returns the exit code the process should end with: 0 after an orderly `shutdown` and `exit`,
+/// and 1 otherwise. Messages that are not valid JSON are answered with a parse error; a stream
+/// that is not framed correctly ends the session with an error.
+pub fn run(mut input: impl BufRead, mut output: impl Write) -> io::Result<i32> {
+    let mut server = Server::new();
+    while let Some(body) = transport::read_message(&mut input)? {
+        let replies = match serde_json::from_str::<Value>(&body) {
+            Ok(message) => server.handle(message),
+            Err(error) => vec![server::response(
+                Value::Null,
+                Err(ResponseError::new(PARSE_ERROR, error.to_string())),
+            )],
+        };
+        for reply in &replies {
+            transport::write_message(&mut output, reply)?;
+        }
+        if let Some(code) = server.exit_code() {
+            return Ok(code);
+        }
+    }
+    Ok(if server.shutdown_requested() { 0 } else { 1 })
+}
@@ -0,0 +1,112 @@
+//! Conversion between byte offsets and LSP positions.
+//!
+//! NX tooling reports byte offsets into UTF-8 source, while LSP positions count UTF-16 code units
+//! within a line.
+
+use crate::protocol::{Position, Range};
+use text_size::{TextRange, TextSize};
+
+/// The start offsets of the lines of a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    /// Returns the position of byte `offset` of `text`, clamped to the end of the text.
+    pub fn position(&self, text: &str, offset: usize) -> Position {
+        let mut offset = offset.min(text.len());
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let character = text[line_start..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum::<usize>();
+        Position {
+            line: line as u32,
+            character: character as u32,
+        }
+    }
+
+    /// Returns the byte offset of `position` in `text`.
+    ///
+    /// As the protocol requires, a character past the end of its line means the end of the line,
+    /// and a line past the last one means the end of the text.
+    pub fn offset(&self, text: &str, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return text.len();
+        };
+        let line = text[line_start..].split('\n').next().unwrap_or_default();
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        let mut units = 0;
+        for (index, c) in line.char_indices() {
+            if units >= position.character as usize {
+                return line_start + index;
+            }
+            units += c.len_utf16();
+        }
+        line_start + line.len()
+    }
+
+    /// Returns the LSP range of byte range `range` of `text`.
+    pub fn range(&self, text: &str, range: TextRange) -> Range {
+        Range {
+            start: self.position(text, range.start().into()),
+            end: self.position(text, range.end().into()),
+        }
+    }
+
+    /// Returns the byte range of LSP range `range` of `text`.
+    pub fn text_range(&self, text: &str, range: Range) -> TextRange {
+        let start = self.offset(text, range.start);
+        let end = self.offset(text, range.end).max(start);
+        TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(line: u32, character: u32) -> Position {
+        Position { line, character }
+    }
+
+    #[test]
+    fn test_positions_count_utf16_units() {
+        let text = "let a = \"é😀\"\r\nlet b = 1\n";
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.position(text, 0), position(0, 0));
+        let quote = text.rfind('"').unwrap();
+        assert_eq!(index.position(text, quote), position(0, 12));
+        let b = text.find('b').unwrap();
+        assert_eq!(index.position(text, b), position(1, 4));
+        assert_eq!(index.position(text, text.len()), position(2, 0));
+
+        assert_eq!(index.offset(text, position(0, 12)), quote);
+        assert_eq!(index.offset(text, position(1, 4)), b);
+    }
+
+    #[test]
+    fn test_offsets_clamp_to_line_and_text_ends() {
+        let text = "ab\r\ncd";
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.offset(text, position(0, 10)), 2);
+        assert_eq!(index.offset(text, position(1, 10)), text.len());
+        assert_eq!(index.offset(text, position(5, 0)), text.len());
+        assert_eq!(index.position(text, 100), position(1, 2));
+    }
+}
@@ -0,0 +1,15 @@
+//! `nx-lsp` - the NX language server, speaking the Language Server Protocol over stdio.
+
+use std::io;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match nx_lsp::run(io::stdin().lock(), io::stdout().lock()) {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("nx-lsp: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
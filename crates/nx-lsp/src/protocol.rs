@@ -0,0 +1,164 @@
+//! The subset of Language Server Protocol messages the server reads and writes.
+//!
+//! Field names follow the protocol's camelCase spelling through serde. Only the fields the
+//! server uses are declared; unknown fields in client messages are ignored.
+
+use serde::{Deserialize, Serialize};
+
+/// JSON-RPC error code for a message that is not valid JSON.
+pub const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code for a message that is not a valid request.
+pub const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC error code for a request method the server does not implement.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for request parameters that do not match the method.
+pub const INVALID_PARAMS: i64 = -32602;
+/// LSP error code for a request sent before `initialize`.
+pub const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+/// `TextDocumentSyncKind.Incremental`: changes are sent as edits of the previous text.
+pub const SYNC_INCREMENTAL: u8 = 2;
+
+/// A zero-based line and UTF-16 code unit offset within that line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// A half-open range between two positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TextDocumentIdentifier {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentItem {
+    pub uri: String,
+    pub version: i32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VersionedTextDocumentIdentifier {
+    pub uri: String,
+    pub version: i32,
+}
+
+/// A change to a document: the replacement of `range`, or of the whole text when it is absent.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TextDocumentContentChangeEvent {
+    pub range: Option<Range>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidOpenTextDocumentParams {
+    pub text_document: TextDocumentItem,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidChangeTextDocumentParams {
+    pub text_document: VersionedTextDocumentIdentifier,
+    pub content_changes: Vec<TextDocumentContentChangeEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidSaveTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The saved text, sent only when the client was asked to include it.
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidCloseTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentPositionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Position,
+}
+
+/// `DiagnosticSeverity` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error = 1,
+    Warning = 2,
+    Information = 3,
+    Hint = 4,
+}
+
+impl Serialize for DiagnosticSeverity {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiagnosticRelatedInformation {
+    pub location: Location,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: DiagnosticSeverity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub source: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<DiagnosticRelatedInformation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PublishDiagnosticsParams {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i32>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Markdown text shown by the client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MarkupContent {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+impl MarkupContent {
+    pub fn markdown(value: String) -> Self {
+        Self {
+            kind: "markdown",
+            value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Hover {
+    pub contents: MarkupContent,
+    pub range: Range,
+}
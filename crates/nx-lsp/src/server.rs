@@ -0,0 +1,315 @@
+//! Request and notification dispatch.
+
+use crate::document::Document;
+use crate::protocol::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, Location, PublishDiagnosticsParams, TextDocumentPositionParams,
+    INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, SERVER_NOT_INITIALIZED, SYNC_INCREMENTAL,
+};
+use crate::workspace::Workspace;
+use nx_types::SymbolIndex;
+use rustc_hash::FxHashMap;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// An error response to a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl ResponseError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// The state of a language server session: its open documents, their declarations and the
+/// libraries they import.
+///
+/// The server is transport-agnostic: [`handle`](Self::handle) takes one decoded JSON-RPC message
+/// and returns the messages to send back, in order. [`run`](crate::run) drives it over a byte
+/// stream.
+#[derive(Default)]
+pub struct Server {
+    documents: FxHashMap<String, Document>,
+    /// Declarations of the open documents, keyed by document file name.
+    symbols: SymbolIndex,
+    workspace: Workspace,
+    initialized: bool,
+    shutdown_requested: bool,
+    exit_code: Option<i32>,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles one message from the client and returns the responses and notifications to send.
+    pub fn handle(&mut self, message: Value) -> Vec<Value> {
+        let method = message.get("method").and_then(Value::as_str);
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        match (method, message.get("id")) {
+            (Some(method), Some(id)) => {
+                let result = self.request(method, params);
+                vec![response(id.clone(), result)]
+            }
+            (Some(method), None) => self.notification(method, params),
+            // The server sends no requests, so there are no responses to process.
+            (None, _) if message.get("result").is_some() || message.get("error").is_some() => {
+                Vec::new()
+            }
+            (None, id) => vec![response(
+                id.cloned().unwrap_or(Value::Null),
+                Err(ResponseError::new(INVALID_REQUEST, "message has no method")),
+            )],
+        }
+    }
+
+    /// Returns the process exit code once the client has sent `exit`: 0 if it shut the server
+    /// down first, and 1 otherwise.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Returns true once the client has sent `shutdown`.
+    pub fn shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, ResponseError> {
+        if self.shutdown_requested {
+            return Err(ResponseError::new(
+                INVALID_REQUEST,
+                "the server is shutting down",
+            ));
+        }
+        if method == "initialize" {
+            self.initialized = true;
+            return Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": {
+                        "openClose": true,
+                        "change": SYNC_INCREMENTAL,
+                        "save": { "includeText": false },
+                    },
+                    "definitionProvider": true,
+                    "hoverProvider": true,
+                },
+                "serverInfo": { "name": "nx-lsp", "version": crate::VERSION },
+            }));
+        }
+        if !self.initialized {
+            return Err(ResponseError::new(
+                SERVER_NOT_INITIALIZED,
+                format!("received {method} before initialize"),
+            ));
+        }
+
+        match method {
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => self.definition(parse_params(params)?),
+            "textDocument/hover" => self.hover(parse_params(params)?),
+            _ => Err(ResponseError::new(
+                METHOD_NOT_FOUND,
+                format!("unsupported method {method}"),
+            )),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: Value) -> Vec<Value> {
+        if method == "exit" {
+            self.exit_code = Some(if self.shutdown_requested { 0 } else { 1 });
+            return Vec::new();
+        }
+        if !self.initialized || self.shutdown_requested {
+            return Vec::new();
+        }
+
+        // Notifications have no response, so malformed parameters are ignored.
+        match method {
+            "textDocument/didOpen" => {
+                let Ok(params) = parse_params::<DidOpenTextDocumentParams>(params) else {
+                    return Vec::new();
+                };
+                let item = params.text_document;
+                let document =
+                    Document::new(item.uri.clone(), item.version, item.text, &self.workspace);
+                self.documents.insert(item.uri.clone(), document);
+                self.document_changed(&item.uri)
+            }
+            "textDocument/didChange" => {
+                let Ok(params) = parse_params::<DidChangeTextDocumentParams>(params) else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                let Some(document) = self.documents.get_mut(&uri) else {
+                    return Vec::new();
+                };
+                document.apply_changes(
+                    params.text_document.version,
+                    params.content_changes,
+                    &self.workspace,
+                );
+                self.document_changed(&uri)
+            }
+            "textDocument/didSave" => {
+                let Ok(params) = parse_params::<DidSaveTextDocumentParams>(params) else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                let Some(document) = self.documents.get_mut(&uri) else {
+                    return Vec::new();
+                };
+                document.saved(params.text, &self.workspace);
+                self.document_changed(&uri)
+            }
+            "textDocument/didClose" => {
+                let Ok(params) = parse_params::<DidCloseTextDocumentParams>(params) else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                let Some(document) = self.documents.remove(&uri) else {
+                    return Vec::new();
+                };
+                self.symbols.remove_file(&document.file_name);
+                self.workspace.close_buffer(&document.file_name);
+                let mut messages = vec![publish_diagnostics(PublishDiagnosticsParams {
+                    uri,
+                    version: None,
+                    diagnostics: Vec::new(),
+                })];
+                messages.extend(self.reanalyze_importers(None));
+                messages
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Re-indexes the declarations of the document at `uri` and publishes its diagnostics, then
+    /// does the same for the other open documents that import libraries, which may include it.
+    fn document_changed(&mut self, uri: &str) -> Vec<Value> {
+        let Some(document) = self.documents.get(uri) else {
+            return Vec::new();
+        };
+        match document.module() {
+            Some(module) => self.symbols.update_module(&document.file_name, module),
+            None => self
+                .symbols
+                .update_file(&document.file_name, document.text()),
+        }
+        let mut messages = vec![document_diagnostics(document)];
+        messages.extend(self.reanalyze_importers(Some(uri)));
+        messages
+    }
+
+    /// Analyzes the open documents that import libraries again, except the one at `skip`, and
+    /// returns the diagnostics to publish for them.
+    fn reanalyze_importers(&mut self, skip: Option<&str>) -> Vec<Value> {
+        let mut messages = Vec::new();
+        for document in self.documents.values_mut() {
+            if Some(document.uri.as_str()) == skip || !document.has_imports() {
+                continue;
+            }
+            document.analyze(&self.workspace);
+            messages.push(document_diagnostics(document));
+        }
+        messages
+    }
+
+    /// Resolves the identifier at a position to the declarations of that name.
+    ///
+    /// Parameters resolve within their function or component. Other names resolve to the
+    /// top-level declarations and enum members of the open documents, with those of the
+    /// requesting document first.
+    fn definition(&self, params: TextDocumentPositionParams) -> Result<Value, ResponseError> {
+        let Some(document) = self.documents.get(&params.text_document.uri) else {
+            return Ok(Value::Null);
+        };
+        let offset = document.offset(params.position);
+        let Some(identifier) = document.identifier_at(offset) else {
+            return Ok(Value::Null);
+        };
+        if let Some(span) = document.parameter_definition(&identifier) {
+            return Ok(json!([document.location(span)]));
+        }
+
+        let mut locations: Vec<(bool, Location)> = self
+            .symbols
+            .search(&identifier.name)
+            .into_iter()
+            .filter(|symbol| {
+                symbol.name == identifier.name
+                    && (identifier.container.is_none() || symbol.container == identifier.container)
+            })
+            .filter_map(|symbol| {
+                let target = self
+                    .documents
+                    .values()
+                    .find(|candidate| candidate.file_name == symbol.file)?;
+                let location = target.location(target.declaration_name(symbol.span));
+                Some((target.uri != document.uri, location))
+            })
+            .collect();
+        locations.sort_by(|(left_other, left), (right_other, right)| {
+            left_other
+                .cmp(right_other)
+                .then_with(|| left.uri.cmp(&right.uri))
+                .then_with(|| left.range.start.cmp(&right.range.start))
+        });
+        let locations: Vec<Location> = locations
+            .into_iter()
+            .map(|(_, location)| location)
+            .collect();
+        Ok(json!(locations))
+    }
+
+    fn hover(&self, params: TextDocumentPositionParams) -> Result<Value, ResponseError> {
+        let hover = self
+            .documents
+            .get(&params.text_document.uri)
+            .and_then(|document| document.hover(document.offset(params.position)));
+        Ok(json!(hover))
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, ResponseError> {
+    serde_json::from_value(params)
+        .map_err(|error| ResponseError::new(INVALID_PARAMS, error.to_string()))
+}
+
+/// Builds the response to the request with id `id`.
+pub fn response(id: Value, result: Result<Value, ResponseError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    }
+}
+
+fn document_diagnostics(document: &Document) -> Value {
+    publish_diagnostics(PublishDiagnosticsParams {
+        uri: document.uri.clone(),
+        version: Some(document.version),
+        diagnostics: document.diagnostics(),
+    })
+}
+
+fn publish_diagnostics(params: PublishDiagnosticsParams) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": params,
+    })
+}
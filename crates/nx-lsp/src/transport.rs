@@ -0,0 +1,114 @@
+//! JSON-RPC message framing over byte streams.
+//!
+//! Each message is a `Content-Length` header, optionally other headers, a blank line, and then
+//! exactly that many bytes of UTF-8 JSON.
+
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+/// Reads the body of the next message from `input`.
+///
+/// Returns `Ok(None)` when the stream ends before a new message starts.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut content_length = None;
+    let mut in_headers = false;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return if in_headers {
+                Err(invalid_data("stream ended inside message headers"))
+            } else {
+                Ok(None)
+            };
+        }
+        let header = line.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            // Tolerate blank lines between messages.
+            if in_headers {
+                break;
+            }
+            continue;
+        }
+        in_headers = true;
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| invalid_data(format!("malformed header {header:?}")))?;
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            let length = value
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| invalid_data(format!("invalid Content-Length {:?}", value.trim())))?;
+            content_length = Some(length);
+        }
+    }
+
+    let length = content_length.ok_or_else(|| invalid_data("missing Content-Length header"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|_| invalid_data("message body is not UTF-8"))
+}
+
+/// Writes `message` to `output` with its header, and flushes it.
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_messages_round_trip() {
+        let mut output = Vec::new();
+        let messages = [json!({"method": "é"}), json!({"id": 1})];
+        for message in &messages {
+            write_message(&mut output, message).unwrap();
+        }
+
+        let mut input = Cursor::new(output);
+        for message in &messages {
+            let body = read_message(&mut input).unwrap().unwrap();
+            assert_eq!(&serde_json::from_str::<Value>(&body).unwrap(), message);
+        }
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_message_accepts_other_headers() {
+        let mut input = Cursor::new(
+            "Content-Type: application/vscode-jsonrpc; charset=utf-8\r\ncontent-length: 2\r\n\r\n{}",
+        );
+        assert_eq!(read_message(&mut input).unwrap().as_deref(), Some("{}"));
+    }
+
+    #[test]
+    fn test_read_message_rejects_malformed_headers() {
+        for input in [
+            "Content-Length: two\r\n\r\n{}",
+            "Content-Length 2\r\n\r\n{}",
+            "Content-Length: 2\r\n",
+            "Content-Length: 10\r\n\r\n{}",
+            "Content-Type: text/plain\r\n\r\n{}",
+        ] {
+            let error = read_message(&mut Cursor::new(input)).unwrap_err();
+            assert!(
+                matches!(
+                    error.kind(),
+                    io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+                ),
+                "{input:?}: {error}"
+            );
+        }
+    }
+}
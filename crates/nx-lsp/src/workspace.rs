@@ -0,0 +1,112 @@
+//! State shared by the open documents.
+//!
+//! The text of every open document is kept as a buffer of an [`OverlayModuleResolver`], and the
+//! libraries that documents import are loaded through it, so imports see unsaved edits to other
+//! open documents and files that only exist in the editor. Changing or closing a buffer drops the
+//! loaded libraries containing it, which are read again the next time a document imports them.
+
+use nx_api::{
+    build_program_artifact_from_parse, ImportPathResolver, LibraryRegistry, NxConfig,
+    OverlayModuleResolver,
+};
+use nx_diagnostics::Diagnostic;
+use nx_syntax::ParseResult;
+use nx_types::{analyze_str, ModuleArtifact};
+use std::path::Path;
+use std::sync::Arc;
+
+/// The unsaved buffers of the open documents and the libraries loaded through them.
+pub struct Workspace {
+    overlay: Arc<OverlayModuleResolver>,
+    registry: LibraryRegistry,
+}
+
+/// The analysis of one document.
+pub struct Analysis {
+    pub artifact: ModuleArtifact,
+    /// Diagnostics of the document, at the levels its configuration sets.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        let overlay = Arc::new(OverlayModuleResolver::default());
+        let registry = LibraryRegistry::with_module_resolver(overlay.clone());
+        overlay.on_change({
+            let registry = registry.clone();
+            move |change| {
+                registry.invalidate(&change.path);
+            }
+        });
+        Self { overlay, registry }
+    }
+
+    /// Records the text of the open document `file_name`.
+    pub fn set_buffer(&self, file_name: &str, text: &str) {
+        self.overlay.set_buffer(file_name, text);
+    }
+
+    /// Forgets the text of a closed document, so imports read the file on disk again.
+    pub fn close_buffer(&self, file_name: &str) {
+        self.overlay.close_buffer(file_name);
+    }
+
+    /// Analyzes the document `file_name` from `parse_result`, the parse of `text`.
+    ///
+    /// Imports resolve against the document's path and the search paths of `config`. When an
+    /// import cannot be resolved or its library fails to load, the document is still analyzed
+    /// for hovers and definitions, but only the import diagnostics are reported, as `nx check`
+    /// does.
+    pub fn analyze(
+        &self,
+        file_name: &str,
+        text: &str,
+        parse_result: &ParseResult,
+        config: &NxConfig,
+    ) -> Analysis {
+        let resolver = ImportPathResolver::new(config.imports.search_paths.iter().cloned());
+        let (build_context, import_diagnostics) = match &parse_result.tree {
+            Some(tree) => {
+                match self
+                    .registry
+                    .build_context_for_tree(tree, Path::new(file_name), &resolver)
+                {
+                    Ok(build_context) => (build_context, Vec::new()),
+                    Err(diagnostics) => (self.registry.build_context(), diagnostics),
+                }
+            }
+            None => (self.registry.build_context(), Vec::new()),
+        };
+
+        let Ok(program) =
+            build_program_artifact_from_parse(text, parse_result, file_name, &build_context)
+        else {
+            // Only a file name that is not a valid module identity gets here.
+            let artifact = analyze_str(text, file_name);
+            let diagnostics = config.apply_to_diagnostics(artifact.diagnostics.clone());
+            return Analysis {
+                artifact,
+                diagnostics,
+            };
+        };
+        let diagnostics = if import_diagnostics.is_empty() {
+            config.program_diagnostics(&program)
+        } else {
+            import_diagnostics
+        };
+        Analysis {
+            diagnostics: config.apply_to_diagnostics(diagnostics),
+            artifact: program
+                .root_modules
+                .into_iter()
+                .next()
+                .expect("a program built from one file has one root module"),
+        }
+    }
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,397 @@
+//! Integration tests for the NX language server
+//!
+//! Sessions are driven over in-memory streams with the same framing a client uses on stdio.
+
+use nx_lsp::{run, Server};
+use serde_json::{json, Value};
+use std::io::Cursor;
+
+const APP_URI: &str = "file:///workspace/app.nx";
+const THEME_URI: &str = "file:///workspace/theme.nx";
+
+const APP: &str = r#"type User = { name: string age: int }
+let double(n:int): int = { n * 2 }
+let greet(user: User) = { user.name }
+let <Badge label:string /> = <span>{label}</span>
+let total = { double(21) + 1 }
+let card() = { <Badge label="new" /> }
+let tone = { Tone.Dark }
+"#;
+
+const THEME: &str = "enum Tone = | Light | Dark\n";
+
+fn frame(message: &Value) -> String {
+    let body = message.to_string();
+    format!("Content-Length: {}\r\n\r\n{body}", body.len())
+}
+
+fn request(id: i64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn did_open(uri: &str, text: &str) -> Value {
+    notification(
+        "textDocument/didOpen",
+        json!({ "textDocument": { "uri": uri, "languageId": "nx", "version": 1, "text": text } }),
+    )
+}
+
+fn position_params(uri: &str, line: u32, character: u32) -> Value {
+    json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } })
+}
+
+/// Runs a session over framed streams and returns the exit code and the decoded replies.
+fn run_session(input: String) -> (i32, Vec<Value>) {
+    let mut output = Vec::new();
+    let code = run(Cursor::new(input.into_bytes()), &mut output).expect("session runs");
+
+    let mut replies = Vec::new();
+    let mut rest = String::from_utf8(output).unwrap();
+    while !rest.is_empty() {
+        let (header, body) = rest.split_once("\r\n\r\n").expect("reply has a header");
+        let length: usize = header
+            .strip_prefix("Content-Length: ")
+            .expect("reply has a length")
+            .parse()
+            .unwrap();
+        replies.push(serde_json::from_str(&body[..length]).unwrap());
+        rest = body[length..].to_string();
+    }
+    (code, replies)
+}
+
+/// Returns an initialized server with `documents` open.
+fn open(documents: &[(&str, &str)]) -> Server {
+    let mut server = Server::new();
+    server.handle(request(1, "initialize", json!({})));
+    server.handle(notification("initialized", json!({})));
+    for (uri, text) in documents {
+        server.handle(did_open(uri, text));
+    }
+    server
+}
+
+/// Returns the zero-based line and character of the first `needle` on the line starting with
+/// `line_prefix`.
+fn position_of(text: &str, line_prefix: &str, needle: &str) -> (u32, u32) {
+    let (line, content) = text
+        .lines()
+        .enumerate()
+        .find(|(_, line)| line.starts_with(line_prefix))
+        .expect("line is in the text");
+    let character = content.find(needle).expect("needle is on the line");
+    (line as u32, character as u32)
+}
+
+fn definition(server: &mut Server, line_prefix: &str, needle: &str) -> Value {
+    let (line, character) = position_of(APP, line_prefix, needle);
+    let replies = server.handle(request(
+        2,
+        "textDocument/definition",
+        position_params(APP_URI, line, character),
+    ));
+    replies[0]["result"].clone()
+}
+
+fn hover(server: &mut Server, line_prefix: &str, needle: &str) -> Value {
+    let (line, character) = position_of(APP, line_prefix, needle);
+    let replies = server.handle(request(
+        3,
+        "textDocument/hover",
+        position_params(APP_URI, line, character),
+    ));
+    replies[0]["result"].clone()
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 },
+    })
+}
+
+#[test]
+fn test_session_lifecycle() {
+    let input = [
+        request(1, "textDocument/hover", position_params(APP_URI, 0, 0)),
+        request(2, "initialize", json!({ "capabilities": {} })),
+        notification("initialized", json!({})),
+        request(3, "workspace/symbol", json!({ "query": "" })),
+        request(4, "shutdown", Value::Null),
+        request(5, "textDocument/hover", position_params(APP_URI, 0, 0)),
+        notification("exit", Value::Null),
+    ]
+    .iter()
+    .map(frame)
+    .collect::<String>();
+    let (code, replies) = run_session(input);
+
+    assert_eq!(code, 0);
+    assert_eq!(replies.len(), 5);
+    assert_eq!(replies[0]["error"]["code"], -32002);
+    let capabilities = &replies[1]["result"]["capabilities"];
+    assert_eq!(capabilities["textDocumentSync"]["change"], 2);
+    assert_eq!(capabilities["definitionProvider"], true);
+    assert_eq!(capabilities["hoverProvider"], true);
+    assert_eq!(replies[1]["result"]["serverInfo"]["name"], "nx-lsp");
+    assert_eq!(replies[2]["error"]["code"], -32601);
+    assert_eq!(
+        replies[3],
+        json!({ "jsonrpc": "2.0", "id": 4, "result": null })
+    );
+    assert_eq!(replies[4]["error"]["code"], -32600);
+}
+
+#[test]
+fn test_session_exit_codes() {
+    let initialize = frame(&request(1, "initialize", json!({})));
+    let (code, _) = run_session(initialize.clone() + &frame(&notification("exit", Value::Null)));
+    assert_eq!(code, 1);
+
+    let (code, _) = run_session(initialize.clone());
+    assert_eq!(code, 1);
+
+    let (code, replies) = run_session(
+        initialize
+            + "Content-Length: 8\r\n\r\nnot json"
+            + &frame(&request(2, "shutdown", Value::Null)),
+    );
+    assert_eq!(code, 0);
+    assert_eq!(replies[1]["error"]["code"], -32700);
+    assert_eq!(replies[1]["id"], Value::Null);
+}
+
+#[test]
+fn test_diagnostics_follow_open_change_save_and_close() {
+    let mut server = open(&[]);
+    let source = "let answer(): int = { 42 }\n";
+
+    let replies = server.handle(did_open(APP_URI, source));
+    assert_eq!(replies[0]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(replies[0]["params"]["uri"], APP_URI);
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+
+    // Replace `42` with a string, which no longer matches the declared return type.
+    let replies = server.handle(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": APP_URI, "version": 2 },
+            "contentChanges": [{ "range": range((0, 22), (0, 24)), "text": "\"forty-two\"" }],
+        }),
+    ));
+    let params = &replies[0]["params"];
+    assert_eq!(params["version"], 2);
+    let diagnostics = params["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["source"], "nx");
+
+    let replies = server.handle(notification(
+        "textDocument/didSave",
+        json!({ "textDocument": { "uri": APP_URI }, "text": source }),
+    ));
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+
+    let replies = server.handle(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": APP_URI, "version": 3 },
+            "contentChanges": [{ "text": "let broken = {" }],
+        }),
+    ));
+    assert_ne!(replies[0]["params"]["diagnostics"], json!([]));
+
+    let replies = server.handle(notification(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": APP_URI } }),
+    ));
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+    let (line, character) = (0, 5);
+    let replies = server.handle(request(
+        4,
+        "textDocument/hover",
+        position_params(APP_URI, line, character),
+    ));
+    assert_eq!(replies[0]["result"], Value::Null);
+}
+
+#[test]
+fn test_imports_resolve_against_unsaved_documents() {
+    // Neither file exists on disk; the import resolves to the open palette document.
+    const MAIN_URI: &str = "file:///workspace/main.nx";
+    const PALETTE_URI: &str = "file:///workspace/palette.nx";
+    let main = "import \"./palette.nx\"\nlet accent(): string = { primary() }\n";
+    let mut server = open(&[]);
+
+    let replies = server.handle(did_open(MAIN_URI, main));
+    assert_eq!(
+        replies[0]["params"]["diagnostics"][0]["code"],
+        "import-not-found"
+    );
+
+    let replies = server.handle(did_open(PALETTE_URI, "export let primary(): int = { 1 }\n"));
+    assert_eq!(replies[0]["params"]["uri"], PALETTE_URI);
+    assert_eq!(replies[1]["params"]["uri"], MAIN_URI);
+    let diagnostics = replies[1]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0]["code"], "return-type-mismatch");
+
+    // Make `primary` return a string, which matches the declared return type of `accent`.
+    let replies = server.handle(notification(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": PALETTE_URI, "version": 2 },
+            "contentChanges": [{ "range": range((0, 22), (0, 33)), "text": "string = { \"teal\" }" }],
+        }),
+    ));
+    assert_eq!(replies[1]["params"]["uri"], MAIN_URI);
+    assert_eq!(replies[1]["params"]["diagnostics"], json!([]));
+    let (line, character) = position_of(main, "let accent", "primary()");
+    let replies = server.handle(request(
+        2,
+        "textDocument/hover",
+        position_params(MAIN_URI, line, character + 8),
+    ));
+    assert_eq!(
+        replies[0]["result"]["contents"]["value"],
+        "```nx\nstring\n```"
+    );
+
+    let replies = server.handle(notification(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": PALETTE_URI } }),
+    ));
+    assert_eq!(replies[1]["params"]["uri"], MAIN_URI);
+    assert_eq!(
+        replies[1]["params"]["diagnostics"][0]["code"],
+        "import-not-found"
+    );
+}
+
+#[test]
+fn test_positions_count_utf16_units() {
+    let mut server = open(&[(APP_URI, "let s = { \"😀\" + \"x\" }\n")]);
+
+    // The `+` is byte 17 but UTF-16 unit 15, as the emoji takes two units.
+    let replies = server.handle(request(
+        3,
+        "textDocument/hover",
+        position_params(APP_URI, 0, 15),
+    ));
+    let result = &replies[0]["result"];
+    assert_eq!(result["contents"]["value"], "```nx\nstring\n```");
+    assert_eq!(result["range"], range((0, 10), (0, 20)));
+}
+
+#[test]
+fn test_definition_resolves_functions_types_and_components() {
+    let mut server = open(&[(APP_URI, APP)]);
+
+    let location = |line_prefix, needle: &str| {
+        let (line, character) = position_of(APP, line_prefix, needle);
+        json!([{
+            "uri": APP_URI,
+            "range": range((line, character), (line, character + needle.len() as u32)),
+        }])
+    };
+
+    assert_eq!(
+        definition(&mut server, "let total", "double"),
+        location("let double", "double")
+    );
+    assert_eq!(
+        definition(&mut server, "let greet", "User"),
+        location("type User", "User")
+    );
+    assert_eq!(
+        definition(&mut server, "let card", "Badge"),
+        location("let <Badge", "Badge")
+    );
+    // The end of an identifier resolves too.
+    let (line, character) = position_of(APP, "let total", "(21)");
+    let replies = server.handle(request(
+        2,
+        "textDocument/definition",
+        position_params(APP_URI, line, character),
+    ));
+    assert_eq!(replies[0]["result"], location("let double", "double"));
+}
+
+#[test]
+fn test_definition_resolves_parameters_locally() {
+    let mut server = open(&[(APP_URI, APP)]);
+
+    let result = definition(&mut server, "let double", "n *");
+    let (line, character) = position_of(APP, "let double", "n:int");
+    assert_eq!(
+        result,
+        json!([{ "uri": APP_URI, "range": range((line, character), (line, character + 5)) }])
+    );
+    assert_eq!(definition(&mut server, "let total", "+"), Value::Null);
+}
+
+#[test]
+fn test_definition_resolves_across_open_documents() {
+    let mut server = open(&[(APP_URI, APP), (THEME_URI, THEME)]);
+
+    let dark = THEME.find("Dark").unwrap() as u32;
+    assert_eq!(
+        definition(&mut server, "let tone", "Dark"),
+        json!([{ "uri": THEME_URI, "range": range((0, dark), (0, dark + 4)) }])
+    );
+    assert_eq!(
+        definition(&mut server, "let tone", "Tone"),
+        json!([{ "uri": THEME_URI, "range": range((0, 5), (0, 9)) }])
+    );
+
+    server.handle(notification(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": THEME_URI } }),
+    ));
+    assert_eq!(definition(&mut server, "let tone", "Tone"), json!([]));
+}
+
+#[test]
+fn test_hover_shows_declaration_and_expression_types() {
+    let mut server = open(&[(APP_URI, APP), (THEME_URI, THEME)]);
+    let contents = |result: Value| result["contents"]["value"].as_str().unwrap().to_string();
+
+    assert_eq!(
+        contents(hover(&mut server, "let total", "double")),
+        "```nx\nfunction double: (int) => int\n```"
+    );
+    assert_eq!(
+        contents(hover(&mut server, "let card", "Badge")),
+        "```nx\nfunction Badge: (string) => Element\n```"
+    );
+    assert_eq!(
+        contents(hover(&mut server, "let total", "total")),
+        "```nx\nlet total: int\n```"
+    );
+    assert_eq!(
+        contents(hover(&mut server, "let greet", "User")),
+        "```nx\ntype User = { name: string age: int }\n```"
+    );
+    assert_eq!(
+        contents(hover(&mut server, "let double", "n *")),
+        "```nx\n(parameter) n:int\n```"
+    );
+    assert_eq!(
+        contents(hover(&mut server, "let greet", "user.name")),
+        "```nx\n(parameter) user: User\n```"
+    );
+
+    let result = hover(&mut server, "let total", "+ 1");
+    assert_eq!(contents(result.clone()), "```nx\nint\n```");
+    let (line, character) = position_of(APP, "let total", "double(21) + 1");
+    assert_eq!(
+        result["range"],
+        range((line, character), (line, character + 14))
+    );
+
+    assert_eq!(hover(&mut server, "type User", "="), Value::Null);
+}
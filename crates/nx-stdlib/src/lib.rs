@@ -0,0 +1,120 @@
+//! NX standard library sources shipped with the toolchain.
+//!
+//! Each module is an NX source file under `std/`, embedded into the binary at build time and
+//! importable from any program as `std/<name>`:
+//!
+//! ```nx
+//! import "std/math"
+//!
+//! let root() = gcd(12, 18)
+//! ```
+//!
+//! Modules are written in NX on top of the native builtins declared in `nx_types::builtins`, and
+//! are versioned together with the interpreter: [`VERSION`] always matches the workspace version.
+//! Resolving and loading the modules is left to the host (see `nx-api`).
+
+/// Version of the standard library, identical to the toolchain version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Import-path prefix that selects a standard library module.
+pub const IMPORT_PREFIX: &str = "std/";
+
+/// A standard library module embedded in the toolchain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StdModule {
+    /// Module name, as written after `std/` in an import.
+    pub name: &'static str,
+    /// NX source of the module.
+    pub source: &'static str,
+}
+
+impl StdModule {
+    /// Returns the path used to import this module, such as `std/math`.
+    pub fn import_path(&self) -> String {
+        format!("{}{}", IMPORT_PREFIX, self.name)
+    }
+
+    /// Returns the file name reported in diagnostics for this module, such as `math.nx`.
+    pub fn file_name(&self) -> String {
+        format!("{}.nx", self.name)
+    }
+}
+
+/// All standard library modules, sorted by name.
+pub const MODULES: &[StdModule] = &[
+    StdModule {
+        name: "collections",
+        source: include_str!("../std/collections.nx"),
+    },
+    StdModule {
+        name: "dates",
+        source: include_str!("../std/dates.nx"),
+    },
+    StdModule {
+        name: "math",
+        source: include_str!("../std/math.nx"),
+    },
+    StdModule {
+        name: "strings",
+        source: include_str!("../std/strings.nx"),
+    },
+];
+
+/// Returns the standard library module called `name`.
+pub fn module(name: &str) -> Option<&'static StdModule> {
+    MODULES.iter().find(|module| module.name == name)
+}
+
+/// Returns the module selected by an import path such as `std/math`, or `None` when the path does
+/// not start with `std/` or names no standard library module.
+pub fn module_for_import(library_path: &str) -> Option<&'static StdModule> {
+    library_path
+        .strip_prefix(IMPORT_PREFIX)
+        .and_then(|name| module(name.trim_end_matches('/')))
+}
+
+/// Returns true when `library_path` targets the standard library namespace, whether or not the
+/// named module exists.
+pub fn is_std_import(library_path: &str) -> bool {
+    library_path.starts_with(IMPORT_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modules_are_sorted_and_unique() {
+        let names = MODULES.iter().map(|module| module.name).collect::<Vec<_>>();
+        let mut sorted = names.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(names, sorted);
+    }
+
+    #[test]
+    fn test_every_module_type_checks() {
+        for module in MODULES {
+            let result = nx_types::check_str(module.source, &module.file_name());
+            assert!(
+                result.is_ok(),
+                "std/{} has errors: {:?}",
+                module.name,
+                result
+                    .errors()
+                    .iter()
+                    .map(|error| error.message())
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_module_for_import() {
+        assert_eq!(module_for_import("std/math").unwrap().name, "math");
+        assert!(module_for_import("std/missing").is_none());
+        assert!(module_for_import("./std/math").is_none());
+        assert!(is_std_import("std/missing"));
+        assert_eq!(module("dates").unwrap().import_path(), "std/dates");
+    }
+}
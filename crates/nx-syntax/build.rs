@@ -8,9 +8,19 @@ fn main() {
     println!("cargo:rerun-if-changed={}/parser.c", src_dir.display());
     println!("cargo:rerun-if-changed={}/scanner.c", src_dir.display());
 
+    let grammar = std::fs::read("grammar.js").expect("grammar.js should be readable");
+    println!("cargo:rustc-env=NX_GRAMMAR_HASH={:016x}", fnv1a(&grammar));
+
     cc::Build::new()
         .include(&src_dir)
         .file(src_dir.join("parser.c"))
         .file(src_dir.join("scanner.c"))
         .compile("tree-sitter-nx");
 }
+
+/// 64-bit FNV-1a, stable across Rust releases unlike the standard library's hasher.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
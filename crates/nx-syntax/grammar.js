@@ -68,6 +68,7 @@ module.exports = grammar({
     // ===== Module Definition =====
     module_definition: $ => seq(
       repeat($.import_statement),
+      optional(field('meta', $.meta_block)),
       repeat(choice(
         $.record_definition,
         $.action_definition,
@@ -78,7 +79,7 @@ module.exports = grammar({
         $.function_definition,
         $.component_definition,
       )),
-      optional($.element),
+      repeat($.element),
     ),
 
     // ===== Imports =====
@@ -143,11 +144,33 @@ module.exports = grammar({
         field('base', $.qualified_name),
       )),
       '=',
+      choice(
+        $._record_fields,
+        field('composition', $.record_composition),
+      ),
+    ),
+
+    _record_fields: $ => seq(
       '{',
       repeat(field('properties', $.property_definition)),
       '}',
     ),
 
+    // `User & Audited & { role: string }` copies the fields of the named records into the one
+    // being defined. The braces are optional and come last.
+    record_composition: $ => seq(
+      $.qualified_name,
+      $._record_composition_tail,
+    ),
+
+    _record_composition_tail: $ => seq(
+      '&',
+      choice(
+        $._record_fields,
+        seq($.qualified_name, optional($._record_composition_tail)),
+      ),
+    ),
+
     action_definition: $ => seq(
       optional(field('visibility', $.visibility_modifier)),
       optional(field('abstract', 'abstract')),
@@ -215,7 +238,10 @@ module.exports = grammar({
     value_definition: $ => seq(
       optional(field('visibility', $.visibility_modifier)),
       'let',
-      field('name', $.identifier),
+      choice(
+        field('name', $.identifier),
+        field('pattern', choice($.array_binding_pattern, $.record_binding_pattern)),
+      ),
       optional(seq(
         ':',
         field('type', $.type),
@@ -224,6 +250,31 @@ module.exports = grammar({
       field('value', $.rhs_expression),
     ),
 
+    // `let [first, second, ...rest] = ...` binds leading elements and, optionally, the remainder.
+    array_binding_pattern: $ => seq(
+      '[',
+      choice(
+        seq(
+          field('element', $.identifier),
+          repeat(seq(',', field('element', $.identifier))),
+          optional(seq(',', field('rest', $.rest_binding))),
+        ),
+        field('rest', $.rest_binding),
+      ),
+      ']',
+    ),
+
+    rest_binding: $ => seq('...', field('name', $.identifier)),
+
+    // `let { name, age } = ...` binds record fields to values of the same name.
+    record_binding_pattern: $ => seq(
+      '{',
+      field('field', $.identifier),
+      repeat(seq(',', field('field', $.identifier))),
+      optional(','),
+      '}',
+    ),
+
     type: $ => seq(
       choice(
         $.primitive_type,
@@ -300,11 +351,20 @@ module.exports = grammar({
         field('base', $.qualified_name),
       )),
       repeat(field('properties', alias($._component_property_definition, $.property_definition))),
+      optional(field('rest', $.rest_property_definition)),
       optional(field('emits', $.emits_group)),
       '/',
       '>',
     ),
 
+    // `...rest:props` captures props the component does not declare so they can be forwarded.
+    rest_property_definition: $ => seq(
+      '...',
+      field('name', $.identifier),
+      ':',
+      'props',
+    ),
+
     // Components require at least one emitted action when the emits block is
     // present, but each action payload may be empty.
     emits_group: $ => seq(
@@ -385,6 +445,7 @@ module.exports = grammar({
         field('name', $.markup_identifier),
         ':',
         field('type', $.type),
+        repeat(field('constraints', $.field_constraint)),
         optional(seq(
           '=',
           field('default', $.rhs_expression),
@@ -395,6 +456,7 @@ module.exports = grammar({
         field('name', $.markup_identifier),
         ':',
         field('type', $.type),
+        repeat(field('constraints', $.field_constraint)),
         optional(seq(
           '=',
           field('default', $.rhs_expression),
@@ -402,10 +464,28 @@ module.exports = grammar({
       ),
     ),
 
+    // `@min(0)`: a validation constraint checked whenever the record is constructed.
+    field_constraint: $ => seq(
+      '@',
+      field('name', $.identifier),
+      '(',
+      optional(seq(
+        field('arguments', $.constraint_argument),
+        repeat(seq(',', field('arguments', $.constraint_argument))),
+      )),
+      ')',
+    ),
+
+    constraint_argument: $ => seq(
+      optional(field('sign', '-')),
+      field('value', choice($.int_literal, $.real_literal)),
+    ),
+
     // ===== Expressions =====
     rhs_expression: $ => choice(
       $.element,
       $.literal,
+      $.sequence_expression,
       $.values_braced_expression,
     ),
 
@@ -429,9 +509,12 @@ module.exports = grammar({
       $.element,
       $.value_if_expression,
       $.value_for_expression,
+      $.provide_expression,
+      $.use_expression,
       $.call_expression,
       $.member_access_expression,
       $.literal,
+      $.sequence_expression,
       $.identifier_expression,
       $.unit_literal,
       $.parenthesized_expression,
@@ -448,6 +531,17 @@ module.exports = grammar({
 
     unit_literal: $ => seq('(', ')'),
 
+    // `[]` or `[a, b]`: a comma-separated array literal.
+    sequence_expression: $ => seq(
+      '[',
+      optional(seq(
+        $.value_expression,
+        repeat(seq(',', $.value_expression)),
+        optional(','),
+      )),
+      ']',
+    ),
+
     parenthesized_expression: $ => seq(
       '(',
       $.value_expression,
@@ -515,6 +609,7 @@ module.exports = grammar({
       $.int_literal,
       $.real_literal,
       $.hex_literal,
+      $.binary_literal,
       $.bool_literal,
       $.null_literal,
     ),
@@ -528,9 +623,16 @@ module.exports = grammar({
       '"'
     )),
 
-    int_literal: $ => /[0-9]+/,
-    real_literal: $ => /[0-9]+\.[0-9]+([eE][+-]?[0-9]+)?/,
-    hex_literal: $ => /0[xX][0-9a-fA-F]+/,
+    // Numeric literals allow a single `_` between digits, as in `1_000_000`.
+    int_literal: $ => /[0-9](_?[0-9])*/,
+    real_literal: $ => token(choice(
+      /[0-9](_?[0-9])*\.[0-9](_?[0-9])*([eE][+-]?[0-9](_?[0-9])*)?/,
+      /[0-9](_?[0-9])*[eE][+-]?[0-9](_?[0-9])*/,
+      // Hex floats: `0x1.8p3` is 1.5 * 2^3.
+      /0[xX][0-9a-fA-F](_?[0-9a-fA-F])*(\.[0-9a-fA-F](_?[0-9a-fA-F])*)?[pP][+-]?[0-9](_?[0-9])*/,
+    )),
+    hex_literal: $ => /0[xX][0-9a-fA-F](_?[0-9a-fA-F])*/,
+    binary_literal: $ => /0[bB][01](_?[01])*/,
     bool_literal: $ => choice('true', 'false'),
     null_literal: $ => 'null',
 
@@ -612,6 +714,22 @@ module.exports = grammar({
       field('body', $.values_braced_expression),
     ),
 
+    // ===== Context Expressions =====
+    // `provide theme = darkTheme { ... }` overrides the top-level value `theme` for everything
+    // evaluated inside the body; `use theme` reads the nearest provided value.
+    provide_expression: $ => seq(
+      'provide',
+      field('key', $.identifier),
+      '=',
+      field('value', $.value_expression),
+      field('body', $.values_braced_expression),
+    ),
+
+    use_expression: $ => seq(
+      'use',
+      field('key', $.identifier),
+    ),
+
     // ===== Elements Expression =====
     // Keep bare if/for/else prefixes available for control-flow items in mixed content. Text
     // that would otherwise collide with those prefixes can still be written via braces.
@@ -726,7 +844,7 @@ module.exports = grammar({
             seq('/', '>'),  // self-closing
             seq(
               '>',
-              field('content', $.mixed_content),
+              field('content', optional($.mixed_content)),  // `<div></div>` is an empty element
               '<',
               '/',
               field('close_name', $.element_name),
@@ -790,9 +908,27 @@ module.exports = grammar({
     // ===== Property Lists =====
     property_list: $ => repeat1(choice(
       $.property_value,
+      $.property_spread,
+      $.property_shorthand,
       $.property_list_if_expression,
     )),
 
+    property_spread: $ => seq(
+      '{',
+      '...',
+      field('value', $.value_expression),
+      '}',
+    ),
+
+    // `{name, age}` passes locals as the properties of the same names.
+    property_shorthand: $ => seq(
+      '{',
+      field('name', $.identifier),
+      repeat(seq(',', field('name', $.identifier))),
+      optional(','),
+      '}',
+    ),
+
     property_value: $ => seq(
       field('name', $.qualified_markup_name),
       '=',
@@ -922,6 +1058,37 @@ module.exports = grammar({
       $.qualified_name,
     ),
 
+    // ===== Module Metadata =====
+    meta_block: $ => seq(
+      'meta',
+      optional(field('schema', $.qualified_name)),
+      '{',
+      optional(seq(
+        $.meta_entry,
+        repeat(seq(optional(','), $.meta_entry)),
+        optional(','),
+      )),
+      '}',
+    ),
+
+    meta_entry: $ => seq(
+      field('name', $.identifier),
+      ':',
+      field('value', $._meta_value),
+    ),
+
+    _meta_value: $ => choice($.literal, $.meta_array),
+
+    meta_array: $ => seq(
+      '[',
+      optional(seq(
+        $._meta_value,
+        repeat(seq(',', $._meta_value)),
+        optional(','),
+      )),
+      ']',
+    ),
+
     // ===== Names =====
     qualified_name: $ => seq(
       $.identifier,
@@ -22,6 +22,9 @@
   "for"
   "in"
   "is"
+  "provide"
+  "use"
+  "meta"
   "raw"
 ] @keyword
 
@@ -42,6 +45,10 @@
   base: (qualified_name
     (identifier) @type))
 
+(record_composition
+  (qualified_name
+    (identifier) @type))
+
 (action_definition
   name: (identifier) @type)
 
@@ -95,6 +102,11 @@
   (property_definition
     name: (markup_identifier) @property))
 
+;; Field constraints
+(field_constraint
+  "@" @attribute
+  name: (identifier) @attribute)
+
 ;; Operators
 [
   "+"
@@ -110,6 +122,7 @@
   ">="
   "&&"
   "||"
+  "&"
   "!"
   "?"
   "=>"
@@ -141,6 +154,7 @@
 (int_literal) @number
 (real_literal) @number
 (hex_literal) @number
+(binary_literal) @number
 (bool_literal) @constant.builtin
 (null_literal) @constant.builtin
 
@@ -156,6 +170,10 @@
   (property_definition
     name: (markup_identifier) @property))
 
+(record_composition
+  (property_definition
+    name: (markup_identifier) @property))
+
 (action_definition
   (property_definition
     name: (markup_identifier) @property))
@@ -243,10 +243,30 @@ impl<'tree> RecordDef<'tree> {
         self.syntax.child_by_field("base")
     }
 
-    /// Returns an iterator over record property definitions.
+    /// Returns the `User & Audited & { ... }` composition node when the record is composed from
+    /// other records.
+    pub fn composition(&self) -> Option<SyntaxNode<'tree>> {
+        self.syntax.child_by_field("composition")
+    }
+
+    /// Returns an iterator over the names of the records this record is composed from.
+    pub fn includes(&self) -> impl Iterator<Item = SyntaxNode<'tree>> + 'tree {
+        self.composition()
+            .into_iter()
+            .flat_map(|composition| composition.children())
+            .filter(|node| node.kind() == SyntaxKind::QUALIFIED_NAME)
+    }
+
+    /// Returns an iterator over record property definitions, including those declared in the
+    /// braces of a composition.
     pub fn properties(&self) -> impl Iterator<Item = SyntaxNode<'tree>> + 'tree {
         self.syntax
             .children()
+            .chain(
+                self.composition()
+                    .into_iter()
+                    .flat_map(|composition| composition.children()),
+            )
             .filter(move |node| node.kind() == SyntaxKind::PROPERTY_DEFINITION)
     }
 }
@@ -375,6 +395,25 @@ mod tests {
         assert_eq!(props.len(), 2);
     }
 
+    #[test]
+    fn test_composed_record_def_exposes_includes_and_properties() {
+        let mut parser = parser();
+        let source = "type Admin = User & audit.Audited & { role: string }\ntype Pair = A & B";
+        let tree = parser.parse(source, None).unwrap();
+        let root = SyntaxNode::new(tree.root_node(), source);
+
+        let records: Vec<_> = root.children().filter_map(RecordDef::cast).collect();
+        assert_eq!(records.len(), 2);
+        let includes: Vec<_> = records[0].includes().map(|name| name.text()).collect();
+        assert_eq!(includes, ["User", "audit.Audited"]);
+        let props: Vec<_> = records[0].properties().collect();
+        assert_eq!(props.len(), 1);
+
+        let includes: Vec<_> = records[1].includes().map(|name| name.text()).collect();
+        assert_eq!(includes, ["A", "B"]);
+        assert_eq!(records[1].properties().count(), 0);
+    }
+
     #[test]
     fn test_action_def_casts_as_record_def() {
         let mut parser = parser();
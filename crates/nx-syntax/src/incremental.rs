@@ -0,0 +1,305 @@
+//! Incremental reparsing of a [`SyntaxTree`] after a text edit.
+//!
+//! The edit is described to tree-sitter, which reuses the unchanged parts of the old tree while
+//! reparsing. Validation then reruns only for the top-level nodes that overlap a changed range
+//! or contain errors; every other node keeps its diagnostics from the previous parse, moved by
+//! the length difference of the edit.
+
+use crate::{parse_tree, validate_source_size, ParseResult, SyntaxTree};
+use nx_diagnostics::{Diagnostic, Label, TextEdit};
+use std::ops::Range;
+use tree_sitter::{InputEdit, Point};
+
+impl SyntaxTree {
+    /// Applies `edit` to the source of this tree and reparses the region it affects.
+    ///
+    /// The edit's range is a byte range of the current source; its `file` is not consulted. On
+    /// success the tree is updated in place and the returned result holds a copy of it, with the
+    /// same diagnostics [`parse_str_with_options`](crate::parse_str_with_options) reports for the
+    /// edited source under the original file name and options.
+    ///
+    /// An edit whose range falls outside the source or inside a UTF-8 character, and an edit that
+    /// makes the source exceed the parse limits, produces a result with a single error diagnostic
+    /// and no tree, and leaves this tree unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nx_diagnostics::TextEdit;
+    /// use nx_syntax::parse_str;
+    /// use text_size::TextRange;
+    ///
+    /// let mut tree = parse_str("let x = 1", "example.nx").tree.unwrap();
+    /// let result = tree.apply_edit(TextEdit::replace(
+    ///     "example.nx",
+    ///     TextRange::new(8.into(), 9.into()),
+    ///     "42",
+    /// ));
+    ///
+    /// assert!(result.is_ok());
+    /// assert_eq!(tree.source(), "let x = 42");
+    /// ```
+    pub fn apply_edit(&mut self, edit: TextEdit) -> ParseResult {
+        let start = usize::from(edit.range.start());
+        let old_end = usize::from(edit.range.end());
+        if old_end > self.source.len()
+            || !self.source.is_char_boundary(start)
+            || !self.source.is_char_boundary(old_end)
+        {
+            return self.failed(invalid_edit(&edit, &self.file_name, self.source.len()));
+        }
+
+        let mut source =
+            String::with_capacity(self.source.len() - (old_end - start) + edit.replacement.len());
+        source.push_str(&self.source[..start]);
+        source.push_str(&edit.replacement);
+        source.push_str(&self.source[old_end..]);
+        let new_end = start + edit.replacement.len();
+
+        if let Some(diagnostic) = validate_source_size(
+            source.len(),
+            self.options.limits.effective_max_source_bytes(),
+            &self.file_name,
+        ) {
+            return self.failed(diagnostic);
+        }
+
+        let mut old_tree = self.tree.clone();
+        old_tree.edit(&InputEdit {
+            start_byte: start,
+            old_end_byte: old_end,
+            new_end_byte: new_end,
+            start_position: point_at(&self.source, start),
+            old_end_position: point_at(&self.source, old_end),
+            new_end_position: point_at(&source, new_end),
+        });
+        let tree = match parse_tree(
+            &source,
+            Some(&old_tree),
+            &self.options.limits,
+            &self.file_name,
+        ) {
+            Ok(tree) => tree,
+            Err(diagnostic) => return self.failed(*diagnostic),
+        };
+
+        // Top-level nodes that overlap or touch one of these ranges are validated again.
+        let mut changed = old_tree
+            .changed_ranges(&tree)
+            .map(|range| range.start_byte..range.end_byte)
+            .collect::<Vec<_>>();
+        changed.push(start..new_end);
+
+        let delta = edit.replacement.len() as isize - (old_end - start) as isize;
+        let previous = &self.diagnostics.items;
+        let reuse = |_: usize, node: tree_sitter::Node| {
+            let range = node.byte_range();
+            if node.has_error() || changed.iter().any(|changed| touches(&range, changed)) {
+                return None;
+            }
+            let old_range = if range.start >= new_end {
+                range.start.checked_add_signed(-delta)?..range.end.checked_add_signed(-delta)?
+            } else {
+                range.clone()
+            };
+            let index = previous
+                .binary_search_by_key(&old_range.start, |item| item.range.start)
+                .ok()?;
+            let item = &previous[index];
+            if item.range != old_range {
+                return None;
+            }
+            Some(if range.start >= new_end {
+                item.shifted(delta)
+            } else {
+                item.clone()
+            })
+        };
+
+        *self = SyntaxTree::new(
+            tree,
+            source,
+            self.source_id,
+            self.file_name.clone(),
+            self.options,
+            reuse,
+        );
+        ParseResult {
+            errors: self.diagnostics.to_vec(),
+            tree: Some(self.clone()),
+            source_id: self.source_id,
+        }
+    }
+
+    fn failed(&self, diagnostic: Diagnostic) -> ParseResult {
+        ParseResult {
+            tree: None,
+            errors: vec![diagnostic],
+            source_id: self.source_id,
+        }
+    }
+}
+
+/// Returns true when `a` and `b` overlap or share an endpoint.
+fn touches(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
+
+/// Returns the tree-sitter position of byte `offset` in `text`.
+fn point_at(text: &str, offset: usize) -> Point {
+    let before = &text[..offset];
+    match before.rfind('\n') {
+        Some(newline) => Point::new(before.matches('\n').count(), offset - newline - 1),
+        None => Point::new(0, offset),
+    }
+}
+
+fn invalid_edit(edit: &TextEdit, file_name: &str, source_len: usize) -> Diagnostic {
+    Diagnostic::error("invalid-edit")
+        .with_message(format!(
+            "Edit range {}..{} is not a valid range of the {source_len}-byte source",
+            u32::from(edit.range.start()),
+            u32::from(edit.range.end())
+        ))
+        .with_label(Label::primary(file_name, edit.range))
+        .with_help("Edit ranges must lie within the source and on UTF-8 character boundaries.")
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_str, parse_str_with_options, LanguageVersion, ParseOptions, ParseResult};
+    use nx_diagnostics::TextEdit;
+    use text_size::{TextRange, TextSize};
+
+    fn edit_at(source: &str, find: &str, replacement: &str) -> TextEdit {
+        let start = source.find(find).expect("text to edit is in the source");
+        TextEdit::replace(
+            "edit.nx",
+            TextRange::at(
+                TextSize::from(start as u32),
+                TextSize::from(find.len() as u32),
+            ),
+            replacement,
+        )
+    }
+
+    fn apply(source: &str, edits: &[(&str, &str)], options: ParseOptions) {
+        let mut tree = parse_str_with_options(source, "edit.nx", options)
+            .tree
+            .expect("source parses");
+        let mut expected_source = source.to_string();
+
+        for (find, replacement) in edits {
+            let edit = edit_at(&expected_source, find, replacement);
+            let start = usize::from(edit.range.start());
+            expected_source.replace_range(start..usize::from(edit.range.end()), replacement);
+
+            let result = tree.apply_edit(edit);
+            let expected = parse_str_with_options(&expected_source, "edit.nx", options);
+            assert_same_parse(&result, &expected, &expected_source);
+            assert_eq!(tree.source(), expected_source);
+        }
+    }
+
+    fn assert_same_parse(actual: &ParseResult, expected: &ParseResult, source: &str) {
+        assert_eq!(
+            actual.root().map(|root| root.raw().to_sexp()),
+            expected.root().map(|root| root.raw().to_sexp()),
+            "trees differ for {source:?}"
+        );
+        assert_eq!(
+            actual.errors, expected.errors,
+            "diagnostics differ for {source:?}"
+        );
+        assert_eq!(actual.source_id, expected.source_id);
+    }
+
+    #[test]
+    fn test_apply_edit_matches_full_parse() {
+        let source = "type User = { name: string? ? }\n\
+                      let greet(name: string) = { \"Hello\" }\n\
+                      <div><span></span></div>\n\
+                      let root = 1\n";
+        apply(
+            source,
+            &[
+                ("\"Hello\"", "name"),
+                ("let root", "let main"),
+                ("<div>", "<div class=\"x\">"),
+                ("name: string? ?", "name: string age: int name: int"),
+                ("</div>", "</section>"),
+                ("</section>", "</div>"),
+            ],
+            ParseOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_apply_edit_recovers_from_syntax_errors() {
+        let source = "let a = 1\nlet b(x: int) = { x + 1 }\nlet c = 3\n";
+        apply(
+            source,
+            &[
+                ("{ x + 1 }", "{ x + "),
+                ("let c", "let c = = "),
+                ("x + ", "x + 2 }"),
+                ("let c = = ", "let c"),
+                ("", "let z = 0\n"),
+            ],
+            ParseOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_apply_edit_keeps_language_version() {
+        let source = "let a = 1\ntype Shape = | circle\n";
+        apply(
+            source,
+            &[("let a = 1", "let a = 10"), ("circle", "circle | square")],
+            ParseOptions::for_version(LanguageVersion::V1),
+        );
+    }
+
+    #[test]
+    fn test_apply_edit_handles_multibyte_text_and_line_breaks() {
+        let source = "let a = \"héllo\"\nlet b = \"wörld\"\n";
+        apply(
+            source,
+            &[("héllo", "ünï\ncode"), ("wörld", "é"), ("\n", "\r\n")],
+            ParseOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_apply_edit_rejects_invalid_ranges() {
+        let source = "let a = \"é\"";
+        let mut tree = parse_str(source, "edit.nx").tree.unwrap();
+
+        for range in [
+            TextRange::new(TextSize::from(0), TextSize::from(100)),
+            TextRange::new(TextSize::from(10), TextSize::from(10)),
+        ] {
+            let result = tree.apply_edit(TextEdit::replace("edit.nx", range, "x"));
+            assert!(result.tree.is_none());
+            assert_eq!(result.errors.len(), 1);
+            assert_eq!(result.errors[0].code(), Some("invalid-edit"));
+            assert_eq!(tree.source(), source);
+        }
+    }
+
+    #[test]
+    fn test_apply_edit_enforces_source_limits() {
+        let source = "let a = 1";
+        let mut options = ParseOptions::default();
+        options.limits.max_source_bytes = 12;
+        let mut tree = parse_str_with_options(source, "edit.nx", options)
+            .tree
+            .unwrap();
+
+        let result = tree.apply_edit(edit_at(source, "1", "12345"));
+        assert!(result.tree.is_none());
+        assert_eq!(result.errors[0].code(), Some("source-too-large"));
+        assert_eq!(tree.source(), source);
+    }
+}
@@ -0,0 +1,245 @@
+//! Lexical layout scanning for on-type formatting.
+//!
+//! The scanner follows brackets, tags, strings and comments in the source text alone, so it
+//! works while the source is incomplete and does not parse.
+
+use nx_diagnostics::TextEdit;
+use std::ops::Range;
+use text_size::{TextRange, TextSize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BlockKind {
+    Brace,
+    Bracket,
+    Paren,
+    Element,
+}
+
+impl BlockKind {
+    pub(crate) fn closer(self) -> &'static str {
+        match self {
+            BlockKind::Brace => "}",
+            BlockKind::Bracket => "]",
+            BlockKind::Paren => ")",
+            BlockKind::Element => "</",
+        }
+    }
+
+    /// Length of the opener at `at`: the bracket, or the whole opening tag of an element.
+    pub(crate) fn opener_len(self, source: &str, at: usize) -> usize {
+        match self {
+            BlockKind::Element => scan_tag(source, at).map_or(1, |(end, _)| end - at),
+            _ => 1,
+        }
+    }
+}
+
+/// A bracket or element that is still open, with the byte offset of its opener.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OpenBlock {
+    pub(crate) kind: BlockKind,
+    pub(crate) at: usize,
+}
+
+/// Scans a text from its start, tracking the brackets and elements that are open.
+///
+/// Strings and comments are skipped. Element content is text, so quotes in it do not start
+/// strings, while braces in it open embedded expressions.
+pub(crate) struct BlockScanner<'a> {
+    text: &'a str,
+    index: usize,
+    open: Vec<OpenBlock>,
+}
+
+impl<'a> BlockScanner<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            index: 0,
+            open: Vec::new(),
+        }
+    }
+
+    /// Returns true when the scanner is in element content.
+    pub(crate) fn in_markup(&self) -> bool {
+        self.open
+            .last()
+            .is_some_and(|block| block.kind == BlockKind::Element)
+    }
+
+    /// Scans forward to `end`.
+    ///
+    /// A string, comment or tag that starts before `end` is skipped whole, so the scanner can
+    /// stop past `end`.
+    pub(crate) fn scan_to(&mut self, end: usize) {
+        let text = self.text;
+        let bytes = text.as_bytes();
+        let end = end.min(bytes.len());
+
+        while self.index < end {
+            let index = self.index;
+            let in_markup = self.in_markup();
+            // Only ASCII bytes are matched, so `index` is a char boundary wherever `text` is
+            // sliced.
+            let rest = &bytes[index..];
+            let byte = bytes[index];
+
+            self.index = if rest.starts_with(b"<!--") {
+                text[index..]
+                    .find("-->")
+                    .map_or(bytes.len(), |close| index + close + 3)
+            } else if !in_markup && rest.starts_with(b"//") {
+                text[index..]
+                    .find('\n')
+                    .map_or(bytes.len(), |newline| index + newline)
+            } else if !in_markup && rest.starts_with(b"/*") {
+                text[index..]
+                    .find("*/")
+                    .map_or(bytes.len(), |close| index + close + 2)
+            } else if !in_markup && byte == b'"' {
+                skip_string(text, index)
+            } else if rest.starts_with(b"</") {
+                if let Some(close) = self
+                    .open
+                    .iter()
+                    .rposition(|block| block.kind == BlockKind::Element)
+                {
+                    self.open.truncate(close);
+                }
+                text[index..]
+                    .find('>')
+                    .map_or(bytes.len(), |close| index + close + 1)
+            } else if byte == b'<' && bytes.get(index + 1).is_some_and(u8::is_ascii_alphabetic) {
+                match scan_tag(text, index) {
+                    Some((tag_end, self_closing)) => {
+                        if !self_closing {
+                            self.open.push(OpenBlock {
+                                kind: BlockKind::Element,
+                                at: index,
+                            });
+                        }
+                        tag_end
+                    }
+                    // The tag is still being typed.
+                    None => bytes.len(),
+                }
+            } else {
+                let kind = match byte {
+                    b'{' => Some(BlockKind::Brace),
+                    b'[' if !in_markup => Some(BlockKind::Bracket),
+                    b'(' if !in_markup => Some(BlockKind::Paren),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    self.open.push(OpenBlock { kind, at: index });
+                } else if !in_markup
+                    && self
+                        .open
+                        .last()
+                        .is_some_and(|block| block.kind.closer().as_bytes() == [byte])
+                {
+                    self.open.pop();
+                }
+                index + 1
+            };
+        }
+    }
+}
+
+/// Returns the brackets and elements that are open at `end`, outermost first.
+///
+/// Only `source[..end]` is scanned, so a string, comment or tag that `end` falls inside counts
+/// as unterminated.
+pub(crate) fn open_blocks(source: &str, end: usize) -> Vec<OpenBlock> {
+    let mut scanner = BlockScanner::new(&source[..end]);
+    scanner.scan_to(end);
+    scanner.open
+}
+
+/// Scans the opening tag starting at `start`, returning where it ends and whether it is
+/// self-closing, or `None` when the source ends inside it.
+pub(crate) fn scan_tag(text: &str, start: usize) -> Option<(usize, bool)> {
+    let bytes = text.as_bytes();
+    let mut index = start + 1;
+    let mut depth = 0usize;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' => {
+                index = skip_string(text, index);
+                continue;
+            }
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            b'/' if depth == 0 && bytes.get(index + 1) == Some(&b'>') => {
+                return Some((index + 2, true))
+            }
+            b'>' if depth == 0 => return Some((index + 1, false)),
+            _ => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Returns the offset just past the string literal starting at `start`.
+pub(crate) fn skip_string(text: &str, start: usize) -> usize {
+    let bytes = text.as_bytes();
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'"' | b'\n' => return index + 1,
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
+pub(crate) fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset]
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1)
+}
+
+/// Returns the indentation of the line containing `offset`.
+pub(crate) fn indentation(source: &str, offset: usize) -> &str {
+    let start = line_start(source, offset);
+    &source[start..start + leading_whitespace(&source[start..])]
+}
+
+pub(crate) fn leading_whitespace(text: &str) -> usize {
+    text.len() - text.trim_start_matches([' ', '\t']).len()
+}
+
+pub(crate) fn is_blank(text: &str) -> bool {
+    text.chars().all(|c| c == ' ' || c == '\t')
+}
+
+/// Returns the indentation unit of `source`: a tab when lines are indented with tabs, otherwise
+/// the smallest indentation in spaces, or two spaces for unindented source.
+pub(crate) fn indent_unit(source: &str) -> String {
+    let mut smallest: Option<usize> = None;
+    for line in source.lines() {
+        let indent = leading_whitespace(line);
+        if indent == 0 || indent == line.len() {
+            continue;
+        }
+        if line.starts_with('\t') {
+            return "\t".to_string();
+        }
+        smallest = Some(smallest.map_or(indent, |smallest| smallest.min(indent)));
+    }
+    " ".repeat(smallest.unwrap_or(2).min(8))
+}
+
+/// Returns an edit of an unnamed source that replaces `range` with `replacement`.
+pub(crate) fn edit(range: Range<usize>, replacement: String) -> TextEdit {
+    TextEdit {
+        file: String::new(),
+        range: TextRange::new(
+            TextSize::from(range.start as u32),
+            TextSize::from(range.end as u32),
+        ),
+        replacement,
+    }
+}
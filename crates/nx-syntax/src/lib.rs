@@ -4,29 +4,51 @@
 //! with typed wrappers and a high-level API for parsing files.
 
 mod ast;
+mod incremental;
+mod layout;
+mod limits;
+mod metadata;
+mod on_type;
+mod ranges;
 mod syntax_kind;
 mod syntax_node;
 mod validation;
+mod version;
 
 pub use ast::{
     AstNode, ComponentDef, Element, FunctionDef, RecordDef, SyntaxNodeExt, TypeDef, UnionDef,
 };
+pub use limits::ParseLimits;
+pub use metadata::{language_metadata, LanguageMetadata, TokenPair};
+pub use on_type::on_type_format;
+pub use ranges::{folding_ranges, selection_ranges, FoldingRange, FoldingRangeKind};
 pub use syntax_kind::{syntax_kind_from_str, SyntaxKind};
 pub use syntax_node::SyntaxNode;
-pub use validation::validate;
+pub use validation::{validate, validate_language_version};
+pub use version::{LanguageVersion, ParseOptions, SyntaxFeature};
 
-use nx_diagnostics::{Diagnostic, Severity};
+use nx_diagnostics::{normalize_path, Diagnostic, Severity};
 use std::fs;
 use std::io;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
 use text_size::TextRange;
 use tree_sitter::{Language, Parser, Tree};
+use validation::{ItemDiagnostics, ModuleDiagnostics};
 
 extern "C" {
     fn tree_sitter_nx() -> Language;
 }
 
+/// Version of the nx-syntax crate, identical to the toolchain version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Hash of `grammar.js`, identifying the grammar the parser was generated from.
+///
+/// Two builds with the same hash parse identically, even when the crate version differs.
+pub const GRAMMAR_HASH: &str = env!("NX_GRAMMAR_HASH");
+
 /// Maximum supported NX source size in bytes.
 ///
 /// The limit is kept below 2 GiB so byte offsets and 1-based line/column positions remain
@@ -63,21 +85,48 @@ impl SourceId {
     }
 }
 
-/// An immutable syntax tree from tree-sitter.
+/// A syntax tree from tree-sitter, together with the source it was parsed from.
+///
+/// A tree only changes through [`SyntaxTree::apply_edit`], which reparses it incrementally, and
+/// cloning one is cheap. Syntax trees are `Send + Sync`, so a parsed file can be moved to a worker thread or shared
+/// between threads behind an `Arc`.
+#[derive(Clone)]
 pub struct SyntaxTree {
     tree: Tree,
     source: Arc<String>,
     source_id: SourceId,
+    /// The normalized file name and options the tree was parsed with, kept for reparsing.
+    file_name: Arc<str>,
+    options: ParseOptions,
+    diagnostics: Arc<ModuleDiagnostics>,
 }
 
 impl SyntaxTree {
-    /// Creates a new syntax tree.
-    fn new(tree: Tree, source: String, source_id: SourceId) -> Self {
-        Self {
+    /// Creates a new syntax tree and validates it, reusing the diagnostics `reuse` returns for
+    /// top-level nodes.
+    fn new(
+        tree: Tree,
+        source: String,
+        source_id: SourceId,
+        file_name: Arc<str>,
+        options: ParseOptions,
+        reuse: impl FnMut(usize, tree_sitter::Node) -> Option<ItemDiagnostics>,
+    ) -> Self {
+        let mut syntax_tree = Self {
             tree,
             source: Arc::new(source),
             source_id,
-        }
+            file_name,
+            options,
+            diagnostics: Arc::default(),
+        };
+        syntax_tree.diagnostics = Arc::new(validation::validate_module(
+            &syntax_tree,
+            &syntax_tree.file_name,
+            &syntax_tree.options,
+            reuse,
+        ));
+        syntax_tree
     }
 
     /// Returns the root syntax node.
@@ -112,6 +161,14 @@ impl SyntaxTree {
     }
 }
 
+// tree-sitter marks `Tree` as `Send + Sync` since it is never mutated after parsing. Keep the
+// wrappers that hand trees to callers thread-safe as well.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SyntaxTree>();
+    assert_send_sync::<ParseResult>();
+};
+
 /// Error types for parsing operations.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -159,6 +216,8 @@ impl ParseResult {
 
 /// Parses NX source code into a syntax tree.
 ///
+/// Uses [`ParseOptions::default`], which targets the latest language version.
+///
 /// # Examples
 ///
 /// ```
@@ -170,7 +229,38 @@ impl ParseResult {
 /// assert!(result.tree.is_some());
 /// ```
 pub fn parse_str(source: &str, file_name: &str) -> ParseResult {
-    if let Some(diagnostic) = validate_source_size(source.len(), file_name) {
+    parse_str_with_options(source, file_name, ParseOptions::default())
+}
+
+/// Parses NX source code written against a specific language version.
+///
+/// `file_name` is the path of the source, normalized with [`normalize_path`] before it is used in
+/// diagnostics and to derive the [`SourceId`], so `src\ui\main.nx` and `src/ui/main.nx` name the
+/// same file and `app/main.nx` and `lib/main.nx` stay distinct.
+///
+/// Syntax that is newer than `options.language_version`, or experimental syntax when
+/// `options.allow_experimental_syntax` is false, still parses but produces error diagnostics.
+/// Sources that exceed `options.limits`, including sources that take longer than
+/// `options.limits.max_parse_time` to parse, produce a single error diagnostic and no tree.
+///
+/// # Examples
+///
+/// ```
+/// use nx_syntax::{parse_str_with_options, LanguageVersion, ParseOptions};
+///
+/// let options = ParseOptions::for_version(LanguageVersion::V1);
+/// let result = parse_str_with_options("type Shape = | circle", "example.nx", options);
+///
+/// assert!(result.has_errors());
+/// ```
+pub fn parse_str_with_options(source: &str, file_name: &str, options: ParseOptions) -> ParseResult {
+    let file_name = normalize_path(file_name);
+    let file_name = file_name.as_str();
+    if let Some(diagnostic) = validate_source_size(
+        source.len(),
+        options.limits.effective_max_source_bytes(),
+        file_name,
+    ) {
         return ParseResult {
             tree: None,
             errors: vec![diagnostic],
@@ -189,46 +279,84 @@ pub fn parse_str(source: &str, file_name: &str) -> ParseResult {
         };
     }
 
-    let mut parser = parser();
-    let tree = parser.parse(source, None);
+    let source_id = source_id_for(file_name);
+    let tree = match parse_tree(source, None, &options.limits, file_name) {
+        Ok(tree) => tree,
+        Err(diagnostic) => {
+            return ParseResult {
+                tree: None,
+                errors: vec![*diagnostic],
+                source_id,
+            }
+        }
+    };
+
+    let syntax_tree = SyntaxTree::new(
+        tree,
+        source.to_string(),
+        source_id,
+        Arc::from(file_name),
+        options,
+        |_, _| None,
+    );
+    ParseResult {
+        errors: syntax_tree.diagnostics.to_vec(),
+        tree: Some(syntax_tree),
+        source_id,
+    }
+}
 
-    let source_id = SourceId::new(
+/// Derives the [`SourceId`] of a normalized file name.
+fn source_id_for(file_name: &str) -> SourceId {
+    SourceId::new(
         file_name
             .as_bytes()
             .iter()
             .fold(0u32, |acc, &b| acc.wrapping_mul(31).wrapping_add(b as u32)),
-    );
-
-    match tree {
-        Some(tree) => {
-            // Collect parse errors from the tree with enhanced messages
-            let mut errors = validation::collect_enhanced_errors(&tree, source, file_name);
-
-            // Create the syntax tree
-            let syntax_tree = SyntaxTree::new(tree, source.to_string(), source_id);
-
-            // Run post-parse validation (e.g., tag matching)
-            let validation_errors = validation::validate(&syntax_tree, file_name);
-            errors.extend(validation_errors);
+    )
+}
 
-            ParseResult {
-                tree: Some(syntax_tree),
-                errors,
-                source_id,
-            }
+/// Parses `source`, reusing the unchanged parts of `old_tree` when it is given, and enforces the
+/// parse time and tree shape limits.
+fn parse_tree(
+    source: &str,
+    old_tree: Option<&Tree>,
+    limits: &ParseLimits,
+    file_name: &str,
+) -> Result<Tree, Box<Diagnostic>> {
+    let mut parser = parser();
+    let tree = match limits.max_parse_time {
+        Some(limit) => {
+            let deadline = Instant::now() + limit;
+            let mut timed_out = |_: &tree_sitter::ParseState| Instant::now() >= deadline;
+            parser
+                .parse_with_options(
+                    &mut |offset, _| source.as_bytes().get(offset..).unwrap_or_default(),
+                    old_tree,
+                    Some(tree_sitter::ParseOptions::new().progress_callback(&mut timed_out)),
+                )
+                .ok_or_else(|| Box::new(limits::parse_timed_out(limit, file_name)))?
         }
-        None => ParseResult {
-            tree: None,
-            errors: vec![Diagnostic::error("parse-failed")
-                .with_message("Failed to parse source")
-                .build()],
-            source_id,
-        },
+        None => parser.parse(source, old_tree).ok_or_else(|| {
+            Box::new(
+                Diagnostic::error("parse-failed")
+                    .with_message("Failed to parse source")
+                    .build(),
+            )
+        })?,
+    };
+
+    // Reject oversized trees before any recursive walk over them.
+    match limits::check_tree_limits(&tree, limits, file_name) {
+        Some(diagnostic) => Err(Box::new(diagnostic)),
+        None => Ok(tree),
     }
 }
 
 /// Parses NX source from a file.
 ///
+/// Diagnostics name the file by its full path as given, not just its file name.
+///
 /// # Errors
 ///
 /// Returns `Err` if the file cannot be read or is not valid UTF-8.
@@ -269,23 +397,22 @@ pub fn parse_file(path: impl AsRef<Path>) -> io::Result<ParseResult> {
         });
     }
 
-    let file_name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown");
-
-    Ok(parse_str(&source, file_name))
+    Ok(parse_str(&source, &path.to_string_lossy()))
 }
 
-fn validate_source_size(source_len: usize, file_name: &str) -> Option<Diagnostic> {
-    if source_len <= MAX_SOURCE_BYTES {
+fn validate_source_size(
+    source_len: usize,
+    max_source_bytes: usize,
+    file_name: &str,
+) -> Option<Diagnostic> {
+    if source_len <= max_source_bytes {
         return None;
     }
 
     Some(
         Diagnostic::error("source-too-large")
             .with_message(format!(
-                "NX source file '{file_name}' is too large ({source_len} bytes). NX source files must be <= {MAX_SOURCE_BYTES} bytes."
+                "NX source file '{file_name}' is too large ({source_len} bytes). NX source files must be <= {max_source_bytes} bytes."
             ))
             .with_help("Split the source into smaller files before parsing or evaluation.")
             .build(),
@@ -307,6 +434,7 @@ impl Utf8Ext for str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     #[test]
     fn test_language_can_be_loaded() {
@@ -340,6 +468,48 @@ mod tests {
         assert!(!result.errors.is_empty());
     }
 
+    #[test]
+    fn test_parse_str_labels_diagnostics_with_normalized_full_path() {
+        let windows = parse_str("let x = ", r"app\ui\main.nx");
+        let unix = parse_str("let x = ", "./app/ui/main.nx");
+        let other = parse_str("let x = ", "lib/ui/main.nx");
+
+        assert_eq!(windows.source_id, unix.source_id);
+        assert_ne!(unix.source_id, other.source_id);
+        for result in [&windows, &unix] {
+            let files = result
+                .errors
+                .iter()
+                .flat_map(|diagnostic| diagnostic.labels())
+                .map(|label| label.file.as_str())
+                .collect::<Vec<_>>();
+            assert!(!files.is_empty());
+            assert!(
+                files.iter().all(|file| *file == "app/ui/main.nx"),
+                "{files:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_file_keeps_directories_in_diagnostics() {
+        let dir = std::env::temp_dir().join(format!("nx-syntax-parse-file-{}", std::process::id()));
+        let path = dir.join("ui").join("main.nx");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "let x = ").unwrap();
+
+        let result = parse_file(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let expected = normalize_path(&path.to_string_lossy());
+        assert!(expected.ends_with("/ui/main.nx"));
+        assert!(result
+            .errors
+            .iter()
+            .flat_map(|diagnostic| diagnostic.labels())
+            .all(|label| label.file == expected));
+    }
+
     #[test]
     fn test_syntax_tree_root() {
         let source = r#"import "./foo""#;
@@ -390,15 +560,167 @@ mod tests {
         assert!(result.has_errors());
     }
 
+    #[test]
+    fn test_parse_str_with_options_accepts_unions_in_latest_version() {
+        let source = "type Shape = | circle | square { size: int }";
+        let result = parse_str_with_options(source, "test.nx", ParseOptions::default());
+
+        assert!(result.is_ok(), "unexpected errors: {:?}", result.errors);
+    }
+
+    #[test]
+    fn test_parse_str_with_options_rejects_unions_in_v1() {
+        let source = "type Shape = | circle | square { size: int }";
+        let options = ParseOptions::for_version(LanguageVersion::V1);
+        let result = parse_str_with_options(source, "test.nx", options);
+
+        let version_errors: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|d| d.code() == Some("unsupported-language-version"))
+            .collect();
+        assert_eq!(version_errors.len(), 1);
+        assert!(version_errors[0].message().contains("version 2"));
+        assert!(result.tree.is_some());
+    }
+
+    #[test]
+    fn test_parse_str_with_options_rejects_property_list_fragments_in_v1() {
+        let source = "let root() = <Button if enabled { disabled=false } />";
+        let options = ParseOptions::for_version(LanguageVersion::V1);
+        let result = parse_str_with_options(source, "test.nx", options);
+
+        assert!(result
+            .errors
+            .iter()
+            .any(|d| d.code() == Some("unsupported-language-version")));
+    }
+
+    #[test]
+    fn test_parse_str_with_options_rejects_each_version_2_feature_in_v1() {
+        for (source, description) in [
+            (
+                "component <Button ...rest:props /> = { <button /> }",
+                "Rest props",
+            ),
+            (
+                "let theme = \"light\"\nlet root() = { provide theme = \"dark\" { <p /> } }",
+                "`provide` and `use`",
+            ),
+            ("let [first, ...others] = {xs}", "Destructuring"),
+            (
+                "let add(a:int, b:int) = { a + b }\nlet inc() = { add(_, 1) }",
+                "`_` placeholder",
+            ),
+            (
+                "meta Info { title: \"Invoice\" }\ntype Info = { title:string }",
+                "`meta` blocks",
+            ),
+            ("let big = 0xFF_FF", "Digit separators"),
+            ("let mask = 0b1010", "Binary literals"),
+            ("let million = 1e6", "Hex-float and exponent-only"),
+            ("let twelve = 0x1.8p3", "Hex-float and exponent-only"),
+            ("let xs = [1, 2]", "`[...]` array literals"),
+            (
+                "type User = { name:string }\ntype Admin = User & { role:string }",
+                "Record compositions",
+            ),
+            (
+                "let make(name:string) = <User {name} />",
+                "Property shorthands",
+            ),
+            ("type Person = { age:int @min(0) }", "`@` field constraints"),
+        ] {
+            let result = parse_str_with_options(source, "test.nx", ParseOptions::default());
+            assert!(result.is_ok(), "{source}: {:?}", result.errors);
+
+            let options = ParseOptions::for_version(LanguageVersion::V1);
+            let result = parse_str_with_options(source, "test.nx", options);
+            let version_errors: Vec<_> = result
+                .errors
+                .iter()
+                .filter(|d| d.code() == Some("unsupported-language-version"))
+                .collect();
+            assert_eq!(version_errors.len(), 1, "{source}: {:?}", result.errors);
+            assert!(
+                version_errors[0].message().starts_with(description),
+                "{source}: {}",
+                version_errors[0].message()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_str_with_options_accepts_version_1_number_literals_in_v1() {
+        let source = "let a = 1000\nlet b = 0xFF\nlet c = 1.5\nlet d = 2.5e-3\nlet e = {1 2}";
+        let options = ParseOptions::for_version(LanguageVersion::V1);
+        let result = parse_str_with_options(source, "test.nx", options);
+
+        assert!(result.is_ok(), "unexpected errors: {:?}", result.errors);
+    }
+
+    #[test]
+    fn test_parse_str_rejects_deeply_nested_source_without_tree() {
+        let depth = ParseLimits::DEFAULT_MAX_TREE_DEPTH;
+        let source = format!(
+            "let root() = {}x{}",
+            "<div>".repeat(depth),
+            "</div>".repeat(depth)
+        );
+
+        let result = parse_str(&source, "deep.nx");
+
+        assert!(result.tree.is_none());
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code(), Some("syntax-tree-too-deep"));
+    }
+
+    #[test]
+    fn test_parse_str_with_options_applies_configured_limits() {
+        let source = "let a = 1\nlet b = 2";
+        let options = ParseOptions::default()
+            .with_limits(ParseLimits::default().with_max_source_bytes(source.len() - 1));
+        let result = parse_str_with_options(source, "small.nx", options);
+        assert_eq!(result.errors[0].code(), Some("source-too-large"));
+
+        let options =
+            ParseOptions::default().with_limits(ParseLimits::default().with_max_node_count(3));
+        let result = parse_str_with_options(source, "small.nx", options);
+        assert!(result.tree.is_none());
+        assert_eq!(result.errors[0].code(), Some("syntax-tree-too-large"));
+
+        let options = ParseOptions::default().with_limits(ParseLimits::unlimited());
+        assert!(parse_str_with_options(source, "small.nx", options).is_ok());
+    }
+
+    #[test]
+    fn test_parse_str_with_options_stops_parses_that_exceed_the_time_limit() {
+        let source = "let a = 1\n".repeat(10_000);
+        let options = ParseOptions::default()
+            .with_limits(ParseLimits::default().with_max_parse_time(Some(Duration::ZERO)));
+
+        let result = parse_str_with_options(&source, "slow.nx", options);
+
+        assert!(result.tree.is_none());
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code(), Some("parse-timed-out"));
+        assert!(result.errors[0].message().contains("slow.nx"));
+
+        let options =
+            ParseOptions::default().with_limits(ParseLimits::default().with_max_parse_time(None));
+        assert!(parse_str_with_options(&source, "slow.nx", options).is_ok());
+    }
+
     #[test]
     fn test_validate_source_size_accepts_limit() {
-        assert!(validate_source_size(MAX_SOURCE_BYTES, "test.nx").is_none());
+        assert!(validate_source_size(MAX_SOURCE_BYTES, MAX_SOURCE_BYTES, "test.nx").is_none());
     }
 
     #[test]
     fn test_validate_source_size_rejects_sources_larger_than_limit() {
-        let diagnostic = validate_source_size(MAX_SOURCE_BYTES + 1, "too-large.nx")
-            .expect("Source larger than the NX limit should be rejected");
+        let diagnostic =
+            validate_source_size(MAX_SOURCE_BYTES + 1, MAX_SOURCE_BYTES, "too-large.nx")
+                .expect("Source larger than the NX limit should be rejected");
 
         assert_eq!(diagnostic.code(), Some("source-too-large"));
         assert_eq!(diagnostic.severity(), Severity::Error);
@@ -0,0 +1,203 @@
+//! Resource limits enforced while parsing.
+//!
+//! Validation, lowering, and type checking all walk the syntax tree, so pathological sources are
+//! rejected right after parsing with a diagnostic and no tree, rather than being handed to stages
+//! that would churn on them or exhaust the stack.
+
+use crate::MAX_SOURCE_BYTES;
+use nx_diagnostics::{Diagnostic, Label};
+use std::time::Duration;
+use text_size::{TextRange, TextSize};
+
+/// Limits on the size and shape of source accepted by the parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseLimits {
+    /// Maximum source length in bytes. Values above [`MAX_SOURCE_BYTES`] are treated as
+    /// [`MAX_SOURCE_BYTES`].
+    pub max_source_bytes: usize,
+    /// Maximum nesting depth of the syntax tree, counting the root as depth 1.
+    pub max_tree_depth: usize,
+    /// Maximum number of nodes in the syntax tree, including anonymous tokens.
+    pub max_node_count: usize,
+    /// Maximum wall-clock time tree-sitter may spend parsing, or `None` for no limit.
+    pub max_parse_time: Option<Duration>,
+}
+
+impl ParseLimits {
+    /// Default maximum syntax tree depth.
+    pub const DEFAULT_MAX_TREE_DEPTH: usize = 512;
+    /// Default maximum syntax tree node count.
+    pub const DEFAULT_MAX_NODE_COUNT: usize = 2_000_000;
+    /// Default maximum parse time. Ordinary sources parse in milliseconds; the limit only stops
+    /// inputs that drive the parser's error recovery into pathological backtracking.
+    pub const DEFAULT_MAX_PARSE_TIME: Duration = Duration::from_secs(10);
+
+    /// Returns limits that only enforce [`MAX_SOURCE_BYTES`].
+    pub fn unlimited() -> Self {
+        Self {
+            max_source_bytes: MAX_SOURCE_BYTES,
+            max_tree_depth: usize::MAX,
+            max_node_count: usize::MAX,
+            max_parse_time: None,
+        }
+    }
+
+    /// Returns a copy of these limits with a different maximum source length.
+    pub fn with_max_source_bytes(mut self, max_source_bytes: usize) -> Self {
+        self.max_source_bytes = max_source_bytes;
+        self
+    }
+
+    /// Returns a copy of these limits with a different maximum tree depth.
+    pub fn with_max_tree_depth(mut self, max_tree_depth: usize) -> Self {
+        self.max_tree_depth = max_tree_depth;
+        self
+    }
+
+    /// Returns a copy of these limits with a different maximum node count.
+    pub fn with_max_node_count(mut self, max_node_count: usize) -> Self {
+        self.max_node_count = max_node_count;
+        self
+    }
+
+    /// Returns a copy of these limits with a different maximum parse time.
+    pub fn with_max_parse_time(mut self, max_parse_time: Option<Duration>) -> Self {
+        self.max_parse_time = max_parse_time;
+        self
+    }
+
+    /// Returns the effective maximum source length in bytes.
+    pub fn effective_max_source_bytes(&self) -> usize {
+        self.max_source_bytes.min(MAX_SOURCE_BYTES)
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_source_bytes: MAX_SOURCE_BYTES,
+            max_tree_depth: Self::DEFAULT_MAX_TREE_DEPTH,
+            max_node_count: Self::DEFAULT_MAX_NODE_COUNT,
+            max_parse_time: Some(Self::DEFAULT_MAX_PARSE_TIME),
+        }
+    }
+}
+
+/// Returns the diagnostic for a parse that was stopped after running longer than `limit`.
+pub(crate) fn parse_timed_out(limit: Duration, file_name: &str) -> Diagnostic {
+    Diagnostic::error("parse-timed-out")
+        .with_message(format!(
+            "Parsing '{file_name}' timed out after {} ms",
+            limit.as_millis()
+        ))
+        .with_help("Fix the syntax errors the parser is recovering from, or split the source into smaller files.")
+        .build()
+}
+
+/// Checks the tree against the depth and node count limits without recursing.
+///
+/// Returns a diagnostic for the first limit exceeded, if any.
+pub(crate) fn check_tree_limits(
+    tree: &tree_sitter::Tree,
+    limits: &ParseLimits,
+    file_name: &str,
+) -> Option<Diagnostic> {
+    let mut cursor = tree.walk();
+    let mut depth = 1usize;
+    let mut node_count = 0usize;
+
+    loop {
+        node_count += 1;
+        if node_count > limits.max_node_count {
+            return Some(
+                Diagnostic::error("syntax-tree-too-large")
+                    .with_message(format!(
+                        "NX source file '{file_name}' has more than {} syntax nodes",
+                        limits.max_node_count
+                    ))
+                    .with_help("Split the source into smaller files before parsing or evaluation.")
+                    .build(),
+            );
+        }
+
+        if depth > limits.max_tree_depth {
+            let node = cursor.node();
+            let range = TextRange::new(
+                TextSize::from(node.start_byte() as u32),
+                TextSize::from(node.end_byte() as u32),
+            );
+            return Some(
+                Diagnostic::error("syntax-tree-too-deep")
+                    .with_message(format!(
+                        "Source is nested more than {} syntax levels deep",
+                        limits.max_tree_depth
+                    ))
+                    .with_label(
+                        Label::primary(file_name, range).with_message("nesting limit exceeded here"),
+                    )
+                    .with_help("Extract deeply nested markup or expressions into separate components or functions.")
+                    .build(),
+            );
+        }
+
+        if cursor.goto_first_child() {
+            depth += 1;
+            continue;
+        }
+
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                return None;
+            }
+            depth -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_tree(source: &str) -> tree_sitter::Tree {
+        crate::parser()
+            .parse(source, None)
+            .expect("tree-sitter should produce a tree")
+    }
+
+    #[test]
+    fn test_tree_within_limits_passes() {
+        let tree = parse_tree("let root() = <div><span>hi</span></div>");
+        assert!(check_tree_limits(&tree, &ParseLimits::default(), "test.nx").is_none());
+    }
+
+    #[test]
+    fn test_tree_deeper_than_limit_is_rejected() {
+        let tree = parse_tree("let root() = <div><span>hi</span></div>");
+        let limits = ParseLimits::default().with_max_tree_depth(4);
+
+        let diagnostic = check_tree_limits(&tree, &limits, "deep.nx").expect("depth diagnostic");
+
+        assert_eq!(diagnostic.code(), Some("syntax-tree-too-deep"));
+        assert_eq!(diagnostic.labels()[0].file, "deep.nx");
+    }
+
+    #[test]
+    fn test_tree_with_too_many_nodes_is_rejected() {
+        let tree = parse_tree("let a = 1\nlet b = 2\nlet c = 3");
+        let limits = ParseLimits::default().with_max_node_count(5);
+
+        let diagnostic = check_tree_limits(&tree, &limits, "big.nx").expect("size diagnostic");
+
+        assert_eq!(diagnostic.code(), Some("syntax-tree-too-large"));
+        assert!(diagnostic.message().contains("big.nx"));
+    }
+
+    #[test]
+    fn test_source_limit_is_clamped_to_api_maximum() {
+        let limits = ParseLimits::default().with_max_source_bytes(usize::MAX);
+        assert_eq!(limits.effective_max_source_bytes(), MAX_SOURCE_BYTES);
+    }
+}
@@ -0,0 +1,185 @@
+//! Lexical metadata about NX for editors and tooling.
+//!
+//! Editor integrations need the language's keywords, operators, bracket pairs and comment tokens
+//! to configure highlighting, auto-closing pairs and comment toggling. [`language_metadata`]
+//! exposes them from the crate that owns the grammar, and the tests below keep the lists in sync
+//! with the generated grammar.
+
+/// An opening token and the token that closes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenPair {
+    pub open: &'static str,
+    pub close: &'static str,
+}
+
+impl TokenPair {
+    const fn new(open: &'static str, close: &'static str) -> Self {
+        Self { open, close }
+    }
+}
+
+/// The lexical surface of the NX language, as returned by [`language_metadata`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageMetadata {
+    /// Reserved and contextual keywords, such as `let`, `component` and `raw`.
+    pub keywords: &'static [&'static str],
+    /// Keywords that denote literal values.
+    pub literals: &'static [&'static str],
+    /// Names of the built-in primitive types.
+    pub primitive_types: &'static [&'static str],
+    /// Operator and punctuation tokens of expressions and declarations, longest first.
+    pub operators: &'static [&'static str],
+    /// Bracket pairs that nest and may be matched by an editor.
+    pub brackets: &'static [TokenPair],
+    /// Pairs an editor may close automatically: the brackets plus string quotes.
+    pub auto_closing_pairs: &'static [TokenPair],
+    /// The token that starts a line comment.
+    pub line_comment: &'static str,
+    /// Block comment delimiters; the first pair is the one to insert when toggling comments.
+    pub block_comments: &'static [TokenPair],
+}
+
+const BRACKETS: &[TokenPair] = &[
+    TokenPair::new("{", "}"),
+    TokenPair::new("[", "]"),
+    TokenPair::new("(", ")"),
+];
+
+static METADATA: LanguageMetadata = LanguageMetadata {
+    keywords: &[
+        "abstract",
+        "action",
+        "as",
+        "component",
+        "else",
+        "emits",
+        "enum",
+        "export",
+        "extends",
+        "external",
+        "for",
+        "from",
+        "if",
+        "import",
+        "in",
+        "is",
+        "let",
+        "meta",
+        "private",
+        "props",
+        "provide",
+        "raw",
+        "state",
+        "type",
+        "use",
+    ],
+    literals: &["true", "false", "null"],
+    primitive_types: &[
+        "string", "int", "i32", "i64", "float", "f32", "f64", "bool", "void", "object",
+    ],
+    operators: &[
+        "...", "==", "!=", "<=", ">=", "&&", "||", "=>", "+", "-", "*", "/", "%", "<", ">", "!",
+        "?", "|", ":", "=", ".", ",",
+    ],
+    brackets: BRACKETS,
+    auto_closing_pairs: &[
+        TokenPair::new("{", "}"),
+        TokenPair::new("[", "]"),
+        TokenPair::new("(", ")"),
+        TokenPair::new("\"", "\""),
+    ],
+    line_comment: "//",
+    block_comments: &[TokenPair::new("/*", "*/"), TokenPair::new("<!--", "-->")],
+};
+
+/// Returns the keywords, operators, bracket pairs and comment tokens of NX.
+pub fn language_metadata() -> &'static LanguageMetadata {
+    &METADATA
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{syntax_kind_from_str, SyntaxKind};
+    use std::collections::BTreeSet;
+
+    /// Every literal token in the generated grammar.
+    fn grammar_strings() -> BTreeSet<String> {
+        let grammar = include_str!("grammar.json");
+        let mut strings = BTreeSet::new();
+        let mut lines = grammar.lines();
+        while let Some(line) = lines.next() {
+            if line.trim() != r#""type": "STRING","# {
+                continue;
+            }
+            let value = lines
+                .next()
+                .and_then(|line| line.trim().strip_prefix(r#""value": ""#))
+                .and_then(|value| value.strip_suffix('"'))
+                .expect("a STRING rule is followed by its value");
+            strings.insert(value.replace(r"\\", r"\").replace(r#"\""#, "\""));
+        }
+        strings
+    }
+
+    #[test]
+    fn test_metadata_words_cover_the_grammar_words() {
+        let metadata = language_metadata();
+        let words = grammar_strings()
+            .into_iter()
+            .filter(|value| value.chars().all(|c| c.is_ascii_alphanumeric()))
+            .collect::<BTreeSet<_>>();
+        let listed = metadata
+            .keywords
+            .iter()
+            .chain(metadata.literals)
+            .chain(metadata.primitive_types)
+            .map(|word| word.to_string())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(listed, words);
+    }
+
+    #[test]
+    fn test_metadata_tokens_are_grammar_tokens() {
+        let metadata = language_metadata();
+        let strings = grammar_strings();
+        let pairs = metadata
+            .brackets
+            .iter()
+            .chain(metadata.auto_closing_pairs)
+            .chain(metadata.block_comments);
+        for token in metadata
+            .operators
+            .iter()
+            .copied()
+            .chain(pairs.flat_map(|pair| [pair.open, pair.close]))
+            .chain([metadata.line_comment])
+        {
+            assert!(strings.contains(token), "{token:?} is not in the grammar");
+        }
+    }
+
+    #[test]
+    fn test_metadata_operators_are_longest_first() {
+        let operators = language_metadata().operators;
+        for (index, operator) in operators.iter().enumerate() {
+            for longer in &operators[index + 1..] {
+                assert!(
+                    !longer.starts_with(operator),
+                    "{longer:?} must come before {operator:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_metadata_keywords_map_to_keyword_kinds() {
+        for keyword in language_metadata().keywords {
+            let kind = syntax_kind_from_str(keyword);
+            // Contextual keywords without a token kind of their own map to ERROR.
+            if kind != SyntaxKind::ERROR && kind != SyntaxKind::TYPE {
+                assert!(kind.is_keyword(), "{keyword:?} maps to {kind:?}");
+            }
+        }
+    }
+}
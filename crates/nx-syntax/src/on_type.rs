@@ -0,0 +1,220 @@
+//! On-type formatting for editors.
+//!
+//! [`on_type_format`] adjusts the indentation of the line being typed on: it indents a new line
+//! inside a `{}`, `[]` or `()` block or an element, and aligns a closing bracket or closing tag
+//! with the line that opened it. It works on the source text alone, scanning for brackets, tags,
+//! strings and comments, so it behaves the same while the source is incomplete and does not
+//! parse.
+
+use crate::layout::{
+    edit, indent_unit, indentation, is_blank, leading_whitespace, line_start, open_blocks,
+    BlockKind,
+};
+use nx_diagnostics::TextEdit;
+
+/// Returns the edits that format the line at `offset` after `typed_char` was typed.
+///
+/// `source` already contains the typed character, and `offset` is the byte offset just after
+/// it, as editors report on-type formatting requests. Formatting is triggered by:
+///
+/// - a newline, which indents the new line one level deeper than the innermost open block or
+///   element, or one level deeper than the previous line when that line ends with `=`. Pressing
+///   enter between a bracket or tag and its closer also moves the closer to its own line.
+/// - `}`, `]` or `)` as the first character on a line, which aligns it with its opening line.
+/// - `/` or `>` completing a closing tag at the start of a line, which aligns the tag with the
+///   line of its opening tag.
+///
+/// The indentation unit is taken from the source, defaulting to two spaces. The edits leave
+/// `file` empty, since the source has no name here, and are returned in source order.
+pub fn on_type_format(source: &str, offset: usize, typed_char: char) -> Vec<TextEdit> {
+    if offset > source.len()
+        || !source.is_char_boundary(offset)
+        || !source[..offset].ends_with(typed_char)
+    {
+        return Vec::new();
+    }
+    let typed_at = offset - typed_char.len_utf8();
+
+    match typed_char {
+        '\n' => indent_new_line(source, offset),
+        '}' | ']' | ')' => {
+            let line_start = line_start(source, typed_at);
+            if !is_blank(&source[line_start..typed_at]) {
+                return Vec::new();
+            }
+            match open_blocks(source, typed_at).last() {
+                Some(open) if open.kind.closer() == typed_char.to_string() => {
+                    reindent(source, line_start, typed_at, indentation(source, open.at))
+                }
+                _ => Vec::new(),
+            }
+        }
+        '/' | '>' => {
+            let line_start = line_start(source, typed_at);
+            let line = &source[line_start..offset];
+            let content = line.trim_start();
+            let completes_close_tag = if typed_char == '/' {
+                content == "</"
+            } else {
+                content.starts_with("</") && content.find('>') == Some(content.len() - 1)
+            };
+            if !completes_close_tag {
+                return Vec::new();
+            }
+            let tag_start = offset - content.len();
+            match open_blocks(source, tag_start).last() {
+                Some(open) if open.kind == BlockKind::Element => {
+                    reindent(source, line_start, tag_start, indentation(source, open.at))
+                }
+                _ => Vec::new(),
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn indent_new_line(source: &str, new_line: usize) -> Vec<TextEdit> {
+    let unit = indent_unit(source);
+    let whitespace_end = new_line + leading_whitespace(&source[new_line..]);
+    let rest = &source[whitespace_end..];
+    let previous_line = source[..new_line - 1].trim_end_matches('\r');
+    let previous_line = &previous_line[line_start(previous_line, previous_line.len())..];
+
+    let open_blocks = open_blocks(source, new_line);
+    let mut indent = match open_blocks.last() {
+        Some(open) => {
+            let opener_indent = indentation(source, open.at);
+            if rest.starts_with(open.kind.closer()) {
+                // Enter between an opener and its closer leaves the cursor on an indented line
+                // of its own, with the closer below it.
+                let opener_end = open.at + open.kind.opener_len(source, open.at);
+                if source[opener_end..new_line].trim().is_empty() {
+                    let replacement = format!("{opener_indent}{unit}\n{opener_indent}");
+                    return vec![edit(new_line..whitespace_end, replacement)];
+                }
+                opener_indent.to_string()
+            } else {
+                format!("{opener_indent}{unit}")
+            }
+        }
+        None => String::new(),
+    };
+
+    // A definition continued on the next line, as in `let <Spinner /> =`, is indented one level.
+    if previous_line.trim_end().ends_with('=') {
+        let continued = format!(
+            "{}{unit}",
+            &previous_line[..leading_whitespace(previous_line)]
+        );
+        if continued.len() > indent.len() {
+            indent = continued;
+        }
+    }
+
+    reindent(source, new_line, whitespace_end, &indent)
+}
+
+/// Replaces the whitespace in `start..end` with `indent`, unless it is already equal.
+fn reindent(source: &str, start: usize, end: usize, indent: &str) -> Vec<TextEdit> {
+    if &source[start..end] == indent {
+        return Vec::new();
+    }
+    vec![edit(start..end, indent.to_string())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Types `typed` at the `|` in `source` and returns the source with the edits applied.
+    fn type_at(source: &str, typed: char) -> String {
+        let cursor = source.find('|').expect("source marks the cursor");
+        let mut text = source.replacen('|', &typed.to_string(), 1);
+        let edits = on_type_format(&text, cursor + typed.len_utf8(), typed);
+        for edit in edits.iter().rev() {
+            text.replace_range(
+                usize::from(edit.range.start())..usize::from(edit.range.end()),
+                &edit.replacement,
+            );
+        }
+        text
+    }
+
+    #[test]
+    fn test_enter_indents_inside_blocks() {
+        assert_eq!(type_at("let f() = {|", '\n'), "let f() = {\n  ");
+        assert_eq!(type_at("let f() = {|}", '\n'), "let f() = {\n  \n}");
+        assert_eq!(
+            type_at("let f() = {\n    x|\n}", '\n'),
+            "let f() = {\n    x\n    \n}"
+        );
+        assert_eq!(type_at("let xs = [1,|", '\n'), "let xs = [1,\n  ");
+    }
+
+    #[test]
+    fn test_enter_indents_element_children() {
+        assert_eq!(
+            type_at("<div class=\"a\">|</div>", '\n'),
+            "<div class=\"a\">\n  \n</div>"
+        );
+        assert_eq!(
+            type_at("<div>\n  <span>it's \"quoted\"</span>|\n</div>", '\n'),
+            "<div>\n  <span>it's \"quoted\"</span>\n  \n</div>"
+        );
+        assert_eq!(
+            type_at("<div>\n  <img src=\"a\" />|", '\n'),
+            "<div>\n  <img src=\"a\" />\n  "
+        );
+        assert_eq!(type_at("let <Spinner/> =|", '\n'), "let <Spinner/> =\n  ");
+    }
+
+    #[test]
+    fn test_enter_ignores_brackets_in_strings_and_comments() {
+        assert_eq!(type_at("let s = \"{\"|", '\n'), "let s = \"{\"\n");
+        assert_eq!(type_at("// {\nlet a = 1|", '\n'), "// {\nlet a = 1\n");
+        assert_eq!(type_at("<!-- <div> -->|", '\n'), "<!-- <div> -->\n");
+        assert_eq!(type_at("let b = a < b|", '\n'), "let b = a < b\n");
+    }
+
+    #[test]
+    fn test_closing_bracket_aligns_with_opening_line() {
+        assert_eq!(type_at("let f() = {\n  x\n  |", '}'), "let f() = {\n  x\n}");
+        assert_eq!(
+            type_at("  let xs = [\n    1\n|", ']'),
+            "  let xs = [\n    1\n  ]"
+        );
+        assert_eq!(type_at("let f() = { x |", '}'), "let f() = { x }");
+    }
+
+    #[test]
+    fn test_closing_tag_aligns_with_opening_tag() {
+        assert_eq!(
+            type_at("<div>\n  <p>hi</p>\n    </div|", '>'),
+            "<div>\n  <p>hi</p>\n</div>"
+        );
+        assert_eq!(
+            type_at("<div>\n  <section>\n    text\n    <|", '/'),
+            "<div>\n  <section>\n    text\n  </"
+        );
+    }
+
+    #[test]
+    fn test_indent_unit_follows_source() {
+        assert_eq!(
+            type_at("let f() = {\n\tx\n}\nlet g() = {|", '\n'),
+            "let f() = {\n\tx\n}\nlet g() = {\n\t"
+        );
+        assert_eq!(
+            type_at("let f() = {\n    x\n}\nlet g() = {|", '\n'),
+            "let f() = {\n    x\n}\nlet g() = {\n    "
+        );
+    }
+
+    #[test]
+    fn test_on_type_format_ignores_mismatched_requests() {
+        assert!(on_type_format("let a = 1", 3, '\n').is_empty());
+        assert!(on_type_format("let a = 1", 100, '\n').is_empty());
+        assert!(on_type_format("let a = \"é\"", 10, '\n').is_empty());
+        assert!(on_type_format("let a = {\n  ", 10, '\n').is_empty());
+    }
+}
@@ -0,0 +1,328 @@
+//! Folding and selection ranges for editors.
+//!
+//! Both are derived from the syntax tree alone, so they are available for any parsed file,
+//! including one with syntax errors.
+
+use crate::{SyntaxKind, SyntaxNode, SyntaxTree};
+use std::collections::HashSet;
+use text_size::{TextRange, TextSize};
+
+/// What a [`FoldingRange`] folds away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FoldingRangeKind {
+    /// A definition, block, element or multi-line list.
+    Region,
+    /// A block comment, or a run of line comments on consecutive lines.
+    Comment,
+    /// A run of consecutive import statements.
+    Imports,
+}
+
+/// A range of source that an editor can fold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FoldingRange {
+    /// The folded construct, including its closing delimiter.
+    pub range: TextRange,
+    /// Zero-based line the range starts on.
+    pub start_line: usize,
+    /// Zero-based line the range ends on.
+    pub end_line: usize,
+    pub kind: FoldingRangeKind,
+}
+
+/// Returns the foldable ranges of `tree`, ordered by start offset.
+///
+/// Definitions, braced blocks, elements, control-flow expressions and lists fold when they span
+/// more than one line. Only the outermost range starting on a line is returned, since editors
+/// fold by line.
+///
+/// # Examples
+///
+/// ```
+/// use nx_syntax::{folding_ranges, parse_str};
+///
+/// let result = parse_str("let f() = {\n  1\n}", "example.nx");
+/// let ranges = folding_ranges(result.tree.as_ref().unwrap());
+///
+/// assert_eq!(ranges.len(), 1);
+/// assert_eq!((ranges[0].start_line, ranges[0].end_line), (0, 2));
+/// ```
+pub fn folding_ranges(tree: &SyntaxTree) -> Vec<FoldingRange> {
+    let mut folds = Folds::default();
+    folds.visit(&tree.root());
+    folds.ranges.sort_by_key(|fold| fold.range.start());
+    folds.ranges
+}
+
+#[derive(Default)]
+struct Folds {
+    ranges: Vec<FoldingRange>,
+    /// Lines a range already starts on.
+    start_lines: HashSet<usize>,
+}
+
+impl Folds {
+    fn visit(&mut self, node: &SyntaxNode) {
+        let mut comments: Option<(SyntaxNode, SyntaxNode)> = None;
+        let mut imports: Option<(SyntaxNode, SyntaxNode)> = None;
+
+        for child in node.children() {
+            match child.kind() {
+                SyntaxKind::LINE_COMMENT => {
+                    imports = self.flush(imports.take(), FoldingRangeKind::Imports);
+                    comments = match comments {
+                        Some((first, last))
+                            if child.start_position().0 == last.end_position().0 + 1 =>
+                        {
+                            Some((first, child))
+                        }
+                        run => {
+                            self.flush(run, FoldingRangeKind::Comment);
+                            Some((child, child))
+                        }
+                    };
+                }
+                SyntaxKind::IMPORT_STATEMENT => {
+                    comments = self.flush(comments.take(), FoldingRangeKind::Comment);
+                    imports = Some((imports.map_or(child, |(first, _)| first), child));
+                }
+                kind => {
+                    comments = self.flush(comments.take(), FoldingRangeKind::Comment);
+                    imports = self.flush(imports.take(), FoldingRangeKind::Imports);
+                    if let Some(fold_kind) = folding_kind(kind) {
+                        self.push(&child, &child, fold_kind);
+                    }
+                    self.visit(&child);
+                }
+            }
+        }
+
+        self.flush(comments, FoldingRangeKind::Comment);
+        self.flush(imports, FoldingRangeKind::Imports);
+    }
+
+    /// Folds a run of sibling nodes from `first` to `last`, and ends the run.
+    fn flush<'a>(
+        &mut self,
+        run: Option<(SyntaxNode<'a>, SyntaxNode<'a>)>,
+        kind: FoldingRangeKind,
+    ) -> Option<(SyntaxNode<'a>, SyntaxNode<'a>)> {
+        if let Some((first, last)) = run {
+            self.push(&first, &last, kind);
+        }
+        None
+    }
+
+    fn push(&mut self, first: &SyntaxNode, last: &SyntaxNode, kind: FoldingRangeKind) {
+        let start_line = first.start_position().0;
+        let end_line = last.end_position().0;
+        if end_line > start_line && self.start_lines.insert(start_line) {
+            self.ranges.push(FoldingRange {
+                range: TextRange::new(first.span().start(), last.span().end()),
+                start_line,
+                end_line,
+                kind,
+            });
+        }
+    }
+}
+
+fn folding_kind(kind: SyntaxKind) -> Option<FoldingRangeKind> {
+    match kind {
+        SyntaxKind::BLOCK_COMMENT | SyntaxKind::HTML_BLOCK_COMMENT => {
+            Some(FoldingRangeKind::Comment)
+        }
+        SyntaxKind::META_BLOCK
+        | SyntaxKind::TYPE_DEFINITION
+        | SyntaxKind::RECORD_DEFINITION
+        | SyntaxKind::ACTION_DEFINITION
+        | SyntaxKind::UNION_DEFINITION
+        | SyntaxKind::ENUM_DEFINITION
+        | SyntaxKind::VALUE_DEFINITION
+        | SyntaxKind::FUNCTION_DEFINITION
+        | SyntaxKind::COMPONENT_DEFINITION
+        | SyntaxKind::COMPONENT_BODY
+        | SyntaxKind::EMITS_GROUP
+        | SyntaxKind::STATE_GROUP
+        | SyntaxKind::ELEMENT
+        | SyntaxKind::TEXT_CHILD_ELEMENT
+        | SyntaxKind::PROPERTY_LIST
+        | SyntaxKind::VALUES_BRACED_EXPRESSION
+        | SyntaxKind::ELEMENTS_BRACED_EXPRESSION
+        | SyntaxKind::SEQUENCE_EXPRESSION
+        | SyntaxKind::PARENTHESIZED_EXPRESSION
+        | SyntaxKind::VALUE_IF_EXPRESSION
+        | SyntaxKind::ELEMENTS_IF_EXPRESSION
+        | SyntaxKind::PROPERTY_LIST_IF_EXPRESSION
+        | SyntaxKind::VALUE_FOR_EXPRESSION
+        | SyntaxKind::ELEMENTS_FOR_EXPRESSION
+        | SyntaxKind::VALUE_MATCH_EXPRESSION
+        | SyntaxKind::ELEMENTS_MATCH_EXPRESSION
+        | SyntaxKind::VALUE_IF_CONDITION_ARM
+        | SyntaxKind::VALUE_IF_MATCH_ARM
+        | SyntaxKind::ELEMENTS_IF_CONDITION_ARM
+        | SyntaxKind::ELEMENTS_IF_MATCH_ARM => Some(FoldingRangeKind::Region),
+        _ => None,
+    }
+}
+
+/// Returns the ranges an editor's expand-selection steps through from `offset`, innermost first.
+///
+/// The first range is the token at `offset`; each following range is the nearest enclosing node
+/// with a larger span, ending with the whole file. Inside a string literal, the text between the
+/// quotes comes before the literal itself. An offset past the end of the source yields no ranges.
+///
+/// # Examples
+///
+/// ```
+/// use nx_syntax::{parse_str, selection_ranges};
+///
+/// let source = "let f(a:int, b:int) = { a + b }";
+/// let result = parse_str(source, "example.nx");
+/// let ranges = selection_ranges(result.tree.as_ref().unwrap(), source.find("a +").unwrap());
+///
+/// let texts: Vec<&str> = ranges.iter().map(|range| &source[*range]).collect();
+/// assert_eq!(texts[0], "a");
+/// assert!(texts.contains(&"a + b"));
+/// assert_eq!(*texts.last().unwrap(), source);
+/// ```
+pub fn selection_ranges(tree: &SyntaxTree, offset: usize) -> Vec<TextRange> {
+    if offset > tree.source().len() {
+        return Vec::new();
+    }
+
+    let mut ranges: Vec<TextRange> = Vec::new();
+    let mut node = tree.node_at(offset);
+    while let Some(current) = node {
+        let span = current.span();
+        if current.kind() == SyntaxKind::STRING_LITERAL && span.len() >= TextSize::from(2) {
+            let contents = TextRange::new(
+                span.start() + TextSize::from(1),
+                span.end() - TextSize::from(1),
+            );
+            if contents.contains_inclusive(TextSize::from(offset as u32)) {
+                ranges.push(contents);
+            }
+        }
+        if ranges.last() != Some(&span) {
+            ranges.push(span);
+        }
+        node = current.parent();
+    }
+
+    // The root node may not cover leading or trailing trivia.
+    let file = TextRange::up_to(TextSize::from(tree.source().len() as u32));
+    if ranges.last() != Some(&file) {
+        ranges.push(file);
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_str;
+
+    const SOURCE: &str = r#"import "./a.nx"
+import "./b.nx"
+
+// Users
+// and their names
+type User = {
+  name: string
+}
+
+let xs = [
+  1,
+  2
+]
+
+let f(x:int) = {
+  if x > 0 {
+    x
+  } else { 0 }
+}
+
+/* multi
+   line */
+let <Card title:string /> = <div class="a">
+  <p>{title}</p>
+</div>
+
+let one = { 1 }
+"#;
+
+    fn folds(source: &str) -> Vec<(usize, usize, FoldingRangeKind, String)> {
+        let result = parse_str(source, "folding.nx");
+        folding_ranges(result.tree.as_ref().unwrap())
+            .into_iter()
+            .map(|fold| {
+                let text = &source[fold.range];
+                let first_line = text.lines().next().unwrap_or_default().to_string();
+                (fold.start_line, fold.end_line, fold.kind, first_line)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_folding_ranges_cover_multi_line_constructs() {
+        assert_eq!(
+            folds(SOURCE),
+            vec![
+                (0, 1, FoldingRangeKind::Imports, r#"import "./a.nx""#.into()),
+                (3, 4, FoldingRangeKind::Comment, "// Users".into()),
+                (5, 7, FoldingRangeKind::Region, "type User = {".into()),
+                (9, 12, FoldingRangeKind::Region, "let xs = [".into()),
+                (14, 18, FoldingRangeKind::Region, "let f(x:int) = {".into()),
+                (15, 17, FoldingRangeKind::Region, "if x > 0 {".into()),
+                (20, 21, FoldingRangeKind::Comment, "/* multi".into()),
+                (
+                    22,
+                    24,
+                    FoldingRangeKind::Region,
+                    "let <Card title:string /> = <div class=\"a\">".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_folding_ranges_skip_single_line_runs() {
+        assert!(folds("// one\nlet a = 1\n// two\nimport \"./a.nx\"\n").is_empty());
+    }
+
+    #[test]
+    fn test_selection_ranges_expand_through_enclosing_nodes() {
+        let source = r#"let f(x:int) = { if x > 0 { x + 1 } else { 0 } }"#;
+        let result = parse_str(source, "selection.nx");
+        let tree = result.tree.as_ref().unwrap();
+        let offset = source.find("x + 1").unwrap();
+
+        let ranges = selection_ranges(tree, offset);
+        let texts = ranges
+            .iter()
+            .map(|range| &source[*range])
+            .collect::<Vec<_>>();
+        assert_eq!(texts[0], "x");
+        assert!(texts.contains(&"x + 1"));
+        assert!(texts.contains(&"{ x + 1 }"));
+        assert!(texts.contains(&"if x > 0 { x + 1 } else { 0 }"));
+        assert_eq!(*texts.last().unwrap(), source);
+        for pair in ranges.windows(2) {
+            assert!(pair[1].contains_range(pair[0]) && pair[1] != pair[0]);
+        }
+    }
+
+    #[test]
+    fn test_selection_ranges_select_string_contents_first() {
+        let source = "let s = \"hello world\"\n";
+        let result = parse_str(source, "selection.nx");
+        let tree = result.tree.as_ref().unwrap();
+
+        let ranges = selection_ranges(tree, source.find("world").unwrap());
+        assert_eq!(&source[ranges[0]], "hello world");
+        assert_eq!(&source[ranges[1]], "\"hello world\"");
+        assert_eq!(&source[*ranges.last().unwrap()], source);
+        assert!(selection_ranges(tree, source.len() + 1).is_empty());
+    }
+}
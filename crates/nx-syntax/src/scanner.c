@@ -241,19 +241,23 @@ bool tree_sitter_nx_external_scanner_scan(void *payload, TSLexer *lexer, const b
       break;
     }
 
+    // Lookahead below ends the token with mark_end before peeking. Copying the TSLexer does not
+    // save the lexer position, and restoring a stale lookahead at end of input loops forever.
     if (embed_mode && lexer->lookahead == '@') {
       // Check for typed text braced-value opener "@{"
-      TSLexer saved = *lexer;
+      lexer->mark_end(lexer);
       lexer->advance(lexer, false);
       if (lexer->lookahead == '{') {
-        *lexer = saved;
         if (has_content) {
           lexer->result_symbol = chunk_kind;
           return true;
         }
         return false;
       }
-      *lexer = saved;
+      // A lone '@' is text
+      has_content = true;
+      lexer->mark_end(lexer);
+      continue;
     }
 
     // Stop at braced-value delimiters
@@ -281,18 +285,18 @@ bool tree_sitter_nx_external_scanner_scan(void *payload, TSLexer *lexer, const b
     // Stop at entity start (let entity scanner handle it)
     if (lexer->lookahead == '&' && allow_entity) {
       // Check if it looks like an entity
-      TSLexer saved = *lexer;
+      lexer->mark_end(lexer);
       if (is_entity_start(lexer)) {
-        // Restore lexer position
-        *lexer = saved;
         if (has_content) {
           lexer->result_symbol = chunk_kind;
           return true;
         }
         return false;
       }
-      // Not an entity, restore and include in text
-      *lexer = saved;
+      // Not an entity, so the characters peeked at are text
+      has_content = true;
+      lexer->mark_end(lexer);
+      continue;
     }
 
     // Include this character in text chunk
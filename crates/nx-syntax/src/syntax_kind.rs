@@ -18,9 +18,15 @@ pub enum SyntaxKind {
     LIBRARY_PATH,
     VISIBILITY_MODIFIER,
 
+    // === Module Metadata ===
+    META_BLOCK,
+    META_ENTRY,
+    META_ARRAY,
+
     // === Type Definitions ===
     TYPE_DEFINITION,
     RECORD_DEFINITION,
+    RECORD_COMPOSITION,
     ACTION_DEFINITION,
     UNION_DEFINITION,
     UNION_CASE_LIST,
@@ -29,6 +35,9 @@ pub enum SyntaxKind {
     ENUM_MEMBER_LIST,
     ENUM_MEMBER,
     VALUE_DEFINITION,
+    ARRAY_BINDING_PATTERN,
+    RECORD_BINDING_PATTERN,
+    REST_BINDING,
     TYPE,
     PRIMITIVE_TYPE,
     USER_DEFINED_TYPE,
@@ -74,6 +83,11 @@ pub enum SyntaxKind {
     PROPERTY_LIST,
     PROPERTY_DEFINITION,
     PROPERTY_VALUE,
+    PROPERTY_SPREAD,
+    PROPERTY_SHORTHAND,
+    REST_PROPERTY_DEFINITION,
+    FIELD_CONSTRAINT,
+    CONSTRAINT_ARGUMENT,
 
     // === Expressions ===
     VALUE_EXPRESSION,
@@ -108,6 +122,8 @@ pub enum SyntaxKind {
     ELEMENTS_FOR_EXPRESSION,
     VALUE_MATCH_EXPRESSION,
     ELEMENTS_MATCH_EXPRESSION,
+    PROVIDE_EXPRESSION,
+    USE_EXPRESSION,
     PATTERN,
 
     // === If Expression Parts ===
@@ -136,6 +152,7 @@ pub enum SyntaxKind {
     INT_LITERAL,
     REAL_LITERAL,
     HEX_LITERAL,
+    BINARY_LITERAL,
     UNIT_LITERAL,
     BOOLEAN_LITERAL,
     BOOL_LITERAL,
@@ -168,10 +185,14 @@ pub enum SyntaxKind {
     EXPORT,
     ABSTRACT,
     EXTENDS,
+    PROPS,
     IF,
     ELSE,
     FOR,
     IN,
+    PROVIDE,
+    USE,
+    META,
     MATCH,
     TRUE,
     FALSE,
@@ -210,6 +231,7 @@ pub enum SyntaxKind {
     LT_SLASH,
     COMMA,
     DOT,
+    DOT_DOT_DOT,
     EQ,
     ESCAPED_LBRACE,
     ESCAPED_RBRACE,
@@ -244,11 +266,15 @@ impl SyntaxKind {
                 | SyntaxKind::EXPORT
                 | SyntaxKind::ABSTRACT
                 | SyntaxKind::EXTENDS
+                | SyntaxKind::PROPS
                 | SyntaxKind::ENUM
                 | SyntaxKind::IF
                 | SyntaxKind::ELSE
                 | SyntaxKind::FOR
                 | SyntaxKind::IN
+                | SyntaxKind::PROVIDE
+                | SyntaxKind::USE
+                | SyntaxKind::META
                 | SyntaxKind::MATCH
                 | SyntaxKind::TRUE
                 | SyntaxKind::FALSE
@@ -283,6 +309,7 @@ impl SyntaxKind {
                 | SyntaxKind::LT_SLASH
                 | SyntaxKind::COMMA
                 | SyntaxKind::DOT
+                | SyntaxKind::DOT_DOT_DOT
                 | SyntaxKind::EQ
                 | SyntaxKind::WHITESPACE
                 | SyntaxKind::NEWLINE
@@ -316,10 +343,14 @@ impl SyntaxKind {
                 | SyntaxKind::EXPORT
                 | SyntaxKind::ABSTRACT
                 | SyntaxKind::EXTENDS
+                | SyntaxKind::PROPS
                 | SyntaxKind::IF
                 | SyntaxKind::ELSE
                 | SyntaxKind::FOR
                 | SyntaxKind::IN
+                | SyntaxKind::PROVIDE
+                | SyntaxKind::USE
+                | SyntaxKind::META
                 | SyntaxKind::MATCH
                 | SyntaxKind::TRUE
                 | SyntaxKind::FALSE
@@ -354,6 +385,9 @@ pub fn syntax_kind_from_str(kind: &str) -> SyntaxKind {
         "module_definition" => SyntaxKind::MODULE_DEFINITION,
         "ERROR" => SyntaxKind::ERROR,
         "import_statement" => SyntaxKind::IMPORT_STATEMENT,
+        "meta_block" => SyntaxKind::META_BLOCK,
+        "meta_entry" => SyntaxKind::META_ENTRY,
+        "meta_array" => SyntaxKind::META_ARRAY,
         "wildcard_import" => SyntaxKind::WILDCARD_IMPORT,
         "selective_import_list" => SyntaxKind::SELECTIVE_IMPORT_LIST,
         "selective_import" => SyntaxKind::SELECTIVE_IMPORT,
@@ -361,6 +395,7 @@ pub fn syntax_kind_from_str(kind: &str) -> SyntaxKind {
         "visibility_modifier" => SyntaxKind::VISIBILITY_MODIFIER,
         "type_definition" => SyntaxKind::TYPE_DEFINITION,
         "record_definition" => SyntaxKind::RECORD_DEFINITION,
+        "record_composition" => SyntaxKind::RECORD_COMPOSITION,
         "action_definition" => SyntaxKind::ACTION_DEFINITION,
         "union_definition" => SyntaxKind::UNION_DEFINITION,
         "union_case_list" => SyntaxKind::UNION_CASE_LIST,
@@ -369,6 +404,9 @@ pub fn syntax_kind_from_str(kind: &str) -> SyntaxKind {
         "enum_member_list" => SyntaxKind::ENUM_MEMBER_LIST,
         "enum_member" => SyntaxKind::ENUM_MEMBER,
         "value_definition" => SyntaxKind::VALUE_DEFINITION,
+        "array_binding_pattern" => SyntaxKind::ARRAY_BINDING_PATTERN,
+        "record_binding_pattern" => SyntaxKind::RECORD_BINDING_PATTERN,
+        "rest_binding" => SyntaxKind::REST_BINDING,
         "type" => SyntaxKind::TYPE,
         "primitive_type" => SyntaxKind::PRIMITIVE_TYPE,
         "user_defined_type" => SyntaxKind::USER_DEFINED_TYPE,
@@ -406,6 +444,11 @@ pub fn syntax_kind_from_str(kind: &str) -> SyntaxKind {
         "property_list" => SyntaxKind::PROPERTY_LIST,
         "property_definition" => SyntaxKind::PROPERTY_DEFINITION,
         "property_value" => SyntaxKind::PROPERTY_VALUE,
+        "property_spread" => SyntaxKind::PROPERTY_SPREAD,
+        "property_shorthand" => SyntaxKind::PROPERTY_SHORTHAND,
+        "rest_property_definition" => SyntaxKind::REST_PROPERTY_DEFINITION,
+        "field_constraint" => SyntaxKind::FIELD_CONSTRAINT,
+        "constraint_argument" => SyntaxKind::CONSTRAINT_ARGUMENT,
         "value_expression" => SyntaxKind::VALUE_EXPRESSION,
         "value_expr" => SyntaxKind::VALUE_EXPR,
         "rhs_expression" => SyntaxKind::RHS_EXPRESSION,
@@ -455,6 +498,8 @@ pub fn syntax_kind_from_str(kind: &str) -> SyntaxKind {
         "elements_for_expression" => SyntaxKind::ELEMENTS_FOR_EXPRESSION,
         "value_match_expression" => SyntaxKind::VALUE_MATCH_EXPRESSION,
         "elements_match_expression" => SyntaxKind::ELEMENTS_MATCH_EXPRESSION,
+        "provide_expression" => SyntaxKind::PROVIDE_EXPRESSION,
+        "use_expression" => SyntaxKind::USE_EXPRESSION,
         "pattern" => SyntaxKind::PATTERN,
         "literal" => SyntaxKind::LITERAL,
         "string_literal" => SyntaxKind::STRING_LITERAL,
@@ -462,6 +507,7 @@ pub fn syntax_kind_from_str(kind: &str) -> SyntaxKind {
         "int_literal" => SyntaxKind::INT_LITERAL,
         "real_literal" => SyntaxKind::REAL_LITERAL,
         "hex_literal" => SyntaxKind::HEX_LITERAL,
+        "binary_literal" => SyntaxKind::BINARY_LITERAL,
         "unit_literal" => SyntaxKind::UNIT_LITERAL,
         "boolean_literal" => SyntaxKind::BOOLEAN_LITERAL,
         "bool_literal" => SyntaxKind::BOOL_LITERAL,
@@ -490,11 +536,15 @@ pub fn syntax_kind_from_str(kind: &str) -> SyntaxKind {
         "export" => SyntaxKind::EXPORT,
         "abstract" => SyntaxKind::ABSTRACT,
         "extends" => SyntaxKind::EXTENDS,
+        "props" => SyntaxKind::PROPS,
         "enum" => SyntaxKind::ENUM,
         "if" => SyntaxKind::IF,
         "else" => SyntaxKind::ELSE,
         "for" => SyntaxKind::FOR,
         "in" => SyntaxKind::IN,
+        "provide" => SyntaxKind::PROVIDE,
+        "use" => SyntaxKind::USE,
+        "meta" => SyntaxKind::META,
         "match" => SyntaxKind::MATCH,
         "true" => SyntaxKind::TRUE,
         "false" => SyntaxKind::FALSE,
@@ -525,6 +575,7 @@ pub fn syntax_kind_from_str(kind: &str) -> SyntaxKind {
         "]" => SyntaxKind::RBRACKET,
         "," => SyntaxKind::COMMA,
         "." => SyntaxKind::DOT,
+        "..." => SyntaxKind::DOT_DOT_DOT,
         "=" => SyntaxKind::EQ,
         _ => SyntaxKind::ERROR,
     }
@@ -3,12 +3,17 @@
 //! This module provides semantic validation that goes beyond what tree-sitter
 //! can detect during parsing, such as:
 //! - Element tag matching (opening and closing tags must match)
+//! - Empty elements written with a close tag, which canonicalize to the self-closing form
 //! - Error recovery within scopes
 //! - Enhanced error messages with suggestions
 
-use crate::{AstNode, ComponentDef, SyntaxKind, SyntaxNode, SyntaxTree, UnionDef};
-use nx_diagnostics::{Diagnostic, Label};
-use text_size::TextRange;
+use crate::{
+    AstNode, ComponentDef, ParseOptions, RecordDef, SyntaxFeature, SyntaxKind, SyntaxNode,
+    SyntaxTree, UnionDef,
+};
+use nx_diagnostics::{suggest, Diagnostic, Fix, Label, TextEdit};
+use std::ops::Range;
+use text_size::{TextRange, TextSize};
 
 const COMPONENT_SIGNATURE_SYNTAX: &str =
     "Expected: <Name [extends BaseComponent] prop:type emits { ActionName { prop:type } \
@@ -22,6 +27,8 @@ const COMPONENT_DEFINITION_SYNTAX: &str =
 const DUPLICATE_NULLABLE_SUFFIX_NOTE: &str =
     "A nullable suffix can only be applied once per type layer. `string?[]?` is valid because \
      `[]` creates a new outer list layer.";
+/// Expected-token lists longer than this are omitted because they stop being actionable.
+const MAX_EXPECTED_TOKENS: usize = 6;
 const UNION_DEFINITION_SYNTAX: &str =
     "Expected: type UnionName [extends AbstractRecord] = | caseName | payloadCase { prop:type }";
 
@@ -52,18 +59,100 @@ pub fn validate(tree: &SyntaxTree, file_name: &str) -> Vec<Diagnostic> {
     // Validate element tag matching
     validate_element_tags(&root, tree, file_name, &mut diagnostics);
 
+    // Suggest the self-closing form for elements with an explicit close tag and no content
+    validate_empty_elements(&root, file_name, &mut diagnostics);
+
     // Validate root definitions (no duplicates between explicit 'root' and top-level element)
     validate_root_definitions(&root, file_name, &mut diagnostics);
 
     // Validate component declarations that depend on modifier/body combinations.
-    validate_component_definitions(&root, file_name, &mut diagnostics);
+    for child in root.children() {
+        validate_component_definition(&child, file_name, &mut diagnostics);
+    }
 
     // Validate union declarations that depend on complete case metadata.
-    validate_union_definitions(&root, file_name, &mut diagnostics);
+    for child in root.children() {
+        validate_union_definition(&child, file_name, &mut diagnostics);
+    }
+
+    // Validate record and action declarations that repeat a field name.
+    for child in root.children() {
+        validate_record_definition(&child, file_name, &mut diagnostics);
+    }
+
+    diagnostics
+}
 
+/// Reports syntax that is not available under the selected language version.
+///
+/// Diagnostics are emitted for the outermost node of each gated construct only, so a union with
+/// many cases produces a single error.
+pub fn validate_language_version(
+    tree: &SyntaxTree,
+    file_name: &str,
+    options: &ParseOptions,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if !options.allows_all() {
+        validate_syntax_features(&tree.root(), file_name, options, &mut diagnostics);
+    }
     diagnostics
 }
 
+fn validate_syntax_features(
+    node: &SyntaxNode,
+    file_name: &str,
+    options: &ParseOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let Some(feature) = SyntaxFeature::for_node(node) {
+        if !options.allows(feature) {
+            diagnostics.push(unsupported_feature_diagnostic(
+                feature,
+                node.span(),
+                file_name,
+                options,
+            ));
+            return;
+        }
+    }
+
+    for child in node.children() {
+        validate_syntax_features(&child, file_name, options, diagnostics);
+    }
+}
+
+fn unsupported_feature_diagnostic(
+    feature: SyntaxFeature,
+    span: TextRange,
+    file_name: &str,
+    options: &ParseOptions,
+) -> Diagnostic {
+    match feature.introduced_in() {
+        Some(version) => Diagnostic::error("unsupported-language-version")
+            .with_message(format!(
+                "{} require NX language version {} or later",
+                feature.description(),
+                version
+            ))
+            .with_label(Label::primary(file_name, span).with_message(format!(
+                "not available in language version {}",
+                options.language_version
+            )))
+            .with_help(format!(
+                "Select language version {version} or later, or rewrite this construct."
+            ))
+            .build(),
+        None => Diagnostic::error("experimental-syntax")
+            .with_message(format!("{} are experimental", feature.description()))
+            .with_label(
+                Label::primary(file_name, span).with_message("experimental syntax used here"),
+            )
+            .with_help("Enable experimental syntax in the parse options to use this construct.")
+            .build(),
+    }
+}
+
 fn validate_type_suffixes(node: &SyntaxNode, file_name: &str, diagnostics: &mut Vec<Diagnostic>) {
     if node.kind() == SyntaxKind::TYPE && !node.has_error() {
         validate_type_suffix_chain(node, file_name, diagnostics);
@@ -116,146 +205,196 @@ fn validate_type_suffix_chain(
     }
 }
 
-fn validate_component_definitions(
-    root: &SyntaxNode,
+fn validate_component_definition(
+    node: &SyntaxNode,
     file_name: &str,
     diagnostics: &mut Vec<Diagnostic>,
 ) {
-    for child in root.children() {
-        let Some(component) = ComponentDef::cast(child) else {
-            continue;
-        };
+    let Some(component) = ComponentDef::cast(*node) else {
+        return;
+    };
+
+    let is_abstract = component.is_abstract();
+    let is_external = component.is_external();
+    let body = component.body();
+    let has_body = body.is_some();
+    let has_state = body
+        .and_then(|body| body.child_by_field("state"))
+        .is_some_and(|node| !node.raw().is_missing());
+    let has_render_body = body
+        .and_then(|body| body.child_by_field("body"))
+        .is_some_and(|node| !node.raw().is_missing());
+
+    if !is_abstract && !is_external && !has_body {
+        diagnostics.push(
+            Diagnostic::error("invalid-component-definition")
+                .with_message("Concrete components must declare a body")
+                .with_label(
+                    Label::primary(file_name, component.syntax().span())
+                        .with_message("bodyless component declaration"),
+                )
+                .with_note(COMPONENT_DEFINITION_SYNTAX)
+                .build(),
+        );
+    }
+
+    if is_abstract && has_body {
+        diagnostics.push(
+            Diagnostic::error("invalid-component-definition")
+                .with_message("Abstract components cannot declare a body or local state")
+                .with_label(
+                    Label::primary(file_name, component.syntax().span())
+                        .with_message("remove the component body"),
+                )
+                .with_note(COMPONENT_DEFINITION_SYNTAX)
+                .build(),
+        );
+    }
 
-        let is_abstract = component.is_abstract();
-        let is_external = component.is_external();
-        let body = component.body();
-        let has_body = body.is_some();
-        let has_state = body
-            .and_then(|body| body.child_by_field("state"))
-            .is_some_and(|node| !node.raw().is_missing());
-        let has_render_body = body
-            .and_then(|body| body.child_by_field("body"))
-            .is_some_and(|node| !node.raw().is_missing());
-
-        if !is_abstract && !is_external && !has_body {
+    if is_external && has_body {
+        if !has_state {
             diagnostics.push(
                 Diagnostic::error("invalid-component-definition")
-                    .with_message("Concrete components must declare a body")
+                    .with_message("External component bodies must declare state")
                     .with_label(
                         Label::primary(file_name, component.syntax().span())
-                            .with_message("bodyless component declaration"),
+                            .with_message("add a state block or remove the body"),
                     )
                     .with_note(COMPONENT_DEFINITION_SYNTAX)
                     .build(),
             );
-        }
-
-        if is_abstract && has_body {
+        } else if has_render_body {
             diagnostics.push(
                 Diagnostic::error("invalid-component-definition")
-                    .with_message("Abstract components cannot declare a body or local state")
+                    .with_message("External component bodies can only declare state")
                     .with_label(
                         Label::primary(file_name, component.syntax().span())
-                            .with_message("remove the component body"),
+                            .with_message("remove the rendered body expression"),
                     )
                     .with_note(COMPONENT_DEFINITION_SYNTAX)
                     .build(),
             );
         }
+    }
 
-        if is_external && has_body {
-            if !has_state {
-                diagnostics.push(
-                    Diagnostic::error("invalid-component-definition")
-                        .with_message("External component bodies must declare state")
-                        .with_label(
-                            Label::primary(file_name, component.syntax().span())
-                                .with_message("add a state block or remove the body"),
-                        )
-                        .with_note(COMPONENT_DEFINITION_SYNTAX)
-                        .build(),
-                );
-            } else if has_render_body {
-                diagnostics.push(
-                    Diagnostic::error("invalid-component-definition")
-                        .with_message("External component bodies can only declare state")
-                        .with_label(
-                            Label::primary(file_name, component.syntax().span())
-                                .with_message("remove the rendered body expression"),
-                        )
-                        .with_note(COMPONENT_DEFINITION_SYNTAX)
-                        .build(),
-                );
-            }
-        }
+    if !is_abstract && !is_external && has_body && !has_render_body {
+        diagnostics.push(
+            Diagnostic::error("invalid-component-definition")
+                .with_message("Concrete components must declare a rendered body expression")
+                .with_label(
+                    Label::primary(file_name, component.syntax().span())
+                        .with_message("add a rendered body expression"),
+                )
+                .with_note(COMPONENT_DEFINITION_SYNTAX)
+                .build(),
+        );
+    }
+}
+
+fn validate_union_definition(
+    node: &SyntaxNode,
+    file_name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(union_def) = UnionDef::cast(*node) else {
+        return;
+    };
+
+    let mut seen_cases: Vec<(String, TextRange)> = Vec::new();
+
+    for case in union_def.case_definitions() {
+        let Some(name) = case.child_by_field("name") else {
+            continue;
+        };
+        let case_name = name.text().to_string();
+
+        if let Some((_, first_span)) = seen_cases
+            .iter()
+            .find(|(previous_name, _)| previous_name == &case_name)
+        {
+            let union_name = union_def
+                .name()
+                .map(|name| name.text().to_string())
+                .unwrap_or_else(|| "<unknown>".to_string());
 
-        if !is_abstract && !is_external && has_body && !has_render_body {
             diagnostics.push(
-                Diagnostic::error("invalid-component-definition")
-                    .with_message("Concrete components must declare a rendered body expression")
+                Diagnostic::error("duplicate-union-case")
+                    .with_message(format!(
+                        "Duplicate case '{}' in union '{}'",
+                        case_name, union_name
+                    ))
                     .with_label(
-                        Label::primary(file_name, component.syntax().span())
-                            .with_message("add a rendered body expression"),
+                        Label::primary(file_name, name.span())
+                            .with_message("duplicate case declared here"),
                     )
-                    .with_note(COMPONENT_DEFINITION_SYNTAX)
+                    .with_label(
+                        Label::secondary(file_name, *first_span)
+                            .with_message("first case declared here"),
+                    )
+                    .with_note("Each discriminated union case name must be unique.")
                     .build(),
             );
+        } else {
+            seen_cases.push((case_name, name.span()));
         }
     }
 }
 
-fn validate_union_definitions(
-    root: &SyntaxNode,
+/// Validates that element opening and closing tags match.
+fn validate_record_definition(
+    node: &SyntaxNode,
     file_name: &str,
     diagnostics: &mut Vec<Diagnostic>,
 ) {
-    for child in root.children() {
-        let Some(union_def) = UnionDef::cast(child) else {
+    let Some(record_def) = RecordDef::cast(*node) else {
+        return;
+    };
+
+    let mut seen_fields: Vec<(String, TextRange)> = Vec::new();
+
+    for property in record_def.properties() {
+        let Some(name) = property.child_by_field("name") else {
             continue;
         };
+        let field_name = name.text().to_string();
 
-        let mut seen_cases: Vec<(String, TextRange)> = Vec::new();
-
-        for case in union_def.case_definitions() {
-            let Some(name) = case.child_by_field("name") else {
-                continue;
+        if let Some((_, first_span)) = seen_fields
+            .iter()
+            .find(|(previous_name, _)| previous_name == &field_name)
+        {
+            let record_name = record_def
+                .name()
+                .map(|name| name.text().to_string())
+                .unwrap_or_else(|| "<unknown>".to_string());
+            let kind = if record_def.is_action() {
+                "Action"
+            } else {
+                "Record"
             };
-            let case_name = name.text().to_string();
 
-            if let Some((_, first_span)) = seen_cases
-                .iter()
-                .find(|(previous_name, _)| previous_name == &case_name)
-            {
-                let union_name = union_def
-                    .name()
-                    .map(|name| name.text().to_string())
-                    .unwrap_or_else(|| "<unknown>".to_string());
-
-                diagnostics.push(
-                    Diagnostic::error("duplicate-union-case")
-                        .with_message(format!(
-                            "Duplicate case '{}' in union '{}'",
-                            case_name, union_name
-                        ))
-                        .with_label(
-                            Label::primary(file_name, name.span())
-                                .with_message("duplicate case declared here"),
-                        )
-                        .with_label(
-                            Label::secondary(file_name, *first_span)
-                                .with_message("first case declared here"),
-                        )
-                        .with_note("Each discriminated union case name must be unique.")
-                        .build(),
-                );
-            } else {
-                seen_cases.push((case_name, name.span()));
-            }
+            diagnostics.push(
+                Diagnostic::error("duplicate-field")
+                    .with_message(format!(
+                        "{} '{}' declares field '{}' more than once",
+                        kind, record_name, field_name
+                    ))
+                    .with_label(
+                        Label::primary(file_name, name.span())
+                            .with_message("duplicate field declared here"),
+                    )
+                    .with_label(
+                        Label::secondary(file_name, *first_span)
+                            .with_message("first field declared here"),
+                    )
+                    .with_note("Each field name in a record must be unique.")
+                    .build(),
+            );
+        } else {
+            seen_fields.push((field_name, name.span()));
         }
     }
 }
 
-/// Validates that element opening and closing tags match.
 fn validate_element_tags(
     node: &SyntaxNode,
     _tree: &SyntaxTree,
@@ -311,6 +450,43 @@ fn validate_element_tags(
     }
 }
 
+/// Reports `<div></div>` as a hint with a fix that rewrites it to the canonical `<div />`.
+fn validate_empty_elements(node: &SyntaxNode, file_name: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if node.kind() == SyntaxKind::ELEMENT
+        && node.child_by_field("close_name").is_some()
+        && node.child_by_field("content").is_none()
+    {
+        let open_end = node
+            .child_by_field("properties")
+            .or_else(|| node.child_by_field("name"))
+            .map(|child| child.span().end());
+        let name = node
+            .child_by_field("name")
+            .and_then(|name| extract_tag_name(&name));
+
+        if let (Some(open_end), Some(name)) = (open_end, name) {
+            let element = node.span();
+            let diagnostic = Diagnostic::hint("empty-element")
+                .with_message(format!("Element '{}' has no content", name))
+                .with_label(Label::primary(file_name, element))
+                .with_help(format!(
+                    "Write it as a self-closing element: `<{} />`",
+                    name
+                ))
+                .with_fix(Fix::new(
+                    "Use a self-closing tag",
+                    TextEdit::replace(file_name, TextRange::new(open_end, element.end()), " />"),
+                ))
+                .build();
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    for child in node.children() {
+        validate_empty_elements(&child, file_name, diagnostics);
+    }
+}
+
 /// Extracts the tag name from an element tag node.
 fn extract_tag_name(tag_node: &SyntaxNode) -> Option<String> {
     for child in tag_node.children() {
@@ -341,13 +517,14 @@ fn extract_tag_name(tag_node: &SyntaxNode) -> Option<String> {
 /// This function detects:
 /// - Multiple explicit 'root' definitions (error)
 /// - Both explicit 'root' and top-level element (error)
+/// - More than one top-level element (warning); only the first one becomes 'root'
 fn validate_root_definitions(
     root: &SyntaxNode,
     file_name: &str,
     diagnostics: &mut Vec<Diagnostic>,
 ) {
     let mut explicit_roots: Vec<TextRange> = Vec::new();
-    let mut implicit_root: Option<TextRange> = None;
+    let mut implicit_roots: Vec<TextRange> = Vec::new();
 
     // Scan top-level children of the module
     for child in root.children() {
@@ -362,7 +539,7 @@ fn validate_root_definitions(
             }
             SyntaxKind::ELEMENT => {
                 // Top-level element becomes implicit 'root'
-                implicit_root = Some(child.span());
+                implicit_roots.push(child.span());
             }
             _ => {}
         }
@@ -389,9 +566,10 @@ fn validate_root_definitions(
     }
 
     // Check for conflict between explicit root and top-level element
-    if let (Some(explicit_span), Some(implicit_span)) =
-        (explicit_roots.first().copied(), implicit_root)
-    {
+    if let (Some(explicit_span), Some(implicit_span)) = (
+        explicit_roots.first().copied(),
+        implicit_roots.first().copied(),
+    ) {
         let diagnostic = Diagnostic::error("duplicate-root")
             .with_message("Duplicate definition of 'root'")
             .with_label(
@@ -409,32 +587,187 @@ fn validate_root_definitions(
 
         diagnostics.push(diagnostic);
     }
+
+    // Check for top-level elements after the first, which are never evaluated
+    if let Some((first_span, rest)) = implicit_roots.split_first() {
+        for span in rest {
+            let diagnostic = Diagnostic::warning("multiple-root-elements")
+                .with_message("Only the first top-level element defines 'root'")
+                .with_label(
+                    Label::primary(file_name, *span).with_message("this element is ignored"),
+                )
+                .with_label(
+                    Label::secondary(file_name, *first_span)
+                        .with_message("'root' is defined by this element"),
+                )
+                .with_help("Wrap the top-level elements in a single parent element.")
+                .build();
+
+            diagnostics.push(diagnostic);
+        }
+    }
 }
 
-/// Collects all parse errors from tree-sitter ERROR nodes with enhanced messages.
-///
-/// This function walks the CST and converts tree-sitter ERROR and MISSING nodes
-/// into rich `Diagnostic` messages with context-aware suggestions.
-///
-/// # Arguments
-///
-/// * `tree` - The tree-sitter parse tree
-/// * `source` - The original source code
-/// * `file_name` - The name of the file being parsed (for error messages)
-///
-/// # Returns
+/// Passes run on every top-level node, in the order a whole-module validation reports them.
+#[derive(Debug, Clone, Copy)]
+enum ItemPass {
+    Syntax,
+    TypeSuffixes,
+    ElementTags,
+    EmptyElements,
+    Components,
+    Unions,
+    Records,
+    LanguageVersion,
+}
+
+const ITEM_PASSES: usize = ItemPass::LanguageVersion as usize + 1;
+
+/// Diagnostics of one top-level node of a module, grouped by the pass that produced them.
+#[derive(Debug, Clone)]
+pub(crate) struct ItemDiagnostics {
+    /// Byte range of the node in the source it was validated against.
+    pub(crate) range: Range<usize>,
+    passes: [Vec<Diagnostic>; ITEM_PASSES],
+}
+
+impl ItemDiagnostics {
+    /// Returns these diagnostics for the same node moved by `delta` bytes.
+    pub(crate) fn shifted(&self, delta: isize) -> Self {
+        let shift = |offset: usize| offset.checked_add_signed(delta).expect("shifted in bounds");
+        let shift_size = |size: TextSize| TextSize::from(shift(usize::from(size)) as u32);
+        Self {
+            range: shift(self.range.start)..shift(self.range.end),
+            passes: self.passes.clone().map(|diagnostics| {
+                diagnostics
+                    .into_iter()
+                    .map(|diagnostic| {
+                        diagnostic.map_ranges(|range| {
+                            TextRange::new(shift_size(range.start()), shift_size(range.end()))
+                        })
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// All diagnostics of a parsed module, kept per top-level node so that a reparse after an edit
+/// only has to revalidate the nodes the edit touched.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ModuleDiagnostics {
+    /// A syntax error covering the module node itself.
+    module_error: Option<Diagnostic>,
+    pub(crate) items: Vec<ItemDiagnostics>,
+    /// Checks across top-level nodes, which are cheap enough to rerun on every parse.
+    root_definitions: Vec<Diagnostic>,
+}
+
+impl ModuleDiagnostics {
+    /// Returns every diagnostic, in the same order as the separate parse and validation passes.
+    pub(crate) fn to_vec(&self) -> Vec<Diagnostic> {
+        let pass = |pass: ItemPass| {
+            self.items
+                .iter()
+                .flat_map(move |item| &item.passes[pass as usize])
+        };
+        self.module_error
+            .iter()
+            .chain(pass(ItemPass::Syntax))
+            .chain(pass(ItemPass::TypeSuffixes))
+            .chain(pass(ItemPass::ElementTags))
+            .chain(pass(ItemPass::EmptyElements))
+            .chain(&self.root_definitions)
+            .chain(pass(ItemPass::Components))
+            .chain(pass(ItemPass::Unions))
+            .chain(pass(ItemPass::Records))
+            .chain(pass(ItemPass::LanguageVersion))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Collects the syntax errors, validation diagnostics and language version errors of a module.
 ///
-/// A vector of diagnostic messages for all syntax errors found in the tree.
-pub fn collect_enhanced_errors(
-    tree: &tree_sitter::Tree,
-    source: &str,
+/// `reuse` is called with the index and node of every top-level node and may return diagnostics
+/// from an earlier parse in place of validating the node again.
+pub(crate) fn validate_module(
+    tree: &SyntaxTree,
     file_name: &str,
-) -> Vec<Diagnostic> {
-    let mut errors = Vec::new();
-    let root = tree.root_node();
+    options: &ParseOptions,
+    mut reuse: impl FnMut(usize, tree_sitter::Node) -> Option<ItemDiagnostics>,
+) -> ModuleDiagnostics {
+    let root = tree.root();
+    let mut cursor = root.raw().walk();
+    let items = root
+        .raw()
+        .children(&mut cursor)
+        .enumerate()
+        .map(|(index, node)| {
+            reuse(index, node).unwrap_or_else(|| validate_item(tree, node, file_name, options))
+        })
+        .collect();
+
+    let mut root_definitions = Vec::new();
+    validate_root_definitions(&root, file_name, &mut root_definitions);
+
+    ModuleDiagnostics {
+        module_error: syntax_error(root.raw(), tree.source(), file_name),
+        items,
+        root_definitions,
+    }
+}
+
+fn validate_item(
+    tree: &SyntaxTree,
+    node: tree_sitter::Node,
+    file_name: &str,
+    options: &ParseOptions,
+) -> ItemDiagnostics {
+    let mut passes: [Vec<Diagnostic>; ITEM_PASSES] = Default::default();
+    walk_and_collect_errors(
+        node,
+        tree.source(),
+        file_name,
+        &mut passes[ItemPass::Syntax as usize],
+    );
+
+    // Validation walks named nodes only, so anonymous tokens at the top level are skipped.
+    if node.is_named() {
+        let node = SyntaxNode::new(node, tree.source());
+        validate_type_suffixes(
+            &node,
+            file_name,
+            &mut passes[ItemPass::TypeSuffixes as usize],
+        );
+        validate_element_tags(
+            &node,
+            tree,
+            file_name,
+            &mut passes[ItemPass::ElementTags as usize],
+        );
+        validate_empty_elements(
+            &node,
+            file_name,
+            &mut passes[ItemPass::EmptyElements as usize],
+        );
+        validate_component_definition(&node, file_name, &mut passes[ItemPass::Components as usize]);
+        validate_union_definition(&node, file_name, &mut passes[ItemPass::Unions as usize]);
+        validate_record_definition(&node, file_name, &mut passes[ItemPass::Records as usize]);
+        if !options.allows_all() {
+            validate_syntax_features(
+                &node,
+                file_name,
+                options,
+                &mut passes[ItemPass::LanguageVersion as usize],
+            );
+        }
+    }
 
-    walk_and_collect_errors(root, source, file_name, &mut errors);
-    errors
+    ItemDiagnostics {
+        range: node.byte_range(),
+        passes,
+    }
 }
 
 /// Recursively walks the tree and collects errors with context-aware messages.
@@ -444,30 +777,7 @@ fn walk_and_collect_errors(
     file_name: &str,
     errors: &mut Vec<Diagnostic>,
 ) {
-    if node.is_error() || node.is_missing() {
-        let raw_start = u32::try_from(node.start_byte())
-            .expect("NX source size should be validated before collecting syntax diagnostics");
-        let raw_end = u32::try_from(node.end_byte())
-            .expect("NX source size should be validated before collecting syntax diagnostics");
-
-        // Get the text of the error node for context
-        let error_text = &source[raw_start as usize..raw_end.min(source.len() as u32) as usize];
-
-        // Generate context-aware error message
-        let (message, suggestion) = analyze_error_context(&node, error_text, source);
-        let (start, end) = refine_error_range(raw_start, raw_end, error_text, &message);
-        let range = TextRange::new(start.into(), end.into());
-
-        let mut diagnostic_builder = Diagnostic::error("syntax-error")
-            .with_message(message)
-            .with_label(Label::primary(file_name, range).with_message("unexpected syntax here"));
-
-        if let Some(note) = suggestion {
-            diagnostic_builder = diagnostic_builder.with_note(note);
-        }
-
-        errors.push(diagnostic_builder.build());
-    }
+    errors.extend(syntax_error(node, source, file_name));
 
     // Recursively check children
     let mut cursor = node.walk();
@@ -476,6 +786,40 @@ fn walk_and_collect_errors(
     }
 }
 
+/// Converts a tree-sitter ERROR or MISSING node into a diagnostic with a context-aware message.
+fn syntax_error(node: tree_sitter::Node, source: &str, file_name: &str) -> Option<Diagnostic> {
+    if !node.is_error() && !node.is_missing() {
+        return None;
+    }
+
+    let raw_start = u32::try_from(node.start_byte())
+        .expect("NX source size should be validated before collecting syntax diagnostics");
+    let raw_end = u32::try_from(node.end_byte())
+        .expect("NX source size should be validated before collecting syntax diagnostics");
+
+    // Get the text of the error node for context
+    let error_text = &source[raw_start as usize..raw_end.min(source.len() as u32) as usize];
+
+    // Generate context-aware error message
+    let (message, suggestion) = analyze_error_context(&node, error_text, source);
+    let (start, end) = refine_error_range(raw_start, raw_end, error_text, &message);
+    let range = TextRange::new(start.into(), end.into());
+
+    let mut diagnostic_builder = Diagnostic::error("syntax-error")
+        .with_message(message)
+        .with_label(Label::primary(file_name, range).with_message("unexpected syntax here"));
+
+    if let Some(note) = suggestion {
+        diagnostic_builder = diagnostic_builder.with_note(note);
+    }
+
+    if let Some(help) = expected_token_help(&node, source) {
+        diagnostic_builder = diagnostic_builder.with_help(help);
+    }
+
+    Some(diagnostic_builder.build())
+}
+
 fn refine_error_range(start: u32, end: u32, error_text: &str, message: &str) -> (u32, u32) {
     let delimiter = match message {
         "Unclosed brace" => Some('{'),
@@ -545,7 +889,7 @@ fn analyze_error_context(
                 return (
                     "Invalid record definition".to_string(),
                     Some(
-                        "Expected: [abstract] type RecordName [extends BaseRecord] = { prop:type }"
+                        "Expected: [abstract] type RecordName [extends BaseRecord] = [Record & ...] { prop:type }"
                             .to_string(),
                     ),
                 );
@@ -709,6 +1053,164 @@ fn analyze_error_context(
     )
 }
 
+/// Describes what the parser could have accepted at the point where an ERROR node went wrong.
+///
+/// The first child of the ERROR node that is not valid in its own parse state is treated as the
+/// offending token. When every child is valid, as when `let f(x int) = { x }` recovers inside the
+/// function definition, the parser gave up on the token after the node, which is checked against
+/// the state after the node's last child. If the offending token is a word close to a keyword
+/// that was valid there, a "did you mean" suggestion is returned; otherwise the valid tokens are
+/// listed when there are few enough of them to be useful.
+///
+/// The listed tokens are placed after the token where they could first have been inserted,
+/// which is not always the one just before the offending token: in `let f(x int)`, `int` is
+/// accepted as part of the parameter name and `)` is the offending token, but the missing `:`
+/// belongs after `x`.
+fn expected_token_help(node: &tree_sitter::Node, source: &str) -> Option<String> {
+    if !node.is_error() {
+        return None;
+    }
+
+    let language = node.language();
+    let mut preceding: Vec<tree_sitter::Node> = Vec::new();
+    let mut cursor = node.walk();
+
+    for child in node.children(&mut cursor) {
+        if child.is_extra() {
+            continue;
+        }
+
+        let valid_symbols = symbols_valid_in(&language, child.parse_state());
+        if valid_symbols.is_empty() || valid_symbols.contains(&child.grammar_id()) {
+            preceding.push(child);
+            continue;
+        }
+
+        return describe_expected_tokens(&language, source, &preceding, &valid_symbols, child);
+    }
+
+    let next = first_token(node.next_sibling()?);
+    let valid_symbols = symbols_valid_in(&language, preceding.last()?.next_parse_state());
+    if valid_symbols.is_empty() || valid_symbols.contains(&next.grammar_id()) {
+        return None;
+    }
+    describe_expected_tokens(&language, source, &preceding, &valid_symbols, next)
+}
+
+/// Describes `valid_symbols`, the tokens that could have replaced `unexpected` after the tokens
+/// `preceding` it.
+fn describe_expected_tokens(
+    language: &tree_sitter::Language,
+    source: &str,
+    preceding: &[tree_sitter::Node],
+    valid_symbols: &[u16],
+    unexpected: tree_sitter::Node,
+) -> Option<String> {
+    let unexpected = source.get(unexpected.byte_range()).unwrap_or("").trim();
+    let keywords = valid_symbols
+        .iter()
+        .filter(|id| !language.node_kind_is_named(**id))
+        .filter_map(|id| language.node_kind_for_id(*id))
+        .filter(|kind| kind.chars().all(|c| c.is_ascii_alphabetic()));
+    if is_word(unexpected) {
+        if let Some(keyword) = suggest::closest_match(unexpected, keywords) {
+            return Some(format!("Did you mean `{keyword}`?"));
+        }
+    }
+
+    let mut expected: Vec<String> = Vec::new();
+    let mut expected_symbols: Vec<u16> = Vec::new();
+    for id in valid_symbols {
+        let Some(description) = describe_expected_symbol(language, *id) else {
+            continue;
+        };
+        expected_symbols.push(*id);
+        if !expected.contains(&description) {
+            expected.push(description);
+        }
+    }
+    if expected.is_empty() || expected.len() > MAX_EXPECTED_TOKENS {
+        return None;
+    }
+
+    // Move the insertion point back over tokens that every expected token could precede.
+    let mut insertion = preceding.len();
+    while insertion > 1 {
+        let before = symbols_valid_in(language, preceding[insertion - 1].parse_state());
+        if !expected_symbols.iter().all(|id| before.contains(id)) {
+            break;
+        }
+        insertion -= 1;
+    }
+
+    let expected = join_alternatives(&expected);
+    let after = insertion
+        .checked_sub(1)
+        .and_then(|index| source.get(preceding[index].byte_range()))
+        .map(str::trim)
+        .filter(|text| !text.is_empty() && text.len() <= 32 && !text.contains('\n'));
+    Some(match after {
+        Some(after) => format!("Expected {expected} after `{after}`"),
+        None => format!("Expected {expected}"),
+    })
+}
+
+/// Returns the first leaf token of `node`.
+fn first_token(mut node: tree_sitter::Node) -> tree_sitter::Node {
+    while let Some(child) = node.child(0) {
+        node = child;
+    }
+    node
+}
+
+/// Returns the symbols valid in parse state `state`, or none for the error and end states.
+fn symbols_valid_in(language: &tree_sitter::Language, state: u16) -> Vec<u16> {
+    if state == 0 || state == u16::MAX {
+        return Vec::new();
+    }
+    language
+        .lookahead_iterator(state)
+        .map(|symbols| symbols.collect())
+        .unwrap_or_default()
+}
+
+/// Returns a user-facing description of a terminal symbol, or `None` for grammar-internal
+/// symbols and comments that should not be listed as expected input.
+fn describe_expected_symbol(language: &tree_sitter::Language, id: u16) -> Option<String> {
+    if !language.node_kind_is_visible(id) {
+        return None;
+    }
+    let kind = language.node_kind_for_id(id)?;
+
+    if !language.node_kind_is_named(id) {
+        return Some(format!("'{kind}'"));
+    }
+
+    let description = match kind {
+        "identifier" => "a name",
+        "markup_identifier" => "a property name",
+        "string_literal" => "a string",
+        "int_literal" | "real_literal" | "hex_literal" | "binary_literal" => "a number",
+        "bool_literal" => "`true` or `false`",
+        "null_literal" => "`null`",
+        _ => return None,
+    };
+    Some(description.to_string())
+}
+
+fn join_alternatives(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [first, second] => format!("{first} or {second}"),
+        [rest @ .., last] => format!("{}, or {last}", rest.join(", ")),
+    }
+}
+
+fn is_word(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 fn looks_like_type_definition_prefix(node: &tree_sitter::Node, source: &str) -> bool {
     let Some(prefix) = source.get(..node.start_byte()) else {
         return false;
@@ -739,6 +1241,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_empty_element_suggests_self_closing_form() {
+        let source = r#"<div class="x"><span></span></div>"#;
+        let result = parse_str(source, "test.nx");
+        let tree = result.tree.unwrap();
+
+        let diagnostics = validate(&tree, "test.nx");
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.code(), Some("empty-element"));
+        assert_eq!(diagnostic.severity(), nx_diagnostics::Severity::Hint);
+        assert_eq!(diagnostic.message(), "Element 'span' has no content");
+
+        let edit = &diagnostic.fixes()[0].edits[0];
+        let mut fixed = source.to_string();
+        fixed.replace_range(
+            usize::from(edit.range.start())..usize::from(edit.range.end()),
+            &edit.replacement,
+        );
+        assert_eq!(fixed, r#"<div class="x"><span /></div>"#);
+    }
+
     #[test]
     fn test_validate_mismatched_tags() {
         let source = "<Button>content</Input>";
@@ -1016,6 +1540,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_multiple_top_level_elements_warns_about_ignored_ones() {
+        let source = "<Header />\n<Main />\n<Footer />\n";
+        let result = parse_str(source, "test.nx");
+        let tree = result.tree.unwrap();
+
+        let diagnostics = validate(&tree, "test.nx");
+        let ignored: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code() == Some("multiple-root-elements"))
+            .map(|d| {
+                assert_eq!(d.severity(), nx_diagnostics::Severity::Warning);
+                let [primary, secondary] = d.labels() else {
+                    panic!("Expected the ignored element and the root element to be labelled");
+                };
+                (
+                    &source[primary.range.start().into()..primary.range.end().into()],
+                    &source[secondary.range.start().into()..secondary.range.end().into()],
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            ignored,
+            vec![("<Main />", "<Header />"), ("<Footer />", "<Header />")]
+        );
+        assert!(!diagnostics
+            .iter()
+            .any(|d| d.code() == Some("duplicate-root")));
+    }
+
     #[test]
     fn test_validate_duplicate_root_value_and_element() {
         // Both explicit root value and top-level element should produce error
@@ -1354,4 +1909,79 @@ mod tests {
             "Expected state-only concrete component diagnostic, got: {messages}"
         );
     }
+
+    #[test]
+    fn test_syntax_error_lists_expected_tokens_after_parameter_name() {
+        let result = parse_str("let f(x int) = x", "test.nx");
+
+        let helps: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|diagnostic| diagnostic.code() == Some("syntax-error"))
+            .filter_map(|diagnostic| diagnostic.help())
+            .collect();
+
+        assert_eq!(helps, vec!["Expected ':' after `x`"]);
+    }
+
+    #[test]
+    fn test_syntax_error_lists_expected_tokens_after_parameter_name_with_braced_body() {
+        let result = parse_str("let f(x int) = { x }", "test.nx");
+
+        let helps: Vec<_> = result
+            .errors
+            .iter()
+            .filter(|diagnostic| diagnostic.code() == Some("syntax-error"))
+            .filter_map(|diagnostic| diagnostic.help())
+            .collect();
+
+        assert_eq!(helps, vec!["Expected ':' after `x`"]);
+    }
+
+    #[test]
+    fn test_syntax_error_lists_multiple_expected_tokens() {
+        let result = parse_str("let f(x: int) int = x", "test.nx");
+
+        assert!(
+            result
+                .errors
+                .iter()
+                .any(|diagnostic| diagnostic.help() == Some("Expected '=' or ':' after `)`")),
+            "Expected a help listing '=' and ':', got: {:?}",
+            result.errors
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_suggests_misspelled_keyword() {
+        for (source, keyword) in [
+            ("tpye Foo = { a: int }", "type"),
+            ("componet <Button />", "component"),
+            ("improt \"./lib\"", "import"),
+        ] {
+            let result = parse_str(source, "test.nx");
+            let expected = format!("Did you mean `{keyword}`?");
+
+            assert!(
+                result
+                    .errors
+                    .iter()
+                    .any(|diagnostic| diagnostic.help() == Some(expected.as_str())),
+                "Expected keyword suggestion for {source:?}, got: {:?}",
+                result.errors
+            );
+        }
+    }
+
+    #[test]
+    fn test_join_alternatives_formats_lists() {
+        let items = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(join_alternatives(&items(&["'='"])), "'='");
+        assert_eq!(join_alternatives(&items(&["'='", "':'"])), "'=' or ':'");
+        assert_eq!(
+            join_alternatives(&items(&["'='", "'{'", "':'"])),
+            "'=', '{', or ':'"
+        );
+    }
 }
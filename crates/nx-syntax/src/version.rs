@@ -0,0 +1,295 @@
+//! Language versions and version-gated syntax features.
+//!
+//! The grammar always parses the newest NX syntax. Older templates are supported by selecting a
+//! [`LanguageVersion`] in [`ParseOptions`]; validation then reports any construct that was not
+//! part of the selected version instead of silently accepting it.
+
+use crate::{ParseLimits, SyntaxKind, SyntaxNode};
+use std::fmt;
+
+/// A released revision of the NX language syntax.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LanguageVersion {
+    /// The original NX syntax: records, actions, enums, aliases, functions, and components.
+    V1,
+    /// Adds discriminated union definitions, `if` fragments inside element property lists, rest
+    /// props and property spreads, `provide`/`use` context expressions, destructuring `let`
+    /// bindings, `_` placeholder arguments, `meta` blocks, digit separators in number literals,
+    /// binary, hex-float and exponent-only number literals, `[a, b]` array literals, record
+    /// composition with `&`, property shorthand and `@` field constraints.
+    #[default]
+    V2,
+}
+
+impl LanguageVersion {
+    /// The newest language version understood by the parser.
+    pub const LATEST: LanguageVersion = LanguageVersion::V2;
+
+    /// Returns every known language version, oldest first.
+    pub fn all() -> &'static [LanguageVersion] {
+        &[LanguageVersion::V1, LanguageVersion::V2]
+    }
+
+    /// Returns the version number as written in configuration files (e.g. `"2"`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LanguageVersion::V1 => "1",
+            LanguageVersion::V2 => "2",
+        }
+    }
+
+    /// Parses a version number such as `"2"` or `"v2"`.
+    pub fn parse(text: &str) -> Option<LanguageVersion> {
+        let text = text.trim();
+        let text = text
+            .strip_prefix('v')
+            .or_else(|| text.strip_prefix('V'))
+            .unwrap_or(text);
+        LanguageVersion::all()
+            .iter()
+            .copied()
+            .find(|version| version.as_str() == text)
+    }
+}
+
+impl fmt::Display for LanguageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Options controlling how NX source is parsed and validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    /// Language version the source is written against.
+    pub language_version: LanguageVersion,
+    /// Whether syntax that has not yet shipped in a released language version is accepted.
+    pub allow_experimental_syntax: bool,
+    /// Size and shape limits enforced before the syntax tree is returned.
+    pub limits: ParseLimits,
+}
+
+impl ParseOptions {
+    /// Creates options for the given language version with experimental syntax disabled.
+    pub fn for_version(language_version: LanguageVersion) -> Self {
+        Self {
+            language_version,
+            allow_experimental_syntax: false,
+            limits: ParseLimits::default(),
+        }
+    }
+
+    /// Returns a copy of these options with experimental syntax enabled or disabled.
+    pub fn with_experimental_syntax(mut self, allow: bool) -> Self {
+        self.allow_experimental_syntax = allow;
+        self
+    }
+
+    /// Returns a copy of these options with different parse limits.
+    pub fn with_limits(mut self, limits: ParseLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns true if every known syntax feature may be used under these options.
+    pub fn allows_all(&self) -> bool {
+        SyntaxFeature::all()
+            .iter()
+            .all(|feature| self.allows(*feature))
+    }
+
+    /// Returns true if `feature` may be used under these options.
+    pub fn allows(&self, feature: SyntaxFeature) -> bool {
+        match feature.introduced_in() {
+            Some(version) => version <= self.language_version,
+            None => self.allow_experimental_syntax,
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::for_version(LanguageVersion::LATEST)
+    }
+}
+
+/// A piece of syntax whose availability depends on the selected language version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxFeature {
+    /// `type Name = | case | case { ... }` declarations.
+    DiscriminatedUnions,
+    /// `if` fragments inside an element's property list.
+    PropertyListFragments,
+    /// `...rest:props` declarations and `{...rest}` spreads in element property lists.
+    RestProps,
+    /// `provide name = value { ... }` and `use name` expressions.
+    ContextExpressions,
+    /// `let [a, b] = ...` and `let { a, b } = ...` bindings.
+    Destructuring,
+    /// `_` arguments that partially apply a call.
+    PlaceholderArguments,
+    /// `meta Type { ... }` module metadata blocks.
+    MetaBlocks,
+    /// `_` between the digits of a number literal, as in `1_000`.
+    DigitSeparators,
+    /// `0b1010` binary integer literals.
+    BinaryLiterals,
+    /// Real literals without a fraction, such as `1e6`, and hex floats such as `0x1.8p3`.
+    ExtendedRealLiterals,
+    /// `[a, b]` array literals.
+    ArrayLiterals,
+    /// `type Admin = User & Audited & { ... }` record definitions.
+    RecordComposition,
+    /// `{name, age}` in an element's property list.
+    PropertyShorthand,
+    /// `@min(0)` style constraints on record fields.
+    FieldConstraints,
+}
+
+impl SyntaxFeature {
+    /// Returns every version-gated syntax feature.
+    pub fn all() -> &'static [SyntaxFeature] {
+        &[
+            SyntaxFeature::DiscriminatedUnions,
+            SyntaxFeature::PropertyListFragments,
+            SyntaxFeature::RestProps,
+            SyntaxFeature::ContextExpressions,
+            SyntaxFeature::Destructuring,
+            SyntaxFeature::PlaceholderArguments,
+            SyntaxFeature::MetaBlocks,
+            SyntaxFeature::DigitSeparators,
+            SyntaxFeature::BinaryLiterals,
+            SyntaxFeature::ExtendedRealLiterals,
+            SyntaxFeature::ArrayLiterals,
+            SyntaxFeature::RecordComposition,
+            SyntaxFeature::PropertyShorthand,
+            SyntaxFeature::FieldConstraints,
+        ]
+    }
+
+    /// Returns the language version that introduced this feature, or `None` while the feature is
+    /// still experimental.
+    pub fn introduced_in(&self) -> Option<LanguageVersion> {
+        match self {
+            SyntaxFeature::DiscriminatedUnions
+            | SyntaxFeature::PropertyListFragments
+            | SyntaxFeature::RestProps
+            | SyntaxFeature::ContextExpressions
+            | SyntaxFeature::Destructuring
+            | SyntaxFeature::PlaceholderArguments
+            | SyntaxFeature::MetaBlocks
+            | SyntaxFeature::DigitSeparators
+            | SyntaxFeature::BinaryLiterals
+            | SyntaxFeature::ExtendedRealLiterals
+            | SyntaxFeature::ArrayLiterals
+            | SyntaxFeature::RecordComposition
+            | SyntaxFeature::PropertyShorthand
+            | SyntaxFeature::FieldConstraints => Some(LanguageVersion::V2),
+        }
+    }
+
+    /// Returns true if the feature has not shipped in any released language version yet.
+    pub fn is_experimental(&self) -> bool {
+        self.introduced_in().is_none()
+    }
+
+    /// Returns a short human-readable description used in diagnostics.
+    pub fn description(&self) -> &'static str {
+        match self {
+            SyntaxFeature::DiscriminatedUnions => "Discriminated union definitions",
+            SyntaxFeature::PropertyListFragments => "Property list `if` fragments",
+            SyntaxFeature::RestProps => "Rest props and property spreads",
+            SyntaxFeature::ContextExpressions => "`provide` and `use` expressions",
+            SyntaxFeature::Destructuring => "Destructuring bindings",
+            SyntaxFeature::PlaceholderArguments => "`_` placeholder arguments",
+            SyntaxFeature::MetaBlocks => "`meta` blocks",
+            SyntaxFeature::DigitSeparators => "Digit separators",
+            SyntaxFeature::BinaryLiterals => "Binary literals",
+            SyntaxFeature::ExtendedRealLiterals => "Hex-float and exponent-only real literals",
+            SyntaxFeature::ArrayLiterals => "`[...]` array literals",
+            SyntaxFeature::RecordComposition => "Record compositions with `&`",
+            SyntaxFeature::PropertyShorthand => "Property shorthands",
+            SyntaxFeature::FieldConstraints => "`@` field constraints",
+        }
+    }
+
+    /// Returns the feature introduced by a syntax node, if any.
+    ///
+    /// Most features have their own node kinds. Placeholder arguments are `_` identifiers passed
+    /// to a call, and digit separators and the newer real literal forms only show in the text of
+    /// number literals.
+    pub(crate) fn for_node(node: &SyntaxNode) -> Option<SyntaxFeature> {
+        match node.kind() {
+            SyntaxKind::UNION_DEFINITION => Some(SyntaxFeature::DiscriminatedUnions),
+            SyntaxKind::PROPERTY_LIST_IF_EXPRESSION => Some(SyntaxFeature::PropertyListFragments),
+            SyntaxKind::REST_PROPERTY_DEFINITION | SyntaxKind::PROPERTY_SPREAD => {
+                Some(SyntaxFeature::RestProps)
+            }
+            SyntaxKind::PROVIDE_EXPRESSION | SyntaxKind::USE_EXPRESSION => {
+                Some(SyntaxFeature::ContextExpressions)
+            }
+            SyntaxKind::ARRAY_BINDING_PATTERN | SyntaxKind::RECORD_BINDING_PATTERN => {
+                Some(SyntaxFeature::Destructuring)
+            }
+            SyntaxKind::META_BLOCK => Some(SyntaxFeature::MetaBlocks),
+            SyntaxKind::SEQUENCE_EXPRESSION => Some(SyntaxFeature::ArrayLiterals),
+            SyntaxKind::RECORD_COMPOSITION => Some(SyntaxFeature::RecordComposition),
+            SyntaxKind::BINARY_LITERAL => Some(SyntaxFeature::BinaryLiterals),
+            // Version 1 real literals always have digits on both sides of a `.`.
+            SyntaxKind::REAL_LITERAL
+                if node.text().starts_with("0x")
+                    || node.text().starts_with("0X")
+                    || !node.text().contains('.') =>
+            {
+                Some(SyntaxFeature::ExtendedRealLiterals)
+            }
+            SyntaxKind::PROPERTY_SHORTHAND => Some(SyntaxFeature::PropertyShorthand),
+            SyntaxKind::FIELD_CONSTRAINT => Some(SyntaxFeature::FieldConstraints),
+            SyntaxKind::INT_LITERAL | SyntaxKind::REAL_LITERAL | SyntaxKind::HEX_LITERAL
+                if node.text().contains('_') =>
+            {
+                Some(SyntaxFeature::DigitSeparators)
+            }
+            _ if node.text() == "_"
+                && node.prev_sibling().is_some()
+                && node
+                    .parent()
+                    .is_some_and(|parent| parent.kind() == SyntaxKind::CALL_EXPRESSION) =>
+            {
+                Some(SyntaxFeature::PlaceholderArguments)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_version_parse_accepts_prefixed_and_bare_numbers() {
+        assert_eq!(LanguageVersion::parse("1"), Some(LanguageVersion::V1));
+        assert_eq!(LanguageVersion::parse("v2"), Some(LanguageVersion::V2));
+        assert_eq!(LanguageVersion::parse(" V2 "), Some(LanguageVersion::V2));
+        assert_eq!(LanguageVersion::parse("3"), None);
+    }
+
+    #[test]
+    fn test_default_options_target_latest_version() {
+        let options = ParseOptions::default();
+        assert_eq!(options.language_version, LanguageVersion::LATEST);
+        assert!(!options.allow_experimental_syntax);
+        assert!(options.allows(SyntaxFeature::DiscriminatedUnions));
+    }
+
+    #[test]
+    fn test_older_version_rejects_newer_features() {
+        let options = ParseOptions::for_version(LanguageVersion::V1);
+        assert!(!options.allows(SyntaxFeature::DiscriminatedUnions));
+        assert!(!options.allows(SyntaxFeature::PropertyListFragments));
+        assert!(SyntaxFeature::all()
+            .iter()
+            .all(|feature| !options.allows(*feature)));
+    }
+}
@@ -3,7 +3,9 @@
 mod tree_helpers;
 
 use nx_diagnostics::render_diagnostics_cli;
-use nx_syntax::{parse_file, parse_str, SyntaxKind};
+use nx_syntax::{
+    parse_file, parse_str, parse_str_with_options, LanguageVersion, ParseOptions, SyntaxKind,
+};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -533,6 +535,300 @@ fn test_parse_component_definition_with_base() {
     );
 }
 
+#[test]
+fn test_parse_component_rest_props_and_spread() {
+    let path = fixture_path("valid/component-rest.nx");
+    let result = parse_file(&path).unwrap();
+
+    assert!(result.is_ok(), "Component rest fixture should parse");
+    let root = result.root().expect("Should have root node");
+
+    let component = root
+        .children()
+        .find(|c| c.kind() == SyntaxKind::COMPONENT_DEFINITION)
+        .expect("Should find component_definition node");
+    let rest = component
+        .child_by_field("signature")
+        .and_then(|signature| signature.child_by_field("rest"))
+        .expect("Component signature should expose rest definition");
+    assert_eq!(rest.kind(), SyntaxKind::REST_PROPERTY_DEFINITION);
+    assert_eq!(
+        rest.child_by_field("name")
+            .expect("Rest definition should expose name")
+            .text(),
+        "rest"
+    );
+
+    let mut stack = vec![component];
+    let mut spread = None;
+    while let Some(node) = stack.pop() {
+        if node.kind() == SyntaxKind::PROPERTY_SPREAD {
+            spread = Some(node);
+            break;
+        }
+        stack.extend(node.children());
+    }
+    let spread = spread.expect("Component body should contain a property spread");
+    assert_eq!(
+        spread
+            .child_by_field("value")
+            .expect("Spread should expose value")
+            .text(),
+        "rest"
+    );
+}
+
+#[test]
+fn test_parse_context_provide_and_use() {
+    let path = fixture_path("valid/context-provide.nx");
+    let result = parse_file(&path).unwrap();
+
+    assert!(result.is_ok(), "Context fixture should parse");
+    let root = result.root().expect("Should have root node");
+
+    let mut stack = vec![root];
+    let (mut provide, mut use_expr) = (None, None);
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            SyntaxKind::PROVIDE_EXPRESSION => provide = Some(node),
+            SyntaxKind::USE_EXPRESSION => use_expr = Some(node),
+            _ => {}
+        }
+        stack.extend(node.children());
+    }
+
+    let provide = provide.expect("Should find provide_expression node");
+    assert_eq!(
+        provide
+            .child_by_field("key")
+            .expect("Provide should expose key")
+            .text(),
+        "theme"
+    );
+    assert_eq!(
+        provide
+            .child_by_field("value")
+            .expect("Provide should expose value")
+            .text(),
+        "\"dark\""
+    );
+    assert!(provide.child_by_field("body").is_some());
+
+    let use_expr = use_expr.expect("Should find use_expression node");
+    assert_eq!(
+        use_expr
+            .child_by_field("key")
+            .expect("Use should expose key")
+            .text(),
+        "theme"
+    );
+}
+
+#[test]
+fn test_parse_module_metadata_block() {
+    let path = fixture_path("valid/module-metadata.nx");
+    let result = parse_file(&path).unwrap();
+
+    assert!(result.is_ok(), "Metadata fixture should parse");
+    let root = result.root().expect("Should have root node");
+
+    let meta = root
+        .child_by_field("meta")
+        .expect("Module should expose its meta block");
+    assert_eq!(meta.kind(), SyntaxKind::META_BLOCK);
+    assert_eq!(
+        meta.child_by_field("schema")
+            .expect("Meta block should expose schema")
+            .text(),
+        "TemplateInfo"
+    );
+
+    let entries: Vec<_> = meta
+        .children()
+        .filter(|node| node.kind() == SyntaxKind::META_ENTRY)
+        .collect();
+    let names: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.child_by_field("name").unwrap().text().to_string())
+        .collect();
+    assert_eq!(names, vec!["title", "version", "tags", "draft"]);
+    assert_eq!(
+        entries[2].child_by_field("value").unwrap().kind(),
+        SyntaxKind::META_ARRAY
+    );
+
+    assert!(
+        root.children()
+            .any(|node| node.kind() == SyntaxKind::VALUE_DEFINITION),
+        "`meta` should stay usable as a value name"
+    );
+}
+
+#[test]
+fn test_parse_bracketed_array_literals() {
+    let source = r#"let empty: string[] = []
+let numbers = [1, 2, 3,]
+let root() = { take([], [<Item />]) }
+let user = <User tags=[] />"#;
+    let result = parse_str(source, "arrays.nx");
+
+    assert!(
+        result.is_ok(),
+        "Array literals should parse: {:?}",
+        result.errors
+    );
+    let root = result.root().expect("Should have root node");
+
+    let mut stack = vec![root];
+    let mut item_counts = Vec::new();
+    while let Some(node) = stack.pop() {
+        if node.kind() == SyntaxKind::SEQUENCE_EXPRESSION {
+            item_counts.push((
+                node.span().start(),
+                node.children()
+                    .filter(|child| child.kind() == SyntaxKind::VALUE_EXPRESSION)
+                    .count(),
+            ));
+        }
+        stack.extend(node.children());
+    }
+    item_counts.sort();
+    let item_counts: Vec<_> = item_counts.into_iter().map(|(_, count)| count).collect();
+    assert_eq!(item_counts, vec![0, 3, 0, 1, 0]);
+}
+
+#[test]
+fn test_parse_numeric_literal_forms() {
+    let source = "let values = [1_000_000, 0b1010, 0xFF_FF, 1e6, 2.5e-3, 0x1.8p3, 1_0.0_1]";
+    let result = parse_str(source, "numbers.nx");
+
+    assert!(
+        result.is_ok(),
+        "Numeric literals should parse: {:?}",
+        result.errors
+    );
+    let mut literals = Vec::new();
+    let mut stack = vec![result.root().expect("Should have root node")];
+    while let Some(node) = stack.pop() {
+        if matches!(
+            node.kind(),
+            SyntaxKind::INT_LITERAL
+                | SyntaxKind::BINARY_LITERAL
+                | SyntaxKind::HEX_LITERAL
+                | SyntaxKind::REAL_LITERAL
+        ) {
+            literals.push((
+                u32::from(node.span().start()),
+                node.text().to_string(),
+                node.kind(),
+            ));
+        }
+        stack.extend(node.children());
+    }
+    literals.sort_by_key(|(start, _, _)| *start);
+    let literals: Vec<_> = literals
+        .into_iter()
+        .map(|(_, text, kind)| (kind, text))
+        .collect();
+    assert_eq!(
+        literals,
+        vec![
+            (SyntaxKind::INT_LITERAL, "1_000_000".to_string()),
+            (SyntaxKind::BINARY_LITERAL, "0b1010".to_string()),
+            (SyntaxKind::HEX_LITERAL, "0xFF_FF".to_string()),
+            (SyntaxKind::REAL_LITERAL, "1e6".to_string()),
+            (SyntaxKind::REAL_LITERAL, "2.5e-3".to_string()),
+            (SyntaxKind::REAL_LITERAL, "0x1.8p3".to_string()),
+            (SyntaxKind::REAL_LITERAL, "1_0.0_1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_nested_and_multiline_array_literals() {
+    let source = "let grid = [[1, 2], [], [3]]\nlet names = [\n  \"Ada\",\n  \"Grace\",\n]";
+    let result = parse_str(source, "arrays.nx");
+
+    assert!(
+        result.is_ok(),
+        "Array literals should parse: {:?}",
+        result.errors
+    );
+    let root = result.root().expect("Should have root node");
+    assert_eq!(count_kind(&root, SyntaxKind::SEQUENCE_EXPRESSION), 5);
+}
+
+#[test]
+fn test_parse_array_literal_items_need_commas() {
+    let result = parse_str("let xs = [1 2]", "arrays.nx");
+
+    assert!(result.has_errors(), "`[1 2]` should be a syntax error");
+}
+
+#[test]
+fn test_parse_array_literals_require_language_version_2() {
+    let source = "let xs = [1, 2]";
+    let v1 = parse_str_with_options(
+        source,
+        "arrays.nx",
+        ParseOptions::for_version(LanguageVersion::V1),
+    );
+    let codes: Vec<_> = v1.errors.iter().map(|error| error.code()).collect();
+    assert_eq!(codes, vec![Some("unsupported-language-version")]);
+
+    let v2 = parse_str_with_options(
+        source,
+        "arrays.nx",
+        ParseOptions::for_version(LanguageVersion::V2),
+    );
+    assert!(v2.is_ok(), "{:?}", v2.errors);
+}
+
+#[test]
+fn test_parse_destructuring_value_definitions() {
+    let path = fixture_path("valid/destructuring.nx");
+    let result = parse_file(&path).unwrap();
+
+    assert!(result.is_ok(), "Destructuring fixture should parse");
+    let root = result.root().expect("Should have root node");
+
+    let mut stack = vec![root];
+    let (mut array, mut record) = (None, None);
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            SyntaxKind::ARRAY_BINDING_PATTERN => array = Some(node),
+            SyntaxKind::RECORD_BINDING_PATTERN => record = Some(node),
+            _ => {}
+        }
+        stack.extend(node.children());
+    }
+
+    let array = array.expect("Should find array_binding_pattern node");
+    let elements: Vec<_> = array
+        .children()
+        .filter(|node| node.kind() == SyntaxKind::IDENTIFIER)
+        .map(|node| node.text().to_string())
+        .collect();
+    assert_eq!(elements, vec!["first", "second"]);
+    let rest = array
+        .child_by_field("rest")
+        .expect("Array pattern should expose rest");
+    assert_eq!(rest.kind(), SyntaxKind::REST_BINDING);
+    assert_eq!(
+        rest.child_by_field("name")
+            .expect("Rest should expose name")
+            .text(),
+        "others"
+    );
+
+    let record = record.expect("Should find record_binding_pattern node");
+    let fields: Vec<_> = record
+        .children()
+        .map(|node| node.text().to_string())
+        .collect();
+    assert_eq!(fields, vec!["name", "age"]);
+}
+
 #[test]
 fn test_parse_component_with_emits() {
     let path = fixture_path("valid/component-emits.nx");
@@ -2147,13 +2443,8 @@ fn test_parse_all_valid_fixtures() {
                 path.file_name(),
                 {
                     let mut sources = HashMap::new();
-                    let file_name = path
-                        .file_name()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("")
-                        .to_string();
                     let src = std::fs::read_to_string(&path).unwrap_or_default();
-                    sources.insert(file_name.clone(), src);
+                    sources.insert(path.to_string_lossy().into_owned(), src);
                     render_diagnostics_cli(&result.errors, &sources)
                 }
             );
@@ -2401,6 +2692,25 @@ fn test_concurrent_parsing_stress() {
     }
 }
 
+#[test]
+fn test_parsed_tree_shared_across_threads() {
+    let result = Arc::new(parse_str(
+        "let <Card title:string /> = <div>{title}</div>",
+        "card.nx",
+    ));
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            let result = Arc::clone(&result);
+            scope.spawn(move || {
+                let root = result.root().expect("Should have root");
+                assert_eq!(root.kind(), SyntaxKind::MODULE_DEFINITION);
+                assert!(root.text().starts_with("let <Card"));
+            });
+        }
+    });
+}
+
 // ============================================================================
 // Snapshot Tests (T052)
 // ============================================================================
@@ -2931,3 +3241,21 @@ fn test_value_definition_vs_function_definition() {
         .any(|c| c.kind() == SyntaxKind::FUNCTION_DEFINITION);
     assert!(has_func_def, "Should have function_definition node");
 }
+
+#[test]
+fn test_stray_ampersand_and_at_sign_in_text_terminate() {
+    // Error recovery asks the external scanner for text at a trailing `&`; it used to loop forever.
+    for source in ["x.&", "[#.&", "<p>AT&", "<markdown:text>a@"] {
+        let result = parse_str(source, "test.nx");
+        assert!(result.tree.is_some(), "{source:?} should produce a tree");
+    }
+
+    let result = parse_str(
+        "let root() = <markdown:text>AT&T &#x mail@host &amp; @{name}</markdown>",
+        "test.nx",
+    );
+    assert!(result.is_ok(), "Errors: {:?}", result.errors);
+    let root = result.root().expect("Should have root node");
+    assert_eq!(count_kind(&root, SyntaxKind::ENTITY), 1);
+    assert!(contains_kind(&root, SyntaxKind::EMBED_BRACED_EXPRESSION));
+}
@@ -0,0 +1,254 @@
+//! What the checker knows about the sign of numbers, for the `possible-division-by-zero` and
+//! `possible-negative-index` lints.
+//!
+//! A value's sign is known when it is a number literal, a sum or product of values with known
+//! signs, a `length`, `abs` or `clamp` call, the index of a `for` loop over an array, or a name
+//! that an enclosing `if` compared with a literal: inside `if count > 0 { ... }`, `count` is
+//! positive, and inside the `else` of `if count == 0 { ... }` it is non-zero.
+
+use nx_hir::ast::{BinOp, Expr, Literal, UnOp};
+use nx_hir::{ExprId, LoweredModule, Name};
+
+/// A known property of a number's sign
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Sign {
+    /// Greater than zero.
+    Positive,
+    /// Zero or greater.
+    NonNegative,
+    /// Not zero, but possibly negative.
+    NonZero,
+}
+
+impl Sign {
+    pub(crate) fn is_non_zero(self) -> bool {
+        matches!(self, Sign::Positive | Sign::NonZero)
+    }
+
+    pub(crate) fn is_non_negative(self) -> bool {
+        matches!(self, Sign::Positive | Sign::NonNegative)
+    }
+
+    fn of_number(value: f64) -> Option<Sign> {
+        if value > 0.0 {
+            Some(Sign::Positive)
+        } else if value == 0.0 {
+            Some(Sign::NonNegative)
+        } else if value < 0.0 {
+            Some(Sign::NonZero)
+        } else {
+            None
+        }
+    }
+
+    fn negated(self) -> Option<Sign> {
+        self.is_non_zero().then_some(Sign::NonZero)
+    }
+
+    fn sum(self, other: Sign) -> Option<Sign> {
+        match (self, other) {
+            (Sign::Positive, sign) | (sign, Sign::Positive) if sign.is_non_negative() => {
+                Some(Sign::Positive)
+            }
+            (Sign::NonNegative, Sign::NonNegative) => Some(Sign::NonNegative),
+            _ => None,
+        }
+    }
+
+    fn product(self, other: Sign) -> Option<Sign> {
+        match (self, other) {
+            (Sign::Positive, Sign::Positive) => Some(Sign::Positive),
+            (lhs, rhs) if lhs.is_non_zero() && rhs.is_non_zero() => Some(Sign::NonZero),
+            (lhs, rhs) if lhs.is_non_negative() && rhs.is_non_negative() => Some(Sign::NonNegative),
+            _ => None,
+        }
+    }
+}
+
+/// Signs of names established by enclosing `if` conditions and `for` loops, innermost last
+#[derive(Debug, Default)]
+pub(crate) struct SignFacts {
+    facts: Vec<(Name, Option<Sign>)>,
+}
+
+impl SignFacts {
+    /// Returns a mark to [`restore`](Self::restore) once the facts pushed after it go out of
+    /// scope.
+    pub(crate) fn mark(&self) -> usize {
+        self.facts.len()
+    }
+
+    pub(crate) fn restore(&mut self, mark: usize) {
+        self.facts.truncate(mark);
+    }
+
+    pub(crate) fn assume(&mut self, name: Name, sign: Sign) {
+        self.facts.push((name, Some(sign)));
+    }
+
+    /// Records that `name` was rebound, hiding what was known about the outer binding.
+    pub(crate) fn forget(&mut self, name: Name) {
+        self.facts.push((name, None));
+    }
+
+    /// Assumes the facts that hold while `condition` evaluates to `holds`.
+    pub(crate) fn assume_condition(
+        &mut self,
+        module: &LoweredModule,
+        condition: ExprId,
+        holds: bool,
+    ) {
+        match module.expr(condition) {
+            Expr::UnaryOp {
+                op: UnOp::Not,
+                expr,
+                ..
+            } => self.assume_condition(module, *expr, !holds),
+            Expr::BinaryOp { lhs, op, rhs, .. } => match (op, holds) {
+                (BinOp::And, true) | (BinOp::Or, false) => {
+                    self.assume_condition(module, *lhs, holds);
+                    self.assume_condition(module, *rhs, holds);
+                }
+                _ => {
+                    if let Some((name, sign)) = comparison_fact(module, *lhs, *op, *rhs, holds) {
+                        self.assume(name, sign);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn lookup(&self, name: &Name) -> Option<Sign> {
+        self.facts
+            .iter()
+            .rev()
+            .find(|(fact_name, _)| fact_name == name)
+            .and_then(|(_, sign)| *sign)
+    }
+}
+
+/// Returns what is known about the sign of `expr`. `builtin_callee` names the builtin a call's
+/// callee refers to, if any.
+pub(crate) fn sign_of(
+    module: &LoweredModule,
+    facts: &SignFacts,
+    builtin_callee: &dyn Fn(ExprId) -> Option<Name>,
+    expr: ExprId,
+) -> Option<Sign> {
+    let sign = |expr| sign_of(module, facts, builtin_callee, expr);
+    match module.expr(expr) {
+        Expr::Literal(literal) => number(literal).and_then(Sign::of_number),
+        Expr::Ident(name) => facts.lookup(name),
+        Expr::UnaryOp {
+            op: UnOp::Neg,
+            expr,
+            ..
+        } => sign(*expr)?.negated(),
+        Expr::BinaryOp {
+            lhs,
+            op: BinOp::Add,
+            rhs,
+            ..
+        } => sign(*lhs)?.sum(sign(*rhs)?),
+        Expr::BinaryOp {
+            lhs,
+            op: BinOp::Mul,
+            rhs,
+            ..
+        } => sign(*lhs)?.product(sign(*rhs)?),
+        Expr::Block {
+            stmts,
+            expr: Some(expr),
+            ..
+        } if stmts.is_empty() => sign(*expr),
+        Expr::Call { func, args, .. } => match builtin_callee(*func)?.as_str() {
+            "length" | "byteLength" | "graphemeLength" => Some(Sign::NonNegative),
+            "abs" => match args.first().and_then(|arg| sign(*arg)) {
+                Some(sign) if sign.is_non_zero() => Some(Sign::Positive),
+                _ => Some(Sign::NonNegative),
+            },
+            "clamp" => args
+                .get(1)
+                .and_then(|min| sign(*min))
+                .filter(|sign| sign.is_non_negative()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the fact about a name that `lhs op rhs` evaluating to `holds` establishes, when one
+/// side is a name and the other a number literal.
+fn comparison_fact(
+    module: &LoweredModule,
+    lhs: ExprId,
+    op: BinOp,
+    rhs: ExprId,
+    holds: bool,
+) -> Option<(Name, Sign)> {
+    let (name, op, bound) = match (module.expr(lhs), literal_number(module, rhs)) {
+        (Expr::Ident(name), Some(bound)) => (name, op, bound),
+        _ => match (literal_number(module, lhs), module.expr(rhs)) {
+            (Some(bound), Expr::Ident(name)) => (name, mirrored(op)?, bound),
+            _ => return None,
+        },
+    };
+    let op = if holds { op } else { negated(op)? };
+    let sign = match op {
+        BinOp::Eq => Sign::of_number(bound)?,
+        BinOp::Ne if bound == 0.0 => Sign::NonZero,
+        BinOp::Gt if bound >= 0.0 => Sign::Positive,
+        BinOp::Ge if bound > 0.0 => Sign::Positive,
+        BinOp::Ge if bound == 0.0 => Sign::NonNegative,
+        BinOp::Lt if bound <= 0.0 => Sign::NonZero,
+        BinOp::Le if bound < 0.0 => Sign::NonZero,
+        _ => return None,
+    };
+    Some((name.clone(), sign))
+}
+
+fn literal_number(module: &LoweredModule, expr: ExprId) -> Option<f64> {
+    match module.expr(expr) {
+        Expr::Literal(literal) => number(literal),
+        Expr::UnaryOp {
+            op: UnOp::Neg,
+            expr,
+            ..
+        } => literal_number(module, *expr).map(|value| -value),
+        _ => None,
+    }
+}
+
+fn number(literal: &Literal) -> Option<f64> {
+    match literal {
+        Literal::Int(value) => Some(*value as f64),
+        Literal::Float(value) => Some(value.0),
+        _ => None,
+    }
+}
+
+/// Returns the operator that compares the same operands in swapped order.
+fn mirrored(op: BinOp) -> Option<BinOp> {
+    Some(match op {
+        BinOp::Eq | BinOp::Ne => op,
+        BinOp::Lt => BinOp::Gt,
+        BinOp::Le => BinOp::Ge,
+        BinOp::Gt => BinOp::Lt,
+        BinOp::Ge => BinOp::Le,
+        _ => return None,
+    })
+}
+
+/// Returns the comparison that holds when `op` does not.
+fn negated(op: BinOp) -> Option<BinOp> {
+    Some(match op {
+        BinOp::Eq => BinOp::Ne,
+        BinOp::Ne => BinOp::Eq,
+        BinOp::Lt => BinOp::Ge,
+        BinOp::Le => BinOp::Gt,
+        BinOp::Gt => BinOp::Le,
+        BinOp::Ge => BinOp::Lt,
+        _ => return None,
+    })
+}
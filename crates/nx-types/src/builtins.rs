@@ -0,0 +1,377 @@
+//! Signatures of the native functions every NX module can call without an import.
+//!
+//! Builtins are resolved after local and imported bindings, so a module-level declaration with
+//! the same name always shadows the builtin. A builtin may have several overloads; calls pick the
+//! first overload whose parameters accept the argument types.
+//!
+//! Numeric builtins follow the arithmetic operators: integers and floats are not mixed implicitly,
+//! so each numeric builtin has one overload per category.
+//!
+//! `map`, `filter` and `reduce` take a function value such as `add(_, 1)`. Their overloads accept
+//! any array and function; [`builtin_return_type`] then derives the result from the argument
+//! types, so with `let roundPrice(price:float): float = { round(price) }`,
+//! `map(prices, roundPrice(_))` is a `float[]` rather than an array of unknown items. Builtins
+//! themselves cannot be partially applied, so `round(_)` is not a function value.
+
+use crate::ty::Type;
+use std::fmt;
+
+/// One overload of a builtin function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltinSignature {
+    /// Parameter types, in order.
+    pub params: Vec<Type>,
+    /// Return type.
+    pub ret: Type,
+}
+
+impl BuiltinSignature {
+    fn new(params: Vec<Type>, ret: Type) -> Self {
+        Self { params, ret }
+    }
+
+    /// Returns true when this overload accepts arguments of the given types.
+    pub fn accepts(&self, arg_tys: &[Type]) -> bool {
+        self.params.len() == arg_tys.len()
+            && arg_tys
+                .iter()
+                .zip(&self.params)
+                .all(|(arg_ty, param_ty)| arg_ty.is_compatible_with(param_ty))
+    }
+}
+
+impl fmt::Display for BuiltinSignature {
+    /// Formats the overload as a function type, showing parameters of any type as `any`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params = self.params.iter().map(any_for_unknown).collect();
+        write!(f, "{}", Type::function(params, self.ret.clone()))
+    }
+}
+
+fn any_for_unknown(ty: &Type) -> Type {
+    match ty {
+        Type::Unknown => Type::named("any"),
+        Type::Array(element) => Type::array(any_for_unknown(element)),
+        Type::Nullable(inner) => Type::nullable(any_for_unknown(inner)),
+        other => other.clone(),
+    }
+}
+
+const BUILTIN_NAMES: &[&str] = &[
+    "abs",
+    "assert",
+    "byteLength",
+    "ceil",
+    "clamp",
+    "contains",
+    "currencyCode",
+    "encodeUriComponent",
+    "endsWith",
+    "fail",
+    "filter",
+    "floor",
+    "formatMoney",
+    "graphemeLength",
+    "length",
+    "log",
+    "lower",
+    "map",
+    "max",
+    "min",
+    "money",
+    "padLeft",
+    "padRight",
+    "parseCsv",
+    "pow",
+    "random",
+    "randomInt",
+    "reduce",
+    "round",
+    "sanitizeUrl",
+    "sqrt",
+    "startsWith",
+    "substring",
+    "t",
+    "trim",
+    "truncate",
+    "upper",
+    "uuid",
+    "wrap",
+];
+
+/// Returns the names of all builtin functions.
+pub fn builtin_names() -> &'static [&'static str] {
+    BUILTIN_NAMES
+}
+
+/// Returns true when `name` is a builtin function.
+pub fn is_builtin(name: &str) -> bool {
+    BUILTIN_NAMES.contains(&name)
+}
+
+/// Returns the overloads of builtin `name`, or an empty list when it is not a builtin.
+pub fn builtin_signatures(name: &str) -> Vec<BuiltinSignature> {
+    match name {
+        // Strings are measured in Unicode scalar values, arrays in items.
+        "length" => vec![
+            BuiltinSignature::new(vec![Type::string()], Type::int()),
+            BuiltinSignature::new(vec![Type::array(Type::Unknown)], Type::int()),
+        ],
+        // UTF-8 bytes and extended grapheme clusters; `graphemeLength` fails at runtime when the
+        // interpreter is built without its `graphemes` feature.
+        "byteLength" | "graphemeLength" => {
+            vec![BuiltinSignature::new(vec![Type::string()], Type::int())]
+        }
+        "abs" | "floor" | "ceil" => vec![
+            BuiltinSignature::new(vec![Type::int()], Type::int()),
+            BuiltinSignature::new(vec![Type::float()], Type::float()),
+        ],
+        // Rounds half away from zero; the optional second argument keeps that many decimals.
+        "round" => vec![
+            BuiltinSignature::new(vec![Type::int()], Type::int()),
+            BuiltinSignature::new(vec![Type::float()], Type::float()),
+            BuiltinSignature::new(vec![Type::float(), Type::int()], Type::float()),
+        ],
+        "pow" => vec![
+            BuiltinSignature::new(vec![Type::int(), Type::int()], Type::int()),
+            BuiltinSignature::new(vec![Type::float(), Type::float()], Type::float()),
+        ],
+        "sqrt" => vec![
+            BuiltinSignature::new(vec![Type::float()], Type::float()),
+            BuiltinSignature::new(vec![Type::int()], Type::float()),
+        ],
+        "min" | "max" => vec![
+            BuiltinSignature::new(vec![Type::int(), Type::int()], Type::int()),
+            BuiltinSignature::new(vec![Type::float(), Type::float()], Type::float()),
+        ],
+        "clamp" => vec![
+            BuiltinSignature::new(vec![Type::int(), Type::int(), Type::int()], Type::int()),
+            BuiltinSignature::new(
+                vec![Type::float(), Type::float(), Type::float()],
+                Type::float(),
+            ),
+        ],
+        // Random builtins draw from the evaluation's random source and fail when it is disabled.
+        "random" => vec![BuiltinSignature::new(Vec::new(), Type::float())],
+        "randomInt" => vec![BuiltinSignature::new(
+            vec![Type::int(), Type::int()],
+            Type::int(),
+        )],
+        "uuid" => vec![BuiltinSignature::new(Vec::new(), Type::string())],
+        // Case mapping is the full, locale-independent Unicode mapping (`upper("ß") == "SS"`).
+        "upper" | "lower" | "trim" => {
+            vec![BuiltinSignature::new(vec![Type::string()], Type::string())]
+        }
+        // Sanitizers for text placed in attributes. `sanitizeUrl` replaces URLs whose scheme is
+        // not http, https, mailto or tel with `about:blank`; `encodeUriComponent` percent-encodes
+        // everything but unreserved characters.
+        "sanitizeUrl" | "encodeUriComponent" => {
+            vec![BuiltinSignature::new(vec![Type::string()], Type::string())]
+        }
+        // `t(message, args?)` translates `message` with the evaluation's message catalog, or
+        // returns it unchanged, then replaces `{0}`, `{1}`, ... with the items of `args`.
+        "t" => vec![
+            BuiltinSignature::new(vec![Type::string()], Type::string()),
+            BuiltinSignature::new(
+                vec![Type::string(), Type::array(Type::Unknown)],
+                Type::string(),
+            ),
+        ],
+        // `money(amount, currency)` is exact to the currency's minor unit, such as cents for
+        // "EUR" or whole yen for "JPY"; a string amount such as "19.99" avoids float rounding.
+        // `formatMoney` writes the amount with its currency symbol and thousands separators.
+        "money" => vec![
+            BuiltinSignature::new(vec![Type::int(), Type::string()], Type::money()),
+            BuiltinSignature::new(vec![Type::float(), Type::string()], Type::money()),
+            BuiltinSignature::new(vec![Type::string(), Type::string()], Type::money()),
+        ],
+        "formatMoney" => vec![BuiltinSignature::new(vec![Type::money()], Type::string())],
+        "currencyCode" => vec![BuiltinSignature::new(vec![Type::money()], Type::string())],
+        // `parseCsv(text, delimiter?)` returns one record per row after the header row, with a
+        // string field per column; pass "\t" as the delimiter for TSV.
+        "parseCsv" => vec![
+            BuiltinSignature::new(vec![Type::string()], Type::array(Type::Unknown)),
+            BuiltinSignature::new(
+                vec![Type::string(), Type::string()],
+                Type::array(Type::Unknown),
+            ),
+        ],
+        // `substring(text, start, end?)` counts Unicode scalar values, like `length`. Indices past
+        // the end are clamped, an end before the start gives "", and negative indices fail.
+        "substring" => vec![
+            BuiltinSignature::new(vec![Type::string(), Type::int()], Type::string()),
+            BuiltinSignature::new(
+                vec![Type::string(), Type::int(), Type::int()],
+                Type::string(),
+            ),
+        ],
+        "startsWith" | "endsWith" | "contains" => vec![BuiltinSignature::new(
+            vec![Type::string(), Type::string()],
+            Type::bool(),
+        )],
+        // Pads to a width in characters; the optional fill defaults to a space.
+        "padLeft" | "padRight" => vec![
+            BuiltinSignature::new(vec![Type::string(), Type::int()], Type::string()),
+            BuiltinSignature::new(
+                vec![Type::string(), Type::int(), Type::string()],
+                Type::string(),
+            ),
+        ],
+        // Both count grapheme clusters. `truncate` keeps the result, including the ellipsis
+        // (default `…`), within `max`; `wrap` returns the lines of a greedy word wrap.
+        "truncate" => vec![
+            BuiltinSignature::new(vec![Type::string(), Type::int()], Type::string()),
+            BuiltinSignature::new(
+                vec![Type::string(), Type::int(), Type::string()],
+                Type::string(),
+            ),
+        ],
+        "wrap" => vec![BuiltinSignature::new(
+            vec![Type::string(), Type::int()],
+            Type::array(Type::string()),
+        )],
+        // `assert` fails evaluation with the message (or "Assertion failed") when the condition
+        // is false. `fail` always does, so its result fits wherever a value is expected.
+        "assert" => vec![
+            BuiltinSignature::new(vec![Type::bool()], Type::void()),
+            BuiltinSignature::new(vec![Type::bool(), Type::string()], Type::void()),
+        ],
+        "fail" => vec![BuiltinSignature::new(vec![Type::string()], Type::Unknown)],
+        // `log(level, message, value?)` reports to the host's log sink; the level is one of
+        // "debug", "info", "warn" or "error".
+        "log" => vec![
+            BuiltinSignature::new(vec![Type::string(), Type::string()], Type::void()),
+            BuiltinSignature::new(
+                vec![Type::string(), Type::string(), Type::Unknown],
+                Type::void(),
+            ),
+        ],
+        // `map(items, f)`, `filter(items, keep)` and `reduce(items, initial, f)` call the function
+        // once per item, in order; `reduce` passes the running value first.
+        "map" => vec![BuiltinSignature::new(
+            vec![
+                Type::array(Type::Unknown),
+                Type::function(vec![Type::Unknown], Type::Unknown),
+            ],
+            Type::array(Type::Unknown),
+        )],
+        "filter" => vec![BuiltinSignature::new(
+            vec![
+                Type::array(Type::Unknown),
+                Type::function(vec![Type::Unknown], Type::bool()),
+            ],
+            Type::array(Type::Unknown),
+        )],
+        "reduce" => vec![BuiltinSignature::new(
+            vec![
+                Type::array(Type::Unknown),
+                Type::Unknown,
+                Type::function(vec![Type::Unknown, Type::Unknown], Type::Unknown),
+            ],
+            Type::Unknown,
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the first overload of builtin `name` that accepts `arg_tys`.
+pub fn resolve_builtin(name: &str, arg_tys: &[Type]) -> Option<BuiltinSignature> {
+    builtin_signatures(name)
+        .into_iter()
+        .find(|signature| signature.accepts(arg_tys))
+}
+
+/// Returns the result type of a call to builtin `name` with `arg_tys`, through `signature`.
+///
+/// This is the overload's return type, except for the higher-order builtins: `map` returns an
+/// array of the function's results, `filter` the type of its array, and `reduce` the type of its
+/// initial value.
+pub fn builtin_return_type(name: &str, signature: &BuiltinSignature, arg_tys: &[Type]) -> Type {
+    match (name, arg_tys) {
+        ("map", [_, Type::Function { ret, .. }]) => Type::array(ret.as_ref().clone()),
+        ("filter", [items @ Type::Array(_), _]) => items.clone(),
+        ("reduce", [_, initial, _]) if !initial.is_unknown() => initial.clone(),
+        _ => signature.ret.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_builtin_name_has_a_signature() {
+        for name in builtin_names() {
+            assert!(
+                !builtin_signatures(name).is_empty(),
+                "builtin '{}' has no signature",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_builtin_picks_matching_overload() {
+        let on_array = resolve_builtin("length", &[Type::array(Type::bool())]).unwrap();
+        assert_eq!(on_array.params, vec![Type::array(Type::Unknown)]);
+        assert_eq!(on_array.ret, Type::int());
+
+        assert_eq!(on_array.to_string(), "(any[]) => int");
+        assert!(resolve_builtin("length", &[Type::bool()]).is_none());
+        assert!(resolve_builtin("missing", &[]).is_none());
+    }
+
+    #[test]
+    fn test_numeric_builtins_do_not_mix_categories() {
+        let int_abs = resolve_builtin("abs", &[Type::int()]).unwrap();
+        assert_eq!(int_abs.ret, Type::int());
+        let float_abs = resolve_builtin("abs", &[Type::float()]).unwrap();
+        assert_eq!(float_abs.ret, Type::float());
+
+        assert!(resolve_builtin("pow", &[Type::int(), Type::float()]).is_none());
+        assert_eq!(
+            resolve_builtin("round", &[Type::float(), Type::int()])
+                .unwrap()
+                .ret,
+            Type::float()
+        );
+    }
+
+    #[test]
+    fn test_higher_order_builtins_derive_their_result_from_the_arguments() {
+        let add_one = Type::function(vec![Type::int()], Type::float());
+        let items = Type::array(Type::int());
+        let args = [items.clone(), add_one];
+        let map = resolve_builtin("map", &args).unwrap();
+        assert_eq!(
+            builtin_return_type("map", &map, &args),
+            Type::array(Type::float())
+        );
+
+        let args = [
+            items.clone(),
+            Type::function(vec![Type::int()], Type::bool()),
+        ];
+        let filter = resolve_builtin("filter", &args).unwrap();
+        assert_eq!(builtin_return_type("filter", &filter, &args), items);
+        assert!(resolve_builtin(
+            "filter",
+            &[
+                items.clone(),
+                Type::function(vec![Type::int()], Type::int())
+            ]
+        )
+        .is_none());
+
+        let args = [
+            items,
+            Type::string(),
+            Type::function(vec![Type::string(), Type::int()], Type::string()),
+        ];
+        let reduce = resolve_builtin("reduce", &args).unwrap();
+        assert_eq!(
+            builtin_return_type("reduce", &reduce, &args),
+            Type::string()
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Call graph of local functions, used to order return type inference.
+//!
+//! Functions are grouped into strongly connected components so that callees are inferred before
+//! their callers and mutually recursive functions are inferred together.
+
+use nx_hir::walk::collect_referenced_names;
+use nx_hir::{Function, Item, LoweredModule};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// A set of local functions whose return types depend on each other.
+#[derive(Debug)]
+pub(crate) struct FunctionGroup<'m> {
+    /// Functions in the group, in source order.
+    pub functions: Vec<&'m Function>,
+    /// Whether any function in the group calls itself, directly or through other members.
+    pub recursive: bool,
+}
+
+/// Returns the module's functions grouped into strongly connected components, ordered so that
+/// every group comes after the groups it calls.
+pub(crate) fn function_inference_order(module: &LoweredModule) -> Vec<FunctionGroup<'_>> {
+    let functions = module
+        .items()
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(func) => Some(func),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let indices = functions
+        .iter()
+        .enumerate()
+        .map(|(index, func)| (func.name.clone(), index))
+        .collect::<FxHashMap<_, _>>();
+
+    let edges = functions
+        .iter()
+        .map(|func| {
+            // Shadowing is ignored, so a parameter that shares a function's name still counts as
+            // a call. That only merges groups that could have been inferred separately.
+            let mut names = FxHashSet::default();
+            collect_referenced_names(module, func.body, &mut names);
+            let mut callees = names
+                .iter()
+                .filter_map(|name| indices.get(name).copied())
+                .collect::<Vec<_>>();
+            callees.sort_unstable();
+            callees
+        })
+        .collect::<Vec<_>>();
+
+    let mut tarjan = Tarjan::new(&edges);
+    for index in 0..functions.len() {
+        if tarjan.index[index].is_none() {
+            tarjan.visit(index);
+        }
+    }
+
+    tarjan
+        .components
+        .into_iter()
+        .map(|mut component| {
+            component.sort_unstable();
+            let recursive = component.len() > 1 || edges[component[0]].contains(&component[0]);
+            FunctionGroup {
+                functions: component
+                    .into_iter()
+                    .map(|index| functions[index])
+                    .collect(),
+                recursive,
+            }
+        })
+        .collect()
+}
+
+/// Tarjan's strongly connected components algorithm. Components are emitted in reverse
+/// topological order, i.e. callees before callers.
+struct Tarjan<'e> {
+    edges: &'e [Vec<usize>],
+    next_index: usize,
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    components: Vec<Vec<usize>>,
+}
+
+impl<'e> Tarjan<'e> {
+    fn new(edges: &'e [Vec<usize>]) -> Self {
+        Self {
+            edges,
+            next_index: 0,
+            index: vec![None; edges.len()],
+            low_link: vec![0; edges.len()],
+            on_stack: vec![false; edges.len()],
+            stack: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.low_link[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &callee in &self.edges[node] {
+            match self.index[callee] {
+                None => {
+                    self.visit(callee);
+                    self.low_link[node] = self.low_link[node].min(self.low_link[callee]);
+                }
+                Some(callee_index) if self.on_stack[callee] => {
+                    self.low_link[node] = self.low_link[node].min(callee_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_link[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_hir::{lower, SourceId};
+
+    fn group_names(source: &str) -> Vec<(Vec<String>, bool)> {
+        let parse = nx_syntax::parse_str(source, "test.nx");
+        let module = lower(parse.root().expect("root"), SourceId::new(0));
+        function_inference_order(&module)
+            .into_iter()
+            .map(|group| {
+                (
+                    group
+                        .functions
+                        .iter()
+                        .map(|func| func.name.to_string())
+                        .collect(),
+                    group.recursive,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_callees_are_ordered_before_callers() {
+        let groups = group_names(
+            r#"
+            let caller() = { helper() + 1 }
+            let helper() = { 41 }
+            "#,
+        );
+
+        assert_eq!(
+            groups,
+            vec![
+                (vec!["helper".to_string()], false),
+                (vec!["caller".to_string()], false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mutually_recursive_functions_share_a_group() {
+        let groups = group_names(
+            r#"
+            let isEven(n:int) = { if n == 0 { true } else { isOdd(n - 1) } }
+            let isOdd(n:int) = { if n == 0 { false } else { isEven(n - 1) } }
+            let count(n:int) = { if n <= 0 { 0 } else { count(n - 1) + 1 } }
+            "#,
+        );
+
+        assert_eq!(
+            groups,
+            vec![
+                (vec!["isEven".to_string(), "isOdd".to_string()], true),
+                (vec!["count".to_string()], true),
+            ]
+        );
+    }
+}
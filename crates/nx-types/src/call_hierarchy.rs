@@ -0,0 +1,216 @@
+//! Call hierarchy index: which declarations call or render which functions and components.
+//!
+//! References are recorded per file by name, so the index can answer both directions of a
+//! "who uses this?" query across a project without re-walking every module.
+
+use nx_diagnostics::TextSpan;
+use nx_hir::walk::walk_expr;
+use nx_hir::{ast, ExprId, Item, LoweredModule};
+use rustc_hash::FxHashMap;
+
+/// How a call site invokes its callee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallKind {
+    /// A parenthesized call such as `format(price)`, including partial applications.
+    Call,
+    /// An element such as `<ProductCard />`.
+    Element,
+}
+
+/// One call site found in a declaration's body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallReference {
+    /// Name of the function, component, or value whose body contains the call.
+    pub caller: String,
+    /// Declared name of the called function or component, without any import qualifier.
+    pub callee: String,
+    /// How the callee is invoked.
+    pub kind: CallKind,
+    /// File that contains the call site.
+    pub file: String,
+    /// Source span of the call expression or element.
+    pub span: TextSpan,
+}
+
+/// Call sites of a set of files, indexed by caller and callee name.
+///
+/// Callees are matched by their declared name: `Ui.Button(...)` and `<Ui.Button />` both refer
+/// to `Button`. Local bindings that shadow a declaration are not distinguished from it.
+#[derive(Debug, Clone, Default)]
+pub struct CallIndex {
+    files: FxHashMap<String, Vec<CallReference>>,
+}
+
+impl CallIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the call sites of `module`, replacing any previously recorded for `file`.
+    pub fn update_module(&mut self, file: impl Into<String>, module: &LoweredModule) {
+        let file = file.into();
+        let references = module_calls(module, &file);
+        self.files.insert(file, references);
+    }
+
+    /// Returns the call sites recorded for `file`, in the order they were found.
+    pub(crate) fn file_calls(&self, file: &str) -> &[CallReference] {
+        self.files.get(file).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Replaces the call sites recorded for `file` with ones recorded earlier, such as from a
+    /// session snapshot.
+    pub(crate) fn restore_file(&mut self, file: String, references: Vec<CallReference>) {
+        self.files.insert(file, references);
+    }
+
+    /// Removes the call sites recorded for `file`.
+    pub fn remove_file(&mut self, file: &str) {
+        self.files.remove(file);
+    }
+
+    /// Returns every call site whose callee is `name`, ordered by file and position.
+    pub fn callers_of(&self, name: &str) -> Vec<CallReference> {
+        self.references(|reference| reference.callee == name)
+    }
+
+    /// Returns every call site in the body of the declaration named `name`, ordered by file and
+    /// position.
+    pub fn callees_of(&self, name: &str) -> Vec<CallReference> {
+        self.references(|reference| reference.caller == name)
+    }
+
+    fn references(&self, filter: impl Fn(&CallReference) -> bool) -> Vec<CallReference> {
+        let mut references: Vec<_> = self
+            .files
+            .values()
+            .flatten()
+            .filter(|reference| filter(reference))
+            .cloned()
+            .collect();
+        references.sort_by(|left, right| {
+            left.file
+                .cmp(&right.file)
+                .then_with(|| left.span.start().cmp(&right.span.start()))
+        });
+        references
+    }
+}
+
+fn module_calls(module: &LoweredModule, file: &str) -> Vec<CallReference> {
+    let mut references = Vec::new();
+    for item in module.items() {
+        let body = match item {
+            Item::Function(function) => Some(function.body),
+            Item::Value(value) => Some(value.value),
+            Item::Component(component) => component.body,
+            Item::TypeAlias(_) | Item::Enum(_) | Item::Union(_) | Item::Record(_) => None,
+        };
+        let Some(body) = body else {
+            continue;
+        };
+
+        let caller = item.name().as_str();
+        walk_expr(module, body, &mut |expr| {
+            let call = match expr {
+                ast::Expr::Call { func, span, .. } | ast::Expr::PartialApply { func, span, .. } => {
+                    callee_name(module, *func).map(|callee| (callee, CallKind::Call, *span))
+                }
+                ast::Expr::Element { element, span } => {
+                    let tag = module.element(*element).tag.as_str();
+                    Some((unqualified(tag), CallKind::Element, *span))
+                }
+                _ => None,
+            };
+            if let Some((callee, kind, span)) = call {
+                references.push(CallReference {
+                    caller: caller.to_string(),
+                    callee: callee.to_string(),
+                    kind,
+                    file: file.to_string(),
+                    span,
+                });
+            }
+        });
+    }
+    references
+}
+
+fn callee_name(module: &LoweredModule, func: ExprId) -> Option<&str> {
+    match module.expr(func) {
+        ast::Expr::Ident(name) => Some(unqualified(name.as_str())),
+        ast::Expr::Member { member, .. } => Some(member.as_str()),
+        _ => None,
+    }
+}
+
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_hir::{lower, SourceId};
+
+    fn indexed(files: &[(&str, &str)]) -> CallIndex {
+        let mut index = CallIndex::new();
+        for (file, source) in files {
+            let parse = nx_syntax::parse_str(source, file);
+            let module = lower(parse.root().expect("root"), SourceId::new(0));
+            index.update_module(*file, &module);
+        }
+        index
+    }
+
+    fn edges(references: &[CallReference]) -> Vec<(&str, &str, CallKind)> {
+        references
+            .iter()
+            .map(|reference| {
+                (
+                    reference.caller.as_str(),
+                    reference.callee.as_str(),
+                    reference.kind,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_callers_and_callees_include_calls_and_elements() {
+        let index = indexed(&[
+            (
+                "card.nx",
+                r#"let formatPrice(cents:int) = { cents / 100 }
+let <Price cents:int /> = <span>{formatPrice(cents)}</span>"#,
+            ),
+            (
+                "page.nx",
+                r#"let <Page /> = <main><Ui.Price cents={250} /><Price cents={formatPrice(5)} /></main>"#,
+            ),
+        ]);
+
+        assert_eq!(
+            edges(&index.callers_of("Price")),
+            vec![
+                ("Page", "Price", CallKind::Element),
+                ("Page", "Price", CallKind::Element),
+            ]
+        );
+        assert_eq!(
+            edges(&index.callers_of("formatPrice")),
+            vec![
+                ("Price", "formatPrice", CallKind::Call),
+                ("Page", "formatPrice", CallKind::Call),
+            ]
+        );
+        assert_eq!(
+            edges(&index.callees_of("Price")),
+            vec![
+                ("Price", "span", CallKind::Element),
+                ("Price", "formatPrice", CallKind::Call),
+            ]
+        );
+    }
+}
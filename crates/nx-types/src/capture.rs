@@ -0,0 +1,282 @@
+//! The `unused-array-capture` lint for action handlers.
+//!
+//! An action handler such as `onSearch=<DoSearch query={action.text} />` keeps a copy of every
+//! variable visible where it is created, whether its body uses it or not. A large array in scope
+//! is therefore copied into each handler, and into component state when the handler is stored.
+//! The lint warns when a handler is created while a parameter, prop, state field, loop variable
+//! or local binding of array type is in scope that the handler's body never mentions.
+//!
+//! Top-level values are bound again when a handler runs, so only local bindings are checked.
+
+use crate::env::TypeEnvironment;
+use crate::ty::Type;
+use nx_diagnostics::{Diagnostic, Label, TextSpan};
+use nx_hir::ast::{Expr, Stmt, TypeRef};
+use nx_hir::walk::{child_exprs, collect_referenced_names};
+use nx_hir::{ExprId, Item, LoweredModule, Name};
+use rustc_hash::FxHashSet;
+
+/// Diagnostic code of the lint.
+pub const UNUSED_ARRAY_CAPTURE: &str = "unused-array-capture";
+
+/// Returns a warning for every array binding captured by an action handler of `module` that the
+/// handler's body does not use.
+///
+/// `type_env` is the module's analyzed type environment; it tells which local bindings hold
+/// arrays.
+pub fn check_unused_array_captures(
+    module: &LoweredModule,
+    type_env: &TypeEnvironment,
+    file_name: &str,
+) -> Vec<Diagnostic> {
+    let mut checker = CaptureChecker {
+        module,
+        type_env,
+        file_name,
+        locals: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    for item in module.items() {
+        match item {
+            Item::Function(function) => {
+                checker.locals = function
+                    .params
+                    .iter()
+                    .map(|param| Local::declared(&param.name, &param.ty, param.span))
+                    .collect();
+                checker.expr(function.body);
+            }
+            Item::Component(component) => {
+                let Some(body) = component.body else {
+                    continue;
+                };
+                checker.locals = component
+                    .props
+                    .iter()
+                    .chain(&component.state)
+                    .map(|field| Local::declared(&field.name, &field.ty, field.span))
+                    .collect();
+                checker.expr(body);
+            }
+            _ => {}
+        }
+    }
+    checker.diagnostics
+}
+
+/// A binding visible inside a function or component body.
+struct Local {
+    name: Name,
+    is_array: bool,
+    span: TextSpan,
+}
+
+impl Local {
+    fn declared(name: &Name, ty: &TypeRef, span: TextSpan) -> Self {
+        let is_array = match ty {
+            TypeRef::Nullable(inner) => matches!(**inner, TypeRef::Array(_)),
+            ty => matches!(ty, TypeRef::Array(_)),
+        };
+        Self {
+            name: name.clone(),
+            is_array,
+            span,
+        }
+    }
+}
+
+struct CaptureChecker<'a> {
+    module: &'a LoweredModule,
+    type_env: &'a TypeEnvironment,
+    file_name: &'a str,
+    /// Bindings in scope, innermost last.
+    locals: Vec<Local>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl CaptureChecker<'_> {
+    fn expr(&mut self, expr_id: ExprId) {
+        match self.module.expr(expr_id) {
+            Expr::ActionHandler { body, span, .. } => {
+                self.check_handler(*body, *span);
+                self.expr(*body);
+            }
+            Expr::Let {
+                name,
+                value,
+                body,
+                span,
+            } => {
+                self.expr(*value);
+                let scope = self.locals.len();
+                self.bind(name, *value, *span);
+                self.expr(*body);
+                self.locals.truncate(scope);
+            }
+            Expr::Block { stmts, expr, .. } => {
+                let scope = self.locals.len();
+                for stmt in stmts {
+                    match stmt {
+                        Stmt::Let {
+                            name, init, span, ..
+                        } => {
+                            self.expr(*init);
+                            self.bind(name, *init, *span);
+                        }
+                        Stmt::Expr(expr, _) => self.expr(*expr),
+                    }
+                }
+                if let Some(expr) = expr {
+                    self.expr(*expr);
+                }
+                self.locals.truncate(scope);
+            }
+            Expr::For {
+                item,
+                index,
+                iterable,
+                body,
+                span,
+            } => {
+                self.expr(*iterable);
+                let scope = self.locals.len();
+                let item_is_array = matches!(
+                    self.type_env.get_expr_type(*iterable),
+                    Some(Type::Array(inner)) if is_array_type(inner)
+                );
+                self.locals.push(Local {
+                    name: item.clone(),
+                    is_array: item_is_array,
+                    span: *span,
+                });
+                if let Some(index) = index {
+                    self.locals.push(Local {
+                        name: index.clone(),
+                        is_array: false,
+                        span: *span,
+                    });
+                }
+                self.expr(*body);
+                self.locals.truncate(scope);
+            }
+            _ => {
+                let mut children = Vec::new();
+                child_exprs(self.module, expr_id, &mut children);
+                for child in children {
+                    self.expr(child);
+                }
+            }
+        }
+    }
+
+    fn bind(&mut self, name: &Name, init: ExprId, span: TextSpan) {
+        let is_array = self.type_env.get_expr_type(init).is_some_and(is_array_type);
+        self.locals.push(Local {
+            name: name.clone(),
+            is_array,
+            span,
+        });
+    }
+
+    fn check_handler(&mut self, body: ExprId, span: TextSpan) {
+        let mut used = FxHashSet::default();
+        collect_referenced_names(self.module, body, &mut used);
+
+        let mut seen = FxHashSet::default();
+        let mut unused = Vec::new();
+        for local in self.locals.iter().rev() {
+            // Only the innermost binding of a name is captured, and `action` is replaced by the
+            // action the handler receives.
+            if !seen.insert(&local.name) || local.name.as_str() == "action" {
+                continue;
+            }
+            if local.is_array && !used.contains(&local.name) {
+                unused.push(local);
+            }
+        }
+
+        for local in unused.into_iter().rev() {
+            self.diagnostics.push(
+                Diagnostic::warning(UNUSED_ARRAY_CAPTURE)
+                    .with_message(format!(
+                        "This action handler captures the array '{}' without using it",
+                        local.name
+                    ))
+                    .with_label(
+                        Label::primary(self.file_name, span)
+                            .with_message(format!("keeps a copy of '{}'", local.name)),
+                    )
+                    .with_label(
+                        Label::secondary(self.file_name, local.span)
+                            .with_message(format!("'{}' is bound here", local.name)),
+                    )
+                    .with_note(
+                        "Action handlers keep every variable in scope where they are created, \
+                         and component state stores them with the handler.",
+                    )
+                    .with_help(format!(
+                        "Create the handler in a component that receives only the values it \
+                         needs, so '{}' is not in scope.",
+                        local.name
+                    ))
+                    .build(),
+            );
+        }
+    }
+}
+
+fn is_array_type(ty: &Type) -> bool {
+    match ty {
+        Type::Array(_) => true,
+        Type::Nullable(inner) => matches!(**inner, Type::Array(_)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::check_str;
+
+    fn capture_warnings(source: &str) -> Vec<String> {
+        check_str(source, "test.nx")
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.code() == Some(super::UNUSED_ARRAY_CAPTURE))
+            .map(|diagnostic| diagnostic.message().to_string())
+            .collect()
+    }
+
+    const ACTIONS: &str = r#"
+        action SearchSubmitted = { searchString:string }
+        action DoSearch = { userId:string search:string }
+        component <SearchBox emits { SearchSubmitted } /> = { <TextInput /> }
+    "#;
+
+    #[test]
+    fn handlers_warn_about_array_bindings_they_do_not_use() {
+        let source = format!(
+            "{ACTIONS}
+            let render(userId:string, rows:string[], groups:int[][]) = <div>{{for group in groups {{
+              <SearchBox onSearchSubmitted=<DoSearch userId={{userId}} search={{action.searchString}} /> />
+            }}}}</div>"
+        );
+        assert_eq!(
+            capture_warnings(&source),
+            vec![
+                "This action handler captures the array 'rows' without using it",
+                "This action handler captures the array 'groups' without using it",
+                "This action handler captures the array 'group' without using it",
+            ]
+        );
+    }
+
+    #[test]
+    fn handlers_using_their_arrays_are_not_reported() {
+        let source = format!(
+            "{ACTIONS}
+            let render(userIds:string[], count:int) =
+              <SearchBox onSearchSubmitted=<DoSearch userId={{userIds[count]}} search={{action.searchString}} /> />"
+        );
+        assert!(capture_warnings(&source).is_empty());
+    }
+}
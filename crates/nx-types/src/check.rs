@@ -1,19 +1,33 @@
 //! High-level type checking and source-analysis API.
 
-use crate::{InferenceContext, Type, TypeEnvironment};
-use nx_diagnostics::{Diagnostic, Label, Severity};
-use nx_hir::{lower, ExprId, Import, LoweredModule, LoweringDiagnostic, PreparedModule, SourceId};
+use crate::call_hierarchy::{CallIndex, CallReference};
+use crate::snapshot::{self, FileState, SnapshotError};
+use crate::symbols::{lower_for_index, SymbolIndex, SymbolInfo};
+use crate::timings::{time_stage, Stage};
+use crate::{
+    check_unused_array_captures, HostFunctionTypes, InferenceContext, Type, TypeEnvironment,
+    UnsafeInterpolationLint,
+};
+use nx_diagnostics::{normalize_path, Diagnostic, Label, Severity, TextSpan};
+use nx_hir::{
+    lower, ExprId, Import, LoweredModule, LoweringDiagnostic, Name, PreparedModule, ScopeManager,
+    SourceId, Symbol, SymbolKind,
+};
 use nx_syntax::{parse_file as syntax_parse_file, parse_str as syntax_parse_str};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::io;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// File-scoped analysis artifact for one NX source file.
 ///
 /// This artifact preserves the parse outcome, lowered HIR, inferred type environment, static
 /// diagnostics, and import metadata produced while parsing, lowering, preparing an analysis
 /// module, building scopes, and type checking one source file.
+///
+/// Artifacts are `Send + Sync`, so analysis results can be built on one thread and read from
+/// others.
 #[derive(Debug, Clone)]
 pub struct ModuleArtifact {
     /// Source file name used for diagnostics.
@@ -32,6 +46,12 @@ pub struct ModuleArtifact {
     pub imports: Vec<Import>,
 }
 
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ModuleArtifact>();
+    assert_send_sync::<crate::TypeEnvironment>();
+};
+
 impl ModuleArtifact {
     /// Returns true if analysis succeeded without any error diagnostics.
     pub fn is_ok(&self) -> bool {
@@ -69,8 +89,8 @@ pub type TypeCheckResult = ModuleArtifact;
 /// produces a syntax tree, the returned analysis result preserves the lowered module even when
 /// later phases report diagnostics.
 pub fn analyze_str(source: &str, file_name: &str) -> ModuleArtifact {
-    let parse_result = syntax_parse_str(source, file_name);
-    analyze_string_parse_result(parse_result, file_name)
+    let parse_result = time_stage(Stage::Parse, || syntax_parse_str(source, file_name));
+    analyze_string_parse_result(parse_result, file_name, &HostFunctionTypes::default())
 }
 
 /// Type checks NX source code from a string.
@@ -92,6 +112,62 @@ pub fn check_str(source: &str, file_name: &str) -> TypeCheckResult {
     analyze_str(source, file_name)
 }
 
+/// Type checks NX source code from a string whose calls may target the host functions declared
+/// in `host_functions`.
+pub fn check_str_with_host_functions(
+    source: &str,
+    file_name: &str,
+    host_functions: &HostFunctionTypes,
+) -> TypeCheckResult {
+    let parse_result = time_stage(Stage::Parse, || syntax_parse_str(source, file_name));
+    analyze_string_parse_result(parse_result, file_name, host_functions)
+}
+
+/// The outcome of [`check_str_with_budget`]
+#[derive(Debug, Clone)]
+pub struct BudgetedCheckResult {
+    /// What was checked before the budget ran out, or the full result when `complete` is true.
+    pub result: TypeCheckResult,
+    /// False when checking stopped early. Diagnostics of the functions that were not reached are
+    /// missing, and their expressions have no types.
+    pub complete: bool,
+}
+
+/// Type checks NX source code from a string, stopping once `budget` has elapsed.
+///
+/// Meant for interactive tooling, which should stay responsive on huge generated files; batch
+/// checks such as CI should use [`check_str`]. Parsing, lowering and scope checks always run to
+/// completion, so syntax errors and undefined names are always reported. Type inference stops at
+/// the first function group it reaches after the budget is spent, keeping the diagnostics found
+/// so far.
+pub fn check_str_with_budget(
+    source: &str,
+    file_name: &str,
+    budget: Duration,
+) -> BudgetedCheckResult {
+    let deadline = Instant::now() + budget;
+    let parse_result = time_stage(Stage::Parse, || syntax_parse_str(source, file_name));
+    let source_id = SourceId::new(parse_result.source_id.as_u32());
+    let diagnostics = normalize_diagnostics_file_name(parse_result.errors, file_name);
+
+    let Some(tree) = parse_result.tree else {
+        return BudgetedCheckResult {
+            result: parse_failure_artifact(file_name, source_id, diagnostics),
+            complete: true,
+        };
+    };
+
+    let module = time_stage(Stage::Lower, || lower(tree.root(), source_id));
+    let (result, complete) = analyze_prepared_module_until(
+        file_name,
+        PreparedModule::standalone(file_name, module),
+        diagnostics,
+        Some(deadline),
+        &HostFunctionTypes::default(),
+    );
+    BudgetedCheckResult { result, complete }
+}
+
 /// Type checks an NX source file.
 ///
 /// # Example
@@ -114,42 +190,66 @@ pub fn check_str(source: &str, file_name: &str) -> TypeCheckResult {
 /// Returns an error if the file cannot be read or is not valid UTF-8.
 pub fn check_file(path: impl AsRef<Path>) -> io::Result<TypeCheckResult> {
     let path = path.as_ref();
-    let parse_result = syntax_parse_file(path)?;
+    let parse_result = time_stage(Stage::Parse, || syntax_parse_file(path))?;
     let file_name = path.display().to_string();
     Ok(analyze_parse_result(parse_result, &file_name))
 }
 
 /// Analyzes a caller-prepared module where visible bindings have already been constructed.
 pub fn analyze_prepared_module(
+    file_name: &str,
+    prepared_module: PreparedModule,
+    diagnostics: Vec<Diagnostic>,
+) -> ModuleArtifact {
+    analyze_prepared_module_until(
+        file_name,
+        prepared_module,
+        diagnostics,
+        None,
+        &HostFunctionTypes::default(),
+    )
+    .0
+}
+
+/// Analyzes a prepared module, stopping type inference once `deadline` passes. Returns whether
+/// inference finished.
+fn analyze_prepared_module_until(
     file_name: &str,
     mut prepared_module: PreparedModule,
     mut diagnostics: Vec<Diagnostic>,
-) -> ModuleArtifact {
-    for error in nx_hir::validate_record_definitions(&prepared_module) {
-        prepared_module.add_diagnostic(LoweringDiagnostic {
-            message: error.message(),
-            span: error.span(),
-        });
-    }
-    for error in nx_hir::validate_component_definitions(&prepared_module) {
-        prepared_module.add_diagnostic(LoweringDiagnostic {
-            message: error.message(),
-            span: error.span(),
-        });
-    }
-    let suppress_hir_duplicate_union_cases = diagnostics
-        .iter()
-        .any(|diagnostic| diagnostic.code() == Some("duplicate-union-case"));
-    for error in nx_hir::validate_union_definitions(&prepared_module) {
-        if suppress_hir_duplicate_union_cases && error.code() == "union-duplicate-case" {
-            continue;
+    deadline: Option<Instant>,
+    host_functions: &HostFunctionTypes,
+) -> (ModuleArtifact, bool) {
+    time_stage(Stage::Check, || {
+        for error in nx_hir::validate_record_definitions(&prepared_module) {
+            prepared_module.add_diagnostic(LoweringDiagnostic {
+                code: Some(error.code()),
+                message: error.message(),
+                span: error.span(),
+            });
         }
+        for error in nx_hir::validate_component_definitions(&prepared_module) {
+            prepared_module.add_diagnostic(LoweringDiagnostic {
+                code: None,
+                message: error.message(),
+                span: error.span(),
+            });
+        }
+        let suppress_hir_duplicate_union_cases = diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.code() == Some("duplicate-union-case"));
+        for error in nx_hir::validate_union_definitions(&prepared_module) {
+            if suppress_hir_duplicate_union_cases && error.code() == "union-duplicate-case" {
+                continue;
+            }
 
-        prepared_module.add_diagnostic(LoweringDiagnostic {
-            message: error.message(),
-            span: error.span(),
-        });
-    }
+            prepared_module.add_diagnostic(LoweringDiagnostic {
+                code: None,
+                message: error.message(),
+                span: error.span(),
+            });
+        }
+    });
 
     nx_hir::promote_component_handler_bindings(&mut prepared_module);
 
@@ -162,40 +262,50 @@ pub fn analyze_prepared_module(
         file_name,
     ));
 
-    let (scope_manager, scope_diagnostics) = nx_hir::build_scopes(&prepared_module);
-    diagnostics.extend(normalize_diagnostics_file_name(
-        scope_diagnostics,
+    time_stage(Stage::Scope, || {
+        let (mut scope_manager, scope_diagnostics) = nx_hir::build_scopes(&prepared_module);
+        define_host_function_symbols(&mut scope_manager, host_functions);
+        define_builtin_symbols(&mut scope_manager);
+        diagnostics.extend(normalize_diagnostics_file_name(
+            scope_diagnostics,
+            file_name,
+        ));
+        diagnostics.extend(normalize_diagnostics_file_name(
+            nx_hir::check_undefined_identifiers(&prepared_module, &scope_manager),
+            file_name,
+        ));
+    });
+
+    let (type_env, type_diagnostics, complete) = time_stage(Stage::Check, || {
+        let mut ctx =
+            InferenceContext::with_host_functions(&prepared_module, file_name, host_functions);
+        let complete = match deadline {
+            Some(deadline) => ctx.infer_functions_until(deadline),
+            None => {
+                ctx.infer_functions();
+                true
+            }
+        };
+        let (type_env, type_diagnostics) = ctx.finish();
+        (type_env, type_diagnostics, complete)
+    });
+    diagnostics.extend(normalize_diagnostics_file_name(type_diagnostics, file_name));
+    diagnostics.extend(UnsafeInterpolationLint::default().check(
+        prepared_module.raw_module(),
+        &type_env,
         file_name,
     ));
-    diagnostics.extend(normalize_diagnostics_file_name(
-        nx_hir::check_undefined_identifiers(&prepared_module, &scope_manager),
+    diagnostics.extend(check_unused_array_captures(
+        prepared_module.raw_module(),
+        &type_env,
         file_name,
     ));
 
-    let mut ctx = InferenceContext::with_file_name(&prepared_module, file_name);
-
-    for item in prepared_module.raw_module().items() {
-        match item {
-            nx_hir::Item::Function(func) => {
-                ctx.infer_function(func);
-            }
-            nx_hir::Item::Value(_) => {}
-            nx_hir::Item::Component(_) => {}
-            nx_hir::Item::TypeAlias(_) => {}
-            nx_hir::Item::Record(_) => {}
-            nx_hir::Item::Enum(_) => {}
-            nx_hir::Item::Union(_) => {}
-        }
-    }
-
-    let (type_env, type_diagnostics) = ctx.finish();
-    diagnostics.extend(normalize_diagnostics_file_name(type_diagnostics, file_name));
-
     let preserved_module = prepared_module.raw_module().clone();
     let source_id = prepared_module.source_id();
     let imports = preserved_module.imports.clone();
 
-    ModuleArtifact {
+    let artifact = ModuleArtifact {
         file_name: file_name.to_string(),
         source_id,
         parse_succeeded: true,
@@ -203,12 +313,55 @@ pub fn analyze_prepared_module(
         type_env,
         diagnostics,
         imports,
-    }
+    };
+    (artifact, complete)
+}
+
+/// Checks the function `function_name`, which a tool added to an already analyzed `module`, and
+/// returns the diagnostics about that function alone.
+///
+/// Debuggers and REPLs lower a snippet as a function of the module it runs in, so the snippet
+/// can call the module's functions and use its types and values. The rest of the module is
+/// assumed to be checked already and is not reported on again.
+pub fn check_added_function(
+    module: &LoweredModule,
+    function_name: &str,
+    file_name: &str,
+) -> Vec<Diagnostic> {
+    let Some(nx_hir::Item::Function(function)) = module.find_item(function_name) else {
+        return Vec::new();
+    };
+    let prepared_module = PreparedModule::standalone(file_name, module.clone());
+    let in_function = |diagnostic: &Diagnostic| {
+        diagnostic
+            .labels()
+            .iter()
+            .find(|label| label.primary)
+            .is_some_and(|label| function.span.contains_range(label.range))
+    };
+
+    let (mut scope_manager, _) = nx_hir::build_scopes(&prepared_module);
+    define_builtin_symbols(&mut scope_manager);
+    let mut diagnostics = normalize_diagnostics_file_name(
+        nx_hir::check_undefined_identifiers(&prepared_module, &scope_manager),
+        file_name,
+    );
+    diagnostics.retain(in_function);
+
+    let mut ctx = InferenceContext::with_file_name(&prepared_module, file_name);
+    let before = ctx.diagnostics().len();
+    ctx.infer_function(function);
+    diagnostics.extend(normalize_diagnostics_file_name(
+        ctx.diagnostics()[before..].to_vec(),
+        file_name,
+    ));
+    diagnostics
 }
 
 fn analyze_string_parse_result(
     parse_result: nx_syntax::ParseResult,
     file_name: &str,
+    host_functions: &HostFunctionTypes,
 ) -> ModuleArtifact {
     let source_id = SourceId::new(parse_result.source_id.as_u32());
     let diagnostics = normalize_diagnostics_file_name(parse_result.errors, file_name);
@@ -217,12 +370,15 @@ fn analyze_string_parse_result(
         return parse_failure_artifact(file_name, source_id, diagnostics);
     };
 
-    let module = lower(tree.root(), source_id);
-    analyze_prepared_module(
+    let module = time_stage(Stage::Lower, || lower(tree.root(), source_id));
+    analyze_prepared_module_until(
         file_name,
         PreparedModule::standalone(file_name, module),
         diagnostics,
+        None,
+        host_functions,
     )
+    .0
 }
 
 fn analyze_parse_result(parse_result: nx_syntax::ParseResult, file_name: &str) -> ModuleArtifact {
@@ -233,7 +389,7 @@ fn analyze_parse_result(parse_result: nx_syntax::ParseResult, file_name: &str) -
         return parse_failure_artifact(file_name, source_id, diagnostics);
     };
 
-    let module = lower(tree.root(), source_id);
+    let module = time_stage(Stage::Lower, || lower(tree.root(), source_id));
     analyze_prepared_module(
         file_name,
         PreparedModule::standalone(file_name, module),
@@ -280,11 +436,42 @@ fn module_artifact(
     }
 }
 
+/// Makes host functions visible in the root scope unless a module binding already uses the name.
+fn define_host_function_symbols(
+    scope_manager: &mut ScopeManager,
+    host_functions: &HostFunctionTypes,
+) {
+    let root = scope_manager.root();
+    for (name, _) in host_functions.iter() {
+        let name = Name::new(name);
+        if scope_manager.get(root).lookup_local(&name).is_none() {
+            scope_manager.define(
+                root,
+                Symbol::new(name, SymbolKind::Function, TextSpan::default()),
+            );
+        }
+    }
+}
+
+/// Makes builtin functions visible in the root scope unless a module binding already uses the name.
+fn define_builtin_symbols(scope_manager: &mut ScopeManager) {
+    let root = scope_manager.root();
+    for name in crate::builtins::builtin_names() {
+        let name = Name::new(name);
+        if scope_manager.get(root).lookup_local(&name).is_none() {
+            scope_manager.define(
+                root,
+                Symbol::new(name, SymbolKind::Builtin, TextSpan::default()),
+            );
+        }
+    }
+}
+
 fn lowering_diagnostics(diagnostics: &[LoweringDiagnostic], file_name: &str) -> Vec<Diagnostic> {
     diagnostics
         .iter()
         .map(|diagnostic| {
-            Diagnostic::error("lowering-error")
+            Diagnostic::error(diagnostic.code.unwrap_or("lowering-error"))
                 .with_message(diagnostic.message.clone())
                 .with_label(Label::primary(file_name, diagnostic.span))
                 .build()
@@ -305,7 +492,7 @@ fn normalize_diagnostics_file_name(
                 .cloned()
                 .map(|mut label| {
                     if label.file.is_empty() {
-                        label.file = file_name.to_string();
+                        label.file = normalize_path(file_name);
                     }
                     label
                 })
@@ -329,7 +516,16 @@ fn normalize_diagnostics_file_name(
                 builder = builder.with_note(note);
             }
 
-            builder.build()
+            let fixes = diagnostic.fixes().iter().cloned().map(|mut fix| {
+                for edit in &mut fix.edits {
+                    if edit.file.is_empty() {
+                        edit.file = normalize_path(file_name);
+                    }
+                }
+                fix
+            });
+
+            builder.with_fixes(fixes).build()
         })
         .collect()
 }
@@ -359,10 +555,22 @@ fn normalize_diagnostics_file_name(
 ///     }
 /// }
 /// ```
+///
+/// Sessions can be saved to disk with [`save`](Self::save) and restored with
+/// [`load`](Self::load), so that repeated CI runs and editor restarts only re-analyze the files
+/// that changed in between.
 #[derive(Debug, Clone, Default)]
 pub struct TypeCheckSession {
     /// Files in the session
     files: FxHashMap<String, String>,
+    /// Diagnostics of unchanged files, filled by `cache_diagnostics` or restored from a snapshot
+    cached_diagnostics: FxHashMap<String, Vec<Diagnostic>>,
+    /// Declarations of the session's files, kept current as files are added
+    symbols: SymbolIndex,
+    /// Call sites of the session's files, kept current as files are added
+    calls: CallIndex,
+    /// Types of the functions the host provides to every file
+    host_functions: HostFunctionTypes,
     /// Next source ID to allocate
     _next_id: u32,
 }
@@ -373,29 +581,201 @@ impl TypeCheckSession {
         Self::default()
     }
 
-    /// Adds a source file to the session.
+    /// Adds a source file to the session, replacing any file with the same name.
+    ///
+    /// Re-adding a file with unchanged source keeps its indexed symbols and cached diagnostics.
     pub fn add_file(&mut self, name: impl Into<String>, source: impl Into<String>) {
-        self.files.insert(name.into(), source.into());
+        let name = name.into();
+        let source = source.into();
+        if self.files.get(&name) == Some(&source) {
+            return;
+        }
+        self.cached_diagnostics.remove(&name);
+        match lower_for_index(&source, &name) {
+            Some(module) => {
+                self.symbols.update_module(name.clone(), &module);
+                self.calls.update_module(name.clone(), &module);
+            }
+            None => {
+                self.symbols.remove_file(&name);
+                self.calls.remove_file(&name);
+            }
+        }
+        self.files.insert(name, source);
+    }
+
+    /// Declares a function the host provides to every file of the session, such as one
+    /// registered with the interpreter's `register_function`.
+    ///
+    /// Files may then call `name` with arguments of the declared function type. Functions that
+    /// files declare themselves take precedence. Changing a declaration drops the cached
+    /// diagnostics. Declarations are not saved with the session, so declare them again after
+    /// [`load`](Self::load).
+    pub fn declare_host_function(&mut self, name: impl Into<String>, ty: Type) {
+        if self.host_functions.declare(name, ty) {
+            self.cached_diagnostics.clear();
+        }
+    }
+
+    /// Removes a file from the session. Returns false if the session had no such file.
+    pub fn remove_file(&mut self, name: &str) -> bool {
+        self.cached_diagnostics.remove(name);
+        self.symbols.remove_file(name);
+        self.calls.remove_file(name);
+        self.files.remove(name).is_some()
+    }
+
+    /// Returns the source text of a file in the session.
+    pub fn file_source(&self, name: &str) -> Option<&str> {
+        self.files.get(name).map(String::as_str)
     }
 
     /// Type checks a specific file in the session.
     pub fn check_file(&self, name: &str) -> Option<TypeCheckResult> {
-        self.files.get(name).map(|source| check_str(source, name))
+        self.files
+            .get(name)
+            .map(|source| check_str_with_host_functions(source, name, &self.host_functions))
     }
 
     /// Type checks all files in the session.
     pub fn check_all(&self) -> Vec<(String, TypeCheckResult)> {
         self.files
             .iter()
-            .map(|(name, source)| (name.clone(), check_str(source, name)))
+            .map(|(name, source)| {
+                let result = check_str_with_host_functions(source, name, &self.host_functions);
+                (name.clone(), result)
+            })
             .collect()
     }
 
     /// Returns all diagnostics from all files.
+    ///
+    /// Files with cached diagnostics are not checked again.
     pub fn diagnostics(&self) -> Vec<Diagnostic> {
-        self.check_all()
+        self.files
+            .iter()
+            .flat_map(|(name, source)| match self.cached_diagnostics.get(name) {
+                Some(diagnostics) => diagnostics.clone(),
+                None => {
+                    check_str_with_host_functions(source, name, &self.host_functions).diagnostics
+                }
+            })
+            .collect()
+    }
+
+    /// Checks the files whose diagnostics are not cached yet and caches them, returning how
+    /// many files were checked.
+    ///
+    /// Cached diagnostics are used by [`diagnostics`](Self::diagnostics) and stored by
+    /// [`save`](Self::save) until the file changes.
+    pub fn cache_diagnostics(&mut self) -> usize {
+        let unchecked: Vec<(String, Vec<Diagnostic>)> = self
+            .files
+            .iter()
+            .filter(|(name, _)| !self.cached_diagnostics.contains_key(*name))
+            .map(|(name, source)| {
+                let result = check_str_with_host_functions(source, name, &self.host_functions);
+                (name.clone(), result.diagnostics)
+            })
+            .collect();
+        let checked = unchecked.len();
+        self.cached_diagnostics.extend(unchecked);
+        checked
+    }
+
+    /// Saves the session's files, indexes, and cached diagnostics to `path`.
+    ///
+    /// Call [`cache_diagnostics`](Self::cache_diagnostics) first to include the diagnostics of
+    /// every file. A typical warm start loads the previous snapshot, re-adds the current files,
+    /// and saves again:
+    ///
+    /// ```no_run
+    /// use nx_types::TypeCheckSession;
+    ///
+    /// let mut session = TypeCheckSession::load("target/nx-check.json").unwrap_or_default();
+    /// session.add_file("app.nx", std::fs::read_to_string("app.nx").unwrap());
+    /// session.cache_diagnostics(); // only files that changed are checked
+    /// let diagnostics = session.diagnostics();
+    /// session.save("target/nx-check.json").unwrap();
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let mut names: Vec<&String> = self.files.keys().collect();
+        names.sort();
+        let files = names
             .into_iter()
-            .flat_map(|(_, result)| result.diagnostics)
+            .map(|name| FileState {
+                name: name.clone(),
+                source: self.files[name].clone(),
+                symbols: self.symbols.file_symbols(name).to_vec(),
+                calls: self.calls.file_calls(name).to_vec(),
+                diagnostics: self.cached_diagnostics.get(name).cloned(),
+            })
+            .collect();
+        snapshot::write_snapshot(path.as_ref(), files)
+    }
+
+    /// Restores a session saved with [`save`](Self::save).
+    ///
+    /// Files are restored without being parsed or checked again. Files that were deleted since
+    /// should be removed with [`remove_file`](Self::remove_file), and changed files re-added.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the snapshot cannot be read, or was written by a different checker version or
+    /// grammar; start from a new session in that case.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let mut session = Self::new();
+        for file in snapshot::read_snapshot(path.as_ref())? {
+            session
+                .symbols
+                .restore_file(file.name.clone(), file.symbols);
+            session.calls.restore_file(file.name.clone(), file.calls);
+            if let Some(diagnostics) = file.diagnostics {
+                session
+                    .cached_diagnostics
+                    .insert(file.name.clone(), diagnostics);
+            }
+            session.files.insert(file.name, file.source);
+        }
+        Ok(session)
+    }
+
+    /// Returns the names of the files in the session, in no particular order.
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Returns the declarations across all files whose names fuzzy-match `query`, best first.
+    ///
+    /// See [`SymbolIndex::search`] for how matches are ranked.
+    pub fn workspace_symbols(&self, query: &str) -> Vec<SymbolInfo> {
+        self.symbols.search(query)
+    }
+
+    /// Returns the call sites across all files that call or render the function or component
+    /// named `name`, ordered by file and position.
+    ///
+    /// Both `name(...)` calls and `<name />` elements count, with or without an import
+    /// qualifier. See [`CallIndex`] for how names are matched.
+    pub fn callers_of(&self, name: &str) -> Vec<CallReference> {
+        self.calls.callers_of(name)
+    }
+
+    /// Returns the call sites in the body of the declaration named `name` whose callee is a
+    /// function or component declared in the session, ordered by file and position.
+    ///
+    /// Builtins and plain markup elements such as `<div>` are left out.
+    pub fn callees_of(&self, name: &str) -> Vec<CallReference> {
+        let callables: FxHashSet<&str> = self
+            .symbols
+            .all()
+            .filter(|symbol| matches!(symbol.kind, SymbolKind::Function | SymbolKind::Component))
+            .map(|symbol| symbol.name.as_str())
+            .collect();
+        self.calls
+            .callees_of(name)
+            .into_iter()
+            .filter(|reference| callables.contains(reference.callee.as_str()))
             .collect()
     }
 
@@ -413,6 +793,7 @@ impl TypeCheckSession {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::CallKind;
     use nx_hir::{Item, Name};
 
     #[test]
@@ -456,7 +837,11 @@ mod tests {
             errors: vec![diagnostic],
             source_id: nx_syntax::SourceId::new(7),
         };
-        let result = analyze_string_parse_result(parse_result, "widgets/search-box.nx");
+        let result = analyze_string_parse_result(
+            parse_result,
+            "widgets/search-box.nx",
+            &HostFunctionTypes::default(),
+        );
 
         assert!(
             !result.parse_succeeded,
@@ -505,7 +890,7 @@ mod tests {
         let lowering = result
             .diagnostics
             .iter()
-            .find(|diagnostic| diagnostic.code() == Some("lowering-error"))
+            .find(|diagnostic| diagnostic.code() == Some("record-base-not-abstract"))
             .expect("Expected lowering diagnostic");
         let return_type = result
             .diagnostics
@@ -517,6 +902,25 @@ mod tests {
         assert_eq!(return_type.labels()[0].file, file_name);
     }
 
+    #[test]
+    fn test_analyze_str_reports_nesting_too_deep_with_its_own_code() {
+        let depth = nx_hir::MAX_NESTING_DEPTH + 1;
+        let source = format!(
+            "let <Root /> = {}x{}",
+            "<p>".repeat(depth),
+            "</p>".repeat(depth)
+        );
+
+        let result = analyze_str(&source, "deep.nx");
+
+        let nesting = result
+            .diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.code() == Some("nesting-too-deep"))
+            .expect("Expected nesting diagnostic");
+        assert_eq!(nesting.labels()[0].file, "deep.nx");
+    }
+
     #[test]
     fn test_check_str_simple() {
         let source = "let x = 42";
@@ -571,6 +975,84 @@ mod tests {
         assert!(result.is_some());
     }
 
+    #[test]
+    fn test_session_host_function_declarations_type_check_calls() {
+        let mut session = TypeCheckSession::new();
+        session.add_file(
+            "app.nx",
+            r#"
+                let ok(): string = { shout("ada") }
+                let bad(): string = { shout(1) }
+            "#,
+        );
+        assert!(session
+            .diagnostics()
+            .iter()
+            .any(|diag| diag.code() == Some("undefined-identifier")));
+        session.cache_diagnostics();
+
+        session.declare_host_function(
+            "shout",
+            Type::function(vec![Type::string()], Type::string()),
+        );
+        let diagnostics = session.diagnostics();
+        let codes: Vec<_> = diagnostics.iter().map(|diag| diag.code()).collect();
+        assert_eq!(codes, vec![Some("type-mismatch")]);
+    }
+
+    #[test]
+    fn test_session_workspace_symbols_search_every_file() {
+        let mut session = TypeCheckSession::new();
+        session.add_file("button.nx", "let <PrimaryButton /> = <button />");
+        session.add_file("app.nx", "let <App /> = <PrimaryButton />");
+
+        let symbols = session.workspace_symbols("button");
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "PrimaryButton");
+        assert_eq!(symbols[0].file, "button.nx");
+
+        session.add_file("button.nx", "let <SecondaryButton /> = <button />");
+        let names: Vec<_> = session
+            .workspace_symbols("")
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        assert_eq!(names, vec!["App", "SecondaryButton"]);
+    }
+
+    #[test]
+    fn test_session_call_hierarchy_spans_files() {
+        let mut session = TypeCheckSession::new();
+        session.add_file(
+            "button.nx",
+            "let label(text:string) = { text }\nlet <Button text:string /> = <button>{label(text)}</button>",
+        );
+        session.add_file(
+            "app.nx",
+            "let <App /> = <div><Button text=\"Save\" /><Ui.Button text=\"Undo\" /></div>",
+        );
+
+        let callers: Vec<_> = session
+            .callers_of("Button")
+            .into_iter()
+            .map(|reference| (reference.file, reference.caller, reference.kind))
+            .collect();
+        assert_eq!(
+            callers,
+            vec![
+                ("app.nx".to_string(), "App".to_string(), CallKind::Element),
+                ("app.nx".to_string(), "App".to_string(), CallKind::Element),
+            ]
+        );
+
+        let callees: Vec<_> = session
+            .callees_of("Button")
+            .into_iter()
+            .map(|reference| reference.callee)
+            .collect();
+        assert_eq!(callees, vec!["label"]);
+    }
+
     #[test]
     fn test_session_check_all() {
         let mut session = TypeCheckSession::new();
@@ -624,11 +1106,11 @@ mod tests {
     }
 
     #[test]
-    fn test_unannotated_multi_value_element_brace_falls_back_to_object_array() {
+    fn test_unannotated_multi_value_element_brace_joins_to_element_array() {
         let source = r#"
             let root() = { <A /> <B /> }
         "#;
-        let result = check_str(source, "object-fallback.nx");
+        let result = check_str(source, "element-join.nx");
         let module = result
             .lowered_module
             .as_ref()
@@ -646,14 +1128,14 @@ mod tests {
             .type_of(root.body)
             .expect("Expected inferred body type")
             .clone();
-        assert_eq!(root_ty, Type::array(Type::named("object")));
+        assert_eq!(root_ty, Type::array(Type::element()));
 
         let func_ty = result
             .type_env
             .lookup(&Name::new("root"))
             .expect("Expected root function type");
         match func_ty {
-            Type::Function { ret, .. } => assert_eq!(**ret, Type::array(Type::named("object"))),
+            Type::Function { ret, .. } => assert_eq!(**ret, Type::array(Type::element())),
             other => panic!("Expected function type, got {:?}", other),
         }
     }
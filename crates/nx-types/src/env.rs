@@ -121,6 +121,11 @@ impl TypeEnvironment {
         None
     }
 
+    /// Looks up the type of a name in the outermost (module) scope, ignoring local shadowing.
+    pub fn lookup_global(&self, name: &Name) -> Option<&Type> {
+        self.scopes.first().and_then(|scope| scope.lookup(name))
+    }
+
     /// Records the type of an expression.
     pub fn set_expr_type(&mut self, expr: ExprId, ty: Type) {
         self.expr_types.insert(expr, Arc::new(ty));
@@ -131,6 +136,18 @@ impl TypeEnvironment {
         self.expr_types.get(&expr).map(|arc| arc.as_ref())
     }
 
+    /// Replaces every binding and expression type with `f` applied to it.
+    pub fn map_types(&mut self, mut f: impl FnMut(&Type) -> Type) {
+        for scope in &mut self.scopes {
+            for ty in scope.bindings.values_mut() {
+                *ty = Arc::new(f(ty));
+            }
+        }
+        for ty in self.expr_types.values_mut() {
+            *ty = Arc::new(f(ty));
+        }
+    }
+
     /// Returns all name bindings across all scopes.
     pub fn bindings(&self) -> impl Iterator<Item = (&Name, &Type)> {
         self.scopes
@@ -0,0 +1,41 @@
+//! Types of functions that an embedding host provides to NX code.
+//!
+//! Hosts register the Rust implementations with the interpreter and declare their types here, so
+//! that scope and type checking accept calls to them.
+
+use crate::Type;
+use std::collections::BTreeMap;
+
+/// Declared types of host functions, by the name NX code calls them with
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HostFunctionTypes {
+    functions: BTreeMap<String, Type>,
+}
+
+impl HostFunctionTypes {
+    /// Creates an empty set of declarations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares `name` as a host function of type `ty`, usually a [`Type::function`], replacing
+    /// any earlier declaration. Returns false if `name` already had this type.
+    pub fn declare(&mut self, name: impl Into<String>, ty: Type) -> bool {
+        self.functions.insert(name.into(), ty.clone()) != Some(ty)
+    }
+
+    /// Returns the declared type of `name`.
+    pub fn get(&self, name: &str) -> Option<&Type> {
+        self.functions.get(name)
+    }
+
+    /// Returns the declarations ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Type)> {
+        self.functions.iter().map(|(name, ty)| (name.as_str(), ty))
+    }
+
+    /// Returns true if no host function is declared.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+}
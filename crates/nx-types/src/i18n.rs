@@ -0,0 +1,102 @@
+//! Translatable messages marked with the `t` builtin.
+//!
+//! `t("Save changes")` marks text for translation, and `t("Hello, {0}!", [name])` fills numbered
+//! placeholders from an array after translating. Extraction tools list the messages of a module
+//! with [`message_references`] and write them to a catalog for translators; at runtime the
+//! interpreter looks each message up in the catalog the host provides.
+//!
+//! Only a string literal can be extracted, so a call whose message is computed is reported with
+//! no text.
+
+use nx_diagnostics::TextSpan;
+use nx_hir::ast::{Expr, Literal};
+use nx_hir::LoweredModule;
+
+/// Name of the builtin that marks translatable messages.
+pub const TRANSLATE_BUILTIN: &str = "t";
+
+/// One call of the `t` builtin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageReference {
+    /// The message, or `None` when the first argument is not a string literal.
+    pub message: Option<String>,
+    /// Source span of the call.
+    pub span: TextSpan,
+}
+
+/// Returns every call of the `t` builtin in `module`, in source order.
+///
+/// Returns nothing when the module declares its own `t`, which shadows the builtin.
+pub fn message_references(module: &LoweredModule) -> Vec<MessageReference> {
+    if module
+        .items()
+        .iter()
+        .any(|item| item.name().as_str() == TRANSLATE_BUILTIN)
+    {
+        return Vec::new();
+    }
+
+    let mut references: Vec<_> = module
+        .exprs()
+        .filter_map(|(_, expr)| {
+            let Expr::Call { func, args, span } = expr else {
+                return None;
+            };
+            if !matches!(module.expr(*func), Expr::Ident(name) if name.as_str() == TRANSLATE_BUILTIN)
+            {
+                return None;
+            }
+            let message = match args.first().map(|arg| module.expr(*arg)) {
+                Some(Expr::Literal(Literal::String(text))) => Some(text.to_string()),
+                _ => None,
+            };
+            Some(MessageReference {
+                message,
+                span: *span,
+            })
+        })
+        .collect();
+    references.sort_by_key(|reference| reference.span.start());
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_hir::{lower, SourceId};
+
+    fn messages(source: &str) -> Vec<Option<String>> {
+        let parse = nx_syntax::parse_str(source, "test.nx");
+        let module = lower(parse.root().expect("root"), SourceId::new(0));
+        message_references(&module)
+            .into_iter()
+            .map(|reference| reference.message)
+            .collect()
+    }
+
+    #[test]
+    fn literal_messages_are_listed_in_source_order() {
+        let source = r#"
+            let title(name:string) = { t("Hello, {0}!", [name]) }
+            let root() = <main><h1>{title("Ada")}</h1><p>{t("Welcome back")}</p></main>
+            let greeting(key:string) = { t(key) }
+        "#;
+        assert_eq!(
+            messages(source),
+            vec![
+                Some("Hello, {0}!".to_string()),
+                Some("Welcome back".to_string()),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn a_declared_t_shadows_the_builtin() {
+        let source = r#"
+            let t(key:string) = { key }
+            let root() = <p>{t("Welcome back")}</p>
+        "#;
+        assert!(messages(source).is_empty());
+    }
+}
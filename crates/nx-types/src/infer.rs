@@ -1,20 +1,33 @@
 //! Type inference for expressions.
 
 use crate::{
+    bounds::{sign_of, Sign, SignFacts},
+    call_graph::function_inference_order,
     common_supertype as generic_common_supertype, is_object_type, resolve_type_ref_with,
     resolve_type_ref_with_seen,
     ty::{EnumType, UnionCaseType, UnionType},
-    type_satisfies_expected as generic_type_satisfies_expected, Type, TypeEnvironment,
+    type_satisfies_expected as generic_type_satisfies_expected,
+    unify::Substitution,
+    HostFunctionTypes, Type, TypeEnvironment,
 };
-use nx_diagnostics::{Diagnostic, Label, TextSpan};
+use nx_diagnostics::{suggest, Diagnostic, Fix, Label, TextEdit, TextSize, TextSpan};
 use nx_hir::{
     ast, effective_component_contract_for_name, effective_record_shape_for_name,
     interface_component, interface_enum, interface_function_signature, interface_type_alias,
     interface_union, is_record_subtype, ExprId, InterfaceItemKind, Item, Name,
-    PreparedBindingOrigin, PreparedModule, PreparedNamespace, PropertyEntry, ResolvedPreparedItem,
-    UnionCaseDef, UnionDef,
+    PreparedBindingOrigin, PreparedBindingTarget, PreparedModule, PreparedNamespace, PropertyEntry,
+    ResolvedPreparedItem, UnionCaseDef, UnionDef,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::time::Instant;
+
+/// Upper bound on re-inference passes over a group of mutually recursive functions.
+const MAX_RECURSIVE_INFERENCE_PASSES: usize = 8;
+/// HTML void elements, which never have content.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
 
 struct TypeAliasInfo {
     target: ast::TypeRef,
@@ -25,6 +38,8 @@ struct ElementBindingSpec {
     content_property: Option<Name>,
     properties: FxHashMap<Name, ElementPropertySpec>,
     handler_properties: FxHashSet<Name>,
+    /// Whether undeclared properties are captured by a `...rest:props` binding.
+    accepts_rest: bool,
 }
 
 struct ElementPropertySpec {
@@ -35,6 +50,8 @@ struct ElementPropertySpec {
 #[derive(Clone)]
 struct PropertyPath {
     properties: Vec<PropertyPathBinding>,
+    /// Whether a `{...record}` spread on this path may supply properties not listed here.
+    spread: bool,
 }
 
 #[derive(Clone)]
@@ -63,6 +80,8 @@ pub struct InferenceContext<'a> {
     diagnostics: Vec<Diagnostic>,
     /// Next type variable ID for inference
     next_var_id: u32,
+    /// Type variables solved so far
+    substitution: Substitution,
     /// Placeholder return types for functions without explicit annotations
     function_return_placeholders: FxHashMap<Name, Type>,
     /// Registered type aliases
@@ -71,6 +90,8 @@ pub struct InferenceContext<'a> {
     enum_defs: FxHashMap<Name, EnumType>,
     /// Registered discriminated union definitions.
     union_defs: FxHashMap<Name, UnionDef>,
+    /// Signs of names established by the enclosing `if` conditions and `for` loops
+    sign_facts: SignFacts,
 }
 
 impl<'a> InferenceContext<'a> {
@@ -81,22 +102,40 @@ impl<'a> InferenceContext<'a> {
 
     /// Creates a new inference context for a module with a diagnostic file name.
     pub fn with_file_name(module: &'a PreparedModule, file_name: impl Into<String>) -> Self {
+        Self::with_host_functions(module, file_name, &HostFunctionTypes::default())
+    }
+
+    /// Creates a new inference context for a module whose code may call the host functions
+    /// declared in `host_functions`. Module bindings shadow host functions of the same name.
+    pub fn with_host_functions(
+        module: &'a PreparedModule,
+        file_name: impl Into<String>,
+        host_functions: &HostFunctionTypes,
+    ) -> Self {
         let mut ctx = Self {
             module,
             file_name: file_name.into(),
             env: TypeEnvironment::new(),
             diagnostics: Vec::new(),
             next_var_id: 0,
+            substitution: Substitution::new(),
             function_return_placeholders: FxHashMap::default(),
             type_aliases: FxHashMap::default(),
             enum_defs: FxHashMap::default(),
             union_defs: FxHashMap::default(),
+            sign_facts: SignFacts::default(),
         };
         ctx.register_type_definitions();
+        for (name, ty) in host_functions.iter() {
+            ctx.env.bind(Name::new(name), ty.clone());
+        }
         ctx.register_function_signatures();
         ctx.register_value_bindings();
         ctx.validate_local_record_defaults();
+        ctx.validate_local_record_constraints();
+        ctx.validate_local_param_defaults();
         ctx.validate_local_union_defaults();
+        ctx.validate_module_metadata();
         ctx
     }
 
@@ -131,7 +170,7 @@ impl<'a> InferenceContext<'a> {
             // Identifiers look up in environment
             ast::Expr::Ident(name) => {
                 if let Some(ty) = self.env.lookup(name) {
-                    ty.clone()
+                    self.substitution.apply(ty)
                 } else {
                     Type::Error
                 }
@@ -142,7 +181,15 @@ impl<'a> InferenceContext<'a> {
                 let lhs_ty = self.infer_expr(*lhs);
                 let rhs_ty = self.infer_expr(*rhs);
 
-                self.infer_binop(*op, &lhs_ty, &rhs_ty, *span)
+                let ty = self.infer_binop(*op, &lhs_ty, &rhs_ty, *span);
+                let numeric = matches!(&ty, Type::Primitive(primitive) if primitive.is_numeric());
+                if matches!(op, ast::BinOp::Div | ast::BinOp::Mod)
+                    && numeric
+                    && !self.sign_of(*rhs).is_some_and(Sign::is_non_zero)
+                {
+                    self.warn_possible_division_by_zero(*op, *rhs, *span);
+                }
+                ty
             }
 
             // Unary operations
@@ -156,9 +203,47 @@ impl<'a> InferenceContext<'a> {
                 let func_ty = self.infer_expr(*func);
 
                 // Infer argument types
-                let arg_tys: Vec<_> = args.iter().map(|arg| self.infer_expr(*arg)).collect();
+                let arg_tys = self.infer_call_args(&func_ty, args);
 
-                self.infer_call(&func_ty, &arg_tys, *span)
+                if let Some(name) = self.builtin_callee(*func) {
+                    self.infer_builtin_call(&name, &arg_tys, *span)
+                } else {
+                    let arg_tys: Vec<_> = arg_tys.into_iter().map(Some).collect();
+                    self.infer_call(&func_ty, &arg_tys, *span)
+                }
+            }
+
+            // Partial application: the open arguments become the parameters of a new function
+            ast::Expr::PartialApply { func, args, span } => {
+                if let Some(name) = self.builtin_callee(*func) {
+                    for arg in args.iter().flatten() {
+                        self.infer_expr(*arg);
+                    }
+                    self.error_with_help(
+                        "partial-application",
+                        format!("Builtin '{}' cannot be partially applied", name),
+                        *span,
+                        Some(
+                            "Declare a function that takes the missing arguments and calls it"
+                                .to_string(),
+                        ),
+                    );
+                    Type::Error
+                } else {
+                    let func_ty = self.infer_expr(*func);
+                    let params = match &func_ty {
+                        Type::Function { params, .. } if params.len() == args.len() => {
+                            params.clone()
+                        }
+                        _ => vec![Type::Unknown; args.len()],
+                    };
+                    let arg_tys: Vec<_> = args
+                        .iter()
+                        .zip(&params)
+                        .map(|(arg, param)| arg.map(|arg| self.infer_expr_expecting(arg, param)))
+                        .collect();
+                    self.infer_call(&func_ty, &arg_tys, *span)
+                }
             }
 
             // If expressions
@@ -174,15 +259,24 @@ impl<'a> InferenceContext<'a> {
                 if !cond_ty.is_compatible_with(&Type::bool()) && !cond_ty.is_error() {
                     self.error(
                         "type-mismatch",
-                        format!("If condition must be bool, found {}", cond_ty),
+                        format!(
+                            "If condition must be bool, found {}",
+                            self.display_type(&cond_ty)
+                        ),
                         *span,
                     );
                 }
 
+                let mark = self.sign_facts.mark();
+                let module = self.module.raw_module();
+                self.sign_facts.assume_condition(module, *condition, true);
                 let then_ty = self.infer_expr(*then_branch);
+                self.sign_facts.restore(mark);
 
                 if let Some(else_id) = else_branch {
+                    self.sign_facts.assume_condition(module, *condition, false);
                     let else_ty = self.infer_expr(*else_id);
+                    self.sign_facts.restore(mark);
 
                     self.common_supertype(&then_ty, &else_ty)
                 } else {
@@ -219,9 +313,16 @@ impl<'a> InferenceContext<'a> {
                 if !index_ty.is_compatible_with(&Type::int()) && !index_ty.is_error() {
                     self.error(
                         "type-mismatch",
-                        format!("Array index must be int, found {}", index_ty),
+                        format!(
+                            "Array index must be int, found {}",
+                            self.display_type(&index_ty)
+                        ),
                         *span,
                     );
+                } else if !index_ty.is_error()
+                    && !self.sign_of(*index).is_some_and(Sign::is_non_negative)
+                {
+                    self.warn_possible_negative_index(*index, *span);
                 }
 
                 // Base must be array
@@ -271,15 +372,8 @@ impl<'a> InferenceContext<'a> {
                         if enum_info.members.iter().any(|m| m == member) {
                             Type::Enum(enum_info.clone())
                         } else {
-                            self.error(
-                                "undefined-enum-member",
-                                format!(
-                                    "Enum '{}' has no member named '{}'",
-                                    enum_info.name, member
-                                ),
-                                *span,
-                            );
-                            Type::Error
+                            let enum_info = enum_info.clone();
+                            self.report_undefined_enum_member(&enum_info, member, *span)
                         }
                     } else {
                         let base_ty = self.infer_expr(*base);
@@ -289,12 +383,8 @@ impl<'a> InferenceContext<'a> {
                     if enum_info.members.iter().any(|m| m == member) {
                         Type::Enum(enum_info.clone())
                     } else {
-                        self.error(
-                            "undefined-enum-member",
-                            format!("Enum '{}' has no member named '{}'", enum_info.name, member),
-                            *span,
-                        );
-                        Type::Error
+                        let enum_info = enum_info.clone();
+                        self.report_undefined_enum_member(&enum_info, member, *span)
                     }
                 } else {
                     let base_ty = self.infer_expr(*base);
@@ -334,28 +424,53 @@ impl<'a> InferenceContext<'a> {
                 body,
                 ..
             } => {
-                // Infer iterable type (should be array)
+                // Arrays bind each element and its int index; records bind each field value and
+                // its string name.
                 let iterable_ty = self.infer_expr(*iterable);
-                let item_ty = match iterable_ty.clone() {
-                    Type::Array(inner) => *inner,
-                    Type::Error => Type::Error,
-                    other => {
-                        self.error(
-                            "type-mismatch",
-                            format!("For iterable must be an array, found {}", other),
-                            expr.span(),
-                        );
-                        Type::Error
+                let (item_ty, index_ty) = match iterable_ty.clone() {
+                    Type::Array(inner) => (*inner, Type::int()),
+                    Type::Nullable(array) if matches!(*array, Type::Array(_)) => {
+                        self.warn_nullable_iteration(*iterable, &iterable_ty, expr.span());
+                        let Type::Array(inner) = *array else {
+                            unreachable!("checked by the match guard")
+                        };
+                        (*inner, Type::int())
                     }
+                    Type::Error => (Type::Error, Type::int()),
+                    other => match self.record_field_value_type(&other) {
+                        Some(value_ty) => (value_ty, Type::string()),
+                        None => {
+                            self.error(
+                                "type-mismatch",
+                                format!(
+                                    "For iterable must be an array or record, found {}",
+                                    self.display_type(&other)
+                                ),
+                                expr.span(),
+                            );
+                            (Type::Error, Type::int())
+                        }
+                    },
                 };
 
+                let mark = self.sign_facts.mark();
+                self.sign_facts.forget(item.clone());
+                if let Some(index_name) = index {
+                    if index_ty == Type::int() {
+                        self.sign_facts
+                            .assume(index_name.clone(), Sign::NonNegative);
+                    } else {
+                        self.sign_facts.forget(index_name.clone());
+                    }
+                }
                 self.env.push_scope();
                 self.env.bind(item.clone(), item_ty);
                 if let Some(index_name) = index {
-                    self.env.bind(index_name.clone(), Type::int());
+                    self.env.bind(index_name.clone(), index_ty);
                 }
                 let body_ty = self.infer_expr(*body);
                 self.env.pop_scope();
+                self.sign_facts.restore(mark);
 
                 Type::array(body_ty)
             }
@@ -372,16 +487,51 @@ impl<'a> InferenceContext<'a> {
 
                 // Bind the name to the value type in this scope
                 self.env.bind(name.clone(), value_ty);
+                let mark = self.sign_facts.mark();
+                self.sign_facts.forget(name.clone());
 
                 // Infer the body with the binding in scope
                 let body_ty = self.infer_expr(*body);
 
                 // Pop the scope to remove the binding
+                self.sign_facts.restore(mark);
                 self.env.pop_scope();
 
                 body_ty
             }
 
+            // Context expressions take the type of the top-level value used as the key
+            ast::Expr::Provide {
+                key,
+                value,
+                body,
+                span,
+            } => {
+                let key_ty = self.context_key_type(key, *span);
+                let value_ty = match key_ty.as_ref() {
+                    Some(key_ty) => self.infer_expr_expecting(*value, key_ty),
+                    None => self.infer_expr(*value),
+                };
+                if let Some(key_ty) = key_ty {
+                    self.check_typed_binding(
+                        &value_ty,
+                        &key_ty,
+                        *span,
+                        "context-type-mismatch",
+                        format!("Context '{}'", key),
+                    );
+                }
+                self.infer_expr(*body)
+            }
+            ast::Expr::UseContext { key, span } => {
+                self.context_key_type(key, *span).unwrap_or(Type::Error)
+            }
+
+            ast::Expr::Destructure { source, part, span } => {
+                let source_ty = self.infer_expr(*source);
+                self.infer_destructure(&source_ty, part, *span)
+            }
+
             // Error expressions
             ast::Expr::Error(_) => Type::Error,
         };
@@ -391,6 +541,57 @@ impl<'a> InferenceContext<'a> {
         ty
     }
 
+    /// Infers the type of an expression whose expected type is already known.
+    ///
+    /// Expressions with no type of their own take it from the expectation: `[]` becomes an array
+    /// of the expected element type and `null` the expected nullable type, instead of leaving an
+    /// unresolved type variable. Array elements are inferred against the expected element type
+    /// in turn. Everything else is inferred as by [`Self::infer_expr`], and callers still check
+    /// the result against `expected`.
+    pub fn infer_expr_expecting(&mut self, expr_id: ExprId, expected: &Type) -> Type {
+        if expected.is_error() || expected.is_unknown() || expected.is_variable() {
+            return self.infer_expr(expr_id);
+        }
+
+        let ty = match self.module.raw_module().expr(expr_id) {
+            ast::Expr::Literal(ast::Literal::Null) if expected.is_nullable() => expected.clone(),
+            ast::Expr::Literal(ast::Literal::Null) => Type::nullable(expected.clone()),
+            ast::Expr::Array { elements, span } => {
+                let Type::Array(expected_item) = expected.strip_nullable() else {
+                    return self.infer_expr(expr_id);
+                };
+                if elements.is_empty() {
+                    Type::array(expected_item.as_ref().clone())
+                } else {
+                    let elem_tys: Vec<_> = elements
+                        .iter()
+                        .map(|element| self.infer_expr_expecting(*element, expected_item))
+                        .collect();
+                    Type::array(self.common_sequence_item_type(&elem_tys, *span))
+                }
+            }
+            _ => return self.infer_expr(expr_id),
+        };
+
+        self.env.set_expr_type(expr_id, ty.clone());
+        ty
+    }
+
+    /// Infers call arguments, passing each parameter type of a known function type inward.
+    fn infer_call_args(&mut self, func_ty: &Type, args: &[ExprId]) -> Vec<Type> {
+        let params = match func_ty {
+            Type::Function { params, .. } if params.len() == args.len() => Some(params.clone()),
+            _ => None,
+        };
+        args.iter()
+            .enumerate()
+            .map(|(index, arg)| match params.as_ref() {
+                Some(params) => self.infer_expr_expecting(*arg, &params[index]),
+                None => self.infer_expr(*arg),
+            })
+            .collect()
+    }
+
     /// Infers all types within a function, binding parameters while visiting the body.
     pub fn infer_function(&mut self, func: &nx_hir::Function) {
         let mut bound_names = Vec::new();
@@ -401,21 +602,21 @@ impl<'a> InferenceContext<'a> {
             bound_names.push(param.name.clone());
         }
 
-        let body_ty = self.infer_expr(func.body);
+        let expected_return = func
+            .return_type
+            .as_ref()
+            .map(|ty| self.type_from_type_ref(ty));
+        let body_ty = match expected_return.as_ref() {
+            Some(expected) => self.infer_expr_expecting(func.body, expected),
+            None => self.infer_expr(func.body),
+        };
 
         for name in bound_names {
             self.env.remove(&name);
         }
 
-        let return_ty = if let Some(ty) = func.return_type.as_ref() {
-            let expected = self.type_from_type_ref(ty);
-            self.check_typed_binding(
-                &body_ty,
-                &expected,
-                func.span,
-                "return-type-mismatch",
-                format!("Return value for function '{}'", func.name),
-            );
+        let return_ty = if let Some(expected) = expected_return {
+            self.check_return_value(func, &body_ty, &expected);
             expected
         } else {
             body_ty.clone()
@@ -427,6 +628,183 @@ impl<'a> InferenceContext<'a> {
         }
     }
 
+    /// Checks a function body against its declared return type.
+    ///
+    /// Each result expression, such as every branch of an `if` or `match`, must conform on its
+    /// own: branches of unrelated types join to `object`, which would otherwise hide the
+    /// mismatch. Mismatches are reported at the offending result rather than at the function as
+    /// a whole.
+    fn check_return_value(&mut self, func: &nx_hir::Function, body_ty: &Type, expected: &Type) {
+        let context = format!("Return value for function '{}'", func.name);
+        let _ = self.substitution.unify(body_ty, expected);
+        let expected = self.substitution.finalize(expected);
+
+        let mut results = Vec::new();
+        self.collect_result_exprs(func.body, func.span, &mut results);
+        let mut reported = false;
+        for (expr_id, span) in results {
+            let Some(result_ty) = self.env.get_expr_type(expr_id).cloned() else {
+                continue;
+            };
+            let result_ty = self.substitution.finalize(&result_ty);
+            if !self.type_satisfies_expected_with_coercion(&result_ty, &expected) {
+                self.check_typed_binding(
+                    &result_ty,
+                    &expected,
+                    span,
+                    "return-type-mismatch",
+                    context.clone(),
+                );
+                reported = true;
+            }
+        }
+
+        // The results may each conform while their combination does not, e.g. an `if` without
+        // an `else` against a non-nullable return type.
+        if !reported {
+            let span = non_empty_span(self.module.raw_module().expr(func.body).span())
+                .unwrap_or(func.span);
+            self.check_typed_binding(body_ty, &expected, span, "return-type-mismatch", context);
+        }
+    }
+
+    /// Collects the expressions whose values become the value of `expr_id`, descending through
+    /// blocks, bindings, and branches, each paired with the span to report it at.
+    ///
+    /// Literals and identifiers carry no span of their own, so they are reported at the nearest
+    /// enclosing expression that has one.
+    fn collect_result_exprs(
+        &self,
+        expr_id: ExprId,
+        enclosing_span: TextSpan,
+        results: &mut Vec<(ExprId, TextSpan)>,
+    ) {
+        let expr = self.module.raw_module().expr(expr_id);
+        let span = non_empty_span(expr.span()).unwrap_or(enclosing_span);
+        match expr {
+            ast::Expr::Block {
+                expr: Some(result), ..
+            } => self.collect_result_exprs(*result, span, results),
+            ast::Expr::Let { body, .. } => self.collect_result_exprs(*body, span, results),
+            ast::Expr::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.collect_result_exprs(*then_branch, span, results);
+                if let Some(else_branch) = else_branch {
+                    self.collect_result_exprs(*else_branch, span, results);
+                }
+            }
+            ast::Expr::Match {
+                arms, else_branch, ..
+            } => {
+                for arm in arms {
+                    self.collect_result_exprs(arm.body, span, results);
+                }
+                if let Some(else_branch) = else_branch {
+                    self.collect_result_exprs(*else_branch, span, results);
+                }
+            }
+            _ => results.push((expr_id, span)),
+        }
+    }
+
+    /// Infers all local functions, inferring callees before their callers.
+    ///
+    /// Mutually recursive functions without return type annotations are re-inferred together
+    /// until their return types stop changing. A return type that is still unresolved after that
+    /// is reported, since only an annotation can determine it.
+    pub fn infer_functions(&mut self) {
+        self.infer_function_groups(None);
+    }
+
+    /// Infers function types like [`infer_functions`](Self::infer_functions), but stops before
+    /// the next group of mutually recursive functions once `deadline` has passed.
+    ///
+    /// Returns true if every function was inferred. Functions that were not reached keep their
+    /// declared signatures and contribute no diagnostics.
+    pub fn infer_functions_until(&mut self, deadline: Instant) -> bool {
+        self.infer_function_groups(Some(deadline))
+    }
+
+    fn infer_function_groups(&mut self, deadline: Option<Instant>) -> bool {
+        let module = self.module;
+        for group in function_inference_order(module.raw_module()) {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return false;
+            }
+            let needs_fixpoint = group.recursive
+                && group
+                    .functions
+                    .iter()
+                    .any(|func| func.return_type.is_none());
+            if needs_fixpoint {
+                self.infer_recursive_functions(&group.functions);
+            } else {
+                for func in group.functions {
+                    self.infer_function(func);
+                }
+            }
+        }
+        true
+    }
+
+    fn infer_recursive_functions(&mut self, functions: &[&nx_hir::Function]) {
+        let mut previous = self.function_return_types(functions);
+        for pass in 1..=MAX_RECURSIVE_INFERENCE_PASSES {
+            let diagnostics_start = self.diagnostics.len();
+            for func in functions {
+                self.infer_function(func);
+            }
+
+            let current = self.function_return_types(functions);
+            // Once a return type becomes an error, later passes would only suppress its cause.
+            if current == previous
+                || current.iter().any(Type::is_error)
+                || pass == MAX_RECURSIVE_INFERENCE_PASSES
+            {
+                break;
+            }
+            // Diagnostics from a pass that saw stale return types are recomputed by the next.
+            self.diagnostics.truncate(diagnostics_start);
+            previous = current;
+        }
+
+        for func in functions {
+            let unresolved = func.return_type.is_none()
+                && self
+                    .function_return_types(std::slice::from_ref(func))
+                    .first()
+                    .is_some_and(Type::is_variable);
+            if unresolved {
+                self.error_with_help(
+                    "unresolved-return-type",
+                    format!(
+                        "Cannot infer the return type of recursive function '{}'",
+                        func.name
+                    ),
+                    func.span,
+                    Some(format!(
+                        "Add a return type annotation to '{}' or a non-recursive branch to its body",
+                        func.name
+                    )),
+                );
+                self.bind_function_signature(func, Type::Error);
+            }
+        }
+    }
+
+    fn function_return_types(&self, functions: &[&nx_hir::Function]) -> Vec<Type> {
+        functions
+            .iter()
+            .map(|func| match self.env.lookup(&func.name) {
+                Some(Type::Function { ret, .. }) => ret.as_ref().clone(),
+                _ => Type::Error,
+            })
+            .collect()
+    }
+
     /// Infers the type of a literal.
     fn infer_literal(&mut self, lit: &ast::Literal) -> Type {
         match lit {
@@ -625,12 +1003,55 @@ impl<'a> InferenceContext<'a> {
     ) -> Type {
         use ast::BinOp::*;
 
+        let lhs = &self.substitution.apply(lhs);
+        let rhs = &self.substitution.apply(rhs);
+
         // Skip error checking if either operand is error
         if lhs.is_error() || rhs.is_error() {
             return Type::Error;
         }
 
+        // A pending recursive return type is resolved by a later inference pass, so assume the
+        // operation is well-typed for now. Operands with no static type, such as `parseCsv` cells,
+        // are checked at runtime instead.
+        let is_open = |ty: &Type| ty.is_variable() || ty.is_unknown();
+        if is_open(lhs) || is_open(rhs) {
+            return match op {
+                Add | Sub | Mul | Div | Mod if is_open(lhs) => rhs.clone(),
+                Add | Sub | Mul | Div | Mod => lhs.clone(),
+                Eq | Ne | Lt | Le | Gt | Ge | And | Or => Type::bool(),
+                Concat => Type::string(),
+            };
+        }
+
         match op {
+            // Money: amounts add to amounts and scale by numbers; the currencies are checked at
+            // runtime
+            Add | Sub | Mul | Div | Mod if *lhs == Type::money() || *rhs == Type::money() => {
+                let is_number = |ty: &Type| matches!(ty, Type::Primitive(p) if p.is_numeric());
+                let allowed = match op {
+                    Add | Sub => lhs == rhs,
+                    Mul => is_number(lhs) || is_number(rhs),
+                    Div => is_number(rhs),
+                    _ => false,
+                };
+                if allowed {
+                    Type::money()
+                } else {
+                    self.error(
+                        "type-mismatch",
+                        format!(
+                            "Binary operator {:?} cannot be applied to types {} and {}",
+                            op,
+                            self.display_type(lhs),
+                            self.display_type(rhs)
+                        ),
+                        span,
+                    );
+                    Type::Error
+                }
+            }
+
             // Arithmetic: same numeric category with promotion
             Add | Sub | Mul | Div | Mod => {
                 if let (Type::Primitive(a), Type::Primitive(b)) = (lhs, rhs) {
@@ -640,7 +1061,11 @@ impl<'a> InferenceContext<'a> {
                         } else {
                             self.error(
                                 "type-mismatch",
-                                format!("Cannot mix integer and float types: {} and {}", lhs, rhs),
+                                format!(
+                                    "Cannot mix integer and float types: {} and {}",
+                                    self.display_type(lhs),
+                                    self.display_type(rhs)
+                                ),
                                 span,
                             );
                             return Type::Error;
@@ -649,12 +1074,17 @@ impl<'a> InferenceContext<'a> {
                 }
                 if lhs == &Type::string() && rhs == &Type::string() && op == Add {
                     Type::string()
+                } else if op == Add && is_nullable_string_concat(lhs, rhs) {
+                    self.warn_null_concatenation(lhs, rhs, span);
+                    Type::string()
                 } else {
                     self.error(
                         "type-mismatch",
                         format!(
                             "Binary operator {:?} cannot be applied to types {} and {}",
-                            op, lhs, rhs
+                            op,
+                            self.display_type(lhs),
+                            self.display_type(rhs)
                         ),
                         span,
                     );
@@ -670,7 +1100,11 @@ impl<'a> InferenceContext<'a> {
                 } else {
                     self.error(
                         "type-mismatch",
-                        format!("Cannot compare types {} and {}", lhs, rhs),
+                        format!(
+                            "Cannot compare types {} and {}",
+                            self.display_type(lhs),
+                            self.display_type(rhs)
+                        ),
                         span,
                     );
                     Type::Error
@@ -686,7 +1120,9 @@ impl<'a> InferenceContext<'a> {
                         "type-mismatch",
                         format!(
                             "Logical operator {:?} requires bool operands, found {} and {}",
-                            op, lhs, rhs
+                            op,
+                            self.display_type(lhs),
+                            self.display_type(rhs)
                         ),
                         span,
                     );
@@ -698,12 +1134,16 @@ impl<'a> InferenceContext<'a> {
                 // String concatenation
                 if lhs == &Type::string() && rhs == &Type::string() {
                     Type::string()
+                } else if is_nullable_string_concat(lhs, rhs) {
+                    self.warn_null_concatenation(lhs, rhs, span);
+                    Type::string()
                 } else {
                     self.error(
                         "type-mismatch",
                         format!(
                             "String concatenation requires string operands, found {} and {}",
-                            lhs, rhs
+                            self.display_type(lhs),
+                            self.display_type(rhs)
                         ),
                         span,
                     );
@@ -720,20 +1160,31 @@ impl<'a> InferenceContext<'a> {
         operand: &Type,
         span: nx_diagnostics::TextSpan,
     ) -> Type {
+        let operand = &self.substitution.apply(operand);
         if operand.is_error() {
             return Type::Error;
         }
 
+        if operand.is_variable() {
+            return match op {
+                ast::UnOp::Neg => operand.clone(),
+                ast::UnOp::Not => Type::bool(),
+            };
+        }
+
         match op {
             ast::UnOp::Neg => {
                 if let Type::Primitive(p) = operand {
-                    if p.is_numeric() {
+                    if p.is_numeric() || *p == crate::ty::Primitive::Money {
                         return operand.clone();
                     }
                 }
                 self.error(
                     "type-mismatch",
-                    format!("Negation requires a numeric type, found {}", operand),
+                    format!(
+                        "Negation requires a numeric type, found {}",
+                        self.display_type(operand)
+                    ),
                     span,
                 );
                 Type::Error
@@ -744,7 +1195,10 @@ impl<'a> InferenceContext<'a> {
                 } else {
                     self.error(
                         "type-mismatch",
-                        format!("Logical NOT requires bool, found {}", operand),
+                        format!(
+                            "Logical NOT requires bool, found {}",
+                            self.display_type(operand)
+                        ),
                         span,
                     );
                     Type::Error
@@ -753,11 +1207,76 @@ impl<'a> InferenceContext<'a> {
         }
     }
 
+    /// Returns the builtin name called by `func`, unless a binding in scope shadows it.
+    fn builtin_callee(&self, func: ExprId) -> Option<Name> {
+        match self.module.raw_module().expr(func) {
+            ast::Expr::Ident(name)
+                if crate::builtins::is_builtin(name.as_str())
+                    && self.env.lookup(name).is_none() =>
+            {
+                Some(name.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Infers the result type of a call to a builtin function by picking a matching overload.
+    fn infer_builtin_call(
+        &mut self,
+        name: &Name,
+        arg_tys: &[Type],
+        span: nx_diagnostics::TextSpan,
+    ) -> Type {
+        if arg_tys.iter().any(Type::is_error) {
+            return Type::Error;
+        }
+
+        // A pending recursive return type is resolved by a later pass; accept any overload for it.
+        let resolvable_tys = arg_tys
+            .iter()
+            .map(|ty| {
+                if ty.is_variable() {
+                    Type::Unknown
+                } else {
+                    ty.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(signature) = crate::builtins::resolve_builtin(name.as_str(), &resolvable_tys) {
+            return crate::builtins::builtin_return_type(
+                name.as_str(),
+                &signature,
+                &resolvable_tys,
+            );
+        }
+
+        let overloads = crate::builtins::builtin_signatures(name.as_str())
+            .iter()
+            .map(|signature| format!("{}{}", name, signature))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let args = arg_tys
+            .iter()
+            .map(|ty| self.display_type(ty).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.error_with_help(
+            "no-matching-overload",
+            format!("No overload of '{}' accepts ({})", name, args),
+            span,
+            Some(format!("Available overloads: {}", overloads)),
+        );
+        Type::Error
+    }
+
     /// Infers the result type of a function call.
+    ///
+    /// A `None` argument is a `_` placeholder: when any are present the result is a function taking
+    /// the placeholder parameters in order and returning the callee's return type.
     fn infer_call(
         &mut self,
         func_ty: &Type,
-        arg_tys: &[Type],
+        arg_tys: &[Option<Type>],
         span: nx_diagnostics::TextSpan,
     ) -> Type {
         if func_ty.is_error() {
@@ -781,17 +1300,27 @@ impl<'a> InferenceContext<'a> {
                 }
 
                 // Check argument types
+                let mut open_params = Vec::new();
                 for (i, (param_ty, arg_ty)) in params.iter().zip(arg_tys.iter()).enumerate() {
-                    self.check_typed_binding(
-                        arg_ty,
-                        param_ty,
-                        span,
-                        "type-mismatch",
-                        format!("Argument {}", i),
-                    );
+                    match arg_ty {
+                        Some(arg_ty) => {
+                            self.check_typed_binding(
+                                arg_ty,
+                                param_ty,
+                                span,
+                                "type-mismatch",
+                                format!("Argument {}", i),
+                            );
+                        }
+                        None => open_params.push(param_ty.clone()),
+                    }
                 }
 
-                (**ret).clone()
+                if arg_tys.iter().any(Option::is_none) {
+                    Type::function(open_params, (**ret).clone())
+                } else {
+                    (**ret).clone()
+                }
             }
             _ => {
                 self.error(
@@ -844,10 +1373,82 @@ impl<'a> InferenceContext<'a> {
                     Type::Error
                 }),
             Type::Error => Type::Error,
-            _ => {
+            // Fields of a value with no static type, such as a `parseCsv` row, are unchecked.
+            Type::Unknown => Type::Unknown,
+            Type::Named(name) => match self.effective_record_shape(name).ok().flatten() {
+                Some(shape) => match shape.fields.iter().find(|field| field.name == *member) {
+                    Some(field) => self.type_from_type_ref(&field.ty),
+                    None => {
+                        self.error(
+                            "unknown-record-field",
+                            format!("Record '{}' has no field '{}'", name, member),
+                            span,
+                        );
+                        Type::Error
+                    }
+                },
+                None => self.unsupported_member_access(member, span),
+            },
+            _ => self.unsupported_member_access(member, span),
+        }
+    }
+
+    fn unsupported_member_access(&mut self, member: &Name, span: TextSpan) -> Type {
+        self.error(
+            "not-implemented",
+            format!("Member access not yet implemented: .{}", member),
+            span,
+        );
+        Type::Error
+    }
+
+    /// Returns the type one destructuring binding receives from a value of type `source_ty`.
+    fn infer_destructure(
+        &mut self,
+        source_ty: &Type,
+        part: &ast::DestructurePart,
+        span: TextSpan,
+    ) -> Type {
+        match (part, source_ty) {
+            (_, Type::Error) => Type::Error,
+            (_, Type::Unknown) => Type::Unknown,
+            (ast::DestructurePart::Element { .. }, Type::Array(inner)) => (**inner).clone(),
+            (ast::DestructurePart::Rest { .. }, Type::Array(_)) => source_ty.clone(),
+            (ast::DestructurePart::Field(field), Type::Union(_) | Type::UnionCase(_)) => {
+                self.infer_member_access(source_ty, field, span)
+            }
+            (ast::DestructurePart::Field(field), _) => {
+                let shape = match source_ty {
+                    Type::Named(name) => self.effective_record_shape(name).ok().flatten(),
+                    _ => None,
+                };
+                let Some(shape) = shape else {
+                    self.error(
+                        "destructure-mismatch",
+                        format!("Cannot destructure {} with a record pattern", source_ty),
+                        span,
+                    );
+                    return Type::Error;
+                };
+                match shape.fields.iter().find(|f| f.name == *field) {
+                    Some(found) => self.type_from_type_ref(&found.ty),
+                    None => {
+                        self.error(
+                            "destructure-mismatch",
+                            format!(
+                                "Record '{}' has no field '{}' to destructure",
+                                source_ty, field
+                            ),
+                            span,
+                        );
+                        Type::Error
+                    }
+                }
+            }
+            (_, _) => {
                 self.error(
-                    "not-implemented",
-                    format!("Member access not yet implemented: .{}", member),
+                    "destructure-mismatch",
+                    format!("Cannot destructure {} with an array pattern", source_ty),
                     span,
                 );
                 Type::Error
@@ -911,15 +1512,26 @@ impl<'a> InferenceContext<'a> {
             }
 
             let effective_shape = self.effective_record_shape(record).ok().flatten();
+            let mut seen = FxHashMap::<Name, TextSpan>::default();
             for property in properties {
+                if let Some(first) = seen.insert(property.name.clone(), property.span) {
+                    self.report_duplicate_property(
+                        format!(
+                            "Property '{}' on '{}' is supplied more than once",
+                            property.name, record
+                        ),
+                        property.span,
+                        first,
+                    );
+                }
                 match self.record_field_type_ref(
                     &record_def,
                     effective_shape.as_ref(),
                     &property.name,
                 ) {
                     Some(field_ty) => {
-                        let actual = self.infer_expr(property.value);
                         let expected = self.type_from_type_ref(&field_ty);
+                        let actual = self.infer_expr_expecting(property.value, &expected);
                         self.check_typed_binding(
                             &actual,
                             &expected,
@@ -984,17 +1596,18 @@ impl<'a> InferenceContext<'a> {
                         .map(|ty| self.type_from_type_ref(ty))
                         .unwrap_or_else(|| Type::named(element.tag.clone()));
                 }
-                ResolvedPreparedItem::Imported { item, .. } => {
-                    if let Some((_name, _visibility, params, return_type, _span)) =
-                        interface_function_signature(&item)
+                ResolvedPreparedItem::Imported { item, .. } => {
+                    if let InterfaceItemKind::Function {
+                        params,
+                        return_type,
+                        ..
+                    } = &item.item
                     {
-                        let spec = self.build_element_binding_spec(
-                            params
-                                .iter()
-                                .map(|param| (&param.name, &param.ty, param.is_content, true)),
-                        );
+                        let spec = self.build_element_binding_spec(params.iter().map(|param| {
+                            (&param.name, &param.ty, param.is_content, param.is_required)
+                        }));
                         self.check_element_bindings(element, span, &spec);
-                        return self.type_from_type_ref(&return_type);
+                        return self.type_from_type_ref(return_type);
                     }
                 }
                 _ => {}
@@ -1056,7 +1669,49 @@ impl<'a> InferenceContext<'a> {
             return Type::union_case_type(union_def.name, case.name);
         }
 
-        Type::named(element.tag.clone())
+        self.check_intrinsic_element(element, span);
+        Type::element()
+    }
+
+    /// Checks an element with no NX definition, such as `<div>`. Any property or content is
+    /// accepted except content on a void element, but the values themselves are still checked.
+    fn check_intrinsic_element(&mut self, element: &nx_hir::Element, span: TextSpan) {
+        let property_paths = self.property_paths_for_entries(element.property_entries());
+        self.report_duplicate_property_paths(&property_paths, &element.tag);
+        if element.content.is_empty() {
+            return;
+        }
+
+        for child in &element.content {
+            let child_ty = self.infer_expr(*child);
+            if !self.type_is_renderable(&child_ty) {
+                let child_span = self.module.raw_module().expr(*child).span();
+                self.error(
+                    "invalid-element-content",
+                    format!(
+                        "{} cannot be rendered as content of '{}'",
+                        self.display_type(&child_ty),
+                        element.tag
+                    ),
+                    if child_span.is_empty() {
+                        span
+                    } else {
+                        child_span
+                    },
+                );
+            }
+        }
+        if VOID_ELEMENTS.contains(&element.tag.as_str()) {
+            self.error_with_help(
+                "void-element-content",
+                format!(
+                    "'{}' is a void element and cannot have content",
+                    element.tag
+                ),
+                span,
+                Some(format!("Write it as `<{} />`", element.tag)),
+            );
+        }
     }
 
     fn check_element_bindings_against_function(
@@ -1065,12 +1720,14 @@ impl<'a> InferenceContext<'a> {
         function: &nx_hir::Function,
         span: TextSpan,
     ) {
-        let spec = self.build_element_binding_spec(
-            function
-                .params
-                .iter()
-                .map(|param| (&param.name, &param.ty, param.is_content, true)),
-        );
+        let spec = self.build_element_binding_spec(function.params.iter().map(|param| {
+            (
+                &param.name,
+                &param.ty,
+                param.is_content,
+                param.is_required(),
+            )
+        }));
         self.check_element_bindings(element, span, &spec);
     }
 
@@ -1105,6 +1762,12 @@ impl<'a> InferenceContext<'a> {
             .as_ref()
             .map(|contract| contract.emits.as_slice())
             .unwrap_or(component.emits.as_slice());
+        spec.accepts_rest = effective_contract
+            .as_ref()
+            .map(|contract| &contract.component)
+            .unwrap_or(component)
+            .rest
+            .is_some();
         spec.handler_properties.extend(
             emits
                 .iter()
@@ -1247,6 +1910,7 @@ impl<'a> InferenceContext<'a> {
             content_property,
             properties,
             handler_properties: FxHashSet::default(),
+            accepts_rest: false,
         };
         let property_paths = self.property_paths_for_entries(element.property_entries());
         self.report_duplicate_property_paths(&property_paths, &element.tag);
@@ -1328,6 +1992,7 @@ impl<'a> InferenceContext<'a> {
             content_property,
             properties,
             handler_properties: FxHashSet::default(),
+            accepts_rest: false,
         }
     }
 
@@ -1399,6 +2064,7 @@ impl<'a> InferenceContext<'a> {
     fn property_paths_for_entries(&mut self, entries: &[PropertyEntry]) -> Vec<PropertyPath> {
         let mut paths = vec![PropertyPath {
             properties: Vec::new(),
+            spread: false,
         }];
 
         for entry in entries {
@@ -1408,7 +2074,10 @@ impl<'a> InferenceContext<'a> {
                 for alternative in &alternatives {
                     let mut properties = path.properties.clone();
                     properties.extend(alternative.properties.clone());
-                    next_paths.push(PropertyPath { properties });
+                    next_paths.push(PropertyPath {
+                        properties,
+                        spread: path.spread || alternative.spread,
+                    });
                 }
             }
             paths = next_paths;
@@ -1425,7 +2094,25 @@ impl<'a> InferenceContext<'a> {
                     ty: self.infer_expr(property.value),
                     span: property.span,
                 }],
+                spread: false,
             }],
+            PropertyEntry::Spread { value, span } => {
+                let ty = self.infer_expr(*value);
+                if !Self::is_spreadable(&ty) {
+                    self.error(
+                        "invalid-spread",
+                        format!(
+                            "Only records can be spread into properties, found {}",
+                            self.display_type(&ty)
+                        ),
+                        *span,
+                    );
+                }
+                vec![PropertyPath {
+                    properties: Vec::new(),
+                    spread: true,
+                }]
+            }
             PropertyEntry::If {
                 condition,
                 then_entries,
@@ -1537,23 +2224,42 @@ impl<'a> InferenceContext<'a> {
                 );
                 paths.push(PropertyPath {
                     properties: Vec::new(),
+                    spread: false,
                 });
             }
         } else {
             paths.push(PropertyPath {
                 properties: Vec::new(),
+                spread: false,
             });
         }
 
         paths
     }
 
+    /// Returns true when values of `ty` may be spread into a property list.
+    fn is_spreadable(ty: &Type) -> bool {
+        matches!(
+            ty,
+            Type::Named(_)
+                | Type::Union(_)
+                | Type::UnionCase(_)
+                | Type::Variable(_)
+                | Type::Unknown
+                | Type::Error
+        )
+    }
+
     fn check_boolean_condition(&mut self, condition: ExprId, span: TextSpan, context: &str) {
         let condition_ty = self.infer_expr(condition);
         if !condition_ty.is_error() && !self.type_satisfies_expected(&condition_ty, &Type::bool()) {
             self.error(
                 "type-mismatch",
-                format!("{} expects bool, found {}", context, condition_ty),
+                format!(
+                    "{} expects bool, found {}",
+                    context,
+                    self.display_type(&condition_ty)
+                ),
                 span,
             );
         }
@@ -1562,26 +2268,45 @@ impl<'a> InferenceContext<'a> {
     fn report_duplicate_property_paths(&mut self, paths: &[PropertyPath], element_name: &Name) {
         let mut reported = FxHashSet::<(Name, usize, usize)>::default();
         for path in paths {
-            let mut seen = FxHashSet::<Name>::default();
+            let mut seen = FxHashMap::<Name, TextSpan>::default();
             for property in &path.properties {
-                if !seen.insert(property.key.clone()) {
-                    let start: usize = property.span.start().into();
-                    let end: usize = property.span.end().into();
-                    if reported.insert((property.key.clone(), start, end)) {
-                        self.error(
-                            "duplicate-property",
-                            format!(
-                                "Property '{}' on '{}' can be supplied more than once on the same path",
-                                property.key, element_name
-                            ),
-                            property.span,
-                        );
-                    }
+                let Some(first) = seen.get(&property.key).copied() else {
+                    seen.insert(property.key.clone(), property.span);
+                    continue;
+                };
+                let start: usize = property.span.start().into();
+                let end: usize = property.span.end().into();
+                if reported.insert((property.key.clone(), start, end)) {
+                    let message = if paths.len() == 1 {
+                        format!(
+                            "Property '{}' on '{}' is supplied more than once",
+                            property.key, element_name
+                        )
+                    } else {
+                        format!(
+                            "Property '{}' on '{}' can be supplied more than once on the same path",
+                            property.key, element_name
+                        )
+                    };
+                    self.report_duplicate_property(message, property.span, first);
                 }
             }
         }
     }
 
+    /// Reports a property supplied twice, labeling both occurrences.
+    fn report_duplicate_property(&mut self, message: String, span: TextSpan, first: TextSpan) {
+        let diag = Diagnostic::error("duplicate-property")
+            .with_message(message)
+            .with_label(Label::primary(self.file_name.clone(), span))
+            .with_label(
+                Label::secondary(self.file_name.clone(), first).with_message("first supplied here"),
+            )
+            .with_note("Only one value can be bound to each property")
+            .build();
+        self.diagnostics.push(diag);
+    }
+
     fn check_property_path_bindings(
         &mut self,
         paths: &[PropertyPath],
@@ -1605,25 +2330,24 @@ impl<'a> InferenceContext<'a> {
                         type_mismatch_code,
                         format!("Property '{}' on '{}'", property.key, element_name),
                     );
-                } else if spec.handler_properties.contains(&property.key) {
+                } else if spec.handler_properties.contains(&property.key) || spec.accepts_rest {
                     continue;
                 } else {
                     let start: usize = property.span.start().into();
                     let end: usize = property.span.end().into();
                     if reported_unknown.insert((property.key.clone(), start, end)) {
-                        self.error(
+                        self.report_unknown_property(
                             unknown_property_code,
-                            format!(
-                                "Element '{}' has no property '{}'",
-                                element_name, property.key
-                            ),
-                            property.span,
+                            element_name,
+                            property,
+                            spec,
                         );
                     }
                 }
             }
         }
 
+        let mut missing = Vec::new();
         for (name, expected) in &spec.properties {
             if !expected.is_required {
                 continue;
@@ -1638,17 +2362,70 @@ impl<'a> InferenceContext<'a> {
                 continue;
             }
 
-            if paths
-                .iter()
-                .any(|path| !path.properties.iter().any(|property| property.key == *name))
-            {
-                self.error(
-                    missing_property_code,
-                    format!("Element '{}' requires property '{}'", element_name, name),
-                    span,
-                );
+            if paths.iter().any(|path| {
+                !path.spread && !path.properties.iter().any(|property| property.key == *name)
+            }) {
+                missing.push(name.as_str());
+            }
+        }
+
+        missing.sort_unstable();
+        let message = match missing.as_slice() {
+            [] => return,
+            [name] => format!("Element '{}' requires property '{}'", element_name, name),
+            names => format!(
+                "Element '{}' is missing required properties {}",
+                element_name,
+                names
+                    .iter()
+                    .map(|name| format!("'{}'", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        self.error_with_help(
+            missing_property_code,
+            message,
+            span,
+            Some(
+                "Properties without a default value or a nullable type must be supplied"
+                    .to_string(),
+            ),
+        );
+    }
+
+    fn report_unknown_property(
+        &mut self,
+        code: &str,
+        element_name: &Name,
+        property: &PropertyPathBinding,
+        spec: &ElementBindingSpec,
+    ) {
+        let mut declared: Vec<&str> = spec.properties.keys().map(Name::as_str).collect();
+        declared.sort_unstable();
+
+        let mut builder = Diagnostic::error(code)
+            .with_message(format!(
+                "Element '{}' has no property '{}'",
+                element_name, property.key
+            ))
+            .with_label(Label::primary(self.file_name.clone(), property.span));
+
+        if let Some(suggestion) = suggest::closest_match(property.key.as_str(), declared) {
+            builder = builder.with_help(format!("Did you mean `{suggestion}`?"));
+
+            // Property spans start at the key, so the key occupies the first `key.len()` bytes.
+            let key_len = TextSize::of(property.key.as_str());
+            if key_len <= property.span.len() {
+                let key_range = TextSpan::at(property.span.start(), key_len);
+                builder = builder.with_fix(Fix::new(
+                    format!("Replace `{}` with `{suggestion}`", property.key),
+                    TextEdit::replace(self.file_name.clone(), key_range, suggestion),
+                ));
             }
         }
+
+        self.diagnostics.push(builder.build());
     }
 
     fn normalized_sequence_type(&mut self, exprs: &[ExprId], span: TextSpan) -> Type {
@@ -1692,6 +2469,11 @@ impl<'a> InferenceContext<'a> {
         code: &str,
         context: String,
     ) -> bool {
+        // Solve the variables in `actual` from the expected type; a failure leaves them unsolved
+        // and the compatibility check below reports the mismatch.
+        let _ = self.substitution.unify(actual, expected);
+        let actual = &self.substitution.finalize(actual);
+        let expected = &self.substitution.finalize(expected);
         if self.type_satisfies_expected_with_coercion(actual, expected) {
             true
         } else {
@@ -1701,7 +2483,15 @@ impl<'a> InferenceContext<'a> {
             } else {
                 format!("{} expects {}, found {}", context, expected, actual)
             };
-            self.error(code, message, span);
+            let mut builder = Diagnostic::error(code)
+                .with_message(message)
+                .with_label(Label::primary(self.file_name.clone(), span));
+            // Nested mismatches are hard to spot in two long type names, so point at the part
+            // that differs.
+            if let Some(mismatch) = actual.explain_mismatch(expected) {
+                builder = builder.with_note(mismatch.to_string());
+            }
+            self.diagnostics.push(builder.build());
             false
         }
     }
@@ -1715,6 +2505,149 @@ impl<'a> InferenceContext<'a> {
         self.diagnostics.push(diag);
     }
 
+    fn error_with_help(
+        &mut self,
+        code: &str,
+        message: String,
+        span: nx_diagnostics::TextSpan,
+        help: Option<String>,
+    ) {
+        let mut builder = Diagnostic::error(code)
+            .with_message(message)
+            .with_label(Label::primary(self.file_name.clone(), span));
+        if let Some(help) = help {
+            builder = builder.with_help(help);
+        }
+        self.diagnostics.push(builder.build());
+    }
+
+    /// Warns that a concatenation operand may be null, which fails at runtime unless the host
+    /// chooses another null concatenation policy.
+    fn warn_null_concatenation(&mut self, lhs: &Type, rhs: &Type, span: TextSpan) {
+        let nullable = if lhs.is_nullable() { lhs } else { rhs };
+        let message = match nullable.strip_nullable() {
+            Type::Variable(_) => "Concatenating null with a string".to_string(),
+            _ => format!("Concatenating a possibly null {} with a string", nullable),
+        };
+        let diag = Diagnostic::warning("null-concatenation")
+            .with_message(message)
+            .with_label(Label::primary(self.file_name.clone(), span))
+            .with_help(
+                "Concatenating null is a runtime error unless the host selects an empty or \
+                 \"null\" null concatenation policy; give the value a non-null default first",
+            )
+            .build();
+        self.diagnostics.push(diag);
+    }
+
+    /// Returns what is known about the sign of `expr` from its form and the enclosing guards.
+    fn sign_of(&self, expr: ExprId) -> Option<Sign> {
+        sign_of(
+            self.module.raw_module(),
+            &self.sign_facts,
+            &|func| self.builtin_callee(func),
+            expr,
+        )
+    }
+
+    /// Warns that a divisor is not known to be non-zero, which fails at runtime when it is.
+    fn warn_possible_division_by_zero(&mut self, op: ast::BinOp, divisor: ExprId, span: TextSpan) {
+        let action = if op == ast::BinOp::Mod {
+            "Taking the remainder of a division by"
+        } else {
+            "Dividing by"
+        };
+        let (message, help) = match self.module.raw_module().expr(divisor) {
+            ast::Expr::Ident(name) => (
+                format!("{} '{}', which may be zero", action, name),
+                format!(
+                    "Division by zero is a runtime error; check the divisor first, as in \
+                     `if {} != 0 {{ ... }} else {{ ... }}`",
+                    name
+                ),
+            ),
+            _ => (
+                format!("{} a value that may be zero", action),
+                "Division by zero is a runtime error; divide by a name that an enclosing `if` \
+                 compares with 0, or by a non-zero literal"
+                    .to_string(),
+            ),
+        };
+        let diag = Diagnostic::warning("possible-division-by-zero")
+            .with_message(message)
+            .with_label(Label::primary(self.file_name.clone(), span))
+            .with_help(help)
+            .build();
+        self.diagnostics.push(diag);
+    }
+
+    /// Warns that an array index is not known to be non-negative.
+    fn warn_possible_negative_index(&mut self, index: ExprId, span: TextSpan) {
+        let (message, help) = match self.module.raw_module().expr(index) {
+            ast::Expr::Ident(name) => (
+                format!("Index '{}' may be negative", name),
+                format!(
+                    "Negative indexes are a runtime error; check the index first, as in \
+                     `if {} >= 0 {{ ... }}`, or use the index of a `for` loop",
+                    name
+                ),
+            ),
+            _ => (
+                "Array index may be negative".to_string(),
+                "Negative indexes are a runtime error; index with a literal, a `for` loop \
+                 index, or a name that an enclosing `if` compares with 0"
+                    .to_string(),
+            ),
+        };
+        let diag = Diagnostic::warning("possible-negative-index")
+            .with_message(message)
+            .with_label(Label::primary(self.file_name.clone(), span))
+            .with_help(help)
+            .build();
+        self.diagnostics.push(diag);
+    }
+
+    /// Warns that a `for` loop iterates a nullable array, which fails at runtime when it is null.
+    fn warn_nullable_iteration(&mut self, iterable: ExprId, iterable_ty: &Type, span: TextSpan) {
+        let array_ty = iterable_ty.strip_nullable();
+        let help = match self.module.raw_module().expr(iterable) {
+            ast::Expr::Ident(name) => format!(
+                "Iterating null is a runtime error; declare '{}' as {} with a default of `[]`",
+                name, array_ty
+            ),
+            _ => format!(
+                "Iterating null is a runtime error; make the value {} with a default of `[]`",
+                array_ty
+            ),
+        };
+        let diag = Diagnostic::warning("nullable-iteration")
+            .with_message(format!("Iterating a possibly null {}", iterable_ty))
+            .with_label(Label::primary(self.file_name.clone(), span))
+            .with_help(help)
+            .build();
+        self.diagnostics.push(diag);
+    }
+
+    fn report_undefined_enum_member(
+        &mut self,
+        enum_info: &EnumType,
+        member: &Name,
+        span: TextSpan,
+    ) -> Type {
+        let suggestion = suggest::closest_match(
+            member.as_str(),
+            enum_info.members.iter().map(|candidate| candidate.as_str()),
+        )
+        .map(|candidate| format!("Did you mean `{}.{}`?", enum_info.name, candidate));
+        self.error_with_help(
+            "undefined-enum-member",
+            format!("Enum '{}' has no member named '{}'", enum_info.name, member),
+            span,
+            suggestion,
+        );
+        Type::Error
+    }
+
     /// Returns the collected diagnostics.
     pub fn diagnostics(&self) -> &[Diagnostic] {
         &self.diagnostics
@@ -1726,10 +2659,21 @@ impl<'a> InferenceContext<'a> {
     }
 
     /// Consumes the context and returns the environment and diagnostics.
-    pub fn finish(self) -> (TypeEnvironment, Vec<Diagnostic>) {
+    ///
+    /// Solved type variables are substituted throughout the environment and unsolved ones are
+    /// replaced with their defaults, so the returned types contain no variables.
+    pub fn finish(mut self) -> (TypeEnvironment, Vec<Diagnostic>) {
+        let substitution = &self.substitution;
+        self.env.map_types(|ty| substitution.finalize(ty));
         (self.env, self.diagnostics)
     }
 
+    /// Returns `ty` as it should appear in a diagnostic, with type variables resolved or
+    /// defaulted.
+    fn display_type(&self, ty: &Type) -> Type {
+        self.substitution.finalize(ty)
+    }
+
     fn register_type_definitions(&mut self) {
         let bindings = self
             .module
@@ -1811,10 +2755,29 @@ impl<'a> InferenceContext<'a> {
         let local_items = self.module.raw_module().items().to_vec();
         for item in local_items {
             if let Item::Record(record_def) = item {
+                if record_def
+                    .properties
+                    .iter()
+                    .all(|prop| prop.default.is_none())
+                {
+                    continue;
+                }
+                // A default may read any field of the record, inherited ones included.
+                let fields = self
+                    .effective_record_shape(&record_def.name)
+                    .ok()
+                    .flatten()
+                    .map(|shape| shape.fields)
+                    .unwrap_or_default();
+                self.env.push_scope();
+                for field in &fields {
+                    let ty = self.type_from_type_ref(&field.ty);
+                    self.env.bind(field.name.clone(), ty);
+                }
                 for prop in &record_def.properties {
                     if let Some(default_expr) = prop.default {
                         let expected = self.type_from_type_ref(&prop.ty);
-                        let actual = self.infer_expr(default_expr);
+                        let actual = self.infer_expr_expecting(default_expr, &expected);
                         self.check_typed_binding(
                             &actual,
                             &expected,
@@ -1824,6 +2787,175 @@ impl<'a> InferenceContext<'a> {
                         );
                     }
                 }
+                self.env.pop_scope();
+            }
+        }
+    }
+
+    /// Checks that each field constraint fits its field's type: `@min` and `@max` need a number,
+    /// `@minLength` and `@maxLength` a string or an array.
+    fn validate_local_record_constraints(&mut self) {
+        let local_items = self.module.raw_module().items().to_vec();
+        for item in local_items {
+            let Item::Record(record_def) = item else {
+                continue;
+            };
+            for prop in &record_def.properties {
+                if prop.constraints.is_empty() {
+                    continue;
+                }
+                let ty = self.type_from_type_ref(&prop.ty);
+                let inner = ty.strip_nullable();
+                if inner.is_error() || inner.is_unknown() {
+                    continue;
+                }
+                for constraint in &prop.constraints {
+                    let applies = if constraint.kind.is_numeric() {
+                        matches!(inner, Type::Primitive(primitive) if primitive.is_numeric())
+                    } else {
+                        matches!(
+                            inner,
+                            Type::Primitive(crate::ty::Primitive::String) | Type::Array(_)
+                        )
+                    };
+                    if !applies {
+                        self.error(
+                            "constraint-type-mismatch",
+                            format!(
+                                "Constraint '{}' does not apply to field '{}' of type '{}'; it needs {}",
+                                constraint.kind,
+                                prop.name,
+                                ty,
+                                if constraint.kind.is_numeric() {
+                                    "a number"
+                                } else {
+                                    "a string or an array"
+                                }
+                            ),
+                            constraint.span,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the module's `meta` block for repeated fields and, when it names a schema record,
+    /// for unknown, missing and mistyped fields.
+    fn validate_module_metadata(&mut self) {
+        let Some(metadata) = self.module.raw_module().metadata().cloned() else {
+            return;
+        };
+
+        let shape = metadata
+            .schema
+            .as_ref()
+            .map(|schema| self.effective_record_shape(schema).ok().flatten());
+        let mut seen = FxHashMap::<Name, TextSpan>::default();
+        let mut entries = Vec::new();
+        for entry in &metadata.entries {
+            let expected = shape
+                .iter()
+                .flatten()
+                .flat_map(|shape| &shape.fields)
+                .find(|field| field.name == entry.name)
+                .map(|field| self.type_from_type_ref(&field.ty));
+            let ty = match expected.as_ref() {
+                Some(expected) => self.infer_expr_expecting(entry.value, expected),
+                None => self.infer_expr(entry.value),
+            };
+            if let Some(first) = seen.get(&entry.name).copied() {
+                self.report_duplicate_property(
+                    format!("Metadata field '{}' is supplied more than once", entry.name),
+                    entry.span,
+                    first,
+                );
+                continue;
+            }
+            seen.insert(entry.name.clone(), entry.span);
+            entries.push((entry, ty));
+        }
+
+        let (Some(schema), Some(shape)) = (metadata.schema.as_ref(), shape) else {
+            return;
+        };
+        let Some(shape) = shape else {
+            self.error(
+                "invalid-metadata-schema",
+                format!("Metadata schema '{}' is not a record type", schema),
+                metadata.span,
+            );
+            return;
+        };
+
+        for (entry, ty) in entries {
+            match shape.fields.iter().find(|field| field.name == entry.name) {
+                Some(field) => {
+                    let expected = self.type_from_type_ref(&field.ty);
+                    self.check_typed_binding(
+                        &ty,
+                        &expected,
+                        entry.span,
+                        "metadata-type-mismatch",
+                        format!("Metadata field '{}'", entry.name),
+                    );
+                }
+                None => self.error(
+                    "unknown-metadata-field",
+                    format!("Metadata schema '{}' has no field '{}'", schema, entry.name),
+                    entry.span,
+                ),
+            }
+        }
+
+        let mut missing = shape
+            .fields
+            .iter()
+            .filter(|field| field.is_required && !seen.contains_key(&field.name))
+            .map(|field| field.name.as_str())
+            .collect::<Vec<_>>();
+        missing.sort_unstable();
+        let message = match missing.as_slice() {
+            [] => return,
+            [name] => format!("Metadata schema '{}' requires field '{}'", schema, name),
+            names => format!(
+                "Metadata schema '{}' is missing required fields {}",
+                schema,
+                names
+                    .iter()
+                    .map(|name| format!("'{}'", name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        self.error_with_help(
+            "missing-property",
+            message,
+            metadata.span,
+            Some("Fields without a default value or a nullable type must be supplied".to_string()),
+        );
+    }
+
+    fn validate_local_param_defaults(&mut self) {
+        let local_items = self.module.raw_module().items().to_vec();
+        for item in local_items {
+            if let Item::Function(function) = item {
+                for param in &function.params {
+                    if let Some(default_expr) = param.default {
+                        let expected = self.type_from_type_ref(&param.ty);
+                        let actual = self.infer_expr_expecting(default_expr, &expected);
+                        self.check_typed_binding(
+                            &actual,
+                            &expected,
+                            param.span,
+                            "param-default-type-mismatch",
+                            format!(
+                                "Default value for parameter '{}.{}'",
+                                function.name, param.name
+                            ),
+                        );
+                    }
+                }
             }
         }
     }
@@ -1836,7 +2968,7 @@ impl<'a> InferenceContext<'a> {
                     for field in &case.fields {
                         if let Some(default_expr) = field.default {
                             let expected = self.type_from_type_ref(&field.ty);
-                            let actual = self.infer_expr(default_expr);
+                            let actual = self.infer_expr_expecting(default_expr, &expected);
                             self.check_typed_binding(
                                 &actual,
                                 &expected,
@@ -1910,11 +3042,17 @@ impl<'a> InferenceContext<'a> {
     }
 
     fn register_value_bindings(&mut self) {
-        let bindings = self
+        // Infer local values in declaration order so an initializer sees the types of the values
+        // declared before it.
+        let mut bindings = self
             .module
             .bindings(PreparedNamespace::Value)
             .cloned()
             .collect::<Vec<_>>();
+        bindings.sort_by_key(|binding| match &binding.target {
+            PreparedBindingTarget::Local { definition_id } => Some(definition_id.as_u32()),
+            _ => None,
+        });
 
         for binding in bindings {
             let Some(resolved) = self.module.resolve_prepared_item(&binding) else {
@@ -1928,9 +3066,9 @@ impl<'a> InferenceContext<'a> {
                     ..
                 } => {
                     let binding_ty = if module_identity == self.module.module_identity() {
-                        let actual = self.infer_expr(value.value);
                         if let Some(ty_ref) = value.ty.as_ref() {
                             let expected = self.type_from_type_ref(ty_ref);
+                            let actual = self.infer_expr_expecting(value.value, &expected);
                             self.check_typed_binding(
                                 &actual,
                                 &expected,
@@ -1940,7 +3078,7 @@ impl<'a> InferenceContext<'a> {
                             );
                             expected
                         } else {
-                            actual
+                            self.infer_expr(value.value)
                         }
                     } else {
                         value
@@ -1963,6 +3101,36 @@ impl<'a> InferenceContext<'a> {
         }
     }
 
+    /// Returns the type of the top-level value that `key` names, or reports `unknown-context` when
+    /// `key` does not name a top-level value.
+    fn context_key_type(&mut self, key: &Name, span: TextSpan) -> Option<Type> {
+        let is_value = self
+            .module
+            .resolve_binding(PreparedNamespace::Value, key)
+            .and_then(|binding| self.module.resolve_prepared_item(binding))
+            .is_some_and(|resolved| match resolved {
+                ResolvedPreparedItem::Raw { item, .. } => matches!(item, Item::Value(_)),
+                ResolvedPreparedItem::Imported { item, .. } => {
+                    matches!(item.item, InterfaceItemKind::Value { .. })
+                }
+            });
+        if !is_value {
+            self.error_with_help(
+                "unknown-context",
+                format!("Context '{}' is not a top-level value", key),
+                span,
+                Some(
+                    "Contexts are keyed by top-level values: declare `let theme: Theme = ...` \
+                     and provide or use it by that name"
+                        .to_string(),
+                ),
+            );
+            return None;
+        }
+
+        Some(self.env.lookup_global(key).cloned().unwrap_or(Type::Error))
+    }
+
     fn resolve_function_definition(&self, name: &Name) -> Option<ResolvedPreparedItem> {
         self.module
             .resolve_binding(PreparedNamespace::Element, name)
@@ -2181,34 +3349,43 @@ impl<'a> InferenceContext<'a> {
                 .unwrap_or(false)
     }
 
-    fn named_type_is_element_like(&self, name: &Name) -> bool {
-        if name.as_str() == "Element" {
-            return true;
+    /// Returns true if values of `ty` can appear as element content: text, numbers, elements,
+    /// enum members, and arrays or nullable values of those.
+    fn type_is_renderable(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Primitive(_)
+            | Type::Element
+            | Type::Enum(_)
+            | Type::Variable(_)
+            | Type::Unknown
+            | Type::Error => true,
+            Type::Array(inner) | Type::Nullable(inner) => self.type_is_renderable(inner),
+            Type::Named(name) => is_object_type(ty) || self.named_type_is_element_like(name),
+            Type::Function { .. } | Type::Union(_) | Type::UnionCase(_) => false,
         }
+    }
 
-        if self
-            .module
-            .resolve_binding(PreparedNamespace::Element, name)
-            .is_some()
-        {
-            true
-        } else {
-            self.module
-                .resolve_binding(PreparedNamespace::Type, name)
-                .or_else(|| self.module.resolve_binding(PreparedNamespace::Value, name))
-                .is_none()
-        }
+    /// Returns true if values of the named type are elements: component invocations, and names
+    /// that resolve to nothing so an earlier error is not repeated. Records are not elements.
+    fn named_type_is_element_like(&self, name: &Name) -> bool {
+        self.resolve_component_definition(name).is_some()
+            || [
+                PreparedNamespace::Element,
+                PreparedNamespace::Type,
+                PreparedNamespace::Value,
+            ]
+            .into_iter()
+            .all(|namespace| self.module.resolve_binding(namespace, name).is_none())
     }
 
     fn type_satisfies_expected(&self, actual: &Type, expected: &Type) -> bool {
-        if generic_type_satisfies_expected(actual, expected) {
+        // Unresolved variables are checked once a later inference pass resolves them.
+        if actual.is_variable() || generic_type_satisfies_expected(actual, expected) {
             return true;
         }
 
         match (actual, expected) {
-            (Type::Named(actual_name), Type::Named(expected_name))
-                if expected_name.as_str() == "Element" =>
-            {
+            (Type::Named(actual_name), Type::Element) => {
                 self.named_type_is_element_like(actual_name)
             }
             (Type::Named(actual_name), Type::Named(expected_name)) => {
@@ -2253,6 +3430,10 @@ impl<'a> InferenceContext<'a> {
 
     fn common_supertype(&self, lhs: &Type, rhs: &Type) -> Type {
         match (lhs, rhs) {
+            // An unresolved variable, such as the pending return type of a recursive call,
+            // adds nothing to the join.
+            (Type::Variable(_), _) => rhs.clone(),
+            (_, Type::Variable(_)) => lhs.clone(),
             (Type::Array(lhs_inner), Type::Array(rhs_inner)) => {
                 Type::array(self.common_supertype(lhs_inner, rhs_inner))
             }
@@ -2276,11 +3457,41 @@ impl<'a> InferenceContext<'a> {
             (Type::Named(lhs_name), Type::Named(rhs_name)) => self
                 .common_record_supertype(lhs_name, rhs_name)
                 .or_else(|| self.common_component_supertype(lhs_name, rhs_name))
+                .or_else(|| {
+                    (self.named_type_is_element_like(lhs_name)
+                        && self.named_type_is_element_like(rhs_name))
+                    .then(Type::element)
+                })
                 .unwrap_or_else(|| generic_common_supertype(lhs, rhs)),
+            (Type::Element, Type::Named(name)) | (Type::Named(name), Type::Element)
+                if self.named_type_is_element_like(name) =>
+            {
+                Type::element()
+            }
             _ => generic_common_supertype(lhs, rhs),
         }
     }
 
+    /// Returns the type `for value in record` binds to `value`: the common supertype of the
+    /// record's field types, or `None` when `ty` is not a record type.
+    fn record_field_value_type(&mut self, ty: &Type) -> Option<Type> {
+        let Type::Named(name) = ty else {
+            return None;
+        };
+        let shape = self.effective_record_shape(name).ok().flatten()?;
+        let field_tys = shape
+            .fields
+            .iter()
+            .map(|field| self.type_from_type_ref(&field.ty))
+            .collect::<Vec<_>>();
+        Some(
+            field_tys
+                .into_iter()
+                .reduce(|joined, field_ty| self.common_supertype(&joined, &field_ty))
+                .unwrap_or(Type::Unknown),
+        )
+    }
+
     fn common_record_supertype(&self, lhs: &Name, rhs: &Name) -> Option<Type> {
         let lhs_shape = self.effective_record_shape(lhs).ok().flatten()?;
         let rhs_shape = self.effective_record_shape(rhs).ok().flatten()?;
@@ -2329,6 +3540,23 @@ impl TypeInference {
     }
 }
 
+/// Returns `span` unless it is empty, as it is for expressions that do not track their source.
+fn non_empty_span(span: TextSpan) -> Option<TextSpan> {
+    (!span.is_empty()).then_some(span)
+}
+
+/// Returns true when `lhs + rhs` concatenates strings and at least one operand may be null. A bare
+/// `null` literal is nullable with an unresolved inner type.
+fn is_nullable_string_concat(lhs: &Type, rhs: &Type) -> bool {
+    let is_text = |ty: &Type| {
+        matches!(ty.strip_nullable(), Type::Variable(_)) || ty.strip_nullable() == &Type::string()
+    };
+    (lhs.is_nullable() || rhs.is_nullable())
+        && is_text(lhs)
+        && is_text(rhs)
+        && (lhs.strip_nullable() == &Type::string() || rhs.strip_nullable() == &Type::string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2385,10 +3613,7 @@ mod tests {
         let prepared = prepared(&module);
         let ctx = InferenceContext::new(&prepared);
 
-        assert!(ctx.type_satisfies_expected(
-            &Type::named(Name::new("div")),
-            &Type::named(Name::new("Element"))
-        ));
+        assert!(ctx.type_satisfies_expected(&Type::named(Name::new("div")), &Type::element()));
         assert!(!ctx.type_satisfies_expected(
             &Type::named(Name::new("div")),
             &Type::named(Name::new("element"))
@@ -0,0 +1,213 @@
+//! The `unsafe-interpolation` lint for injection-prone element properties.
+//!
+//! Text built by concatenating strings with dynamic values is risky in two kinds of properties:
+//! URL-bearing ones such as `href`, where a value like `"javascript:..."` runs script, and
+//! event-like ones such as `onclick`, whose value is itself script. The lint warns when a
+//! property matching one of those name patterns gets a string concatenation with an operand
+//! that is neither a literal nor the result of a sanitizer builtin (`sanitizeUrl`,
+//! `encodeUriComponent`).
+//!
+//! Analysis runs the lint with the default patterns. Hosts with their own attribute names build
+//! an [`UnsafeInterpolationLint`] with other patterns and run [`UnsafeInterpolationLint::check`]
+//! on the analyzed module instead.
+
+use crate::env::TypeEnvironment;
+use crate::ty::Type;
+use nx_diagnostics::{Diagnostic, Label};
+use nx_hir::ast::{BinOp, Expr, Literal};
+use nx_hir::{ExprId, LoweredModule, Property, PropertyEntry};
+
+/// Diagnostic code of the lint.
+pub const UNSAFE_INTERPOLATION: &str = "unsafe-interpolation";
+
+/// Builtins whose results are safe to concatenate into checked properties.
+pub const SANITIZER_BUILTINS: &[&str] = &["encodeUriComponent", "sanitizeUrl"];
+
+/// Which element properties the `unsafe-interpolation` lint checks
+///
+/// Patterns match property names case-insensitively, and `*` matches any run of characters, so
+/// `on*` matches `onclick` and `onMouseOver`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsafeInterpolationLint {
+    /// Patterns of properties holding URLs.
+    pub url_props: Vec<String>,
+    /// Patterns of properties holding script, such as inline event handlers.
+    pub event_props: Vec<String>,
+}
+
+impl Default for UnsafeInterpolationLint {
+    fn default() -> Self {
+        Self {
+            url_props: ["href", "src", "action", "formaction", "poster", "srcset"]
+                .map(String::from)
+                .to_vec(),
+            event_props: vec!["on*".to_string()],
+        }
+    }
+}
+
+impl UnsafeInterpolationLint {
+    /// Returns a warning for every checked property of `module`'s elements whose value
+    /// concatenates unsanitized dynamic text.
+    ///
+    /// `type_env` is the module's analyzed type environment; it tells string concatenation apart
+    /// from numeric addition.
+    pub fn check(
+        &self,
+        module: &LoweredModule,
+        type_env: &TypeEnvironment,
+        file_name: &str,
+    ) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (_, element) in module.elements() {
+            let mut properties = Vec::new();
+            collect_properties(element.property_entries(), &mut properties);
+            for property in properties {
+                let key = property.key.as_str();
+                let kind = if matches_any(&self.url_props, key) {
+                    "URL"
+                } else if matches_any(&self.event_props, key) {
+                    "event handler"
+                } else {
+                    continue;
+                };
+                if !is_unsafe_concatenation(module, type_env, property.value) {
+                    continue;
+                }
+                diagnostics.push(
+                    Diagnostic::warning(UNSAFE_INTERPOLATION)
+                        .with_message(format!(
+                            "The {} property '{}' is built from unsanitized text",
+                            kind, key
+                        ))
+                        .with_label(Label::primary(
+                            file_name,
+                            module.expr(property.value).span(),
+                        ))
+                        .with_help(
+                            "Pass dynamic parts through sanitizeUrl(...) or \
+                             encodeUriComponent(...)",
+                        )
+                        .build(),
+                );
+            }
+        }
+        diagnostics
+    }
+}
+
+fn collect_properties<'a>(entries: &'a [PropertyEntry], out: &mut Vec<&'a Property>) {
+    for entry in entries {
+        match entry {
+            PropertyEntry::Value(property) => out.push(property),
+            PropertyEntry::If {
+                then_entries,
+                else_entries,
+                ..
+            } => {
+                collect_properties(then_entries, out);
+                collect_properties(else_entries, out);
+            }
+            PropertyEntry::ConditionList {
+                arms, else_entries, ..
+            } => {
+                for arm in arms {
+                    collect_properties(&arm.entries, out);
+                }
+                collect_properties(else_entries, out);
+            }
+            PropertyEntry::Match {
+                arms, else_entries, ..
+            } => {
+                for arm in arms {
+                    collect_properties(&arm.entries, out);
+                }
+                collect_properties(else_entries, out);
+            }
+            PropertyEntry::Spread { .. } => {}
+        }
+    }
+}
+
+/// Returns true if `value` is a string concatenation with an operand that is neither a literal
+/// nor a sanitizer call.
+fn is_unsafe_concatenation(
+    module: &LoweredModule,
+    type_env: &TypeEnvironment,
+    value: ExprId,
+) -> bool {
+    let Expr::BinaryOp { op, .. } = module.expr(value) else {
+        return false;
+    };
+    let mut operands = Vec::new();
+    collect_operands(module, value, &mut operands);
+    let is_string = |expr: ExprId| {
+        type_env
+            .get_expr_type(expr)
+            .is_some_and(|ty| *ty.strip_nullable() == Type::string())
+    };
+    let concatenates = *op == BinOp::Concat
+        || (*op == BinOp::Add
+            && (is_string(value)
+                || operands.iter().any(|operand| {
+                    matches!(module.expr(*operand), Expr::Literal(Literal::String(_)))
+                })));
+    concatenates
+        && operands
+            .iter()
+            .any(|operand| !is_safe_operand(module, *operand))
+}
+
+fn collect_operands(module: &LoweredModule, expr: ExprId, out: &mut Vec<ExprId>) {
+    match module.expr(expr) {
+        Expr::BinaryOp {
+            lhs,
+            op: BinOp::Add | BinOp::Concat,
+            rhs,
+            ..
+        } => {
+            collect_operands(module, *lhs, out);
+            collect_operands(module, *rhs, out);
+        }
+        _ => out.push(expr),
+    }
+}
+
+fn is_safe_operand(module: &LoweredModule, expr: ExprId) -> bool {
+    match module.expr(expr) {
+        Expr::Literal(_) => true,
+        Expr::Call { func, .. } => match module.expr(*func) {
+            Expr::Ident(name) => {
+                SANITIZER_BUILTINS.contains(&name.as_str())
+                    && module.find_item(name.as_str()).is_none()
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn matches_any(patterns: &[String], name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    patterns
+        .iter()
+        .any(|pattern| matches_pattern(&pattern.to_ascii_lowercase(), &name))
+}
+
+/// Matches `name` against a pattern where `*` stands for any run of characters.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            if rest.is_empty() {
+                return true;
+            }
+            (0..=name.len())
+                .filter(|index| name.is_char_boundary(*index))
+                .any(|index| matches_pattern(rest, &name[index..]))
+        }
+    }
+}
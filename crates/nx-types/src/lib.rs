@@ -84,8 +84,16 @@
 //!         println!("{}: {} errors", name, result.errors().len());
 //!     }
 //! }
+//!
+//! // Find declarations by partial name
+//! let buttons = session.workspace_symbols("butt");
+//! assert_eq!(buttons[0].name, "Button");
 //! ```
 //!
+//! Long-lived hosts can persist a session with [`TypeCheckSession::save`] and restore it with
+//! [`TypeCheckSession::load`]; only files whose source changed since the snapshot are analyzed
+//! again.
+//!
 //! # Type System Features
 //!
 //! ## Primitive Types
@@ -127,21 +135,53 @@
 //! - Memory usage stays under 100MB for large files (10,000+ lines)
 //! - Incremental checking is supported via the session API
 
+mod bounds;
+pub mod builtins;
+mod call_graph;
+pub mod call_hierarchy;
+pub mod capture;
 pub mod check;
 pub mod env;
+pub mod host;
+pub mod i18n;
 pub mod infer;
+pub mod injection;
+pub mod metrics;
 pub mod semantics;
+pub mod snapshot;
+pub mod symbols;
+pub mod timings;
 pub mod ty;
+pub mod type_syntax;
+pub mod unify;
 
 // Re-export main types
+pub use builtins::{
+    builtin_names, builtin_return_type, builtin_signatures, is_builtin, BuiltinSignature,
+};
+pub use call_hierarchy::{CallIndex, CallKind, CallReference};
+pub use capture::{check_unused_array_captures, UNUSED_ARRAY_CAPTURE};
 pub use check::{
-    analyze_prepared_module, analyze_str, check_file, check_str, ModuleArtifact,
+    analyze_prepared_module, analyze_str, check_added_function, check_file, check_str,
+    check_str_with_budget, check_str_with_host_functions, BudgetedCheckResult, ModuleArtifact,
     SourceAnalysisResult, TypeCheckResult, TypeCheckSession,
 };
 pub use env::{TypeBinding, TypeEnvironment};
+pub use host::HostFunctionTypes;
+pub use i18n::{message_references, MessageReference, TRANSLATE_BUILTIN};
 pub use infer::{InferenceContext, TypeInference};
+pub use injection::{UnsafeInterpolationLint, UNSAFE_INTERPOLATION};
+pub use metrics::{module_metrics, ItemMetrics};
 pub use semantics::{
     common_supertype, is_object_type, resolve_type_ref_with, resolve_type_ref_with_seen,
     type_satisfies_expected, type_satisfies_expected_with_coercion,
 };
-pub use ty::{Primitive, Type, TypeId};
+pub use snapshot::SnapshotError;
+pub use symbols::{SymbolIndex, SymbolInfo};
+pub use timings::{collect_timings, current_stage, time_stage, Stage, StageTimings};
+pub use ty::{MismatchReason, Primitive, Type, TypeId, TypeMismatch, TypePathStep};
+pub use type_syntax::TypeParseError;
+pub use unify::{Substitution, UnifyError};
+
+/// Version of the nx-types crate, identical to the toolchain version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,285 @@
+//! Size and complexity metrics of the declarations in a module.
+//!
+//! Metrics are computed from the lowered module alone, so they are available for files that do
+//! not type check. For each function, component and value with a body:
+//!
+//! - **Complexity** is the cyclomatic complexity: one, plus one for each `if`, `for`, `&&` and
+//!   `||`, and for each arm of a `match` or condition list, in expressions and in element
+//!   property lists alike. `else` branches add nothing.
+//! - **Maximum depth** is the deepest nesting of elements, where a top-level element has depth 1
+//!   and the elements in its content have depth 2.
+//! - **Expressions** counts the expressions in the body, including nested ones.
+
+use nx_diagnostics::TextSpan;
+use nx_hir::{ast, ExprId, Item, LoweredModule, PropertyEntry, SymbolKind};
+
+/// Metrics of one declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemMetrics {
+    /// Declared name.
+    pub name: String,
+    /// [`SymbolKind::Function`], [`SymbolKind::Component`] or [`SymbolKind::Variable`].
+    pub kind: SymbolKind,
+    /// Source span of the declaration.
+    pub span: TextSpan,
+    /// Cyclomatic complexity of the body.
+    pub complexity: usize,
+    /// Deepest element nesting in the body, or 0 if it builds no elements.
+    pub max_depth: usize,
+    /// Number of expressions in the body.
+    pub expressions: usize,
+}
+
+/// Returns the metrics of every function, component and value of `module` with a body, in
+/// declaration order.
+pub fn module_metrics(module: &LoweredModule) -> Vec<ItemMetrics> {
+    module
+        .items()
+        .iter()
+        .filter_map(|item| {
+            let (name, kind, body, span) = match item {
+                Item::Function(function) => (
+                    &function.name,
+                    SymbolKind::Function,
+                    function.body,
+                    function.span,
+                ),
+                Item::Component(component) => (
+                    &component.name,
+                    SymbolKind::Component,
+                    component.body?,
+                    component.span,
+                ),
+                Item::Value(value) => (&value.name, SymbolKind::Variable, value.value, value.span),
+                _ => return None,
+            };
+            let mut counter = Counter {
+                module,
+                complexity: 1,
+                max_depth: 0,
+                expressions: 0,
+            };
+            counter.expr(body, 0);
+            Some(ItemMetrics {
+                name: name.to_string(),
+                kind,
+                span,
+                complexity: counter.complexity,
+                max_depth: counter.max_depth,
+                expressions: counter.expressions,
+            })
+        })
+        .collect()
+}
+
+struct Counter<'a> {
+    module: &'a LoweredModule,
+    complexity: usize,
+    max_depth: usize,
+    expressions: usize,
+}
+
+impl Counter<'_> {
+    /// Counts `expr_id` and everything nested in it, at element nesting `depth`.
+    fn expr(&mut self, expr_id: ExprId, depth: usize) {
+        self.expressions += 1;
+        match self.module.expr(expr_id) {
+            ast::Expr::Literal(_)
+            | ast::Expr::Error(_)
+            | ast::Expr::Ident(_)
+            | ast::Expr::UseContext { .. } => {}
+            ast::Expr::BinaryOp { lhs, op, rhs, .. } => {
+                if matches!(op, ast::BinOp::And | ast::BinOp::Or) {
+                    self.complexity += 1;
+                }
+                self.expr(*lhs, depth);
+                self.expr(*rhs, depth);
+            }
+            ast::Expr::UnaryOp { expr, .. } => self.expr(*expr, depth),
+            ast::Expr::Call { func, args, .. } => {
+                self.expr(*func, depth);
+                for arg in args {
+                    self.expr(*arg, depth);
+                }
+            }
+            ast::Expr::PartialApply { func, args, .. } => {
+                self.expr(*func, depth);
+                for arg in args.iter().flatten() {
+                    self.expr(*arg, depth);
+                }
+            }
+            ast::Expr::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.complexity += 1;
+                self.expr(*condition, depth);
+                self.expr(*then_branch, depth);
+                if let Some(else_branch) = else_branch {
+                    self.expr(*else_branch, depth);
+                }
+            }
+            ast::Expr::Match {
+                scrutinee,
+                arms,
+                else_branch,
+                ..
+            } => {
+                self.complexity += arms.len();
+                self.expr(*scrutinee, depth);
+                for arm in arms {
+                    for pattern in &arm.patterns {
+                        self.expr(*pattern, depth);
+                    }
+                    self.expr(arm.body, depth);
+                }
+                if let Some(else_branch) = else_branch {
+                    self.expr(*else_branch, depth);
+                }
+            }
+            ast::Expr::Let { value, body, .. } => {
+                self.expr(*value, depth);
+                self.expr(*body, depth);
+            }
+            ast::Expr::Block { stmts, expr, .. } => {
+                for stmt in stmts {
+                    match stmt {
+                        ast::Stmt::Let { init, .. } => self.expr(*init, depth),
+                        ast::Stmt::Expr(expr, _) => self.expr(*expr, depth),
+                    }
+                }
+                if let Some(expr) = expr {
+                    self.expr(*expr, depth);
+                }
+            }
+            ast::Expr::Array { elements, .. } => {
+                for element in elements {
+                    self.expr(*element, depth);
+                }
+            }
+            ast::Expr::Index { base, index, .. } => {
+                self.expr(*base, depth);
+                self.expr(*index, depth);
+            }
+            ast::Expr::Member { base, .. } => self.expr(*base, depth),
+            ast::Expr::RecordLiteral { properties, .. } => {
+                for property in properties {
+                    self.expr(property.value, depth);
+                }
+            }
+            ast::Expr::Element { element, .. } => {
+                let depth = depth + 1;
+                self.max_depth = self.max_depth.max(depth);
+                let element = self.module.element(*element);
+                self.property_entries(element.property_entries(), depth);
+                for content in &element.content {
+                    self.expr(*content, depth);
+                }
+            }
+            ast::Expr::ActionHandler { body, .. } => self.expr(*body, depth),
+            ast::Expr::For { iterable, body, .. } => {
+                self.complexity += 1;
+                self.expr(*iterable, depth);
+                self.expr(*body, depth);
+            }
+            ast::Expr::Provide { value, body, .. } => {
+                self.expr(*value, depth);
+                self.expr(*body, depth);
+            }
+            ast::Expr::Destructure { source, .. } => self.expr(*source, depth),
+        }
+    }
+
+    fn property_entries(&mut self, entries: &[PropertyEntry], depth: usize) {
+        for entry in entries {
+            match entry {
+                PropertyEntry::Value(property) => self.expr(property.value, depth),
+                PropertyEntry::Spread { value, .. } => self.expr(*value, depth),
+                PropertyEntry::If {
+                    condition,
+                    then_entries,
+                    else_entries,
+                    ..
+                } => {
+                    self.complexity += 1;
+                    self.expr(*condition, depth);
+                    self.property_entries(then_entries, depth);
+                    self.property_entries(else_entries, depth);
+                }
+                PropertyEntry::ConditionList {
+                    arms, else_entries, ..
+                } => {
+                    self.complexity += arms.len();
+                    for arm in arms {
+                        self.expr(arm.condition, depth);
+                        self.property_entries(&arm.entries, depth);
+                    }
+                    self.property_entries(else_entries, depth);
+                }
+                PropertyEntry::Match {
+                    scrutinee,
+                    arms,
+                    else_entries,
+                    ..
+                } => {
+                    self.complexity += arms.len();
+                    self.expr(*scrutinee, depth);
+                    for arm in arms {
+                        for pattern in &arm.patterns {
+                            self.expr(*pattern, depth);
+                        }
+                        self.property_entries(&arm.entries, depth);
+                    }
+                    self.property_entries(else_entries, depth);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_hir::{lower, SourceId};
+
+    fn metrics(source: &str) -> Vec<(String, &'static str, usize, usize)> {
+        let parse = nx_syntax::parse_str(source, "test.nx");
+        let module = lower(parse.root().expect("root"), SourceId::new(0));
+        module_metrics(&module)
+            .into_iter()
+            .map(|item| {
+                (
+                    item.name,
+                    item.kind.as_str(),
+                    item.complexity,
+                    item.max_depth,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_decisions_and_element_nesting_per_item() {
+        let source = r#"
+            let limit = { 3 }
+            let label(count:int, urgent:bool): string = {
+                if count > 0 && urgent { "now" } else if count > 0 { "soon" } else { "never" }
+            }
+            let root() = <main>
+                <ul>{for item in ["a", "b"] { <li><b>{item}</b></li> }}</ul>
+                <footer />
+            </main>
+            type Row = string
+        "#;
+        assert_eq!(
+            metrics(source),
+            vec![
+                ("limit".to_string(), "variable", 1, 0),
+                ("label".to_string(), "function", 4, 0),
+                ("root".to_string(), "function", 2, 4),
+            ]
+        );
+    }
+}
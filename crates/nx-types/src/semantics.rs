@@ -97,6 +97,10 @@ where
 }
 
 fn builtin_type(name: &Name) -> Option<Type> {
+    // Unlike the primitives, `Element` is case-sensitive so HTML's `<element>` stays a tag name.
+    if name.as_str() == "Element" {
+        return Some(Type::element());
+    }
     match name.as_str().to_ascii_lowercase().as_str() {
         "string" => Some(Type::string()),
         "i32" => Some(Type::i32()),
@@ -106,6 +110,7 @@ fn builtin_type(name: &Name) -> Option<Type> {
         "f64" => Some(Type::f64()),
         "float" => Some(Type::float()),
         "bool" => Some(Type::bool()),
+        "money" => Some(Type::money()),
         "void" => Some(Type::void()),
         _ => None,
     }
@@ -191,6 +196,12 @@ mod tests {
         assert_eq!(builtin_type(&Name::new("Bool")), Some(Type::bool()));
     }
 
+    #[test]
+    fn test_builtin_element_type_is_case_sensitive() {
+        assert_eq!(builtin_type(&Name::new("Element")), Some(Type::element()));
+        assert_eq!(builtin_type(&Name::new("element")), None);
+    }
+
     #[test]
     fn test_resolve_type_ref_with_uses_builtin_and_callback_resolution() {
         let type_ref = ast::TypeRef::function(
@@ -0,0 +1,406 @@
+//! On-disk snapshots of a [`TypeCheckSession`](crate::TypeCheckSession).
+//!
+//! A snapshot stores each file's source together with a hash of it, the symbols and call sites
+//! indexed from it, and its diagnostics when they were cached. All of these depend only on the
+//! file's own source, so a session restored from a snapshot reuses them until the file changes
+//! and only re-analyzes the files that did. Snapshots written by another checker version or
+//! grammar are rejected rather than trusted.
+
+use crate::{CallKind, CallReference, SymbolInfo};
+use nx_diagnostics::{
+    Diagnostic, DiagnosticBuilder, Fix, Label, Severity, TextEdit, TextSize, TextSpan,
+};
+use nx_hir::SymbolKind;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Version of the snapshot layout, bumped whenever the stored fields change.
+const SNAPSHOT_FORMAT: u32 = 1;
+
+/// Error returned when a session snapshot cannot be saved or loaded.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// Reading or writing the snapshot file failed.
+    Io(std::io::Error),
+    /// The snapshot is not valid JSON or does not have the expected shape.
+    Json(serde_json::Error),
+    /// The snapshot was written by another checker version or grammar, or a file's source no
+    /// longer matches its recorded hash.
+    Incompatible(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(f, "io error: {}", error),
+            SnapshotError::Json(error) => write!(f, "json error: {}", error),
+            SnapshotError::Incompatible(reason) => write!(f, "incompatible snapshot: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(error) => Some(error),
+            SnapshotError::Json(error) => Some(error),
+            SnapshotError::Incompatible(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(error: std::io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(error: serde_json::Error) -> Self {
+        SnapshotError::Json(error)
+    }
+}
+
+/// The analysis state of one session file.
+pub(crate) struct FileState {
+    pub name: String,
+    pub source: String,
+    pub symbols: Vec<SymbolInfo>,
+    pub calls: Vec<CallReference>,
+    /// `None` when the file's diagnostics were not cached.
+    pub diagnostics: Option<Vec<Diagnostic>>,
+}
+
+/// Writes `files` to `path`, replacing any previous snapshot only once the new one is complete.
+pub(crate) fn write_snapshot(path: &Path, files: Vec<FileState>) -> Result<(), SnapshotError> {
+    let snapshot = SnapshotData {
+        format: SNAPSHOT_FORMAT,
+        checker: crate::VERSION.to_string(),
+        grammar: nx_syntax::GRAMMAR_HASH.to_string(),
+        files: files.into_iter().map(FileData::from_state).collect(),
+    };
+    let bytes = serde_json::to_vec(&snapshot)?;
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    fs::write(&partial, bytes)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Reads the files stored in the snapshot at `path`.
+pub(crate) fn read_snapshot(path: &Path) -> Result<Vec<FileState>, SnapshotError> {
+    let snapshot: SnapshotData = serde_json::from_slice(&fs::read(path)?)?;
+    if snapshot.format != SNAPSHOT_FORMAT {
+        return Err(SnapshotError::Incompatible(format!(
+            "snapshot format {} is not {}",
+            snapshot.format, SNAPSHOT_FORMAT
+        )));
+    }
+    if snapshot.checker != crate::VERSION || snapshot.grammar != nx_syntax::GRAMMAR_HASH {
+        return Err(SnapshotError::Incompatible(format!(
+            "written by checker {} with grammar {}",
+            snapshot.checker, snapshot.grammar
+        )));
+    }
+    snapshot
+        .files
+        .into_iter()
+        .map(FileData::into_state)
+        .collect()
+}
+
+/// 64-bit FNV-1a, stable across Rust releases unlike the standard library's hasher.
+fn source_hash(source: &str) -> String {
+    let hash = source
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotData {
+    format: u32,
+    checker: String,
+    grammar: String,
+    files: Vec<FileData>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileData {
+    name: String,
+    hash: String,
+    source: String,
+    symbols: Vec<SymbolData>,
+    calls: Vec<CallData>,
+    diagnostics: Option<Vec<DiagnosticData>>,
+}
+
+impl FileData {
+    fn from_state(state: FileState) -> Self {
+        Self {
+            hash: source_hash(&state.source),
+            symbols: state.symbols.iter().map(SymbolData::from_info).collect(),
+            calls: state.calls.iter().map(CallData::from_reference).collect(),
+            diagnostics: state.diagnostics.map(|diagnostics| {
+                diagnostics
+                    .iter()
+                    .map(DiagnosticData::from_diagnostic)
+                    .collect()
+            }),
+            name: state.name,
+            source: state.source,
+        }
+    }
+
+    fn into_state(self) -> Result<FileState, SnapshotError> {
+        if source_hash(&self.source) != self.hash {
+            return Err(SnapshotError::Incompatible(format!(
+                "source of '{}' does not match its hash",
+                self.name
+            )));
+        }
+        let symbols = self
+            .symbols
+            .into_iter()
+            .map(|symbol| symbol.into_info(&self.name))
+            .collect::<Result<_, _>>()?;
+        let calls = self
+            .calls
+            .into_iter()
+            .map(|call| call.into_reference(&self.name))
+            .collect::<Result<_, _>>()?;
+        let diagnostics = self
+            .diagnostics
+            .map(|diagnostics| {
+                diagnostics
+                    .into_iter()
+                    .map(DiagnosticData::into_diagnostic)
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?;
+        Ok(FileState {
+            name: self.name,
+            source: self.source,
+            symbols,
+            calls,
+            diagnostics,
+        })
+    }
+}
+
+/// A span as its start and end byte offsets.
+type SpanData = [u32; 2];
+
+fn span_data(span: TextSpan) -> SpanData {
+    [span.start().into(), span.end().into()]
+}
+
+fn span_from_data([start, end]: SpanData) -> Result<TextSpan, SnapshotError> {
+    if start > end {
+        return Err(SnapshotError::Incompatible(format!(
+            "span {start}..{end} ends before it starts"
+        )));
+    }
+    Ok(TextSpan::new(TextSize::from(start), TextSize::from(end)))
+}
+
+fn unknown(what: &str, name: &str) -> SnapshotError {
+    SnapshotError::Incompatible(format!("unknown {what} '{name}'"))
+}
+
+#[derive(Serialize, Deserialize)]
+struct SymbolData {
+    name: String,
+    kind: String,
+    container: Option<String>,
+    span: SpanData,
+}
+
+impl SymbolData {
+    fn from_info(info: &SymbolInfo) -> Self {
+        Self {
+            name: info.name.clone(),
+            kind: info.kind.as_str().to_string(),
+            container: info.container.clone(),
+            span: span_data(info.span),
+        }
+    }
+
+    fn into_info(self, file: &str) -> Result<SymbolInfo, SnapshotError> {
+        let kind = [
+            SymbolKind::Function,
+            SymbolKind::Component,
+            SymbolKind::Variable,
+            SymbolKind::Parameter,
+            SymbolKind::Type,
+            SymbolKind::EnumMember,
+            SymbolKind::Builtin,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == self.kind)
+        .ok_or_else(|| unknown("symbol kind", &self.kind))?;
+        Ok(SymbolInfo {
+            name: self.name,
+            kind,
+            container: self.container,
+            file: file.to_string(),
+            span: span_from_data(self.span)?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CallData {
+    caller: String,
+    callee: String,
+    element: bool,
+    span: SpanData,
+}
+
+impl CallData {
+    fn from_reference(reference: &CallReference) -> Self {
+        Self {
+            caller: reference.caller.clone(),
+            callee: reference.callee.clone(),
+            element: reference.kind == CallKind::Element,
+            span: span_data(reference.span),
+        }
+    }
+
+    fn into_reference(self, file: &str) -> Result<CallReference, SnapshotError> {
+        Ok(CallReference {
+            caller: self.caller,
+            callee: self.callee,
+            kind: if self.element {
+                CallKind::Element
+            } else {
+                CallKind::Call
+            },
+            file: file.to_string(),
+            span: span_from_data(self.span)?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiagnosticData {
+    severity: String,
+    code: Option<String>,
+    message: String,
+    labels: Vec<LabelData>,
+    help: Option<String>,
+    note: Option<String>,
+    fixes: Vec<FixData>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LabelData {
+    file: String,
+    span: SpanData,
+    message: Option<String>,
+    primary: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FixData {
+    message: String,
+    edits: Vec<EditData>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EditData {
+    file: String,
+    span: SpanData,
+    replacement: String,
+}
+
+impl DiagnosticData {
+    fn from_diagnostic(diagnostic: &Diagnostic) -> Self {
+        Self {
+            severity: diagnostic.severity().as_str().to_string(),
+            code: diagnostic.code().map(str::to_string),
+            message: diagnostic.message().to_string(),
+            labels: diagnostic
+                .labels()
+                .iter()
+                .map(|label| LabelData {
+                    file: label.file.clone(),
+                    span: span_data(label.range),
+                    message: label.message.clone(),
+                    primary: label.primary,
+                })
+                .collect(),
+            help: diagnostic.help().map(str::to_string),
+            note: diagnostic.note().map(str::to_string),
+            fixes: diagnostic
+                .fixes()
+                .iter()
+                .map(|fix| FixData {
+                    message: fix.message.clone(),
+                    edits: fix
+                        .edits
+                        .iter()
+                        .map(|edit| EditData {
+                            file: edit.file.clone(),
+                            span: span_data(edit.range),
+                            replacement: edit.replacement.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn into_diagnostic(self) -> Result<Diagnostic, SnapshotError> {
+        let severity = [
+            Severity::Error,
+            Severity::Warning,
+            Severity::Info,
+            Severity::Hint,
+        ]
+        .into_iter()
+        .find(|severity| severity.as_str() == self.severity)
+        .ok_or_else(|| unknown("severity", &self.severity))?;
+
+        let mut labels = Vec::with_capacity(self.labels.len());
+        for label in self.labels {
+            labels.push(Label {
+                file: label.file,
+                range: span_from_data(label.span)?,
+                message: label.message,
+                primary: label.primary,
+            });
+        }
+        let mut fixes = Vec::with_capacity(self.fixes.len());
+        for fix in self.fixes {
+            let mut edits = Vec::with_capacity(fix.edits.len());
+            for edit in fix.edits {
+                edits.push(TextEdit::replace(
+                    edit.file,
+                    span_from_data(edit.span)?,
+                    edit.replacement,
+                ));
+            }
+            fixes.push(Fix {
+                message: fix.message,
+                edits,
+            });
+        }
+
+        let mut builder = DiagnosticBuilder::new(severity, self.code.unwrap_or_default())
+            .with_message(self.message)
+            .with_labels(labels)
+            .with_fixes(fixes);
+        if let Some(help) = self.help {
+            builder = builder.with_help(help);
+        }
+        if let Some(note) = self.note {
+            builder = builder.with_note(note);
+        }
+        Ok(builder.build())
+    }
+}
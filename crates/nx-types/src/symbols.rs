@@ -0,0 +1,242 @@
+//! Workspace symbol index with fuzzy name search.
+//!
+//! The index stores the top-level declarations of each file, along with enum members, so that
+//! editors and the CLI can find a component or type by a partial name without re-parsing every
+//! file on each query.
+
+use nx_diagnostics::TextSpan;
+use nx_hir::{lower, Item, LoweredModule, SourceId, SymbolKind};
+use rustc_hash::FxHashMap;
+
+/// A declaration found by a workspace symbol search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolInfo {
+    /// Declared name.
+    pub name: String,
+    /// What the declaration is.
+    pub kind: SymbolKind,
+    /// Name of the enclosing declaration, such as the enum that declares a member.
+    pub container: Option<String>,
+    /// File that declares the symbol, as it was added to the index.
+    pub file: String,
+    /// Source span of the declaration.
+    pub span: TextSpan,
+}
+
+/// Declarations of a set of files, indexed for fuzzy name search.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    files: FxHashMap<String, Vec<SymbolInfo>>,
+}
+
+impl SymbolIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexes the declarations of `source`, replacing any symbols previously indexed for `file`.
+    ///
+    /// Files with syntax errors are indexed from the recovered tree so that symbols stay
+    /// searchable while a file is being edited.
+    pub fn update_file(&mut self, file: impl Into<String>, source: &str) {
+        let file = file.into();
+        match lower_for_index(source, &file) {
+            Some(module) => self.update_module(file, &module),
+            None => {
+                self.files.insert(file, Vec::new());
+            }
+        }
+    }
+
+    /// Indexes the declarations of an already lowered module, replacing any symbols previously
+    /// indexed for `file`.
+    pub fn update_module(&mut self, file: impl Into<String>, module: &LoweredModule) {
+        let file = file.into();
+        let symbols = module_symbols(module, &file);
+        self.files.insert(file, symbols);
+    }
+
+    /// Replaces the symbols indexed for `file` with ones indexed earlier, such as from a
+    /// session snapshot.
+    pub(crate) fn restore_file(&mut self, file: String, symbols: Vec<SymbolInfo>) {
+        self.files.insert(file, symbols);
+    }
+
+    /// Removes the symbols indexed for `file`.
+    pub fn remove_file(&mut self, file: &str) {
+        self.files.remove(file);
+    }
+
+    /// Returns the symbols indexed for `file`, in declaration order.
+    pub fn file_symbols(&self, file: &str) -> &[SymbolInfo] {
+        self.files.get(file).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the symbols whose names fuzzy-match `query`, best matches first.
+    ///
+    /// Matching ignores case. Exact names rank first, then prefixes, then names containing the
+    /// query, then names containing its characters in order, such as `PrCa` for `ProductCard`.
+    /// Within a rank, shorter names come first. An empty query returns every symbol.
+    pub fn search(&self, query: &str) -> Vec<SymbolInfo> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(MatchRank, &SymbolInfo)> = self
+            .files
+            .values()
+            .flatten()
+            .filter_map(|symbol| match_rank(&query, &symbol.name).map(|rank| (rank, symbol)))
+            .collect();
+
+        matches.sort_by(|(left_rank, left), (right_rank, right)| {
+            left_rank
+                .cmp(right_rank)
+                .then_with(|| left.name.len().cmp(&right.name.len()))
+                .then_with(|| left.name.cmp(&right.name))
+                .then_with(|| left.file.cmp(&right.file))
+                .then_with(|| left.span.start().cmp(&right.span.start()))
+        });
+        matches
+            .into_iter()
+            .map(|(_, symbol)| symbol.clone())
+            .collect()
+    }
+
+    /// Returns every indexed symbol, in no particular order.
+    pub(crate) fn all(&self) -> impl Iterator<Item = &SymbolInfo> {
+        self.files.values().flatten()
+    }
+
+    /// Returns the number of indexed files.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns true if no files are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchRank {
+    Exact,
+    Prefix,
+    Substring,
+    Subsequence,
+}
+
+fn match_rank(query: &str, name: &str) -> Option<MatchRank> {
+    let name = name.to_lowercase();
+    if name == query {
+        Some(MatchRank::Exact)
+    } else if name.starts_with(query) {
+        Some(MatchRank::Prefix)
+    } else if name.contains(query) {
+        Some(MatchRank::Substring)
+    } else {
+        let mut name_chars = name.chars();
+        query
+            .chars()
+            .all(|query_char| name_chars.any(|name_char| name_char == query_char))
+            .then_some(MatchRank::Subsequence)
+    }
+}
+
+/// Lowers `source` for indexing, recovering from syntax errors where the parser could.
+pub(crate) fn lower_for_index(source: &str, file: &str) -> Option<LoweredModule> {
+    let parse_result = nx_syntax::parse_str(source, file);
+    parse_result
+        .tree
+        .as_ref()
+        .map(|tree| lower(tree.root(), SourceId::new(0)))
+}
+
+fn module_symbols(module: &LoweredModule, file: &str) -> Vec<SymbolInfo> {
+    let mut symbols = Vec::new();
+    for item in module.items() {
+        let (kind, span) = match item {
+            Item::Function(function) => (SymbolKind::Function, function.span),
+            Item::Value(value) => (SymbolKind::Variable, value.span),
+            Item::Component(component) => (SymbolKind::Component, component.span),
+            Item::TypeAlias(alias) => (SymbolKind::Type, alias.span),
+            Item::Enum(enum_def) => (SymbolKind::Type, enum_def.span),
+            Item::Union(union_def) => (SymbolKind::Type, union_def.span),
+            Item::Record(record) => (SymbolKind::Type, record.span),
+        };
+        symbols.push(SymbolInfo {
+            name: item.name().as_str().to_string(),
+            kind,
+            container: None,
+            file: file.to_string(),
+            span,
+        });
+
+        if let Item::Enum(enum_def) = item {
+            symbols.extend(enum_def.members.iter().map(|member| SymbolInfo {
+                name: member.name.as_str().to_string(),
+                kind: SymbolKind::EnumMember,
+                container: Some(enum_def.name.as_str().to_string()),
+                file: file.to_string(),
+                span: member.span,
+            }));
+        }
+    }
+    symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexed(files: &[(&str, &str)]) -> SymbolIndex {
+        let mut index = SymbolIndex::new();
+        for (file, source) in files {
+            index.update_file(*file, source);
+        }
+        index
+    }
+
+    fn names(symbols: &[SymbolInfo]) -> Vec<&str> {
+        symbols.iter().map(|symbol| symbol.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_search_ranks_exact_prefix_substring_then_subsequence() {
+        let index = indexed(&[
+            (
+                "ui/cards.nx",
+                "let <ProductCard /> = <div />\nlet <Card /> = <div />\nlet <CardList /> = <div />",
+            ),
+            ("ui/price.nx", "let priceCalculator(x:int) = { x }"),
+        ]);
+
+        assert_eq!(
+            names(&index.search("card")),
+            vec!["Card", "CardList", "ProductCard"]
+        );
+        assert_eq!(
+            names(&index.search("PrCa")),
+            vec!["ProductCard", "priceCalculator"]
+        );
+        assert!(index.search("zzz").is_empty());
+        assert_eq!(index.search("").len(), 4);
+    }
+
+    #[test]
+    fn test_update_file_replaces_symbols_and_indexes_enum_members() {
+        let mut index = indexed(&[(
+            "theme.nx",
+            "enum Tone = | Light | Dark\nlet <Old /> = <p />",
+        )]);
+        index.update_file("theme.nx", "enum Tone = | Light | Dark");
+
+        assert!(index.search("Old").is_empty());
+        let dark = &index.search("Dark")[0];
+        assert_eq!(dark.kind, SymbolKind::EnumMember);
+        assert_eq!(dark.container.as_deref(), Some("Tone"));
+        assert_eq!(dark.file, "theme.nx");
+
+        index.remove_file("theme.nx");
+        assert!(index.is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+//! Per-stage timing of the NX pipeline.
+//!
+//! Pipeline code wraps each stage in [`time_stage`]. Timing is off unless the caller runs the
+//! work inside [`collect_timings`], which measures every stage entered on the current thread,
+//! including stages of imported libraries, and returns the totals. When no collection is active
+//! `time_stage` only calls its closure and records which stage is running, so [`current_stage`]
+//! can name the stage that was active when something went wrong.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A stage of the pipeline, from reading the source to serializing the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// Reading source files.
+    Read,
+    /// Parsing source text into a syntax tree.
+    Parse,
+    /// Lowering syntax trees to HIR.
+    Lower,
+    /// Building scopes and resolving names.
+    Scope,
+    /// Validating declarations and inferring types.
+    Check,
+    /// Evaluating the program.
+    Eval,
+    /// Formatting the result for output.
+    Serialize,
+}
+
+impl Stage {
+    /// Every stage, in pipeline order.
+    pub const ALL: [Stage; 7] = [
+        Stage::Read,
+        Stage::Parse,
+        Stage::Lower,
+        Stage::Scope,
+        Stage::Check,
+        Stage::Eval,
+        Stage::Serialize,
+    ];
+
+    /// Returns the lowercase name of this stage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Read => "read",
+            Stage::Parse => "parse",
+            Stage::Lower => "lower",
+            Stage::Scope => "scope",
+            Stage::Check => "check",
+            Stage::Eval => "eval",
+            Stage::Serialize => "serialize",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Total time spent in each stage.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StageTimings {
+    durations: [Duration; Stage::ALL.len()],
+}
+
+impl StageTimings {
+    /// Returns the total time spent in `stage`.
+    pub fn get(&self, stage: Stage) -> Duration {
+        self.durations[stage.index()]
+    }
+
+    /// Adds `duration` to the time spent in `stage`.
+    pub fn record(&mut self, stage: Stage, duration: Duration) {
+        self.durations[stage.index()] += duration;
+    }
+
+    /// Returns every stage with its total time, in pipeline order.
+    pub fn iter(&self) -> impl Iterator<Item = (Stage, Duration)> + '_ {
+        Stage::ALL.into_iter().map(|stage| (stage, self.get(stage)))
+    }
+
+    /// Returns the time spent in all stages together.
+    pub fn total(&self) -> Duration {
+        self.durations.iter().sum()
+    }
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<StageTimings>> = const { RefCell::new(None) };
+    static CURRENT: Cell<Option<Stage>> = const { Cell::new(None) };
+}
+
+/// Returns the innermost stage running on this thread, if any.
+///
+/// A panic hook sees the stage that panicked, since hooks run before the stack unwinds.
+pub fn current_stage() -> Option<Stage> {
+    CURRENT.with(Cell::get)
+}
+
+/// Restores the enclosing stage when a stage ends, including by unwinding.
+struct CurrentStageGuard(Option<Stage>);
+
+impl CurrentStageGuard {
+    fn enter(stage: Stage) -> Self {
+        Self(CURRENT.with(|current| current.replace(Some(stage))))
+    }
+}
+
+impl Drop for CurrentStageGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.0));
+    }
+}
+
+/// Runs `f` as part of `stage`, adding its duration to the active collection, if any.
+pub fn time_stage<R>(stage: Stage, f: impl FnOnce() -> R) -> R {
+    let _current = CurrentStageGuard::enter(stage);
+    if ACTIVE.with(|active| active.borrow().is_none()) {
+        return f();
+    }
+
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    ACTIVE.with(|active| {
+        if let Some(timings) = active.borrow_mut().as_mut() {
+            timings.record(stage, elapsed);
+        }
+    });
+    result
+}
+
+/// Runs `f` with stage timing enabled on this thread and returns the time spent in each stage.
+///
+/// Collections nest: time recorded inside `f` also counts toward an enclosing collection.
+pub fn collect_timings<R>(f: impl FnOnce() -> R) -> (R, StageTimings) {
+    let previous = ACTIVE.with(|active| active.replace(Some(StageTimings::default())));
+    let result = f();
+    let timings = ACTIVE.with(|active| {
+        let mut active = active.borrow_mut();
+        let timings = std::mem::replace(&mut *active, previous).unwrap_or_default();
+        if let Some(outer) = active.as_mut() {
+            for (stage, duration) in timings.iter() {
+                outer.record(stage, duration);
+            }
+        }
+        timings
+    });
+    (result, timings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_stage_records_only_inside_collect_timings() {
+        time_stage(Stage::Parse, || {
+            std::thread::sleep(Duration::from_millis(2))
+        });
+
+        let (value, timings) = collect_timings(|| {
+            time_stage(Stage::Parse, || {
+                std::thread::sleep(Duration::from_millis(2))
+            });
+            time_stage(Stage::Parse, || 42)
+        });
+
+        assert_eq!(value, 42);
+        assert!(timings.get(Stage::Parse) >= Duration::from_millis(2));
+        assert_eq!(timings.get(Stage::Check), Duration::ZERO);
+        assert_eq!(timings.total(), timings.get(Stage::Parse));
+
+        let (_, idle) = collect_timings(|| ());
+        assert_eq!(idle, StageTimings::default());
+    }
+
+    #[test]
+    fn test_nested_collect_timings_count_toward_the_enclosing_collection() {
+        let ((_, inner), outer) = collect_timings(|| {
+            collect_timings(|| {
+                time_stage(Stage::Check, || {
+                    std::thread::sleep(Duration::from_millis(2))
+                })
+            })
+        });
+
+        assert!(inner.get(Stage::Check) >= Duration::from_millis(2));
+        assert_eq!(outer, inner);
+    }
+
+    #[test]
+    fn test_current_stage_tracks_nesting_and_unwinding() {
+        assert_eq!(current_stage(), None);
+        time_stage(Stage::Check, || {
+            time_stage(Stage::Lower, || {
+                assert_eq!(current_stage(), Some(Stage::Lower))
+            });
+            assert_eq!(current_stage(), Some(Stage::Check));
+
+            let panicked = std::panic::catch_unwind(|| time_stage(Stage::Eval, || panic!("boom")));
+            assert!(panicked.is_err());
+            assert_eq!(current_stage(), Some(Stage::Check));
+        });
+        assert_eq!(current_stage(), None);
+    }
+}
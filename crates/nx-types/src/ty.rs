@@ -20,6 +20,7 @@ enum CanonicalPrimitive {
     F64,
     String,
     Bool,
+    Money,
     Void,
 }
 
@@ -45,6 +46,8 @@ pub enum Primitive {
     String,
     /// Boolean type
     Bool,
+    /// Amount of money in a currency; only amounts in the same currency add or subtract
+    Money,
     /// Void/unit type (functions with no return value)
     Void,
 }
@@ -59,6 +62,7 @@ impl Primitive {
             Primitive::F64 | Primitive::Float => CanonicalPrimitive::F64,
             Primitive::String => CanonicalPrimitive::String,
             Primitive::Bool => CanonicalPrimitive::Bool,
+            Primitive::Money => CanonicalPrimitive::Money,
             Primitive::Void => CanonicalPrimitive::Void,
         }
     }
@@ -74,6 +78,7 @@ impl Primitive {
             Primitive::Float => "float",
             Primitive::String => "string",
             Primitive::Bool => "bool",
+            Primitive::Money => "money",
             Primitive::Void => "void",
         }
     }
@@ -150,7 +155,7 @@ impl fmt::Display for Primitive {
 /// Types are immutable and can be shared via `Arc` for efficiency.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Type {
-    /// Primitive type (i32, i64, int, f32, f64, float, string, bool, void)
+    /// Primitive type (i32, i64, int, f32, f64, float, string, bool, money, void)
     Primitive(Primitive),
 
     /// Array type: T[]
@@ -187,6 +192,13 @@ pub enum Type {
     /// Discriminated union case type scoped to an owning union.
     UnionCase(UnionCaseType),
 
+    /// Rendered markup: an intrinsic element such as `<div>`, or a component invocation.
+    ///
+    /// Written `Element` in source. Component invocations keep their component's [`Type::Named`]
+    /// type and satisfy `Element`; record values do not, even though records share the element
+    /// syntax.
+    Element,
+
     /// Type variable for inference (e.g., T0, T1, T2)
     ///
     /// Used during type inference before the concrete type is known.
@@ -244,6 +256,11 @@ impl Type {
         Type::Primitive(Primitive::Bool)
     }
 
+    /// Creates a primitive money type.
+    pub fn money() -> Self {
+        Type::Primitive(Primitive::Money)
+    }
+
     /// Creates a primitive void type.
     pub fn void() -> Self {
         Type::Primitive(Primitive::Void)
@@ -272,6 +289,11 @@ impl Type {
         Type::Named(name.into())
     }
 
+    /// Creates the built-in element type.
+    pub fn element() -> Self {
+        Type::Element
+    }
+
     /// Creates an enum type.
     pub fn enum_type(name: impl Into<Name>, members: Vec<Name>) -> Self {
         Type::Enum(EnumType::new(name.into(), members))
@@ -405,6 +427,160 @@ impl Type {
 
         false
     }
+
+    /// Explains why this type is not compatible with `expected` when the mismatch is nested
+    /// inside an array, nullable, or function type.
+    ///
+    /// Returns `None` when the types are compatible, or when the mismatch is at the top level
+    /// and printing both types already says everything.
+    pub fn explain_mismatch(&self, expected: &Type) -> Option<TypeMismatch> {
+        let mut path = Vec::new();
+        let reason = mismatch_reason(self, expected, &mut path)?;
+        if path.is_empty() && matches!(reason, MismatchReason::Types { .. }) {
+            return None;
+        }
+        Some(TypeMismatch { path, reason })
+    }
+}
+
+/// One step from a composite type to a type nested inside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypePathStep {
+    /// The element type of an array.
+    ArrayElement,
+    /// The inner type of a nullable type.
+    NullableInner,
+    /// A function parameter, counted from zero.
+    Parameter(usize),
+    /// A function return type.
+    Return,
+}
+
+impl fmt::Display for TypePathStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypePathStep::ArrayElement => write!(f, "element of array type"),
+            TypePathStep::NullableInner => write!(f, "inner type of nullable type"),
+            TypePathStep::Parameter(index) => {
+                write!(f, "parameter {} of function type", index + 1)
+            }
+            TypePathStep::Return => write!(f, "return type of function type"),
+        }
+    }
+}
+
+/// The innermost difference between two incompatible types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MismatchReason {
+    /// The nested types differ.
+    Types { expected: Type, actual: Type },
+    /// Two function types take different numbers of parameters.
+    Arity { expected: usize, actual: usize },
+}
+
+/// Where two types stop being compatible, as found by [`Type::explain_mismatch`].
+///
+/// Displays as a single line such as
+/// `parameter 2 of function type: expected int, found string`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeMismatch {
+    /// Steps from the outermost type to the mismatch, outermost first.
+    pub path: Vec<TypePathStep>,
+    /// What differs at the end of the path.
+    pub reason: MismatchReason,
+}
+
+impl fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "function type")?;
+        }
+        for (index, step) in self.path.iter().rev().enumerate() {
+            if index > 0 {
+                write!(f, " in ")?;
+            }
+            write!(f, "{}", step)?;
+        }
+        match &self.reason {
+            MismatchReason::Types { expected, actual } => {
+                write!(f, ": expected {}, found {}", expected, actual)
+            }
+            MismatchReason::Arity { expected, actual } => write!(
+                f,
+                ": expected {} parameter{}, found {}",
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                actual
+            ),
+        }
+    }
+}
+
+fn mismatch_reason(
+    actual: &Type,
+    expected: &Type,
+    path: &mut Vec<TypePathStep>,
+) -> Option<MismatchReason> {
+    if actual.is_compatible_with(expected) {
+        return None;
+    }
+
+    let nested = match (actual, expected) {
+        (Type::Array(actual_inner), Type::Array(expected_inner)) => {
+            Some((TypePathStep::ArrayElement, actual_inner, expected_inner))
+        }
+        (Type::Nullable(actual_inner), Type::Nullable(expected_inner)) => {
+            Some((TypePathStep::NullableInner, actual_inner, expected_inner))
+        }
+        (
+            Type::Function {
+                params: actual_params,
+                ret: actual_ret,
+            },
+            Type::Function {
+                params: expected_params,
+                ret: expected_ret,
+            },
+        ) => {
+            if actual_params.len() != expected_params.len() {
+                return Some(MismatchReason::Arity {
+                    expected: expected_params.len(),
+                    actual: actual_params.len(),
+                });
+            }
+            // Parameters are contravariant: the expected parameter must fit the actual one.
+            if let Some((index, (actual_param, expected_param))) =
+                actual_params.iter().zip(expected_params).enumerate().find(
+                    |(_, (actual_param, expected_param))| {
+                        !expected_param.is_compatible_with(actual_param)
+                    },
+                )
+            {
+                path.push(TypePathStep::Parameter(index));
+                return Some(MismatchReason::Types {
+                    expected: expected_param.clone(),
+                    actual: actual_param.clone(),
+                });
+            }
+            Some((TypePathStep::Return, actual_ret, expected_ret))
+        }
+        // A non-null value fits a nullable type when it fits the inner type.
+        (_, Type::Nullable(expected_inner)) if !actual.is_nullable() => {
+            return mismatch_reason(actual, expected_inner, path);
+        }
+        _ => None,
+    };
+
+    match nested {
+        Some((step, actual_inner, expected_inner)) => {
+            path.push(step);
+            mismatch_reason(actual_inner, expected_inner, path)
+        }
+        None => Some(MismatchReason::Types {
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+    }
 }
 
 impl fmt::Display for Type {
@@ -427,6 +603,7 @@ impl fmt::Display for Type {
             Type::Enum(enum_ty) => write!(f, "{}", enum_ty.name),
             Type::Union(union_ty) => write!(f, "{}", union_ty.name),
             Type::UnionCase(case_ty) => write!(f, "{}.{}", case_ty.union, case_ty.case),
+            Type::Element => write!(f, "Element"),
             Type::Variable(id) => write!(f, "T{}", id),
             Type::Unknown => write!(f, "?"),
             Type::Error => write!(f, "<error>"),
@@ -713,6 +890,40 @@ mod tests {
         assert!(!f1.is_compatible_with(&f3));
     }
 
+    #[test]
+    fn test_explain_mismatch_walks_to_the_nested_difference() {
+        let expected = Type::function(vec![Type::int(), Type::int()], Type::bool());
+        let actual = Type::function(vec![Type::int(), Type::string()], Type::bool());
+        assert_eq!(
+            actual.explain_mismatch(&expected).unwrap().to_string(),
+            "parameter 2 of function type: expected int, found string"
+        );
+
+        let expected = Type::array(Type::function(vec![Type::int()], Type::int()));
+        let actual = Type::array(Type::function(vec![Type::int()], Type::string()));
+        assert_eq!(
+            actual.explain_mismatch(&expected).unwrap().to_string(),
+            "return type of function type in element of array type: expected int, found string"
+        );
+
+        let expected = Type::function(vec![Type::int(), Type::int()], Type::int());
+        let actual = Type::function(vec![Type::int()], Type::int());
+        assert_eq!(
+            actual.explain_mismatch(&expected).unwrap().to_string(),
+            "function type: expected 2 parameters, found 1"
+        );
+    }
+
+    #[test]
+    fn test_explain_mismatch_skips_compatible_and_top_level_mismatches() {
+        assert_eq!(Type::int().explain_mismatch(&Type::int()), None);
+        assert_eq!(Type::int().explain_mismatch(&Type::string()), None);
+        assert_eq!(
+            Type::array(Type::int()).explain_mismatch(&Type::nullable(Type::array(Type::int()))),
+            None
+        );
+    }
+
     #[test]
     fn test_strip_nullable() {
         let nullable = Type::nullable(Type::int());
@@ -0,0 +1,375 @@
+//! Textual forms of types: parsing the compact display syntax, and verbose descriptions.
+//!
+//! The compact form written by `Display for Type` is the one used in diagnostics. It is also
+//! stable and parseable: [`Type::parse`] reads it back, so tests and tools can spell expected
+//! types as strings instead of building them by hand.
+
+use crate::{resolve_type_ref_with, Primitive, Type};
+use nx_hir::{ast, Item, LoweredModule, Name, RecordKind};
+use std::fmt;
+
+/// Error returned by [`Type::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeParseError {
+    /// Byte offset in the input where parsing failed.
+    pub offset: usize,
+    /// What the parser expected at that offset.
+    pub message: String,
+}
+
+impl fmt::Display for TypeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for TypeParseError {}
+
+impl Type {
+    /// Parses the compact type syntax produced by `Display`.
+    ///
+    /// Accepts primitives, names (including dotted names such as `LoadState.idle`), `T[]`,
+    /// `T?`, function types such as `(int, string) => bool`, parentheses for grouping, and the
+    /// placeholders `T0`, `?` and `<error>`. Nominal types parse as [`Type::Named`], since the
+    /// compact form does not carry enum members or union cases; the parsed type displays the
+    /// same as the original.
+    pub fn parse(text: &str) -> Result<Type, TypeParseError> {
+        let mut parser = TypeParser { text, pos: 0 };
+        let ty = parser.parse_type()?;
+        parser.skip_whitespace();
+        if parser.pos < text.len() {
+            return Err(parser.error("expected end of type"));
+        }
+        Ok(ty)
+    }
+
+    /// Describes this type along with the definitions of the named types it mentions.
+    ///
+    /// The first line is the compact form. Each enum, union, record or alias the type refers
+    /// to follows on its own line in declaration syntax, such as
+    /// `type User = { name:string tags:string[] }`, in order of first appearance. Records and
+    /// aliases are looked up in `module`; names it does not declare are left out.
+    pub fn display_verbose(&self, module: &LoweredModule) -> String {
+        let mut nominal = Vec::new();
+        collect_nominal_types(self, &mut nominal);
+
+        let mut lines = vec![self.to_string()];
+        let mut described: Vec<&str> = Vec::new();
+        for ty in nominal {
+            let name = match ty {
+                Type::Named(name) => name.as_str(),
+                Type::Enum(enum_ty) => enum_ty.name.as_str(),
+                Type::Union(union_ty) => union_ty.name.as_str(),
+                Type::UnionCase(case_ty) => case_ty.union.as_str(),
+                _ => continue,
+            };
+            if described.contains(&name) {
+                continue;
+            }
+            described.push(name);
+            if let Some(definition) = describe_definition(ty, name, module) {
+                lines.push(definition);
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+struct TypeParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> TypeParser<'a> {
+    fn parse_type(&mut self) -> Result<Type, TypeParseError> {
+        let mut ty = self.parse_primary()?;
+        loop {
+            self.skip_whitespace();
+            if self.eat("[]") {
+                ty = Type::array(ty);
+            } else if self.eat("?") {
+                ty = Type::nullable(ty);
+            } else {
+                return Ok(ty);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Type, TypeParseError> {
+        self.skip_whitespace();
+        if self.eat("(") {
+            return self.parse_parenthesized();
+        }
+        if self.eat("?") {
+            return Ok(Type::Unknown);
+        }
+        if self.eat("<error>") {
+            return Ok(Type::Error);
+        }
+
+        let name = self.parse_name()?;
+        if let Some(primitive) = primitive(name) {
+            return Ok(Type::Primitive(primitive));
+        }
+        if let Some(id) = name.strip_prefix('T').and_then(|id| id.parse().ok()) {
+            return Ok(Type::var(id));
+        }
+        Ok(Type::named(name))
+    }
+
+    /// Parses the rest of `( ... )`: a function type's parameter list or a grouped type.
+    fn parse_parenthesized(&mut self) -> Result<Type, TypeParseError> {
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if !self.eat(")") {
+            loop {
+                items.push(self.parse_type()?);
+                self.skip_whitespace();
+                if self.eat(")") {
+                    break;
+                }
+                if !self.eat(",") {
+                    return Err(self.error("expected ',' or ')'"));
+                }
+            }
+        }
+
+        self.skip_whitespace();
+        if self.eat("=>") {
+            let ret = self.parse_type()?;
+            return Ok(Type::function(items, ret));
+        }
+        match <[Type; 1]>::try_from(items) {
+            Ok([grouped]) => Ok(grouped),
+            Err(_) => Err(self.error("expected '=>' after parameter list")),
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<&'a str, TypeParseError> {
+        let start = self.pos;
+        loop {
+            let segment_len = self.text[self.pos..]
+                .char_indices()
+                .take_while(|(index, ch)| {
+                    ch.is_alphabetic() || *ch == '_' || (*index > 0 && ch.is_ascii_digit())
+                })
+                .last()
+                .map_or(0, |(index, ch)| index + ch.len_utf8());
+            if segment_len == 0 {
+                return Err(self.error("expected a type"));
+            }
+            self.pos += segment_len;
+            if !self.eat(".") {
+                return Ok(&self.text[start..self.pos]);
+            }
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.text[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, message: &str) -> TypeParseError {
+        TypeParseError {
+            offset: self.pos,
+            message: message.to_string(),
+        }
+    }
+}
+
+fn primitive(name: &str) -> Option<Primitive> {
+    [
+        Primitive::I32,
+        Primitive::I64,
+        Primitive::Int,
+        Primitive::F32,
+        Primitive::F64,
+        Primitive::Float,
+        Primitive::String,
+        Primitive::Bool,
+        Primitive::Money,
+        Primitive::Void,
+    ]
+    .into_iter()
+    .find(|primitive| primitive.as_str() == name)
+}
+
+fn collect_nominal_types<'a>(ty: &'a Type, out: &mut Vec<&'a Type>) {
+    match ty {
+        Type::Array(inner) | Type::Nullable(inner) => collect_nominal_types(inner, out),
+        Type::Function { params, ret } => {
+            for param in params {
+                collect_nominal_types(param, out);
+            }
+            collect_nominal_types(ret, out);
+        }
+        Type::Named(_) | Type::Enum(_) | Type::Union(_) | Type::UnionCase(_) => out.push(ty),
+        Type::Primitive(_) | Type::Element | Type::Variable(_) | Type::Unknown | Type::Error => {}
+    }
+}
+
+fn describe_definition(ty: &Type, name: &str, module: &LoweredModule) -> Option<String> {
+    match (module.find_item(name), ty) {
+        (Some(Item::Record(record)), _) => {
+            let keyword = match record.kind {
+                RecordKind::Plain => "type",
+                RecordKind::Action => "action",
+            };
+            let modifier = if record.is_abstract { "abstract " } else { "" };
+            let base = record
+                .base
+                .as_ref()
+                .map(|base| format!(" extends {}", base))
+                .unwrap_or_default();
+            let fields = record
+                .properties
+                .iter()
+                .map(|field| (&field.name, &field.ty));
+            Some(format!(
+                "{modifier}{keyword} {name}{base} = {}",
+                describe_fields(fields)
+            ))
+        }
+        (Some(Item::Union(union_def)), _) => {
+            let base = union_def
+                .base
+                .as_ref()
+                .map(|base| format!(" extends {}", base))
+                .unwrap_or_default();
+            let cases = union_def
+                .cases
+                .iter()
+                .map(|case| {
+                    if case.is_fieldless() {
+                        format!(" | {}", case.name)
+                    } else {
+                        let fields = case.fields.iter().map(|field| (&field.name, &field.ty));
+                        format!(" | {} {}", case.name, describe_fields(fields))
+                    }
+                })
+                .collect::<String>();
+            Some(format!("type {name}{base} ={cases}"))
+        }
+        (Some(Item::Enum(enum_def)), _) => {
+            let members = enum_def.members.iter().map(|member| member.name.as_str());
+            Some(describe_enum(name, members))
+        }
+        (_, Type::Enum(enum_ty)) => Some(describe_enum(
+            name,
+            enum_ty.members.iter().map(Name::as_str),
+        )),
+        (_, Type::Union(union_ty)) => {
+            let cases = union_ty
+                .cases
+                .iter()
+                .map(|case| format!(" | {}", case))
+                .collect::<String>();
+            Some(format!("type {name} ={cases}"))
+        }
+        (Some(Item::TypeAlias(alias)), _) => {
+            Some(format!("type {name} = {}", display_type_ref(&alias.ty)))
+        }
+        _ => None,
+    }
+}
+
+fn describe_enum<'a>(name: &str, members: impl Iterator<Item = &'a str>) -> String {
+    format!("enum {name} = {}", members.collect::<Vec<_>>().join(" | "))
+}
+
+fn describe_fields<'a>(fields: impl Iterator<Item = (&'a Name, &'a ast::TypeRef)>) -> String {
+    let fields = fields
+        .map(|(name, ty)| format!(" {}:{}", name, display_type_ref(ty)))
+        .collect::<String>();
+    format!("{{{fields} }}")
+}
+
+fn display_type_ref(type_ref: &ast::TypeRef) -> String {
+    resolve_type_ref_with(type_ref, &mut |name, _| Type::Named(name.clone())).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nx_hir::{lower, SourceId};
+
+    #[test]
+    fn test_parse_round_trips_the_compact_display() {
+        let types = [
+            Type::int(),
+            Type::array(Type::nullable(Type::string())),
+            Type::nullable(Type::array(Type::i32())),
+            Type::nullable(Type::function(vec![Type::int()], Type::bool())),
+            Type::array(Type::function(vec![], Type::void())),
+            Type::function(
+                vec![
+                    Type::function(vec![Type::named("User")], Type::string()),
+                    Type::float(),
+                ],
+                Type::function(vec![Type::int()], Type::nullable(Type::int())),
+            ),
+            Type::named("LoadState.idle"),
+            Type::array(Type::var(3)),
+            Type::nullable(Type::Unknown),
+            Type::Error,
+        ];
+
+        for ty in types {
+            let text = ty.to_string();
+            assert_eq!(Type::parse(&text), Ok(ty), "round trip of {text}");
+        }
+        assert_eq!(
+            Type::parse(" ( int ,string )=>bool ").unwrap().to_string(),
+            "(int, string) => bool"
+        );
+    }
+
+    #[test]
+    fn test_parse_reports_offset_of_invalid_syntax() {
+        assert_eq!(
+            Type::parse("(int, string)"),
+            Err(TypeParseError {
+                offset: 13,
+                message: "expected '=>' after parameter list".to_string(),
+            })
+        );
+        assert_eq!(Type::parse("int[]x").unwrap_err().offset, 5);
+        assert_eq!(Type::parse("").unwrap_err().offset, 0);
+    }
+
+    #[test]
+    fn test_display_verbose_lists_referenced_definitions() {
+        let parse = nx_syntax::parse_str(
+            r#"
+type User = { name:string tags:string[] }
+enum Tone = light | dark
+type LoadState = | idle | failed { message:string }
+"#,
+            "verbose.nx",
+        );
+        let module = lower(parse.root().expect("root"), SourceId::new(0));
+
+        let ty = Type::function(
+            vec![Type::array(Type::named("User")), Type::named("Tone")],
+            Type::nullable(Type::named("LoadState")),
+        );
+        assert_eq!(
+            ty.display_verbose(&module),
+            "(User[], Tone) => LoadState?\n\
+             type User = { name:string tags:string[] }\n\
+             enum Tone = light | dark\n\
+             type LoadState = | idle | failed { message:string }"
+        );
+        assert_eq!(Type::int().display_verbose(&module), "int");
+    }
+}
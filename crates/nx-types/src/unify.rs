@@ -0,0 +1,274 @@
+//! Type variable substitution and unification.
+//!
+//! Inference creates type variables for expressions whose type depends on later context, such
+//! as the element type of `[]` or the inner type of `null`. Unifying a variable with a concrete
+//! type records a substitution; [`Substitution::apply`] rewrites types through it and
+//! [`Substitution::finalize`] also replaces any variable that stayed unconstrained with its
+//! default, so variables never reach diagnostics or the finished type environment.
+
+use crate::{Type, TypeId};
+use rustc_hash::FxHashMap;
+use std::fmt;
+
+/// Why two types could not be unified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnifyError {
+    /// Binding the variable would make its type contain itself, as in `T = T[]`.
+    Occurs { var: TypeId, ty: Box<Type> },
+    /// The types have different shapes, such as an array and a function.
+    Mismatch { lhs: Box<Type>, rhs: Box<Type> },
+}
+
+impl fmt::Display for UnifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnifyError::Occurs { var, ty } => {
+                write!(f, "type variable T{} occurs in {}", var, ty)
+            }
+            UnifyError::Mismatch { lhs, rhs } => write!(f, "cannot unify {} with {}", lhs, rhs),
+        }
+    }
+}
+
+/// Solved type variables, each bound to the type it stands for.
+#[derive(Debug, Clone, Default)]
+pub struct Substitution {
+    bindings: FxHashMap<TypeId, Type>,
+}
+
+impl Substitution {
+    /// Creates an empty substitution.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the type a variable is bound to, if it has been solved.
+    pub fn lookup(&self, var: TypeId) -> Option<&Type> {
+        self.bindings.get(&var)
+    }
+
+    /// Unifies two types, binding the type variables in either so that both describe the same
+    /// type.
+    ///
+    /// Shapes are matched through arrays, nullables and function types. Leaf types are not
+    /// compared beyond that: whether `int` fits `int?` or a record fits its base is decided by
+    /// the compatibility rules, so two different non-variable leaves unify without binding
+    /// anything. On error, bindings made before the failing position are kept.
+    pub fn unify(&mut self, lhs: &Type, rhs: &Type) -> Result<(), UnifyError> {
+        let lhs = self.shallow_resolve(lhs);
+        let rhs = self.shallow_resolve(rhs);
+
+        match (&lhs, &rhs) {
+            (Type::Variable(a), Type::Variable(b)) if a == b => Ok(()),
+            (Type::Variable(var), other) | (other, Type::Variable(var)) => self.bind(*var, other),
+            (Type::Array(lhs_inner), Type::Array(rhs_inner)) => self.unify(lhs_inner, rhs_inner),
+            (Type::Nullable(lhs_inner), Type::Nullable(rhs_inner)) => {
+                self.unify(lhs_inner, rhs_inner)
+            }
+            // A non-null value may flow into a nullable slot, so `T` unifies with `int?` as `int`.
+            (Type::Nullable(inner), other) | (other, Type::Nullable(inner))
+                if has_variables(inner) || has_variables(other) =>
+            {
+                self.unify(inner, other)
+            }
+            (
+                Type::Function {
+                    params: lhs_params,
+                    ret: lhs_ret,
+                },
+                Type::Function {
+                    params: rhs_params,
+                    ret: rhs_ret,
+                },
+            ) => {
+                if lhs_params.len() != rhs_params.len() {
+                    return Err(UnifyError::Mismatch {
+                        lhs: Box::new(self.apply(&lhs)),
+                        rhs: Box::new(self.apply(&rhs)),
+                    });
+                }
+                for (lhs_param, rhs_param) in lhs_params.iter().zip(rhs_params) {
+                    self.unify(lhs_param, rhs_param)?;
+                }
+                self.unify(lhs_ret, rhs_ret)
+            }
+            (Type::Array(_) | Type::Function { .. }, _)
+            | (_, Type::Array(_) | Type::Function { .. })
+                if has_variables(&lhs) || has_variables(&rhs) =>
+            {
+                Err(UnifyError::Mismatch {
+                    lhs: Box::new(self.apply(&lhs)),
+                    rhs: Box::new(self.apply(&rhs)),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Rewrites `ty`, replacing every solved variable with its binding. Unsolved variables are
+    /// left in place.
+    pub fn apply(&self, ty: &Type) -> Type {
+        self.map_variables(ty, &|var| Type::Variable(var))
+    }
+
+    /// Rewrites `ty` like [`Self::apply`], and replaces every unsolved variable with its
+    /// default.
+    ///
+    /// An unconstrained variable defaults to `object`, the type every value satisfies, so an
+    /// unused `[]` is an `object[]` and a bare `null` an `object?`.
+    pub fn finalize(&self, ty: &Type) -> Type {
+        self.map_variables(ty, &|_| Type::named("object"))
+    }
+
+    fn map_variables(&self, ty: &Type, unsolved: &dyn Fn(TypeId) -> Type) -> Type {
+        match ty {
+            Type::Variable(var) => match self.bindings.get(var) {
+                Some(bound) => self.map_variables(bound, unsolved),
+                None => unsolved(*var),
+            },
+            Type::Array(inner) => Type::array(self.map_variables(inner, unsolved)),
+            Type::Nullable(inner) => match self.map_variables(inner, unsolved) {
+                // `T?` with `T = int?` is just `int?`.
+                inner @ Type::Nullable(_) => inner,
+                inner => Type::nullable(inner),
+            },
+            Type::Function { params, ret } => Type::function(
+                params
+                    .iter()
+                    .map(|param| self.map_variables(param, unsolved))
+                    .collect(),
+                self.map_variables(ret, unsolved),
+            ),
+            _ => ty.clone(),
+        }
+    }
+
+    fn shallow_resolve(&self, ty: &Type) -> Type {
+        let mut ty = ty;
+        while let Type::Variable(var) = ty {
+            match self.bindings.get(var) {
+                Some(bound) => ty = bound,
+                None => break,
+            }
+        }
+        ty.clone()
+    }
+
+    fn bind(&mut self, var: TypeId, ty: &Type) -> Result<(), UnifyError> {
+        let ty = self.apply(ty);
+        if occurs(var, &ty) {
+            return Err(UnifyError::Occurs {
+                var,
+                ty: Box::new(ty),
+            });
+        }
+        // Error and unknown types carry no information worth solving a variable with.
+        if !ty.is_error() && !ty.is_unknown() {
+            self.bindings.insert(var, ty);
+        }
+        Ok(())
+    }
+}
+
+fn occurs(var: TypeId, ty: &Type) -> bool {
+    match ty {
+        Type::Variable(other) => *other == var,
+        Type::Array(inner) | Type::Nullable(inner) => occurs(var, inner),
+        Type::Function { params, ret } => {
+            params.iter().any(|param| occurs(var, param)) || occurs(var, ret)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if `ty` contains a type variable anywhere.
+pub fn has_variables(ty: &Type) -> bool {
+    match ty {
+        Type::Variable(_) => true,
+        Type::Array(inner) | Type::Nullable(inner) => has_variables(inner),
+        Type::Function { params, ret } => params.iter().any(has_variables) || has_variables(ret),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unify_binds_variables_through_nested_types() {
+        let mut subst = Substitution::new();
+        let elem = Type::var(0);
+        let ret = Type::var(1);
+
+        subst
+            .unify(
+                &Type::function(vec![Type::array(elem.clone())], ret.clone()),
+                &Type::function(
+                    vec![Type::array(Type::int())],
+                    Type::nullable(Type::string()),
+                ),
+            )
+            .unwrap();
+
+        assert_eq!(subst.apply(&elem), Type::int());
+        assert_eq!(subst.apply(&ret), Type::nullable(Type::string()));
+        assert_eq!(
+            subst.apply(&Type::nullable(ret)),
+            Type::nullable(Type::string())
+        );
+    }
+
+    #[test]
+    fn test_unify_follows_chains_of_variables() {
+        let mut subst = Substitution::new();
+        subst.unify(&Type::var(0), &Type::var(1)).unwrap();
+        subst.unify(&Type::var(1), &Type::bool()).unwrap();
+
+        assert_eq!(
+            subst.apply(&Type::array(Type::var(0))),
+            Type::array(Type::bool())
+        );
+    }
+
+    #[test]
+    fn test_unify_rejects_infinite_types() {
+        let mut subst = Substitution::new();
+        let var = Type::var(0);
+
+        assert_eq!(
+            subst.unify(&var, &Type::array(var.clone())),
+            Err(UnifyError::Occurs {
+                var: 0,
+                ty: Box::new(Type::array(Type::var(0))),
+            })
+        );
+        assert_eq!(subst.lookup(0), None);
+    }
+
+    #[test]
+    fn test_unify_reports_shape_mismatches_involving_variables() {
+        let mut subst = Substitution::new();
+
+        assert!(matches!(
+            subst.unify(&Type::array(Type::var(0)), &Type::int()),
+            Err(UnifyError::Mismatch { .. })
+        ));
+        assert_eq!(subst.unify(&Type::int(), &Type::string()), Ok(()));
+    }
+
+    #[test]
+    fn test_finalize_defaults_unsolved_variables_to_object() {
+        let mut subst = Substitution::new();
+        subst.unify(&Type::var(1), &Type::int()).unwrap();
+
+        assert_eq!(
+            subst.finalize(&Type::array(Type::var(0))),
+            Type::array(Type::named("object"))
+        );
+        assert_eq!(
+            subst.finalize(&Type::nullable(Type::var(1))),
+            Type::nullable(Type::int())
+        );
+    }
+}
@@ -2,7 +2,11 @@
 //!
 //! These tests verify end-to-end type checking behavior on realistic NX code.
 
-use nx_types::{check_str, Type, TypeCheckSession};
+use nx_types::{
+    analyze_prepared_module, check_str, check_str_with_budget, Type, TypeCheckSession,
+    UNSAFE_INTERPOLATION,
+};
+use std::time::Duration;
 
 // ============================================================================
 // Type Inference Tests (T131, T136)
@@ -45,6 +49,390 @@ fn test_infer_function_call() {
     assert!(result.lowered_module.is_some());
 }
 
+#[test]
+fn test_builtin_calls_resolve_overloads() {
+    let ok = check_str(
+        r#"let count(items:string[]): int = { length(items) + length("abc") }"#,
+        "builtins.nx",
+    );
+    assert!(ok.is_ok(), "{:?}", ok.errors());
+
+    let mismatch = check_str(r#"let root(): int = { length(true) }"#, "builtins.nx");
+    let errors = mismatch.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code(), Some("no-matching-overload"));
+    assert_eq!(
+        errors[0].message(),
+        "No overload of 'length' accepts (bool)"
+    );
+}
+
+#[test]
+fn test_math_builtins_keep_numeric_categories() {
+    let ok = check_str(
+        r#"
+        let scale(x:float): float = { clamp(round(sqrt(x), 2) + abs(-1.5), 0.0, 10.0) }
+        let square(n:int): int = { pow(abs(n), 2) }
+    "#,
+        "math.nx",
+    );
+    assert!(ok.is_ok(), "{:?}", ok.errors());
+
+    let mixed = check_str(r#"let root(): float = { pow(2, 0.5) }"#, "math.nx");
+    assert_eq!(mixed.errors().len(), 1);
+    assert_eq!(
+        mixed.errors()[0].message(),
+        "No overload of 'pow' accepts (int, float)"
+    );
+}
+
+#[test]
+fn test_string_builtins_type_check() {
+    let ok = check_str(
+        r#"
+        let label(name:string): string = { padLeft(upper(trim(name)), 8, ".") }
+        let isDraft(title:string): bool = { startsWith(lower(title), "draft") || contains(title, "wip") }
+        let summary(body:string): string[] = { wrap(truncate(body, 200, "..."), 40) }
+    "#,
+        "strings.nx",
+    );
+    assert!(ok.is_ok(), "{:?}", ok.errors());
+
+    let mismatch = check_str(
+        r#"let root(): string = { padRight("x", "3") }"#,
+        "strings.nx",
+    );
+    assert_eq!(mismatch.errors().len(), 1);
+    assert_eq!(
+        mismatch.errors()[0].message(),
+        "No overload of 'padRight' accepts (string, string)"
+    );
+}
+
+#[test]
+fn test_collection_builtins_type_check() {
+    let ok = check_str(
+        r#"
+        let add(a:int, b:int): int = { a + b }
+        let isEven(n:int): bool = { n % 2 == 0 }
+        let initial(name:string): string = { substring(name, 0, 1) }
+        let scaled(items:int[]): int[] = { map(items, add(_, 10)) }
+        let evens(items:int[]): int[] = { filter(items, isEven(_)) }
+        let total(items:int[]): int = { max(reduce(items, 0, add(_, _)), min(0, 1)) }
+        let initials(names:string[]): string[] = { map(names, initial(_)) }
+    "#,
+        "collections.nx",
+    );
+    assert!(ok.is_ok(), "{:?}", ok.errors());
+
+    let mismatch = check_str(
+        r#"
+        let add(a:int, b:int): int = { a + b }
+        let root(items:int[]): string[] = { map(items, add(_, 1)) }
+    "#,
+        "collections.nx",
+    );
+    assert_eq!(mismatch.errors().len(), 1, "{:?}", mismatch.errors());
+    assert_eq!(mismatch.errors()[0].code(), Some("return-type-mismatch"));
+
+    let not_a_predicate = check_str(
+        r#"
+        let add(a:int, b:int): int = { a + b }
+        let root(items:int[]): int[] = { filter(items, add(_, 1)) }
+    "#,
+        "collections.nx",
+    );
+    assert_eq!(not_a_predicate.errors().len(), 1);
+    assert_eq!(
+        not_a_predicate.errors()[0].code(),
+        Some("no-matching-overload")
+    );
+
+    let wrapped = check_str(
+        r#"
+        let roundPrice(price:float): float = { round(price) }
+        let root(prices:float[]): float[] = { map(prices, roundPrice(_)) }
+    "#,
+        "collections.nx",
+    );
+    assert!(wrapped.is_ok(), "{:?}", wrapped.errors());
+
+    for (source, message, help) in [
+        (
+            r#"
+            let double(n:int): int = { n * 2 }
+            let root(items:int[]): int[] = { map(items, double) }
+        "#,
+            "Function 'double' cannot be used as a value",
+            "Pass a partial application instead: `double(_)`",
+        ),
+        (
+            r#"
+            let add(a:int, b:int): int = { a + b }
+            let root(items:int[]): int = { reduce(items, 0, add) }
+        "#,
+            "Function 'add' cannot be used as a value",
+            "Pass a partial application instead: `add(_, _)`",
+        ),
+        (
+            r#"let root(names:string[]): string[] = { map(names, upper) }"#,
+            "Builtin 'upper' cannot be used as a value",
+            "Builtins cannot be partially applied; declare a function that calls `upper` and \
+             pass it as a partial application such as `f(_)`",
+        ),
+    ] {
+        let result = check_str(source, "collections.nx");
+        let errors = result.errors();
+        assert_eq!(errors.len(), 1, "{source}: {errors:?}");
+        assert_eq!(errors[0].code(), Some("function-as-value"));
+        assert_eq!(errors[0].message(), message);
+        assert_eq!(errors[0].help(), Some(help));
+    }
+
+    let builtin_callback = check_str(
+        r#"let root(prices:float[]): float[] = { map(prices, round(_)) }"#,
+        "collections.nx",
+    );
+    assert_eq!(builtin_callback.errors().len(), 1);
+    assert_eq!(
+        builtin_callback.errors()[0].code(),
+        Some("partial-application")
+    );
+}
+
+#[test]
+fn test_money_adds_to_money_and_scales_by_numbers() {
+    let ok = check_str(
+        r#"
+        type Line = { price:money quantity:int }
+        let lineTotal(line:Line): money = { line.price * line.quantity }
+        let total(line:Line, shipping:money): money = { lineTotal(line) + shipping - money("0.50", "EUR") * 0.5 }
+        let label(amount:money): string = { formatMoney(-amount / 2) + " " + currencyCode(amount) }
+        let isFree(amount:money): bool = { amount <= money(0, "EUR") }
+    "#,
+        "money.nx",
+    );
+    assert!(ok.is_ok(), "{:?}", ok.errors());
+
+    let mixed = check_str(
+        r#"let root(amount:money): money = { amount + 5 }"#,
+        "money.nx",
+    );
+    assert_eq!(mixed.errors().len(), 1);
+    assert_eq!(
+        mixed.errors()[0].message(),
+        "Binary operator Add cannot be applied to types money and int"
+    );
+}
+
+#[test]
+fn test_record_member_access_uses_field_types() {
+    let ok = check_str(
+        r#"
+        type User = { name:string age:int }
+        let isAdult(user:User): bool = { user.age >= 18 }
+        let greet(user:User): string = { "Hi " + user.name }
+    "#,
+        "members.nx",
+    );
+    assert!(ok.is_ok(), "{:?}", ok.errors());
+
+    let unknown = check_str(
+        r#"
+        type User = { name:string age:int }
+        let email(user:User): string = { user.email }
+    "#,
+        "members.nx",
+    );
+    assert_eq!(unknown.errors().len(), 1);
+    assert_eq!(unknown.errors()[0].code(), Some("unknown-record-field"));
+    assert_eq!(
+        unknown.errors()[0].message(),
+        "Record 'User' has no field 'email'"
+    );
+}
+
+#[test]
+fn test_parse_csv_rows_allow_unchecked_field_access() {
+    let result = check_str(
+        r#"
+        let labels(table:string) = { for row in parseCsv(table, ";") { row.name + ": " + row.qty } }
+    "#,
+        "csv.nx",
+    );
+    assert!(result.is_ok(), "{:?}", result.errors());
+}
+
+#[test]
+fn test_concatenating_nullable_string_warns() {
+    let result = check_str(
+        r#"
+        let greet(name:string?): string = { "Hi " + name }
+        let root(): string = { "x" + null }
+    "#,
+        "null-concat.nx",
+    );
+    assert!(result.is_ok(), "{:?}", result.errors());
+
+    let warnings = result
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.code() == Some("null-concatenation"))
+        .map(|diagnostic| diagnostic.message())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            "Concatenating a possibly null string? with a string",
+            "Concatenating null with a string",
+        ]
+    );
+
+    let mismatch = check_str(r#"let root(): string = { "x" + 1 }"#, "null-concat.nx");
+    assert_eq!(mismatch.errors().len(), 1);
+}
+
+#[test]
+fn test_interpolated_url_and_event_properties_warn_unless_sanitized() {
+    let source = r#"
+        let profile(id:string, next:string, count:int) = <div>
+            <a href={"/users/" + id}>Profile</a>
+            <a href={"/users/" + encodeUriComponent(id)}>Safe</a>
+            <a href={sanitizeUrl(next)}>Next</a>
+            <img src={"https://cdn.example.com/" + id + ".png"} alt={"Photo of " + id} />
+            <button onclick={"select('" + id + "')"} tabindex={count + 1}>Select</button>
+            <a href={"/static/" + "about"}>About</a>
+        </div>
+    "#;
+    let result = check_str(source, "links.nx");
+    assert!(result.is_ok(), "{:?}", result.errors());
+
+    let warnings = result
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.code() == Some(UNSAFE_INTERPOLATION))
+        .map(|diagnostic| {
+            let span = diagnostic.labels()[0].range;
+            (
+                diagnostic.message(),
+                &source[usize::from(span.start())..usize::from(span.end())],
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                "The URL property 'href' is built from unsanitized text",
+                r#""/users/" + id"#
+            ),
+            (
+                "The URL property 'src' is built from unsanitized text",
+                r#""https://cdn.example.com/" + id + ".png""#
+            ),
+            (
+                "The event handler property 'onclick' is built from unsanitized text",
+                r#""select('" + id + "')""#
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_bounds_lints_flag_unguarded_divisors_and_nullable_iteration() {
+    let result = check_str(
+        r#"
+        let average(total:int, count:int): int = { if count == 0 { 0 } else { total / count } }
+        let share(total:int, count:int): int = { if count > 0 && total > 0 { total / count } else { 0 } }
+        let half(value:int): int = { value / 2 }
+        let perItem(total:int, items:string[]): int = { total / (length(items) + 1) }
+        let scaled(total:int, count:int): int = { total / clamp(count, 1, 100) }
+        let spread(total:int, items:string[]) = <ul>{for item, i in items { <li>{total / (i + 1)}</li> }}</ul>
+        let ratio(a:float, b:float): float = { a / b }
+        let bucket(value:int, size:int): int = { value % (size - 1) }
+        let labels(items:string[]?) = <ul>{for item in items { <li>{item}</li> }}</ul>
+    "#,
+        "bounds.nx",
+    );
+    assert!(result.is_ok(), "{:?}", result.errors());
+
+    let warnings = result
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.code() != Some("unused-variable"))
+        .map(|diagnostic| {
+            (
+                diagnostic.code().unwrap(),
+                diagnostic.message(),
+                diagnostic.help().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                "possible-division-by-zero",
+                "Dividing by 'b', which may be zero",
+                "Division by zero is a runtime error; check the divisor first, as in \
+                 `if b != 0 { ... } else { ... }`"
+            ),
+            (
+                "possible-division-by-zero",
+                "Taking the remainder of a division by a value that may be zero",
+                "Division by zero is a runtime error; divide by a name that an enclosing `if` \
+                 compares with 0, or by a non-zero literal"
+            ),
+            (
+                "nullable-iteration",
+                "Iterating a possibly null string[]?",
+                "Iterating null is a runtime error; declare 'items' as string[] with a default \
+                 of `[]`"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_void_and_content_free_elements_reject_body_content() {
+    let result = check_str(
+        r#"
+        let <Icon name:string /> = <i title={name} />
+        let root() = <div><br>oops</br><Icon name="x">hi</Icon><img></img></div>
+    "#,
+        "void.nx",
+    );
+
+    let codes = result
+        .errors()
+        .iter()
+        .map(|diagnostic| diagnostic.code())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        vec![
+            Some("void-element-content"),
+            Some("missing-content-property")
+        ]
+    );
+    assert_eq!(
+        result.errors()[0].message(),
+        "'br' is a void element and cannot have content"
+    );
+}
+
+#[test]
+fn test_local_function_shadows_builtin() {
+    let result = check_str(
+        r#"
+        let length(flag:bool): string = { "local" }
+        let root(): string = { length(true) }
+    "#,
+        "shadow.nx",
+    );
+    assert!(result.is_ok(), "{:?}", result.errors());
+}
+
 #[test]
 fn test_infer_array_types() {
     let source = r#"
@@ -83,6 +471,149 @@ fn test_unknown_enum_member_diagnostic() {
     );
 }
 
+#[test]
+fn test_unknown_enum_member_suggests_closest_member() {
+    let source = r#"
+        enum Direction = | north | south
+        let useDirection(): Direction = { Direction.nort }
+    "#;
+
+    let result = check_str(source, "bad-enum.nx");
+    let diagnostic = result
+        .errors()
+        .into_iter()
+        .find(|diag| diag.code() == Some("undefined-enum-member"))
+        .expect("Expected undefined-enum-member diagnostic");
+    assert_eq!(diagnostic.help(), Some("Did you mean `Direction.north`?"));
+}
+
+#[test]
+fn test_undefined_identifier_suggests_in_scope_name() {
+    let source = r#"
+        let greet(userName:string) = { usrName }
+    "#;
+
+    let result = check_str(source, "typo.nx");
+    let diagnostic = result
+        .errors()
+        .into_iter()
+        .find(|diag| diag.code() == Some("undefined-identifier"))
+        .expect("Expected undefined-identifier diagnostic");
+    assert_eq!(diagnostic.help(), Some("Did you mean `userName`?"));
+}
+
+#[test]
+fn test_undefined_identifier_suggests_component_name() {
+    let source = r#"
+        component <SearchBox /> = { <input /> }
+        let render() = { SerchBox }
+    "#;
+
+    let result = check_str(source, "component-typo.nx");
+    let diagnostic = result
+        .errors()
+        .into_iter()
+        .find(|diag| diag.code() == Some("undefined-identifier"))
+        .expect("Expected undefined-identifier diagnostic");
+    assert_eq!(diagnostic.help(), Some("Did you mean `SearchBox`?"));
+}
+
+fn undefined_identifier_help(source: &str) -> Option<String> {
+    let result = check_str(source, "help.nx");
+    let diagnostic = result
+        .errors()
+        .into_iter()
+        .find(|diag| diag.code() == Some("undefined-identifier"))
+        .expect("Expected undefined-identifier diagnostic");
+    diagnostic.help().map(str::to_string)
+}
+
+#[test]
+fn test_undefined_short_identifier_only_suggests_a_different_case() {
+    assert_eq!(undefined_identifier_help("let f(t:int) = { x }"), None);
+    assert_eq!(undefined_identifier_help("let f() = { fro }"), None);
+    assert_eq!(
+        undefined_identifier_help("let f(x:int) = { X }").as_deref(),
+        Some("Did you mean `x`?")
+    );
+}
+
+#[test]
+fn test_undefined_keyword_shaped_identifier_does_not_suggest_builtins() {
+    assert_eq!(undefined_identifier_help("let f() = { let }"), None);
+    assert_eq!(undefined_identifier_help("let f() = { typ }"), None);
+    assert_eq!(
+        undefined_identifier_help("let f() = { rund(1.5) }").as_deref(),
+        Some("Did you mean `round`?")
+    );
+    assert_eq!(
+        undefined_identifier_help("let typo() = 1\nlet f() = { typ }").as_deref(),
+        Some("Did you mean `typo`?")
+    );
+}
+
+#[test]
+fn test_record_defaults_can_read_sibling_fields() {
+    let source = r#"
+        type Invoice = {
+          total: int = { reduce(items, 0, add(_, _)) }
+          items: int[]
+          label: string = { length(items) }
+        }
+
+        let add(a: int, b: int): int = { a + b }
+    "#;
+
+    let result = check_str(source, "record-sibling-defaults.nx");
+    let errors: Vec<_> = result
+        .errors()
+        .iter()
+        .map(|diag| (diag.code(), diag.message().to_string()))
+        .collect();
+    assert_eq!(
+        errors.len(),
+        1,
+        "Expected only the label default to be reported, got {:?}",
+        errors
+    );
+    assert_eq!(errors[0].0, Some("record-default-type-mismatch"));
+    assert!(errors[0].1.contains("label"), "got {:?}", errors);
+}
+
+#[test]
+fn test_record_constraints_must_fit_field_types() {
+    let source = r#"
+        type Person = {
+          name: string @minLength(1) @min(0)
+          age: int? @min(0) @max(150)
+          tags: string[] @maxLength(3)
+          active: bool @maxLength(1)
+        }
+    "#;
+
+    let result = check_str(source, "record-constraints.nx");
+    let errors: Vec<_> = result
+        .errors()
+        .iter()
+        .map(|diag| (diag.code(), diag.message().to_string()))
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            (
+                Some("constraint-type-mismatch"),
+                "Constraint '@min(0)' does not apply to field 'name' of type 'string'; it needs a number"
+                    .to_string()
+            ),
+            (
+                Some("constraint-type-mismatch"),
+                "Constraint '@maxLength(1)' does not apply to field 'active' of type 'bool'; it needs a string or an array"
+                    .to_string()
+            ),
+        ]
+    );
+}
+
 #[test]
 fn test_record_default_type_mismatch_diagnostic() {
     let source = r#"
@@ -374,100 +905,697 @@ fn test_exhaustive_union_match_narrows_identifier() {
 }
 
 #[test]
-fn test_non_exhaustive_union_match_without_else_is_rejected() {
-    let source = r#"
-        type LoadState = | idle | failed { message:string }
-        let view(state: LoadState): string = {
-            if state is {
-                LoadState.idle => ""
-            }
-        }
-    "#;
+fn test_non_exhaustive_union_match_without_else_is_rejected() {
+    let source = r#"
+        type LoadState = | idle | failed { message:string }
+        let view(state: LoadState): string = {
+            if state is {
+                LoadState.idle => ""
+            }
+        }
+    "#;
+
+    let result = check_str(source, "union-match-non-exhaustive.nx");
+    let errors = result.errors();
+    assert!(
+        errors
+            .iter()
+            .any(|diag| diag.code() == Some("non-exhaustive-union-match")),
+        "Expected non-exhaustive-union-match diagnostic, got {:?}",
+        errors
+    );
+}
+
+#[test]
+fn test_union_match_else_allows_partial_coverage() {
+    let source = r#"
+        type LoadState = | idle | failed { message:string }
+        let view(state: LoadState): string = {
+            if state is {
+                LoadState.idle => ""
+                else => "fallback"
+            }
+        }
+    "#;
+
+    let result = check_str(source, "union-match-else.nx");
+    assert!(
+        result.is_ok(),
+        "Expected else arm to allow partial union match coverage, got {:?}",
+        result.errors()
+    );
+}
+
+#[test]
+fn test_union_match_rejects_pattern_from_other_union() {
+    let source = r#"
+        type LoadState = | idle
+        type SaveState = | idle
+        let view(state: LoadState): string = {
+            if state is {
+                SaveState.idle => ""
+                else => "fallback"
+            }
+        }
+    "#;
+
+    let result = check_str(source, "union-match-wrong-union.nx");
+    let errors = result.errors();
+    assert!(
+        errors
+            .iter()
+            .any(|diag| diag.code() == Some("wrong-union-pattern")),
+        "Expected wrong-union-pattern diagnostic, got {:?}",
+        errors
+    );
+}
+
+#[test]
+fn test_property_fragment_required_property_must_be_on_every_path() {
+    let accepted = check_str(
+        r#"
+            let <Button label:string /> = <button>{label}</button>
+            let <Main primary:bool /> = <Button if primary { label="Save" } else { label="Cancel" } />
+        "#,
+        "property-fragment-required-accepted.nx",
+    );
+    assert!(
+        accepted.is_ok(),
+        "Expected every-branch required property to type check, got {:?}",
+        accepted.errors()
+    );
+
+    let rejected = check_str(
+        r#"
+            let <Button label:string /> = <button>{label}</button>
+            let <Main primary:bool /> = <Button if primary { label="Save" } />
+        "#,
+        "property-fragment-required-rejected.nx",
+    );
+    assert!(
+        rejected
+            .errors()
+            .iter()
+            .any(|diag| diag.code() == Some("missing-property")),
+        "Expected missing-property diagnostic, got {:?}",
+        rejected.errors()
+    );
+}
+
+#[test]
+fn test_defaults_and_nullable_types_make_properties_optional() {
+    let accepted = check_str(
+        r#"
+            let <Badge tone:string note:string? size:int = 3 /> = <span>{tone}</span>
+            let <Main /> = <Badge tone="neutral" />
+        "#,
+        "optional-properties.nx",
+    );
+    assert!(
+        accepted.is_ok(),
+        "Expected defaulted and nullable properties to be optional, got {:?}",
+        accepted.errors()
+    );
+
+    let rejected = check_str(
+        r#"
+            let <Badge tone:string label:string note:string? size:int = 3 /> = <span>{tone}</span>
+            let <Main /> = <Badge />
+        "#,
+        "missing-required-properties.nx",
+    );
+    let missing: Vec<_> = rejected
+        .errors()
+        .iter()
+        .filter(|diag| diag.code() == Some("missing-property"))
+        .map(|diag| diag.message().to_string())
+        .collect();
+    assert_eq!(
+        missing,
+        vec!["Element 'Badge' is missing required properties 'label', 'tone'".to_string()]
+    );
+
+    let mismatched = check_str(
+        r#"
+            let <Badge size:int = "large" /> = <span>{size}</span>
+        "#,
+        "param-default-mismatch.nx",
+    );
+    assert!(
+        mismatched
+            .errors()
+            .iter()
+            .any(|diag| diag.code() == Some("param-default-type-mismatch")),
+        "Expected param-default-type-mismatch diagnostic, got {:?}",
+        mismatched.errors()
+    );
+}
+
+#[test]
+fn test_rest_props_accept_undeclared_properties_and_spread() {
+    let accepted = check_str(
+        r#"
+            component <Button label:string ...rest:props /> = {
+                <button {...rest} class="btn">{label}</button>
+            }
+            type Link = { href:string label:string }
+            let defaults() = <Link href="/" label="Home" />
+            let <Main /> = <Button label="Save" id="save" disabled={true} />
+            let <Nav /> = <Button {...defaults()} />
+        "#,
+        "component-rest-props.nx",
+    );
+    assert!(
+        accepted.is_ok(),
+        "Expected rest props and spreads to type check, got {:?}",
+        accepted.errors()
+    );
+
+    let rejected = check_str(
+        r#"
+            component <Button label:string /> = {
+                <button>{label}</button>
+            }
+            let <Main /> = <Button label="Save" id="save" {...3} />
+        "#,
+        "component-without-rest.nx",
+    );
+    let codes: Vec<_> = rejected
+        .errors()
+        .iter()
+        .filter_map(|diag| diag.code())
+        .collect();
+    assert!(
+        codes.contains(&"unknown-property"),
+        "Expected unknown-property without a rest binding, got {:?}",
+        rejected.errors()
+    );
+    assert!(
+        codes.contains(&"invalid-spread"),
+        "Expected invalid-spread for a non-record spread, got {:?}",
+        rejected.errors()
+    );
+}
+
+#[test]
+fn test_property_shorthand_checks_locals_against_field_names_and_types() {
+    let accepted = check_str(
+        r#"
+            type User = { name:string age:int }
+            let make(name:string, age:int): User = { <User {name, age} /> }
+            let older(name:string, age:int): User = { <User {name} age={age + 1} /> }
+        "#,
+        "property-shorthand.nx",
+    );
+    assert!(accepted.is_ok(), "{:?}", accepted.errors());
+
+    let rejected = check_str(
+        r#"
+            type User = { name:string age:int }
+            let wrongType(name:string, age:string): User = { <User {name, age} /> }
+            let unknownField(name:string, age:int, email:string): User = {
+                <User {name, age, email} />
+            }
+            let missingLocal(name:string): User = { <User {name, age} /> }
+        "#,
+        "property-shorthand.nx",
+    );
+    let messages: Vec<_> = rejected
+        .errors()
+        .iter()
+        .map(|diag| diag.message())
+        .collect();
+    assert!(
+        messages.contains(&"Record field 'age' on 'User' expects int, found string"),
+        "{:?}",
+        messages
+    );
+    assert!(
+        messages.contains(&"Record 'User' has no field 'email'"),
+        "{:?}",
+        messages
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("'age'") && message.contains("ndefined")),
+        "{:?}",
+        messages
+    );
+}
+
+#[test]
+fn test_element_type_accepts_markup_and_rejects_records_and_scalars() {
+    let accepted = check_str(
+        r#"
+            component <Card title:string content body:Element[]? /> = {
+                <section><h2>{title}</h2>{body}</section>
+            }
+            let <Page />: Element = <main><Card title="Hi"><p>{"text"} {3}</p></Card></main>
+            let card(): Element = { <Card title="Docs" /> }
+            let items(): Element[] = { [<li />, <Card title="x" />] }
+            let wrap(child:Element) = <div>{child}</div>
+        "#,
+        "element-type.nx",
+    );
+    assert!(accepted.is_ok(), "{:?}", accepted.errors());
+    let module = accepted.lowered_module.as_ref().unwrap();
+    let page = module.find_item("Page").unwrap();
+    let nx_hir::Item::Function(page) = page else {
+        panic!("Expected Page function");
+    };
+    assert_eq!(accepted.type_of(page.body), Some(&Type::element()));
+
+    let rejected = check_str(
+        r#"
+            type User = { name:string }
+            let fromRecord(user:User): Element = { user }
+            let fromLiteral(): Element = { <User name="Ada" /> }
+            let fromText(): Element[] = { ["a", "b"] }
+            let listing(user:User) = <div>{user}</div>
+        "#,
+        "element-type.nx",
+    );
+    let messages: Vec<_> = rejected
+        .errors()
+        .iter()
+        .map(|diag| diag.message())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Return value for function 'fromRecord' expects Element, found User",
+            "Return value for function 'fromLiteral' expects Element, found User",
+            "Return value for function 'fromText' expects Element[], found string[]",
+            "User cannot be rendered as content of 'div'",
+        ]
+    );
+}
+
+#[test]
+fn test_return_type_mismatch_labels_the_offending_result() {
+    let source = r#"
+        type User = { name:string }
+        let greeting(name:string): int = { "Hello, " + name }
+        let pick(flag:bool): int = { if flag { 1 } else { "two" } }
+        let label(n:int): string = {
+            if n is {
+                1 => "one"
+                2 => n * 2
+                else => "many"
+            }
+        }
+        let <Badge new:bool />: Element = { if new { <span /> } else { <User name="Ada" /> } }
+        let ok(flag:bool): string = { if flag { "yes" } else { "no" } }
+    "#;
+    let result = check_str(source, "return-contracts.nx");
+
+    let mismatches = result
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.code() == Some("return-type-mismatch"))
+        .map(|diagnostic| {
+            let span = diagnostic.labels()[0].range;
+            (
+                diagnostic.message(),
+                &source[usize::from(span.start())..usize::from(span.end())],
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mismatches,
+        vec![
+            (
+                "Return value for function 'greeting' expects int, found string",
+                r#""Hello, " + name"#,
+            ),
+            (
+                "Return value for function 'pick' expects int, found string",
+                r#"if flag { 1 } else { "two" }"#,
+            ),
+            (
+                "Return value for function 'label' expects string, found int",
+                "n * 2",
+            ),
+            (
+                "Return value for function 'Badge' expects Element, found User",
+                r#"<User name="Ada" />"#,
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_for_over_record_binds_joined_field_type_and_string_key() {
+    let accepted = check_str(
+        r#"
+            type Size = { width:int height:int }
+            type User = { name:string age:int }
+            let doubled(): int[] = { for value in <Size width={3} height={4} /> { value * 2 } }
+            let keys(): string[] = { for value, key in <User name="Ada" age={36} /> { key } }
+        "#,
+        "for-record-accepted.nx",
+    );
+    assert!(
+        accepted.is_ok(),
+        "Expected for over records to type check, got {:?}",
+        accepted.errors()
+    );
+
+    let rejected = check_str(
+        r#"
+            type Size = { width:int height:int }
+            let keys(): int[] = { for value, key in <Size width={3} height={4} /> { key } }
+            let scalar() = { for value in 3 { value } }
+        "#,
+        "for-record-rejected.nx",
+    );
+    let messages: Vec<_> = rejected
+        .errors()
+        .iter()
+        .map(|diag| diag.message().to_string())
+        .collect();
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("expects int[], found string[]")),
+        "Expected record keys to be strings, got {:?}",
+        messages
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|message| message.contains("must be an array or record, found int")),
+        "Expected a scalar iterable to be rejected, got {:?}",
+        messages
+    );
+}
+
+#[test]
+fn test_context_keys_are_typed_by_their_top_level_value() {
+    let accepted = check_str(
+        r#"
+            let theme: string = "light"
+            let <Badge label:string /> = <span class={use theme}>{label}</span>
+            let shout(): string = { use theme + "!" }
+            let root() = { provide theme = "dark" { <Badge label="Save" /> } }
+        "#,
+        "context-accepted.nx",
+    );
+    assert!(
+        accepted.is_ok(),
+        "Expected provide/use of a top-level value to type check, got {:?}",
+        accepted.errors()
+    );
+
+    let rejected = check_str(
+        r#"
+            let theme: string = "light"
+            let wrong() = { provide theme = 3 { use theme } }
+            let missing() = { use colour }
+        "#,
+        "context-rejected.nx",
+    );
+    let codes: Vec<_> = rejected
+        .errors()
+        .iter()
+        .filter_map(|diag| diag.code())
+        .collect();
+    assert!(
+        codes.contains(&"context-type-mismatch"),
+        "Expected context-type-mismatch for a provided int, got {:?}",
+        rejected.errors()
+    );
+    assert!(
+        codes.contains(&"unknown-context"),
+        "Expected unknown-context for an undeclared key, got {:?}",
+        rejected.errors()
+    );
+}
+
+#[test]
+fn test_expected_types_flow_into_empty_arrays_and_null() {
+    let result = check_str(
+        r#"
+            type User = { name:string tags:string[] nickname:string? }
+            let take(values: int[]): int = { 0 }
+            let empty: int[] = []
+            let count: int = {take([])}
+            let nickname: string? = null
+            let maybe: string?[] = [null, "a"]
+            let user: User = <User name="a" tags=[] nickname=null />
+            let missing: int = null
+        "#,
+        "expected-types.nx",
+    );
+
+    let messages: Vec<_> = result
+        .errors()
+        .iter()
+        .map(|diag| diag.message().to_string())
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["Initializer for value 'missing' expects int, found int?"]
+    );
+
+    let module = result.lowered_module.as_ref().expect("lowered module");
+    let initializer_type = |name: &str| {
+        module
+            .items()
+            .iter()
+            .find_map(|item| match item {
+                nx_hir::Item::Value(value) if value.name.as_str() == name => Some(value.value),
+                _ => None,
+            })
+            .and_then(|expr| result.type_of(expr))
+            .map(ToString::to_string)
+    };
+    assert_eq!(initializer_type("empty").as_deref(), Some("int[]"));
+    assert_eq!(initializer_type("nickname").as_deref(), Some("string?"));
+    assert_eq!(initializer_type("maybe").as_deref(), Some("string?[]"));
+}
+
+#[test]
+fn test_type_variables_are_solved_or_defaulted_before_reporting() {
+    let result = check_str(
+        r#"
+            let take(values: int[]): int = { 0 }
+            let xs = []
+            let count: int = {take(xs)}
+            let unused = []
+            let nothing = null
+            let wrong: string = {[[]]}
+        "#,
+        "type-variables.nx",
+    );
 
-    let result = check_str(source, "union-match-non-exhaustive.nx");
-    let errors = result.errors();
-    assert!(
-        errors
-            .iter()
-            .any(|diag| diag.code() == Some("non-exhaustive-union-match")),
-        "Expected non-exhaustive-union-match diagnostic, got {:?}",
-        errors
+    let messages: Vec<_> = result
+        .errors()
+        .iter()
+        .map(|diag| diag.message().to_string())
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["Initializer for value 'wrong' expects string, found list object[][]"]
     );
+
+    let module = result.lowered_module.as_ref().expect("lowered module");
+    let initializer_type = |name: &str| {
+        module
+            .items()
+            .iter()
+            .find_map(|item| match item {
+                nx_hir::Item::Value(value) if value.name.as_str() == name => Some(value.value),
+                _ => None,
+            })
+            .and_then(|expr| result.type_of(expr))
+            .map(ToString::to_string)
+    };
+    assert_eq!(initializer_type("xs").as_deref(), Some("int[]"));
+    assert_eq!(initializer_type("unused").as_deref(), Some("object[]"));
+    assert_eq!(initializer_type("nothing").as_deref(), Some("object?"));
 }
 
 #[test]
-fn test_union_match_else_allows_partial_coverage() {
-    let source = r#"
-        type LoadState = | idle | failed { message:string }
-        let view(state: LoadState): string = {
-            if state is {
-                LoadState.idle => ""
-                else => "fallback"
-            }
-        }
-    "#;
+fn test_nested_type_mismatch_notes_the_differing_part() {
+    let result = check_str(
+        r#"
+            let add(a:int, b:int): int = { a + b }
+            let handlers: int[] = {add(_, 1) add(_, 2)}
+            let count: int = 3
+            let label: string = {count}
+        "#,
+        "nested-mismatch.nx",
+    );
 
-    let result = check_str(source, "union-match-else.nx");
-    assert!(
-        result.is_ok(),
-        "Expected else arm to allow partial union match coverage, got {:?}",
-        result.errors()
+    let notes: Vec<_> = result
+        .errors()
+        .iter()
+        .map(|diag| (diag.message().to_string(), diag.note().map(str::to_string)))
+        .collect();
+    assert_eq!(
+        notes,
+        vec![
+            (
+                "Initializer for value 'handlers' expects int[], found ((int) => int)[]"
+                    .to_string(),
+                Some("element of array type: expected int, found (int) => int".to_string()),
+            ),
+            (
+                "Initializer for value 'label' expects string, found int".to_string(),
+                None,
+            ),
+        ]
     );
 }
 
 #[test]
-fn test_union_match_rejects_pattern_from_other_union() {
-    let source = r#"
-        type LoadState = | idle
-        type SaveState = | idle
-        let view(state: LoadState): string = {
-            if state is {
-                SaveState.idle => ""
-                else => "fallback"
-            }
-        }
-    "#;
-
-    let result = check_str(source, "union-match-wrong-union.nx");
-    let errors = result.errors();
+fn test_module_metadata_is_validated_against_its_schema() {
+    let accepted = check_str(
+        r#"
+            meta TemplateInfo { title: "Invoice", version: 2, tags: ["billing"] }
+            type TemplateInfo = { title:string version:int tags:string[] draft:bool = false }
+            let root() = <p />
+        "#,
+        "metadata-accepted.nx",
+    );
     assert!(
-        errors
-            .iter()
-            .any(|diag| diag.code() == Some("wrong-union-pattern")),
-        "Expected wrong-union-pattern diagnostic, got {:?}",
-        errors
+        accepted.is_ok(),
+        "Expected metadata matching its schema to type check, got {:?}",
+        accepted.errors()
     );
-}
 
-#[test]
-fn test_property_fragment_required_property_must_be_on_every_path() {
+    let rejected = check_str(
+        r#"
+            meta TemplateInfo { title: 3, author: "me", tags: [], tags: ["x"] }
+            type TemplateInfo = { title:string version:int tags:string[] }
+            let root() = <p />
+        "#,
+        "metadata-rejected.nx",
+    );
+    let messages: Vec<_> = rejected
+        .errors()
+        .iter()
+        .map(|diag| (diag.code().unwrap_or_default(), diag.message().to_string()))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (
+                "duplicate-property",
+                "Metadata field 'tags' is supplied more than once".to_string()
+            ),
+            (
+                "metadata-type-mismatch",
+                "Metadata field 'title' expects string, found int".to_string()
+            ),
+            (
+                "unknown-metadata-field",
+                "Metadata schema 'TemplateInfo' has no field 'author'".to_string()
+            ),
+            (
+                "missing-property",
+                "Metadata schema 'TemplateInfo' requires field 'version'".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_partial_application_types_the_open_parameters() {
     let accepted = check_str(
         r#"
-            let <Button label:string /> = <button>{label}</button>
-            let <Main primary:bool /> = <Button if primary { label="Save" } else { label="Cancel" } />
+            let add(a:int, b:int): int = { a + b }
+            let addTen = { add(_, 10) }
+            let twelve(): int = { addTen(2) }
         "#,
-        "property-fragment-required-accepted.nx",
+        "partial-accepted.nx",
     );
     assert!(
         accepted.is_ok(),
-        "Expected every-branch required property to type check, got {:?}",
+        "Expected partial application to type check, got {:?}",
         accepted.errors()
     );
 
     let rejected = check_str(
         r#"
-            let <Button label:string /> = <button>{label}</button>
-            let <Main primary:bool /> = <Button if primary { label="Save" } />
+            let add(a:int, b:int): int = { a + b }
+            let addTen = { add(_, 10) }
+            let wrongArg(): int = { addTen("2") }
+            let wrongBound = { add(_, "10") }
+            let shout = { upper(_) }
         "#,
-        "property-fragment-required-rejected.nx",
+        "partial-rejected.nx",
+    );
+    let messages: Vec<_> = rejected
+        .errors()
+        .iter()
+        .map(|diag| diag.message().to_string())
+        .collect();
+    assert!(
+        messages.contains(&"Argument 0 expects int, found string".to_string()),
+        "Expected the open parameter to keep its type, got {:?}",
+        messages
+    );
+    assert!(
+        messages.contains(&"Argument 1 expects int, found string".to_string()),
+        "Expected supplied arguments to be checked, got {:?}",
+        messages
     );
     assert!(
         rejected
             .errors()
             .iter()
-            .any(|diag| diag.code() == Some("missing-property")),
-        "Expected missing-property diagnostic, got {:?}",
-        rejected.errors()
+            .any(|diag| diag.code() == Some("partial-application")),
+        "Expected builtins to reject placeholders, got {:?}",
+        messages
+    );
+}
+
+#[test]
+fn test_destructured_bindings_take_element_and_field_types() {
+    let accepted = check_str(
+        r#"
+            type User = { name:string age:int }
+            let scores: int[] = {3 5 8}
+            let [first, ...others] = {scores}
+            let { name, age } = <User name="Ada" age={36} />
+            let total(): int = { first + age }
+            let rest(): int[] = { others }
+            let greeting(): string = { name }
+        "#,
+        "destructure-accepted.nx",
+    );
+    assert!(
+        accepted.is_ok(),
+        "Expected destructured bindings to type check, got {:?}",
+        accepted.errors()
+    );
+
+    let rejected = check_str(
+        r#"
+            type User = { name:string age:int }
+            let scores: int[] = {3 5 8}
+            let { email } = <User name="Ada" age={36} />
+            let [user] = <User name="Ada" age={36} />
+            let { length } = {scores}
+        "#,
+        "destructure-rejected.nx",
+    );
+    let messages: Vec<_> = rejected
+        .errors()
+        .iter()
+        .filter(|diag| diag.code() == Some("destructure-mismatch"))
+        .map(|diag| diag.message().to_string())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Record 'User' has no field 'email' to destructure",
+            "Cannot destructure User with an array pattern",
+            "Cannot destructure int[] with a record pattern",
+        ]
     );
 }
 
@@ -535,6 +1663,45 @@ fn test_property_fragment_duplicates_are_path_sensitive() {
     );
 }
 
+#[test]
+fn test_duplicate_properties_label_both_occurrences() {
+    let result = check_str(
+        r#"
+            type User = { name:string age:int name:string }
+            let <Badge tone:string /> = <span>{tone}</span>
+            let badge() = <Badge tone="a" tone="b" />
+            let div() = <div id="a" id="b" />
+            let user() = <User name="a" age={1} name="b" />
+        "#,
+        "duplicate-properties.nx",
+    );
+    let errors = result.errors();
+
+    let duplicate_properties: Vec<_> = errors
+        .iter()
+        .filter(|diag| diag.code() == Some("duplicate-property"))
+        .collect();
+    assert_eq!(
+        duplicate_properties.len(),
+        3,
+        "Expected duplicate props on Badge, div and User, got {:?}",
+        errors
+    );
+    for diag in &duplicate_properties {
+        assert_eq!(diag.labels().len(), 2, "Expected both spans in {:?}", diag);
+    }
+
+    let duplicate_field = errors
+        .iter()
+        .find(|diag| diag.code() == Some("duplicate-field"))
+        .expect("Expected duplicate-field diagnostic");
+    assert_eq!(
+        duplicate_field.message(),
+        "Record 'User' declares field 'name' more than once"
+    );
+    assert_eq!(duplicate_field.labels().len(), 2);
+}
+
 #[test]
 fn test_property_fragment_content_property_rules_are_path_sensitive() {
     let accepted = check_str(
@@ -948,6 +2115,36 @@ fn test_derived_record_accepts_inherited_fields_and_rejects_unknown_record_field
     );
 }
 
+#[test]
+fn test_composed_record_accepts_included_fields_and_substitutes_for_its_parts() {
+    let source = r#"
+        type User = { name: string }
+        type Audited = { createdBy: string = "system" }
+        type Admin = User & Audited & { role: string = "admin" }
+
+        let greet(user: User): string = { user.name }
+        let audit(entry: Audited): string = { entry.createdBy }
+        let root(): string = { greet(<Admin name={"Ada"} />) }
+        let who(): string = { audit(<Admin name={"Ada"} />) }
+        let bad(): Admin = { <Admin name={"Ada"} level={1} /> }
+    "#;
+
+    let result = check_str(source, "record-composition.nx");
+    let errors: Vec<_> = result
+        .errors()
+        .iter()
+        .map(|diag| (diag.code(), diag.message().to_string()))
+        .collect();
+    assert_eq!(
+        errors.len(),
+        1,
+        "Expected only the unknown field to be reported, got {:?}",
+        errors
+    );
+    assert_eq!(errors[0].0, Some("unknown-record-field"));
+    assert!(errors[0].1.contains("level"), "got {:?}", errors);
+}
+
 #[test]
 fn test_record_inheritance_uses_shared_abstract_supertype_for_branches() {
     let source = r#"
@@ -1483,7 +2680,7 @@ fn test_concrete_record_cannot_be_used_as_base() {
         result
             .diagnostics
             .iter()
-            .any(|diag| diag.code() == Some("lowering-error")
+            .any(|diag| diag.code() == Some("record-base-not-abstract")
                 && diag
                     .message()
                     .contains("only abstract records may be extended")),
@@ -1758,6 +2955,111 @@ fn test_session_with_errors() {
     assert!(total_errors > 0);
 }
 
+#[test]
+fn test_budgeted_check_stops_inference_but_keeps_syntax_and_scope_errors() {
+    let source = r#"let wrong(): int = { "text" }
+let root() = { missing }"#;
+
+    let partial = check_str_with_budget(source, "budget.nx", Duration::ZERO);
+    assert!(!partial.complete);
+    let codes: Vec<_> = partial
+        .result
+        .all_diagnostics()
+        .iter()
+        .filter_map(|diagnostic| diagnostic.code())
+        .collect();
+    assert_eq!(codes, vec!["undefined-identifier"]);
+
+    let full = check_str_with_budget(source, "budget.nx", Duration::from_secs(60));
+    assert!(full.complete);
+    assert_eq!(
+        full.result.all_diagnostics(),
+        check_str(source, "budget.nx").all_diagnostics()
+    );
+}
+
+#[test]
+fn test_session_remove_file_drops_its_symbols() {
+    let mut session = TypeCheckSession::new();
+    session.add_file("button.nx", "let <Button /> = <button />");
+    session.add_file("app.nx", "let <App /> = <Button />");
+
+    assert_eq!(
+        session.file_source("app.nx"),
+        Some("let <App /> = <Button />")
+    );
+    assert!(session.remove_file("button.nx"));
+    assert!(!session.remove_file("button.nx"));
+    assert_eq!(session.len(), 1);
+    assert_eq!(session.file_source("button.nx"), None);
+    assert!(session.workspace_symbols("Button").is_empty());
+}
+
+#[test]
+fn test_session_snapshot_restores_indexes_and_cached_diagnostics() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+
+    let mut session = TypeCheckSession::new();
+    session.add_file("button.nx", "let <Button /> = <button />");
+    session.add_file(
+        "app.nx",
+        "let <App /> = <Button />\nlet count(): int = { \"x\" }",
+    );
+    assert_eq!(session.cache_diagnostics(), 2);
+    assert_eq!(session.cache_diagnostics(), 0);
+    session.save(&path).unwrap();
+
+    let mut restored = TypeCheckSession::load(&path).unwrap();
+    assert_eq!(restored.len(), 2);
+    assert_eq!(
+        restored.file_source("button.nx"),
+        session.file_source("button.nx")
+    );
+    assert_eq!(
+        restored.workspace_symbols("Button"),
+        session.workspace_symbols("Button")
+    );
+    assert_eq!(restored.callers_of("Button"), session.callers_of("Button"));
+    let sort = |mut diagnostics: Vec<nx_diagnostics::Diagnostic>| {
+        diagnostics.sort_by(|a, b| a.message().cmp(b.message()));
+        diagnostics
+    };
+    assert_eq!(sort(restored.diagnostics()), sort(session.diagnostics()));
+
+    // Unchanged files keep their cached results; changed ones are checked again.
+    restored.add_file("button.nx", "let <Button /> = <button />");
+    restored.add_file("app.nx", "let <App /> = <Button />");
+    assert_eq!(restored.cache_diagnostics(), 1);
+    assert!(restored
+        .diagnostics()
+        .iter()
+        .all(|diagnostic| diagnostic.code() != Some("return-type-mismatch")));
+}
+
+#[test]
+fn test_session_snapshot_rejects_edited_sources() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+
+    let mut session = TypeCheckSession::new();
+    session.add_file("button.nx", "let <Button /> = <button />");
+    session.save(&path).unwrap();
+
+    let tampered = std::fs::read_to_string(&path)
+        .unwrap()
+        .replace("<button />", "<span />");
+    std::fs::write(&path, tampered).unwrap();
+    assert!(matches!(
+        TypeCheckSession::load(&path),
+        Err(nx_types::SnapshotError::Incompatible(_))
+    ));
+    assert!(matches!(
+        TypeCheckSession::load(dir.path().join("missing.json")),
+        Err(nx_types::SnapshotError::Io(_))
+    ));
+}
+
 // ============================================================================
 // Type System Features Tests
 // ============================================================================
@@ -1902,6 +3204,95 @@ fn test_function_type_compatibility() {
     assert!(!f1.is_compatible_with(&f3));
 }
 
+// ============================================================================
+// Recursive Function Tests
+// ============================================================================
+
+fn function_return_type(result: &nx_types::TypeCheckResult, name: &str) -> Option<Type> {
+    match result.type_env.lookup(&nx_hir::Name::new(name)) {
+        Some(Type::Function { ret, .. }) => Some(ret.as_ref().clone()),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_mutually_recursive_functions_infer_return_types() {
+    let source = r#"
+        let isEven(n:int) = { if n == 0 { true } else { isOdd(n - 1) } }
+        let isOdd(n:int) = { if n == 0 { false } else { isEven(n - 1) } }
+        let check() = { isEven(4) && !isOdd(4) }
+    "#;
+
+    let result = check_str(source, "parity.nx");
+
+    assert!(result.is_ok(), "Unexpected errors: {:?}", result.errors());
+    assert_eq!(function_return_type(&result, "isEven"), Some(Type::bool()));
+    assert_eq!(function_return_type(&result, "isOdd"), Some(Type::bool()));
+    assert_eq!(function_return_type(&result, "check"), Some(Type::bool()));
+}
+
+#[test]
+fn test_self_recursive_function_infers_return_type_from_base_case() {
+    let source = r#"
+        let depth(n:int) = { if n <= 0 { 0 } else { depth(n - 1) + 1 } }
+    "#;
+
+    let result = check_str(source, "depth.nx");
+
+    assert!(result.is_ok(), "Unexpected errors: {:?}", result.errors());
+    assert_eq!(function_return_type(&result, "depth"), Some(Type::int()));
+}
+
+#[test]
+fn test_caller_declared_before_callee_sees_inferred_return_type() {
+    let source = r#"
+        let label() = { "count: " + count() }
+        let count() = { "3" }
+    "#;
+
+    let result = check_str(source, "order.nx");
+
+    assert!(result.is_ok(), "Unexpected errors: {:?}", result.errors());
+    assert_eq!(function_return_type(&result, "label"), Some(Type::string()));
+}
+
+#[test]
+fn test_recursive_return_type_mismatch_is_reported_once() {
+    let source = r#"
+        let ping(n:int) = { if n == 0 { 1 } else { pong(n - 1) } }
+        let pong(n:int) = { if n == 0 { 2 } else { !ping(n - 1) } }
+    "#;
+
+    let result = check_str(source, "pingpong.nx");
+    let errors = result.errors();
+
+    assert_eq!(errors.len(), 1, "Expected one error, got: {:?}", errors);
+    assert!(errors[0]
+        .message()
+        .contains("Logical NOT requires bool, found int"));
+}
+
+#[test]
+fn test_recursion_without_base_case_requires_annotation() {
+    let source = r#"
+        let forever(n:int) = { forever(n + 1) }
+    "#;
+
+    let result = check_str(source, "forever.nx");
+    let errors = result.errors();
+
+    assert_eq!(errors.len(), 1, "Expected one error, got: {:?}", errors);
+    assert_eq!(errors[0].code(), Some("unresolved-return-type"));
+    assert!(errors[0].message().contains("'forever'"));
+
+    let annotated = check_str("let forever(n:int): int = { forever(n + 1) }", "forever.nx");
+    assert!(
+        annotated.is_ok(),
+        "Unexpected errors: {:?}",
+        annotated.errors()
+    );
+}
+
 // ============================================================================
 // Error Recovery Tests
 // ============================================================================
@@ -1974,3 +3365,92 @@ fn test_readme_example() {
     let result = check_str(source, "readme.nx");
     assert!(result.lowered_module.is_some());
 }
+
+#[test]
+fn test_unknown_property_suggests_declared_prop_with_fix() {
+    let source = r#"let <Swatch color:string /> = <div>{color}</div>
+let root() = <Swatch colour="red" />
+"#;
+
+    let result = check_str(source, "swatch.nx");
+    let diagnostic = result
+        .errors()
+        .into_iter()
+        .find(|diag| diag.code() == Some("unknown-property"))
+        .expect("Expected unknown-property diagnostic");
+    assert_eq!(diagnostic.help(), Some("Did you mean `color`?"));
+
+    let fix = diagnostic.fixes().first().expect("Expected a quick-fix");
+    assert_eq!(fix.message, "Replace `colour` with `color`");
+    let edit = &fix.edits[0];
+    let start: usize = edit.range.start().into();
+    let end: usize = edit.range.end().into();
+    assert_eq!(&source[start..end], "colour");
+    assert_eq!(edit.replacement, "color");
+    assert_eq!(edit.file, "swatch.nx");
+}
+
+#[test]
+fn test_diagnostic_explanation_examples() {
+    for explanation in nx_diagnostics::explanations() {
+        let codes = |source: &str| {
+            check_str(source, "example.nx")
+                .all_diagnostics()
+                .iter()
+                .filter_map(|diag| diag.code().map(str::to_string))
+                .collect::<Vec<_>>()
+        };
+        let wrong = codes(explanation.wrong);
+        assert!(
+            wrong.iter().any(|code| code == explanation.code),
+            "the example of {} reports {:?}",
+            explanation.code,
+            wrong
+        );
+        let right = codes(explanation.right);
+        assert!(
+            right.is_empty(),
+            "the corrected example of {} reports {:?}",
+            explanation.code,
+            right
+        );
+    }
+}
+
+#[test]
+fn test_module_graph_prepared_module_checks_calls_into_imported_functions() {
+    let mut graph = nx_hir::ModuleGraph::new();
+    for (identity, source) in [
+        (
+            "app/main.nx",
+            r#"import { double } from "./math"
+let root() = { double(21) }
+let label() = { double("twenty") }"#,
+        ),
+        ("app/math.nx", "export let double(x: int) = { x * 2 }"),
+    ] {
+        let module = nx_hir::lower_source_module(source, identity).expect("module should lower");
+        graph.add_module(identity, module);
+    }
+
+    let prepared = graph
+        .prepare("app/main.nx")
+        .expect("module should be prepared");
+    let artifact = analyze_prepared_module("app/main.nx", prepared, Vec::new());
+    let errors = artifact.errors();
+
+    assert!(
+        !errors
+            .iter()
+            .any(|diagnostic| diagnostic.code() == Some("undefined-identifier")),
+        "imported function should resolve, got {:?}",
+        errors
+    );
+    assert_eq!(
+        errors.len(),
+        1,
+        "only the string argument should be rejected, got {:?}",
+        errors
+    );
+    assert_eq!(errors[0].code(), Some("type-mismatch"));
+}
@@ -0,0 +1,95 @@
+//! Element attribute coercion.
+//!
+//! Element attributes keep their authored types through evaluation: `<div tabindex={3}
+//! hidden={true} />` produces a record whose `tabindex` is [`NxValue::Int`] and whose `hidden` is
+//! [`NxValue::Bool`]. Values become text only when a renderer writes markup, using these rules:
+//!
+//! - strings are written unchanged;
+//! - integers and floats are written in decimal (`3`, `0.5`, `-2`);
+//! - booleans are written as `true`/`false`, or as a bare attribute that is omitted when false
+//!   under [`BooleanAttributes::Presence`];
+//! - `null` omits the attribute;
+//! - arrays and records are not attribute values; renderers write them as children.
+
+use crate::NxValue;
+
+/// How a renderer writes boolean attribute values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BooleanAttributes {
+    /// Write `true` or `false` as text, as in `hidden="true"`.
+    #[default]
+    Text,
+    /// Write `true` as a bare attribute and omit `false`, as HTML boolean attributes do.
+    Presence,
+}
+
+/// The rendered form of one attribute value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeText {
+    /// Write the attribute with this text value.
+    Text(String),
+    /// Write the attribute name alone.
+    Present,
+    /// Leave the attribute out.
+    Omitted,
+}
+
+impl NxValue {
+    /// Coerces a scalar value to its attribute text, or returns `None` for arrays and records.
+    pub fn attribute_text(&self, booleans: BooleanAttributes) -> Option<AttributeText> {
+        let text = match self {
+            NxValue::Null => return Some(AttributeText::Omitted),
+            NxValue::Bool(value) => {
+                return Some(match (booleans, value) {
+                    (BooleanAttributes::Text, _) => AttributeText::Text(value.to_string()),
+                    (BooleanAttributes::Presence, true) => AttributeText::Present,
+                    (BooleanAttributes::Presence, false) => AttributeText::Omitted,
+                })
+            }
+            NxValue::Int32(value) => value.to_string(),
+            NxValue::Int(value) => value.to_string(),
+            NxValue::Float32(value) => value.to_string(),
+            NxValue::Float(value) => value.to_string(),
+            NxValue::String(value) => value.clone(),
+            NxValue::Array(_) | NxValue::Record { .. } => return None,
+        };
+        Some(AttributeText::Text(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalars_coerce_to_text_and_null_is_omitted() {
+        let text = |value: NxValue| value.attribute_text(BooleanAttributes::Text);
+
+        assert_eq!(
+            text(NxValue::Int(3)),
+            Some(AttributeText::Text("3".to_string()))
+        );
+        assert_eq!(
+            text(NxValue::Float(0.5)),
+            Some(AttributeText::Text("0.5".to_string()))
+        );
+        assert_eq!(
+            text(NxValue::Bool(true)),
+            Some(AttributeText::Text("true".to_string()))
+        );
+        assert_eq!(text(NxValue::Null), Some(AttributeText::Omitted));
+        assert_eq!(text(NxValue::Array(Vec::new())), None);
+    }
+
+    #[test]
+    fn presence_booleans_write_bare_or_omit() {
+        assert_eq!(
+            NxValue::Bool(true).attribute_text(BooleanAttributes::Presence),
+            Some(AttributeText::Present)
+        );
+        assert_eq!(
+            NxValue::Bool(false).attribute_text(BooleanAttributes::Presence),
+            Some(AttributeText::Omitted)
+        );
+    }
+}
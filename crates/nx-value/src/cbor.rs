@@ -0,0 +1,467 @@
+//! CBOR (RFC 8949) encoding of values.
+//!
+//! Records are maps with text keys; a record's type name is written as a `"$type"` entry before
+//! its properties, exactly as in JSON and MessagePack. Integers use the shortest CBOR encoding, so
+//! `Int32` values decode as `Int`. Floats keep their width: `Float32` is written as a
+//! single-precision float and decodes back to `Float32`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::NxValue;
+
+/// Deepest nesting of arrays, maps and tags that [`NxValue::from_cbor_slice`] accepts.
+const MAX_DEPTH: usize = 512;
+
+/// Error returned by [`NxValue::from_cbor_slice`] for malformed or unsupported input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CborError {
+    offset: usize,
+    message: String,
+}
+
+impl CborError {
+    /// Byte offset in the input where decoding failed.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Description of the failure, without the offset.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for CborError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for CborError {}
+
+impl NxValue {
+    /// Serialize a value to CBOR bytes.
+    pub fn to_cbor_vec(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        encode(self, &mut out);
+        out
+    }
+
+    /// Deserialize a value from CBOR bytes.
+    ///
+    /// Tags are ignored in favour of the value they wrap. Byte strings, and map keys other than
+    /// text strings, have no NX representation and are rejected.
+    pub fn from_cbor_slice(bytes: &[u8]) -> Result<Self, CborError> {
+        let mut decoder = Decoder { bytes, offset: 0 };
+        let value = decoder.value(0)?;
+        if decoder.offset != bytes.len() {
+            return Err(decoder.error("trailing bytes after value"));
+        }
+        Ok(value)
+    }
+}
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+/// Additional-information value of an indefinite-length item, and of the break that ends it.
+const INDEFINITE: u8 = 31;
+
+fn encode(value: &NxValue, out: &mut Vec<u8>) {
+    match value {
+        NxValue::Null => out.push(0xf6),
+        NxValue::Bool(false) => out.push(0xf4),
+        NxValue::Bool(true) => out.push(0xf5),
+        NxValue::Int32(value) => encode_int(i64::from(*value), out),
+        NxValue::Int(value) => encode_int(*value, out),
+        NxValue::Float32(value) => {
+            out.push(0xfa);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        NxValue::Float(value) => {
+            out.push(0xfb);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        NxValue::String(text) => encode_text(text, out),
+        NxValue::Array(items) => {
+            encode_head(ARRAY, items.len() as u64, out);
+            for item in items {
+                encode(item, out);
+            }
+        }
+        NxValue::Record {
+            type_name,
+            properties,
+        } => {
+            let len = properties.len() + usize::from(type_name.is_some());
+            encode_head(MAP, len as u64, out);
+            if let Some(type_name) = type_name {
+                encode_text("$type", out);
+                encode_text(type_name, out);
+            }
+            for (key, value) in properties {
+                encode_text(key, out);
+                encode(value, out);
+            }
+        }
+    }
+}
+
+fn encode_int(value: i64, out: &mut Vec<u8>) {
+    if value >= 0 {
+        encode_head(UNSIGNED, value as u64, out);
+    } else {
+        // CBOR stores a negative integer `n` as `-1 - n`, which is `!n` in two's complement.
+        encode_head(NEGATIVE, !value as u64, out);
+    }
+}
+
+fn encode_text(text: &str, out: &mut Vec<u8>) {
+    encode_head(TEXT, text.len() as u64, out);
+    out.extend_from_slice(text.as_bytes());
+}
+
+/// Writes an item head with the shortest encoding of `argument`.
+fn encode_head(major: u8, argument: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    if argument < 24 {
+        out.push(major | argument as u8);
+    } else if let Ok(argument) = u8::try_from(argument) {
+        out.extend_from_slice(&[major | 24, argument]);
+    } else if let Ok(argument) = u16::try_from(argument) {
+        out.push(major | 25);
+        out.extend_from_slice(&argument.to_be_bytes());
+    } else if let Ok(argument) = u32::try_from(argument) {
+        out.push(major | 26);
+        out.extend_from_slice(&argument.to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&argument.to_be_bytes());
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+/// The head of a data item: its major type and additional information, and the argument that
+/// follows for definite lengths and integers.
+struct Head {
+    major: u8,
+    info: u8,
+    argument: u64,
+}
+
+impl Decoder<'_> {
+    fn error(&self, message: impl Into<String>) -> CborError {
+        CborError {
+            offset: self.offset,
+            message: message.into(),
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], CborError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        let bytes = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], CborError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn head(&mut self) -> Result<Head, CborError> {
+        let [initial] = self.take_array()?;
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+        let argument = match info {
+            0..=23 => u64::from(info),
+            24 => u64::from(u8::from_be_bytes(self.take_array()?)),
+            25 => u64::from(u16::from_be_bytes(self.take_array()?)),
+            26 => u64::from(u32::from_be_bytes(self.take_array()?)),
+            27 => u64::from_be_bytes(self.take_array()?),
+            INDEFINITE if matches!(major, BYTES | TEXT | ARRAY | MAP | SIMPLE) => 0,
+            _ => {
+                self.offset -= 1;
+                return Err(self.error(format!("invalid additional information {info}")));
+            }
+        };
+        Ok(Head {
+            major,
+            info,
+            argument,
+        })
+    }
+
+    /// Returns true, consuming it, if the next byte is the break that ends an indefinite item.
+    fn at_break(&mut self) -> bool {
+        let at_break = self.bytes.get(self.offset) == Some(&0xff);
+        if at_break {
+            self.offset += 1;
+        }
+        at_break
+    }
+
+    /// Returns the number of items a definite-length array or map declares, refusing lengths the
+    /// remaining input could not possibly hold.
+    fn length(&self, head: &Head) -> Result<usize, CborError> {
+        usize::try_from(head.argument)
+            .ok()
+            .filter(|&len| len <= self.bytes.len() - self.offset)
+            .ok_or_else(|| self.error(format!("length {} exceeds the input", head.argument)))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<NxValue, CborError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error(format!("nesting deeper than {MAX_DEPTH} levels")));
+        }
+        let start = self.offset;
+        let head = self.head()?;
+        match head.major {
+            UNSIGNED => Ok(match i64::try_from(head.argument) {
+                Ok(value) => NxValue::Int(value),
+                Err(_) => NxValue::Float(head.argument as f64),
+            }),
+            NEGATIVE => Ok(match i64::try_from(head.argument) {
+                Ok(value) => NxValue::Int(!value),
+                Err(_) => NxValue::Float(-1.0 - head.argument as f64),
+            }),
+            BYTES => {
+                self.offset = start;
+                Err(self.error("byte strings have no NX value"))
+            }
+            TEXT => self.text(&head).map(NxValue::String),
+            ARRAY => {
+                let mut items = Vec::new();
+                if head.info == INDEFINITE {
+                    while !self.at_break() {
+                        items.push(self.value(depth + 1)?);
+                    }
+                } else {
+                    let len = self.length(&head)?;
+                    items.reserve(len);
+                    for _ in 0..len {
+                        items.push(self.value(depth + 1)?);
+                    }
+                }
+                Ok(NxValue::Array(items))
+            }
+            MAP => self.record(&head, depth),
+            TAG => self.value(depth + 1),
+            _ => self.simple(&head, start),
+        }
+    }
+
+    fn text(&mut self, head: &Head) -> Result<String, CborError> {
+        if head.info != INDEFINITE {
+            let len = usize::try_from(head.argument)
+                .map_err(|_| self.error("text string longer than the input"))?;
+            let start = self.offset;
+            let bytes = self.take(len)?.to_vec();
+            return String::from_utf8(bytes).map_err(|_| CborError {
+                offset: start,
+                message: "text string is not valid UTF-8".to_string(),
+            });
+        }
+        // An indefinite-length string is a series of definite-length chunks.
+        let mut text = String::new();
+        while !self.at_break() {
+            let chunk = self.head()?;
+            if chunk.major != TEXT || chunk.info == INDEFINITE {
+                return Err(self.error("indefinite-length text string holds a non-text chunk"));
+            }
+            text.push_str(&self.text(&chunk)?);
+        }
+        Ok(text)
+    }
+
+    fn record(&mut self, head: &Head, depth: usize) -> Result<NxValue, CborError> {
+        let mut type_name = None;
+        let mut properties = BTreeMap::new();
+        let len = if head.info == INDEFINITE {
+            None
+        } else {
+            Some(self.length(head)?)
+        };
+        let mut read = 0;
+        while len.map_or(!self.at_break(), |len| read < len) {
+            read += 1;
+            let key_offset = self.offset;
+            let key = self.head()?;
+            if key.major != TEXT {
+                self.offset = key_offset;
+                return Err(self.error("map key is not a text string"));
+            }
+            let key = self.text(&key)?;
+            let value_offset = self.offset;
+            let value = self.value(depth + 1)?;
+            if key != "$type" {
+                properties.insert(key, value);
+                continue;
+            }
+            match value {
+                NxValue::String(name) => type_name = Some(name),
+                other => {
+                    return Err(CborError {
+                        offset: value_offset,
+                        message: format!("expected \"$type\" to be a string, got {other:?}"),
+                    })
+                }
+            }
+        }
+        Ok(NxValue::Record {
+            type_name,
+            properties,
+        })
+    }
+
+    fn simple(&mut self, head: &Head, start: usize) -> Result<NxValue, CborError> {
+        let value = match (head.info, head.argument) {
+            (20, _) => NxValue::Bool(false),
+            (21, _) => NxValue::Bool(true),
+            (22 | 23, _) => NxValue::Null,
+            (25, bits) => NxValue::Float(f16_to_f64(bits as u16)),
+            (26, bits) => NxValue::Float32(f32::from_bits(bits as u32)),
+            (27, bits) => NxValue::Float(f64::from_bits(bits)),
+            (INDEFINITE, _) => {
+                self.offset = start;
+                return Err(self.error("unexpected break"));
+            }
+            (_, simple) => {
+                self.offset = start;
+                return Err(self.error(format!("simple value {simple} has no NX value")));
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Widens an IEEE 754 half-precision float, which CBOR encoders may use for small floats.
+fn f16_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let fraction = f64::from(bits & 0x3ff);
+    sign * match exponent {
+        0 => fraction * 2f64.powi(-24),
+        31 if fraction == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(type_name: Option<&str>, properties: &[(&str, NxValue)]) -> NxValue {
+        NxValue::Record {
+            type_name: type_name.map(str::to_string),
+            properties: properties
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn round_trips_every_kind_of_value() {
+        let value = record(
+            Some("User"),
+            &[
+                ("name", NxValue::String("Ada ✓".to_string())),
+                ("age", NxValue::Int(-4_000_000_000)),
+                ("score", NxValue::Float(0.1)),
+                ("ratio", NxValue::Float32(1.5)),
+                ("active", NxValue::Bool(true)),
+                ("manager", NxValue::Null),
+                (
+                    "tags",
+                    NxValue::Array(vec![record(None, &[]), NxValue::Int(23), NxValue::Int(24)]),
+                ),
+            ],
+        );
+
+        let bytes = value.to_cbor_vec();
+        assert_eq!(NxValue::from_cbor_slice(&bytes), Ok(value));
+        assert_eq!(
+            NxValue::from_cbor_slice(&NxValue::Int32(-1).to_cbor_vec()),
+            Ok(NxValue::Int(-1))
+        );
+    }
+
+    #[test]
+    fn writes_the_type_name_first() {
+        let value = record(Some("A"), &[("$a", NxValue::Int(1))]);
+        assert_eq!(
+            value.to_cbor_vec(),
+            [0xa2, 0x65, b'$', b't', b'y', b'p', b'e', 0x61, b'A', 0x62, b'$', b'a', 0x01]
+        );
+    }
+
+    #[test]
+    fn reads_indefinite_lengths_half_floats_and_tags() {
+        // {_ "a": [_ 1.5 (half), "x" "y" (chunked)], "b": 0("2024")}
+        let bytes = [
+            0xbf, 0x61, b'a', 0x9f, 0xf9, 0x3e, 0x00, 0x7f, 0x61, b'x', 0x61, b'y', 0xff, 0xff,
+            0x61, b'b', 0xc0, 0x64, b'2', b'0', b'2', b'4', 0xff,
+        ];
+        assert_eq!(
+            NxValue::from_cbor_slice(&bytes),
+            Ok(record(
+                None,
+                &[
+                    (
+                        "a",
+                        NxValue::Array(vec![
+                            NxValue::Float(1.5),
+                            NxValue::String("xy".to_string())
+                        ])
+                    ),
+                    ("b", NxValue::String("2024".to_string())),
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_input_without_an_nx_value() {
+        let error = |bytes: &[u8]| NxValue::from_cbor_slice(bytes).unwrap_err().to_string();
+        assert_eq!(
+            error(&[0x42, 1, 2]),
+            "byte strings have no NX value at byte 0"
+        );
+        assert_eq!(
+            error(&[0xa1, 0x01, 0x02]),
+            "map key is not a text string at byte 1"
+        );
+        assert_eq!(
+            error(&[0xa1, 0x65, b'$', b't', b'y', b'p', b'e', 0x01]),
+            "expected \"$type\" to be a string, got Int(1) at byte 7"
+        );
+        assert_eq!(
+            error(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+            "length 18446744073709551615 exceeds the input at byte 9"
+        );
+        assert_eq!(error(&[0x01, 0x02]), "trailing bytes after value at byte 1");
+        assert_eq!(error(&[0x62, b'a']), "unexpected end of input at byte 1");
+        assert_eq!(
+            error(&[0x81; MAX_DEPTH + 2]),
+            "nesting deeper than 512 levels at byte 513"
+        );
+    }
+}
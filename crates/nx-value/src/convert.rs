@@ -0,0 +1,992 @@
+//! Converting Rust types to and from [`NxValue`] through their serde implementations.
+//!
+//! [`NxValue::from_serialize`] builds the value that parsing the type's JSON form would produce,
+//! without writing the JSON: integers become [`NxValue::Int`], floats [`NxValue::Float`], structs
+//! and maps records, and unit enum variants bare member strings. Other enum variants use serde's
+//! default externally tagged form, a record with the variant name as its only property. A
+//! `"$type"` string property becomes the record's type name, so `#[serde(tag = "$type")]` on an
+//! enum produces typed records.
+//!
+//! [`NxValue::deserialize_into`] reads a value back into any type implementing
+//! [`DeserializeOwned`], accepting every number width for numeric fields.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::NxValue;
+
+impl NxValue {
+    /// Converts any serializable value into an [`NxValue`].
+    pub fn from_serialize<T: Serialize + ?Sized>(value: &T) -> Result<Self, NxValueSerdeError> {
+        value.serialize(ValueSerializer)
+    }
+
+    /// Converts this value into any deserializable Rust type.
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> Result<T, NxValueSerdeError> {
+        T::deserialize(self)
+    }
+}
+
+/// Error converting between [`NxValue`] and a Rust type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NxValueSerdeError {
+    message: String,
+}
+
+impl NxValueSerdeError {
+    /// Returns the description of what could not be converted.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for NxValueSerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for NxValueSerdeError {}
+
+impl ser::Error for NxValueSerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self {
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl de::Error for NxValueSerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self {
+            message: msg.to_string(),
+        }
+    }
+}
+
+fn record(properties: BTreeMap<String, NxValue>) -> NxValue {
+    let mut properties = properties;
+    let type_name = match properties.remove("$type") {
+        Some(NxValue::String(name)) => Some(name),
+        Some(other) => {
+            properties.insert("$type".to_string(), other);
+            None
+        }
+        None => None,
+    };
+    NxValue::Record {
+        type_name,
+        properties,
+    }
+}
+
+fn tagged(variant: &str, value: NxValue) -> NxValue {
+    NxValue::Record {
+        type_name: None,
+        properties: BTreeMap::from([(variant.to_string(), value)]),
+    }
+}
+
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = NxValue;
+    type Error = NxValueSerdeError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Int(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Int(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Int(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Int(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Int(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Int(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Int(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<NxValue, NxValueSerdeError> {
+        // Same as the JSON reader: integers past i64 keep their magnitude as a float.
+        Ok(match i64::try_from(v) {
+            Ok(v) => NxValue::Int(v),
+            Err(_) => NxValue::Float(v as f64),
+        })
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Float(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Array(
+            v.iter().map(|byte| NxValue::Int((*byte).into())).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+    ) -> Result<NxValue, NxValueSerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<NxValue, NxValueSerdeError> {
+        Ok(NxValue::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<NxValue, NxValueSerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<NxValue, NxValueSerdeError> {
+        Ok(tagged(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, NxValueSerdeError> {
+        Ok(SeqSerializer {
+            variant: None,
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, NxValueSerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, NxValueSerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, NxValueSerdeError> {
+        Ok(SeqSerializer {
+            variant: Some(variant),
+            elements: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSerializer, NxValueSerdeError> {
+        Ok(MapSerializer {
+            variant: None,
+            properties: BTreeMap::new(),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<MapSerializer, NxValueSerdeError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<MapSerializer, NxValueSerdeError> {
+        Ok(MapSerializer {
+            variant: Some(variant),
+            properties: BTreeMap::new(),
+            next_key: None,
+        })
+    }
+}
+
+struct SeqSerializer {
+    /// Variant name when serializing a tuple variant
+    variant: Option<&'static str>,
+    elements: Vec<NxValue>,
+}
+
+impl SeqSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NxValueSerdeError> {
+        self.elements.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> NxValue {
+        let array = NxValue::Array(self.elements);
+        match self.variant {
+            Some(variant) => tagged(variant, array),
+            None => array,
+        }
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = NxValue;
+    type Error = NxValueSerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), NxValueSerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = NxValue;
+    type Error = NxValueSerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), NxValueSerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = NxValue;
+    type Error = NxValueSerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), NxValueSerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = NxValue;
+    type Error = NxValueSerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), NxValueSerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(self.finish())
+    }
+}
+
+struct MapSerializer {
+    /// Variant name when serializing a struct variant
+    variant: Option<&'static str>,
+    properties: BTreeMap<String, NxValue>,
+    next_key: Option<String>,
+}
+
+impl MapSerializer {
+    fn insert<T: Serialize + ?Sized>(
+        &mut self,
+        key: String,
+        value: &T,
+    ) -> Result<(), NxValueSerdeError> {
+        self.properties
+            .insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> NxValue {
+        match self.variant {
+            Some(variant) => tagged(variant, record(self.properties)),
+            None => record(self.properties),
+        }
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = NxValue;
+    type Error = NxValueSerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), NxValueSerdeError> {
+        self.next_key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), NxValueSerdeError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ser::Error::custom("map value serialized before its key"))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = NxValue;
+    type Error = NxValueSerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), NxValueSerdeError> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for MapSerializer {
+    type Ok = NxValue;
+    type Error = NxValueSerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), NxValueSerdeError> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<NxValue, NxValueSerdeError> {
+        Ok(self.finish())
+    }
+}
+
+/// Serializes map keys, which must be strings, characters, integers or unit variants.
+struct KeySerializer;
+
+fn key_must_be_a_string() -> NxValueSerdeError {
+    ser::Error::custom("record keys must be strings")
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = NxValueSerdeError;
+    type SerializeSeq = Impossible<String, NxValueSerdeError>;
+    type SerializeTuple = Impossible<String, NxValueSerdeError>;
+    type SerializeTupleStruct = Impossible<String, NxValueSerdeError>;
+    type SerializeTupleVariant = Impossible<String, NxValueSerdeError>;
+    type SerializeMap = Impossible<String, NxValueSerdeError>;
+    type SerializeStruct = Impossible<String, NxValueSerdeError>;
+    type SerializeStructVariant = Impossible<String, NxValueSerdeError>;
+
+    fn serialize_bool(self, _v: bool) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, NxValueSerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(
+        self,
+        _value: &T,
+    ) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, NxValueSerdeError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, NxValueSerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NxValueSerdeError> {
+        Err(key_must_be_a_string())
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &'de NxValue {
+    type Error = NxValueSerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NxValueSerdeError> {
+        match self {
+            NxValue::Null => visitor.visit_unit(),
+            NxValue::Bool(v) => visitor.visit_bool(*v),
+            NxValue::Int32(v) => visitor.visit_i32(*v),
+            NxValue::Int(v) => visitor.visit_i64(*v),
+            NxValue::Float32(v) => visitor.visit_f32(*v),
+            NxValue::Float(v) => visitor.visit_f64(*v),
+            NxValue::String(v) => visitor.visit_borrowed_str(v),
+            NxValue::Array(elements) => visitor.visit_seq(SeqDeserializer {
+                elements: elements.iter(),
+            }),
+            NxValue::Record {
+                type_name,
+                properties,
+            } => visitor.visit_map(RecordDeserializer {
+                type_name: type_name.as_deref(),
+                properties: properties.iter(),
+                value: None,
+            }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, NxValueSerdeError> {
+        match self {
+            NxValue::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NxValueSerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NxValueSerdeError> {
+        match self {
+            NxValue::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                value: None,
+            }),
+            NxValue::Record {
+                type_name: None,
+                properties,
+            } if properties.len() == 1 => {
+                let (variant, value) = properties.iter().next().expect("one property");
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value: Some(value),
+                })
+            }
+            _ => Err(de::Error::invalid_type(unexpected(self), &visitor)),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+fn unexpected(value: &NxValue) -> de::Unexpected<'_> {
+    match value {
+        NxValue::Null => de::Unexpected::Unit,
+        NxValue::Bool(v) => de::Unexpected::Bool(*v),
+        NxValue::Int32(v) => de::Unexpected::Signed((*v).into()),
+        NxValue::Int(v) => de::Unexpected::Signed(*v),
+        NxValue::Float32(v) => de::Unexpected::Float((*v).into()),
+        NxValue::Float(v) => de::Unexpected::Float(*v),
+        NxValue::String(v) => de::Unexpected::Str(v),
+        NxValue::Array(_) => de::Unexpected::Seq,
+        NxValue::Record { .. } => de::Unexpected::Map,
+    }
+}
+
+struct SeqDeserializer<'de> {
+    elements: std::slice::Iter<'de, NxValue>,
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer<'de> {
+    type Error = NxValueSerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, NxValueSerdeError> {
+        self.elements
+            .next()
+            .map(|element| seed.deserialize(element))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+/// Visits a record's type name as a `"$type"` entry ahead of its properties.
+struct RecordDeserializer<'de> {
+    type_name: Option<&'de str>,
+    properties: std::collections::btree_map::Iter<'de, String, NxValue>,
+    /// Value of the entry whose key was visited last
+    value: Option<EntryValue<'de>>,
+}
+
+enum EntryValue<'de> {
+    TypeName(&'de str),
+    Property(&'de NxValue),
+}
+
+impl<'de> MapAccess<'de> for RecordDeserializer<'de> {
+    type Error = NxValueSerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, NxValueSerdeError> {
+        if let Some(type_name) = self.type_name.take() {
+            self.value = Some(EntryValue::TypeName(type_name));
+            return seed.deserialize("$type".into_deserializer()).map(Some);
+        }
+        match self.properties.next() {
+            Some((key, value)) => {
+                self.value = Some(EntryValue::Property(value));
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, NxValueSerdeError> {
+        match self.value.take() {
+            Some(EntryValue::TypeName(type_name)) => {
+                seed.deserialize(type_name.into_deserializer())
+            }
+            Some(EntryValue::Property(value)) => seed.deserialize(value),
+            None => Err(de::Error::custom("record value requested before its key")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.properties.len() + usize::from(self.type_name.is_some()))
+    }
+}
+
+struct EnumDeserializer<'de> {
+    variant: &'de str,
+    value: Option<&'de NxValue>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = NxValueSerdeError;
+    type Variant = VariantDeserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantDeserializer<'de>), NxValueSerdeError> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer<'de> {
+    value: Option<&'de NxValue>,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer<'de> {
+    type Error = NxValueSerdeError;
+
+    fn unit_variant(self) -> Result<(), NxValueSerdeError> {
+        match self.value {
+            None | Some(NxValue::Null) => Ok(()),
+            Some(value) => Err(de::Error::invalid_type(unexpected(value), &"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, NxValueSerdeError> {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, NxValueSerdeError> {
+        match self.value {
+            Some(value @ NxValue::Array(_)) => de::Deserializer::deserialize_any(value, visitor),
+            Some(value) => Err(de::Error::invalid_type(unexpected(value), &"tuple variant")),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NxValueSerdeError> {
+        match self.value {
+            Some(value @ NxValue::Record { .. }) => {
+                de::Deserializer::deserialize_any(value, visitor)
+            }
+            Some(value) => Err(de::Error::invalid_type(
+                unexpected(value),
+                &"struct variant",
+            )),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        id: u32,
+        customer_name: String,
+        total: f64,
+        status: Status,
+        note: Option<String>,
+        lines: Vec<Line>,
+        tags: BTreeMap<String, i64>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    enum Status {
+        Pending,
+        Shipped { carrier: String },
+        Refunded(f64),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "$type")]
+    enum Line {
+        Product { sku: String, qty: i32 },
+        Discount { percent: f32 },
+    }
+
+    fn order(status: Status) -> Order {
+        Order {
+            id: 7,
+            customer_name: "Ada".to_string(),
+            total: 12.5,
+            status,
+            note: None,
+            lines: vec![
+                Line::Product {
+                    sku: "A-1".to_string(),
+                    qty: 2,
+                },
+                Line::Discount { percent: 0.5 },
+            ],
+            tags: BTreeMap::from([("priority".to_string(), 1)]),
+        }
+    }
+
+    #[test]
+    fn from_serialize_matches_the_json_reader() {
+        for status in [
+            Status::Pending,
+            Status::Shipped {
+                carrier: "post".to_string(),
+            },
+            Status::Refunded(3.0),
+        ] {
+            let order = order(status);
+            let value = NxValue::from_serialize(&order).unwrap();
+            let json = serde_json::to_string(&order).unwrap();
+            assert_eq!(value, NxValue::from_json_str(&json).unwrap());
+            assert_eq!(value.deserialize_into::<Order>().unwrap(), order);
+        }
+    }
+
+    #[test]
+    fn typed_records_and_enum_members_convert_to_nx_shapes() {
+        let value = NxValue::from_serialize(&order(Status::Pending)).unwrap();
+        let NxValue::Record { properties, .. } = value else {
+            panic!("expected a record, got {value:?}");
+        };
+        assert_eq!(properties["status"], NxValue::String("pending".to_string()));
+        assert_eq!(properties["note"], NxValue::Null);
+        assert_eq!(
+            properties["lines"],
+            NxValue::Array(vec![
+                NxValue::Record {
+                    type_name: Some("Product".to_string()),
+                    properties: BTreeMap::from([
+                        ("qty".to_string(), NxValue::Int(2)),
+                        ("sku".to_string(), NxValue::String("A-1".to_string())),
+                    ]),
+                },
+                NxValue::Record {
+                    type_name: Some("Discount".to_string()),
+                    properties: BTreeMap::from([("percent".to_string(), NxValue::Float(0.5))]),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn deserialize_into_accepts_every_number_width_and_reports_mismatches() {
+        let value = NxValue::Array(vec![
+            NxValue::Int32(1),
+            NxValue::Float32(2.5),
+            NxValue::Int(3),
+        ]);
+        assert_eq!(
+            value.deserialize_into::<(u8, f64, f32)>().unwrap(),
+            (1, 2.5, 3.0)
+        );
+
+        let error = NxValue::String("x".to_string())
+            .deserialize_into::<i64>()
+            .unwrap_err();
+        assert_eq!(error.message(), "invalid type: string \"x\", expected i64");
+        let error = NxValue::Int(300).deserialize_into::<u8>().unwrap_err();
+        assert!(error.message().contains("300"), "{error}");
+    }
+
+    #[test]
+    fn from_serialize_rejects_keys_that_are_not_strings() {
+        let map = BTreeMap::from([(vec![1], true)]);
+        let error = NxValue::from_serialize(&map).unwrap_err();
+        assert_eq!(error.message(), "record keys must be strings");
+
+        let map = BTreeMap::from([(4, true)]);
+        assert_eq!(
+            NxValue::from_serialize(&map).unwrap(),
+            NxValue::Record {
+                type_name: None,
+                properties: BTreeMap::from([("4".to_string(), NxValue::Bool(true))]),
+            }
+        );
+    }
+}
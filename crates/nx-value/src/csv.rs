@@ -0,0 +1,250 @@
+//! Reading CSV and TSV tables.
+//!
+//! The first row names the columns and every later row becomes a record keyed by those names.
+//! Fields follow RFC 4180: a field wrapped in double quotes may contain the delimiter, line breaks,
+//! and doubled quotes (`""`) standing for one quote. Rows end with `\n` or `\r\n`; blank lines are
+//! skipped. Every cell is read as a string, so `007` and `1e3` keep their text.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::NxValue;
+
+/// A parsed table: the header row and the rows under it, each as long as the header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvTable {
+    /// Column names from the first row.
+    pub headers: Vec<String>,
+    /// Data rows, one cell per column.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// Parses `text` with fields separated by `delimiter`, such as `','` or `'\t'`.
+    pub fn parse(text: &str, delimiter: char) -> Result<Self, CsvError> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut rows = Reader {
+            chars: text.chars().peekable(),
+            delimiter,
+            line: 1,
+        }
+        .rows()?
+        .into_iter();
+
+        let Some((_, headers)) = rows.next() else {
+            return Ok(Self {
+                headers: Vec::new(),
+                rows: Vec::new(),
+            });
+        };
+        for (index, header) in headers.iter().enumerate() {
+            if header.is_empty() {
+                return Err(CsvError::new(
+                    1,
+                    format!("column {} has no name", index + 1),
+                ));
+            }
+            if headers[..index].contains(header) {
+                return Err(CsvError::new(
+                    1,
+                    format!("column name '{}' is used more than once", header),
+                ));
+            }
+        }
+
+        let rows = rows
+            .map(|(line, row)| {
+                if row.len() == headers.len() {
+                    Ok(row)
+                } else {
+                    Err(CsvError::new(
+                        line,
+                        format!(
+                            "row has {} fields but the header has {}",
+                            row.len(),
+                            headers.len()
+                        ),
+                    ))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { headers, rows })
+    }
+
+    /// Returns the rows as an array of untyped records with string properties.
+    pub fn into_nx_value(self) -> NxValue {
+        let headers = self.headers;
+        NxValue::Array(
+            self.rows
+                .into_iter()
+                .map(|row| NxValue::Record {
+                    type_name: None,
+                    properties: headers
+                        .iter()
+                        .cloned()
+                        .zip(row.into_iter().map(NxValue::String))
+                        .collect::<BTreeMap<_, _>>(),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Error returned for a malformed CSV or TSV table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    line: usize,
+    message: String,
+}
+
+impl CsvError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the 1-based line the problem was found on.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    delimiter: char,
+    line: usize,
+}
+
+impl Reader<'_> {
+    /// Returns every non-blank row with the line it starts on.
+    fn rows(mut self) -> Result<Vec<(usize, Vec<String>)>, CsvError> {
+        let mut rows = Vec::new();
+        while self.chars.peek().is_some() {
+            let line = self.line;
+            let row = self.row()?;
+            if !(row.len() == 1 && row[0].is_empty()) {
+                rows.push((line, row));
+            }
+        }
+        Ok(rows)
+    }
+
+    fn row(&mut self) -> Result<Vec<String>, CsvError> {
+        let mut fields = Vec::new();
+        loop {
+            fields.push(self.field()?);
+            match self.chars.next() {
+                Some(ch) if ch == self.delimiter => continue,
+                Some('\r') => {
+                    self.chars.next_if_eq(&'\n');
+                    self.line += 1;
+                    return Ok(fields);
+                }
+                Some('\n') => {
+                    self.line += 1;
+                    return Ok(fields);
+                }
+                _ => return Ok(fields),
+            }
+        }
+    }
+
+    /// Reads one field, stopping before the delimiter or line break that ends it.
+    fn field(&mut self) -> Result<String, CsvError> {
+        let mut field = String::new();
+        if self.chars.next_if_eq(&'"').is_none() {
+            while let Some(&ch) = self.chars.peek() {
+                if ch == self.delimiter || ch == '\n' || ch == '\r' {
+                    break;
+                }
+                if ch == '"' {
+                    return Err(CsvError::new(
+                        self.line,
+                        "quote inside an unquoted field; quote the whole field and double the quote",
+                    ));
+                }
+                field.push(ch);
+                self.chars.next();
+            }
+            return Ok(field);
+        }
+
+        let start = self.line;
+        loop {
+            match self.chars.next() {
+                Some('"') if self.chars.next_if_eq(&'"').is_some() => field.push('"'),
+                Some('"') => break,
+                Some(ch) => {
+                    if ch == '\n' {
+                        self.line += 1;
+                    }
+                    field.push(ch);
+                }
+                None => return Err(CsvError::new(start, "quoted field is never closed")),
+            }
+        }
+        match self.chars.peek() {
+            None | Some('\n' | '\r') => Ok(field),
+            Some(&ch) if ch == self.delimiter => Ok(field),
+            Some(_) => Err(CsvError::new(
+                self.line,
+                "text after the closing quote of a field",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_become_records_keyed_by_the_header() {
+        let table = CsvTable::parse(
+            "sku,name,price\r\nA-1,\"Desk, oak\",199.00\r\n\r\nB-2,\"24\"\" monitor\",\"1\n299\"\r\n",
+            ',',
+        )
+        .unwrap();
+        assert_eq!(table.headers, vec!["sku", "name", "price"]);
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["A-1", "Desk, oak", "199.00"],
+                vec!["B-2", "24\" monitor", "1\n299"],
+            ]
+        );
+
+        let value = CsvTable::parse("a\tb\n1\t\n", '\t')
+            .unwrap()
+            .into_nx_value();
+        assert_eq!(value.to_json_string().unwrap(), r#"[{"a":"1","b":""}]"#);
+    }
+
+    #[test]
+    fn malformed_tables_report_the_line() {
+        let error = |text: &str| CsvTable::parse(text, ',').unwrap_err().to_string();
+        assert_eq!(
+            error("a,b\n1,2\n3\n"),
+            "line 3: row has 1 fields but the header has 2"
+        );
+        assert_eq!(
+            error("a,a\n"),
+            "line 1: column name 'a' is used more than once"
+        );
+        assert_eq!(error("a\n\"open\n"), "line 2: quoted field is never closed");
+        assert_eq!(
+            error("a\n\"x\"y\n"),
+            "line 2: text after the closing quote of a field"
+        );
+    }
+}
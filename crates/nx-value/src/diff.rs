@@ -0,0 +1,353 @@
+//! Structural diffing of evaluated element trees.
+//!
+//! Preview hosts that re-evaluate a template after every edit can use [`diff_elements`] to turn
+//! the previous and the new result into an [`ElementPatch`], and update only the UI nodes that
+//! changed instead of re-rendering the whole document.
+//!
+//! Elements are [`NxValue::Record`]s whose `type_name` is the tag. Their children are the
+//! `content` property: the items of an array, a single value, or none when `content` is absent or
+//! `null`. Every other property is a prop and is compared as a whole value.
+
+use crate::NxValue;
+
+/// The property that holds an element's children.
+const CONTENT_PROPERTY: &str = "content";
+
+/// One change in an [`ElementPatch`].
+///
+/// A path lists child indices from the root element, so `[]` is the root and `[1, 0]` is the
+/// first child of the root's second child.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementChange {
+    /// Replace the node at `path`, because its tag or kind changed or it is not an element.
+    Replace { path: Vec<usize>, value: NxValue },
+    /// Insert `value` as a child so that it ends up at `path`.
+    Insert { path: Vec<usize>, value: NxValue },
+    /// Remove the child at `path`.
+    Remove { path: Vec<usize> },
+    /// Add a prop to the element at `path`, or change its value.
+    SetProp {
+        path: Vec<usize>,
+        name: String,
+        value: NxValue,
+    },
+    /// Remove a prop from the element at `path`.
+    RemoveProp { path: Vec<usize>, name: String },
+}
+
+/// The changes that turn one evaluation result into another.
+///
+/// Changes are listed in the order they must be applied, and each path refers to the tree as left
+/// by the changes before it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ElementPatch {
+    pub changes: Vec<ElementChange>,
+}
+
+impl ElementPatch {
+    /// Returns `true` when both results were identical.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Computes the changes that turn `old` into `new`.
+///
+/// Elements with the same tag are patched in place: changed props are set or removed, and their
+/// children are aligned so that the longest run of children with matching tags is kept, with the
+/// rest inserted or removed. Nodes whose tag or kind changes are replaced, and `content` that
+/// changes between a single value and an array is set as a whole.
+pub fn diff_elements(old: &NxValue, new: &NxValue) -> ElementPatch {
+    let mut patch = ElementPatch::default();
+    diff_node(&mut Vec::new(), old, new, &mut patch.changes);
+    patch
+}
+
+fn diff_node(
+    path: &mut Vec<usize>,
+    old: &NxValue,
+    new: &NxValue,
+    changes: &mut Vec<ElementChange>,
+) {
+    if old == new {
+        return;
+    }
+
+    let (
+        NxValue::Record {
+            type_name: old_tag,
+            properties: old_props,
+        },
+        NxValue::Record {
+            type_name: new_tag,
+            properties: new_props,
+        },
+    ) = (old, new)
+    else {
+        return replace(path, new, changes);
+    };
+    if old_tag != new_tag {
+        return replace(path, new, changes);
+    }
+
+    let old_content = old_props.get(CONTENT_PROPERTY).unwrap_or(&NxValue::Null);
+    let new_content = new_props.get(CONTENT_PROPERTY).unwrap_or(&NxValue::Null);
+    let children_diffable = matches!(
+        (old_content, new_content),
+        (NxValue::Array(_), NxValue::Array(_)) | (NxValue::Record { .. }, NxValue::Record { .. })
+    );
+
+    for (name, old_value) in old_props {
+        if !new_props.contains_key(name) {
+            changes.push(ElementChange::RemoveProp {
+                path: path.clone(),
+                name: name.clone(),
+            });
+        } else if name == CONTENT_PROPERTY && children_diffable {
+            continue;
+        } else if new_props[name] != *old_value {
+            changes.push(ElementChange::SetProp {
+                path: path.clone(),
+                name: name.clone(),
+                value: new_props[name].clone(),
+            });
+        }
+    }
+    for (name, new_value) in new_props {
+        if !old_props.contains_key(name) {
+            changes.push(ElementChange::SetProp {
+                path: path.clone(),
+                name: name.clone(),
+                value: new_value.clone(),
+            });
+        }
+    }
+
+    match (old_content, new_content) {
+        (NxValue::Array(old_children), NxValue::Array(new_children)) => {
+            diff_children(path, old_children, new_children, changes);
+        }
+        (old_child @ NxValue::Record { .. }, new_child @ NxValue::Record { .. }) => {
+            path.push(0);
+            diff_node(path, old_child, new_child, changes);
+            path.pop();
+        }
+        _ => {}
+    }
+}
+
+fn replace(path: &[usize], new: &NxValue, changes: &mut Vec<ElementChange>) {
+    changes.push(ElementChange::Replace {
+        path: path.to_vec(),
+        value: new.clone(),
+    });
+}
+
+/// Aligns two child lists by their longest common subsequence of matching children, patching the
+/// matched pairs and removing or inserting the others.
+fn diff_children(
+    path: &mut Vec<usize>,
+    old: &[NxValue],
+    new: &[NxValue],
+    changes: &mut Vec<ElementChange>,
+) {
+    // kept[i][j] is the number of children kept when aligning old[i..] with new[j..].
+    let mut kept = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            kept[i][j] = if same_node(&old[i], &new[j]) {
+                kept[i + 1][j + 1] + 1
+            } else {
+                kept[i + 1][j].max(kept[i][j + 1])
+            };
+        }
+    }
+
+    // `index` is the position in the child list as patched so far.
+    let (mut i, mut j, mut index) = (0, 0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && same_node(&old[i], &new[j]) {
+            path.push(index);
+            diff_node(path, &old[i], &new[j], changes);
+            path.pop();
+            i += 1;
+            j += 1;
+            index += 1;
+        } else if j == new.len() || (i < old.len() && kept[i + 1][j] >= kept[i][j + 1]) {
+            path.push(index);
+            changes.push(ElementChange::Remove { path: path.clone() });
+            path.pop();
+            i += 1;
+        } else {
+            path.push(index);
+            changes.push(ElementChange::Insert {
+                path: path.clone(),
+                value: new[j].clone(),
+            });
+            path.pop();
+            j += 1;
+            index += 1;
+        }
+    }
+}
+
+/// Returns `true` when `new` can be patched from `old` rather than replacing it: elements with
+/// the same tag, or equal values of any other kind.
+fn same_node(old: &NxValue, new: &NxValue) -> bool {
+    match (old, new) {
+        (
+            NxValue::Record {
+                type_name: old_tag, ..
+            },
+            NxValue::Record {
+                type_name: new_tag, ..
+            },
+        ) => old_tag == new_tag,
+        _ => old == new,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn element(tag: &str, props: Vec<(&str, NxValue)>) -> NxValue {
+        NxValue::Record {
+            type_name: Some(tag.to_string()),
+            properties: props
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    fn text(value: &str) -> NxValue {
+        NxValue::String(value.to_string())
+    }
+
+    fn list(children: Vec<NxValue>) -> NxValue {
+        NxValue::Array(children)
+    }
+
+    #[test]
+    fn identical_trees_produce_an_empty_patch() {
+        let tree = element("div", vec![("content", list(vec![text("hi")]))]);
+        assert!(diff_elements(&tree, &tree.clone()).is_empty());
+    }
+
+    #[test]
+    fn changed_props_are_set_and_removed_in_place() {
+        let old = element("div", vec![("class", text("a")), ("id", text("x"))]);
+        let new = element(
+            "div",
+            vec![("class", text("b")), ("hidden", NxValue::Bool(true))],
+        );
+
+        assert_eq!(
+            diff_elements(&old, &new).changes,
+            vec![
+                ElementChange::SetProp {
+                    path: vec![],
+                    name: "class".to_string(),
+                    value: text("b"),
+                },
+                ElementChange::RemoveProp {
+                    path: vec![],
+                    name: "id".to_string(),
+                },
+                ElementChange::SetProp {
+                    path: vec![],
+                    name: "hidden".to_string(),
+                    value: NxValue::Bool(true),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn children_are_aligned_by_tag_and_patched_in_order() {
+        let old = element(
+            "ul",
+            vec![(
+                "content",
+                list(vec![
+                    element("li", vec![("content", text("one"))]),
+                    element("hr", vec![]),
+                    element("li", vec![("content", text("two"))]),
+                ]),
+            )],
+        );
+        let new = element(
+            "ul",
+            vec![(
+                "content",
+                list(vec![
+                    element("h2", vec![]),
+                    element("li", vec![("content", text("one"))]),
+                    element("li", vec![("content", text("2"))]),
+                ]),
+            )],
+        );
+
+        assert_eq!(
+            diff_elements(&old, &new).changes,
+            vec![
+                ElementChange::Insert {
+                    path: vec![0],
+                    value: element("h2", vec![]),
+                },
+                ElementChange::Remove { path: vec![2] },
+                ElementChange::SetProp {
+                    path: vec![2],
+                    name: "content".to_string(),
+                    value: text("2"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn single_element_content_is_patched_as_the_first_child() {
+        let old = element(
+            "main",
+            vec![("content", element("p", vec![("class", text("a"))]))],
+        );
+        let new = element(
+            "main",
+            vec![("content", element("p", vec![("class", text("b"))]))],
+        );
+
+        assert_eq!(
+            diff_elements(&old, &new).changes,
+            vec![ElementChange::SetProp {
+                path: vec![0],
+                name: "class".to_string(),
+                value: text("b"),
+            }]
+        );
+    }
+
+    #[test]
+    fn changed_tags_and_content_shapes_are_replaced() {
+        let old = element("div", vec![("content", element("p", vec![]))]);
+        let retagged = element("section", vec![("content", element("p", vec![]))]);
+        let reshaped = element("div", vec![("content", list(vec![element("p", vec![])]))]);
+
+        assert_eq!(
+            diff_elements(&old, &retagged).changes,
+            vec![ElementChange::Replace {
+                path: vec![],
+                value: retagged.clone(),
+            }]
+        );
+        assert_eq!(
+            diff_elements(&old, &reshaped).changes,
+            vec![ElementChange::SetProp {
+                path: vec![],
+                name: "content".to_string(),
+                value: list(vec![element("p", vec![])]),
+            }]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Reading values from serde formats, with the policies JSON input needs.
+//!
+//! JSON allows an object to repeat a key and does not say which occurrence counts. Records keep
+//! their properties sorted by key, so the input order of keys is never preserved; only which of
+//! several equal keys is kept depends on [`DuplicateKeyPolicy`].
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de::Error as _;
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::Deserializer;
+
+use crate::NxValue;
+
+/// Which value [`NxValue::from_json_str_with_options`] keeps when an object repeats a key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// Keep the last value, as [`NxValue::from_json_str`] does.
+    #[default]
+    LastWins,
+    /// Keep the first value and ignore later ones.
+    FirstWins,
+    /// Fail with an error naming the repeated key.
+    Error,
+}
+
+/// How [`NxValue::from_json_str_with_options`] reads objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonReadOptions {
+    /// What to do when an object repeats a key, `"$type"` included.
+    pub duplicate_keys: DuplicateKeyPolicy,
+    /// Fail on a `"$type"` that is not a string. When false, such a value is kept as an ordinary
+    /// `"$type"` property and the record has no type name.
+    pub reject_non_string_type: bool,
+}
+
+impl JsonReadOptions {
+    /// The options [`NxValue::from_json_str`] and every other reader use.
+    pub const DEFAULT: Self = Self {
+        duplicate_keys: DuplicateKeyPolicy::LastWins,
+        reject_non_string_type: true,
+    };
+}
+
+impl Default for JsonReadOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Reads one value, and every value nested in it, with the given options.
+#[derive(Clone, Copy)]
+pub(crate) struct ValueSeed<'a>(pub(crate) &'a JsonReadOptions);
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_> {
+    type Value = NxValue;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<NxValue, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ValueSeed<'_> {
+    type Value = NxValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a JSON-like value")
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(NxValue::Null)
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        Ok(NxValue::Null)
+    }
+
+    fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(NxValue::Bool(v))
+    }
+
+    fn visit_i32<E: serde::de::Error>(self, v: i32) -> Result<Self::Value, E> {
+        Ok(NxValue::Int32(v))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(NxValue::Int(v))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        if v <= i64::MAX as u64 {
+            Ok(NxValue::Int(v as i64))
+        } else {
+            Ok(NxValue::Float(v as f64))
+        }
+    }
+
+    fn visit_f32<E: serde::de::Error>(self, v: f32) -> Result<Self::Value, E> {
+        Ok(NxValue::Float32(v))
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(NxValue::Float(v))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(NxValue::String(v.to_owned()))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(NxValue::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element_seed(self)? {
+            values.push(value);
+        }
+        Ok(NxValue::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let options = self.0;
+        let mut type_name = None;
+        let mut properties = BTreeMap::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(self)?;
+            let value = if key == "$type" {
+                match value {
+                    NxValue::String(name) => {
+                        if keep(options, &key, type_name.is_some())? {
+                            type_name = Some(name);
+                        }
+                        continue;
+                    }
+                    other if options.reject_non_string_type => {
+                        return Err(A::Error::custom(format!(
+                            "expected \"$type\" to be a string, got {:?}",
+                            other
+                        )));
+                    }
+                    other => other,
+                }
+            } else {
+                value
+            };
+
+            match properties.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+                Entry::Occupied(mut entry) => {
+                    if keep(options, entry.key(), true)? {
+                        entry.insert(value);
+                    }
+                }
+            }
+        }
+
+        Ok(NxValue::Record {
+            type_name,
+            properties,
+        })
+    }
+}
+
+/// Returns whether a value for `key` replaces the one kept so far, if `seen`.
+fn keep<E: serde::de::Error>(options: &JsonReadOptions, key: &str, seen: bool) -> Result<bool, E> {
+    if !seen {
+        return Ok(true);
+    }
+    match options.duplicate_keys {
+        DuplicateKeyPolicy::LastWins => Ok(true),
+        DuplicateKeyPolicy::FirstWins => Ok(false),
+        DuplicateKeyPolicy::Error => Err(E::custom(format!("duplicate key \"{key}\""))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(source: &str, options: JsonReadOptions) -> Result<NxValue, String> {
+        NxValue::from_json_str_with_options(source, &options).map_err(|error| error.to_string())
+    }
+
+    fn record(type_name: Option<&str>, properties: &[(&str, NxValue)]) -> NxValue {
+        NxValue::Record {
+            type_name: type_name.map(str::to_string),
+            properties: properties
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn duplicate_keys_follow_the_policy() {
+        let source = r#"{"$type": "A", "b": 1, "a": [{"x": 1, "x": 2}], "b": 3, "$type": "B"}"#;
+        let with = |duplicate_keys| JsonReadOptions {
+            duplicate_keys,
+            ..JsonReadOptions::default()
+        };
+
+        assert_eq!(
+            read(source, with(DuplicateKeyPolicy::LastWins)),
+            Ok(record(
+                Some("B"),
+                &[
+                    (
+                        "a",
+                        NxValue::Array(vec![record(None, &[("x", NxValue::Int(2))])])
+                    ),
+                    ("b", NxValue::Int(3)),
+                ]
+            ))
+        );
+        assert_eq!(
+            NxValue::from_json_str(source).ok(),
+            read(source, with(DuplicateKeyPolicy::LastWins)).ok()
+        );
+        assert_eq!(
+            read(source, with(DuplicateKeyPolicy::FirstWins)),
+            Ok(record(
+                Some("A"),
+                &[
+                    (
+                        "a",
+                        NxValue::Array(vec![record(None, &[("x", NxValue::Int(1))])])
+                    ),
+                    ("b", NxValue::Int(1)),
+                ]
+            ))
+        );
+        assert_eq!(
+            read(source, with(DuplicateKeyPolicy::Error)),
+            Err("duplicate key \"x\" at line 1 column 45".to_string())
+        );
+    }
+
+    #[test]
+    fn non_string_type_is_rejected_or_kept_as_a_property() {
+        let source = r#"{"$type": 7, "name": "Ada"}"#;
+        assert_eq!(
+            read(source, JsonReadOptions::default()),
+            Err("expected \"$type\" to be a string, got Int(7) at line 1 column 11".to_string())
+        );
+        assert_eq!(
+            read(
+                source,
+                JsonReadOptions {
+                    reject_non_string_type: false,
+                    ..JsonReadOptions::default()
+                }
+            ),
+            Ok(record(
+                None,
+                &[
+                    ("$type", NxValue::Int(7)),
+                    ("name", NxValue::String("Ada".to_string()))
+                ]
+            ))
+        );
+    }
+}
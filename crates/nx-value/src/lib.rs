@@ -2,17 +2,60 @@
 //!
 //! `NxValue` is intended to be a stable, serde-compatible data IR that can be used as input/output
 //! across the NX API surface.
+//!
+//! Element attributes stay typed in the value model; [`NxValue::attribute_text`] applies the
+//! coercion rules renderers use when they write attributes as markup text.
+//!
+//! [`diff_elements`] compares two evaluated element trees and returns an [`ElementPatch`], so
+//! preview hosts can update only the nodes that changed after a template is edited.
+//!
+//! [`NxValue::from_csv_reader`] and [`NxValue::from_tsv_reader`] read a table whose first row
+//! names the columns into an array of records with string properties, one record per row.
+//!
+//! [`NxValue::from_serialize`] and [`NxValue::deserialize_into`] convert Rust types with serde
+//! implementations to and from values directly, without going through JSON text.
+//!
+//! With the `msgpack` and `cbor` features, [`NxValue::to_msgpack_vec`] and `NxValue::to_cbor_vec`
+//! write the same shape as JSON in binary form, records carrying their type name as a `"$type"`
+//! entry, so every binding shares one encoding.
+//!
+//! [`assert_nx_output_eq`] and [`compare_nx_output`] compare a result with golden JSON in tests,
+//! ignoring formatting and number width, with options for numeric tolerance and unordered arrays.
 
 use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
 
-use serde::de::Error as _;
-use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::de::DeserializeSeed;
 use serde::ser::{SerializeMap, SerializeSeq};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+use json::ValueSeed;
+
+mod attribute;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod convert;
+mod csv;
+mod diff;
+mod json;
+mod testing;
+
+pub use attribute::{AttributeText, BooleanAttributes};
+#[cfg(feature = "cbor")]
+pub use cbor::CborError;
+pub use convert::NxValueSerdeError;
+pub use csv::{CsvError, CsvTable};
+pub use diff::{diff_elements, ElementChange, ElementPatch};
+pub use json::{DuplicateKeyPolicy, JsonReadOptions};
+pub use testing::{
+    assert_nx_output_eq, assert_nx_output_eq_with, compare_nx_output, CompareOptions,
+};
+
+/// Version of the nx-value crate, identical to the toolchain version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// A JSON-like tree value used as the stable NX API value type.
 ///
 /// Enum values are represented as [`NxValue::String`] carrying the bare authored member name.
@@ -36,7 +79,10 @@ pub enum NxValue {
     Array(Vec<NxValue>),
     /// Record value (ordered properties).
     ///
-    /// When serialized to JSON, `type_name` is encoded as a `"$type"` string property if present.
+    /// When serialized to JSON, `type_name` is encoded as a `"$type"` string property if present,
+    /// before the other properties. The properties are always sorted by key, whatever order the
+    /// input listed them in, so writing a record is deterministic but does not round-trip key
+    /// order. [`JsonReadOptions`] decides what happens to repeated keys.
     Record {
         type_name: Option<String>,
         properties: BTreeMap<String, NxValue>,
@@ -49,6 +95,21 @@ impl NxValue {
         serde_json::from_str(source)
     }
 
+    /// Deserialize a value from a JSON string, choosing how repeated keys and a non-string
+    /// `"$type"` are handled.
+    ///
+    /// [`from_json_str`](Self::from_json_str) reads with [`JsonReadOptions::default`]: the last
+    /// of several equal keys wins and a non-string `"$type"` is an error.
+    pub fn from_json_str_with_options(
+        source: &str,
+        options: &JsonReadOptions,
+    ) -> Result<Self, serde_json::Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(source);
+        let value = ValueSeed(options).deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
     /// Deserialize a value from a JSON reader.
     pub fn from_json_reader<R: Read>(reader: R) -> Result<Self, serde_json::Error> {
         serde_json::from_reader(reader)
@@ -93,14 +154,40 @@ impl NxValue {
     }
 
     /// Serialize a value to MessagePack bytes.
+    #[cfg(feature = "msgpack")]
     pub fn to_msgpack_vec(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
         rmp_serde::to_vec(self)
     }
 
     /// Deserialize a value from MessagePack bytes.
+    #[cfg(feature = "msgpack")]
     pub fn from_msgpack_slice(bytes: &[u8]) -> Result<Self, rmp_serde::decode::Error> {
         rmp_serde::from_slice(bytes)
     }
+
+    /// Read a comma-separated table into an array of records keyed by its header row.
+    pub fn from_csv_str(source: &str) -> Result<Self, CsvError> {
+        CsvTable::parse(source, ',').map(CsvTable::into_nx_value)
+    }
+
+    /// Read a comma-separated table from a reader into an array of records keyed by its header row.
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Self, NxValueIoError> {
+        Self::from_delimited_reader(reader, ',')
+    }
+
+    /// Read a tab-separated table from a reader into an array of records keyed by its header row.
+    pub fn from_tsv_reader<R: Read>(reader: R) -> Result<Self, NxValueIoError> {
+        Self::from_delimited_reader(reader, '\t')
+    }
+
+    fn from_delimited_reader<R: Read>(
+        mut reader: R,
+        delimiter: char,
+    ) -> Result<Self, NxValueIoError> {
+        let mut source = String::new();
+        reader.read_to_string(&mut source)?;
+        Ok(CsvTable::parse(&source, delimiter)?.into_nx_value())
+    }
 }
 
 impl Serialize for NxValue {
@@ -143,105 +230,16 @@ impl Serialize for NxValue {
 
 impl<'de> Deserialize<'de> for NxValue {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        struct NxValueVisitor;
-
-        impl<'de> Visitor<'de> for NxValueVisitor {
-            type Value = NxValue;
-
-            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("a JSON-like value")
-            }
-
-            fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
-                Ok(NxValue::Null)
-            }
-
-            fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
-                Ok(NxValue::Null)
-            }
-
-            fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Self::Value, E> {
-                Ok(NxValue::Bool(v))
-            }
-
-            fn visit_i32<E: serde::de::Error>(self, v: i32) -> Result<Self::Value, E> {
-                Ok(NxValue::Int32(v))
-            }
-
-            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
-                Ok(NxValue::Int(v))
-            }
-
-            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
-                if v <= i64::MAX as u64 {
-                    Ok(NxValue::Int(v as i64))
-                } else {
-                    Ok(NxValue::Float(v as f64))
-                }
-            }
-
-            fn visit_f32<E: serde::de::Error>(self, v: f32) -> Result<Self::Value, E> {
-                Ok(NxValue::Float32(v))
-            }
-
-            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
-                Ok(NxValue::Float(v))
-            }
-
-            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
-                Ok(NxValue::String(v.to_owned()))
-            }
-
-            fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
-                Ok(NxValue::String(v))
-            }
-
-            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
-                let mut values = Vec::new();
-                while let Some(value) = seq.next_element::<NxValue>()? {
-                    values.push(value);
-                }
-                Ok(NxValue::Array(values))
-            }
-
-            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
-                let mut type_name = None;
-                let mut properties = BTreeMap::new();
-                while let Some((key, value)) = map.next_entry::<String, NxValue>()? {
-                    if key == "$type" {
-                        match value {
-                            NxValue::String(name) => {
-                                type_name = Some(name);
-                            }
-                            other => {
-                                return Err(A::Error::custom(format!(
-                                    "expected \"$type\" to be a string, got {:?}",
-                                    other
-                                )));
-                            }
-                        }
-                        continue;
-                    }
-
-                    properties.insert(key, value);
-                }
-
-                Ok(NxValue::Record {
-                    type_name,
-                    properties,
-                })
-            }
-        }
-
-        deserializer.deserialize_any(NxValueVisitor)
+        ValueSeed(&JsonReadOptions::DEFAULT).deserialize(deserializer)
     }
 }
 
-/// Errors for JSON file IO helpers.
+/// Errors for JSON file and CSV reader IO helpers.
 #[derive(Debug)]
 pub enum NxValueIoError {
     Io(std::io::Error),
     Json(serde_json::Error),
+    Csv(CsvError),
 }
 
 impl std::fmt::Display for NxValueIoError {
@@ -249,6 +247,7 @@ impl std::fmt::Display for NxValueIoError {
         match self {
             NxValueIoError::Io(e) => write!(f, "io error: {}", e),
             NxValueIoError::Json(e) => write!(f, "json error: {}", e),
+            NxValueIoError::Csv(e) => write!(f, "csv error: {}", e),
         }
     }
 }
@@ -258,6 +257,7 @@ impl std::error::Error for NxValueIoError {
         match self {
             NxValueIoError::Io(e) => Some(e),
             NxValueIoError::Json(e) => Some(e),
+            NxValueIoError::Csv(e) => Some(e),
         }
     }
 }
@@ -274,6 +274,12 @@ impl From<serde_json::Error> for NxValueIoError {
     }
 }
 
+impl From<CsvError> for NxValueIoError {
+    fn from(value: CsvError) -> Self {
+        NxValueIoError::Csv(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -428,6 +434,24 @@ mod tests {
         assert_eq!(decoded, value);
     }
 
+    #[test]
+    fn csv_reader_produces_records_and_reports_malformed_rows() {
+        let value = NxValue::from_csv_reader(Cursor::new("name,qty\nBolt,4\n")).unwrap();
+        assert_eq!(
+            value,
+            NxValue::Array(vec![NxValue::Record {
+                type_name: None,
+                properties: BTreeMap::from([
+                    ("name".to_string(), NxValue::String("Bolt".to_string())),
+                    ("qty".to_string(), NxValue::String("4".to_string())),
+                ]),
+            }])
+        );
+
+        let err = NxValue::from_tsv_reader(Cursor::new("name\tqty\nBolt\n")).unwrap_err();
+        assert!(matches!(err, NxValueIoError::Csv(ref e) if e.line() == 2));
+    }
+
     #[test]
     fn json_pretty_string_round_trip() {
         let value = NxValue::Record {
@@ -477,6 +501,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "msgpack")]
     fn msgpack_round_trip_in_memory() {
         let mut obj = BTreeMap::new();
         obj.insert("name".to_string(), NxValue::String("Ada".to_string()));
@@ -493,6 +518,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "msgpack")]
     fn msgpack_round_trip_enum_member_as_bare_string() {
         let value = NxValue::String("active".to_string());
 
@@ -502,6 +528,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "msgpack")]
     fn int32_round_trip_msgpack() {
         let value = NxValue::Int32(42);
         let bytes = value.to_msgpack_vec().unwrap();
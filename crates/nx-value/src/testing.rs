@@ -0,0 +1,352 @@
+//! Comparing evaluation results with golden JSON in tests.
+//!
+//! The expected value is JSON text, so its formatting and property order do not matter. Numbers
+//! match by value whatever their width, so `1`, `1.0` and an [`NxValue::Int32`] of 1 are equal,
+//! and an [`NxValue::Float32`] matches the expected number rounded to `f32`. Record type names are
+//! compared through their `"$type"` property.
+
+use crate::NxValue;
+
+/// How [`compare_nx_output`] matches values.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CompareOptions {
+    /// Largest absolute difference at which two numbers still match.
+    pub tolerance: f64,
+    /// Match array items in any order instead of position by position.
+    pub unordered_arrays: bool,
+}
+
+/// Compares `actual` with the value encoded by `expected_json`.
+///
+/// On a mismatch, the error names the path of the first difference, such as `$.items[1].price`,
+/// and shows both values in full. Invalid JSON is reported as an error too.
+///
+/// With [`unordered_arrays`](CompareOptions::unordered_arrays), each expected item is matched with
+/// the first actual item it equals that no earlier expected item took.
+pub fn compare_nx_output(
+    actual: &NxValue,
+    expected_json: &str,
+    options: &CompareOptions,
+) -> Result<(), String> {
+    let expected = NxValue::from_json_str(expected_json)
+        .map_err(|error| format!("expected output is not valid JSON: {}", error))?;
+    let mut path = String::from("$");
+    match first_difference(actual, &expected, options, &mut path) {
+        None => Ok(()),
+        Some(difference) => Err(format!(
+            "{}: {}\nexpected:\n{}\nactual:\n{}",
+            path,
+            difference,
+            pretty(&expected),
+            pretty(actual)
+        )),
+    }
+}
+
+/// Asserts that `actual` equals the value encoded by `expected_json`, with exact numbers and
+/// ordered arrays.
+///
+/// # Panics
+///
+/// Panics with the message of [`compare_nx_output`] when the values differ.
+///
+/// # Examples
+///
+/// ```
+/// use nx_value::{assert_nx_output_eq, NxValue};
+///
+/// let value = NxValue::from_json_str(r#"{"name":"Ada","score":1}"#).unwrap();
+/// assert_nx_output_eq(&value, r#"
+///     { "score": 1.0, "name": "Ada" }
+/// "#);
+/// ```
+#[track_caller]
+pub fn assert_nx_output_eq(actual: &NxValue, expected_json: &str) {
+    assert_nx_output_eq_with(actual, expected_json, &CompareOptions::default());
+}
+
+/// Asserts that `actual` matches the value encoded by `expected_json` under `options`.
+///
+/// # Panics
+///
+/// Panics with the message of [`compare_nx_output`] when the values differ.
+#[track_caller]
+pub fn assert_nx_output_eq_with(actual: &NxValue, expected_json: &str, options: &CompareOptions) {
+    if let Err(message) = compare_nx_output(actual, expected_json, options) {
+        panic!("NX output does not match\n{}", message);
+    }
+}
+
+/// Returns a description of the first place `actual` and `expected` differ, leaving `path`
+/// pointing at it, or `None` when they match.
+fn first_difference(
+    actual: &NxValue,
+    expected: &NxValue,
+    options: &CompareOptions,
+    path: &mut String,
+) -> Option<String> {
+    if let (Some(actual_number), Some(expected_number)) = (number(actual), number(expected)) {
+        return (!numbers_match(actual_number, expected_number, options))
+            .then(|| mismatch(actual, expected));
+    }
+
+    match (actual, expected) {
+        (NxValue::Array(actual_items), NxValue::Array(expected_items)) => {
+            if actual_items.len() != expected_items.len() {
+                return Some(format!(
+                    "expected {} items, got {}",
+                    expected_items.len(),
+                    actual_items.len()
+                ));
+            }
+            if options.unordered_arrays {
+                return unordered_difference(actual_items, expected_items, options);
+            }
+            for (index, (actual_item, expected_item)) in
+                actual_items.iter().zip(expected_items).enumerate()
+            {
+                let len = path.len();
+                path.push_str(&format!("[{}]", index));
+                if let Some(difference) =
+                    first_difference(actual_item, expected_item, options, path)
+                {
+                    return Some(difference);
+                }
+                path.truncate(len);
+            }
+            None
+        }
+        (
+            NxValue::Record {
+                type_name: actual_type,
+                properties: actual_properties,
+            },
+            NxValue::Record {
+                type_name: expected_type,
+                properties: expected_properties,
+            },
+        ) => {
+            if actual_type != expected_type {
+                return Some(format!(
+                    "expected type {}, got {}",
+                    type_label(expected_type),
+                    type_label(actual_type)
+                ));
+            }
+            for name in expected_properties.keys() {
+                if !actual_properties.contains_key(name) {
+                    return Some(format!("missing property '{}'", name));
+                }
+            }
+            for name in actual_properties.keys() {
+                if !expected_properties.contains_key(name) {
+                    return Some(format!("unexpected property '{}'", name));
+                }
+            }
+            for (name, expected_value) in expected_properties {
+                let actual_value = &actual_properties[name];
+                let len = path.len();
+                path.push('.');
+                path.push_str(name);
+                if let Some(difference) =
+                    first_difference(actual_value, expected_value, options, path)
+                {
+                    return Some(difference);
+                }
+                path.truncate(len);
+            }
+            None
+        }
+        _ => (actual != expected).then(|| mismatch(actual, expected)),
+    }
+}
+
+fn unordered_difference(
+    actual_items: &[NxValue],
+    expected_items: &[NxValue],
+    options: &CompareOptions,
+) -> Option<String> {
+    let mut matched = vec![false; actual_items.len()];
+    for (index, expected_item) in expected_items.iter().enumerate() {
+        let found = (0..actual_items.len()).find(|&candidate| {
+            !matched[candidate]
+                && first_difference(
+                    &actual_items[candidate],
+                    expected_item,
+                    options,
+                    &mut String::new(),
+                )
+                .is_none()
+        });
+        match found {
+            Some(candidate) => matched[candidate] = true,
+            None => {
+                return Some(format!(
+                    "no item matches expected item {}: {}",
+                    index,
+                    compact(expected_item)
+                ))
+            }
+        }
+    }
+    None
+}
+
+enum Number {
+    Integer(i64),
+    Float32(f32),
+    Float(f64),
+}
+
+fn number(value: &NxValue) -> Option<Number> {
+    match value {
+        NxValue::Int32(value) => Some(Number::Integer(i64::from(*value))),
+        NxValue::Int(value) => Some(Number::Integer(*value)),
+        NxValue::Float32(value) => Some(Number::Float32(*value)),
+        NxValue::Float(value) => Some(Number::Float(*value)),
+        _ => None,
+    }
+}
+
+fn numbers_match(actual: Number, expected: Number, options: &CompareOptions) -> bool {
+    let (actual, expected) = match (actual, expected) {
+        (Number::Integer(actual), Number::Integer(expected)) if actual == expected => return true,
+        // An `f32` result matches the expected number at `f32` precision.
+        (Number::Float32(actual), expected) => (f64::from(actual), f64::from(expected.as_f32())),
+        (actual, Number::Float32(expected)) => (f64::from(actual.as_f32()), f64::from(expected)),
+        (actual, expected) => (actual.as_f64(), expected.as_f64()),
+    };
+    actual == expected || (actual - expected).abs() <= options.tolerance
+}
+
+impl Number {
+    fn as_f64(&self) -> f64 {
+        match *self {
+            Number::Integer(value) => value as f64,
+            Number::Float32(value) => f64::from(value),
+            Number::Float(value) => value,
+        }
+    }
+
+    fn as_f32(&self) -> f32 {
+        self.as_f64() as f32
+    }
+}
+
+fn mismatch(actual: &NxValue, expected: &NxValue) -> String {
+    format!("expected {}, got {}", compact(expected), compact(actual))
+}
+
+fn type_label(type_name: &Option<String>) -> String {
+    type_name
+        .as_ref()
+        .map_or_else(|| "none".to_string(), |name| format!("'{}'", name))
+}
+
+fn compact(value: &NxValue) -> String {
+    value
+        .to_json_string()
+        .unwrap_or_else(|_| format!("{:?}", value))
+}
+
+fn pretty(value: &NxValue) -> String {
+    value
+        .to_json_string_pretty()
+        .unwrap_or_else(|_| format!("{:?}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(json: &str) -> NxValue {
+        NxValue::from_json_str(json).unwrap()
+    }
+
+    #[test]
+    fn formatting_property_order_and_number_width_are_ignored() {
+        let actual = NxValue::Record {
+            type_name: Some("Point".to_string()),
+            properties: [
+                ("x".to_string(), NxValue::Int32(1)),
+                ("y".to_string(), NxValue::Float32(0.1)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        assert_nx_output_eq(
+            &actual,
+            "{\n  \"y\": 0.1,\n  \"x\": 1.0,\n  \"$type\": \"Point\"\n}",
+        );
+    }
+
+    #[test]
+    fn mismatches_name_the_path_of_the_first_difference() {
+        let actual = value(r#"{"items":[{"price":3.5},{"price":3.6}],"total":7.1}"#);
+        let error = compare_nx_output(
+            &actual,
+            r#"{"items":[{"price":3.5},{"price":3.5}],"total":7.1}"#,
+            &CompareOptions::default(),
+        )
+        .unwrap_err();
+        assert!(
+            error.starts_with("$.items[1].price: expected 3.5, got 3.6\n"),
+            "{error}"
+        );
+
+        let error =
+            compare_nx_output(&actual, r#"{"items":[]}"#, &CompareOptions::default()).unwrap_err();
+        assert!(
+            error.starts_with("$: unexpected property 'total'"),
+            "{error}"
+        );
+        let error = compare_nx_output(&actual, "[1,", &CompareOptions::default()).unwrap_err();
+        assert!(
+            error.starts_with("expected output is not valid JSON"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn tolerance_allows_small_numeric_differences() {
+        let actual = value("[0.30000000000000004, 100]");
+        let options = CompareOptions {
+            tolerance: 1e-9,
+            ..CompareOptions::default()
+        };
+
+        assert!(compare_nx_output(&actual, "[0.3, 100]", &CompareOptions::default()).is_err());
+        assert_nx_output_eq_with(&actual, "[0.3, 100.0000000001]", &options);
+        assert!(compare_nx_output(&actual, "[0.3, 101]", &options).is_err());
+    }
+
+    #[test]
+    fn unordered_arrays_match_items_in_any_order() {
+        let actual = value(r#"[{"id":2},{"id":1},{"id":2}]"#);
+        let options = CompareOptions {
+            unordered_arrays: true,
+            ..CompareOptions::default()
+        };
+
+        assert!(compare_nx_output(
+            &actual,
+            r#"[{"id":1},{"id":2},{"id":2}]"#,
+            &CompareOptions::default()
+        )
+        .is_err());
+        assert_nx_output_eq_with(&actual, r#"[{"id":1},{"id":2},{"id":2}]"#, &options);
+        let error =
+            compare_nx_output(&actual, r#"[{"id":1},{"id":1},{"id":2}]"#, &options).unwrap_err();
+        assert!(
+            error.starts_with("$: no item matches expected item 1: {\"id\":1}"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "$: expected type 'Point', got none")]
+    fn assert_panics_on_mismatch() {
+        assert_nx_output_eq(&value(r#"{"x":1}"#), r#"{"$type":"Point","x":1}"#);
+    }
+}